@@ -0,0 +1,136 @@
+//! Laufzeitvergleich der modularen Exponentiation mit Exponenten voller Länge.
+//!
+//! Verglichen werden `BigUint::modpow`, das frühere `mod_exp` (Square-and-Multiply mit `%`
+//! in jedem Schritt), Montgomery mit gleitendem und festem Fenster sowie Barrett mit
+//! gleitendem Fenster; Kontext bzw. Reduzierer werden einmal je Modul angelegt. Angegeben
+//! ist der Median je Aufruf.
+//!
+//! ```text
+//! cargo bench --bench modexp
+//! cargo bench --bench modexp -- --runs 50 --bits 2048
+//! ```
+
+use cryptomorph::algorithms::modular_arith::{BarrettReducer, MontgomeryContext};
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::{One, Zero};
+use rand::rngs::OsRng;
+use std::time::{Duration, Instant};
+
+/// Bisheriges `mod_exp`: Square-and-Multiply mit Division in jedem Schritt.
+fn naive_mod_exp(mut base: BigUint, mut exponent: BigUint, modulus: &BigUint) -> BigUint {
+    let one = BigUint::one();
+    let mut result = one.clone();
+    base %= modulus;
+    while !exponent.is_zero() {
+        if &exponent % 2u32 == one {
+            result = (result * &base) % modulus;
+        }
+        exponent >>= 1;
+        base = (&base * &base) % modulus;
+    }
+    result
+}
+
+/// Messvariante: Name und Aufruf.
+type Variant<'a> = (&'static str, Box<dyn Fn() -> BigUint + 'a>);
+
+/// Median der Laufzeiten je Variante; die Varianten laufen abwechselnd, damit Schwankungen
+/// der Maschine alle gleich treffen.
+fn measure(runs: usize, variants: &[Variant], expected: &BigUint) -> Vec<Duration> {
+    let mut times = vec![Vec::with_capacity(runs); variants.len()];
+    for _ in 0..runs {
+        for ((_, f), samples) in variants.iter().zip(&mut times) {
+            let start = Instant::now();
+            let result = f();
+            samples.push(start.elapsed());
+            assert_eq!(&result, expected);
+        }
+    }
+    times
+        .into_iter()
+        .map(|mut samples| {
+            samples.sort();
+            samples[samples.len() / 2]
+        })
+        .collect()
+}
+
+fn main() {
+    let mut runs = 20;
+    let mut sizes = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" => {
+                runs = args
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .expect("--runs <N>")
+            }
+            "--bits" => sizes.push(
+                args.next()
+                    .and_then(|v| v.parse().ok())
+                    .expect("--bits <N>"),
+            ),
+            // von `cargo bench` übergeben
+            "--bench" => {}
+            other => panic!("unbekanntes Argument: {}", other),
+        }
+    }
+    if sizes.is_empty() {
+        sizes = vec![1024, 2048, 3072, 4096];
+    }
+    assert!(runs > 0, "mindestens ein Durchlauf");
+
+    let mut rng = OsRng;
+    println!("Modulare Exponentiation, {} Durchläufe", runs);
+    println!(
+        "{:>6}  {:<28} {:>12} {:>8}",
+        "Bit", "Verfahren", "Median", "Faktor"
+    );
+    for bits in sizes {
+        let modulus = rng.gen_biguint(bits) | BigUint::one() | (BigUint::one() << (bits - 1));
+        let base = rng.gen_biguint_below(&modulus);
+        let exponent = rng.gen_biguint(bits);
+        let expected = base.modpow(&exponent, &modulus);
+        let montgomery = MontgomeryContext::new(&modulus).unwrap();
+        let barrett = BarrettReducer::new(&modulus).unwrap();
+
+        let variants: Vec<Variant> = vec![
+            (
+                "BigUint::modpow",
+                Box::new(|| base.modpow(&exponent, &modulus)),
+            ),
+            (
+                "bisheriges mod_exp",
+                Box::new(|| naive_mod_exp(base.clone(), exponent.clone(), &modulus)),
+            ),
+            (
+                "Montgomery, gleitend",
+                Box::new(|| montgomery.modpow(&base, &exponent)),
+            ),
+            (
+                "Montgomery, fest",
+                Box::new(|| {
+                    let residue = montgomery.residue(&base);
+                    montgomery.value(&montgomery.pow_fixed_window(&residue, &exponent))
+                }),
+            ),
+            (
+                "Barrett, gleitend",
+                Box::new(|| barrett.pow(&base, &exponent)),
+            ),
+        ];
+        let times = measure(runs, &variants, &expected);
+        let reference = times[0];
+        for ((name, _), time) in variants.iter().zip(times) {
+            println!(
+                "{:>6}  {:<28} {:>10.3}ms {:>7.2}x",
+                bits,
+                name,
+                time.as_secs_f64() * 1000.0,
+                time.as_secs_f64() / reference.as_secs_f64()
+            );
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Laufzeitvergleich der RSA-Schlüsselerzeugung für 2048, 3072 und 4096 Bit.
+//!
+//! Verglichen werden das frühere Verfahren (zufällige ungerade Kandidaten, je zehn
+//! Miller-Rabin-Runden ohne Sieb), die gesiebte Suche auf einem Thread und
+//! `generate_rsa_keypair`, das gesiebt auf allen Kernen sucht.
+//!
+//! ```text
+//! cargo bench --bench rsa_keygen
+//! cargo bench --bench rsa_keygen -- --runs 5 --bits 2048 --bits 3072
+//! ```
+
+use cryptomorph::algorithms::primality::{generate_prime, miller_rabin, PrimeOptions};
+use cryptomorph::asymmetric::rsa::generate_rsa_keypair;
+use num_bigint::{BigUint, RandBigInt};
+use rand::rngs::OsRng;
+use std::time::{Duration, Instant};
+
+/// Bisherige Primzahlsuche: zufällige ungerade Kandidaten mit zehn Miller-Rabin-Runden.
+fn unsieved_prime(bits: usize) -> BigUint {
+    let mut rng = OsRng;
+    let two = BigUint::from(2u32);
+    loop {
+        let mut candidate = rng.gen_biguint(bits as u64);
+        candidate.set_bit(0, true);
+        candidate.set_bit(bits as u64 - 1, true);
+        let upper = &candidate - 1u32;
+        if (0..10).all(|_| miller_rabin(&candidate, &rng.gen_biguint_range(&two, &upper))) {
+            return candidate;
+        }
+    }
+}
+
+fn sieved_prime(bits: usize) -> BigUint {
+    let options = PrimeOptions {
+        top_two_bits: true,
+        ..PrimeOptions::new(bits)
+    };
+    generate_prime(&options).unwrap()
+}
+
+/// Beide Primfaktoren eines Schlüssels mit `bits` Bit nach dem bisherigen Verfahren.
+fn unsieved_keypair(bits: usize) {
+    unsieved_prime(bits / 2);
+    unsieved_prime(bits / 2);
+}
+
+/// Beide Primfaktoren gesiebt auf einem Thread.
+fn sieved_keypair(bits: usize) {
+    sieved_prime(bits / 2);
+    sieved_prime(bits / 2);
+}
+
+fn parallel_keypair(bits: usize) {
+    generate_rsa_keypair(bits);
+}
+
+/// Mittlere, kleinste und größte Laufzeit von `runs` Aufrufen.
+fn measure(runs: usize, mut f: impl FnMut()) -> (Duration, Duration, Duration) {
+    let times: Vec<Duration> = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect();
+    let total: Duration = times.iter().sum();
+    (
+        total / runs as u32,
+        *times.iter().min().unwrap(),
+        *times.iter().max().unwrap(),
+    )
+}
+
+fn main() {
+    let mut runs = 3;
+    let mut sizes = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" => {
+                runs = args
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .expect("--runs <N>")
+            }
+            "--bits" => sizes.push(
+                args.next()
+                    .and_then(|v| v.parse().ok())
+                    .expect("--bits <N>"),
+            ),
+            // von `cargo bench` übergeben
+            "--bench" => {}
+            other => panic!("unbekanntes Argument: {}", other),
+        }
+    }
+    if sizes.is_empty() {
+        sizes = vec![2048, 3072, 4096];
+    }
+    assert!(runs > 0, "mindestens ein Durchlauf");
+
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    println!(
+        "RSA-Schlüsselerzeugung, {} Durchläufe, {} Kerne",
+        runs, threads
+    );
+    println!(
+        "{:>6}  {:<24} {:>10} {:>10} {:>10}",
+        "Bit", "Verfahren", "Mittel", "Min", "Max"
+    );
+    for bits in sizes {
+        let variants = [
+            ("bisher (ohne Sieb)", unsieved_keypair as fn(usize)),
+            ("Sieb, 1 Thread", sieved_keypair),
+            ("generate_rsa_keypair", parallel_keypair),
+        ];
+        for (name, f) in variants {
+            let (mean, min, max) = measure(runs, || f(bits));
+            println!(
+                "{:>6}  {:<24} {:>9.2}s {:>9.2}s {:>9.2}s",
+                bits,
+                name,
+                mean.as_secs_f64(),
+                min.as_secs_f64(),
+                max.as_secs_f64()
+            );
+        }
+    }
+}
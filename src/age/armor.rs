@@ -0,0 +1,79 @@
+//! ASCII-Armor für age-Dateien (striktes PEM nach RFC 7468).
+
+use crate::age::AgeError;
+use crate::utils::armor::{Armor, Label};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+const BEGIN: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+const END: &str = "-----END AGE ENCRYPTED FILE-----";
+const LINE_LEN: usize = 64;
+
+/// Prüft, ob `data` (nach führendem Leerraum) mit einer Armor-Zeile beginnt.
+pub fn is_armored(data: &[u8]) -> bool {
+    data.trim_ascii_start().starts_with(b"-----")
+}
+
+/// Kodiert eine binäre age-Datei als ASCII-Armor.
+pub fn encode(data: &[u8]) -> String {
+    Armor::new(Label::Age, data.to_vec()).encode()
+}
+
+/// Dekodiert ASCII-Armor streng.
+///
+/// Erlaubt sind nur Leerraum vor und nach dem Block sowie LF- oder CRLF-Zeilenenden.
+/// Alle Zeilen außer der letzten müssen genau 64 Zeichen lang sein; Header,
+/// Prüfsummen und nicht-kanonisches Base64 werden abgelehnt.
+pub fn decode(data: &[u8]) -> Result<Vec<u8>, AgeError> {
+    let err = |msg: &str| AgeError::Armor(msg.to_string());
+    let text = std::str::from_utf8(data.trim_ascii()).map_err(|_| err("kein gültiger Text"))?;
+    let lines: Vec<&str> = text
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+
+    if lines.len() < 3 || lines[0] != BEGIN {
+        return Err(err("ungültige BEGIN-Zeile"));
+    }
+    if lines[lines.len() - 1] != END {
+        return Err(err("ungültige END-Zeile"));
+    }
+    let body = &lines[1..lines.len() - 1];
+    let (last, full) = body.split_last().unwrap();
+    if full.iter().any(|line| line.len() != LINE_LEN) {
+        return Err(err("Zeile hat nicht 64 Zeichen"));
+    }
+    if last.is_empty() || last.len() > LINE_LEN {
+        return Err(err("ungültige letzte Zeile"));
+    }
+    STANDARD
+        .decode(body.concat())
+        .map_err(|_| err("ungültiges Base64"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let armored = encode(&data);
+        assert!(is_armored(armored.as_bytes()));
+        assert_eq!(decode(armored.as_bytes()).unwrap(), data);
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        let mut armored = encode(b"hallo");
+        armored.push_str("garbage\n");
+        assert!(matches!(
+            decode(armored.as_bytes()),
+            Err(AgeError::Armor(_))
+        ));
+        assert!(decode(
+            b"-----BEGIN AGE ENCRYPTED FILE-----\naGFsbG8\n-----END AGE ENCRYPTED FILE-----"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! Bech32-Kodierung (BIP 173) für age-Schlüssel (`age1…`, `AGE-SECRET-KEY-1…`).
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+fn polymod(values: &[u8]) -> u32 {
+    let mut chk = 1u32;
+    for &v in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ v as u32;
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut out: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    out.push(0);
+    out.extend(hrp.bytes().map(|b| b & 31));
+    out
+}
+
+/// Wandelt Gruppen von `from` Bits in Gruppen von `to` Bits um.
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut acc = 0u32;
+    let mut bits = 0u32;
+    let mut out = Vec::new();
+    let max = (1u32 << to) - 1;
+    for &value in data {
+        if (value as u32) >> from != 0 {
+            return None;
+        }
+        acc = (acc << from) | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max) != 0 {
+        return None;
+    }
+    Some(out)
+}
+
+/// Kodiert `data` mit dem Präfix `hrp` (Kleinbuchstaben).
+pub fn encode(hrp: &str, data: &[u8]) -> String {
+    let values = convert_bits(data, 8, 5, true).expect("8-Bit-Werte sind immer gültig");
+    let mut checksum_input = hrp_expand(hrp);
+    checksum_input.extend_from_slice(&values);
+    checksum_input.extend_from_slice(&[0; 6]);
+    let checksum = polymod(&checksum_input) ^ 1;
+
+    let mut out = format!("{}1", hrp);
+    for v in values {
+        out.push(CHARSET[v as usize] as char);
+    }
+    for i in 0..6 {
+        out.push(CHARSET[((checksum >> (5 * (5 - i))) & 31) as usize] as char);
+    }
+    out
+}
+
+/// Dekodiert einen Bech32-String. Gibt (Präfix in Kleinbuchstaben, Daten) zurück.
+///
+/// Gemischte Groß-/Kleinschreibung und falsche Prüfsummen werden abgelehnt.
+pub fn decode(s: &str) -> Option<(String, Vec<u8>)> {
+    let has_lower = s.bytes().any(|b| b.is_ascii_lowercase());
+    let has_upper = s.bytes().any(|b| b.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return None;
+    }
+    let s = s.to_ascii_lowercase();
+    let sep = s.rfind('1')?;
+    if sep == 0 || sep + 7 > s.len() {
+        return None;
+    }
+    let (hrp, rest) = (&s[..sep], &s[sep + 1..]);
+    if hrp.bytes().any(|b| !(33..=126).contains(&b)) {
+        return None;
+    }
+    let values = rest
+        .bytes()
+        .map(|b| CHARSET.iter().position(|&c| c == b).map(|p| p as u8))
+        .collect::<Option<Vec<u8>>>()?;
+
+    let mut checksum_input = hrp_expand(hrp);
+    checksum_input.extend_from_slice(&values);
+    if polymod(&checksum_input) != 1 {
+        return None;
+    }
+    let data = convert_bits(&values[..values.len() - 6], 5, 8, false)?;
+    Some((hrp.to_string(), data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bip173_vector() {
+        let (hrp, data) = decode("A12UEL5L").unwrap();
+        assert_eq!(hrp, "a");
+        assert!(data.is_empty());
+        assert!(decode("a12uel5l").is_some());
+        assert!(decode("A12uEL5L").is_none()); // gemischte Schreibweise
+        assert!(decode("a12uel5m").is_none()); // falsche Prüfsumme
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let data = [0xabu8; 32];
+        let encoded = encode("age", &data);
+        assert!(encoded.starts_with("age1"));
+        assert_eq!(
+            decode(&encoded).unwrap(),
+            ("age".to_string(), data.to_vec())
+        );
+    }
+}
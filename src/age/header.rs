@@ -0,0 +1,216 @@
+//! Textueller age-Header: Versionszeile, Empfänger-Stanzas und Header-MAC.
+
+use crate::age::AgeError;
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::io::{BufRead, Read};
+
+pub const VERSION_LINE: &str = "age-encryption.org/v1";
+const BODY_LINE_LEN: usize = 64;
+const MAX_LINE_LEN: usize = 4096;
+
+/// Ein Empfängereintrag: `-> tag arg…` gefolgt vom Base64-Körper.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stanza {
+    pub tag: String,
+    pub args: Vec<String>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub stanzas: Vec<Stanza>,
+    pub mac: [u8; 32],
+}
+
+fn header_failure(msg: &str) -> AgeError {
+    AgeError::Header(msg.to_string())
+}
+
+/// Dekodiert kanonisches Base64 ohne Padding, wie es im Header verwendet wird.
+pub fn decode_b64(s: &str) -> Option<Vec<u8>> {
+    STANDARD_NO_PAD.decode(s).ok()
+}
+
+pub fn encode_b64(data: &[u8]) -> String {
+    STANDARD_NO_PAD.encode(data)
+}
+
+fn mac_key(file_key: &[u8; 16]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, file_key)
+        .expand(b"header", &mut key)
+        .expect("HKDF-Ausgabelänge ungültig");
+    key
+}
+
+fn header_mac(file_key: &[u8; 16], header_bytes: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(&mac_key(file_key))
+        .expect("HMAC akzeptiert jede Schlüssellänge");
+    mac.update(header_bytes);
+    mac
+}
+
+impl Stanza {
+    fn encode_into(&self, out: &mut String) {
+        out.push_str("-> ");
+        out.push_str(&self.tag);
+        for arg in &self.args {
+            out.push(' ');
+            out.push_str(arg);
+        }
+        out.push('\n');
+        let b64 = encode_b64(&self.body);
+        let mut chunks = b64.as_bytes().chunks(BODY_LINE_LEN).peekable();
+        while let Some(chunk) = chunks.next() {
+            out.push_str(std::str::from_utf8(chunk).unwrap());
+            out.push('\n');
+            // Eine volle letzte Zeile braucht eine leere Abschlusszeile
+            if chunks.peek().is_none() && chunk.len() == BODY_LINE_LEN {
+                out.push('\n');
+            }
+        }
+        if b64.is_empty() {
+            out.push('\n');
+        }
+    }
+}
+
+impl Header {
+    /// Erstellt einen Header und berechnet den MAC mit dem Dateischlüssel.
+    pub fn new(stanzas: Vec<Stanza>, file_key: &[u8; 16]) -> Self {
+        let mut header = Header {
+            stanzas,
+            mac: [0u8; 32],
+        };
+        let tag = header_mac(file_key, header.encode_for_mac().as_bytes()).finalize();
+        header.mac.copy_from_slice(&tag.into_bytes());
+        header
+    }
+
+    /// Header-Bytes bis einschließlich `---`, über die der MAC gebildet wird.
+    fn encode_for_mac(&self) -> String {
+        let mut out = format!("{}\n", VERSION_LINE);
+        for stanza in &self.stanzas {
+            stanza.encode_into(&mut out);
+        }
+        out.push_str("---");
+        out
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.encode_for_mac();
+        out.push(' ');
+        out.push_str(&encode_b64(&self.mac));
+        out.push('\n');
+        out.into_bytes()
+    }
+
+    /// Prüft den Header-MAC (konstante Laufzeit).
+    pub fn verify_mac(&self, file_key: &[u8; 16]) -> bool {
+        header_mac(file_key, self.encode_for_mac().as_bytes())
+            .verify_slice(&self.mac)
+            .is_ok()
+    }
+
+    /// Liest den Header streng kanonisch aus `reader`.
+    ///
+    /// Da nur die kanonische Kodierung akzeptiert wird, reproduziert
+    /// [`Header::to_bytes`] exakt die gelesenen Bytes.
+    pub fn read<R: BufRead>(reader: &mut R) -> Result<Header, AgeError> {
+        if read_line(reader)? != VERSION_LINE {
+            return Err(header_failure("unbekannte Version"));
+        }
+
+        let mut stanzas = Vec::new();
+        let mut line = read_line(reader)?;
+        loop {
+            if let Some(rest) = line.strip_prefix("-> ") {
+                let args: Vec<String> = rest.split(' ').map(str::to_string).collect();
+                if args
+                    .iter()
+                    .any(|a| a.is_empty() || !a.bytes().all(|b| (0x21..=0x7e).contains(&b)))
+                {
+                    return Err(header_failure("ungültiges Stanza-Argument"));
+                }
+                let mut b64 = String::new();
+                loop {
+                    let body_line = read_line(reader)?;
+                    if body_line.len() > BODY_LINE_LEN {
+                        return Err(header_failure("Stanza-Zeile zu lang"));
+                    }
+                    b64.push_str(&body_line);
+                    if body_line.len() < BODY_LINE_LEN {
+                        break;
+                    }
+                }
+                let body = decode_b64(&b64).ok_or_else(|| header_failure("ungültiges Base64"))?;
+                let mut args = args.into_iter();
+                stanzas.push(Stanza {
+                    tag: args.next().unwrap(),
+                    args: args.collect(),
+                    body,
+                });
+                line = read_line(reader)?;
+            } else if let Some(mac_b64) = line.strip_prefix("--- ") {
+                if stanzas.is_empty() {
+                    return Err(header_failure("keine Empfänger"));
+                }
+                let mac = decode_b64(mac_b64)
+                    .and_then(|m| <[u8; 32]>::try_from(m).ok())
+                    .ok_or_else(|| header_failure("ungültiger MAC"))?;
+                return Ok(Header { stanzas, mac });
+            } else {
+                return Err(header_failure("unerwartete Header-Zeile"));
+            }
+        }
+    }
+}
+
+/// Liest eine LF-terminierte Zeile aus druckbarem ASCII (ohne das LF).
+fn read_line<R: BufRead>(reader: &mut R) -> Result<String, AgeError> {
+    let mut buf = Vec::new();
+    Read::take(reader, MAX_LINE_LEN as u64)
+        .read_until(b'\n', &mut buf)
+        .map_err(|e| AgeError::Io(e.to_string()))?;
+    if buf.pop() != Some(b'\n') {
+        return Err(header_failure("Header abgeschnitten"));
+    }
+    if !buf.iter().all(|b| (0x20..=0x7e).contains(b)) {
+        return Err(header_failure("ungültiges Zeichen im Header"));
+    }
+    Ok(String::from_utf8(buf).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_read_roundtrip() {
+        let file_key = [7u8; 16];
+        let header = Header::new(
+            vec![
+                Stanza {
+                    tag: "X25519".into(),
+                    args: vec!["abc".into()],
+                    body: vec![1u8; 32],
+                },
+                Stanza {
+                    tag: "grease".into(),
+                    args: vec![],
+                    body: vec![2u8; 48], // genau 64 Base64-Zeichen
+                },
+            ],
+            &file_key,
+        );
+        let bytes = header.to_bytes();
+        let parsed = Header::read(&mut &bytes[..]).unwrap();
+        assert_eq!(parsed, header);
+        assert!(parsed.verify_mac(&file_key));
+        assert!(!parsed.verify_mac(&[8u8; 16]));
+    }
+}
@@ -0,0 +1,393 @@
+//! Lesen und Schreiben des age-v1-Formats (<https://age-encryption.org/v1>).
+//!
+//! Unterstützt X25519- und scrypt-Empfänger, den ChaCha20-Poly1305-STREAM-Payload,
+//! den Header-MAC und ASCII-Armor. Dateien sind mit `age` und `rage` austauschbar.
+
+pub mod armor;
+pub mod bech32;
+pub mod header;
+pub mod stream;
+
+use crate::asymmetric::x25519;
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::{ChaCha20Poly1305, KeyInit, Nonce};
+use header::{decode_b64, encode_b64, Header, Stanza};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use std::fmt;
+use std::io::{BufRead, Read, Write};
+
+const X25519_INFO: &[u8] = b"age-encryption.org/v1/X25519";
+const SCRYPT_SALT_LABEL: &[u8] = b"age-encryption.org/v1/scrypt";
+const RECIPIENT_HRP: &str = "age";
+const SECRET_KEY_HRP: &str = "age-secret-key-";
+
+/// Standard-Arbeitsfaktor (log2 N) für neue scrypt-Empfänger.
+pub const DEFAULT_SCRYPT_LOG_N: u8 = 18;
+/// Höchster Arbeitsfaktor, der beim Entschlüsseln akzeptiert wird.
+pub const MAX_SCRYPT_LOG_N: u8 = 22;
+
+/// Fehlerklassen beim Verarbeiten von age-Dateien.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AgeError {
+    /// ASCII-Armor ist ungültig.
+    Armor(String),
+    /// Header ist syntaktisch oder semantisch ungültig.
+    Header(String),
+    /// Header-MAC stimmt nicht.
+    HeaderMac,
+    /// Keine Identität passt zu einem der Empfänger.
+    NoMatch,
+    /// Payload ist beschädigt oder abgeschnitten.
+    Payload(String),
+    /// Ein- oder Ausgabefehler.
+    Io(String),
+}
+
+impl fmt::Display for AgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AgeError::Armor(msg) => write!(f, "Ungültiges ASCII-Armor: {}", msg),
+            AgeError::Header(msg) => write!(f, "Ungültiger age-Header: {}", msg),
+            AgeError::HeaderMac => write!(f, "Header-MAC ungültig"),
+            AgeError::NoMatch => write!(f, "Keine Identität passt zu den Empfängern"),
+            AgeError::Payload(msg) => write!(f, "Payload beschädigt: {}", msg),
+            AgeError::Io(msg) => write!(f, "E/A-Fehler: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AgeError {}
+
+/// Empfänger einer age-Datei.
+#[derive(Debug, Clone)]
+pub enum Recipient {
+    X25519(x25519::PublicKey),
+    /// Passwort-Empfänger; muss der einzige Empfänger sein.
+    Scrypt {
+        passphrase: String,
+        log_n: u8,
+    },
+}
+
+/// Identität zum Entschlüsseln einer age-Datei.
+#[derive(Clone)]
+pub enum Identity {
+    X25519(x25519::PrivateKey),
+    Scrypt { passphrase: String, max_log_n: u8 },
+}
+
+/// Kodiert einen X25519-Schlüssel als age-Empfänger (`age1…`).
+pub fn encode_recipient(pub_key: &x25519::PublicKey) -> String {
+    bech32::encode(RECIPIENT_HRP, &pub_key.bytes)
+}
+
+/// Liest einen age-Empfänger (`age1…`).
+pub fn parse_recipient(s: &str) -> Option<x25519::PublicKey> {
+    match bech32::decode(s)? {
+        (hrp, data) if hrp == RECIPIENT_HRP && s.starts_with("age1") => Some(x25519::PublicKey {
+            bytes: data.try_into().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+/// Kodiert einen privaten X25519-Schlüssel als age-Identität (`AGE-SECRET-KEY-1…`).
+pub fn encode_identity(priv_key: &x25519::PrivateKey) -> String {
+    bech32::encode(SECRET_KEY_HRP, &priv_key.bytes).to_ascii_uppercase()
+}
+
+/// Liest eine age-Identität (`AGE-SECRET-KEY-1…`).
+pub fn parse_identity(s: &str) -> Option<x25519::PrivateKey> {
+    match bech32::decode(s)? {
+        (hrp, data) if hrp == SECRET_KEY_HRP && s.starts_with("AGE-SECRET-KEY-1") => {
+            Some(x25519::PrivateKey {
+                bytes: data.try_into().ok()?,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Liest eine age-Identitätsdatei: eine Identität pro Zeile, `#` leitet Kommentare ein.
+pub fn parse_identity_file(content: &str) -> Option<Vec<x25519::PrivateKey>> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(parse_identity)
+        .collect()
+}
+
+fn hkdf_key(ikm: &[u8], salt: &[u8], info: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(salt), ikm)
+        .expand(info, &mut key)
+        .expect("HKDF-Ausgabelänge ungültig");
+    key
+}
+
+/// Verpackt den Dateischlüssel mit ChaCha20-Poly1305 und Null-Nonce (Schlüssel ist einmalig).
+fn wrap_file_key(key: &[u8; 32], file_key: &[u8; 16]) -> Vec<u8> {
+    ChaCha20Poly1305::new(key.into())
+        .encrypt(Nonce::from_slice(&[0u8; 12]), &file_key[..])
+        .expect("ChaCha20-Poly1305-Verschlüsselung fehlgeschlagen")
+}
+
+/// Entpackt den Dateischlüssel. Der Körper muss genau 32 Byte lang sein.
+fn unwrap_file_key(key: &[u8; 32], body: &[u8]) -> Result<Option<[u8; 16]>, AgeError> {
+    if body.len() != 32 {
+        return Err(AgeError::Header(
+            "ungültige Länge des Stanza-Körpers".into(),
+        ));
+    }
+    Ok(ChaCha20Poly1305::new(key.into())
+        .decrypt(Nonce::from_slice(&[0u8; 12]), body)
+        .ok()
+        .map(|k| k.try_into().unwrap()))
+}
+
+fn scrypt_key(passphrase: &str, salt: &[u8], log_n: u8) -> Result<[u8; 32], AgeError> {
+    let params = scrypt::Params::new(log_n, 8, 1, 32)
+        .map_err(|_| AgeError::Header("ungültiger scrypt-Arbeitsfaktor".into()))?;
+    let mut full_salt = SCRYPT_SALT_LABEL.to_vec();
+    full_salt.extend_from_slice(salt);
+    let mut key = [0u8; 32];
+    scrypt::scrypt(passphrase.as_bytes(), &full_salt, &params, &mut key)
+        .expect("scrypt-Ausgabelänge ungültig");
+    Ok(key)
+}
+
+/// Liest den Arbeitsfaktor streng als Dezimalzahl ohne Vorzeichen und führende Nullen.
+fn parse_log_n(s: &str) -> Option<u8> {
+    if s.is_empty() || s.starts_with('0') || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+impl Recipient {
+    fn wrap(&self, file_key: &[u8; 16]) -> Result<Stanza, AgeError> {
+        match self {
+            Recipient::X25519(pub_key) => {
+                let (eph_pub, eph_priv) = x25519::generate_x25519_keypair();
+                let shared = x25519::diffie_hellman(&eph_priv, pub_key)
+                    .ok_or_else(|| AgeError::Header("ungültiger X25519-Empfänger".into()))?;
+                let salt = [eph_pub.bytes, pub_key.bytes].concat();
+                let key = hkdf_key(&shared, &salt, X25519_INFO);
+                Ok(Stanza {
+                    tag: "X25519".into(),
+                    args: vec![encode_b64(&eph_pub.bytes)],
+                    body: wrap_file_key(&key, file_key),
+                })
+            }
+            Recipient::Scrypt { passphrase, log_n } => {
+                let salt: [u8; 16] = rand::random();
+                let key = scrypt_key(passphrase, &salt, *log_n)?;
+                Ok(Stanza {
+                    tag: "scrypt".into(),
+                    args: vec![encode_b64(&salt), log_n.to_string()],
+                    body: wrap_file_key(&key, file_key),
+                })
+            }
+        }
+    }
+}
+
+impl Identity {
+    /// Versucht, den Dateischlüssel aus einer Stanza zu entpacken.
+    ///
+    /// `Ok(None)` bedeutet „nicht für diese Identität“; fehlerhafte Stanzas
+    /// eines bekannten Typs führen zu einem Header-Fehler.
+    fn unwrap(&self, stanza: &Stanza) -> Result<Option<[u8; 16]>, AgeError> {
+        let header_err = |msg: &str| AgeError::Header(msg.to_string());
+        match (self, stanza.tag.as_str()) {
+            (Identity::X25519(priv_key), "X25519") => {
+                let [share] = stanza.args.as_slice() else {
+                    return Err(header_err("X25519-Stanza braucht genau ein Argument"));
+                };
+                let share: [u8; 32] = decode_b64(share)
+                    .and_then(|s| s.try_into().ok())
+                    .ok_or_else(|| header_err("ungültiger X25519-Anteil"))?;
+                let eph_pub = x25519::PublicKey { bytes: share };
+                let shared = x25519::diffie_hellman(priv_key, &eph_pub)
+                    .ok_or_else(|| header_err("X25519-Anteil hat kleine Ordnung"))?;
+                let own_pub = x25519::public_key_from_private(priv_key);
+                let salt = [share, own_pub.bytes].concat();
+                unwrap_file_key(&hkdf_key(&shared, &salt, X25519_INFO), &stanza.body)
+            }
+            (
+                Identity::Scrypt {
+                    passphrase,
+                    max_log_n,
+                },
+                "scrypt",
+            ) => {
+                let [salt, log_n] = stanza.args.as_slice() else {
+                    return Err(header_err("scrypt-Stanza braucht genau zwei Argumente"));
+                };
+                let salt: [u8; 16] = decode_b64(salt)
+                    .and_then(|s| s.try_into().ok())
+                    .ok_or_else(|| header_err("ungültiger scrypt-Salt"))?;
+                let log_n = parse_log_n(log_n)
+                    .filter(|&n| n > 0 && n <= *max_log_n)
+                    .ok_or_else(|| header_err("ungültiger oder zu hoher Arbeitsfaktor"))?;
+                if stanza.body.len() != 32 {
+                    return Err(header_err("ungültige Länge des Stanza-Körpers"));
+                }
+                unwrap_file_key(&scrypt_key(passphrase, &salt, log_n)?, &stanza.body)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Verschlüsselt `input` für alle Empfänger im binären age-Format nach `output`.
+pub fn encrypt_stream<R: Read, W: Write>(
+    recipients: &[Recipient],
+    input: R,
+    mut output: W,
+) -> Result<(), AgeError> {
+    let has_scrypt = recipients
+        .iter()
+        .any(|r| matches!(r, Recipient::Scrypt { .. }));
+    if recipients.is_empty() || (has_scrypt && recipients.len() > 1) {
+        return Err(AgeError::Header(
+            "mindestens ein Empfänger nötig; scrypt nur allein".into(),
+        ));
+    }
+    let io_err = |e: std::io::Error| AgeError::Io(e.to_string());
+
+    let file_key: [u8; 16] = rand::random();
+    let stanzas = recipients
+        .iter()
+        .map(|r| r.wrap(&file_key))
+        .collect::<Result<Vec<_>, _>>()?;
+    let header = Header::new(stanzas, &file_key);
+    let nonce: [u8; 16] = rand::random();
+
+    output.write_all(&header.to_bytes()).map_err(io_err)?;
+    output.write_all(&nonce).map_err(io_err)?;
+    stream::encrypt(&stream::payload_key(&file_key, &nonce), input, output).map_err(io_err)
+}
+
+/// Entschlüsselt eine binäre age-Datei aus `input` nach `output`.
+pub fn decrypt_stream<R: BufRead, W: Write>(
+    identities: &[Identity],
+    mut input: R,
+    output: W,
+) -> Result<(), AgeError> {
+    let header = Header::read(&mut input)?;
+    if header.stanzas.iter().any(|s| s.tag == "scrypt") && header.stanzas.len() > 1 {
+        return Err(AgeError::Header(
+            "scrypt-Stanza muss die einzige sein".into(),
+        ));
+    }
+
+    let mut file_key = None;
+    'search: for identity in identities {
+        for stanza in &header.stanzas {
+            if let Some(key) = identity.unwrap(stanza)? {
+                file_key = Some(key);
+                break 'search;
+            }
+        }
+    }
+    let file_key = file_key.ok_or(AgeError::NoMatch)?;
+    if !header.verify_mac(&file_key) {
+        return Err(AgeError::HeaderMac);
+    }
+
+    let mut nonce = [0u8; 16];
+    input
+        .read_exact(&mut nonce)
+        .map_err(|_| AgeError::Header("Payload-Nonce fehlt".into()))?;
+    stream::decrypt(&stream::payload_key(&file_key, &nonce), input, output)
+}
+
+/// Verschlüsselt `plaintext` im Speicher, optional mit ASCII-Armor.
+pub fn encrypt(
+    plaintext: &[u8],
+    recipients: &[Recipient],
+    armored: bool,
+) -> Result<Vec<u8>, AgeError> {
+    let mut out = Vec::new();
+    encrypt_stream(recipients, plaintext, &mut out)?;
+    Ok(if armored {
+        armor::encode(&out).into_bytes()
+    } else {
+        out
+    })
+}
+
+/// Entschlüsselt eine age-Datei im Speicher; ASCII-Armor wird automatisch erkannt.
+pub fn decrypt(data: &[u8], identities: &[Identity]) -> Result<Vec<u8>, AgeError> {
+    let decoded;
+    let binary = if armor::is_armored(data) {
+        decoded = armor::decode(data)?;
+        &decoded[..]
+    } else {
+        data
+    };
+    let mut out = Vec::new();
+    decrypt_stream(identities, binary, &mut out)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_x25519_roundtrip_armored() {
+        let (pub_key, priv_key) = x25519::generate_x25519_keypair();
+        let data = encrypt(b"hallo age", &[Recipient::X25519(pub_key)], true).unwrap();
+        assert!(data.starts_with(b"-----BEGIN AGE ENCRYPTED FILE-----"));
+        assert_eq!(
+            decrypt(&data, &[Identity::X25519(priv_key)]).unwrap(),
+            b"hallo age"
+        );
+    }
+
+    #[test]
+    fn test_scrypt_roundtrip_and_limits() {
+        let recipient = Recipient::Scrypt {
+            passphrase: "passwort".into(),
+            log_n: 10,
+        };
+        let data = encrypt(b"geheim", std::slice::from_ref(&recipient), false).unwrap();
+
+        let identity = |pw: &str, max_log_n| Identity::Scrypt {
+            passphrase: pw.into(),
+            max_log_n,
+        };
+        assert_eq!(
+            decrypt(&data, &[identity("passwort", 22)]).unwrap(),
+            b"geheim"
+        );
+        assert_eq!(
+            decrypt(&data, &[identity("falsch", 22)]),
+            Err(AgeError::NoMatch)
+        );
+        assert!(matches!(
+            decrypt(&data, &[identity("passwort", 9)]),
+            Err(AgeError::Header(_))
+        ));
+
+        // scrypt darf nicht mit anderen Empfängern kombiniert werden
+        let (pub_key, _) = x25519::generate_x25519_keypair();
+        assert!(encrypt(b"x", &[recipient, Recipient::X25519(pub_key)], false).is_err());
+    }
+
+    #[test]
+    fn test_key_encoding() {
+        let (pub_key, priv_key) = x25519::generate_x25519_keypair();
+        let r = encode_recipient(&pub_key);
+        let i = encode_identity(&priv_key);
+        assert!(r.starts_with("age1"));
+        assert!(i.starts_with("AGE-SECRET-KEY-1"));
+        assert_eq!(parse_recipient(&r), Some(pub_key));
+        assert_eq!(parse_identity(&i).unwrap().bytes, priv_key.bytes);
+        assert!(parse_identity(&i.to_lowercase()).is_none());
+    }
+}
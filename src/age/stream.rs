@@ -0,0 +1,177 @@
+//! STREAM-Konstruktion mit ChaCha20-Poly1305 und 64-KiB-Blöcken (age-Payload).
+
+use crate::age::AgeError;
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::{ChaCha20Poly1305, KeyInit, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use std::io::{self, Read, Write};
+
+pub const CHUNK_SIZE: usize = 64 * 1024;
+const TAG_SIZE: usize = 16;
+
+/// Leitet den Payload-Schlüssel aus Dateischlüssel und 16-Byte-Nonce ab.
+pub fn payload_key(file_key: &[u8; 16], nonce: &[u8; 16]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(nonce), file_key)
+        .expand(b"payload", &mut key)
+        .expect("HKDF-Ausgabelänge ungültig");
+    key
+}
+
+/// Blocknonce: 11-Byte-Zähler (Big-Endian) gefolgt vom Letzter-Block-Flag.
+fn chunk_nonce(counter: u128, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..11].copy_from_slice(&counter.to_be_bytes()[5..]);
+    nonce[11] = last as u8;
+    nonce
+}
+
+/// Liest, bis `buf` voll ist oder die Eingabe endet.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Verschlüsselt `input` blockweise nach `output`.
+pub fn encrypt<R: Read, W: Write>(key: &[u8; 32], mut input: R, mut output: W) -> io::Result<()> {
+    let cipher = ChaCha20Poly1305::new(key.into());
+    let mut current = vec![0u8; CHUNK_SIZE];
+    let mut next = vec![0u8; CHUNK_SIZE];
+    let mut len = read_full(&mut input, &mut current)?;
+    let mut counter = 0u128;
+
+    loop {
+        // Ein voller Block ist nur dann der letzte, wenn danach nichts mehr folgt
+        let next_len = if len == CHUNK_SIZE {
+            read_full(&mut input, &mut next)?
+        } else {
+            0
+        };
+        let last = next_len == 0;
+        let ct = cipher
+            .encrypt(
+                Nonce::from_slice(&chunk_nonce(counter, last)),
+                &current[..len],
+            )
+            .expect("ChaCha20-Poly1305-Verschlüsselung fehlgeschlagen");
+        output.write_all(&ct)?;
+        if last {
+            return output.flush();
+        }
+        std::mem::swap(&mut current, &mut next);
+        len = next_len;
+        counter += 1;
+    }
+}
+
+/// Entschlüsselt `input` blockweise nach `output`.
+///
+/// Es werden nur vollständig authentifizierte Blöcke geschrieben. Fehlende,
+/// leere oder mehrfache Endblöcke sowie Daten nach dem Endblock sind Fehler.
+pub fn decrypt<R: Read, W: Write>(
+    key: &[u8; 32],
+    mut input: R,
+    mut output: W,
+) -> Result<(), AgeError> {
+    let io_err = |e: io::Error| AgeError::Io(e.to_string());
+    let payload_err = |msg: &str| AgeError::Payload(msg.to_string());
+
+    let cipher = ChaCha20Poly1305::new(key.into());
+    let open = |counter, last, chunk: &[u8]| {
+        cipher
+            .decrypt(Nonce::from_slice(&chunk_nonce(counter, last)), chunk)
+            .ok()
+    };
+    let mut buf = vec![0u8; CHUNK_SIZE + TAG_SIZE];
+    let mut counter = 0u128;
+
+    loop {
+        let len = read_full(&mut input, &mut buf).map_err(io_err)?;
+        if len < TAG_SIZE {
+            return Err(payload_err(if len == 0 {
+                "Endblock fehlt"
+            } else {
+                "Block zu kurz"
+            }));
+        }
+        let chunk = &buf[..len];
+
+        if len == buf.len() {
+            // Voller Block: regulär weiter, oder voller Endblock ohne Folgedaten
+            if let Some(pt) = open(counter, false, chunk) {
+                output.write_all(&pt).map_err(io_err)?;
+                counter += 1;
+                continue;
+            }
+            let pt = open(counter, true, chunk)
+                .ok_or_else(|| payload_err("Authentifizierung eines Blocks fehlgeschlagen"))?;
+            output.write_all(&pt).map_err(io_err)?;
+            if read_full(&mut input, &mut [0u8; 1]).map_err(io_err)? != 0 {
+                return Err(payload_err("Daten nach dem Endblock"));
+            }
+        } else {
+            // Kurzer Block: muss der Endblock sein und darf nur bei leerem Payload leer sein
+            let pt = open(counter, true, chunk)
+                .ok_or_else(|| payload_err("Authentifizierung eines Blocks fehlgeschlagen"))?;
+            if pt.is_empty() && counter > 0 {
+                return Err(payload_err("leerer Endblock"));
+            }
+            output.write_all(&pt).map_err(io_err)?;
+        }
+        return output.flush().map_err(io_err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_chunk_boundaries() {
+        let key = [3u8; 32];
+        for size in [
+            0,
+            1,
+            CHUNK_SIZE - 1,
+            CHUNK_SIZE,
+            CHUNK_SIZE + 1,
+            2 * CHUNK_SIZE,
+        ] {
+            let data: Vec<u8> = (0..size).map(|i| i as u8).collect();
+            let mut ct = Vec::new();
+            encrypt(&key, &data[..], &mut ct).unwrap();
+            let blocks = size.div_ceil(CHUNK_SIZE).max(1);
+            assert_eq!(ct.len(), size + blocks * TAG_SIZE);
+
+            let mut pt = Vec::new();
+            decrypt(&key, &ct[..], &mut pt).unwrap();
+            assert_eq!(pt, data);
+        }
+    }
+
+    #[test]
+    fn test_truncation_detected() {
+        let key = [3u8; 32];
+        let data = vec![0u8; CHUNK_SIZE + 10];
+        let mut ct = Vec::new();
+        encrypt(&key, &data[..], &mut ct).unwrap();
+
+        // Endblock abgeschnitten: nur der authentifizierte erste Block wird ausgegeben
+        let mut pt = Vec::new();
+        let truncated = &ct[..CHUNK_SIZE + TAG_SIZE];
+        assert!(matches!(
+            decrypt(&key, truncated, &mut pt),
+            Err(AgeError::Payload(_))
+        ));
+        assert_eq!(pt.len(), CHUNK_SIZE);
+    }
+}
@@ -0,0 +1,574 @@
+//! Ganzzahlen fester Breite mit konstanter Laufzeit für geheime Werte (d, p, q).
+//!
+//! [`FixedUint`] behält immer die Limb-Anzahl, mit der es angelegt wurde. Keine Operation
+//! verzweigt oder indiziert abhängig vom Wert; Bedingungen werden als Masken (0 oder !0)
+//! verrechnet. Die Laufzeit hängt damit nur von den Breiten ab, und die ergeben sich aus
+//! öffentlichen Größen wie der Länge des Moduls. [`CtModulus`] rechnet darauf mit
+//! Montgomery-Multiplikation, Potenzen mit festem Fenster (Tabellenzugriff über alle
+//! Einträge) und Inversen.
+//!
+//! Die Funktionen auf `BigUint` ([`modpow`], [`gcd`], [`lcm`], [`rem`],
+//! [`inverse_mod_prime`], [`inverse_of_public`]) wandeln nur an den Rändern um; die
+//! Zwischenergebnisse bleiben fester Breite.
+
+use num_bigint::BigUint;
+use std::hint::black_box;
+
+/// !0, wenn das unterste Bit von `bit` gesetzt ist, sonst 0.
+fn mask(bit: u64) -> u64 {
+    black_box(bit & 1).wrapping_neg()
+}
+
+/// `a`, wenn `m` = !0, sonst `b`.
+fn select(m: u64, a: u64, b: u64) -> u64 {
+    b ^ (m & (a ^ b))
+}
+
+/// Maske für x < y bei Werten unter 2⁶³ (Zähler, Bitpositionen).
+fn lt_small(x: u64, y: u64) -> u64 {
+    mask(x.wrapping_sub(y) >> 63)
+}
+
+/// Nichtnegative Ganzzahl mit fester Anzahl von 64-Bit-Limbs (niederwertigstes zuerst).
+#[derive(Clone, Debug)]
+pub struct FixedUint {
+    limbs: Vec<u64>,
+}
+
+impl FixedUint {
+    pub fn zero(limbs: usize) -> Self {
+        FixedUint {
+            limbs: vec![0; limbs],
+        }
+    }
+
+    /// `x` mit `limbs` Limbs; panikt, wenn x nicht hineinpasst.
+    pub fn from_biguint(x: &BigUint, limbs: usize) -> Self {
+        let mut value: Vec<u64> = x.iter_u64_digits().collect();
+        assert!(value.len() <= limbs, "Wert passt nicht in {} Limbs", limbs);
+        value.resize(limbs, 0);
+        FixedUint { limbs: value }
+    }
+
+    pub fn to_biguint(&self) -> BigUint {
+        let bytes: Vec<u8> = self.limbs.iter().flat_map(|l| l.to_le_bytes()).collect();
+        BigUint::from_bytes_le(&bytes)
+    }
+
+    /// Anzahl der Limbs.
+    pub fn width(&self) -> usize {
+        self.limbs.len()
+    }
+
+    /// Bit `i` als 0 oder 1 (`i` ist öffentlich).
+    pub fn bit(&self, i: usize) -> u64 {
+        (self.limbs[i / 64] >> (i % 64)) & 1
+    }
+
+    /// Gleiche Zahl mit `limbs` Limbs; panikt, wenn gesetzte Limbs wegfielen.
+    pub fn resized(&self, limbs: usize) -> Self {
+        let mut value = self.limbs.clone();
+        let dropped = value.iter().skip(limbs).fold(0, |acc, &l| acc | l);
+        assert_eq!(dropped, 0, "Wert passt nicht in {} Limbs", limbs);
+        value.resize(limbs, 0);
+        FixedUint { limbs: value }
+    }
+
+    /// Maske: !0 für 0.
+    pub fn is_zero(&self) -> u64 {
+        let any = self.limbs.iter().fold(0, |acc, &l| acc | l);
+        mask(((any | any.wrapping_neg()) >> 63) ^ 1)
+    }
+
+    /// Maske: !0 für self < other (gleiche Breite).
+    pub fn lt(&self, other: &FixedUint) -> u64 {
+        let mut difference = self.clone();
+        mask(difference.sub_assign(other))
+    }
+
+    /// Maske: !0 für self = other (gleiche Breite).
+    pub fn eq(&self, other: &FixedUint) -> u64 {
+        let diff = self
+            .limbs
+            .iter()
+            .zip(&other.limbs)
+            .fold(0, |acc, (&a, &b)| acc | (a ^ b));
+        mask(((diff | diff.wrapping_neg()) >> 63) ^ 1)
+    }
+
+    /// self += other; gibt den Übertrag (0 oder 1) zurück.
+    pub fn add_assign(&mut self, other: &FixedUint) -> u64 {
+        let mut carry = 0u64;
+        for (a, &b) in self.limbs.iter_mut().zip(&other.limbs) {
+            let sum = u128::from(*a) + u128::from(b) + u128::from(carry);
+            *a = sum as u64;
+            carry = (sum >> 64) as u64;
+        }
+        carry
+    }
+
+    /// self −= other; gibt den Borrow (0 oder 1) zurück.
+    pub fn sub_assign(&mut self, other: &FixedUint) -> u64 {
+        let mut borrow = 0u64;
+        for (a, &b) in self.limbs.iter_mut().zip(&other.limbs) {
+            let difference = u128::from(*a)
+                .wrapping_sub(u128::from(b))
+                .wrapping_sub(u128::from(borrow));
+            *a = difference as u64;
+            borrow = ((difference >> 64) as u64) & 1;
+        }
+        borrow
+    }
+
+    /// self = other, wenn `m` = !0.
+    pub fn conditional_assign(&mut self, other: &FixedUint, m: u64) {
+        for (a, &b) in self.limbs.iter_mut().zip(&other.limbs) {
+            *a = select(m, b, *a);
+        }
+    }
+
+    /// Vertauscht a und b, wenn `m` = !0.
+    pub fn conditional_swap(a: &mut FixedUint, b: &mut FixedUint, m: u64) {
+        for (x, y) in a.limbs.iter_mut().zip(b.limbs.iter_mut()) {
+            let t = m & (*x ^ *y);
+            *x ^= t;
+            *y ^= t;
+        }
+    }
+
+    /// Um ein Bit nach rechts; `high` wird oben eingeschoben.
+    fn shr1(&mut self, high: u64) {
+        let mut carry = high & 1;
+        for limb in self.limbs.iter_mut().rev() {
+            let next = *limb & 1;
+            *limb = (*limb >> 1) | (carry << 63);
+            carry = next;
+        }
+    }
+
+    /// Um ein Bit nach links; gibt das herausgeschobene Bit zurück.
+    fn shl1(&mut self, low: u64) -> u64 {
+        let mut carry = low & 1;
+        for limb in self.limbs.iter_mut() {
+            let next = *limb >> 63;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        carry
+    }
+
+    /// Vollständiges Produkt mit `self.width() + other.width()` Limbs.
+    pub fn mul(&self, other: &FixedUint) -> FixedUint {
+        let mut product = vec![0u64; self.width() + other.width()];
+        for (i, &b) in other.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (t, &a) in product[i..].iter_mut().zip(&self.limbs) {
+                let uv = u128::from(*t) + u128::from(a) * u128::from(b) + u128::from(carry);
+                *t = uv as u64;
+                carry = (uv >> 64) as u64;
+            }
+            product[i + self.width()] = carry;
+        }
+        FixedUint { limbs: product }
+    }
+
+    /// Quotient und Rest (Breiten von self bzw. divisor) durch Schieben und Abziehen über
+    /// alle Bit von self; divisor darf nicht 0 sein.
+    pub fn div_rem(&self, divisor: &FixedUint) -> (FixedUint, FixedUint) {
+        let width = divisor.width() + 1;
+        let divisor = divisor.resized(width);
+        let mut quotient = FixedUint::zero(self.width());
+        let mut remainder = FixedUint::zero(width);
+        for i in (0..64 * self.width()).rev() {
+            remainder.shl1(self.bit(i));
+            let mut reduced = remainder.clone();
+            let fits = !mask(reduced.sub_assign(&divisor));
+            remainder.conditional_assign(&reduced, fits);
+            quotient.limbs[i / 64] |= (fits & 1) << (i % 64);
+        }
+        (quotient, remainder.resized(width - 1))
+    }
+
+    /// (self − other) mod m für self, other < m.
+    fn mod_sub(&self, other: &FixedUint, m: &FixedUint) -> FixedUint {
+        let mut difference = self.clone();
+        let borrow = difference.sub_assign(other);
+        let mut corrected = difference.clone();
+        corrected.add_assign(m);
+        difference.conditional_assign(&corrected, mask(borrow));
+        difference
+    }
+
+    /// self / 2 mod m für ungerades m und self < m.
+    fn mod_half(&self, m: &FixedUint) -> FixedUint {
+        let odd = mask(self.limbs[0]);
+        let mut sum = self.clone();
+        let mut addend = m.clone();
+        for limb in addend.limbs.iter_mut() {
+            *limb &= odd;
+        }
+        let carry = sum.add_assign(&addend);
+        sum.shr1(carry);
+        sum
+    }
+
+    /// Größter gemeinsamer Teiler (binär, 3·64·Breite Schritte).
+    pub fn gcd(&self, other: &FixedUint) -> FixedUint {
+        let bits = 64 * self.width();
+        let (mut a, mut b) = (self.clone(), other.clone());
+        // gemeinsame Zweierpotenz abspalten
+        let mut shift = 0u64;
+        for _ in 0..bits {
+            let both_even = mask(!(a.limbs[0] | b.limbs[0]));
+            let (mut half_a, mut half_b) = (a.clone(), b.clone());
+            half_a.shr1(0);
+            half_b.shr1(0);
+            a.conditional_assign(&half_a, both_even);
+            b.conditional_assign(&half_b, both_even);
+            shift += both_even & 1;
+        }
+        // a ungerade machen (außer beide sind 0)
+        let a_even = mask(!a.limbs[0]);
+        FixedUint::conditional_swap(&mut a, &mut b, a_even);
+        for _ in 0..2 * bits {
+            let b_odd = mask(b.limbs[0]);
+            let swap = b_odd & b.lt(&a);
+            FixedUint::conditional_swap(&mut a, &mut b, swap);
+            let mut subtrahend = a.clone();
+            for limb in subtrahend.limbs.iter_mut() {
+                *limb &= b_odd;
+            }
+            b.sub_assign(&subtrahend);
+            b.shr1(0);
+        }
+        for i in 0..bits as u64 {
+            let mut doubled = a.clone();
+            doubled.shl1(0);
+            a.conditional_assign(&doubled, lt_small(i, shift));
+        }
+        a
+    }
+
+    /// Inverses modulo des ungeraden `m` (self < m, gleiche Breite); `None`, wenn
+    /// ggT(self, m) ≠ 1. Binärer erweiterter euklidischer Algorithmus mit 2·64·Breite
+    /// Schritten.
+    pub fn inverse_odd(&self, m: &FixedUint) -> Option<FixedUint> {
+        assert_eq!(m.limbs[0] & 1, 1, "Modul muss ungerade sein");
+        // Invarianten: u·x ≡ a und v·x ≡ b (mod m), b bleibt ungerade
+        let (mut a, mut b) = (self.clone(), m.clone());
+        let mut u = FixedUint::zero(m.width());
+        u.limbs[0] = 1;
+        let mut v = FixedUint::zero(m.width());
+        for _ in 0..2 * 64 * m.width() {
+            let a_odd = mask(a.limbs[0]);
+            let swap = a_odd & a.lt(&b);
+            FixedUint::conditional_swap(&mut a, &mut b, swap);
+            FixedUint::conditional_swap(&mut u, &mut v, swap);
+            let mut subtrahend = b.clone();
+            for limb in subtrahend.limbs.iter_mut() {
+                *limb &= a_odd;
+            }
+            a.sub_assign(&subtrahend);
+            let mut v_masked = v.clone();
+            for limb in v_masked.limbs.iter_mut() {
+                *limb &= a_odd;
+            }
+            u = u.mod_sub(&v_masked, m);
+            a.shr1(0);
+            u = u.mod_half(m);
+        }
+        // jetzt b = ggT(x, m) und v·x ≡ b
+        let mut one = FixedUint::zero(m.width());
+        one.limbs[0] = 1;
+        (b.eq(&one) != 0).then_some(v)
+    }
+}
+
+/// Ungerader Modul für Montgomery-Arithmetik in konstanter Laufzeit.
+#[derive(Clone, Debug)]
+pub struct CtModulus {
+    n: FixedUint,
+    /// −n⁻¹ mod 2⁶⁴
+    n_prime: u64,
+    /// R² mod n, R = 2^(64·Breite)
+    r2: FixedUint,
+}
+
+impl CtModulus {
+    /// Kontext für das ungerade `n`. R² mod n entsteht durch fortgesetztes Verdoppeln
+    /// modulo n, ohne Division.
+    pub fn new(n: &FixedUint) -> Self {
+        assert_eq!(n.limbs[0] & 1, 1, "Modul muss ungerade sein");
+        let mut inverse = 1u64;
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(n.limbs[0].wrapping_mul(inverse)));
+        }
+        // 1 < n, also ist 1 schon reduziert; dann 2·64·Breite-mal verdoppeln
+        let mut r2 = FixedUint::zero(n.width());
+        r2.limbs[0] = 1;
+        for _ in 0..2 * 64 * n.width() {
+            let carry = r2.shl1(0);
+            let mut reduced = r2.clone();
+            let borrow = reduced.sub_assign(n);
+            // übergelaufen oder ≥ n: n abziehen
+            r2.conditional_assign(&reduced, mask(carry | (borrow ^ 1)));
+        }
+        CtModulus {
+            n: n.clone(),
+            n_prime: inverse.wrapping_neg(),
+            r2,
+        }
+    }
+
+    pub fn modulus(&self) -> &FixedUint {
+        &self.n
+    }
+
+    /// Montgomery-Produkt a·b·R⁻¹ mod n (CIOS), abschließendes Abziehen per Maske.
+    pub fn mont_mul(&self, a: &FixedUint, b: &FixedUint) -> FixedUint {
+        let s = self.n.width();
+        let n = &self.n.limbs;
+        let mut t = vec![0u64; s + 2];
+        for &bi in &b.limbs {
+            let mut carry = 0u64;
+            for (tj, &aj) in t.iter_mut().zip(&a.limbs) {
+                let uv = u128::from(*tj) + u128::from(aj) * u128::from(bi) + u128::from(carry);
+                *tj = uv as u64;
+                carry = (uv >> 64) as u64;
+            }
+            let uv = u128::from(t[s]) + u128::from(carry);
+            t[s] = uv as u64;
+            t[s + 1] = (uv >> 64) as u64;
+
+            let m = t[0].wrapping_mul(self.n_prime);
+            let uv = u128::from(t[0]) + u128::from(m) * u128::from(n[0]);
+            let mut carry = (uv >> 64) as u64;
+            for j in 1..s {
+                let uv = u128::from(t[j]) + u128::from(m) * u128::from(n[j]) + u128::from(carry);
+                t[j - 1] = uv as u64;
+                carry = (uv >> 64) as u64;
+            }
+            let uv = u128::from(t[s]) + u128::from(carry);
+            t[s - 1] = uv as u64;
+            t[s] = t[s + 1] + (uv >> 64) as u64;
+        }
+        let top = t[s];
+        t.truncate(s);
+        let mut result = FixedUint { limbs: t };
+        let mut reduced = result.clone();
+        let borrow = reduced.sub_assign(&self.n);
+        // t < 2n: abziehen, wenn ein Überlauf da war oder kein Borrow entstand
+        result.conditional_assign(&reduced, mask(top | (borrow ^ 1)));
+        result
+    }
+
+    /// a (< n) in Montgomery-Form.
+    pub fn to_montgomery(&self, a: &FixedUint) -> FixedUint {
+        self.mont_mul(a, &self.r2)
+    }
+
+    pub fn from_montgomery(&self, a: &FixedUint) -> FixedUint {
+        let mut one = FixedUint::zero(self.n.width());
+        one.limbs[0] = 1;
+        self.mont_mul(a, &one)
+    }
+
+    /// base^exponent mod n (base < n) mit festem 4-Bit-Fenster über alle Bit des Exponenten;
+    /// der Tabellenzugriff liest jedes Mal alle 16 Einträge.
+    pub fn pow(&self, base: &FixedUint, exponent: &FixedUint) -> FixedUint {
+        const WIDTH: usize = 4;
+        let mut one = FixedUint::zero(self.n.width());
+        one.limbs[0] = 1;
+        let mut table = vec![self.to_montgomery(&one), self.to_montgomery(base)];
+        for i in 2..1 << WIDTH {
+            table.push(self.mont_mul(&table[i - 1], &table[1]));
+        }
+
+        let mut result = table[0].clone();
+        for window in (0..64 * exponent.width() / WIDTH).rev() {
+            for _ in 0..WIDTH {
+                result = self.mont_mul(&result, &result);
+            }
+            let digit =
+                (0..WIDTH).fold(0u64, |acc, k| acc | (exponent.bit(window * WIDTH + k) << k));
+            let mut entry = FixedUint::zero(self.n.width());
+            for (i, candidate) in table.iter().enumerate() {
+                entry.conditional_assign(candidate, FixedUint::eq_small(i as u64, digit));
+            }
+            result = self.mont_mul(&result, &entry);
+        }
+        self.from_montgomery(&result)
+    }
+
+    /// Inverses von a (< n) für einen Primzahlmodul n: a^(n − 2) mod n.
+    pub fn inverse_prime(&self, a: &FixedUint) -> FixedUint {
+        let mut exponent = self.n.clone();
+        let mut two = FixedUint::zero(self.n.width());
+        two.limbs[0] = 2;
+        exponent.sub_assign(&two);
+        self.pow(a, &exponent)
+    }
+}
+
+impl FixedUint {
+    /// Maske: !0 für x = y bei kleinen Werten.
+    fn eq_small(x: u64, y: u64) -> u64 {
+        let diff = x ^ y;
+        mask(((diff | diff.wrapping_neg()) >> 63) ^ 1)
+    }
+}
+
+fn limbs_of(x: &BigUint) -> usize {
+    x.iter_u64_digits().len().max(1)
+}
+
+/// base^exponent mod modulus für ungerades `modulus`; der Exponent wird mit der Breite des
+/// Moduls (oder mehr, wenn er länger ist) verarbeitet.
+pub fn modpow(base: &BigUint, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+    let limbs = limbs_of(modulus);
+    let ctx = CtModulus::new(&FixedUint::from_biguint(modulus, limbs));
+    let base = FixedUint::from_biguint(&(base % modulus), limbs);
+    let exponent = FixedUint::from_biguint(exponent, limbs.max(limbs_of(exponent)));
+    ctx.pow(&base, &exponent).to_biguint()
+}
+
+/// a·b mod modulus für ungerades `modulus`.
+pub fn mul_mod(a: &BigUint, b: &BigUint, modulus: &BigUint) -> BigUint {
+    let limbs = limbs_of(modulus);
+    let ctx = CtModulus::new(&FixedUint::from_biguint(modulus, limbs));
+    let a = ctx.to_montgomery(&FixedUint::from_biguint(&(a % modulus), limbs));
+    // (a·R)·b·R⁻¹ = a·b
+    ctx.mont_mul(&a, &FixedUint::from_biguint(&(b % modulus), limbs))
+        .to_biguint()
+}
+
+/// ggT(a, b) mit der Breite des größeren Werts.
+pub fn gcd(a: &BigUint, b: &BigUint) -> BigUint {
+    let limbs = limbs_of(a).max(limbs_of(b));
+    FixedUint::from_biguint(a, limbs)
+        .gcd(&FixedUint::from_biguint(b, limbs))
+        .to_biguint()
+}
+
+/// kgV(a, b) = a·b / ggT(a, b); a und b dürfen nicht beide 0 sein.
+pub fn lcm(a: &BigUint, b: &BigUint) -> BigUint {
+    let limbs = limbs_of(a).max(limbs_of(b));
+    let (a, b) = (
+        FixedUint::from_biguint(a, limbs),
+        FixedUint::from_biguint(b, limbs),
+    );
+    let divisor = a.gcd(&b);
+    a.mul(&b).div_rem(&divisor).0.to_biguint()
+}
+
+/// a mod m.
+pub fn rem(a: &BigUint, m: &BigUint) -> BigUint {
+    let limbs = limbs_of(a).max(limbs_of(m));
+    FixedUint::from_biguint(a, limbs)
+        .div_rem(&FixedUint::from_biguint(m, limbs))
+        .1
+        .to_biguint()
+}
+
+/// a⁻¹ mod p für eine Primzahl p (Fermat); für zusammengesetzte p ist das Ergebnis
+/// falsch, Aufrufer mit ungeprüften Werten kontrollieren es mit [`mul_mod`].
+pub fn inverse_mod_prime(a: &BigUint, p: &BigUint) -> BigUint {
+    let limbs = limbs_of(p);
+    let ctx = CtModulus::new(&FixedUint::from_biguint(p, limbs));
+    ctx.inverse_prime(&FixedUint::from_biguint(&(a % p), limbs))
+        .to_biguint()
+}
+
+/// e⁻¹ mod m für ein öffentliches, ungerades e > 1 und ein geheimes m (etwa d = e⁻¹ mod λ(n)).
+///
+/// Mit t = (−m)⁻¹ mod e ist d = (1 + m·t) / e ganzzahlig und e·d ≡ 1 (mod m); nötig sind
+/// nur Rechnungen modulo e und eine exakte Division durch e. `None`, wenn ggT(e, m) ≠ 1.
+pub fn inverse_of_public(e: &BigUint, m: &BigUint) -> Option<BigUint> {
+    let limbs = limbs_of(m).max(limbs_of(e));
+    let m_fixed = FixedUint::from_biguint(m, limbs);
+    let e_fixed = FixedUint::from_biguint(e, limbs);
+    let m_mod_e = m_fixed.div_rem(&e_fixed).1;
+    let inverse = m_mod_e.inverse_odd(&e_fixed)?;
+    // t = e − m⁻¹ (mod e), hier nie 0, weil e > 1 und das Inverse nicht 0 ist
+    let mut t = e_fixed.clone();
+    t.sub_assign(&inverse);
+    let mut numerator = m_fixed.mul(&t);
+    let mut one = FixedUint::zero(numerator.width());
+    one.limbs[0] = 1;
+    numerator.add_assign(&one);
+    let (d, _) = numerator.div_rem(&e_fixed);
+    Some(d.resized(limbs).to_biguint())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::number_theory::{self, mod_inverse};
+    use num_bigint::RandBigInt;
+    use num_traits::One;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_fixed_uint_arithmetic() {
+        let mut rng = OsRng;
+        for bits in [1, 64, 65, 300] {
+            for _ in 0..10 {
+                let a = rng.gen_biguint(bits);
+                let b = rng.gen_biguint(bits) | BigUint::one();
+                let limbs = limbs_of(&a).max(limbs_of(&b)) + 1;
+                let (fa, fb) = (
+                    FixedUint::from_biguint(&a, limbs),
+                    FixedUint::from_biguint(&b, limbs),
+                );
+                assert_eq!(fa.lt(&fb) != 0, a < b);
+                assert_eq!(fa.eq(&fa.clone()), !0);
+                assert_eq!(fa.mul(&fb).to_biguint(), &a * &b);
+                let (q, r) = fa.div_rem(&fb);
+                assert_eq!((q.to_biguint(), r.to_biguint()), (&a / &b, &a % &b));
+                assert_eq!(gcd(&a, &b), number_theory::gcd(a.clone(), b.clone()));
+                assert_eq!(rem(&a, &b), &a % &b);
+                if b > BigUint::one() {
+                    let inverse = FixedUint::from_biguint(&(&a % &b), limbs)
+                        .inverse_odd(&fb)
+                        .map(|x| x.to_biguint());
+                    assert_eq!(inverse, mod_inverse(&(&a % &b), &b));
+                }
+            }
+        }
+        let twelve = BigUint::from(12u32);
+        assert_eq!(gcd(&twelve, &BigUint::from(18u32)), BigUint::from(6u32));
+        assert_eq!(gcd(&twelve, &BigUint::default()), twelve);
+        assert_eq!(lcm(&twelve, &BigUint::from(18u32)), BigUint::from(36u32));
+    }
+
+    #[test]
+    fn test_ct_modpow_and_inverse() {
+        let mut rng = OsRng;
+        for bits in [3, 64, 127, 512] {
+            let n = rng.gen_biguint(bits) | BigUint::one() | (BigUint::one() << (bits - 1));
+            for _ in 0..5 {
+                let base = rng.gen_biguint(bits + 3);
+                let exponent = rng.gen_biguint(bits + 70);
+                assert_eq!(modpow(&base, &exponent, &n), base.modpow(&exponent, &n));
+            }
+        }
+        let p = BigUint::from(1_000_000_007u64);
+        let a = BigUint::from(12345u32);
+        assert_eq!(inverse_mod_prime(&a, &p), mod_inverse(&a, &p).unwrap());
+        assert!(mul_mod(&inverse_mod_prime(&a, &p), &a, &p).is_one());
+        let b = rng.gen_biguint(900);
+        let n = rng.gen_biguint(512) | BigUint::one();
+        assert_eq!(mul_mod(&b, &a, &n), &b * &a % &n);
+
+        // d = e⁻¹ mod m für gerades, geheimes m
+        let e = BigUint::from(65537u32);
+        for _ in 0..10 {
+            let m = rng.gen_biguint(1000) << 1;
+            assert_eq!(inverse_of_public(&e, &m), mod_inverse(&e, &m));
+        }
+        assert_eq!(
+            inverse_of_public(&BigUint::from(3u32), &BigUint::from(12u32)),
+            None
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Lenstras Faktorisierung mit elliptischen Kurven (ECM).
+//!
+//! Gerechnet wird auf Montgomery-Kurven B·y² = x³ + A·x² + x in projektiven
+//! x-Koordinaten (X : Z), Kurven und Startpunkte nach Suyama, alle Werte in Montgomery-Form.
+//! Stufe 1 multipliziert den Punkt mit allen Primzahlpotenzen bis B1; Stufe 2 prüft jede
+//! Primzahl q bis B2 = 100·B1 mit Baby-Steps j·P und Giant-Steps k·D·P (q = k·D ± j). Ein
+//! Faktor p wird gefunden, wenn die Gruppenordnung der Kurve modulo p bis auf einen Faktor
+//! bis B2 B1-glatt ist; sie wechselt mit jeder Kurve, anders als p − 1 bei Pollard.
+
+use crate::algorithms::factorization::primes_below;
+use crate::algorithms::modular_arith::{MontgomeryContext, Residue};
+use crate::algorithms::number_theory::mod_inverse;
+use num_bigint::{BigUint, RandBigInt};
+use num_integer::Integer;
+use num_traits::One;
+use rand::thread_rng;
+
+/// Abstand der Giant-Steps (2·3·5·7).
+const D: u64 = 210;
+
+/// Punkt in x-Koordinaten (X : Z).
+#[derive(Clone)]
+struct Point {
+    x: Residue,
+    z: Residue,
+}
+
+/// Montgomery-Kurve modulo n mit a24 = (A + 2)/4.
+struct Curve<'a> {
+    ctx: &'a MontgomeryContext,
+    a24: Residue,
+}
+
+impl Curve<'_> {
+    /// 2P
+    fn double(&self, p: &Point) -> Point {
+        let ctx = self.ctx;
+        let t1 = ctx.square(&ctx.add(&p.x, &p.z));
+        let t2 = ctx.square(&ctx.sub(&p.x, &p.z));
+        let t3 = ctx.sub(&t1, &t2);
+        Point {
+            z: ctx.mul(&t3, &ctx.add(&t2, &ctx.mul(&self.a24, &t3))),
+            x: ctx.mul(&t1, &t2),
+        }
+    }
+
+    /// P + Q aus der bekannten Differenz P − Q.
+    fn add(&self, p: &Point, q: &Point, difference: &Point) -> Point {
+        let ctx = self.ctx;
+        let u = ctx.mul(&ctx.sub(&p.x, &p.z), &ctx.add(&q.x, &q.z));
+        let v = ctx.mul(&ctx.add(&p.x, &p.z), &ctx.sub(&q.x, &q.z));
+        Point {
+            x: ctx.mul(&difference.z, &ctx.square(&ctx.add(&u, &v))),
+            z: ctx.mul(&difference.x, &ctx.square(&ctx.sub(&u, &v))),
+        }
+    }
+
+    /// k·P mit der Montgomery-Leiter (k ≥ 1).
+    fn multiply(&self, k: u64, p: &Point) -> Point {
+        let mut low = p.clone();
+        let mut high = self.double(p);
+        for i in (0..63 - k.leading_zeros()).rev() {
+            if (k >> i) & 1 == 1 {
+                low = self.add(&high, &low, p);
+                high = self.double(&high);
+            } else {
+                high = self.add(&low, &high, p);
+                low = self.double(&low);
+            }
+        }
+        low
+    }
+}
+
+/// ggT(value, n), sofern er ein echter Teiler ist.
+fn proper_divisor(value: &BigUint, n: &BigUint) -> Option<BigUint> {
+    let g = value.gcd(n);
+    (!g.is_one() && &g != n).then_some(g)
+}
+
+/// Versucht `curves` zufällige Kurven mit Schranke `b1` (B2 = 100·B1).
+pub fn ecm(n: &BigUint, b1: u64, curves: usize) -> Option<BigUint> {
+    if *n < BigUint::from(4u32) {
+        // 0 bis 3 haben keinen echten Teiler
+        return None;
+    }
+    if n.is_even() {
+        return proper_divisor(&BigUint::from(2u32), n);
+    }
+    let ctx = MontgomeryContext::new(n)?;
+    let b2 = 100 * b1;
+    let primes = primes_below(b2 + 1);
+    let mut rng = thread_rng();
+    let three = BigUint::from(3u32);
+    for _ in 0..curves {
+        let sigma = rng.gen_biguint_range(&BigUint::from(6u32), n.max(&BigUint::from(7u32)));
+        // Suyama: u = σ² − 5, v = 4σ, P = (u³ : v³), (A + 2)/4 = (v − u)³(3u + v) / (16u³v)
+        let u = (&sigma * &sigma + n - 5u32) % n;
+        let v = (&sigma * 4u32) % n;
+        let u3 = u.modpow(&three, n);
+        let denominator = (&u3 * &v * 16u32) % n;
+        let Some(inverse) = mod_inverse(&denominator, n) else {
+            match proper_divisor(&denominator, n) {
+                Some(g) => return Some(g),
+                None => continue,
+            }
+        };
+        let numerator = ((&v + n - &u) % n).modpow(&three, n) * (&u * 3u32 + &v) % n;
+        let curve = Curve {
+            ctx: &ctx,
+            a24: ctx.residue(&(numerator * inverse)),
+        };
+        let mut point = Point {
+            x: ctx.residue(&u3),
+            z: ctx.residue(&v.modpow(&three, n)),
+        };
+
+        // Stufe 1
+        for &q in primes.iter().take_while(|&&q| q <= b1) {
+            let mut power = q;
+            while power <= b1 / q {
+                power *= q;
+            }
+            point = curve.multiply(power, &point);
+        }
+        let z = ctx.value(&point.z);
+        if let Some(g) = proper_divisor(&z, n) {
+            return Some(g);
+        }
+        if &z.gcd(n) == n {
+            continue;
+        }
+        if let Some(g) = stage2(&curve, &point, &primes, b1, b2) {
+            return Some(g);
+        }
+    }
+    None
+}
+
+/// Stufe 2: Für jede Primzahl B1 < q ≤ B2 mit q = k·D ± j geht X_T·Z_S − X_S·Z_T mit
+/// T = k·D·Q und S = j·Q in ein Produkt ein; es ist modulo p null, wenn q·Q = O gilt.
+fn stage2(curve: &Curve, q: &Point, primes: &[u64], b1: u64, b2: u64) -> Option<BigUint> {
+    let ctx = curve.ctx;
+    let n = ctx.modulus();
+    // Baby-Steps j·Q für ungerade j < D/2
+    let double = curve.double(q);
+    let mut babies = vec![q.clone(), curve.add(&double, q, q)];
+    while babies.len() < (D / 4) as usize {
+        let len = babies.len();
+        let next = curve.add(&babies[len - 1], &double, &babies[len - 2]);
+        babies.push(next);
+    }
+    let baby = |j: u64| &babies[(j / 2) as usize];
+
+    // Giant-Steps k·D·Q, fortlaufend ab k = 1
+    let step = curve.multiply(D, q);
+    let mut giant = step.clone();
+    let mut previous: Option<Point> = None;
+    let mut k = 1;
+    let mut product = ctx.one();
+    for (i, &prime) in primes.iter().filter(|&&p| p > b1 && p <= b2).enumerate() {
+        let target = (prime + D / 2) / D;
+        let term = if target == 0 {
+            // q < D/2: q·Q = O genau dann, wenn Z verschwindet
+            baby(prime).z.clone()
+        } else {
+            while k < target {
+                let next = match &previous {
+                    Some(previous) => curve.add(&giant, &step, previous),
+                    None => curve.double(&giant),
+                };
+                previous = Some(std::mem::replace(&mut giant, next));
+                k += 1;
+            }
+            let s = baby(prime.abs_diff(k * D));
+            ctx.sub(&ctx.mul(&giant.x, &s.z), &ctx.mul(&s.x, &giant.z))
+        };
+        product = ctx.mul(&product, &term);
+        if i % 512 == 511 {
+            if let Some(g) = proper_divisor(&ctx.value(&product), n) {
+                return Some(g);
+            }
+        }
+    }
+    proper_divisor(&ctx.value(&product), n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_arithmetic_and_ecm() {
+        // Leiter stimmt mit wiederholter Addition überein
+        let n = BigUint::from(1_000_000_007u64);
+        let ctx = MontgomeryContext::new(&n).unwrap();
+        let curve = Curve {
+            ctx: &ctx,
+            a24: ctx.residue(&BigUint::from(12345u32)),
+        };
+        let p = Point {
+            x: ctx.residue(&BigUint::from(7u32)),
+            z: ctx.one(),
+        };
+        let mut sum = curve.double(&p);
+        let mut previous = p.clone();
+        for k in 3u64..20 {
+            let next = curve.add(&sum, &p, &previous);
+            previous = std::mem::replace(&mut sum, next);
+            let expected = curve.multiply(k, &p);
+            // gleiche projektive Punkte: X₁·Z₂ = X₂·Z₁
+            assert_eq!(ctx.mul(&sum.x, &expected.z), ctx.mul(&expected.x, &sum.z));
+        }
+
+        // zwei 12-stellige Primfaktoren
+        let p = BigUint::from(100_000_000_003u64);
+        let q = BigUint::from(999_999_999_989u64);
+        let n = &p * &q;
+        let factor = ecm(&n, 2000, 200).unwrap();
+        assert!(factor == p || factor == q);
+    }
+}
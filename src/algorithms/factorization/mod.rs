@@ -0,0 +1,309 @@
+//! Zerlegung ganzer Zahlen in Primfaktoren.
+//!
+//! [`factorize`] setzt die Verfahren vom billigsten zum teuersten ein: Probedivision bis
+//! 2¹⁶, Erkennen echter Potenzen, Pollards Rho in der Variante von Brent, Pollards p − 1
+//! und Williams' p + 1 mit kleinen Schranken. Was dann noch zusammengesetzt ist, geht bis
+//! [`SIQS_MAX_DIGITS`] Dezimalstellen nach einigen ECM-Kurven an das quadratische Sieb
+//! (SIQS), dessen Laufzeit nur von der Größe der Zahl abhängt; größere Zahlen bearbeitet ECM
+//! mit wachsenden Schranken, bis ein Faktor gefunden ist. Die einzelnen Verfahren sind auch
+//! direkt aufrufbar.
+
+mod ecm;
+mod pm1;
+mod rho;
+mod siqs;
+
+pub use ecm::ecm;
+pub use pm1::{pollard_pm1, williams_pp1};
+pub use rho::pollard_brent;
+pub use siqs::siqs;
+
+use crate::algorithms::primality::is_prime_baillie_psw;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+use std::collections::BTreeMap;
+
+/// Obergrenze der Probedivision.
+const TRIAL_DIVISION_LIMIT: u32 = 1 << 16;
+
+/// Größte Zahl (in Dezimalstellen), für die [`factorize`] das quadratische Sieb einsetzt.
+pub const SIQS_MAX_DIGITS: u64 = 80;
+
+/// ECM-Schranken B1 und Kurvenzahlen, nach den Empfehlungen von GMP-ECM für Faktoren mit
+/// 20, 25, 30, 35, 40, 45 und 50 Stellen.
+const ECM_SCHEDULE: [(u64, usize); 7] = [
+    (11_000, 90),
+    (50_000, 300),
+    (250_000, 700),
+    (1_000_000, 1800),
+    (3_000_000, 5100),
+    (11_000_000, 10_600),
+    (43_000_000, 19_300),
+];
+
+/// Primfaktorzerlegung einer Zahl n > 0: Primfaktoren aufsteigend mit Exponenten.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Factorization {
+    pub factors: Vec<(BigUint, u32)>,
+}
+
+impl Factorization {
+    /// Die zerlegte Zahl.
+    pub fn value(&self) -> BigUint {
+        self.factors
+            .iter()
+            .fold(BigUint::one(), |acc, (p, e)| acc * p.pow(*e))
+    }
+
+    /// Eulersche φ-Funktion: Π p^(e − 1)·(p − 1).
+    pub fn totient(&self) -> BigUint {
+        self.factors.iter().fold(BigUint::one(), |acc, (p, e)| {
+            acc * p.pow(e - 1) * (p - 1u32)
+        })
+    }
+
+    /// Carmichael-Funktion λ(n), der kleinste Exponent mit a^λ ≡ 1 für alle zu n
+    /// teilerfremden a: kgV der λ(p^e), wobei λ(2^e) = 2^(e − 2) für e ≥ 3.
+    pub fn carmichael_lambda(&self) -> BigUint {
+        self.factors.iter().fold(BigUint::one(), |acc, (p, e)| {
+            let part = if p == &BigUint::from(2u32) && *e >= 3 {
+                BigUint::one() << (e - 2)
+            } else {
+                p.pow(e - 1) * (p - 1u32)
+            };
+            acc.lcm(&part)
+        })
+    }
+
+    /// Anzahl der Teiler τ(n) = Π (e + 1).
+    pub fn divisor_count(&self) -> BigUint {
+        self.factors
+            .iter()
+            .fold(BigUint::one(), |acc, (_, e)| acc * (e + 1))
+    }
+
+    /// Teilersumme σ_k(n) = Σ d^k über alle Teiler d; σ₀ ist die Teileranzahl.
+    pub fn divisor_sum(&self, k: u32) -> BigUint {
+        self.factors.iter().fold(BigUint::one(), |acc, (p, e)| {
+            let pk = p.pow(k);
+            // 1 + p^k + p^2k + … + p^(e·k)
+            let mut term = BigUint::one();
+            let mut sum = BigUint::one();
+            for _ in 0..*e {
+                term *= &pk;
+                sum += &term;
+            }
+            acc * sum
+        })
+    }
+
+    /// Alle Teiler aufsteigend.
+    pub fn divisors(&self) -> Vec<BigUint> {
+        let mut divisors = vec![BigUint::one()];
+        for (p, e) in &self.factors {
+            let mut next = Vec::with_capacity(divisors.len() * (*e as usize + 1));
+            for d in &divisors {
+                let mut power = d.clone();
+                next.push(power.clone());
+                for _ in 0..*e {
+                    power *= p;
+                    next.push(power.clone());
+                }
+            }
+            divisors = next;
+        }
+        divisors.sort();
+        divisors
+    }
+}
+
+/// Zerlegt n > 0 vollständig in Primfaktoren (Primalität nach Baillie-PSW).
+///
+/// Die Laufzeit hängt vom zweitgrößten Primfaktor ab: Faktoren bis etwa 20 Stellen findet
+/// Rho oder ECM in Sekundenbruchteilen, Zahlen bis etwa 60 Stellen zerlegt das Sieb in
+/// Sekunden bis Minuten; darüber wird ECM bei großen Faktoren sehr langsam.
+pub fn factorize(n: &BigUint) -> Factorization {
+    assert!(!n.is_zero(), "0 hat keine Primfaktorzerlegung");
+    let mut factors = BTreeMap::new();
+    let rest = trial_division(n, &mut factors);
+
+    let mut pending = vec![(rest, 1u32)];
+    while let Some((c, multiplicity)) = pending.pop() {
+        if c.is_one() {
+            continue;
+        }
+        if is_prime_baillie_psw(&c) {
+            *factors.entry(c).or_insert(0) += multiplicity;
+        } else if let Some((root, k)) = perfect_power(&c) {
+            pending.push((root, multiplicity * k));
+        } else {
+            let d = find_factor(&c);
+            pending.push((&c / &d, multiplicity));
+            pending.push((d, multiplicity));
+        }
+    }
+    Factorization {
+        factors: factors.into_iter().collect(),
+    }
+}
+
+/// Primzahlen unter `limit` (Sieb des Eratosthenes).
+pub(crate) fn primes_below(limit: u64) -> Vec<u64> {
+    let limit = limit as usize;
+    let mut composite = vec![false; limit.max(2)];
+    let mut primes = Vec::new();
+    for i in 2..limit {
+        if !composite[i] {
+            primes.push(i as u64);
+            for j in (i * i..limit).step_by(i) {
+                composite[j] = true;
+            }
+        }
+    }
+    primes
+}
+
+/// Teilt alle Primfaktoren unter [`TRIAL_DIVISION_LIMIT`] ab und gibt den Rest zurück.
+fn trial_division(n: &BigUint, factors: &mut BTreeMap<BigUint, u32>) -> BigUint {
+    let mut n = n.clone();
+    for p in primes_below(u64::from(TRIAL_DIVISION_LIMIT)) {
+        if BigUint::from(p * p) > n {
+            break;
+        }
+        while (&n % p).is_zero() {
+            n /= p;
+            *factors.entry(BigUint::from(p)).or_insert(0) += 1;
+        }
+    }
+    // der Rest ist 1, prim oder hat nur Faktoren über der Grenze
+    if n.to_u64().is_some_and(|small| small < 1 << 32) && !n.is_one() {
+        *factors.entry(n).or_insert(0) += 1;
+        return BigUint::one();
+    }
+    n
+}
+
+/// (m, k) mit n = m^k für das kleinste k ≥ 2, falls n eine echte Potenz ist.
+pub(crate) fn perfect_power(n: &BigUint) -> Option<(BigUint, u32)> {
+    (2..=n.bits() as u32).find_map(|k| {
+        let root = n.nth_root(k);
+        (root.pow(k) == *n && !root.is_one()).then_some((root, k))
+    })
+}
+
+/// Echter Teiler der zusammengesetzten Zahl `n`, die keine Potenz ist und keine kleinen
+/// Faktoren hat.
+fn find_factor(n: &BigUint) -> BigUint {
+    let proper = |d: Option<BigUint>| d.filter(|d| !d.is_one() && d != n);
+    if let Some(d) = proper(pollard_brent(n, 1 << 14)) {
+        return d;
+    }
+    if let Some(d) = proper(pollard_pm1(n, 10_000, 1_000_000)) {
+        return d;
+    }
+    if let Some(d) = proper(williams_pp1(n, 10_000)) {
+        return d;
+    }
+    let digits = (n.bits() as f64 * std::f64::consts::LOG10_2) as u64 + 1;
+    if digits <= SIQS_MAX_DIGITS {
+        // Faktoren bis etwa 15 bzw. 20 Stellen findet ECM schneller als das Sieb
+        if digits > 40 {
+            if let Some(d) = proper(ecm(n, 2_000, 25)) {
+                return d;
+            }
+        }
+        if digits > 55 {
+            if let Some(d) = proper(ecm(n, 11_000, 90)) {
+                return d;
+            }
+        }
+        if let Some(d) = proper(siqs(n)) {
+            return d;
+        }
+    }
+    let mut schedule = ECM_SCHEDULE.iter().chain(std::iter::repeat(
+        ECM_SCHEDULE.last().expect("Tabelle ist nicht leer"),
+    ));
+    loop {
+        let &(b1, curves) = schedule.next().expect("endlose Folge");
+        if let Some(d) = proper(ecm(n, b1, curves)) {
+            return d;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn factors(pairs: &[(u64, u32)]) -> Factorization {
+        Factorization {
+            factors: pairs.iter().map(|&(p, e)| (BigUint::from(p), e)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_factorize() {
+        assert_eq!(factorize(&BigUint::one()), Factorization::default());
+        assert_eq!(
+            factorize(&BigUint::from(360u32)),
+            factors(&[(2, 3), (3, 2), (5, 1)])
+        );
+        // Mersenne-Zahl 2⁶⁷ − 1 (Cole 1903), Potenz eines großen Primfaktors
+        let m67 = (BigUint::one() << 67) - 1u32;
+        assert_eq!(
+            factorize(&m67),
+            factors(&[(193_707_721, 1), (761_838_257_287, 1)])
+        );
+        let p = BigUint::from(1_000_000_007u64);
+        let q = BigUint::from(998_244_353u64);
+        let n = p.pow(3) * &q;
+        assert_eq!(factorize(&n).factors, vec![(q.clone(), 1), (p.clone(), 3)]);
+        // Semiprimzahl mit zwei 20-stelligen Faktoren
+        let p = BigUint::parse_bytes(b"10000000000000000051", 10).unwrap();
+        let q = BigUint::parse_bytes(b"18446744073709551629", 10).unwrap();
+        let f = factorize(&(&p * &q));
+        assert_eq!(f.factors, vec![(p, 1), (q, 1)]);
+    }
+
+    #[test]
+    fn test_trivial_inputs() {
+        for n in 0u32..4 {
+            let n = BigUint::from(n);
+            assert_eq!(pollard_brent(&n, 1 << 10), None);
+            assert_eq!(pollard_pm1(&n, 100, 1000), None);
+            assert_eq!(williams_pp1(&n, 100), None);
+            assert_eq!(ecm(&n, 100, 2), None);
+            assert_eq!(siqs(&n), None);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_functions() {
+        let f = factorize(&BigUint::from(720u32)); // 2⁴·3²·5
+        assert_eq!(f.value(), BigUint::from(720u32));
+        assert_eq!(f.totient(), BigUint::from(192u32));
+        assert_eq!(f.carmichael_lambda(), BigUint::from(12u32));
+        assert_eq!(f.divisor_count(), BigUint::from(30u32));
+        assert_eq!(f.divisor_sum(0), BigUint::from(30u32));
+        assert_eq!(f.divisor_sum(1), BigUint::from(2418u32));
+        let divisors = f.divisors();
+        assert_eq!(divisors.len(), 30);
+        assert_eq!(divisors[..4], [1u32, 2, 3, 4].map(BigUint::from));
+        assert!(divisors
+            .iter()
+            .all(|d| (BigUint::from(720u32) % d).is_zero()));
+
+        // λ(561) = kgV(2, 10, 16) = 80: Carmichael-Zahl
+        assert_eq!(
+            factorize(&BigUint::from(561u32)).carmichael_lambda(),
+            BigUint::from(80u32)
+        );
+        assert_eq!(
+            perfect_power(&BigUint::from(3u32).pow(40)),
+            Some((BigUint::from(3u32).pow(20), 2))
+        );
+        assert_eq!(perfect_power(&BigUint::from(12u32)), None);
+    }
+}
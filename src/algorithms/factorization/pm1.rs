@@ -0,0 +1,148 @@
+//! Pollards p − 1 und Williams' p + 1.
+//!
+//! Beide finden einen Primfaktor p, wenn p − 1 bzw. p + 1 glatt ist, d. h. nur Primfaktoren
+//! bis zur Schranke B1 hat (bei p − 1 zusätzlich höchstens einen bis B2).
+
+use crate::algorithms::factorization::primes_below;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+/// Größte Potenz von `p`, die `bound` nicht übersteigt.
+fn max_power(p: u64, bound: u64) -> u64 {
+    let mut power = p;
+    while power <= bound / p {
+        power *= p;
+    }
+    power
+}
+
+/// Pollards p − 1: a = 2^M mit M = Π q^e ≤ B1 (Stufe 1), danach a^q für jede Primzahl
+/// B1 < q ≤ B2 (Stufe 2). Ergebnis ist ggT(a − 1, n), sofern echt.
+pub fn pollard_pm1(n: &BigUint, b1: u64, b2: u64) -> Option<BigUint> {
+    if *n < BigUint::from(4u32) {
+        // 0 bis 3 haben keinen echten Teiler
+        return None;
+    }
+    let primes = primes_below(b2.max(b1) + 1);
+    let mut a = BigUint::from(2u32);
+    for &q in primes.iter().take_while(|&&q| q <= b1) {
+        a = a.modpow(&BigUint::from(max_power(q, b1)), n);
+    }
+    let minus_one = |x: &BigUint| (x + n - 1u32) % n;
+    let g = minus_one(&a).gcd(n);
+    if &g == n {
+        return None;
+    }
+    if !g.is_one() {
+        return Some(g);
+    }
+
+    // Stufe 2: a^q aus a^(vorige Primzahl) mal a^Lücke; Lücken sind klein und gerade, daher
+    // ohne die 2 (bei B1 < 2 gehört sie sonst zu Stufe 2 und ergäbe die Lücke 1)
+    let stage2: Vec<u64> = primes.into_iter().filter(|&q| q > b1.max(2)).collect();
+    let &first = stage2.first()?;
+    let mut gaps: Vec<BigUint> = Vec::new();
+    let mut x = a.modpow(&BigUint::from(first), n);
+    let mut product = minus_one(&x);
+    let check = |product: &BigUint| {
+        let g = product.gcd(n);
+        // Produkt 0: alle Faktoren auf einmal erfasst
+        (!product.is_zero() && !g.is_one()).then_some(g)
+    };
+    for (i, pair) in stage2.windows(2).enumerate() {
+        let gap = ((pair[1] - pair[0]) / 2) as usize;
+        while gaps.len() < gap {
+            let next = match gaps.last() {
+                Some(last) => last * &a * &a % n,
+                None => &a * &a % n,
+            };
+            gaps.push(next);
+        }
+        x = x * &gaps[gap - 1] % n;
+        product = product * minus_one(&x) % n;
+        if i % 1024 == 1023 {
+            if product.is_zero() {
+                return None;
+            }
+            if let Some(g) = check(&product) {
+                return (&g != n).then_some(g);
+            }
+        }
+    }
+    check(&product).filter(|g| g != n)
+}
+
+/// V_m(v) mod n der Lucas-Folge V₀ = 2, V₁ = v, V_(k+1) = v·V_k − V_(k−1) (Leiter über
+/// die Bits von m: V_2k = V_k² − 2, V_(2k+1) = V_k·V_(k+1) − v).
+fn lucas_v(v: &BigUint, m: u64, n: &BigUint) -> BigUint {
+    let two = BigUint::from(2u32);
+    let sub = |a: BigUint, b: &BigUint| (a + n - b) % n;
+    let mut low = v.clone();
+    let mut high = sub(v * v, &two);
+    for i in (0..63 - m.leading_zeros()).rev() {
+        if (m >> i) & 1 == 1 {
+            low = sub(&low * &high, v);
+            high = sub(&high * &high, &two);
+        } else {
+            high = sub(&low * &high, v);
+            low = sub(&low * &low, &two);
+        }
+    }
+    low
+}
+
+/// Williams' p + 1 (Stufe 1): V_M(A) mit M = Π q^e ≤ B1 für einige Startwerte A;
+/// ggT(V − 2, n) enthält p, wenn p + 1 (oder p − 1, je nach A) B1-glatt ist.
+pub fn williams_pp1(n: &BigUint, b1: u64) -> Option<BigUint> {
+    if *n < BigUint::from(4u32) {
+        // 0 bis 3 haben keinen echten Teiler
+        return None;
+    }
+    let primes = primes_below(b1 + 1);
+    for seed in [3u32, 5, 7, 11] {
+        let mut v = BigUint::from(seed) % n;
+        for &q in &primes {
+            v = lucas_v(&v, max_power(q, b1), n);
+        }
+        let g = ((v + n - 2u32) % n).gcd(n);
+        if !g.is_one() && &g != n {
+            return Some(g);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pm1_and_pp1() {
+        // 2_147_483_647 − 1 = 2·3²·7·11·31·151·331, 1_000_000_007 − 1 = 2·500000003
+        let other = BigUint::from(1_000_000_007u64);
+        let smooth = BigUint::from(2_147_483_647u64);
+        let n = &smooth * &other;
+        assert_eq!(pollard_pm1(&n, 400, 400), Some(smooth));
+        assert_eq!(pollard_pm1(&other, 1000, 100_000), None);
+        // Ordnung von 2 enthält 409: erst Stufe 2 findet den Faktor
+        let p = BigUint::from(200_200_563_371u64);
+        let n = &p * &other;
+        assert_eq!(pollard_pm1(&n, 200, 200), None);
+        assert_eq!(pollard_pm1(&n, 200, 1000), Some(p));
+        // B1 < 2: Stufe 2 beginnt bei 3; 2 hat modulo 2¹³ − 1 die Ordnung 13
+        let mersenne = BigUint::from(8191u32);
+        let n = &mersenne * &other;
+        assert_eq!(pollard_pm1(&n, 1, 1000), Some(mersenne.clone()));
+        assert_eq!(pollard_pm1(&n, 0, 1000), Some(mersenne));
+
+        // 4_603_587_419 + 1 = 2²·3·5·17·31·41·53·67, 1_000_000_007 + 1 enthält 109²
+        let p = BigUint::from(4_603_587_419u64);
+        assert_eq!(williams_pp1(&(&p * &other), 100), Some(p));
+        // V₅(3) = 123
+        assert_eq!(
+            lucas_v(&BigUint::from(3u32), 5, &BigUint::from(1000u32)),
+            BigUint::from(123u32)
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! Pollards Rho-Methode in der Variante von Brent.
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::One;
+
+/// Sucht einen echten Teiler der zusammengesetzten Zahl `n` mit höchstens etwa
+/// `max_steps` Iterationen je Startwert; gibt danach auf.
+///
+/// Der Aufwand wächst mit der Wurzel des kleinsten Primfaktors, Faktoren bis etwa 10¹²
+/// sind schnell gefunden.
+pub fn pollard_brent(n: &BigUint, max_steps: u64) -> Option<BigUint> {
+    const BATCH: u64 = 128;
+    if *n < BigUint::from(4u32) {
+        // 0 bis 3 haben keinen echten Teiler
+        return None;
+    }
+    let one = BigUint::one();
+    let distance = |x: &BigUint, y: &BigUint| if x > y { x - y } else { y - x };
+
+    for c in 1u32..=8 {
+        let f = |x: &BigUint| (x * x + c) % n;
+        let mut y = BigUint::from(2u32);
+        let mut x = y.clone();
+        let mut saved = y.clone();
+        let mut product = one.clone();
+        let mut g = one.clone();
+        let mut r = 1u64;
+        while g.is_one() && r <= max_steps {
+            x = y.clone();
+            for _ in 0..r {
+                y = f(&y);
+            }
+            let mut k = 0;
+            while k < r && g.is_one() {
+                saved = y.clone();
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(&y);
+                    product = product * distance(&x, &y) % n;
+                }
+                g = product.gcd(n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+        if &g == n {
+            // Produkt wurde 0: den letzten Block Schritt für Schritt wiederholen
+            for _ in 0..BATCH {
+                saved = f(&saved);
+                g = distance(&x, &saved).gcd(n);
+                if !g.is_one() {
+                    break;
+                }
+            }
+        }
+        if !g.is_one() && &g != n {
+            return Some(g);
+        }
+    }
+    None
+}
@@ -0,0 +1,568 @@
+//! Selbstinitialisierendes quadratisches Sieb (SIQS) nach Contini.
+//!
+//! Gesucht sind x mit (a·x + b)² − k·n = a·g(x), deren g(x) über der Faktorbasis zerfällt
+//! (Primzahlen p mit (k·n | p) = 1). Jede solche Relation liefert (a·x + b)² ≡ a·g(x) mod n;
+//! ein Produkt von Relationen, in dem jede Primzahl geradzahlig vorkommt, ergibt X² ≡ Y²
+//! und mit Wahrscheinlichkeit 1/2 einen Faktor ggT(X − Y, n).
+//!
+//! Glatte Werte findet ein Sieb über [−M, M): Statt g(x) zu teilen, werden an den Nullstellen
+//! von g modulo p die gerundeten log₂ p addiert; nur Stellen über einer Schwelle werden
+//! probegeteilt. a ist ein Produkt von s Primzahlen der Faktorbasis, zu jedem a gibt es
+//! 2^(s − 1) Werte b, zwischen denen die Nullstellen mit einer Addition pro Primzahl
+//! wechseln (Gray-Code). Werte mit einem Rest bis 64·p_max werden aufgehoben und zu zweit
+//! mit gleichem Rest zu einer Relation verbunden.
+
+use crate::algorithms::factorization::{pollard_brent, primes_below};
+use crate::algorithms::number_theory::sqrt_mod_prime;
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use std::collections::{HashMap, HashSet};
+
+/// Dezimalstellen, Größe der Faktorbasis und halbe Sieblänge M; es gilt die erste Zeile, die
+/// mindestens so viele Stellen hat wie n.
+const PARAMETERS: [(u64, usize, usize); 13] = [
+    (20, 60, 8_192),
+    (25, 100, 16_384),
+    (30, 160, 16_384),
+    (35, 250, 32_768),
+    (40, 400, 32_768),
+    (45, 600, 65_536),
+    (50, 900, 65_536),
+    (55, 1_300, 65_536),
+    (60, 1_800, 98_304),
+    (65, 2_500, 98_304),
+    (70, 3_300, 131_072),
+    (75, 4_300, 131_072),
+    (80, 5_500, 163_840),
+];
+
+/// Quadratfreie Multiplikatoren k, unter denen Knuth-Schroeppel wählt.
+const MULTIPLIERS: [u32; 31] = [
+    1, 2, 3, 5, 6, 7, 10, 11, 13, 14, 15, 17, 19, 21, 22, 23, 26, 29, 30, 31, 33, 34, 35, 37, 38,
+    39, 41, 42, 43, 46, 47,
+];
+
+/// Primzahlen darunter werden nicht gesiebt, nur bei der Probedivision berücksichtigt.
+const SIEVE_MIN_PRIME: u32 = 30;
+
+/// Obergrenze einer großen Primzahl als Vielfaches der größten Primzahl der Faktorbasis.
+const LARGE_PRIME_MULTIPLIER: u64 = 64;
+
+/// Abschlag der Siebschwelle in Bit für die nicht gesiebten kleinen Primzahlen, gerundete
+/// Logarithmen und |g(x)|, die meist deutlich unter dem Maximum liegen (durch Messung bestimmt).
+const THRESHOLD_SLACK: f64 = 18.0;
+
+/// Relationen über die Zahl der Spalten hinaus, damit genug Abhängigkeiten entstehen.
+const EXTRA_RELATIONS: usize = 20;
+
+/// Abbruch nach so vielen Polynomen je benötigter Relation.
+const MAX_POLYNOMIALS_PER_RELATION: usize = 200;
+
+/// Primzahl der Faktorbasis mit einer Wurzel von k·n modulo p.
+struct Prime {
+    p: u32,
+    sqrt: u32,
+    log: u8,
+}
+
+/// (a·x + b)² ≡ a·g(x) (mod n) mit vollständig zerlegtem a·g(x), bis auf große
+/// Primzahlen, die quadratisch vorkommen.
+#[derive(Clone)]
+struct Relation {
+    /// a·x + b mod n
+    y: BigUint,
+    /// (Spalte, Exponent) aufsteigend; Spalte 0 ist das Vorzeichen, Spalte j + 1 die j-te
+    /// Primzahl der Faktorbasis.
+    factors: Vec<(u32, u32)>,
+    /// Produkt der quadratisch vorkommenden großen Primzahlen (sonst 1).
+    large: u64,
+}
+
+impl Relation {
+    /// Verbindet zwei Relationen mit demselben Rest `large` zu einer vollständigen.
+    fn combine(&self, other: &Relation, n: &BigUint) -> Relation {
+        let mut factors = self.factors.clone();
+        factors.extend_from_slice(&other.factors);
+        Relation {
+            y: &self.y * &other.y % n,
+            factors: merge(factors),
+            large: self.large,
+        }
+    }
+}
+
+/// Sortiert (Spalte, Exponent)-Paare und fasst gleiche Spalten zusammen.
+fn merge(mut factors: Vec<(u32, u32)>) -> Vec<(u32, u32)> {
+    factors.sort_unstable();
+    let mut merged: Vec<(u32, u32)> = Vec::with_capacity(factors.len());
+    for (column, exponent) in factors {
+        match merged.last_mut() {
+            Some(last) if last.0 == column => last.1 += exponent,
+            _ => merged.push((column, exponent)),
+        }
+    }
+    merged
+}
+
+fn pow_mod(mut base: u64, mut exponent: u64, p: u64) -> u64 {
+    let mut result = 1;
+    base %= p;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % p;
+        }
+        base = base * base % p;
+        exponent >>= 1;
+    }
+    result
+}
+
+/// x mod p im Bereich [0, p).
+fn residue(x: &BigInt, p: u64) -> u64 {
+    let r = (x % p).to_i64().expect("Rest kleiner als p");
+    r.rem_euclid(p as i64) as u64
+}
+
+/// Knuth-Schroeppel: wählt k so, dass k·n modulo vieler kleiner Primzahlen ein Quadrat ist
+/// und die Faktorbasis damit aus kleinen Primzahlen besteht.
+fn multiplier(n: &BigUint) -> u32 {
+    let primes = primes_below(500);
+    let ln2 = std::f64::consts::LN_2;
+    let score = |k: u32| {
+        let kn = n * k;
+        let mut score = -0.5 * f64::from(k).ln();
+        score += match (&kn % 8u32).to_u32() {
+            Some(1) => 2.0 * ln2,
+            Some(5) => ln2,
+            Some(3) | Some(7) => 0.5 * ln2,
+            _ => 0.0,
+        };
+        for &p in &primes[1..] {
+            let r = (&kn % p).to_u64().expect("Rest kleiner als p");
+            let ln_p = (p as f64).ln();
+            if r == 0 {
+                score += ln_p / p as f64;
+            } else if pow_mod(r, (p - 1) / 2, p) == 1 {
+                score += 2.0 * ln_p / (p - 1) as f64;
+            }
+        }
+        score
+    };
+    MULTIPLIERS
+        .into_iter()
+        .map(|k| (score(k), k))
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .expect("Liste ist nicht leer")
+        .1
+}
+
+/// Faktorbasis mit `size` Primzahlen, oder ein Faktor von n, falls eine davon n teilt.
+fn factor_base(n: &BigUint, kn: &BigUint, size: usize) -> Result<Vec<Prime>, BigUint> {
+    let mut limit = (size as f64 * (size as f64).ln() * 3.0) as u64 + 100;
+    loop {
+        let mut base = Vec::with_capacity(size);
+        for p in primes_below(limit) {
+            let r = (kn % p).to_u64().expect("Rest kleiner als p");
+            if (n % p).is_zero() {
+                return Err(BigUint::from(p));
+            }
+            let sqrt = if p == 2 || r == 0 {
+                r
+            } else if pow_mod(r, (p - 1) / 2, p) == 1 {
+                sqrt_mod_prime(&BigUint::from(r), &BigUint::from(p))
+                    .and_then(|s| s.to_u64())
+                    .expect("quadratischer Rest hat eine Wurzel")
+            } else {
+                continue;
+            };
+            base.push(Prime {
+                p: p as u32,
+                sqrt: sqrt as u32,
+                log: (p as f64).log2().round() as u8,
+            });
+            if base.len() == size {
+                return Ok(base);
+            }
+        }
+        limit *= 2;
+    }
+}
+
+/// Kandidaten für die Primfaktoren von a (ungerade, kein Teiler von k) um 2^(bits/s) und
+/// deren Anzahl s, bei einem Zielwert a ≈ 2^bits.
+fn a_candidates(base: &[Prime], bits: f64) -> (Vec<usize>, usize) {
+    let usable: Vec<usize> = (0..base.len())
+        .filter(|&j| base[j].p > 2 && base[j].sqrt != 0)
+        .collect();
+    let largest = (base[base.len() - 1].p as f64).log2();
+    let s = ((bits / (largest - 1.0).min(11.5)).round() as usize).max(2);
+    let q_bits = bits / s as f64;
+    let center = usable.partition_point(|&j| (base[j].p as f64).log2() < q_bits);
+    let width = (3 * s).max(15);
+    let low = center.saturating_sub(width);
+    let high = (center + width).min(usable.len());
+    (usable[low..high].to_vec(), s)
+}
+
+/// Wählt ein noch nicht verwendetes a ≈ 2^bits: s − 1 Primzahlen zufällig, die letzte so,
+/// dass das Produkt möglichst nahe am Ziel liegt.
+fn choose_a(
+    base: &[Prime],
+    candidates: &[usize],
+    s: usize,
+    bits: f64,
+    used: &mut HashSet<Vec<usize>>,
+) -> Option<Vec<usize>> {
+    let mut rng = thread_rng();
+    let log = |j: usize| (base[j].p as f64).log2();
+    for _ in 0..100 {
+        let chosen: Vec<usize> = candidates
+            .choose_multiple(&mut rng, s - 1)
+            .copied()
+            .collect();
+        let rest = bits - chosen.iter().map(|&j| log(j)).sum::<f64>();
+        let mut options: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|j| !chosen.contains(j))
+            .collect();
+        options.sort_by(|&x, &y| (log(x) - rest).abs().total_cmp(&(log(y) - rest).abs()));
+        for last in options {
+            let mut factors = chosen.clone();
+            factors.push(last);
+            factors.sort_unstable();
+            if used.insert(factors.clone()) {
+                return Some(factors);
+            }
+        }
+    }
+    None
+}
+
+/// Sucht einen echten Teiler von n mit dem quadratischen Sieb.
+///
+/// Gedacht für Zahlen ohne kleine Faktoren; unter 64 Bit übernimmt Pollards Rho.
+pub fn siqs(n: &BigUint) -> Option<BigUint> {
+    if *n < BigUint::from(4u32) {
+        // 0 bis 3 haben keinen echten Teiler
+        return None;
+    }
+    if n.bits() < 64 {
+        return pollard_brent(n, 1 << 20);
+    }
+    if n.is_even() {
+        return Some(BigUint::from(2u32));
+    }
+    let root = n.sqrt();
+    if &root * &root == *n {
+        return Some(root);
+    }
+
+    let digits = (n.bits() as f64 * std::f64::consts::LOG10_2) as u64 + 1;
+    let &(_, size, m) = PARAMETERS
+        .iter()
+        .find(|row| digits <= row.0)
+        .unwrap_or(&PARAMETERS[PARAMETERS.len() - 1]);
+    let k = multiplier(n);
+    let kn = n * k;
+    let base = match factor_base(n, &kn, size) {
+        Ok(base) => base,
+        Err(p) => return Some(p),
+    };
+    let large_bound = u64::from(base[base.len() - 1].p) * LARGE_PRIME_MULTIPLIER;
+
+    // |g(x)| ≤ M·√(k·n/2) bei a ≈ √(2·k·n)/M
+    let g_bits = (m as f64).log2() + kn.bits() as f64 / 2.0 - 0.5;
+    let threshold = (g_bits - (large_bound as f64).log2() - THRESHOLD_SLACK).max(0.0) as u8;
+    let a_bits = kn.bits() as f64 / 2.0 + 0.5 - (m as f64).log2();
+    let (candidates, s) = a_candidates(&base, a_bits);
+
+    let needed = base.len() + 1 + EXTRA_RELATIONS;
+    let kn = BigInt::from(kn);
+    let mut relations: Vec<Relation> = Vec::with_capacity(needed);
+    let mut partials: HashMap<u64, Relation> = HashMap::new();
+    let mut used = HashSet::new();
+    let mut sieve = vec![0u8; 2 * m];
+    let mut roots = vec![(0u32, 0u32); base.len()];
+    let mut sieved = vec![false; base.len()];
+    let mut polynomials = 0;
+
+    while relations.len() < needed {
+        if polynomials > needed * MAX_POLYNOMIALS_PER_RELATION {
+            return None;
+        }
+        let a_factors = choose_a(&base, &candidates, s, a_bits, &mut used)?;
+        let a_unsigned = a_factors.iter().fold(BigUint::one(), |a, &j| a * base[j].p);
+        let a = BigInt::from(a_unsigned.clone());
+
+        // B_l ≡ √(k·n) mod q_l und ≡ 0 mod den übrigen q; b = Σ ±B_l erfüllt b² ≡ k·n mod a
+        let terms: Vec<BigInt> = a_factors
+            .iter()
+            .map(|&j| {
+                let q = u64::from(base[j].p);
+                let cofactor = &a_unsigned / q;
+                let inverse = pow_mod((&cofactor % q).to_u64().expect("< q"), q - 2, q);
+                let mut gamma = u64::from(base[j].sqrt) * inverse % q;
+                if gamma > q / 2 {
+                    gamma = q - gamma;
+                }
+                BigInt::from(cofactor * gamma)
+            })
+            .collect();
+        let mut b: BigInt = terms.iter().sum();
+
+        // Nullstellen x ≡ a⁻¹(±√(k·n) − b) und die Verschiebungen 2·B_l·a⁻¹ modulo p
+        let mut shifts = vec![vec![0u32; base.len()]; s];
+        for (j, prime) in base.iter().enumerate() {
+            let p = u64::from(prime.p);
+            sieved[j] = prime.p >= SIEVE_MIN_PRIME && prime.sqrt != 0 && !a_factors.contains(&j);
+            if !sieved[j] {
+                continue;
+            }
+            let inverse = pow_mod(residue(&a, p), p - 2, p);
+            for (shift, term) in shifts.iter_mut().zip(&terms) {
+                shift[j] = (2 * residue(term, p) * inverse % p) as u32;
+            }
+            let b_mod = residue(&b, p);
+            let t = u64::from(prime.sqrt);
+            roots[j] = (
+                ((t + p - b_mod) * inverse % p) as u32,
+                ((2 * p - t - b_mod) * inverse % p) as u32,
+            );
+        }
+
+        let mut signs = vec![true; s];
+        for index in 0..1usize << (s - 1) {
+            if index > 0 {
+                // b ± 2·B_l, die Nullstellen wandern um ∓ 2·B_l·a⁻¹
+                let l = index.trailing_zeros() as usize;
+                signs[l] = !signs[l];
+                if signs[l] {
+                    b += &terms[l] * 2;
+                } else {
+                    b -= &terms[l] * 2;
+                }
+                for (j, prime) in base.iter().enumerate() {
+                    if !sieved[j] {
+                        continue;
+                    }
+                    let p = prime.p;
+                    let shift = if signs[l] {
+                        p - shifts[l][j]
+                    } else {
+                        shifts[l][j]
+                    };
+                    let (r1, r2) = roots[j];
+                    roots[j] = (
+                        ((u64::from(r1) + u64::from(shift)) % u64::from(p)) as u32,
+                        ((u64::from(r2) + u64::from(shift)) % u64::from(p)) as u32,
+                    );
+                }
+            }
+            polynomials += 1;
+            let c = (&b * &b - &kn) / &a;
+
+            sieve.fill(0);
+            for (j, prime) in base.iter().enumerate() {
+                if !sieved[j] {
+                    continue;
+                }
+                let p = prime.p as usize;
+                for root in [roots[j].0, roots[j].1] {
+                    let mut i = (root as usize + m) % p;
+                    while i < sieve.len() {
+                        sieve[i] = sieve[i].saturating_add(prime.log);
+                        i += p;
+                    }
+                }
+            }
+
+            for (i, &value) in sieve.iter().enumerate() {
+                if value < threshold {
+                    continue;
+                }
+                let x = i as i64 - m as i64;
+                let Some(relation) = trial_divide(
+                    x,
+                    &a,
+                    &b,
+                    &c,
+                    &a_factors,
+                    &base,
+                    &roots,
+                    &sieved,
+                    large_bound,
+                    n,
+                ) else {
+                    continue;
+                };
+                if relation.large == 1 {
+                    relations.push(relation);
+                } else if let Some(other) = partials.get(&relation.large) {
+                    relations.push(relation.combine(other, n));
+                } else {
+                    partials.insert(relation.large, relation);
+                }
+            }
+        }
+    }
+
+    let columns = base.len() + 1;
+    for dependency in dependencies(&relations, columns) {
+        let mut x = BigUint::one();
+        let mut y = BigUint::one();
+        let mut exponents = vec![0u32; columns];
+        for &i in &dependency {
+            x = x * &relations[i].y % n;
+            y = y * relations[i].large % n;
+            for &(column, exponent) in &relations[i].factors {
+                exponents[column as usize] += exponent;
+            }
+        }
+        for (prime, &exponent) in base.iter().zip(&exponents[1..]) {
+            if exponent > 0 {
+                y = y * BigUint::from(prime.p).modpow(&BigUint::from(exponent / 2), n) % n;
+            }
+        }
+        let g = ((x + n - y) % n).gcd(n);
+        if !g.is_one() && &g != n {
+            return Some(g);
+        }
+    }
+    None
+}
+
+/// Zerlegt g(x) = a·x² + 2·b·x + c über der Faktorbasis; gesiebte Primzahlen werden nur
+/// geteilt, wenn x auf einer ihrer Nullstellen liegt. Ergebnis ist eine Relation, deren Rest
+/// 1 ist oder eine große Primzahl unter `large_bound`.
+#[allow(clippy::too_many_arguments)]
+fn trial_divide(
+    x: i64,
+    a: &BigInt,
+    b: &BigInt,
+    c: &BigInt,
+    a_factors: &[usize],
+    base: &[Prime],
+    roots: &[(u32, u32)],
+    sieved: &[bool],
+    large_bound: u64,
+    n: &BigUint,
+) -> Option<Relation> {
+    let x_big = BigInt::from(x);
+    let y = a * &x_big + b;
+    let g = (&y + b) * &x_big + c;
+    if g.is_zero() {
+        return None;
+    }
+    let mut factors: Vec<(u32, u32)> = Vec::new();
+    if g.is_negative() {
+        factors.push((0, 1));
+    }
+    let mut rest = g.magnitude().clone();
+    for (j, prime) in base.iter().enumerate() {
+        if sieved[j] {
+            let r = x.rem_euclid(i64::from(prime.p)) as u32;
+            if r != roots[j].0 && r != roots[j].1 {
+                continue;
+            }
+        }
+        let mut exponent = 0;
+        while (&rest % prime.p).is_zero() {
+            rest /= prime.p;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((j as u32 + 1, exponent));
+        }
+    }
+    let large = match rest.to_u64() {
+        Some(1) => 1,
+        Some(r) if r < large_bound => r,
+        _ => return None,
+    };
+    // a·g(x): jede Primzahl von a einmal mehr
+    factors.extend(a_factors.iter().map(|&j| (j as u32 + 1, 1)));
+    Some(Relation {
+        y: y.magnitude() % n,
+        factors: merge(factors),
+        large,
+    })
+}
+
+/// Teilmengen der Relationen, deren Exponentenvektoren sich modulo 2 zu null addieren
+/// (Gauß-Elimination über GF(2); jede Zeile führt mit, aus welchen Relationen sie entstand).
+fn dependencies(relations: &[Relation], columns: usize) -> Vec<Vec<usize>> {
+    let rows = relations.len();
+    let words = columns.div_ceil(64);
+    let bit = |row: &[u64], i: usize| row[i / 64] >> (i % 64) & 1 == 1;
+    let mut matrix: Vec<Vec<u64>> = relations
+        .iter()
+        .enumerate()
+        .map(|(i, relation)| {
+            let mut row = vec![0u64; words + rows.div_ceil(64)];
+            for &(column, exponent) in &relation.factors {
+                if exponent % 2 == 1 {
+                    row[column as usize / 64] ^= 1 << (column % 64);
+                }
+            }
+            row[words + i / 64] |= 1 << (i % 64);
+            row
+        })
+        .collect();
+
+    let mut pivot = vec![false; rows];
+    for column in 0..columns {
+        let Some(p) = (0..rows).find(|&r| !pivot[r] && bit(&matrix[r], column)) else {
+            continue;
+        };
+        pivot[p] = true;
+        let pivot_row = matrix[p].clone();
+        for (r, row) in matrix.iter_mut().enumerate() {
+            if r != p && bit(row, column) {
+                for (word, mask) in row.iter_mut().zip(&pivot_row) {
+                    *word ^= mask;
+                }
+            }
+        }
+    }
+    // Zeilen ohne Pivot sind null geworden
+    (0..rows)
+        .filter(|&r| !pivot[r])
+        .map(|r| {
+            (0..rows)
+                .filter(|&i| bit(&matrix[r], words * 64 + i))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_siqs() {
+        let p = BigUint::parse_bytes(b"100000000000031", 10).unwrap();
+        let q = BigUint::parse_bytes(b"1000000000000037", 10).unwrap();
+        let n = &p * &q;
+        let factor = siqs(&n).unwrap();
+        assert!(factor == p || factor == q);
+
+        // Abhängigkeiten: Zeile 2 = Zeile 0 + Zeile 1
+        let relation = |factors: Vec<(u32, u32)>| Relation {
+            y: BigUint::one(),
+            factors,
+            large: 1,
+        };
+        let relations = [
+            relation(vec![(0, 1), (2, 3)]),
+            relation(vec![(1, 1), (2, 2)]),
+            relation(vec![(0, 1), (1, 1), (2, 1)]),
+        ];
+        assert_eq!(dependencies(&relations, 3), vec![vec![0, 1, 2]]);
+        assert_eq!(merge(vec![(3, 1), (1, 2), (3, 2)]), vec![(1, 2), (3, 3)]);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod constant_time;
+pub mod factorization;
 pub mod modular_arith;
 pub mod number_theory;
 pub mod primality;
@@ -0,0 +1,113 @@
+//! Barrett-Reduktion für beliebige (auch gerade) Moduln.
+//!
+//! Mit dem vorberechneten μ = ⌊4^k / n⌋ (k = Bitlänge von n) wird x < n² durch zwei
+//! Multiplikationen und Shifts statt einer Division reduziert (HAC, Algorithmus 14.42).
+
+use crate::algorithms::modular_arith::window::{fixed_window_pow, sliding_window_pow, ModMul};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+/// Vorberechnete Werte für einen Modul n > 0.
+#[derive(Debug, Clone)]
+pub struct BarrettReducer {
+    modulus: BigUint,
+    /// Bitlänge k von n
+    bits: u64,
+    /// ⌊4^k / n⌋
+    mu: BigUint,
+}
+
+impl BarrettReducer {
+    /// Reduzierer für den Modul `n`; `None` für n = 0.
+    pub fn new(n: &BigUint) -> Option<Self> {
+        if n.is_zero() {
+            return None;
+        }
+        let bits = n.bits();
+        Some(BarrettReducer {
+            modulus: n.clone(),
+            bits,
+            mu: (BigUint::one() << (2 * bits)) / n,
+        })
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// x mod n; ohne Division für x < n², größere Werte werden dividiert.
+    pub fn reduce(&self, x: &BigUint) -> BigUint {
+        if x.bits() > 2 * self.bits {
+            return x % &self.modulus;
+        }
+        // q unterschätzt ⌊x / n⌋ um höchstens 2
+        let q = ((x >> (self.bits - 1)) * &self.mu) >> (self.bits + 1);
+        let mut r = x - q * &self.modulus;
+        while r >= self.modulus {
+            r -= &self.modulus;
+        }
+        r
+    }
+
+    /// a·b mod n für a, b < n.
+    pub fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.reduce(&(a * b))
+    }
+
+    /// base^exponent mod n mit gleitendem Fenster.
+    pub fn pow(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        sliding_window_pow(self, &self.reduce(base), exponent)
+    }
+
+    /// base^exponent mod n mit festem Fenster.
+    pub fn pow_fixed_window(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        fixed_window_pow(self, &self.reduce(base), exponent)
+    }
+}
+
+impl ModMul for BarrettReducer {
+    type Elem = BigUint;
+
+    fn one(&self) -> BigUint {
+        self.reduce(&BigUint::one())
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        BarrettReducer::mul(self, a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::RandBigInt;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_barrett_reduction() {
+        let mut rng = OsRng;
+        for bits in [1, 2, 63, 64, 200, 1024] {
+            let n = rng.gen_biguint(bits) | (BigUint::one() << (bits - 1));
+            let reducer = BarrettReducer::new(&n).unwrap();
+            for _ in 0..10 {
+                let x = rng.gen_biguint(2 * bits);
+                assert_eq!(reducer.reduce(&x), &x % &n);
+                let a = rng.gen_biguint(bits + 20);
+                let e = rng.gen_biguint(bits + 5);
+                assert_eq!(reducer.pow(&a, &e), a.modpow(&e, &n));
+                assert_eq!(reducer.pow_fixed_window(&a, &e), a.modpow(&e, &n));
+            }
+        }
+        // gerader Modul und Modul 1
+        let reducer = BarrettReducer::new(&BigUint::from(1u32 << 20)).unwrap();
+        assert_eq!(
+            reducer.pow(&BigUint::from(3u32), &BigUint::from(100u32)),
+            BigUint::from(3u32).modpow(&BigUint::from(100u32), &BigUint::from(1u32 << 20))
+        );
+        let reducer = BarrettReducer::new(&BigUint::one()).unwrap();
+        assert!(reducer
+            .pow(&BigUint::from(5u32), &BigUint::default())
+            .is_zero());
+        assert!(BarrettReducer::new(&BigUint::default()).is_none());
+    }
+}
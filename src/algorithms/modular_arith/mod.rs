@@ -0,0 +1,82 @@
+//! Modulare Arithmetik: Montgomery-Multiplikation für ungerade Moduln, Barrett-Reduktion für
+//! beliebige Moduln und Exponentiation mit gleitendem oder festem Fenster.
+//!
+//! Für viele Operationen mit demselben Modul (RSA, Diffie-Hellman, elliptische Kurven) lohnt
+//! es sich, [`MontgomeryContext`] bzw. [`BarrettReducer`] einmal anzulegen und
+//! wiederzuverwenden; [`mod_exp`] wählt das Verfahren für eine einzelne Potenz selbst.
+//! Laufzeitvergleich mit `BigUint::modpow`: `cargo bench --bench modexp`.
+
+pub mod barrett;
+pub mod montgomery;
+mod window;
+
+pub use barrett::BarrettReducer;
+pub use montgomery::{MontgomeryContext, Residue};
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+/// Modulare Exponentiation für beliebig große Zahlen: Montgomery für ungerade Moduln, sonst
+/// Barrett, jeweils mit gleitendem Fenster.
+///
+/// Gibt (base^exponent mod modulus) zurück; panikt bei Modul 0.
+pub fn mod_exp(base: BigUint, exponent: BigUint, modulus: &BigUint) -> BigUint {
+    assert!(!modulus.is_zero(), "Modul 0");
+    if modulus.is_one() {
+        return BigUint::zero();
+    }
+    match MontgomeryContext::new(modulus) {
+        Some(ctx) => ctx.modpow(&base, &exponent),
+        None => BarrettReducer::new(modulus).unwrap().pow(&base, &exponent),
+    }
+}
+
+// ======= Tests =======
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint;
+    use num_bigint::RandBigInt;
+    use num_traits::FromPrimitive;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_small_numbers() {
+        let base = BigUint::from_u64(4).unwrap();
+        let exp = BigUint::from_u64(13).unwrap();
+        let modulus = BigUint::from_u64(497).unwrap();
+        assert_eq!(
+            mod_exp(base, exp, &modulus),
+            BigUint::from_u64(445).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_large_exponent() {
+        let base = BigUint::from_u64(2).unwrap();
+        let exp = BigUint::from_u64(1024).unwrap();
+        let modulus = BigUint::from_u64(97).unwrap();
+        let result = mod_exp(base, exp, &modulus);
+        assert!(result < modulus); // mod-Ergebnis muss < modulus sein
+    }
+
+    #[test]
+    fn test_against_modpow() {
+        let mut rng = OsRng;
+        for bits in [8, 64, 100, 1024] {
+            for modulus in [
+                rng.gen_biguint(bits) | BigUint::one(),
+                rng.gen_biguint(bits) << 1,
+            ] {
+                let base = rng.gen_biguint(bits + 8);
+                let exp = rng.gen_biguint(bits);
+                assert_eq!(
+                    mod_exp(base.clone(), exp.clone(), &modulus),
+                    base.modpow(&exp, &modulus)
+                );
+            }
+        }
+        assert!(mod_exp(BigUint::from(3u32), BigUint::from(5u32), &BigUint::one()).is_zero());
+    }
+}
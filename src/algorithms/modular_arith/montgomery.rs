@@ -0,0 +1,305 @@
+//! Montgomery-Multiplikation für ungerade Moduln.
+//!
+//! Reste werden als a·R mod n mit R = 2^(64·s) gespeichert (s = Anzahl der 64-Bit-Limbs von
+//! n). Die Multiplikation zweier solcher Reste reduziert mit n' = −n⁻¹ mod 2⁶⁴ wortweise
+//! (SOS, Koç et al. 1996) und kommt ohne Division aus; umgerechnet wird nur beim Ein- und
+//! Auslesen mit dem vorberechneten R² mod n.
+
+use crate::algorithms::modular_arith::window::{fixed_window_pow, sliding_window_pow, ModMul};
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::One;
+
+/// Rest modulo n in Montgomery-Form; nur mit dem [`MontgomeryContext`] verwenden, der ihn
+/// erzeugt hat.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Residue {
+    limbs: Vec<u64>,
+}
+
+/// Vorberechnete Werte für einen ungeraden Modul n.
+#[derive(Debug, Clone)]
+pub struct MontgomeryContext {
+    modulus: BigUint,
+    n: Vec<u64>,
+    /// −n⁻¹ mod 2⁶⁴
+    n_prime: u64,
+    /// R mod n, die 1 in Montgomery-Form
+    r: Vec<u64>,
+    /// R² mod n
+    r2: Vec<u64>,
+}
+
+/// `x` als `len` Limbs, niederwertigstes zuerst.
+pub(crate) fn to_limbs(x: &BigUint, len: usize) -> Vec<u64> {
+    let mut limbs: Vec<u64> = x.iter_u64_digits().collect();
+    limbs.resize(len, 0);
+    limbs
+}
+
+pub(crate) fn from_limbs(limbs: &[u64]) -> BigUint {
+    let bytes: Vec<u8> = limbs.iter().flat_map(|l| l.to_le_bytes()).collect();
+    BigUint::from_bytes_le(&bytes)
+}
+
+/// a − b über gleich lange Limb-Folgen; gibt den Übertrag (Borrow) zurück.
+fn sub_in_place(a: &mut [u64], b: &[u64]) -> bool {
+    let mut borrow = false;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let (d, b1) = x.overflowing_sub(y);
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        *x = d;
+        borrow = b1 || b2;
+    }
+    borrow
+}
+
+/// acc += a·b über die Länge von `a`; gibt den Übertrag zurück.
+fn mul_add_row(acc: &mut [u64], a: &[u64], b: u64) -> u64 {
+    let mut carry = 0u64;
+    for (t, &x) in acc.iter_mut().zip(a) {
+        let uv = u128::from(*t) + u128::from(x) * u128::from(b) + u128::from(carry);
+        *t = uv as u64;
+        carry = (uv >> 64) as u64;
+    }
+    carry
+}
+
+/// Addiert einen Übertrag und reicht ihn weiter, solange nötig.
+fn add_carry(acc: &mut [u64], mut carry: u64) {
+    for limb in acc {
+        if carry == 0 {
+            break;
+        }
+        let (sum, overflow) = limb.overflowing_add(carry);
+        *limb = sum;
+        carry = overflow as u64;
+    }
+}
+
+/// a ≥ b für gleich lange Limb-Folgen.
+fn greater_or_equal(a: &[u64], b: &[u64]) -> bool {
+    for (x, y) in a.iter().zip(b).rev() {
+        if x != y {
+            return x > y;
+        }
+    }
+    true
+}
+
+impl MontgomeryContext {
+    /// Kontext für den Modul `n`; `None`, wenn n gerade oder 1 ist.
+    pub fn new(n: &BigUint) -> Option<Self> {
+        if n.is_even() || n.is_one() {
+            return None;
+        }
+        let len = n.iter_u64_digits().len();
+        let limbs = to_limbs(n, len);
+        // Newton-Iteration: jede Runde verdoppelt die Zahl der korrekten Bit
+        let mut inverse = 1u64;
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(limbs[0].wrapping_mul(inverse)));
+        }
+        let r = (BigUint::one() << (64 * len)) % n;
+        let r2 = (BigUint::one() << (128 * len)) % n;
+        Some(MontgomeryContext {
+            modulus: n.clone(),
+            n: limbs,
+            n_prime: inverse.wrapping_neg(),
+            r: to_limbs(&r, len),
+            r2: to_limbs(&r2, len),
+        })
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Montgomery-Reduktion t·R⁻¹ mod n eines Produkts t < n·R (2s + 1 Limbs, das oberste
+    /// frei für Überträge).
+    fn reduce(&self, mut t: Vec<u64>) -> Vec<u64> {
+        let s = self.n.len();
+        for i in 0..s {
+            // t + m·n·2^(64·i) ist durch 2^(64·(i + 1)) teilbar
+            let m = t[i].wrapping_mul(self.n_prime);
+            let carry = mul_add_row(&mut t[i..i + s], &self.n, m);
+            add_carry(&mut t[i + s..], carry);
+        }
+        t.drain(..s);
+        // t < 2n: höchstens einmal n abziehen
+        if t[s] != 0 || greater_or_equal(&t[..s], &self.n) {
+            let top = t[s];
+            let borrow = sub_in_place(&mut t[..s], &self.n);
+            debug_assert_eq!(top, borrow as u64);
+        }
+        t.truncate(s);
+        t
+    }
+
+    /// Montgomery-Produkt a·b·R⁻¹ mod n der Limb-Folgen (beide < n).
+    fn montgomery_mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let s = self.n.len();
+        let mut t = vec![0u64; 2 * s + 1];
+        for (i, &bi) in b.iter().enumerate() {
+            t[i + s] = mul_add_row(&mut t[i..i + s], a, bi);
+        }
+        self.reduce(t)
+    }
+
+    /// Montgomery-Quadrat: jedes gemischte Produkt aᵢ·aⱼ nur einmal, dann verdoppelt.
+    fn montgomery_square(&self, a: &[u64]) -> Vec<u64> {
+        let s = self.n.len();
+        let mut t = vec![0u64; 2 * s + 1];
+        for i in 0..s - 1 {
+            t[i + s] = mul_add_row(&mut t[2 * i + 1..i + s], &a[i + 1..], a[i]);
+        }
+        let mut high = 0;
+        for limb in &mut t[..2 * s] {
+            let next = *limb >> 63;
+            *limb = (*limb << 1) | high;
+            high = next;
+        }
+        for (i, &ai) in a.iter().enumerate() {
+            let square = u128::from(ai) * u128::from(ai);
+            let (low, c1) = t[2 * i].overflowing_add(square as u64);
+            t[2 * i] = low;
+            let (high, c2) = t[2 * i + 1].overflowing_add((square >> 64) as u64 + c1 as u64);
+            t[2 * i + 1] = high;
+            add_carry(&mut t[2 * i + 2..], c2 as u64);
+        }
+        self.reduce(t)
+    }
+
+    /// Wandelt `a` (beliebig groß) in Montgomery-Form um.
+    pub fn residue(&self, a: &BigUint) -> Residue {
+        let reduced = to_limbs(&(a % &self.modulus), self.n.len());
+        Residue {
+            limbs: self.montgomery_mul(&reduced, &self.r2),
+        }
+    }
+
+    /// Wert eines Rests als Zahl in [0, n).
+    pub fn value(&self, a: &Residue) -> BigUint {
+        let mut one = vec![0u64; self.n.len()];
+        one[0] = 1;
+        from_limbs(&self.montgomery_mul(&a.limbs, &one))
+    }
+
+    pub fn one(&self) -> Residue {
+        Residue {
+            limbs: self.r.clone(),
+        }
+    }
+
+    pub fn mul(&self, a: &Residue, b: &Residue) -> Residue {
+        Residue {
+            limbs: self.montgomery_mul(&a.limbs, &b.limbs),
+        }
+    }
+
+    pub fn square(&self, a: &Residue) -> Residue {
+        Residue {
+            limbs: self.montgomery_square(&a.limbs),
+        }
+    }
+
+    pub fn add(&self, a: &Residue, b: &Residue) -> Residue {
+        let mut sum = Vec::with_capacity(self.n.len());
+        let mut carry = false;
+        for (&x, &y) in a.limbs.iter().zip(&b.limbs) {
+            let (s, c1) = x.overflowing_add(y);
+            let (s, c2) = s.overflowing_add(carry as u64);
+            sum.push(s);
+            carry = c1 || c2;
+        }
+        if carry || greater_or_equal(&sum, &self.n) {
+            sub_in_place(&mut sum, &self.n);
+        }
+        Residue { limbs: sum }
+    }
+
+    pub fn sub(&self, a: &Residue, b: &Residue) -> Residue {
+        let mut difference = a.limbs.clone();
+        if sub_in_place(&mut difference, &b.limbs) {
+            // negativ: n addieren, der Überlauf gleicht den Borrow aus
+            let mut carry = false;
+            for (x, &y) in difference.iter_mut().zip(&self.n) {
+                let (s, c1) = x.overflowing_add(y);
+                let (s, c2) = s.overflowing_add(carry as u64);
+                *x = s;
+                carry = c1 || c2;
+            }
+        }
+        Residue { limbs: difference }
+    }
+
+    /// base^exponent mit gleitendem Fenster.
+    pub fn pow(&self, base: &Residue, exponent: &BigUint) -> Residue {
+        sliding_window_pow(self, base, exponent)
+    }
+
+    /// base^exponent mit festem Fenster (gleiche Operationsfolge für gleich lange Exponenten).
+    pub fn pow_fixed_window(&self, base: &Residue, exponent: &BigUint) -> Residue {
+        fixed_window_pow(self, base, exponent)
+    }
+
+    /// base^exponent mod n für gewöhnliche Zahlen.
+    pub fn modpow(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        self.value(&self.pow(&self.residue(base), exponent))
+    }
+}
+
+impl ModMul for MontgomeryContext {
+    type Elem = Residue;
+
+    fn one(&self) -> Residue {
+        MontgomeryContext::one(self)
+    }
+
+    fn mul(&self, a: &Residue, b: &Residue) -> Residue {
+        MontgomeryContext::mul(self, a, b)
+    }
+
+    fn square(&self, a: &Residue) -> Residue {
+        MontgomeryContext::square(self, a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::RandBigInt;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_montgomery_arithmetic() {
+        let mut rng = OsRng;
+        for bits in [5, 64, 65, 127, 521, 2048] {
+            let n = rng.gen_biguint(bits) | BigUint::one() | (BigUint::one() << (bits - 1));
+            let ctx = MontgomeryContext::new(&n).unwrap();
+            for _ in 0..10 {
+                let a = rng.gen_biguint(bits + 10);
+                let b = rng.gen_biguint_below(&n);
+                let e = rng.gen_biguint(bits);
+                let (ra, rb) = (ctx.residue(&a), ctx.residue(&b));
+                assert_eq!(ctx.value(&ra), &a % &n);
+                assert_eq!(ctx.value(&ctx.mul(&ra, &rb)), &a * &b % &n);
+                assert_eq!(ctx.value(&ctx.add(&ra, &rb)), (&a + &b) % &n);
+                assert_eq!(ctx.value(&ctx.sub(&ra, &rb)), (&a % &n + &n - &b) % &n);
+                assert_eq!(ctx.modpow(&a, &e), a.modpow(&e, &n));
+                assert_eq!(ctx.value(&ctx.pow_fixed_window(&ra, &e)), a.modpow(&e, &n));
+            }
+            assert_eq!(
+                ctx.modpow(&BigUint::from(7u32), &BigUint::default()),
+                BigUint::one()
+            );
+        }
+        // Modul knapp unter 2^256: volle Limbs, größte Überträge
+        let n = (BigUint::one() << 256) - 189u32;
+        let ctx = MontgomeryContext::new(&n).unwrap();
+        let minus_one = &n - 1u32;
+        assert_eq!(ctx.modpow(&minus_one, &BigUint::from(3u32)), minus_one);
+        assert!(MontgomeryContext::new(&BigUint::from(10u32)).is_none());
+        assert!(MontgomeryContext::new(&BigUint::one()).is_none());
+    }
+}
@@ -0,0 +1,97 @@
+//! Fensterexponentiation für beliebige Reduktionsverfahren.
+
+use num_bigint::BigUint;
+
+/// Multiplikation modulo n in einer festen Darstellung (Montgomery-Form, reduzierte Reste).
+pub(crate) trait ModMul {
+    type Elem: Clone;
+
+    /// Darstellung der 1.
+    fn one(&self) -> Self::Elem;
+    fn mul(&self, a: &Self::Elem, b: &Self::Elem) -> Self::Elem;
+
+    fn square(&self, a: &Self::Elem) -> Self::Elem {
+        self.mul(a, a)
+    }
+}
+
+/// Fensterbreite für einen Exponenten mit `bits` Bit (Schwellen wie in OpenSSL).
+pub(crate) fn window_size(bits: u64) -> u32 {
+    match bits {
+        672.. => 6,
+        240..672 => 5,
+        80..240 => 4,
+        24..80 => 3,
+        _ => 1,
+    }
+}
+
+/// Die Bits `low .. low + width` von `exponent` als Zahl.
+fn bits_at(exponent: &BigUint, low: u64, width: u32) -> usize {
+    (0..u64::from(width))
+        .rev()
+        .fold(0, |acc, i| (acc << 1) | exponent.bit(low + i) as usize)
+}
+
+/// Exponentiation von links nach rechts mit gleitendem Fenster: vorberechnet werden nur die
+/// ungeraden Potenzen base¹, base³, …, base^(2^w − 1); Nullbits kosten eine Quadrierung.
+pub(crate) fn sliding_window_pow<M: ModMul>(m: &M, base: &M::Elem, exponent: &BigUint) -> M::Elem {
+    let width = window_size(exponent.bits());
+    let base_squared = m.square(base);
+    let mut odd_powers = vec![base.clone()];
+    for i in 1..1usize << (width - 1) {
+        odd_powers.push(m.mul(&odd_powers[i - 1], &base_squared));
+    }
+
+    let mut result: Option<M::Elem> = None;
+    let mut i = exponent.bits();
+    while i > 0 {
+        if !exponent.bit(i - 1) {
+            result = result.map(|r| m.square(&r));
+            i -= 1;
+            continue;
+        }
+        // längstes Fenster ab Bit i − 1 abwärts, das mit einer Eins endet
+        let mut low = i.saturating_sub(u64::from(width));
+        while !exponent.bit(low) {
+            low += 1;
+        }
+        let len = (i - low) as u32;
+        let power = &odd_powers[bits_at(exponent, low, len) >> 1];
+        result = Some(match result {
+            Some(mut r) => {
+                for _ in 0..len {
+                    r = m.square(&r);
+                }
+                m.mul(&r, power)
+            }
+            None => power.clone(),
+        });
+        i = low;
+    }
+    result.unwrap_or_else(|| m.one())
+}
+
+/// Exponentiation mit festem Fenster: je `w` Bit genau `w` Quadrierungen und eine
+/// Multiplikation, auch für Nullfenster. Die Folge der Operationen hängt so nur von der
+/// Länge des Exponenten ab, nicht von seinen Bits.
+pub(crate) fn fixed_window_pow<M: ModMul>(m: &M, base: &M::Elem, exponent: &BigUint) -> M::Elem {
+    let width = window_size(exponent.bits()).max(2);
+    let mut powers = vec![m.one(), base.clone()];
+    for i in 2..1usize << width {
+        powers.push(m.mul(&powers[i - 1], base));
+    }
+
+    let windows = exponent.bits().div_ceil(u64::from(width));
+    let mut result = m.one();
+    for k in (0..windows).rev() {
+        for _ in 0..width {
+            result = m.square(&result);
+        }
+        result = m.mul(
+            &result,
+            &powers[bits_at(exponent, k * u64::from(width), width)],
+        );
+    }
+    result
+}
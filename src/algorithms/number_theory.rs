@@ -1,5 +1,7 @@
+use crate::algorithms::factorization::factorize;
 use num_bigint::{BigInt, BigUint, ToBigInt};
-use num_traits::{One, Zero};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
 
 /// Berechnet den größten gemeinsamen Teiler (ggT) von a und b
 /// mithilfe des euklidischen Algorithmus.
@@ -67,29 +69,332 @@ pub fn is_coprime(a: &BigUint, b: &BigUint) -> bool {
     gcd(a.clone(), b.clone()) == BigUint::one()
 }
 
-/// Euler’sche Phi-Funktion φ(n): Anzahl der 1 ≤ k < n mit gcd(k, n) = 1.
-/// Einfache Faktorisierung für Demonstrationszwecke.
-pub fn totient(n: &BigUint) -> BigUint {
+/// Löst das System x ≡ aᵢ (mod mᵢ) mit dem chinesischen Restsatz.
+///
+/// Die Moduln müssen nicht teilerfremd sein: je zwei Kongruenzen sind genau dann
+/// verträglich, wenn ggT(m₁, m₂) die Differenz der Reste teilt. Ergebnis ist (x, M) mit
+/// M = kgV aller mᵢ und 0 ≤ x < M; `None` bei widersprüchlichen Kongruenzen oder einem
+/// Modul 0. Ein leeres System hat die Lösung (0, 1).
+pub fn crt(congruences: &[(BigUint, BigUint)]) -> Option<(BigUint, BigUint)> {
+    let mut x = BigUint::zero();
+    let mut modulus = BigUint::one();
+    for (a, m) in congruences {
+        if m.is_zero() {
+            return None;
+        }
+        let a = a % m;
+        let g = gcd(modulus.clone(), m.clone());
+        let difference = BigInt::from(a) - BigInt::from(x.clone());
+        let g_signed = BigInt::from(g.clone());
+        if !(&difference % &g_signed).is_zero() {
+            return None;
+        }
+        // x + M·t ≡ a (mod m)  ⇔  (M/g)·t ≡ (a − x)/g (mod m/g)
+        let step = m / &g;
+        let step_signed = BigInt::from(step.clone());
+        let inverse = mod_inverse(&(&modulus / &g % &step), &step).unwrap_or_default();
+        let t = (difference / &g_signed).mod_floor(&step_signed);
+        let t = (t.to_biguint().unwrap() * inverse) % &step;
+        let combined = &modulus * &step;
+        x = (x + &modulus * t) % &combined;
+        modulus = combined;
+    }
+    Some((x, modulus))
+}
+
+/// Jacobi-Symbol (a/n) für ungerades `n` > 0; `a` darf negativ sein.
+pub fn jacobi(a: &BigInt, n: &BigUint) -> i32 {
+    assert!(n.is_odd(), "Jacobi-Symbol nur für ungerade n");
+    let mut a = a.mod_floor(&BigInt::from(n.clone())).to_biguint().unwrap();
+    let mut n = n.clone();
+    let mut result = 1;
+    while !a.is_zero() {
+        let twos = a.trailing_zeros().unwrap_or(0);
+        a >>= twos;
+        let n_mod_8 = (&n % 8u32).to_u32().unwrap();
+        if twos % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+            result = -result;
+        }
+        std::mem::swap(&mut a, &mut n);
+        if (&a % 4u32).to_u32() == Some(3) && (&n % 4u32).to_u32() == Some(3) {
+            result = -result;
+        }
+        a %= &n;
+    }
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+/// Legendre-Symbol (a/p) für eine ungerade Primzahl p: 1 für quadratische Reste, −1 für
+/// Nichtreste, 0 für p | a. Für Primzahlen stimmt es mit dem Jacobi-Symbol überein.
+pub fn legendre(a: &BigInt, p: &BigUint) -> i32 {
+    jacobi(a, p)
+}
+
+/// Kronecker-Symbol (a/n) für beliebige ganze Zahlen, die Fortsetzung des
+/// Jacobi-Symbols auf gerade und negative n.
+pub fn kronecker(a: &BigInt, n: &BigInt) -> i32 {
+    if n.is_zero() {
+        return if a.abs().is_one() { 1 } else { 0 };
+    }
+    let mut result = 1;
+    if n.is_negative() && a.is_negative() {
+        result = -result;
+    }
+    let mut n = n.magnitude().clone();
+    let twos = n.trailing_zeros().unwrap_or(0);
+    if twos > 0 {
+        if a.is_even() {
+            return 0;
+        }
+        // (a/2) = 1 für a ≡ ±1, −1 für a ≡ ±3 (mod 8)
+        let a_mod_8 = a.mod_floor(&BigInt::from(8)).to_u32().unwrap();
+        if twos % 2 == 1 && (a_mod_8 == 3 || a_mod_8 == 5) {
+            result = -result;
+        }
+        n >>= twos;
+    }
+    result * jacobi(a, &n)
+}
+
+/// Quadratwurzel von `a` modulo der Primzahl `p` nach Tonelli–Shanks; die zweite Wurzel
+/// ist p − r. `None`, wenn a kein quadratischer Rest ist.
+///
+/// Mit p − 1 = q·2^s kostet das Verfahren eine Potenz und bis zu s²/2 Multiplikationen;
+/// für p ≡ 3 (mod 4) genügt r = a^((p + 1)/4). Für zusammengesetzte p ist das Ergebnis
+/// bedeutungslos, das Verfahren endet aber (mit `None`, wo es für Primzahlen nicht weiterginge).
+pub fn tonelli_shanks(a: &BigUint, p: &BigUint) -> Option<BigUint> {
+    let a = a % p;
+    if let Some(root) = trivial_sqrt(&a, p)? {
+        return Some(root);
+    }
     let one = BigUint::one();
-    let mut result = n.clone();
-    let mut nn = n.clone();
-    let mut p = BigUint::from(2u32);
-
-    while &p * &p <= nn {
-        if (&nn % &p).is_zero() {
-            // p ist Faktor
-            result = (&result / &p) * (&p - &one);
-            while (&nn % &p).is_zero() {
-                nn /= &p;
+    let p_minus_one = p - &one;
+    let s = p_minus_one.trailing_zeros().unwrap_or(0);
+    let q = &p_minus_one >> s;
+    if s == 1 {
+        return Some(a.modpow(&((p + &one) >> 2), p));
+    }
+    let z = non_residue(p)?;
+
+    let mut m = s;
+    let mut c = z.modpow(&q, p);
+    let mut t = a.modpow(&q, p);
+    let mut r = a.modpow(&((&q + &one) >> 1), p);
+    while !t.is_one() {
+        // kleinstes i mit t^(2^i) = 1; für Primzahlen ist i < m
+        let mut i = 0;
+        let mut square = t.clone();
+        while !square.is_one() {
+            square = &square * &square % p;
+            i += 1;
+            if i == m {
+                return None;
             }
         }
-        p += &one;
+        let b = c.modpow(&(BigUint::one() << (m - i - 1)), p);
+        m = i;
+        c = &b * &b % p;
+        t = t * &c % p;
+        r = r * b % p;
     }
-    if nn > one {
-        // Restfaktor
-        result = (&result / &nn) * (&nn - &one);
+    Some(r)
+}
+
+/// Quadratwurzel von `a` modulo der Primzahl `p` nach Cipolla: Mit einem t, für das
+/// t² − a kein Rest ist, gilt r = (t + ω)^((p + 1)/2) in 𝔽ₚ(ω), ω² = t² − a. Der Aufwand
+/// hängt im Gegensatz zu Tonelli–Shanks nicht von der Zweierpotenz in p − 1 ab. Für
+/// zusammengesetzte p endet es ebenfalls, meist mit `None`.
+pub fn cipolla(a: &BigUint, p: &BigUint) -> Option<BigUint> {
+    let a = a % p;
+    if let Some(root) = trivial_sqrt(&a, p)? {
+        return Some(root);
     }
-    result
+    // für Primzahlen gibt es ein solches t < p
+    let (t, omega) = (1u32..)
+        .map(BigUint::from)
+        .take_while(|t| t < p)
+        .map(|t| {
+            let omega = (&t * &t + p - &a) % p;
+            (t, omega)
+        })
+        .find(|(_, omega)| legendre(&BigInt::from(omega.clone()), p) == -1)?;
+
+    // (x + y·ω)·(u + v·ω) = (x·u + y·v·ω²) + (x·v + y·u)·ω
+    let mul = |(x, y): &(BigUint, BigUint), (u, v): &(BigUint, BigUint)| {
+        ((x * u + y * v % p * &omega) % p, (x * v + y * u) % p)
+    };
+    let exponent: BigUint = (p + 1u32) >> 1;
+    let mut result = (BigUint::one(), BigUint::zero());
+    for i in (0..exponent.bits()).rev() {
+        result = mul(&result, &result);
+        if exponent.bit(i) {
+            result = mul(&result, &(t.clone(), BigUint::one()));
+        }
+    }
+    // für Primzahlen verschwindet der ω-Anteil immer
+    result.1.is_zero().then_some(result.0)
+}
+
+/// Quadratwurzel modulo einer Primzahl: Tonelli–Shanks, solange die Zweierpotenz s in
+/// p − 1 klein ist, sonst Cipolla (dessen Aufwand von s unabhängig ist).
+pub fn sqrt_mod_prime(a: &BigUint, p: &BigUint) -> Option<BigUint> {
+    let s = (p - 1u32).trailing_zeros().unwrap_or(0);
+    if s * s > 4 * p.bits() {
+        cipolla(a, p)
+    } else {
+        tonelli_shanks(a, p)
+    }
+}
+
+/// Gemeinsame Sonderfälle der Quadratwurzel: `Some(Some(r))` für p = 2 oder a = 0,
+/// `None` für Nichtreste, sonst `Some(None)`.
+fn trivial_sqrt(a: &BigUint, p: &BigUint) -> Option<Option<BigUint>> {
+    if a.is_zero() || p == &BigUint::from(2u32) {
+        return Some(Some(a.clone()));
+    }
+    if legendre(&BigInt::from(a.clone()), p) != 1 {
+        return None;
+    }
+    Some(None)
+}
+
+/// Kleinster quadratischer Nichtrest modulo der ungeraden Primzahl p; `None` nur für
+/// zusammengesetzte p ohne Jacobi-Symbol −1 (etwa Quadratzahlen).
+fn non_residue(p: &BigUint) -> Option<BigUint> {
+    (2u32..)
+        .map(BigUint::from)
+        .take_while(|z| z < p)
+        .find(|z| legendre(&BigInt::from(z.clone()), p) == -1)
+}
+
+/// Hensel-Lifting: hebt eine einfache Nullstelle `root` des Polynoms f modulo p zu einer
+/// Nullstelle modulo p^k. `coefficients` sind die Koeffizienten von f, beginnend beim
+/// konstanten Glied.
+///
+/// Newton-Schritte r ← r − f(r)·f'(r)⁻¹ verdoppeln jeweils den Exponenten. `None`, wenn
+/// `root` modulo p keine Nullstelle ist oder f'(root) durch p teilbar ist (mehrfache
+/// Nullstelle, die sich so nicht eindeutig heben lässt).
+pub fn hensel_lift(
+    coefficients: &[BigInt],
+    root: &BigUint,
+    p: &BigUint,
+    k: u32,
+) -> Option<BigUint> {
+    let evaluate = |coefficients: &[BigInt], x: &BigInt, m: &BigInt| {
+        coefficients
+            .iter()
+            .rev()
+            .fold(BigInt::zero(), |acc, c| (acc * x + c).mod_floor(m))
+    };
+    let derivative: Vec<BigInt> = coefficients
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| c * BigInt::from(i))
+        .collect();
+
+    let p_signed = BigInt::from(p.clone());
+    let mut r = BigInt::from(root % p);
+    if !evaluate(coefficients, &r, &p_signed).is_zero()
+        || evaluate(&derivative, &r, &p_signed).is_zero()
+    {
+        return None;
+    }
+    let mut exponent = 1;
+    while exponent < k {
+        exponent = (2 * exponent).min(k);
+        let modulus = BigInt::from(p.pow(exponent));
+        let value = evaluate(coefficients, &r, &modulus);
+        let slope = evaluate(&derivative, &r, &modulus).to_biguint().unwrap();
+        let inverse = mod_inverse(&slope, modulus.magnitude())?;
+        r = (r - value * BigInt::from(inverse)).mod_floor(&modulus);
+    }
+    r.to_biguint()
+}
+
+/// Quadratwurzel von `a` modulo p^k für eine Primzahl p; für ungerade p per
+/// [`sqrt_mod_prime`] und [`hensel_lift`], für p = 2 bitweise, da dort f'(x) = 2x immer
+/// durch p teilbar ist.
+///
+/// Ist a = p^v·a' mit p ∤ a' und v < k, muss v gerade sein; die Wurzel ist dann p^(v/2)
+/// mal eine Wurzel von a' modulo p^(k − v).
+pub fn sqrt_mod_prime_power(a: &BigUint, p: &BigUint, k: u32) -> Option<BigUint> {
+    let modulus = p.pow(k);
+    let a = a % &modulus;
+    if a.is_zero() || k == 0 {
+        return Some(BigUint::zero());
+    }
+    if (&a % p).is_zero() {
+        let mut rest = a;
+        let mut v = 0;
+        while (&rest % p).is_zero() {
+            rest /= p;
+            v += 1;
+        }
+        if v % 2 == 1 {
+            return None;
+        }
+        let root = sqrt_mod_prime_power(&rest, p, k - v)?;
+        return Some(root * p.pow(v / 2) % modulus);
+    }
+    if p != &BigUint::from(2u32) {
+        let root = sqrt_mod_prime(&a, p)?;
+        let f = [-BigInt::from(a), BigInt::zero(), BigInt::one()];
+        return hensel_lift(&f, &root, p, k);
+    }
+    // ungerade Quadrate sind ≡ 1 (mod 8); darunter genügt Probieren
+    if k <= 3 {
+        return (1u32..1 << k)
+            .step_by(2)
+            .map(BigUint::from)
+            .find(|x| x * x % &modulus == a);
+    }
+    if (&a % 8u32).to_u32() != Some(1) {
+        return None;
+    }
+    let mut x = BigUint::one();
+    for i in 3..k {
+        // x² ≡ a (mod 2^i); fehlt das nächste Bit, x um 2^(i − 1) verschieben
+        if !((&x * &x) >> i ^ (&a >> i)).bit(0) {
+            continue;
+        }
+        x += BigUint::one() << (i - 1);
+    }
+    Some(x % modulus)
+}
+
+/// Euler’sche Phi-Funktion φ(n): Anzahl der 1 ≤ k ≤ n mit gcd(k, n) = 1 (φ(0) = 0).
+/// Zerlegt n mit [`factorize`].
+pub fn totient(n: &BigUint) -> BigUint {
+    if n.is_zero() {
+        return BigUint::zero();
+    }
+    factorize(n).totient()
+}
+
+/// Carmichael-Funktion λ(n) für n > 0, siehe
+/// [`Factorization::carmichael_lambda`](crate::algorithms::factorization::Factorization::carmichael_lambda).
+pub fn carmichael_lambda(n: &BigUint) -> BigUint {
+    factorize(n).carmichael_lambda()
+}
+
+/// Anzahl der Teiler von n > 0.
+pub fn divisor_count(n: &BigUint) -> BigUint {
+    factorize(n).divisor_count()
+}
+
+/// Summe der k-ten Potenzen aller Teiler von n > 0.
+pub fn divisor_sum(n: &BigUint, k: u32) -> BigUint {
+    factorize(n).divisor_sum(k)
+}
+
+/// Alle Teiler von n > 0 aufsteigend.
+pub fn divisors(n: &BigUint) -> Vec<BigUint> {
+    factorize(n).divisors()
 }
 
 #[cfg(test)]
@@ -143,6 +448,145 @@ mod tests {
         assert_eq!(lcm(&a, &b), 60u32.to_biguint().unwrap());
     }
 
+    #[test]
+    fn test_crt() {
+        let n = |v: u32| v.to_biguint().unwrap();
+        // teilerfremd: x ≡ 2 (3), 3 (5), 2 (7) → 23 (105)
+        assert_eq!(
+            crt(&[(n(2), n(3)), (n(3), n(5)), (n(2), n(7))]),
+            Some((n(23), n(105)))
+        );
+        // nicht teilerfremd, verträglich: x ≡ 3 (4), 5 (6) → 11 (12)
+        assert_eq!(crt(&[(n(3), n(4)), (n(5), n(6))]), Some((n(11), n(12))));
+        // widersprüchlich, Modul 0, leer
+        assert_eq!(crt(&[(n(1), n(4)), (n(2), n(6))]), None);
+        assert_eq!(crt(&[(n(1), n(0))]), None);
+        assert_eq!(crt(&[]), Some((n(0), n(1))));
+    }
+
+    #[test]
+    fn test_symbols() {
+        let i = |v: i64| BigInt::from(v);
+        let p = 23u32.to_biguint().unwrap();
+        // Quadrate modulo 23
+        let squares: Vec<u32> = (1..23).map(|x| x * x % 23).collect();
+        for a in 0..23 {
+            let expected = if a == 0 {
+                0
+            } else if squares.contains(&(a as u32)) {
+                1
+            } else {
+                -1
+            };
+            assert_eq!(legendre(&i(a), &p), expected);
+            assert_eq!(legendre(&i(a - 23), &p), expected);
+        }
+        assert_eq!(jacobi(&i(2), &15u32.to_biguint().unwrap()), 1);
+        assert_eq!(jacobi(&i(7), &15u32.to_biguint().unwrap()), -1);
+        assert_eq!(jacobi(&i(5), &15u32.to_biguint().unwrap()), 0);
+        assert_eq!(kronecker(&i(5), &i(8)), -1);
+        assert_eq!(kronecker(&i(7), &i(8)), 1);
+        assert_eq!(kronecker(&i(-1), &i(-3)), 1);
+        assert_eq!(kronecker(&i(-1), &i(3)), -1);
+        assert_eq!(kronecker(&i(4), &i(6)), 0);
+        assert_eq!(kronecker(&i(1), &i(0)), 1);
+        assert_eq!(kronecker(&i(2), &i(0)), 0);
+    }
+
+    #[test]
+    fn test_sqrt_mod_prime() {
+        // p ≡ 3 (mod 4), p ≡ 1 (mod 4) mit großer Zweierpotenz (p − 1 = 119·2²³), 2
+        for p in [23u64, 41, 998_244_353, 2] {
+            let p = BigUint::from(p);
+            for a in 0u32..60 {
+                let a = BigUint::from(a) % &p;
+                let residue = p == BigUint::from(2u32)
+                    || a.is_zero()
+                    || legendre(&BigInt::from(a.clone()), &p) == 1;
+                for root in [
+                    tonelli_shanks(&a, &p),
+                    cipolla(&a, &p),
+                    sqrt_mod_prime(&a, &p),
+                ] {
+                    match root {
+                        Some(r) => assert_eq!(&r * &r % &p, a),
+                        None => assert!(!residue),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrt_composite_modulus_terminates() {
+        // kein sinnvolles Ergebnis, aber keine Endlosschleife (9: kein Jacobi-Symbol −1)
+        for p in [9u32, 21, 65, 91] {
+            let p = BigUint::from(p);
+            for a in 0u32..30 {
+                let a = BigUint::from(a);
+                let _ = tonelli_shanks(&a, &p);
+                let _ = cipolla(&a, &p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hensel_lift() {
+        // x³ − 2 ≡ 0: Nullstelle 3 modulo 5 auf 5⁶ heben
+        let p = 5u32.to_biguint().unwrap();
+        let f = [
+            BigInt::from(-2),
+            BigInt::zero(),
+            BigInt::zero(),
+            BigInt::one(),
+        ];
+        let root = hensel_lift(&f, &3u32.to_biguint().unwrap(), &p, 6).unwrap();
+        let modulus = p.pow(6);
+        assert_eq!(&root * &root * &root % &modulus, 2u32.to_biguint().unwrap());
+        assert_eq!(&root % &p, 3u32.to_biguint().unwrap());
+        // keine Nullstelle
+        assert!(hensel_lift(&f, &BigUint::one(), &p, 3).is_none());
+
+        for (a, p, k) in [
+            (2u32, 7u32, 5),
+            (10, 13, 4),
+            (17, 2, 10),
+            (1, 2, 2),
+            (3, 2, 5),
+        ] {
+            let (a, p) = (a.to_biguint().unwrap(), p.to_biguint().unwrap());
+            let modulus = p.pow(k);
+            match sqrt_mod_prime_power(&a, &p, k) {
+                Some(r) => assert_eq!(&r * &r % &modulus, &a % &modulus),
+                // 3 ist kein Quadrat modulo 32
+                None => assert_eq!((a, p), (3u32.into(), 2u32.into())),
+            }
+        }
+        // 5 ist kein Rest modulo 7
+        assert!(
+            sqrt_mod_prime_power(&5u32.to_biguint().unwrap(), &7u32.to_biguint().unwrap(), 3)
+                .is_none()
+        );
+
+        // durch p teilbare a = p^v·a': 9 mod 27 hat die Wurzel 3; ungerades v oder ein
+        // Nichtrest a' (18 = 3²·2) haben keine
+        for (a, p, k, exists) in [
+            (9u32, 3u32, 3, true),
+            (18, 3, 4, false),
+            (4 * 17, 2, 7, true),
+            (8, 2, 6, false),
+            (25 * 11, 5, 5, true),
+            (125 * 4, 5, 5, false),
+        ] {
+            let (a, p) = (a.to_biguint().unwrap(), p.to_biguint().unwrap());
+            let modulus = p.pow(k);
+            match sqrt_mod_prime_power(&a, &p, k) {
+                Some(r) => assert_eq!(&r * &r % &modulus, a),
+                None => assert!(!exists, "{} mod {}^{}", a, p, k),
+            }
+        }
+    }
+
     #[test]
     fn test_totient() {
         assert_eq!(
@@ -157,5 +601,24 @@ mod tests {
             totient(&10u32.to_biguint().unwrap()),
             4u32.to_biguint().unwrap()
         );
+        assert_eq!(totient(&BigUint::zero()), BigUint::zero());
+        // 2⁶⁴ + 1 = 274177 · 67280421310721
+        let n = (BigUint::one() << 64) + 1u32;
+        assert_eq!(
+            totient(&n),
+            BigUint::from(274_176u32) * BigUint::from(67_280_421_310_720u64)
+        );
+    }
+
+    #[test]
+    fn test_divisor_functions() {
+        let n = 28u32.to_biguint().unwrap();
+        assert_eq!(divisor_sum(&n, 1), &n * 2u32); // vollkommene Zahl
+        assert_eq!(divisor_count(&n), 6u32.to_biguint().unwrap());
+        assert_eq!(divisors(&n), [1u32, 2, 4, 7, 14, 28].map(BigUint::from));
+        assert_eq!(
+            carmichael_lambda(&1729u32.to_biguint().unwrap()),
+            36u32.to_biguint().unwrap()
+        );
     }
 }
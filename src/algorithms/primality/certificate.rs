@@ -0,0 +1,493 @@
+//! Primzahlzertifikate nach Pratt und Pocklington.
+//!
+//! Ein Zertifikat beweist die Primalität von `n` über Primfaktoren `q` von `n − 1`, deren
+//! Primalität wiederum zertifiziert ist; Zahlen unter 2³² werden durch Probedivision bewiesen.
+//!
+//! - Pratt: `n − 1` ist vollständig faktorisiert, und ein Zeuge `a` hat die Ordnung `n − 1`:
+//!   a^(n−1) ≡ 1 und a^((n−1)/q) ≢ 1 (mod n) für jeden Primfaktor `q`.
+//! - Pocklington: Ein Teiler `F` von `n − 1` mit F² > n ist faktorisiert, und für jeden
+//!   Primfaktor `q` von `F` gibt es einen Zeugen `a` mit a^(n−1) ≡ 1 (mod n) und
+//!   ggT(a^((n−1)/q) − 1, n) = 1.
+//!
+//! Im Textformat folgt auf die Kopfzeile eine Zeile je Primzahl in aufsteigender Reihenfolge,
+//! die letzte Zeile ist die bewiesene Zahl. Faktoren `q^e` (bei Pocklington `q^e:a`) müssen in
+//! einer früheren Zeile stehen; `#` leitet Kommentare ein.
+//!
+//! ```text
+//! cryptomorph-prime-certificate v1
+//! 2 trial
+//! 3 trial
+//! 5 trial
+//! 131 trial
+//! 364289 trial
+//! 4294967311 pratt 3 2^1 3^2 5^1 131^1 364289^1
+//! ```
+
+use crate::algorithms::factorization::pollard_brent;
+use crate::algorithms::primality::{is_prime_baillie_psw, PrimeError, SMALL_PRIMES};
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+use std::collections::{BTreeMap, HashMap};
+
+/// Kopfzeile des Textformats.
+pub const HEADER: &str = "cryptomorph-prime-certificate v1";
+
+/// Zahlen unter dieser Grenze werden durch Probedivision bewiesen.
+pub const TRIAL_DIVISION_BOUND: u64 = 1 << 32;
+
+/// Schrittzahl, nach der Pollard-Rho je Startwert aufgibt.
+const MAX_RHO_STEPS: u64 = 1 << 18;
+
+/// Obergrenze für die Suche nach Zeugen.
+const MAX_WITNESS: u32 = 1000;
+
+/// Nachweis, dass `n` prim ist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrimeCertificate {
+    pub n: BigUint,
+    pub proof: PrimeProof,
+}
+
+/// Art des Nachweises.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrimeProof {
+    /// `n` < 2³², geprüft durch Probedivision.
+    TrialDivision,
+    /// Vollständige Faktorisierung von `n − 1` als (Zertifikat von `q`, Exponent) und ein
+    /// Zeuge der Ordnung `n − 1`.
+    Pratt {
+        witness: BigUint,
+        factors: Vec<(PrimeCertificate, u32)>,
+    },
+    /// Faktorisierter Teil `F` von `n − 1` mit F² > n.
+    Pocklington { factors: Vec<PocklingtonFactor> },
+}
+
+/// Primfaktor `q^exponent` des faktorisierten Teils mit eigenem Zeugen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PocklingtonFactor {
+    pub certificate: PrimeCertificate,
+    pub exponent: u32,
+    pub witness: BigUint,
+}
+
+/// Primzahltest durch Probedivision für kleine Zahlen.
+pub(crate) fn is_prime_by_trial_division(n: u64) -> bool {
+    if n < 4 {
+        return n >= 2;
+    }
+    if n.is_multiple_of(2) {
+        return false;
+    }
+    (3..)
+        .step_by(2)
+        .take_while(|d| d * d <= n)
+        .all(|d| !n.is_multiple_of(d))
+}
+
+/// Zerlegt `m` in Primfaktoren, soweit Probedivision und Pollard-Rho reichen.
+///
+/// Gibt die gefundenen (wahrscheinlichen) Primfaktoren mit Exponenten und den nicht
+/// zerlegten Rest zurück.
+fn partial_factorization(m: &BigUint) -> (BTreeMap<BigUint, u32>, BigUint) {
+    let mut factors = BTreeMap::new();
+    let mut m = m.clone();
+    for &p in &SMALL_PRIMES {
+        while (&m % p).is_zero() {
+            m /= p;
+            *factors.entry(BigUint::from(p)).or_insert(0) += 1;
+        }
+    }
+    let mut rest = BigUint::one();
+    let mut pending = vec![m];
+    while let Some(c) = pending.pop() {
+        if c.is_one() {
+            continue;
+        }
+        if is_prime_baillie_psw(&c) {
+            *factors.entry(c).or_insert(0) += 1;
+        } else if let Some(d) = pollard_brent(&c, MAX_RHO_STEPS) {
+            pending.push(&c / &d);
+            pending.push(d);
+        } else {
+            rest *= c;
+        }
+    }
+    (factors, rest)
+}
+
+/// Prüft a^(n−1) ≡ 1 (mod n) und gibt a^((n−1)/q) mod n zurück.
+fn witness_power(n: &BigUint, a: &BigUint, q: &BigUint) -> Option<BigUint> {
+    let n_minus_one = n - 1u32;
+    if !a.modpow(&n_minus_one, n).is_one() {
+        return None;
+    }
+    Some(a.modpow(&(n_minus_one / q), n))
+}
+
+/// Pocklington-Bedingung für einen Faktor `q` und Zeugen `a`.
+fn pocklington_condition(n: &BigUint, a: &BigUint, q: &BigUint) -> bool {
+    witness_power(n, a, q).is_some_and(|power| ((power + n - 1u32) % n).gcd(n).is_one())
+}
+
+impl PrimeCertificate {
+    /// Erstellt ein Zertifikat für `n`.
+    ///
+    /// `n − 1` wird durch Probedivision und Pollard-Rho zerlegt; gelingt das vollständig,
+    /// entsteht ein Pratt-, sonst bei ausreichend großem faktorisiertem Teil ein
+    /// Pocklington-Zertifikat. Faktoren werden rekursiv bewiesen.
+    pub fn prove(n: &BigUint) -> Result<Self, PrimeError> {
+        if let Some(small) = n.to_u64().filter(|&small| small < TRIAL_DIVISION_BOUND) {
+            if !is_prime_by_trial_division(small) {
+                return Err(PrimeError::Composite(n.to_string()));
+            }
+            return Ok(PrimeCertificate {
+                n: n.clone(),
+                proof: PrimeProof::TrialDivision,
+            });
+        }
+        if !is_prime_baillie_psw(n) {
+            return Err(PrimeError::Composite(n.to_string()));
+        }
+
+        let n_minus_one = n - 1u32;
+        let (factors, rest) = partial_factorization(&n_minus_one);
+        let factored = &n_minus_one / &rest;
+        if &(&factored * &factored) <= n {
+            return Err(PrimeError::NotProven(n.to_string()));
+        }
+
+        if rest.is_one() {
+            let witness = (2..MAX_WITNESS).map(BigUint::from).find(|a| {
+                factors
+                    .keys()
+                    .all(|q| witness_power(n, a, q).is_some_and(|power| !power.is_one()))
+            });
+            if let Some(witness) = witness {
+                let factors = factors
+                    .iter()
+                    .map(|(q, &exponent)| Ok((Self::prove(q)?, exponent)))
+                    .collect::<Result<_, PrimeError>>()?;
+                return Ok(PrimeCertificate {
+                    n: n.clone(),
+                    proof: PrimeProof::Pratt { witness, factors },
+                });
+            }
+        }
+
+        let factors = factors
+            .iter()
+            .map(|(q, &exponent)| {
+                let witness = (2..MAX_WITNESS)
+                    .map(BigUint::from)
+                    .find(|a| pocklington_condition(n, a, q))
+                    .ok_or_else(|| PrimeError::NotProven(n.to_string()))?;
+                Ok(PocklingtonFactor {
+                    certificate: Self::prove(q)?,
+                    exponent,
+                    witness,
+                })
+            })
+            .collect::<Result<_, PrimeError>>()?;
+        Ok(PrimeCertificate {
+            n: n.clone(),
+            proof: PrimeProof::Pocklington { factors },
+        })
+    }
+
+    /// Prüft das Zertifikat einschließlich aller Faktorzertifikate.
+    pub fn verify(&self) -> Result<(), PrimeError> {
+        let n = &self.n;
+        let invalid = |msg: &str| Err(PrimeError::Invalid(format!("{}: {}", n, msg)));
+        if n < &BigUint::from(2u32) {
+            return invalid("kleiner als 2");
+        }
+        let n_minus_one = n - 1u32;
+
+        match &self.proof {
+            PrimeProof::TrialDivision => match n.to_u64() {
+                Some(small) if small < TRIAL_DIVISION_BOUND => {
+                    if !is_prime_by_trial_division(small) {
+                        return invalid("hat einen Teiler");
+                    }
+                }
+                _ => return invalid("zu groß für Probedivision"),
+            },
+            PrimeProof::Pratt { witness, factors } => {
+                let factors: Vec<_> = factors.iter().map(|(c, e)| (c, *e)).collect();
+                if check_factors(&factors)? != n_minus_one {
+                    return invalid("Faktoren ergeben nicht n − 1");
+                }
+                for (certificate, _) in factors {
+                    match witness_power(n, witness, &certificate.n) {
+                        Some(power) if !power.is_one() => {}
+                        _ => return invalid("Zeuge hat nicht die Ordnung n − 1"),
+                    }
+                }
+            }
+            PrimeProof::Pocklington { factors } => {
+                let list: Vec<_> = factors
+                    .iter()
+                    .map(|f| (&f.certificate, f.exponent))
+                    .collect();
+                let factored = check_factors(&list)?;
+                if !(&n_minus_one % &factored).is_zero() {
+                    return invalid("Faktoren teilen n − 1 nicht");
+                }
+                if &(&factored * &factored) <= n {
+                    return invalid("faktorisierter Teil von n − 1 zu klein");
+                }
+                for factor in factors {
+                    if !pocklington_condition(n, &factor.witness, &factor.certificate.n) {
+                        return invalid("Pocklington-Bedingung verletzt");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Textform des Zertifikats (siehe Moduldokumentation).
+    pub fn to_text(&self) -> String {
+        let mut entries = BTreeMap::new();
+        self.collect(&mut entries);
+        let mut text = format!("{}\n", HEADER);
+        for certificate in entries.values() {
+            text.push_str(&certificate.line());
+            text.push('\n');
+        }
+        text
+    }
+
+    fn collect<'a>(&'a self, entries: &mut BTreeMap<&'a BigUint, &'a PrimeCertificate>) {
+        entries.entry(&self.n).or_insert(self);
+        match &self.proof {
+            PrimeProof::TrialDivision => {}
+            PrimeProof::Pratt { factors, .. } => {
+                for (certificate, _) in factors {
+                    certificate.collect(entries);
+                }
+            }
+            PrimeProof::Pocklington { factors } => {
+                for factor in factors {
+                    factor.certificate.collect(entries);
+                }
+            }
+        }
+    }
+
+    fn line(&self) -> String {
+        match &self.proof {
+            PrimeProof::TrialDivision => format!("{} trial", self.n),
+            PrimeProof::Pratt { witness, factors } => {
+                let mut line = format!("{} pratt {}", self.n, witness);
+                for (certificate, exponent) in factors {
+                    line.push_str(&format!(" {}^{}", certificate.n, exponent));
+                }
+                line
+            }
+            PrimeProof::Pocklington { factors } => {
+                let mut line = format!("{} pocklington", self.n);
+                for factor in factors {
+                    line.push_str(&format!(
+                        " {}^{}:{}",
+                        factor.certificate.n, factor.exponent, factor.witness
+                    ));
+                }
+                line
+            }
+        }
+    }
+
+    /// Liest ein Zertifikat in Textform, ohne es zu prüfen (siehe [`PrimeCertificate::verify`]).
+    pub fn parse(text: &str) -> Result<Self, PrimeError> {
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        if lines.next() != Some(HEADER) {
+            return Err(PrimeError::Format("Kopfzeile fehlt".into()));
+        }
+
+        let mut proven: HashMap<BigUint, PrimeCertificate> = HashMap::new();
+        let mut last: Option<BigUint> = None;
+        for line in lines {
+            let mut fields = line.split_whitespace();
+            let n = parse_decimal(fields.next().unwrap_or_default())?;
+            if last.as_ref().is_some_and(|last| &n <= last) {
+                return Err(PrimeError::Format(format!(
+                    "{}: Zeilen nicht aufsteigend sortiert",
+                    n
+                )));
+            }
+            let lookup = |q: &str| -> Result<PrimeCertificate, PrimeError> {
+                let q = parse_decimal(q)?;
+                proven
+                    .get(&q)
+                    .cloned()
+                    .ok_or_else(|| PrimeError::Format(format!("Faktor {} nicht bewiesen", q)))
+            };
+            let proof = match fields.next() {
+                Some("trial") => PrimeProof::TrialDivision,
+                Some("pratt") => {
+                    let witness = parse_decimal(fields.next().unwrap_or_default())?;
+                    let factors = fields
+                        .by_ref()
+                        .map(|field| {
+                            let (q, exponent) = split_power(field)?;
+                            Ok((lookup(q)?, exponent))
+                        })
+                        .collect::<Result<_, PrimeError>>()?;
+                    PrimeProof::Pratt { witness, factors }
+                }
+                Some("pocklington") => {
+                    let factors = fields
+                        .by_ref()
+                        .map(|field| {
+                            let (power, witness) = field.split_once(':').ok_or_else(|| {
+                                PrimeError::Format(format!("Zeuge fehlt: {}", field))
+                            })?;
+                            let (q, exponent) = split_power(power)?;
+                            Ok(PocklingtonFactor {
+                                certificate: lookup(q)?,
+                                exponent,
+                                witness: parse_decimal(witness)?,
+                            })
+                        })
+                        .collect::<Result<_, PrimeError>>()?;
+                    PrimeProof::Pocklington { factors }
+                }
+                other => {
+                    return Err(PrimeError::Format(format!(
+                        "unbekanntes Verfahren {:?}",
+                        other.unwrap_or_default()
+                    )))
+                }
+            };
+            if fields.next().is_some() {
+                return Err(PrimeError::Format(format!("{}: überzählige Felder", n)));
+            }
+            proven.insert(
+                n.clone(),
+                PrimeCertificate {
+                    n: n.clone(),
+                    proof,
+                },
+            );
+            last = Some(n);
+        }
+        let last = last.ok_or_else(|| PrimeError::Format("keine Einträge".into()))?;
+        Ok(proven.remove(&last).unwrap())
+    }
+}
+
+/// Prüft Faktorzertifikate rekursiv und gibt das Produkt der Potenzen zurück.
+fn check_factors(factors: &[(&PrimeCertificate, u32)]) -> Result<BigUint, PrimeError> {
+    if factors.is_empty() {
+        return Err(PrimeError::Invalid("keine Faktoren".into()));
+    }
+    let mut product = BigUint::one();
+    for (i, (certificate, exponent)) in factors.iter().enumerate() {
+        if *exponent == 0 || factors[..i].iter().any(|(c, _)| c.n == certificate.n) {
+            return Err(PrimeError::Invalid(format!(
+                "Faktor {} doppelt oder mit Exponent 0",
+                certificate.n
+            )));
+        }
+        certificate.verify()?;
+        product *= certificate.n.pow(*exponent);
+    }
+    Ok(product)
+}
+
+/// Liest eine Dezimalzahl ohne Vorzeichen und führende Nullen.
+fn parse_decimal(s: &str) -> Result<BigUint, PrimeError> {
+    let canonical =
+        !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) && (s == "0" || !s.starts_with('0'));
+    canonical
+        .then(|| BigUint::parse_bytes(s.as_bytes(), 10))
+        .flatten()
+        .ok_or_else(|| PrimeError::Format(format!("keine Dezimalzahl: {:?}", s)))
+}
+
+/// Zerlegt `q^e`.
+fn split_power(field: &str) -> Result<(&str, u32), PrimeError> {
+    let (q, exponent) = field
+        .split_once('^')
+        .ok_or_else(|| PrimeError::Format(format!("Faktor ohne Exponent: {}", field)))?;
+    let exponent = exponent
+        .parse()
+        .map_err(|_| PrimeError::Format(format!("ungültiger Exponent: {}", field)))?;
+    Ok((q, exponent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prove_and_verify() {
+        let one = BigUint::one();
+        // 2^61 − 1: n − 1 zerfällt in kleine Faktoren (Pratt)
+        let m61 = (&one << 61) - &one;
+        let certificate = PrimeCertificate::prove(&m61).unwrap();
+        assert!(matches!(certificate.proof, PrimeProof::Pratt { .. }));
+        certificate.verify().unwrap();
+
+        // 2^127 − 1: größter Faktor von n − 1 ist 77158673929 (Pollard-Rho)
+        let m127 = (&one << 127) - &one;
+        let certificate = PrimeCertificate::prove(&m127).unwrap();
+        certificate.verify().unwrap();
+        let text = certificate.to_text();
+        assert!(text.contains("77158673929 pratt"));
+        assert_eq!(PrimeCertificate::parse(&text).unwrap(), certificate);
+
+        assert_eq!(
+            PrimeCertificate::prove(&BigUint::from(65537u32))
+                .unwrap()
+                .proof,
+            PrimeProof::TrialDivision
+        );
+        assert!(matches!(
+            PrimeCertificate::prove(&(&m61 * &m127)),
+            Err(PrimeError::Composite(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_forged_certificates() {
+        let n = BigUint::from(4294967311u64);
+        let text = PrimeCertificate::prove(&n).unwrap().to_text();
+        assert_eq!(
+            text,
+            "cryptomorph-prime-certificate v1\n2 trial\n3 trial\n5 trial\n131 trial\n\
+             364289 trial\n4294967311 pratt 3 2^1 3^2 5^1 131^1 364289^1\n"
+        );
+        let forged = [
+            // Zeuge ohne volle Ordnung
+            text.replace("pratt 3 ", "pratt 4 "),
+            // Faktorisierung unvollständig
+            text.replace(" 131^1", ""),
+            // zusammengesetzte Zahl mit Pocklington-Nachweis über einen zu kleinen Teil
+            "cryptomorph-prime-certificate v1\n2 trial\n4294967311 pocklington 2^1:3\n".into(),
+            // Faktor keine Primzahl (364287 = 3 · 121429)
+            text.replace("364289", "364287"),
+        ];
+        for case in forged {
+            let result = PrimeCertificate::parse(&case).and_then(|c| c.verify());
+            assert!(result.is_err(), "{}", case);
+        }
+        // unbewiesener Faktor und unsortierte Zeilen sind Formatfehler
+        for case in [
+            text.replace("2 trial\n", ""),
+            text.replace("3 trial\n5 trial", "5 trial\n3 trial"),
+            text.replace(HEADER, "prime"),
+        ] {
+            assert!(matches!(
+                PrimeCertificate::parse(&case),
+                Err(PrimeError::Format(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,464 @@
+//! Erzeugung wahrscheinlicher Primzahlen: zufällige, sichere (p = 2q + 1) und starke
+//! Primzahlen (p − 1 und p + 1 mit großem Primfaktor, FIPS 186-5, Anhang B.3.6).
+//!
+//! Gesucht wird inkrementell: Ab einem zufälligen Startwert streicht ein Sieb über ein Fenster
+//! von Kandidaten alle mit einem Primteiler unter 2¹⁶, nur die übrigen werden mit
+//! Miller-Rabin (Rundenzahl nach FIPS 186-5) und dem starken Lucas-Test geprüft. Primzahlen
+//! nach großen Lücken werden dabei etwas häufiger gewählt; für Schlüssel ist das unerheblich.
+//!
+//! Mit [`PrimeOptions::threads`] suchen mehrere Threads ab eigenen Startwerten gleichzeitig;
+//! der erste Treffer beendet alle übrigen Suchen.
+
+use crate::algorithms::number_theory::mod_inverse;
+use crate::algorithms::primality::{
+    is_prime_baillie_psw, is_probably_prime, is_strong_lucas_prime, miller_rabin, rsa_prime_rounds,
+    PrimeError,
+};
+use num_bigint::{BigUint, RandBigInt};
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive};
+use rand::rngs::OsRng;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::thread;
+
+/// Obergrenze der Siebprimzahlen.
+const SIEVE_LIMIT: usize = 1 << 16;
+
+/// Kandidaten je Siebfenster.
+const WINDOW: usize = 4096;
+
+/// Unterhalb dieser Bitlänge wird ohne Sieb gesucht (das Sieb würde die Primzahlen selbst
+/// streichen).
+const MIN_SIEVE_BITS: usize = 24;
+
+/// Art der gesuchten Primzahl.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrimeKind {
+    /// Beliebige Primzahl.
+    #[default]
+    Random,
+    /// Sichere Primzahl p = 2q + 1 mit Primzahl q, etwa für Diffie-Hellman-Gruppen.
+    Safe,
+    /// Starke Primzahl: p − 1 und p + 1 haben je einen Primfaktor der Länge
+    /// [`strong_prime_aux_bits`].
+    Strong,
+}
+
+/// Parameter von [`generate_prime`].
+#[derive(Debug, Clone)]
+pub struct PrimeOptions {
+    /// Bitlänge; die Primzahl hat immer genau so viele Bit.
+    pub bits: usize,
+    pub kind: PrimeKind,
+    /// Die obersten beiden Bit setzen, damit das Produkt zweier solcher Primzahlen genau
+    /// `2·bits` Bit hat.
+    pub top_two_bits: bool,
+    /// Anzahl der Suchthreads; 0 wählt die Zahl der verfügbaren Kerne.
+    pub threads: usize,
+}
+
+impl PrimeOptions {
+    /// Beliebige Primzahl mit `bits` Bit.
+    pub fn new(bits: usize) -> Self {
+        PrimeOptions {
+            bits,
+            kind: PrimeKind::Random,
+            top_two_bits: false,
+            threads: 1,
+        }
+    }
+}
+
+/// Starke Primzahl mit ihren Hilfsprimzahlen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrongPrime {
+    pub p: BigUint,
+    /// Primfaktor von p − 1.
+    pub p1: BigUint,
+    /// Primfaktor von p + 1.
+    pub p2: BigUint,
+}
+
+/// Ungerade Primzahlen unter [`SIEVE_LIMIT`].
+fn sieve_primes() -> &'static [u32] {
+    static PRIMES: OnceLock<Vec<u32>> = OnceLock::new();
+    PRIMES.get_or_init(|| {
+        let mut composite = vec![false; SIEVE_LIMIT];
+        let mut primes = Vec::new();
+        for i in (3..SIEVE_LIMIT).step_by(2) {
+            if !composite[i] {
+                primes.push(i as u32);
+                for j in (i * i..SIEVE_LIMIT).step_by(2 * i) {
+                    composite[j] = true;
+                }
+            }
+        }
+        primes
+    })
+}
+
+/// Inverses von `a` modulo der Primzahl `p` (kleiner Fermat).
+fn inverse_mod_prime(a: u64, p: u64) -> u64 {
+    let (mut base, mut exponent, mut result) = (a % p, p - 2, 1);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % p;
+        }
+        base = base * base % p;
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Wahrscheinlichkeitstest für erzeugte Primzahlen: Miller-Rabin mit der Rundenzahl nach
+/// FIPS 186-5 und starker Lucas-Test.
+fn is_generated_prime(n: &BigUint) -> bool {
+    is_probably_prime(n, rsa_prime_rounds(n.bits() as usize)) && is_strong_lucas_prime(n)
+}
+
+/// Sucht in `start + k·step` (k = 0, 1, …, Kandidaten höchstens bis `upper`) die erste Zahl x,
+/// für die `accept(x)` gilt. Vorab gestrichen werden Kandidaten mit einem Primteiler unter
+/// 2¹⁶ und, mit `safe`, solche, bei denen 2x + 1 einen hat. Ist `stop` gesetzt, endet die
+/// Suche ohne Ergebnis.
+fn sieve_search(
+    start: &BigUint,
+    step: &BigUint,
+    upper: &BigUint,
+    safe: bool,
+    stop: &AtomicBool,
+    mut accept: impl FnMut(&BigUint) -> bool,
+) -> Option<BigUint> {
+    let mut base = start.clone();
+    while &base <= upper && !stop.load(Ordering::Relaxed) {
+        let mut composite = [false; WINDOW];
+        for &p in sieve_primes() {
+            let p64 = u64::from(p);
+            let x = (&base % p).to_u64().unwrap();
+            let s = (step % p).to_u64().unwrap();
+            // verbotene Reste: x + k·s ≡ 0 und bei safe 2(x + k·s) + 1 ≡ 0, also ≡ (p − 1)/2
+            let forbidden = [Some(0), safe.then_some((p64 - 1) / 2)];
+            for target in forbidden.into_iter().flatten() {
+                if s == 0 {
+                    if x == target {
+                        composite.fill(true);
+                    }
+                    continue;
+                }
+                let first = (target + p64 - x) % p64 * inverse_mod_prime(s, p64) % p64;
+                for k in (first as usize..WINDOW).step_by(p as usize) {
+                    composite[k] = true;
+                }
+            }
+        }
+        for (k, &skip) in composite.iter().enumerate() {
+            let candidate = &base + step * k;
+            if &candidate > upper {
+                return None;
+            }
+            if skip {
+                continue;
+            }
+            if stop.load(Ordering::Relaxed) {
+                return None;
+            }
+            if accept(&candidate) {
+                return Some(candidate);
+            }
+        }
+        base += step * WINDOW;
+    }
+    None
+}
+
+/// Lässt `search` auf `threads` Threads (0: alle Kerne) gleichzeitig laufen und gibt den
+/// ersten Treffer zurück. `search` sucht, bis es fündig wird oder das Flag gesetzt ist; nach
+/// dem ersten Treffer wird es für alle gesetzt.
+fn parallel_search<T: Send>(threads: usize, search: impl Fn(&AtomicBool) -> Option<T> + Sync) -> T {
+    let threads = match threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+    let stop = AtomicBool::new(false);
+    let found = if threads == 1 {
+        search(&stop)
+    } else {
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let found = search(&stop);
+                        if found.is_some() {
+                            stop.store(true, Ordering::Relaxed);
+                        }
+                        found
+                    })
+                })
+                .collect();
+            workers.into_iter().find_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+        })
+    };
+    found.expect("die Suche endet nur nach einem Treffer")
+}
+
+/// Kleinster Wert mit `bits` Bit, mit `top_two_bits` beginnend mit `11`.
+fn lower_bound(bits: usize, top_two_bits: bool) -> BigUint {
+    if top_two_bits && bits >= 2 {
+        BigUint::from(3u32) << (bits - 2)
+    } else {
+        BigUint::one() << (bits - 1)
+    }
+}
+
+/// Länge der Hilfsprimzahlen einer starken Primzahl mit `prime_bits` Bit: nach FIPS 186-5,
+/// Tabelle B.1, für 1024, 1536 und 2048 Bit, darunter ein Viertel der Länge.
+pub fn strong_prime_aux_bits(prime_bits: usize) -> usize {
+    match prime_bits {
+        2048.. => 201,
+        1536..2048 => 171,
+        1024..1536 => 141,
+        _ => (prime_bits / 4).max(2),
+    }
+}
+
+/// Erzeugt eine wahrscheinliche Primzahl nach `options`.
+pub fn generate_prime(options: &PrimeOptions) -> Result<BigUint, PrimeError> {
+    match options.kind {
+        PrimeKind::Random => random_prime(options.bits, options.top_two_bits, options.threads),
+        PrimeKind::Safe => safe_prime(options.bits, options.top_two_bits, options.threads),
+        PrimeKind::Strong => {
+            let aux_bits = strong_prime_aux_bits(options.bits);
+            generate_strong_prime(options, aux_bits).map(|s| s.p)
+        }
+    }
+}
+
+fn random_prime(bits: usize, top_two_bits: bool, threads: usize) -> Result<BigUint, PrimeError> {
+    if bits < 2 {
+        return Err(PrimeError::Generation("mindestens 2 Bit".into()));
+    }
+    let lower = lower_bound(bits, top_two_bits);
+    let upper = (BigUint::one() << bits) - 1u32;
+    let mut rng = OsRng;
+    if bits < MIN_SIEVE_BITS {
+        loop {
+            let candidate = rng.gen_biguint_range(&lower, &(&upper + 1u32));
+            if is_prime_baillie_psw(&candidate) {
+                return Ok(candidate);
+            }
+        }
+    }
+    let two = BigUint::from(2u32);
+    Ok(parallel_search(threads, |stop| {
+        let mut rng = OsRng;
+        while !stop.load(Ordering::Relaxed) {
+            let start = rng.gen_biguint_range(&lower, &upper) | BigUint::one();
+            let found = sieve_search(&start, &two, &upper, false, stop, is_generated_prime);
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    }))
+}
+
+fn safe_prime(bits: usize, top_two_bits: bool, threads: usize) -> Result<BigUint, PrimeError> {
+    if bits < 3 {
+        return Err(PrimeError::Generation(
+            "sichere Primzahlen haben mindestens 3 Bit".into(),
+        ));
+    }
+    // Grenzen für q = (p − 1)/2
+    let lower = (lower_bound(bits, top_two_bits) >> 1u32) | BigUint::one();
+    let upper = (BigUint::one() << (bits - 1)) - 1u32;
+    let mut rng = OsRng;
+    if bits < MIN_SIEVE_BITS {
+        loop {
+            let q = rng.gen_biguint_range(&lower, &(&upper + 1u32));
+            let p: BigUint = (&q << 1u32) + 1u32;
+            if p >= lower_bound(bits, top_two_bits)
+                && is_prime_baillie_psw(&q)
+                && is_prime_baillie_psw(&p)
+            {
+                return Ok(p);
+            }
+        }
+    }
+    let two = BigUint::from(2u32);
+    let q = parallel_search(threads, |stop| {
+        let mut rng = OsRng;
+        while !stop.load(Ordering::Relaxed) {
+            let start = rng.gen_biguint_range(&lower, &upper) | BigUint::one();
+            let found = sieve_search(&start, &two, &upper, true, stop, |q| {
+                let p: BigUint = (q << 1u32) + 1u32;
+                // schneller Ausschluss mit einer Runde für p vor den vollen Tests
+                miller_rabin(&p, &two) && is_generated_prime(q) && is_generated_prime(&p)
+            });
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    });
+    Ok((q << 1u32) + 1u32)
+}
+
+/// Erzeugt eine starke Primzahl mit `options.bits` Bit, deren p − 1 und p + 1 je eine
+/// Primzahl mit `aux_bits` Bit als Faktor haben (FIPS 186-5, Anhang B.3.6); `options.kind`
+/// wird nicht beachtet.
+pub fn generate_strong_prime(
+    options: &PrimeOptions,
+    aux_bits: usize,
+) -> Result<StrongPrime, PrimeError> {
+    let PrimeOptions {
+        bits,
+        top_two_bits,
+        threads,
+        ..
+    } = *options;
+    if aux_bits < 2 || 2 * aux_bits + 8 > bits || bits < MIN_SIEVE_BITS {
+        return Err(PrimeError::Generation(format!(
+            "Hilfsprimzahlen mit {} Bit passen nicht zu {} Bit",
+            aux_bits, bits
+        )));
+    }
+    let p1 = random_prime(aux_bits, false, threads)?;
+    let p2 = loop {
+        let p2 = random_prime(aux_bits, false, threads)?;
+        if p2 != p1 && p2.is_odd() {
+            break p2;
+        }
+    };
+
+    // R ≡ 1 (mod 2·p1) und R ≡ −1 (mod p2)
+    let two_p1: BigUint = &p1 << 1u32;
+    let step = &two_p1 * &p2;
+    let a = mod_inverse(&p2, &two_p1).unwrap() * &p2;
+    let b = mod_inverse(&two_p1, &p2).unwrap() * &two_p1;
+    let r = (a + &step - b % &step) % &step;
+
+    let lower = lower_bound(bits, top_two_bits);
+    let upper = (BigUint::one() << bits) - 1u32;
+    let p = parallel_search(threads, |stop| {
+        let mut rng = OsRng;
+        while !stop.load(Ordering::Relaxed) {
+            let x = rng.gen_biguint_range(&lower, &upper);
+            let y = &x + (&r + &step - &x % &step) % &step;
+            let found = sieve_search(&y, &step, &upper, false, stop, is_generated_prime);
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    });
+    Ok(StrongPrime { p, p1, p2 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::Zero;
+
+    #[test]
+    fn test_sieve_finds_next_prime() {
+        let mut rng = OsRng;
+        let two = BigUint::from(2u32);
+        let upper = BigUint::one() << 80;
+        let stop = AtomicBool::new(false);
+        for _ in 0..5 {
+            let start = rng.gen_biguint(64) | (BigUint::one() << 63) | BigUint::one();
+            let found =
+                sieve_search(&start, &two, &upper, false, &stop, is_prime_baillie_psw).unwrap();
+            let mut expected = start.clone();
+            while !is_prime_baillie_psw(&expected) {
+                expected += 2u32;
+            }
+            assert_eq!(found, expected);
+        }
+        // Obergrenze wird eingehalten
+        let start = BigUint::from(24u32 * 1000);
+        assert!(sieve_search(&(&start + 1u32), &two, &start, false, &stop, |_| true).is_none());
+        // gesetztes Abbruchflag beendet die Suche
+        stop.store(true, Ordering::Relaxed);
+        assert!(sieve_search(&start, &two, &upper, false, &stop, |_| true).is_none());
+        assert_eq!(sieve_primes()[..4], [3, 5, 7, 11]);
+        assert_eq!(sieve_primes().len(), 6541);
+    }
+
+    #[test]
+    fn test_random_and_safe_primes() {
+        for bits in [2, 16, 64, 256, 512] {
+            let p = generate_prime(&PrimeOptions::new(bits)).unwrap();
+            assert_eq!(p.bits(), bits as u64);
+            assert!(is_prime_baillie_psw(&p));
+        }
+        let options = PrimeOptions {
+            top_two_bits: true,
+            ..PrimeOptions::new(128)
+        };
+        assert_eq!(
+            generate_prime(&options).unwrap() >> 126u32,
+            BigUint::from(3u32)
+        );
+
+        for bits in [3, 20, 64, 160] {
+            let options = PrimeOptions {
+                kind: PrimeKind::Safe,
+                ..PrimeOptions::new(bits)
+            };
+            let p = generate_prime(&options).unwrap();
+            assert_eq!(p.bits(), bits as u64);
+            assert!(is_prime_baillie_psw(&p));
+            assert!(is_prime_baillie_psw(&(&p >> 1u32)));
+        }
+        assert!(generate_prime(&PrimeOptions::new(1)).is_err());
+    }
+
+    #[test]
+    fn test_parallel_search() {
+        for kind in [PrimeKind::Random, PrimeKind::Safe, PrimeKind::Strong] {
+            let options = PrimeOptions {
+                kind,
+                top_two_bits: true,
+                threads: 4,
+                ..PrimeOptions::new(256)
+            };
+            let p = generate_prime(&options).unwrap();
+            assert!(is_prime_baillie_psw(&p));
+            assert_eq!(p >> 254u32, BigUint::from(3u32));
+        }
+        // 0 Threads: alle verfügbaren Kerne
+        let p = generate_prime(&PrimeOptions {
+            threads: 0,
+            ..PrimeOptions::new(512)
+        })
+        .unwrap();
+        assert_eq!(p.bits(), 512);
+        assert!(is_prime_baillie_psw(&p));
+    }
+
+    #[test]
+    fn test_strong_primes() {
+        let options = PrimeOptions {
+            top_two_bits: true,
+            ..PrimeOptions::new(256)
+        };
+        let strong = generate_strong_prime(&options, 64).unwrap();
+        assert_eq!(strong.p.bits(), 256);
+        assert!(is_prime_baillie_psw(&strong.p));
+        assert_eq!(strong.p1.bits(), 64);
+        assert!(((&strong.p - 1u32) % &strong.p1).is_zero());
+        assert!(((&strong.p + 1u32) % &strong.p2).is_zero());
+
+        let options = PrimeOptions {
+            kind: PrimeKind::Strong,
+            ..PrimeOptions::new(192)
+        };
+        assert_eq!(generate_prime(&options).unwrap().bits(), 192);
+        assert!(generate_strong_prime(&PrimeOptions::new(100), 50).is_err());
+    }
+}
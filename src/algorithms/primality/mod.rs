@@ -0,0 +1,393 @@
+//! Primzahltests und beweisbare Primzahlen.
+//!
+//! Probabilistische Tests (Miller-Rabin, starker Lucas-Test, Baillie-PSW) stehen hier;
+//! [`generate`] erzeugt zufällige, sichere und starke Primzahlen, [`provable`] Primzahlen
+//! mit Beweis nach Maurer und Shawe-Taylor, [`certificate`] erstellt und prüft
+//! Primzahlzertifikate nach Pratt und Pocklington.
+
+pub mod certificate;
+pub mod generate;
+pub mod provable;
+
+pub use certificate::{PocklingtonFactor, PrimeCertificate, PrimeProof};
+pub use generate::{generate_prime, PrimeKind, PrimeOptions};
+pub use provable::{maurer_prime, shawe_taylor_prime, ShaweTaylorPrime};
+
+use crate::algorithms::number_theory::jacobi;
+use num_bigint::{BigInt, BigUint, RandBigInt, ToBigUint};
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+use rand::thread_rng;
+use std::fmt;
+
+/// Fehler beim Beweisen, Erzeugen oder Prüfen von Primzahlen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrimeError {
+    /// Die Zahl ist zusammengesetzt.
+    Composite(String),
+    /// Kein Beweis gefunden (n − 1 ließ sich nicht weit genug faktorisieren).
+    NotProven(String),
+    /// Das Zertifikat ist syntaktisch fehlerhaft.
+    Format(String),
+    /// Eine Bedingung des Zertifikats ist verletzt.
+    Invalid(String),
+    /// Ungültige Parameter oder Abbruch der Erzeugung.
+    Generation(String),
+}
+
+impl fmt::Display for PrimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrimeError::Composite(n) => write!(f, "{} ist keine Primzahl", n),
+            PrimeError::NotProven(n) => write!(
+                f,
+                "Kein Primzahlbeweis für {} gefunden: n − 1 ließ sich nicht weit genug faktorisieren",
+                n
+            ),
+            PrimeError::Format(msg) => write!(f, "Ungültiges Primzahlzertifikat: {}", msg),
+            PrimeError::Invalid(msg) => write!(f, "Primzahlzertifikat falsch: {}", msg),
+            PrimeError::Generation(msg) => write!(f, "Primzahlerzeugung: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PrimeError {}
+
+/// Obergrenze der Probedivision.
+const TRIAL_DIVISION_LIMIT: u32 = 1000;
+
+/// Alle Primzahlen unter [`TRIAL_DIVISION_LIMIT`] (Sieb des Eratosthenes zur Übersetzungszeit).
+pub const SMALL_PRIMES: [u32; 168] = small_primes();
+
+const fn small_primes() -> [u32; 168] {
+    let mut composite = [false; TRIAL_DIVISION_LIMIT as usize];
+    let mut primes = [0u32; 168];
+    let mut count = 0;
+    let mut i = 2;
+    while i < TRIAL_DIVISION_LIMIT as usize {
+        if !composite[i] {
+            primes[count] = i as u32;
+            count += 1;
+            let mut j = i * i;
+            while j < TRIAL_DIVISION_LIMIT as usize {
+                composite[j] = true;
+                j += i;
+            }
+        }
+        i += 1;
+    }
+    assert!(count == 168);
+    primes
+}
+
+/// Deterministische Basismengen für Miller-Rabin: (Schranke, Basen) – jede ungerade Zahl
+/// unterhalb der Schranke, die für alle Basen den starken Test besteht, ist prim
+/// (Jaeschke 1993; Sorenson/Webster 2015).
+const DETERMINISTIC_BASES: [(u128, &[u32]); 10] = [
+    (2_047, &[2]),
+    (1_373_653, &[2, 3]),
+    (25_326_001, &[2, 3, 5]),
+    (3_215_031_751, &[2, 3, 5, 7]),
+    (2_152_302_898_747, &[2, 3, 5, 7, 11]),
+    (3_474_749_660_383, &[2, 3, 5, 7, 11, 13]),
+    (341_550_071_728_321, &[2, 3, 5, 7, 11, 13, 17]),
+    (3_825_123_056_546_413_051, &[2, 3, 5, 7, 11, 13, 17, 19, 23]),
+    (
+        318_665_857_834_031_151_167_461,
+        &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37],
+    ),
+    (
+        3_317_044_064_679_887_385_961_981,
+        &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41],
+    ),
+];
+
+/// Probedivision durch [`SMALL_PRIMES`].
+///
+/// `Some(true)`/`Some(false)`, wenn `n` damit entschieden ist (kleine Primzahl, kleiner
+/// Teiler oder `n` < 1000²), sonst `None`.
+pub(crate) fn trial_division(n: &BigUint) -> Option<bool> {
+    if let Some(small) = n.to_u32() {
+        if small < TRIAL_DIVISION_LIMIT {
+            return Some(SMALL_PRIMES.binary_search(&small).is_ok());
+        }
+    }
+    if SMALL_PRIMES.iter().any(|&p| (n % p).is_zero()) {
+        return Some(false);
+    }
+    let limit = TRIAL_DIVISION_LIMIT as u64;
+    if n.to_u64().is_some_and(|small| small < limit * limit) {
+        return Some(true);
+    }
+    None
+}
+
+/// Zerlegt `n - 1` in `2^r * d` mit ungeradem `d`.
+fn split_power_of_two(n_minus_one: &BigUint) -> (BigUint, u64) {
+    let r = n_minus_one.trailing_zeros().unwrap_or(0);
+    (n_minus_one >> r, r)
+}
+
+/// Starker Fermat-Test (eine Miller-Rabin-Runde) für ungerades `n` > 3 zur Basis `a`.
+///
+/// Gibt `true` zurück, wenn `n` eine starke Pseudoprimzahl zur Basis `a` ist.
+pub fn miller_rabin(n: &BigUint, a: &BigUint) -> bool {
+    let one = BigUint::one();
+    let n_minus_one = n - &one;
+    let a = a % n;
+    if a.is_zero() || a == one || a == n_minus_one {
+        return true;
+    }
+    let (d, r) = split_power_of_two(&n_minus_one);
+    let mut x = a.modpow(&d, n);
+    if x == one || x == n_minus_one {
+        return true;
+    }
+    for _ in 1..r {
+        x = &x * &x % n;
+        if x == n_minus_one {
+            return true;
+        }
+        if x == one {
+            return false;
+        }
+    }
+    false
+}
+
+/// Deterministischer Miller-Rabin-Test für `n` < 3,3·10²⁴; `None` für größere `n`.
+fn deterministic_miller_rabin(n: &BigUint) -> Option<bool> {
+    let small = n.to_u128()?;
+    let (_, bases) = DETERMINISTIC_BASES
+        .iter()
+        .find(|(bound, _)| small < *bound)?;
+    Some(bases.iter().all(|&a| miller_rabin(n, &BigUint::from(a))))
+}
+
+/// Halbiert `x` modulo ungeradem `n`.
+fn half_mod(x: BigUint, n: &BigUint) -> BigUint {
+    if x.is_odd() {
+        (x + n) >> 1
+    } else {
+        x >> 1
+    }
+}
+
+/// Starker Lucas-Test mit Parametern nach Selfridge (Methode A) für ungerades `n` > 2.
+///
+/// Gewählt wird das erste `D` aus 5, −7, 9, −11, … mit (D/n) = −1, dann `P = 1`,
+/// `Q = (1 − D)/4`. Gibt `true` zurück, wenn `n` eine starke Lucas-Pseudoprimzahl ist.
+/// Quadratzahlen werden vorab abgelehnt, da für sie kein solches `D` existiert.
+pub fn is_strong_lucas_prime(n: &BigUint) -> bool {
+    let one = BigUint::one();
+    if n.is_even() || n <= &one {
+        return n == &BigUint::from(2u32);
+    }
+    let root = n.sqrt();
+    if &(&root * &root) == n {
+        return false;
+    }
+
+    // D suchen; D und Q als Reste modulo n
+    let mut magnitude = 5u32;
+    let mut negative = false;
+    let d = loop {
+        let abs = BigUint::from(magnitude);
+        let d = if negative { n - &abs % n } else { &abs % n };
+        match jacobi(&BigInt::from(d.clone()), n) {
+            -1 => break d,
+            0 if &abs % n != BigUint::zero() => return false,
+            0 => return true,
+            _ => {}
+        }
+        magnitude += 2;
+        negative = !negative;
+    };
+    // Q = (1 - D)/4 mod n
+    let q = half_mod(half_mod((&one + n - &d) % n, n), n);
+
+    // n + 1 = d * 2^s
+    let n_plus_one = n + &one;
+    let s = n_plus_one.trailing_zeros().unwrap_or(0);
+    let k = &n_plus_one >> s;
+
+    // U_k, V_k, Q^k von links nach rechts mit P = 1
+    let mut u = BigUint::zero();
+    let mut v = BigUint::from(2u32) % n;
+    let mut q_k = one.clone();
+    for i in (0..k.bits()).rev() {
+        // Verdoppeln: U_2j = U_j V_j, V_2j = V_j² − 2Q^j
+        u = &u * &v % n;
+        v = (&v * &v + n + n - (&q_k << 1) % n) % n;
+        q_k = &q_k * &q_k % n;
+        if k.bit(i) {
+            // Schritt j → j+1: U = (U + V)/2, V = (D U + V)/2
+            let next_u = half_mod((&u + &v) % n, n);
+            v = half_mod((&d * &u + &v) % n, n);
+            u = next_u;
+            q_k = &q_k * &q % n;
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    for _ in 1..s {
+        v = (&v * &v + n + n - (&q_k << 1) % n) % n;
+        if v.is_zero() {
+            return true;
+        }
+        q_k = &q_k * &q_k % n;
+    }
+    false
+}
+
+/// Baillie-PSW-Test: Probedivision, starker Fermat-Test zur Basis 2 und starker Lucas-Test.
+///
+/// Für `n` < 2⁶⁴ beweisbar korrekt, darüber ist kein Gegenbeispiel bekannt. Für
+/// `n` < 3,3·10²⁴ wird stattdessen deterministisch mit festen Basen geprüft.
+pub fn is_prime_baillie_psw(n: &BigUint) -> bool {
+    if let Some(decided) = trial_division(n) {
+        return decided;
+    }
+    if let Some(decided) = deterministic_miller_rabin(n) {
+        return decided;
+    }
+    miller_rabin(n, &BigUint::from(2u32)) && is_strong_lucas_prime(n)
+}
+
+/// Miller-Rabin-Primzahltest für beliebig große `n`.
+///
+/// Nach Probedivision durch kleine Primzahlen wird für `n` < 3,3·10²⁴ deterministisch
+/// geprüft, sonst mit `k` zufälligen Basen. Gibt `true` zurück, wenn `n` mit hoher
+/// Wahrscheinlichkeit prim ist.
+pub fn is_probably_prime(n: &BigUint, k: u32) -> bool {
+    if let Some(decided) = trial_division(n) {
+        return decided;
+    }
+    if let Some(decided) = deterministic_miller_rabin(n) {
+        return decided;
+    }
+
+    let two = 2u32.to_biguint().unwrap();
+    let upper = n - &two;
+    let mut rng = thread_rng();
+    (0..k).all(|_| miller_rabin(n, &rng.gen_biguint_range(&two, &upper)))
+}
+
+/// Mindestanzahl der Miller-Rabin-Runden für RSA-Primfaktoren der Länge `prime_bits`,
+/// wenn zusätzlich ein starker Lucas-Test folgt (FIPS 186-5, Tabelle B.1).
+///
+/// Faktoren unter 1024 Bit liegen außerhalb der Norm und erhalten die Rundenzahl für
+/// 1024 Bit.
+pub fn rsa_prime_rounds(prime_bits: usize) -> u32 {
+    match prime_bits {
+        0..1536 => 4,
+        1536..2048 => 3,
+        _ => 2,
+    }
+}
+
+// ======= Tests =======
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::ToBigUint;
+
+    fn big(n: u128) -> BigUint {
+        n.to_biguint().unwrap()
+    }
+
+    #[test]
+    fn test_small_primes() {
+        assert!(is_probably_prime(&3u32.to_biguint().unwrap(), 5));
+        assert!(is_probably_prime(&13u32.to_biguint().unwrap(), 5));
+    }
+
+    #[test]
+    fn test_small_composites() {
+        assert!(!is_probably_prime(&15u32.to_biguint().unwrap(), 5));
+        assert!(!is_probably_prime(&221u32.to_biguint().unwrap(), 5)); // 13×17
+    }
+
+    #[test]
+    fn test_large_prime_candidate() {
+        let p = BigUint::parse_bytes(b"32416190071", 10).unwrap(); // bekannte 11-stellige Primzahl
+        assert!(is_probably_prime(&p, 10));
+    }
+
+    #[test]
+    fn test_small_range_against_sieve() {
+        let limit = 20_000usize;
+        let mut composite = vec![false; limit];
+        for i in 2..limit {
+            if !composite[i] {
+                for j in (i * i..limit).step_by(i) {
+                    composite[j] = true;
+                }
+            }
+            let n = big(i as u128);
+            assert_eq!(is_prime_baillie_psw(&n), !composite[i], "{}", i);
+            assert_eq!(is_probably_prime(&n, 1), !composite[i], "{}", i);
+        }
+        assert!(!is_prime_baillie_psw(&BigUint::zero()));
+        assert!(!is_prime_baillie_psw(&BigUint::one()));
+        assert_eq!(SMALL_PRIMES[167], 997);
+    }
+
+    #[test]
+    fn test_strong_pseudoprimes() {
+        // starke Pseudoprimzahlen zur Basis 2 fallen beim Lucas-Test durch
+        for n in [2047u128, 3277, 4033, 4681, 8321, 3_215_031_751] {
+            assert!(miller_rabin(&big(n), &big(2)), "{}", n);
+            assert!(!is_strong_lucas_prime(&big(n)), "{}", n);
+            assert!(!is_prime_baillie_psw(&big(n)), "{}", n);
+        }
+        // starke Lucas-Pseudoprimzahlen (OEIS A217255) fallen beim Fermat-Test durch
+        for n in [
+            5459u128, 5777, 10877, 16109, 18971, 22499, 24569, 25199, 40309,
+        ] {
+            assert!(is_strong_lucas_prime(&big(n)), "{}", n);
+            assert!(!miller_rabin(&big(n), &big(2)), "{}", n);
+            assert!(!is_prime_baillie_psw(&big(n)), "{}", n);
+        }
+        // und sind die einzigen bis 41000
+        let pseudoprimes = (3u128..41_000)
+            .step_by(2)
+            .filter(|&n| is_strong_lucas_prime(&big(n)) && !is_prime_baillie_psw(&big(n)))
+            .count();
+        assert_eq!(pseudoprimes, 9);
+    }
+
+    #[test]
+    fn test_deterministic_bases() {
+        // starke Pseudoprimzahlen zu den ersten 9 bzw. 12 Primzahlbasen
+        for n in [
+            3_825_123_056_546_413_051u128,
+            318_665_857_834_031_151_167_461,
+        ] {
+            assert!(!is_probably_prime(&big(n), 0), "{}", n);
+            assert!(!is_prime_baillie_psw(&big(n)), "{}", n);
+        }
+        let p = big((1 << 61) - 1);
+        assert!(is_probably_prime(&p, 0));
+        assert!(!is_probably_prime(&(&p * &p), 5));
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        let one = BigUint::one();
+        for e in [89u32, 107, 127, 521, 607] {
+            let mersenne = (&one << e) - &one;
+            assert!(is_prime_baillie_psw(&mersenne), "2^{}-1", e);
+            assert!(is_strong_lucas_prime(&mersenne), "2^{}-1", e);
+            assert!(is_probably_prime(&mersenne, 5), "2^{}-1", e);
+        }
+        let composite = ((&one << 127) - &one) * ((&one << 89) - &one);
+        assert!(!is_prime_baillie_psw(&composite));
+        assert!(!is_probably_prime(&composite, 5));
+        // 2^128 + 1 = 59649589127497217 × 5704689200685129054721
+        assert!(!is_prime_baillie_psw(&((&one << 128) + &one)));
+    }
+}
@@ -0,0 +1,255 @@
+//! Beweisbare Primzahlen nach Maurer und nach Shawe-Taylor (FIPS 186-5, Anhang B.10).
+//!
+//! Beide Verfahren setzen eine Primzahl `n = 2·t·q + 1` aus einer kleineren, bereits
+//! bewiesenen Primzahl `q` > √n zusammen; der Pocklington-Test für `q` liefert das
+//! Zertifikat gleich mit. Maurer zieht die Größe von `q` zufällig, sodass die Primzahlen
+//! nahezu gleichverteilt sind; Shawe-Taylor leitet alles reproduzierbar aus einem Startwert
+//! (Seed) mit SHA-256 ab.
+
+use crate::algorithms::primality::certificate::{
+    is_prime_by_trial_division, PocklingtonFactor, PrimeCertificate, PrimeProof,
+};
+use crate::algorithms::primality::{trial_division, PrimeError};
+use num_bigint::{BigUint, RandBigInt};
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive};
+use rand::rngs::OsRng;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+/// Bis zu dieser Bitlänge wird direkt durch Probedivision gesucht.
+const SMALL_BITS: usize = 32;
+
+/// Mindestabstand (in Bit) zwischen `n` und `q` bei Maurer.
+const MAURER_MARGIN: usize = 20;
+
+fn pocklington(n: BigUint, q: PrimeCertificate, witness: BigUint) -> PrimeCertificate {
+    PrimeCertificate {
+        n,
+        proof: PrimeProof::Pocklington {
+            factors: vec![PocklingtonFactor {
+                certificate: q,
+                exponent: 1,
+                witness,
+            }],
+        },
+    }
+}
+
+/// Erzeugt eine zufällige Primzahl mit genau `bits` Bit nach Maurer samt Zertifikat.
+pub fn maurer_prime(bits: usize) -> Result<PrimeCertificate, PrimeError> {
+    if bits < 2 {
+        return Err(PrimeError::Generation("mindestens 2 Bit".into()));
+    }
+    let mut rng = OsRng;
+    if bits <= SMALL_BITS {
+        loop {
+            let candidate = rng.gen_range(1u64 << (bits - 1)..1u64 << bits) | 1;
+            if is_prime_by_trial_division(candidate) {
+                return Ok(PrimeCertificate {
+                    n: BigUint::from(candidate),
+                    proof: PrimeProof::TrialDivision,
+                });
+            }
+        }
+    }
+
+    // relative Größe r = log(q)/log(n) wie die des größten Primfaktors einer Zufallszahl
+    let relative = if bits <= 2 * MAURER_MARGIN {
+        0.5
+    } else {
+        loop {
+            let r = 2f64.powf(rng.gen::<f64>() - 1.0);
+            if bits as f64 * (1.0 - r) > MAURER_MARGIN as f64 {
+                break r;
+            }
+        }
+    };
+    // q > √n erzwingen, damit der Pocklington-Test genügt
+    let q_bits = ((relative * bits as f64) as usize + 1).max(bits.div_ceil(2) + 1);
+    let q_certificate = maurer_prime(q_bits)?;
+    let q = &q_certificate.n;
+
+    // n = 2·R·q + 1 mit R aus [I + 1, 2I], I = ⌊2^(bits−1) / 2q⌋
+    let i = (BigUint::one() << (bits - 1)) / (q << 1);
+    let two = BigUint::from(2u32);
+    loop {
+        let r: BigUint = rng.gen_biguint_range(&(&i + 1u32), &((&i << 1) + 1u32));
+        let n: BigUint = ((&r * q) << 1) + 1u32;
+        if n.bits() != bits as u64 || trial_division(&n) == Some(false) {
+            continue;
+        }
+        let a = rng.gen_biguint_range(&two, &(&n - 1u32));
+        if a.modpow(&(&n - 1u32), &n).is_one()
+            && (a.modpow(&(r << 1), &n) + &n - 1u32).gcd(&n).is_one()
+        {
+            return Ok(pocklington(n, q_certificate, a));
+        }
+    }
+}
+
+/// Ergebnis von [`shawe_taylor_prime`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaweTaylorPrime {
+    pub certificate: PrimeCertificate,
+    /// Fortgeschriebener Startwert (`prime_seed`), gleiche Länge wie der Eingabewert.
+    pub prime_seed: Vec<u8>,
+    /// Anzahl der geprüften Kandidaten (`prime_gen_counter`).
+    pub prime_gen_counter: u64,
+}
+
+/// Startwert als Zahl modulo 2^(8·Länge), wie in FIPS 186-5 fortgezählt.
+struct Seed {
+    value: BigUint,
+    len: usize,
+}
+
+impl Seed {
+    /// SHA-256 über `seed + offset` als Zahl.
+    fn hash(&self, offset: u64) -> BigUint {
+        let value = (&self.value + offset) % (BigUint::one() << (8 * self.len));
+        let bytes = value.to_bytes_be();
+        let mut padded = vec![0u8; self.len - bytes.len()];
+        padded.extend_from_slice(&bytes);
+        BigUint::from_bytes_be(&Sha256::digest(&padded))
+    }
+
+    /// Summe von Hash(seed + i)·2^(256·i) für i = 0 … iterations; rückt den Startwert weiter.
+    fn hash_sequence(&mut self, iterations: u64) -> BigUint {
+        let mut x = BigUint::default();
+        for i in 0..=iterations {
+            x += self.hash(i) << (256 * i as usize);
+        }
+        self.advance(iterations + 1);
+        x
+    }
+
+    fn advance(&mut self, by: u64) {
+        self.value = (&self.value + by) % (BigUint::one() << (8 * self.len));
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let bytes = self.value.to_bytes_be();
+        let mut padded = vec![0u8; self.len - bytes.len()];
+        padded.extend_from_slice(&bytes);
+        padded
+    }
+}
+
+/// Erzeugt deterministisch aus `seed` eine Primzahl mit genau `bits` Bit nach
+/// Shawe-Taylor (FIPS 186-5, Anhang B.10, mit SHA-256) samt Zertifikat.
+pub fn shawe_taylor_prime(bits: usize, seed: &[u8]) -> Result<ShaweTaylorPrime, PrimeError> {
+    if bits < 2 {
+        return Err(PrimeError::Generation("mindestens 2 Bit".into()));
+    }
+    if seed.is_empty() {
+        return Err(PrimeError::Generation("leerer Startwert".into()));
+    }
+    let mut seed = Seed {
+        value: BigUint::from_bytes_be(seed),
+        len: seed.len(),
+    };
+    let failed = || PrimeError::Generation(format!("Shawe-Taylor ohne Ergebnis für {} Bit", bits));
+    let top = BigUint::one() << (bits - 1);
+
+    if bits <= SMALL_BITS {
+        let mut counter = 0u64;
+        loop {
+            // Schritte 5–7
+            let c = seed.hash(0) ^ seed.hash(1);
+            let c = (&top + c % &top) | BigUint::one();
+            counter += 1;
+            seed.advance(2);
+            let small = c.to_u64().unwrap();
+            if is_prime_by_trial_division(small) {
+                return Ok(ShaweTaylorPrime {
+                    certificate: PrimeCertificate {
+                        n: c,
+                        proof: PrimeProof::TrialDivision,
+                    },
+                    prime_seed: seed.to_bytes(),
+                    prime_gen_counter: counter,
+                });
+            }
+            if counter > 4 * bits as u64 {
+                return Err(failed());
+            }
+        }
+    }
+
+    // Schritt 14: c0 mit ⌈bits/2⌉ + 1 Bit
+    let inner = shawe_taylor_prime(bits.div_ceil(2) + 1, &seed.to_bytes())?;
+    seed.value = BigUint::from_bytes_be(&inner.prime_seed);
+    let c0 = &inner.certificate.n;
+    let mut counter = inner.prime_gen_counter;
+    let old_counter = counter;
+
+    // Schritte 16–22
+    let iterations = bits.div_ceil(256) as u64 - 1;
+    let x = &top + seed.hash_sequence(iterations) % &top;
+    let two_c0 = c0 << 1;
+    let mut t = x.div_ceil(&two_c0);
+    loop {
+        // Schritte 23–25
+        if &t * &two_c0 + 1u32 > BigUint::one() << bits {
+            t = top.div_ceil(&two_c0);
+        }
+        let c = &t * &two_c0 + 1u32;
+        counter += 1;
+
+        // Schritte 26–31; Kandidaten mit kleinem Teiler bestehen den Test nie und werden
+        // ohne Potenzierung verworfen, der Startwert rückt trotzdem weiter
+        let a = BigUint::from(2u32) + seed.hash_sequence(iterations) % (&c - 3u32);
+        if trial_division(&c) != Some(false) {
+            let z = a.modpow(&(&t << 1), &c);
+            if (&z + &c - 1u32).gcd(&c).is_one() && z.modpow(c0, &c).is_one() {
+                return Ok(ShaweTaylorPrime {
+                    certificate: pocklington(c, inner.certificate, a),
+                    prime_seed: seed.to_bytes(),
+                    prime_gen_counter: counter,
+                });
+            }
+        }
+        if counter >= 4 * bits as u64 + old_counter {
+            return Err(failed());
+        }
+        t += 1u32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::primality::is_prime_baillie_psw;
+
+    #[test]
+    fn test_maurer() {
+        for bits in [2, 17, 33, 64, 256, 521] {
+            let certificate = maurer_prime(bits).unwrap();
+            assert_eq!(certificate.n.bits(), bits as u64);
+            assert!(is_prime_baillie_psw(&certificate.n));
+            certificate.verify().unwrap();
+        }
+        assert!(maurer_prime(1).is_err());
+    }
+
+    #[test]
+    fn test_shawe_taylor() {
+        let seed = [0x5au8; 32];
+        let first = shawe_taylor_prime(512, &seed).unwrap();
+        assert_eq!(first.certificate.n.bits(), 512);
+        first.certificate.verify().unwrap();
+        assert_ne!(first.prime_seed, seed);
+        // reproduzierbar aus dem Startwert, anderer Startwert ergibt andere Primzahl
+        assert_eq!(shawe_taylor_prime(512, &seed).unwrap(), first);
+        let other = shawe_taylor_prime(512, &[0x5bu8; 32]).unwrap();
+        assert_ne!(other.certificate.n, first.certificate.n);
+
+        for bits in [2, 20, 32, 33, 100] {
+            let prime = shawe_taylor_prime(bits, b"cryptomorph").unwrap();
+            assert_eq!(prime.certificate.n.bits(), bits as u64);
+            prime.certificate.verify().unwrap();
+        }
+        assert!(shawe_taylor_prime(64, &[]).is_err());
+    }
+}
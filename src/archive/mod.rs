@@ -0,0 +1,562 @@
+//! Verschlüsselte Verzeichnisarchive: tar-Strom durch die age-STREAM-Verschlüsselung.
+//!
+//! `encrypt_dir` packt ein Verzeichnis (Dateien, Unterverzeichnisse, symbolische Links mit
+//! Rechten und Änderungszeiten) als tar-Strom und verschlüsselt ihn blockweise, ohne das
+//! Archiv im Speicher zu halten. Das Ergebnis ist eine gewöhnliche age-Datei, also auch mit
+//! `age -d archiv.tar.age | tar x` lesbar.
+//!
+//! `decrypt_dir` entpackt nur in ein neues Verzeichnis und lehnt alles ab, was außerhalb
+//! landen könnte: absolute Pfade, `..`, Schreiben durch symbolische Links und Links, deren
+//! Ziel das Zielverzeichnis verlässt. Erst wenn der ganze Strom authentifiziert und entpackt
+//! ist, wird das Verzeichnis unter seinem Namen sichtbar.
+
+pub mod tar;
+
+use crate::age::{self, AgeError};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tar::{Archive, Builder, EntryHeader, EntryKind};
+
+/// Fehlerklassen beim Packen und Entpacken.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveError {
+    /// Das tar-Archiv ist beschädigt oder abgeschnitten.
+    Format(String),
+    /// Ein Eintrag würde außerhalb des Zielverzeichnisses schreiben.
+    Unsafe(String),
+    /// Eintragstyp wird nicht unterstützt (harte Links, Gerätedateien usw.).
+    Unsupported(String),
+    /// Fehler der age-Verschlüsselung.
+    Age(AgeError),
+    /// Ein- oder Ausgabefehler.
+    Io(String),
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveError::Format(msg) => write!(f, "Ungültiges Archiv: {}", msg),
+            ArchiveError::Unsafe(msg) => write!(f, "Unsicherer Archiveintrag: {}", msg),
+            ArchiveError::Unsupported(what) => write!(f, "Nicht unterstützt: {}", what),
+            ArchiveError::Age(e) => write!(f, "{}", e),
+            ArchiveError::Io(msg) => write!(f, "E/A-Fehler: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl From<AgeError> for ArchiveError {
+    fn from(e: AgeError) -> Self {
+        ArchiveError::Age(e)
+    }
+}
+
+fn io_err(path: &Path) -> impl Fn(io::Error) -> ArchiveError + '_ {
+    move |e| ArchiveError::Io(format!("{}: {}", path.display(), e))
+}
+
+/// Was gepackt bzw. entpackt wurde.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub files: usize,
+    pub directories: usize,
+    pub symlinks: usize,
+    /// Summe der Dateigrößen in Byte.
+    pub bytes: u64,
+    /// Beim Packen übergangene Einträge (Sockets, FIFOs, Gerätedateien).
+    pub skipped: Vec<PathBuf>,
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().replace('\\', "/").into_bytes()
+}
+
+#[cfg(unix)]
+fn bytes_path(bytes: &[u8]) -> Option<PathBuf> {
+    use std::os::unix::ffi::OsStrExt;
+    Some(PathBuf::from(std::ffi::OsStr::from_bytes(bytes)))
+}
+
+#[cfg(not(unix))]
+fn bytes_path(bytes: &[u8]) -> Option<PathBuf> {
+    std::str::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+/// Rechte, Eigentümer und Änderungszeit für den tar-Header.
+fn entry_header(meta: &fs::Metadata, path: Vec<u8>, kind: EntryKind) -> EntryHeader {
+    #[cfg(unix)]
+    let (mode, uid, gid) = {
+        use std::os::unix::fs::MetadataExt;
+        (meta.mode() & 0o7777, meta.uid() as u64, meta.gid() as u64)
+    };
+    #[cfg(not(unix))]
+    let (mode, uid, gid) = match kind {
+        EntryKind::Directory => (0o755, 0, 0),
+        _ if meta.permissions().readonly() => (0o444, 0, 0),
+        _ => (0o644, 0, 0),
+    };
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+    EntryHeader {
+        path,
+        kind,
+        mode,
+        uid,
+        gid,
+        mtime,
+        size: 0,
+        link_target: Vec::new(),
+    }
+}
+
+fn pack_dir<W: Write>(
+    root: &Path,
+    dir: &Path,
+    builder: &mut Builder<W>,
+    summary: &mut Summary,
+) -> Result<(), ArchiveError> {
+    let mut entries = fs::read_dir(dir)
+        .and_then(|entries| {
+            entries
+                .map(|e| e.map(|e| e.path()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(io_err(dir))?;
+    entries.sort();
+    for path in entries {
+        let meta = fs::symlink_metadata(&path).map_err(io_err(&path))?;
+        let name = path_bytes(
+            path.strip_prefix(root)
+                .expect("Pfad liegt unter der Wurzel"),
+        );
+        let file_type = meta.file_type();
+        if file_type.is_dir() {
+            let header = entry_header(&meta, name, EntryKind::Directory);
+            builder.append_header(&header).map_err(io_err(&path))?;
+            summary.directories += 1;
+            pack_dir(root, &path, builder, summary)?;
+        } else if file_type.is_file() {
+            let mut header = entry_header(&meta, name, EntryKind::File);
+            header.size = meta.len();
+            let file = File::open(&path).map_err(io_err(&path))?;
+            builder.append_header(&header).map_err(io_err(&path))?;
+            builder
+                .write_data(file, header.size)
+                .map_err(io_err(&path))?;
+            summary.files += 1;
+            summary.bytes += header.size;
+        } else if file_type.is_symlink() {
+            let mut header = entry_header(&meta, name, EntryKind::Symlink);
+            header.link_target = path_bytes(&fs::read_link(&path).map_err(io_err(&path))?);
+            builder.append_header(&header).map_err(io_err(&path))?;
+            summary.symlinks += 1;
+        } else {
+            summary.skipped.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Schreibt den Inhalt von `dir` als tar-Archiv nach `out`. Pfade sind relativ zu `dir`;
+/// symbolischen Links wird nicht gefolgt.
+pub fn pack<W: Write>(dir: &Path, out: W) -> Result<Summary, ArchiveError> {
+    if !fs::metadata(dir).map_err(io_err(dir))?.is_dir() {
+        return Err(ArchiveError::Io(format!(
+            "{}: kein Verzeichnis",
+            dir.display()
+        )));
+    }
+    let mut builder = Builder::new(out);
+    let mut summary = Summary::default();
+    pack_dir(dir, dir, &mut builder, &mut summary)?;
+    builder.finish().map_err(io_err(dir))?;
+    Ok(summary)
+}
+
+/// Zerlegt einen Archivpfad in sichere relative Komponenten.
+fn safe_components(path: &[u8]) -> Result<Vec<PathBuf>, ArchiveError> {
+    let shown = String::from_utf8_lossy(path);
+    if path.starts_with(b"/") {
+        return Err(ArchiveError::Unsafe(format!("absoluter Pfad {}", shown)));
+    }
+    let mut components = Vec::new();
+    for part in path.split(|&b| b == b'/') {
+        match part {
+            b"" | b"." => continue,
+            b".." => return Err(ArchiveError::Unsafe(format!("Pfad mit .. ({})", shown))),
+            _ => {}
+        }
+        let component = bytes_path(part)
+            .ok_or_else(|| ArchiveError::Unsupported(format!("Dateiname {}", shown)))?;
+        // fängt Laufwerksangaben und `\` unter Windows ab
+        let mut parsed = component.components();
+        if !matches!(
+            (parsed.next(), parsed.next()),
+            (Some(Component::Normal(_)), None)
+        ) {
+            return Err(ArchiveError::Unsafe(format!("Pfad {}", shown)));
+        }
+        components.push(component);
+    }
+    Ok(components)
+}
+
+/// Ein Linkziel darf nur relativ sein, `..` nur am Anfang enthalten und nicht über das
+/// Zielverzeichnis hinausführen. Da auch jeder andere Link diese Regel erfüllt und nie
+/// durch Links geschrieben wird, bleibt jede Auflösung innerhalb des Zielverzeichnisses.
+fn check_link_target(depth: usize, target: &[u8]) -> Result<(), ArchiveError> {
+    let shown = String::from_utf8_lossy(target);
+    if target.is_empty() || target.starts_with(b"/") || target.contains(&b'\\') {
+        return Err(ArchiveError::Unsafe(format!("Linkziel {}", shown)));
+    }
+    let mut ups = 0;
+    let mut descended = false;
+    for part in target.split(|&b| b == b'/') {
+        match part {
+            b"" | b"." => {}
+            b".." if !descended => ups += 1,
+            b".." => return Err(ArchiveError::Unsafe(format!("Linkziel {}", shown))),
+            _ => descended = true,
+        }
+    }
+    if ups > depth {
+        return Err(ArchiveError::Unsafe(format!(
+            "Linkziel {} führt aus dem Zielverzeichnis",
+            shown
+        )));
+    }
+    Ok(())
+}
+
+/// Legt die Elternverzeichnisse an; keines davon darf ein symbolischer Link sein.
+fn prepare_parents(dest: &Path, components: &[PathBuf]) -> Result<PathBuf, ArchiveError> {
+    let mut path = dest.to_path_buf();
+    for component in components {
+        path.push(component);
+        match fs::symlink_metadata(&path) {
+            Ok(meta) if meta.is_dir() => {}
+            Ok(_) => {
+                return Err(ArchiveError::Unsafe(format!(
+                    "{} ist kein Verzeichnis",
+                    path.display()
+                )))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                fs::create_dir(&path).map_err(io_err(&path))?
+            }
+            Err(e) => return Err(io_err(&path)(e)),
+        }
+    }
+    Ok(path)
+}
+
+fn system_time(mtime: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(mtime)
+}
+
+/// Nur Lese-, Schreib- und Ausführungsrechte; setuid, setgid und sticky werden verworfen.
+fn set_mode(file: &File, mode: u32) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(mode & 0o777))
+    }
+    #[cfg(not(unix))]
+    {
+        let mut permissions = file.metadata()?.permissions();
+        permissions.set_readonly(mode & 0o200 == 0);
+        file.set_permissions(permissions)
+    }
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &[u8], path: &Path) -> Result<(), ArchiveError> {
+    let target = bytes_path(target).expect("unter Unix ist jeder Pfad gültig");
+    std::os::unix::fs::symlink(target, path).map_err(io_err(path))
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_target: &[u8], _path: &Path) -> Result<(), ArchiveError> {
+    Err(ArchiveError::Unsupported(
+        "symbolische Links auf diesem System".to_string(),
+    ))
+}
+
+/// Entpackt ein tar-Archiv in das bestehende Verzeichnis `dest`.
+///
+/// Dateien werden nur neu angelegt, nie überschrieben. Rechte und Änderungszeiten von
+/// Verzeichnissen werden am Ende gesetzt, damit schreibgeschützte Verzeichnisse befüllt
+/// werden können. Eigentümer werden nicht übernommen, Änderungszeiten von Links ebenfalls
+/// nicht.
+pub fn unpack<R: Read>(input: R, dest: &Path) -> Result<Summary, ArchiveError> {
+    let mut archive = Archive::new(input);
+    let mut summary = Summary::default();
+    let mut directories = Vec::new();
+    while let Some(header) = archive.next_entry()? {
+        let components = safe_components(&header.path)?;
+        let Some((name, parents)) = components.split_last() else {
+            // Eintrag für das Wurzelverzeichnis selbst (`./`)
+            continue;
+        };
+        let path = prepare_parents(dest, parents)?.join(name);
+        match header.kind {
+            EntryKind::Directory => {
+                prepare_parents(dest, &components)?;
+                directories.push((path, header.mode, header.mtime));
+                summary.directories += 1;
+            }
+            EntryKind::File => {
+                let mut file = File::options()
+                    .write(true)
+                    .create_new(true)
+                    .open(&path)
+                    .map_err(io_err(&path))?;
+                archive.copy_data(&mut file, header.size)?;
+                file.set_modified(system_time(header.mtime))
+                    .and_then(|_| set_mode(&file, header.mode))
+                    .map_err(io_err(&path))?;
+                summary.files += 1;
+                summary.bytes += header.size;
+            }
+            EntryKind::Symlink => {
+                check_link_target(parents.len(), &header.link_target)?;
+                create_symlink(&header.link_target, &path)?;
+                summary.symlinks += 1;
+            }
+        }
+    }
+    archive.drain()?;
+    for (path, mode, mtime) in directories.iter().rev() {
+        File::open(path)
+            .and_then(|dir| {
+                dir.set_modified(system_time(*mtime))?;
+                set_mode(&dir, *mode)
+            })
+            .map_err(io_err(path))?;
+    }
+    Ok(summary)
+}
+
+/// Packt `dir` und verschlüsselt den tar-Strom für `recipients` im age-Format nach `output`.
+/// Packen und Verschlüsseln laufen nebenläufig über eine Pipe.
+pub fn encrypt_dir<W: Write>(
+    dir: &Path,
+    recipients: &[age::Recipient],
+    output: W,
+) -> Result<Summary, ArchiveError> {
+    let (reader, writer) = io::pipe().map_err(io_err(dir))?;
+    std::thread::scope(|scope| {
+        let packer = scope.spawn(move || pack(dir, BufWriter::new(writer)));
+        let encrypted = age::encrypt_stream(recipients, reader, output);
+        let packed = packer.join().expect("Packen abgebrochen");
+        // bricht die Verschlüsselung ab, endet das Packen mit einer geschlossenen Pipe
+        encrypted?;
+        packed
+    })
+}
+
+/// Entschlüsselt ein mit [`encrypt_dir`] erzeugtes Archiv und entpackt es nach `dest`, das
+/// noch nicht existieren darf. Entpackt wird zunächst in ein verstecktes Nachbarverzeichnis;
+/// schlägt etwas fehl, wird es wieder entfernt.
+pub fn decrypt_dir<R: BufRead + Send>(
+    input: R,
+    identities: &[age::Identity],
+    dest: &Path,
+) -> Result<Summary, ArchiveError> {
+    if fs::symlink_metadata(dest).is_ok() {
+        return Err(ArchiveError::Io(format!(
+            "{}: existiert bereits",
+            dest.display()
+        )));
+    }
+    let name = dest
+        .file_name()
+        .ok_or_else(|| ArchiveError::Io(format!("{}: ungültiges Ziel", dest.display())))?;
+    let staging = dest.with_file_name(format!(".{}.partial", name.to_string_lossy()));
+    fs::create_dir(&staging).map_err(io_err(&staging))?;
+
+    let (reader, writer) = io::pipe().map_err(io_err(dest))?;
+    let result = std::thread::scope(|scope| {
+        let decryptor = scope.spawn(move || age::decrypt_stream(identities, input, writer));
+        let unpacked = unpack(reader, &staging);
+        let decrypted = decryptor.join().expect("Entschlüsseln abgebrochen");
+        match (decrypted, unpacked) {
+            // E/A-Fehler beim Entschlüsseln sind meist die Folge eines Entpackfehlers
+            (Err(e), _) if !matches!(e, AgeError::Io(_)) => Err(ArchiveError::Age(e)),
+            (_, Err(e)) => Err(e),
+            (Err(e), Ok(_)) => Err(ArchiveError::Age(e)),
+            (Ok(()), Ok(summary)) => Ok(summary),
+        }
+    });
+    match result.and_then(|summary| {
+        fs::rename(&staging, dest).map_err(io_err(dest))?;
+        Ok(summary)
+    }) {
+        Ok(summary) => Ok(summary),
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "cryptomorph-archive-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn archive_with(entries: &[(&str, EntryKind, &str)]) -> Vec<u8> {
+        let mut builder = Builder::new(Vec::new());
+        for (path, kind, content) in entries {
+            let mut header = EntryHeader {
+                path: path.as_bytes().to_vec(),
+                kind: *kind,
+                mode: 0o644,
+                uid: 0,
+                gid: 0,
+                mtime: 0,
+                size: 0,
+                link_target: Vec::new(),
+            };
+            if *kind == EntryKind::Symlink {
+                header.link_target = content.as_bytes().to_vec();
+            } else if *kind == EntryKind::File {
+                header.size = content.len() as u64;
+            }
+            builder.append_header(&header).unwrap();
+            if *kind == EntryKind::File {
+                builder.write_data(content.as_bytes(), header.size).unwrap();
+            }
+        }
+        builder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_link_targets() {
+        for ok in ["datei", "./a/b", "../x", "../../a/b"] {
+            check_link_target(2, ok.as_bytes()).unwrap();
+        }
+        for bad in ["/etc/passwd", "../../../x", "a/../../x", "a/../b", ""] {
+            assert!(check_link_target(2, bad.as_bytes()).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_unpack_rejects_escapes() {
+        use EntryKind::*;
+        let cases: [&[(&str, EntryKind, &str)]; 5] = [
+            &[("../ausbruch", File, "x")],
+            &[("/tmp/ausbruch", File, "x")],
+            &[("a/../../ausbruch", File, "x")],
+            &[("link", Symlink, "../.."), ("link/ausbruch", File, "x")],
+            // Link auf ein Unterverzeichnis, dann Schreiben durch den Link
+            &[
+                ("sub/", Directory, ""),
+                ("l", Symlink, "sub"),
+                ("l/x", File, "x"),
+            ],
+        ];
+        for (i, entries) in cases.iter().enumerate() {
+            let dest = temp_dir(&format!("escape{}", i));
+            let result = unpack(&archive_with(entries)[..], &dest);
+            assert!(
+                matches!(result, Err(ArchiveError::Unsafe(_))),
+                "{}: {:?}",
+                i,
+                result
+            );
+            fs::remove_dir_all(&dest).unwrap();
+        }
+
+        // vorhandene Dateien werden nicht überschrieben, auch nicht über einen Link
+        let dest = temp_dir("overwrite");
+        let entries = [("a", File, "1"), ("l", Symlink, "a"), ("l", File, "2")];
+        assert!(unpack(&archive_with(&entries)[..], &dest).is_err());
+        assert_eq!(fs::read(dest.join("a")).unwrap(), b"1");
+        fs::remove_dir_all(&dest).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_encrypt_decrypt_dir_roundtrip() {
+        use crate::asymmetric::x25519::generate_x25519_keypair;
+        use std::os::unix::fs::PermissionsExt;
+
+        let src = temp_dir("src");
+        fs::create_dir_all(src.join("bin/leer")).unwrap();
+        fs::write(src.join("bin/start.sh"), "#!/bin/sh\n").unwrap();
+        fs::set_permissions(src.join("bin/start.sh"), fs::Permissions::from_mode(0o750)).unwrap();
+        let big: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+        fs::write(src.join("daten.bin"), &big).unwrap();
+        File::options()
+            .write(true)
+            .open(src.join("daten.bin"))
+            .unwrap()
+            .set_modified(system_time(1_600_000_000))
+            .unwrap();
+        std::os::unix::fs::symlink("bin/start.sh", src.join("start")).unwrap();
+
+        let (public, private) = generate_x25519_keypair();
+        let mut encrypted = Vec::new();
+        let packed = encrypt_dir(&src, &[age::Recipient::X25519(public)], &mut encrypted).unwrap();
+        assert_eq!(
+            (packed.files, packed.directories, packed.symlinks),
+            (2, 2, 1)
+        );
+
+        let dest = temp_dir("dest").join("ziel");
+        let identities = [age::Identity::X25519(private)];
+        let unpacked = decrypt_dir(&encrypted[..], &identities, &dest).unwrap();
+        assert_eq!(unpacked.bytes, packed.bytes);
+        assert_eq!(fs::read(dest.join("daten.bin")).unwrap(), big);
+        let meta = fs::metadata(dest.join("bin/start.sh")).unwrap();
+        assert_eq!(meta.permissions().mode() & 0o777, 0o750);
+        assert_eq!(
+            fs::metadata(dest.join("daten.bin"))
+                .unwrap()
+                .modified()
+                .unwrap(),
+            system_time(1_600_000_000)
+        );
+        assert_eq!(
+            fs::read_link(dest.join("start")).unwrap(),
+            Path::new("bin/start.sh")
+        );
+        assert!(dest.join("bin/leer").is_dir());
+
+        // Ziel existiert bereits; abgeschnittene Datei hinterlässt nichts
+        assert!(decrypt_dir(&encrypted[..], &identities, &dest).is_err());
+        let broken = dest.with_file_name("kaputt");
+        let truncated = &encrypted[..encrypted.len() - 100];
+        assert!(decrypt_dir(truncated, &identities, &broken).is_err());
+        assert!(!broken.exists());
+        assert!(!dest.with_file_name(".kaputt.partial").exists());
+
+        fs::remove_dir_all(&src).unwrap();
+        fs::remove_dir_all(dest.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,448 @@
+//! POSIX-tar (ustar mit pax-Erweiterungen), soweit es für Verzeichnisarchive nötig ist.
+//!
+//! Geschrieben werden reguläre Dateien, Verzeichnisse und symbolische Links. Namen über
+//! 100 Byte, lange Linkziele und Dateien ab 8 GiB stehen in einem pax-Header (`x`). Gelesen
+//! werden zusätzlich die GNU-Langnamen (`L`, `K`), globale pax-Header und Zahlen in der
+//! GNU-Binärdarstellung, sodass auch Archive von GNU tar und bsdtar lesbar sind.
+
+use crate::archive::ArchiveError;
+use std::io::{self, Read, Write};
+
+pub const BLOCK_SIZE: usize = 512;
+
+const NAME_LEN: usize = 100;
+/// Größtes Oktalfeld mit 11 Ziffern (8 GiB - 1).
+const MAX_OCTAL_SIZE: u64 = 0o77777777777;
+
+/// Art eines Archiveintrags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Directory,
+    Symlink,
+}
+
+impl EntryKind {
+    fn type_flag(self) -> u8 {
+        match self {
+            EntryKind::File => b'0',
+            EntryKind::Directory => b'5',
+            EntryKind::Symlink => b'2',
+        }
+    }
+}
+
+/// Kopf eines Archiveintrags; Pfade mit `/` als Trenner, ohne führendes `/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryHeader {
+    pub path: Vec<u8>,
+    pub kind: EntryKind,
+    /// Zugriffsrechte (untere 12 Bit).
+    pub mode: u32,
+    pub uid: u64,
+    pub gid: u64,
+    /// Änderungszeit in Sekunden seit 1970.
+    pub mtime: u64,
+    /// Größe der Nutzdaten (nur bei Dateien ungleich 0).
+    pub size: u64,
+    pub link_target: Vec<u8>,
+}
+
+fn format_err(msg: &str) -> ArchiveError {
+    ArchiveError::Format(msg.to_string())
+}
+
+/// Schreibt `value` als NUL-terminierte Oktalzahl in `field`.
+fn put_octal(field: &mut [u8], value: u64) {
+    let width = field.len() - 1;
+    let digits = format!("{:0width$o}", value, width = width);
+    field[..width].copy_from_slice(&digits.as_bytes()[digits.len() - width..]);
+    field[width] = 0;
+}
+
+/// Liest ein numerisches Feld: Oktal (mit Leerzeichen/NUL) oder GNU-Binär (erstes Bit gesetzt).
+fn parse_number(field: &[u8]) -> Result<u64, ArchiveError> {
+    if field[0] & 0x80 != 0 {
+        if field[0] != 0x80 || field[1..field.len() - 8].iter().any(|&b| b != 0) {
+            return Err(format_err("Binärzahl im tar-Header zu groß oder negativ"));
+        }
+        return Ok(u64::from_be_bytes(
+            field[field.len() - 8..].try_into().unwrap(),
+        ));
+    }
+    let text = field
+        .iter()
+        .take_while(|&&b| b != 0)
+        .map(|&b| b as char)
+        .collect::<String>();
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(text, 8).map_err(|_| format_err("ungültige Oktalzahl im tar-Header"))
+}
+
+fn field_bytes(field: &[u8]) -> &[u8] {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    &field[..end]
+}
+
+/// Prüfsumme: Summe aller Bytes, das Prüfsummenfeld als Leerzeichen gezählt.
+fn checksum(block: &[u8; BLOCK_SIZE]) -> u64 {
+    block
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| {
+            if (148..156).contains(&i) {
+                32
+            } else {
+                b as u64
+            }
+        })
+        .sum()
+}
+
+/// Ein pax-Datensatz: `<länge> <schlüssel>=<wert>\n`, die Länge zählt sich selbst mit.
+fn pax_record(key: &str, value: &[u8]) -> Vec<u8> {
+    let body_len = key.len() + value.len() + 3;
+    let mut len = body_len + 1;
+    while len.to_string().len() + body_len != len {
+        len = len.to_string().len() + body_len;
+    }
+    let mut out = format!("{} {}=", len, key).into_bytes();
+    out.extend_from_slice(value);
+    out.push(b'\n');
+    out
+}
+
+fn parse_pax(data: &[u8]) -> Result<Vec<(String, Vec<u8>)>, ArchiveError> {
+    let mut records = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        let space = rest
+            .iter()
+            .position(|&b| b == b' ')
+            .ok_or_else(|| format_err("ungültiger pax-Datensatz"))?;
+        let len: usize = std::str::from_utf8(&rest[..space])
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|&len| len > space + 1 && len <= rest.len())
+            .ok_or_else(|| format_err("ungültige Länge eines pax-Datensatzes"))?;
+        let record = &rest[space + 1..len];
+        let record = record
+            .strip_suffix(b"\n")
+            .ok_or_else(|| format_err("pax-Datensatz ohne Zeilenende"))?;
+        let eq = record
+            .iter()
+            .position(|&b| b == b'=')
+            .ok_or_else(|| format_err("pax-Datensatz ohne ="))?;
+        let key = String::from_utf8_lossy(&record[..eq]).into_owned();
+        records.push((key, record[eq + 1..].to_vec()));
+        rest = &rest[len..];
+    }
+    Ok(records)
+}
+
+fn padding(size: u64) -> usize {
+    (BLOCK_SIZE - (size % BLOCK_SIZE as u64) as usize) % BLOCK_SIZE
+}
+
+/// Schreibt ein tar-Archiv Eintrag für Eintrag.
+pub struct Builder<W: Write> {
+    out: W,
+}
+
+impl<W: Write> Builder<W> {
+    pub fn new(out: W) -> Self {
+        Builder { out }
+    }
+
+    fn write_block(&mut self, kind: u8, name: &[u8], header: &EntryHeader) -> io::Result<()> {
+        let mut block = [0u8; BLOCK_SIZE];
+        let name = &name[..name.len().min(NAME_LEN)];
+        block[..name.len()].copy_from_slice(name);
+        put_octal(&mut block[100..108], header.mode as u64 & 0o7777);
+        put_octal(&mut block[108..116], header.uid.min(0o7777777));
+        put_octal(&mut block[116..124], header.gid.min(0o7777777));
+        put_octal(&mut block[124..136], header.size.min(MAX_OCTAL_SIZE));
+        put_octal(&mut block[136..148], header.mtime.min(MAX_OCTAL_SIZE));
+        block[156] = kind;
+        if header.link_target.len() <= NAME_LEN {
+            block[157..157 + header.link_target.len()].copy_from_slice(&header.link_target);
+        }
+        block[257..263].copy_from_slice(b"ustar\0");
+        block[263..265].copy_from_slice(b"00");
+        let sum = checksum(&block);
+        block[148..156].copy_from_slice(format!("{:06o}\0 ", sum).as_bytes());
+        self.out.write_all(&block)
+    }
+
+    /// Schreibt den Kopf eines Eintrags; bei Dateien müssen danach genau `size` Byte
+    /// über [`Builder::write_data`] folgen.
+    pub fn append_header(&mut self, header: &EntryHeader) -> io::Result<()> {
+        let mut path = header.path.clone();
+        if header.kind == EntryKind::Directory && !path.ends_with(b"/") {
+            path.push(b'/');
+        }
+        let mut pax = Vec::new();
+        if path.len() > NAME_LEN {
+            pax.extend(pax_record("path", &path));
+        }
+        if header.link_target.len() > NAME_LEN {
+            pax.extend(pax_record("linkpath", &header.link_target));
+        }
+        if header.size > MAX_OCTAL_SIZE {
+            pax.extend(pax_record("size", header.size.to_string().as_bytes()));
+        }
+        if !pax.is_empty() {
+            let pax_header = EntryHeader {
+                size: pax.len() as u64,
+                link_target: Vec::new(),
+                ..header.clone()
+            };
+            self.write_block(b'x', b"././@PaxHeader", &pax_header)?;
+            self.out.write_all(&pax)?;
+            self.out
+                .write_all(&[0u8; BLOCK_SIZE][..padding(pax.len() as u64)])?;
+        }
+        self.write_block(header.kind.type_flag(), &path, header)
+    }
+
+    /// Kopiert die Nutzdaten einer Datei und füllt auf volle Blöcke auf.
+    pub fn write_data<R: Read>(&mut self, data: R, size: u64) -> io::Result<()> {
+        let copied = io::copy(&mut data.take(size), &mut self.out)?;
+        if copied != size {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Datei wurde während des Archivierens kürzer",
+            ));
+        }
+        self.out.write_all(&[0u8; BLOCK_SIZE][..padding(size)])
+    }
+
+    /// Schreibt die Endmarke (zwei leere Blöcke) und gibt den Ausgabestrom zurück.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.write_all(&[0u8; 2 * BLOCK_SIZE])?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Liest ein tar-Archiv Eintrag für Eintrag.
+pub struct Archive<R: Read> {
+    input: R,
+    /// Noch nicht gelesene Nutzdaten des aktuellen Eintrags samt Auffüllung.
+    pending: u64,
+}
+
+impl<R: Read> Archive<R> {
+    pub fn new(input: R) -> Self {
+        Archive { input, pending: 0 }
+    }
+
+    fn io_err(e: io::Error) -> ArchiveError {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            format_err("Archiv abgeschnitten")
+        } else {
+            ArchiveError::Io(e.to_string())
+        }
+    }
+
+    fn skip_pending(&mut self) -> Result<(), ArchiveError> {
+        let skipped = io::copy(&mut (&mut self.input).take(self.pending), &mut io::sink())
+            .map_err(Self::io_err)?;
+        if skipped != self.pending {
+            return Err(format_err("Archiv abgeschnitten"));
+        }
+        self.pending = 0;
+        Ok(())
+    }
+
+    fn read_block(&mut self) -> Result<[u8; BLOCK_SIZE], ArchiveError> {
+        let mut block = [0u8; BLOCK_SIZE];
+        self.input.read_exact(&mut block).map_err(Self::io_err)?;
+        Ok(block)
+    }
+
+    fn read_extension(&mut self, size: u64) -> Result<Vec<u8>, ArchiveError> {
+        if size > 1 << 20 {
+            return Err(format_err("Erweiterungsheader zu groß"));
+        }
+        let mut data = vec![0u8; size as usize];
+        self.input.read_exact(&mut data).map_err(Self::io_err)?;
+        let mut pad = [0u8; BLOCK_SIZE];
+        self.input
+            .read_exact(&mut pad[..padding(size)])
+            .map_err(Self::io_err)?;
+        Ok(data)
+    }
+
+    /// Liest den nächsten Eintragskopf; `None` an der Endmarke. Nicht gelesene Nutzdaten
+    /// des vorigen Eintrags werden übersprungen.
+    pub fn next_entry(&mut self) -> Result<Option<EntryHeader>, ArchiveError> {
+        self.skip_pending()?;
+        let mut long_path = None;
+        let mut long_link = None;
+        let mut pax_size = None;
+        loop {
+            let block = self.read_block()?;
+            if block.iter().all(|&b| b == 0) {
+                return Ok(None);
+            }
+            if parse_number(&block[148..156])? != checksum(&block) {
+                return Err(format_err("Prüfsumme eines tar-Headers stimmt nicht"));
+            }
+            let size = parse_number(&block[124..136])?;
+            let type_flag = block[156];
+            match type_flag {
+                b'x' | b'g' | b'L' | b'K' => {
+                    let data = self.read_extension(size)?;
+                    match type_flag {
+                        b'x' => {
+                            for (key, value) in parse_pax(&data)? {
+                                match key.as_str() {
+                                    "path" => long_path = Some(value),
+                                    "linkpath" => long_link = Some(value),
+                                    "size" => {
+                                        pax_size = std::str::from_utf8(&value)
+                                            .ok()
+                                            .and_then(|s| s.parse::<u64>().ok());
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+                        b'L' => long_path = Some(field_bytes(&data).to_vec()),
+                        b'K' => long_link = Some(field_bytes(&data).to_vec()),
+                        _ => {}
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            let kind = match type_flag {
+                b'0' | b'\0' | b'7' => EntryKind::File,
+                b'5' => EntryKind::Directory,
+                b'2' => EntryKind::Symlink,
+                b'1' => return Err(ArchiveError::Unsupported("harte Links".to_string())),
+                other => {
+                    return Err(ArchiveError::Unsupported(format!(
+                        "Eintragstyp {:?}",
+                        other as char
+                    )))
+                }
+            };
+            let path = long_path.unwrap_or_else(|| {
+                let name = field_bytes(&block[..100]);
+                let prefix = field_bytes(&block[345..500]);
+                if &block[257..262] == b"ustar" && !prefix.is_empty() {
+                    [prefix, b"/", name].concat()
+                } else {
+                    name.to_vec()
+                }
+            });
+            let size = pax_size.unwrap_or(size);
+            let header = EntryHeader {
+                path,
+                kind,
+                mode: parse_number(&block[100..108])? as u32 & 0o7777,
+                uid: parse_number(&block[108..116])?,
+                gid: parse_number(&block[116..124])?,
+                mtime: parse_number(&block[136..148])?,
+                size: if kind == EntryKind::File { size } else { 0 },
+                link_target: long_link.unwrap_or_else(|| field_bytes(&block[157..257]).to_vec()),
+            };
+            self.pending = header.size + padding(header.size) as u64;
+            return Ok(Some(header));
+        }
+    }
+
+    /// Kopiert die Nutzdaten des aktuellen Eintrags nach `out`.
+    pub fn copy_data<W: Write>(&mut self, out: &mut W, size: u64) -> Result<(), ArchiveError> {
+        let copied = io::copy(&mut (&mut self.input).take(size), out).map_err(Self::io_err)?;
+        if copied != size {
+            return Err(format_err("Archiv abgeschnitten"));
+        }
+        self.pending -= size;
+        Ok(())
+    }
+
+    /// Liest alles nach der Endmarke, damit ein vorgeschalteter Entschlüsseler bis zum
+    /// Ende authentifiziert.
+    pub fn drain(mut self) -> Result<(), ArchiveError> {
+        io::copy(&mut self.input, &mut io::sink()).map_err(Self::io_err)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(path: &str, kind: EntryKind, size: u64) -> EntryHeader {
+        EntryHeader {
+            path: path.as_bytes().to_vec(),
+            kind,
+            mode: 0o640,
+            uid: 1000,
+            gid: 1000,
+            mtime: 1_700_000_000,
+            size,
+            link_target: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_pax_record_length_counts_itself() {
+        assert_eq!(pax_record("path", b"abc"), b"12 path=abc\n");
+        // Beim Übergang auf drei Stellen gibt es keinen Datensatz der Länge 100
+        assert!(pax_record("path", &[b'a'; 90]).starts_with(b"99 path="));
+        let record = pax_record("path", &[b'a'; 91]);
+        assert_eq!(record.len(), 101);
+        assert!(record.starts_with(b"101 path="));
+        for len in 0..200 {
+            let record = pax_record("linkpath", &vec![b'x'; len]);
+            let parsed = parse_pax(&record).unwrap();
+            assert_eq!(parsed, [("linkpath".to_string(), vec![b'x'; len])]);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_long_names() {
+        let long = format!("{}/datei.txt", "unterordner".repeat(12));
+        let mut link = header("verweis", EntryKind::Symlink, 0);
+        link.link_target = long.clone().into_bytes();
+        let mut builder = Builder::new(Vec::new());
+        builder
+            .append_header(&header("ordner", EntryKind::Directory, 0))
+            .unwrap();
+        builder
+            .append_header(&header(&long, EntryKind::File, 5))
+            .unwrap();
+        builder.write_data(&b"Hallo"[..], 5).unwrap();
+        builder.append_header(&link).unwrap();
+        let data = builder.finish().unwrap();
+        assert_eq!(data.len() % BLOCK_SIZE, 0);
+
+        let mut archive = Archive::new(&data[..]);
+        let dir = archive.next_entry().unwrap().unwrap();
+        assert_eq!(dir.path, b"ordner/");
+        assert_eq!(dir.kind, EntryKind::Directory);
+        let file = archive.next_entry().unwrap().unwrap();
+        assert_eq!(file, header(&long, EntryKind::File, 5));
+        let mut content = Vec::new();
+        archive.copy_data(&mut content, file.size).unwrap();
+        assert_eq!(content, b"Hallo");
+        assert_eq!(archive.next_entry().unwrap().unwrap(), link);
+        assert_eq!(archive.next_entry().unwrap(), None);
+
+        // Abgeschnittene Archive und verfälschte Header fallen auf
+        let mut archive = Archive::new(&data[..1500]);
+        archive.next_entry().unwrap();
+        assert!(archive.next_entry().is_err());
+        let mut corrupt = data.clone();
+        corrupt[0] ^= 1;
+        assert!(Archive::new(&corrupt[..]).next_entry().is_err());
+    }
+}
@@ -0,0 +1,821 @@
+//! Befehlszeile von Cryptomorph: Unterbefehle nach Bereichen (`key`, `age`, `x509`, …) mit
+//! einheitlichen Optionen `--in`, `--out` und `--key`. `-` steht für stdin bzw. stdout.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(
+    name = "cryptomorph",
+    version,
+    about = "Cryptomorph – Hybrides Verschlüsselungstool",
+    after_help = "Schlüssel (--key, --recipient, --sender) sind Dateipfade oder Bezeichnung, \
+                  Fingerabdruck bzw. Schlüssel-ID eines Eintrags im Schlüsselspeicher."
+)]
+pub struct Cli {
+    /// Keine Statusmeldungen, nur Ergebnisse und Fehler
+    #[arg(short, long, global = true, conflicts_with = "json")]
+    pub quiet: bool,
+
+    /// Meldungen als JSON (ein Objekt pro Zeile), Fehler als {"error": …} auf stderr
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Binäre Ausgaben als ASCII-Armor; Eingaben werden automatisch erkannt
+    #[arg(short, long, global = true)]
+    pub armor: bool,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Eingabedatei, Standard stdin.
+#[derive(Args)]
+pub struct Input {
+    /// Eingabedatei (`-` für stdin)
+    #[arg(short, long = "in", value_name = "DATEI", default_value = "-")]
+    pub input: PathBuf,
+}
+
+/// Ein- und Ausgabedatei, Standard stdin/stdout.
+#[derive(Args)]
+pub struct InOut {
+    /// Eingabedatei (`-` für stdin)
+    #[arg(short, long = "in", value_name = "DATEI", default_value = "-")]
+    pub input: PathBuf,
+
+    /// Ausgabedatei (`-` für stdout)
+    #[arg(short, long, value_name = "DATEI", default_value = "-")]
+    pub out: PathBuf,
+}
+
+/// Ausgabeordner für Schlüssel- und Zertifikatsdateien.
+#[derive(Args)]
+pub struct OutDir {
+    /// Ausgabeordner (wird angelegt)
+    #[arg(short, long, value_name = "ORDNER")]
+    pub out: PathBuf,
+}
+
+/// Empfänger von `encrypt`, `seal` und `rekey`.
+#[derive(Args)]
+#[group(required = true, multiple = true)]
+pub struct Recipients {
+    /// Öffentlicher Schlüssel (RSA, X25519, ML-KEM) oder Eintrag im Schlüsselspeicher
+    #[arg(short, long, value_name = "SCHLÜSSEL")]
+    pub recipient: Vec<String>,
+
+    /// Datei mit einem Schlüsselpfad pro Zeile
+    #[arg(long, value_name = "DATEI")]
+    pub recipients_file: Vec<PathBuf>,
+}
+
+/// Empfänger im age-Format.
+#[derive(Args)]
+#[group(required = true, multiple = true)]
+pub struct AgeRecipients {
+    /// age1…-Empfänger oder X25519-Schlüsseldatei
+    #[arg(short, long, value_name = "EMPFÄNGER")]
+    pub recipient: Vec<String>,
+
+    /// Datei mit einem age1…-Empfänger pro Zeile
+    #[arg(long, value_name = "DATEI")]
+    pub recipients_file: Vec<PathBuf>,
+
+    /// Mit Passphrase (scrypt) aus der ersten Zeile der Datei verschlüsseln
+    #[arg(long, value_name = "DATEI")]
+    pub passphrase_file: Option<PathBuf>,
+}
+
+/// Identitäten zum Entschlüsseln im age-Format.
+#[derive(Args)]
+#[group(required = true, multiple = true)]
+pub struct AgeIdentities {
+    /// age-Identitätsdatei oder X25519-Schlüsseldatei
+    #[arg(long, value_name = "DATEI")]
+    pub identity: Vec<PathBuf>,
+
+    /// Passphrase aus der ersten Zeile der Datei
+    #[arg(long, value_name = "DATEI")]
+    pub passphrase_file: Option<PathBuf>,
+}
+
+/// Optionen für CA-Zertifikate.
+#[derive(Args)]
+pub struct CaArgs {
+    /// Als CA-Zertifikat ausstellen
+    #[arg(long)]
+    pub ca: bool,
+
+    /// Maximale Pfadlänge unter der CA (setzt --ca)
+    #[arg(long, value_name = "N")]
+    pub path_len: Option<u32>,
+}
+
+impl CaArgs {
+    /// `Some(pfadlänge)` für CA-Zertifikate, sonst `None`.
+    pub fn ca(&self) -> Option<Option<u32>> {
+        (self.ca || self.path_len.is_some()).then_some(self.path_len)
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Schlüssel erzeugen und im Schlüsselspeicher verwalten
+    #[command(subcommand)]
+    Key(KeyCommand),
+
+    /// Datei für mehrere Empfänger verschlüsseln (RSA/X25519/ML-KEM gemischt)
+    Encrypt {
+        #[command(flatten)]
+        io: InOut,
+        #[command(flatten)]
+        recipients: Recipients,
+    },
+
+    /// Mehrempfänger-Datei mit einem der privaten Schlüssel entschlüsseln
+    Decrypt {
+        #[command(flatten)]
+        io: InOut,
+        /// Privater Schlüssel (Datei oder Bezeichnung im Schlüsselspeicher)
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+    },
+
+    /// Nur den Dateischlüssel für neue Empfänger verpacken (encrypt- und rsa-Dateien, rekursiv)
+    Rekey {
+        /// Dateien oder Verzeichnisse
+        #[arg(required = true, value_name = "PFAD")]
+        paths: Vec<PathBuf>,
+        /// Privater Schlüssel, mit dem die Dateien bisher geöffnet werden
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+        #[command(flatten)]
+        recipients: Recipients,
+        /// Nur berichten, nichts verändern
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Mit dem RSA-Schlüssel des Absenders signieren und für die Empfänger verschlüsseln
+    Seal {
+        #[command(flatten)]
+        io: InOut,
+        /// Privater RSA-Schlüssel des Absenders
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+        #[command(flatten)]
+        recipients: Recipients,
+    },
+
+    /// Versiegelte Datei entschlüsseln und Absender sowie Empfängerbindung prüfen
+    Open {
+        #[command(flatten)]
+        io: InOut,
+        /// Privater Schlüssel (Datei oder Bezeichnung im Schlüsselspeicher)
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+        /// Öffentlicher RSA-Schlüssel des Absenders
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        sender: String,
+    },
+
+    /// Datei mit RSA- oder Ed25519-Schlüssel signieren (Unterzeichner, Zeitpunkt, Kommentar)
+    Sign {
+        #[command(flatten)]
+        io: InOut,
+        /// Privater Schlüssel (Datei oder Bezeichnung im Schlüsselspeicher)
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+        /// Kommentar, wird mitsigniert
+        #[arg(long, value_name = "TEXT")]
+        comment: Option<String>,
+        /// Text mit eingebetteter Signatur ausgeben statt abgesetzter Signatur
+        #[arg(long)]
+        clearsign: bool,
+        /// Hashfunktion über die Nachricht
+        #[arg(long, value_enum, default_value_t = SignatureHash::Sha256)]
+        hash: SignatureHash,
+    },
+
+    /// Signatur prüfen; Exit-Code 1, wenn sie ungültig ist
+    Verify {
+        #[command(flatten)]
+        input: Input,
+        /// Abgesetzte Signatur; ohne diese Option wird eine signierte Textnachricht erwartet
+        #[arg(short, long, value_name = "DATEI")]
+        sig: Option<PathBuf>,
+        /// Öffentlicher Schlüssel; ohne Angabe wird der Unterzeichner im Schlüsselspeicher gesucht
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: Option<String>,
+        /// Geprüften Text einer signierten Textnachricht schreiben (`-` für stdout)
+        #[arg(short, long, value_name = "DATEI", conflicts_with = "sig")]
+        out: Option<PathBuf>,
+    },
+
+    /// Dateien im age-v1-Format (kompatibel zu age/rage)
+    #[command(subcommand)]
+    Age(AgeCommand),
+
+    /// Verzeichnis als tar (Rechte, Zeiten, Links) packen und im age-Format verschlüsseln
+    EncryptDir {
+        /// Zu verschlüsselndes Verzeichnis
+        #[arg(value_name = "VERZEICHNIS")]
+        dir: PathBuf,
+        /// Ausgabedatei (`-` für stdout)
+        #[arg(short, long, value_name = "DATEI", default_value = "-")]
+        out: PathBuf,
+        #[command(flatten)]
+        recipients: AgeRecipients,
+    },
+
+    /// Verzeichnis entschlüsseln und in ein neues Verzeichnis entpacken
+    DecryptDir {
+        #[command(flatten)]
+        input: Input,
+        /// Zielverzeichnis, darf noch nicht existieren
+        #[arg(value_name = "ZIEL")]
+        dest: PathBuf,
+        #[command(flatten)]
+        identities: AgeIdentities,
+    },
+
+    /// OpenPGP-Schlüssel, -Nachrichten und -Signaturen (RFC 9580)
+    #[command(subcommand)]
+    Pgp(PgpCommand),
+
+    /// X.509-Zertifikate und Zertifikatsanforderungen
+    #[command(subcommand)]
+    X509(X509Command),
+
+    /// CMS-Signaturen und -Verschlüsselung
+    #[command(subcommand)]
+    Cms(CmsCommand),
+
+    /// PKCS#12-Dateien (.p12/.pfx)
+    #[command(subcommand)]
+    P12(P12Command),
+
+    /// OpenSSH-Schlüssel und -Signaturen
+    #[command(subcommand)]
+    Ssh(SshCommand),
+
+    /// Einzel-Empfänger-Verschlüsselung mit RSA
+    #[command(subcommand)]
+    Rsa(RsaCommand),
+
+    /// Symmetrische Verschlüsselung mit AES-256
+    #[command(subcommand)]
+    Aes(AesCommand),
+
+    /// Primzahlen beweisen, Primzahlzertifikate prüfen und beweisbare Primzahlen erzeugen
+    #[command(subcommand)]
+    Prime(PrimeCommand),
+
+    /// Zahl in Primfaktoren zerlegen (mit φ, λ und Teilerfunktionen)
+    Factor {
+        /// Zahl, dezimal oder hexadezimal mit `0x`
+        number: String,
+    },
+
+    /// Shell-Vervollständigung nach stdout schreiben
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+
+    /// Man-Pages für alle Befehle erzeugen
+    Manpages {
+        #[command(flatten)]
+        out: OutDir,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SignatureHash {
+    Sha256,
+    Sha512,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum KeyAlgorithm {
+    Rsa,
+    X25519,
+    Ed25519,
+    Mlkem,
+}
+
+#[derive(Subcommand)]
+pub enum KeyCommand {
+    /// Schlüsselpaar erzeugen, im Schlüsselspeicher (--label) oder als Dateien (--out)
+    Generate {
+        #[arg(value_enum)]
+        algorithm: KeyAlgorithm,
+        /// Bezeichnung im Schlüsselspeicher
+        #[arg(short, long, required_unless_present = "out", conflicts_with = "out")]
+        label: Option<String>,
+        /// Ausgabeordner für die Schlüsseldateien
+        #[arg(short, long, value_name = "ORDNER")]
+        out: Option<PathBuf>,
+        /// Bitlänge bei RSA
+        #[arg(long, default_value_t = 3072)]
+        bits: usize,
+        /// Öffentlicher Exponent bei RSA (dezimal oder 0x…, ungerade)
+        #[arg(long = "e", value_name = "E", default_value = "65537")]
+        exponent: String,
+        /// RSA-Schlüssel nach FIPS 186-5 erzeugen (gerade Bitlänge ab 2048)
+        #[arg(long)]
+        fips: bool,
+    },
+
+    /// Schlüsseldatei aufnehmen (bei privaten Schlüsseln samt öffentlichem)
+    Import {
+        /// Schlüsseldatei
+        #[arg(value_name = "DATEI")]
+        file: PathBuf,
+        /// Bezeichnung im Schlüsselspeicher
+        #[arg(short, long)]
+        label: Option<String>,
+    },
+
+    /// Schlüssel-ID, Typ und Bezeichnungen auflisten
+    List {
+        /// Zusätzlich Fingerabdruck und Pfad
+        #[arg(short, long)]
+        long: bool,
+    },
+
+    /// Weitere Bezeichnung vergeben
+    Label {
+        /// Bezeichnung, SHA-256-Fingerabdruck oder Schlüssel-ID
+        #[arg(value_name = "SCHLÜSSEL")]
+        query: String,
+        label: String,
+    },
+
+    /// Schlüsseldateien aus dem Speicher kopieren
+    Export {
+        /// Bezeichnung, SHA-256-Fingerabdruck oder Schlüssel-ID
+        #[arg(value_name = "SCHLÜSSEL")]
+        query: String,
+        #[command(flatten)]
+        out: OutDir,
+        /// Auch den privaten Schlüssel kopieren
+        #[arg(long)]
+        private: bool,
+    },
+
+    /// Schlüssel entfernen (mit privatem Teil nur mit --force)
+    Delete {
+        /// Bezeichnung, SHA-256-Fingerabdruck oder Schlüssel-ID
+        #[arg(value_name = "SCHLÜSSEL")]
+        query: String,
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AgeCommand {
+    /// age-Identität (X25519) erzeugen und den age1…-Empfänger anzeigen
+    Keygen {
+        /// Identitätsdatei (`-` für stdout)
+        #[arg(short, long, value_name = "DATEI", default_value = "-")]
+        out: PathBuf,
+    },
+
+    /// Datei verschlüsseln (mit --armor als ASCII-Armor)
+    Encrypt {
+        #[command(flatten)]
+        io: InOut,
+        #[command(flatten)]
+        recipients: AgeRecipients,
+    },
+
+    /// Datei entschlüsseln (binär oder ASCII-Armor)
+    Decrypt {
+        #[command(flatten)]
+        io: InOut,
+        #[command(flatten)]
+        identities: AgeIdentities,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PgpCommand {
+    /// OpenPGP-Zertifikat (pgp_public.asc) und privaten Schlüssel (pgp_secret.asc) erstellen
+    ExportKey {
+        /// RSA- oder Ed25519-Schlüssel
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+        /// User-ID, z. B. "Alice <alice@example.org>"
+        #[arg(short, long)]
+        user_id: String,
+        /// X25519-Schlüssel als Verschlüsselungs-Unterschlüssel (nur bei Ed25519)
+        #[arg(long, value_name = "SCHLÜSSEL")]
+        subkey: Option<String>,
+        #[command(flatten)]
+        out: OutDir,
+    },
+
+    /// OpenPGP-Schlüssel importieren und prüfen (z. B. aus gpg --export)
+    Import {
+        #[command(flatten)]
+        input: Input,
+        #[command(flatten)]
+        out: OutDir,
+        /// Passphrase des privaten Schlüssels aus der ersten Zeile der Datei
+        #[arg(long, value_name = "DATEI")]
+        passphrase_file: Option<PathBuf>,
+    },
+
+    /// OpenPGP-Nachricht entschlüsseln
+    Decrypt {
+        #[command(flatten)]
+        io: InOut,
+        /// Privater OpenPGP-Schlüssel (pgp_secret.asc)
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+    },
+
+    /// Abgesetzte Signatur erstellen (prüfbar mit gpg --verify)
+    Sign {
+        #[command(flatten)]
+        io: InOut,
+        /// Privater OpenPGP-Schlüssel (pgp_secret.asc)
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+    },
+
+    /// Abgesetzte Signatur prüfen
+    Verify {
+        #[command(flatten)]
+        input: Input,
+        /// Signaturdatei
+        #[arg(short, long, value_name = "DATEI")]
+        sig: PathBuf,
+        /// OpenPGP-Zertifikat des Unterzeichners
+        #[arg(short, long, value_name = "DATEI")]
+        cert: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum X509Command {
+    /// Selbstsigniertes Zertifikat erstellen (z. B. Wurzel-CA mit --ca)
+    SelfSigned {
+        /// RSA- oder Ed25519-Schlüssel
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+        /// Inhaber, z. B. "CN=server.intern, O=Firma, C=DE"
+        #[arg(short, long)]
+        subject: String,
+        /// Gültigkeit in Tagen
+        #[arg(short, long, default_value_t = 365)]
+        days: u32,
+        /// Alternativer Name: dns:…, ip:…, email:…, uri:…
+        #[arg(long, value_parser = parse_general_name)]
+        san: Vec<cryptomorph::x509::GeneralName>,
+        #[command(flatten)]
+        ca: CaArgs,
+        /// Ausgabedatei (`-` für stdout)
+        #[arg(short, long, value_name = "DATEI", default_value = "-")]
+        out: PathBuf,
+    },
+
+    /// PKCS#10-Zertifikatsanforderung erstellen
+    Csr {
+        /// RSA- oder Ed25519-Schlüssel
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+        /// Inhaber, z. B. "CN=server.intern, O=Firma, C=DE"
+        #[arg(short, long)]
+        subject: String,
+        /// Alternativer Name: dns:…, ip:…, email:…, uri:…
+        #[arg(long, value_parser = parse_general_name)]
+        san: Vec<cryptomorph::x509::GeneralName>,
+        /// Ausgabedatei (`-` für stdout)
+        #[arg(short, long, value_name = "DATEI", default_value = "-")]
+        out: PathBuf,
+    },
+
+    /// Zertifikat für eine Anforderung mit einer CA ausstellen
+    SignCsr {
+        #[command(flatten)]
+        io: InOut,
+        /// Zertifikat der CA
+        #[arg(long, value_name = "DATEI")]
+        ca_cert: PathBuf,
+        /// Privater Schlüssel der CA
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+        /// Gültigkeit in Tagen
+        #[arg(short, long, default_value_t = 365)]
+        days: u32,
+        #[command(flatten)]
+        ca: CaArgs,
+    },
+
+    /// Zertifikat oder Anforderung anzeigen
+    Show {
+        #[command(flatten)]
+        input: Input,
+    },
+
+    /// Zertifikatspfad, Gültigkeit und optional den Hostnamen prüfen
+    Verify {
+        #[command(flatten)]
+        input: Input,
+        /// Vertrauensspeicher: PEM-Datei oder Ordner mit .pem/.crt-Dateien
+        #[arg(short, long, value_name = "PFAD")]
+        trust: PathBuf,
+        /// Zwischenzertifikate
+        #[arg(long, value_name = "DATEI")]
+        intermediate: Vec<PathBuf>,
+        /// Erwarteter Hostname
+        #[arg(long)]
+        host: Option<String>,
+    },
+}
+
+fn parse_general_name(value: &str) -> Result<cryptomorph::x509::GeneralName, String> {
+    cryptomorph::x509::GeneralName::parse(value).map_err(|e| e.to_string())
+}
+
+#[derive(Subcommand)]
+pub enum CmsCommand {
+    /// Als CMS SignedData signieren (DER, prüfbar mit openssl cms -verify)
+    Sign {
+        #[command(flatten)]
+        io: InOut,
+        /// Zertifikat des Unterzeichners
+        #[arg(short, long, value_name = "DATEI")]
+        cert: PathBuf,
+        /// Privater Schlüssel des Unterzeichners
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+        /// Weitere Zertifikate für die Kette
+        #[arg(long, value_name = "DATEI")]
+        chain: Vec<PathBuf>,
+        /// Inhalt nicht einbetten
+        #[arg(long)]
+        detached: bool,
+    },
+
+    /// CMS-Signatur und Zertifikatspfad prüfen
+    Verify {
+        #[command(flatten)]
+        input: Input,
+        /// Vertrauensspeicher: PEM-Datei oder Ordner mit .pem/.crt-Dateien
+        #[arg(short, long, value_name = "PFAD")]
+        trust: PathBuf,
+        /// Inhalt bei abgesetzter Signatur
+        #[arg(long, value_name = "DATEI")]
+        content: Option<PathBuf>,
+        /// Eingebetteten Inhalt speichern (`-` für stdout)
+        #[arg(short, long, value_name = "DATEI")]
+        out: Option<PathBuf>,
+    },
+
+    /// Als CMS verschlüsseln (AES-256-GCM, RSA-OAEP) für Empfängerzertifikate
+    Encrypt {
+        #[command(flatten)]
+        io: InOut,
+        /// Zertifikat eines Empfängers
+        #[arg(short, long, value_name = "DATEI", required = true)]
+        recipient: Vec<PathBuf>,
+    },
+
+    /// CMS-Datei entschlüsseln
+    Decrypt {
+        #[command(flatten)]
+        io: InOut,
+        /// Zertifikat des Empfängers
+        #[arg(short, long, value_name = "DATEI")]
+        cert: PathBuf,
+        /// Privater RSA-Schlüssel des Empfängers
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum P12Command {
+    /// Schlüssel (RSA/Ed25519), Zertifikat (cert.pem) und Kette (chain.pem) importieren
+    Import {
+        #[command(flatten)]
+        input: Input,
+        #[command(flatten)]
+        out: OutDir,
+        /// Passwort aus der ersten Zeile der Datei
+        #[arg(long, value_name = "DATEI")]
+        passphrase_file: Option<PathBuf>,
+    },
+
+    /// Schlüssel und Zertifikate als PKCS#12 exportieren (für Browser und Java-Keystores)
+    Export {
+        /// Privater Schlüssel (Datei oder Bezeichnung im Schlüsselspeicher)
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+        /// Zertifikat zum Schlüssel
+        #[arg(short, long, value_name = "DATEI")]
+        cert: PathBuf,
+        /// Weitere Zertifikate für die Kette
+        #[arg(long, value_name = "DATEI")]
+        chain: Vec<PathBuf>,
+        /// Anzeigename (Standard: Common Name des Zertifikats)
+        #[arg(long)]
+        name: Option<String>,
+        /// Passwort aus der ersten Zeile der Datei
+        #[arg(long, value_name = "DATEI")]
+        passphrase_file: PathBuf,
+        /// Ausgabedatei (`-` für stdout)
+        #[arg(short, long, value_name = "DATEI", default_value = "-")]
+        out: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SshCommand {
+    /// OpenSSH-Schlüssel (openssh-key-v1, auch verschlüsselt) importieren
+    Import {
+        #[command(flatten)]
+        input: Input,
+        #[command(flatten)]
+        out: OutDir,
+        /// Passphrase aus der ersten Zeile der Datei
+        #[arg(long, value_name = "DATEI")]
+        passphrase_file: Option<PathBuf>,
+    },
+
+    /// id_rsa/id_ed25519 und .pub für OpenSSH schreiben
+    Export {
+        /// Privater Schlüssel (Datei oder Bezeichnung im Schlüsselspeicher)
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+        #[command(flatten)]
+        out: OutDir,
+        /// Kommentar im öffentlichen Schlüssel
+        #[arg(long)]
+        comment: Option<String>,
+        /// Privaten Schlüssel mit der Passphrase aus der ersten Zeile der Datei verschlüsseln
+        #[arg(long, value_name = "DATEI")]
+        passphrase_file: Option<PathBuf>,
+    },
+
+    /// Wie ssh-keygen -Y sign signieren
+    Sign {
+        #[command(flatten)]
+        io: InOut,
+        /// Privater Schlüssel (Cryptomorph oder id_*)
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+        /// Namensraum, z. B. file oder git
+        #[arg(short, long)]
+        namespace: String,
+        /// Passphrase aus der ersten Zeile der Datei
+        #[arg(long, value_name = "DATEI")]
+        passphrase_file: Option<PathBuf>,
+    },
+
+    /// Wie ssh-keygen -Y verify gegen erlaubte Unterzeichner prüfen
+    Verify {
+        #[command(flatten)]
+        input: Input,
+        /// Signaturdatei
+        #[arg(short, long, value_name = "DATEI")]
+        sig: PathBuf,
+        /// Datei mit erlaubten Unterzeichnern
+        #[arg(long, value_name = "DATEI")]
+        allowed_signers: PathBuf,
+        /// Prinzipal des Unterzeichners
+        #[arg(short, long)]
+        principal: String,
+        /// Namensraum, z. B. file oder git
+        #[arg(short, long)]
+        namespace: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RsaCommand {
+    /// Mit AES + RSA (hybrid) verschlüsseln
+    Encrypt {
+        #[command(flatten)]
+        io: InOut,
+        /// Öffentlicher RSA-Schlüssel
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+    },
+
+    /// Mit RSA → AES entschlüsseln
+    Decrypt {
+        #[command(flatten)]
+        io: InOut,
+        /// Privater RSA-Schlüssel
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+    },
+
+    /// RSA-Schlüssel auf FIPS 186-5 prüfen (Schlüsseldatei, OpenSSH oder Zertifikat)
+    Validate {
+        /// Öffentlicher oder privater RSA-Schlüssel
+        #[arg(short, long, value_name = "SCHLÜSSEL")]
+        key: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AesCommand {
+    /// Direkt mit AES-256 verschlüsseln
+    Encrypt {
+        #[command(flatten)]
+        io: InOut,
+        /// Schlüssel als 64 Hex-Zeichen
+        #[arg(short, long, value_name = "HEX", alias = "hex-key")]
+        key: String,
+    },
+
+    /// AES-Datei entschlüsseln
+    Decrypt {
+        #[command(flatten)]
+        io: InOut,
+        /// Schlüssel als 64 Hex-Zeichen
+        #[arg(short, long, value_name = "HEX", alias = "hex-key")]
+        key: String,
+    },
+
+    /// Zufälligen 256-Bit-Schlüssel (Hex) erzeugen
+    Keygen,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ProvablePrimeMethod {
+    Maurer,
+    ShaweTaylor,
+}
+
+#[derive(Subcommand)]
+pub enum PrimeCommand {
+    /// Primzahlzertifikat (Pratt/Pocklington) für eine Zahl erstellen
+    Prove {
+        /// Zahl, dezimal oder hexadezimal mit `0x`
+        number: String,
+        /// Zertifikatsdatei (`-` für stdout)
+        #[arg(short, long, value_name = "DATEI", default_value = "-")]
+        out: PathBuf,
+    },
+
+    /// Primzahlzertifikat prüfen
+    Check {
+        #[command(flatten)]
+        input: Input,
+    },
+
+    /// Beweisbare Primzahl erzeugen
+    Generate {
+        /// Bitlänge
+        #[arg(long, value_name = "N")]
+        bits: usize,
+        /// Verfahren
+        #[arg(long, value_enum, default_value = "maurer")]
+        method: ProvablePrimeMethod,
+        /// Startwert für Shawe-Taylor in Hex (Standard: zufällig)
+        #[arg(long, value_name = "HEX")]
+        seed: Option<String>,
+        /// Zertifikat in diese Datei schreiben
+        #[arg(long, value_name = "DATEI")]
+        cert: Option<PathBuf>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+        let cli = Cli::try_parse_from([
+            "cryptomorph",
+            "encrypt",
+            "--in",
+            "brief.txt",
+            "-r",
+            "alice",
+            "--json",
+        ])
+        .unwrap();
+        assert!(cli.json);
+        let Command::Encrypt { io, recipients } = cli.command else {
+            panic!("falscher Befehl");
+        };
+        assert_eq!(io.input, PathBuf::from("brief.txt"));
+        assert_eq!(io.out, PathBuf::from("-"));
+        assert_eq!(recipients.recipient, ["alice"]);
+        // ohne Empfänger und mit --quiet und --json zusammen abgelehnt
+        assert!(Cli::try_parse_from(["cryptomorph", "encrypt"]).is_err());
+        assert!(Cli::try_parse_from(["cryptomorph", "-q", "--json", "key", "list"]).is_err());
+    }
+}
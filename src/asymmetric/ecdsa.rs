@@ -0,0 +1,90 @@
+use p256::ecdsa::signature::{Signer, Verifier};
+use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use p256::EncodedPoint;
+use rand::rngs::OsRng;
+
+/// Öffentlicher P-256-Schlüssel als affine Koordinaten (je 32 Byte, Big-Endian).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    pub x: [u8; 32],
+    pub y: [u8; 32],
+}
+
+/// Privater P-256-Schlüssel (Skalar, 32 Byte Big-Endian).
+#[derive(Clone)]
+pub struct PrivateKey {
+    pub bytes: [u8; 32],
+}
+
+/// Generiert ein ECDSA-Schlüsselpaar auf der Kurve P-256 (secp256r1).
+pub fn generate_p256_keypair() -> (PublicKey, PrivateKey) {
+    let signing = SigningKey::random(&mut OsRng);
+    let priv_key = PrivateKey {
+        bytes: signing.to_bytes().into(),
+    };
+    let pub_key = public_key_from_private(&priv_key).expect("gültiger Skalar");
+    (pub_key, priv_key)
+}
+
+/// Leitet den öffentlichen Schlüssel ab; None, wenn der Skalar 0 oder ≥ n ist.
+pub fn public_key_from_private(priv_key: &PrivateKey) -> Option<PublicKey> {
+    let signing = SigningKey::from_bytes(&priv_key.bytes.into()).ok()?;
+    let point = signing.verifying_key().to_encoded_point(false);
+    Some(PublicKey {
+        x: (*point.x()?).into(),
+        y: (*point.y()?).into(),
+    })
+}
+
+/// Prüft, ob die Koordinaten einen Punkt auf der Kurve beschreiben.
+pub fn is_valid_public_key(pub_key: &PublicKey) -> bool {
+    verifying_key(pub_key).is_some()
+}
+
+fn verifying_key(pub_key: &PublicKey) -> Option<VerifyingKey> {
+    let point = EncodedPoint::from_affine_coordinates(&pub_key.x.into(), &pub_key.y.into(), false);
+    VerifyingKey::from_encoded_point(&point).ok()
+}
+
+/// ECDSA-Signatur mit SHA-256 (deterministisches k nach RFC 6979).
+///
+/// Ergebnis ist r || s mit je 32 Byte, wie von JWS (ES256) verlangt.
+pub fn sign_ecdsa_p256(message: &[u8], priv_key: &PrivateKey) -> [u8; 64] {
+    let signing = SigningKey::from_bytes(&priv_key.bytes.into()).expect("ungültiger P-256-Skalar");
+    let signature: Signature = signing.sign(message);
+    signature.to_bytes().into()
+}
+
+/// Prüft eine ECDSA-P-256-Signatur (r || s) über SHA-256.
+pub fn verify_ecdsa_p256(message: &[u8], signature: &[u8], pub_key: &PublicKey) -> bool {
+    let Ok(signature) = Signature::from_slice(signature) else {
+        return false;
+    };
+    verifying_key(pub_key)
+        .map(|key| key.verify(message, &signature).is_ok())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_verify() {
+        let (pub_key, priv_key) = generate_p256_keypair();
+        let sig = sign_ecdsa_p256(b"Nachricht", &priv_key);
+        assert!(verify_ecdsa_p256(b"Nachricht", &sig, &pub_key));
+        assert!(!verify_ecdsa_p256(b"Manipuliert", &sig, &pub_key));
+        assert_eq!(sign_ecdsa_p256(b"Nachricht", &priv_key), sig);
+    }
+
+    #[test]
+    fn test_invalid_point_rejected() {
+        let (mut pub_key, priv_key) = generate_p256_keypair();
+        let sig = sign_ecdsa_p256(b"Nachricht", &priv_key);
+        pub_key.y[31] ^= 1;
+        assert!(!is_valid_public_key(&pub_key));
+        assert!(!verify_ecdsa_p256(b"Nachricht", &sig, &pub_key));
+        assert!(public_key_from_private(&PrivateKey { bytes: [0; 32] }).is_none());
+    }
+}
@@ -0,0 +1,82 @@
+use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+use rand::rngs::OsRng;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    pub bytes: [u8; 32],
+}
+
+/// Privater Schlüssel als 32-Byte-Seed (RFC 8032).
+#[derive(Clone)]
+pub struct PrivateKey {
+    pub bytes: [u8; 32],
+}
+
+/// Generiert ein Ed25519-Schlüsselpaar (RFC 8032).
+pub fn generate_ed25519_keypair() -> (PublicKey, PrivateKey) {
+    let signing = SigningKey::generate(&mut OsRng);
+    let priv_key = PrivateKey {
+        bytes: signing.to_bytes(),
+    };
+    (public_key_from_private(&priv_key), priv_key)
+}
+
+/// Leitet den öffentlichen Schlüssel aus dem Seed ab.
+pub fn public_key_from_private(priv_key: &PrivateKey) -> PublicKey {
+    PublicKey {
+        bytes: SigningKey::from_bytes(&priv_key.bytes)
+            .verifying_key()
+            .to_bytes(),
+    }
+}
+
+/// Signiert eine Nachricht mit Ed25519 (rein, ohne Vorab-Hash).
+pub fn sign_ed25519(message: &[u8], priv_key: &PrivateKey) -> [u8; 64] {
+    SigningKey::from_bytes(&priv_key.bytes)
+        .sign(message)
+        .to_bytes()
+}
+
+/// Prüft eine Ed25519-Signatur. Ungültige Punkte oder Signaturen ergeben false.
+pub fn verify_ed25519(message: &[u8], signature: &[u8], pub_key: &PublicKey) -> bool {
+    let Ok(signature) = ed25519_dalek::Signature::from_slice(signature) else {
+        return false;
+    };
+    VerifyingKey::from_bytes(&pub_key.bytes)
+        .map(|key| key.verify(message, &signature).is_ok())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_verify() {
+        let (pub_key, priv_key) = generate_ed25519_keypair();
+        let sig = sign_ed25519(b"Nachricht", &priv_key);
+        assert!(verify_ed25519(b"Nachricht", &sig, &pub_key));
+        assert!(!verify_ed25519(b"Manipuliert", &sig, &pub_key));
+        assert_eq!(public_key_from_private(&priv_key), pub_key);
+    }
+
+    #[test]
+    fn test_rfc8032_vector() {
+        // RFC 8032, 7.1, TEST 1 (leere Nachricht)
+        let priv_key = PrivateKey {
+            bytes: hex::decode("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        };
+        assert_eq!(
+            hex::encode(public_key_from_private(&priv_key).bytes),
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+        );
+        assert_eq!(
+            hex::encode(sign_ed25519(b"", &priv_key)),
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155\
+             5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+        );
+    }
+}
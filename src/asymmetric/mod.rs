@@ -1 +1,4 @@
+pub mod ecdsa;
+pub mod ed25519;
 pub mod rsa;
+pub mod x25519;
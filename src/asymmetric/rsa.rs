@@ -1,47 +1,268 @@
-use crate::algorithms::number_theory::{is_coprime, mod_inverse};
-use crate::algorithms::primality::is_probably_prime;
+use crate::algorithms::constant_time::{self, CtModulus, FixedUint};
+use crate::algorithms::primality::{
+    generate_prime, is_probably_prime, is_strong_lucas_prime, rsa_prime_rounds, PrimeOptions,
+    SMALL_PRIMES,
+};
+use crate::utils::error::CryptoError;
+use hmac::{Hmac, Mac};
 use num_bigint::{BigUint, RandBigInt};
-use num_traits::One;
+use num_integer::Integer;
+use num_traits::{One, Zero};
 use rand::rngs::OsRng;
+use rand::RngCore;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::fmt;
+use subtle::{
+    Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeGreater, ConstantTimeLess,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PublicKey {
     pub e: BigUint,
     pub n: BigUint,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PrivateKey {
     pub d: BigUint,
     pub n: BigUint,
+    /// Öffentlicher Exponent, gebraucht für Blinding und die Prüfung jeder Signatur.
+    pub e: BigUint,
 }
 
-/// Generiert ein RSA-Schlüsselpaar mit der gegebenen Bitlänge.
+/// Kleinste Modullänge außerhalb des FIPS-Modus.
+const MIN_BITS: usize = 512;
+
+/// Parameter von [`generate_rsa_key`].
+#[derive(Debug, Clone)]
+pub struct RsaKeyOptions {
+    /// Länge des Moduls in Bit.
+    pub bits: usize,
+    /// Öffentlicher Exponent, ungerade.
+    pub e: BigUint,
+    /// Parameter nach FIPS 186-5 erzwingen: gerade Modullänge ab 2048 Bit und
+    /// 2¹⁶ < e < 2²⁵⁶.
+    pub fips: bool,
+}
+
+impl RsaKeyOptions {
+    /// Schlüssel mit `bits` Bit und e = 65537.
+    pub fn new(bits: usize) -> Self {
+        RsaKeyOptions {
+            bits,
+            e: BigUint::from(65537u32),
+            fips: false,
+        }
+    }
+}
+
+/// Generiert ein RSA-Schlüsselpaar mit der gegebenen Bitlänge und e = 65537.
+///
+/// Panik bei weniger als 512 Bit; [`generate_rsa_key`] meldet das als Fehler.
 pub fn generate_rsa_keypair(bits: usize) -> (PublicKey, PrivateKey) {
-    let one = BigUint::one();
-    let (p, q) = loop {
-        let p = gen_prime(bits / 2);
-        let q = gen_prime(bits / 2);
-        if p != q {
-            break (p, q);
+    generate_rsa_key(&RsaKeyOptions::new(bits)).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Generiert ein RSA-Schlüsselpaar nach FIPS 186-5, Anhang A.1.3 (wahrscheinliche
+/// Primzahlen).
+///
+/// Primfaktoren mit gemeinsamem Teiler von p − 1 und e, zu nahe beieinander liegende
+/// Primfaktoren (|p − q| ≤ 2^(nlen/2 − 100)) und zu kleine private Exponenten
+/// (d ≤ 2^(nlen/2)) werden verworfen und neu gezogen; d ist e⁻¹ modulo λ(n).
+pub fn generate_rsa_key(options: &RsaKeyOptions) -> Result<(PublicKey, PrivateKey), CryptoError> {
+    let bits = options.bits;
+    let e = &options.e;
+    if options.fips {
+        if bits < 2048 || !bits.is_multiple_of(2) {
+            return Err(CryptoError::Unsupported(format!(
+                "RSA mit {} Bit nach FIPS 186-5 (gerade Länge ab 2048 Bit)",
+                bits
+            )));
         }
-    };
+        if !fips_exponent(e) {
+            return Err(CryptoError::Unsupported(format!(
+                "öffentlicher Exponent {} nach FIPS 186-5 (ungerade, 2^16 < e < 2^256)",
+                e
+            )));
+        }
+    } else {
+        if bits < MIN_BITS {
+            return Err(CryptoError::Unsupported(format!(
+                "RSA mit {} Bit (mindestens {})",
+                bits, MIN_BITS
+            )));
+        }
+        if e.is_even() || *e < BigUint::from(3u32) || e.bits() >= bits as u64 {
+            return Err(CryptoError::Unsupported(format!(
+                "öffentlicher Exponent {}",
+                e
+            )));
+        }
+    }
 
-    let n = &p * &q;
-    let phi = (&p - &one) * (&q - &one);
+    // p, q, |p − q|, d und n in fester Breite; verzweigt wird nur auf das Ergebnis der
+    // Prüfungen, das zum Verwerfen des Kandidaten ohnehin sichtbar wird
+    let half = bits / 2;
+    let limbs = (bits - half).div_ceil(64);
+    let n_limbs = 2 * limbs;
+    let min_distance =
+        FixedUint::from_biguint(&(BigUint::one() << half.saturating_sub(100)), limbs);
+    let min_d = FixedUint::from_biguint(&(BigUint::one() << half), n_limbs);
+    let one = FixedUint::from_biguint(&BigUint::one(), limbs);
+    loop {
+        let mut p = FixedUint::from_biguint(&gen_rsa_prime(bits - half, e), limbs);
+        let mut q = FixedUint::from_biguint(&gen_rsa_prime(half, e), limbs);
+        // |p − q|: größeren Faktor nach vorn tauschen, dann abziehen
+        let swap = p.lt(&q);
+        FixedUint::conditional_swap(&mut p, &mut q, swap);
+        let mut distance = p.clone();
+        distance.sub_assign(&q);
+        if min_distance.lt(&distance) == 0 {
+            continue;
+        }
+        let (mut p_minus_one, mut q_minus_one) = (p.clone(), q.clone());
+        p_minus_one.sub_assign(&one);
+        q_minus_one.sub_assign(&one);
+        let lambda = constant_time::lcm(&p_minus_one.to_biguint(), &q_minus_one.to_biguint());
+        let d = constant_time::inverse_of_public(e, &lambda)
+            .expect("e ist teilerfremd zu p − 1 und q − 1");
+        if min_d.lt(&FixedUint::from_biguint(&d, n_limbs)) == 0 {
+            continue;
+        }
+        let n = p.mul(&q).to_biguint();
+        return Ok((
+            PublicKey {
+                e: e.clone(),
+                n: n.clone(),
+            },
+            PrivateKey { d, n, e: e.clone() },
+        ));
+    }
+}
 
-    let e = BigUint::from(65537u32);
-    assert!(is_coprime(&e, &phi), "e muss teilerfremd zu φ(n) sein");
+/// Öffentlicher Exponent nach FIPS 186-5: ungerade und 2¹⁶ < e < 2²⁵⁶.
+fn fips_exponent(e: &BigUint) -> bool {
+    e.is_odd() && e.bits() > 16 && e.bits() <= 256 && *e != BigUint::one() << 16
+}
 
-    let d = mod_inverse(&e, &phi).expect("Kein modulares Inverses für e gefunden");
+/// Verstoß gegen eine Anforderung von FIPS 186-5 bzw. SP 800-89 an einen RSA-Schlüssel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RsaKeyIssue {
+    /// Modullänge ungerade oder unter 2048 Bit.
+    ModulusLength(u64),
+    /// n ist gerade, eine Primzahl oder hat einen kleinen Primteiler.
+    Modulus(String),
+    /// e ist gerade oder liegt nicht zwischen 2¹⁶ und 2²⁵⁶.
+    PublicExponent,
+    /// Aus (n, e, d) lassen sich keine Primfaktoren gewinnen; d passt nicht zu n und e.
+    Inconsistent,
+    /// Ein Primfaktor ist keine Primzahl.
+    FactorNotPrime,
+    /// Ein Primfaktor ist kleiner als √2 · 2^(nlen/2 − 1) oder länger als nlen/2 Bit.
+    FactorLength,
+    /// |p − q| ≤ 2^(nlen/2 − 100).
+    FactorsTooClose,
+    /// d ≤ 2^(nlen/2).
+    PrivateExponentTooSmall,
+    /// d ≥ λ(n), etwa weil d modulo φ(n) statt λ(n) berechnet wurde.
+    PrivateExponentNotReduced,
+}
 
-    (
-        PublicKey {
-            e: e.clone(),
-            n: n.clone(),
-        },
-        PrivateKey { d, n },
-    )
+impl fmt::Display for RsaKeyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RsaKeyIssue::ModulusLength(bits) => write!(
+                f,
+                "Modul hat {} Bit (verlangt: gerade Länge ab 2048 Bit)",
+                bits
+            ),
+            RsaKeyIssue::Modulus(why) => write!(f, "ungültiger Modul: {}", why),
+            RsaKeyIssue::PublicExponent => write!(
+                f,
+                "öffentlicher Exponent muss ungerade sein und zwischen 2^16 und 2^256 liegen"
+            ),
+            RsaKeyIssue::Inconsistent => {
+                write!(f, "privater Exponent passt nicht zu Modul und Exponent")
+            }
+            RsaKeyIssue::FactorNotPrime => write!(f, "Primfaktor ist keine Primzahl"),
+            RsaKeyIssue::FactorLength => {
+                write!(f, "Primfaktor hat nicht die halbe Modullänge")
+            }
+            RsaKeyIssue::FactorsTooClose => {
+                write!(f, "Primfaktoren liegen zu nahe beieinander (|p − q|)")
+            }
+            RsaKeyIssue::PrivateExponentTooSmall => {
+                write!(f, "privater Exponent ist zu klein (d ≤ 2^(nlen/2))")
+            }
+            RsaKeyIssue::PrivateExponentNotReduced => {
+                write!(f, "privater Exponent ist nicht modulo λ(n) reduziert")
+            }
+        }
+    }
+}
+
+/// Prüft einen RSA-Schlüssel auf die Anforderungen von FIPS 186-5 (Anhang A.1) und die
+/// Plausibilitätsprüfung des Moduls nach SP 800-89. Mit privatem Schlüssel werden die
+/// Primfaktoren aus (n, e, d) zurückgewonnen und mitgeprüft.
+///
+/// Gibt alle gefundenen Verstöße zurück; eine leere Liste heißt, der Schlüssel ist gültig.
+pub fn validate_rsa_key(public: &PublicKey, private: Option<&PrivateKey>) -> Vec<RsaKeyIssue> {
+    let n = &public.n;
+    let nlen = n.bits();
+    let mut issues = Vec::new();
+    if nlen < 2048 || !nlen.is_multiple_of(2) {
+        issues.push(RsaKeyIssue::ModulusLength(nlen));
+    }
+    if n.is_even() {
+        issues.push(RsaKeyIssue::Modulus("n ist gerade".into()));
+    } else if let Some(&p) = SMALL_PRIMES
+        .iter()
+        .find(|&&p| n > &BigUint::from(p) && (n % p).is_zero())
+    {
+        issues.push(RsaKeyIssue::Modulus(format!("n ist durch {} teilbar", p)));
+    } else if is_probably_prime(n, rsa_prime_rounds(nlen as usize)) {
+        issues.push(RsaKeyIssue::Modulus("n ist eine Primzahl".into()));
+    }
+    if !fips_exponent(&public.e) {
+        issues.push(RsaKeyIssue::PublicExponent);
+    }
+
+    let Some(private) = private else {
+        return issues;
+    };
+    if private.n != *n {
+        issues.push(RsaKeyIssue::Inconsistent);
+        return issues;
+    }
+    let Some((p, q)) = recover_primes(public, private) else {
+        issues.push(RsaKeyIssue::Inconsistent);
+        return issues;
+    };
+    if ![&p, &q].iter().all(|f| {
+        is_probably_prime(f, rsa_prime_rounds(f.bits() as usize)) && is_strong_lucas_prime(f)
+    }) {
+        issues.push(RsaKeyIssue::FactorNotPrime);
+    }
+    // √2 · 2^(nlen/2 − 1) ≤ p, q < 2^(nlen/2), geprüft als p² ≥ 2^(nlen − 1)
+    let half = nlen / 2;
+    let min_square = BigUint::one() << (nlen - 1);
+    if [&p, &q]
+        .iter()
+        .any(|f| f.bits() > half || *f * *f < min_square)
+    {
+        issues.push(RsaKeyIssue::FactorLength);
+    }
+    if &q - &p <= BigUint::one() << half.saturating_sub(100) {
+        issues.push(RsaKeyIssue::FactorsTooClose);
+    }
+    if private.d <= BigUint::one() << half {
+        issues.push(RsaKeyIssue::PrivateExponentTooSmall);
+    }
+    if private.d >= constant_time::lcm(&(&p - 1u32), &(&q - 1u32)) {
+        issues.push(RsaKeyIssue::PrivateExponentNotReduced);
+    }
+    issues
 }
 
 /// RSA-Verschlüsselung: c = m^e mod n
@@ -50,19 +271,586 @@ pub fn encrypt_rsa(message: &BigUint, pub_key: &PublicKey) -> BigUint {
 }
 
 /// RSA-Entschlüsselung: m = c^d mod n
+///
+/// Läuft über [`private_op`] mit Blinding und in konstanter Laufzeit; nur ein gerader und
+/// damit ungültiger Modul fällt auf `BigUint::modpow` zurück.
 pub fn decrypt_rsa(ciphertext: &BigUint, priv_key: &PrivateKey) -> BigUint {
-    ciphertext.modpow(&priv_key.d, &priv_key.n)
+    if priv_key.n.is_even() {
+        return ciphertext.modpow(&priv_key.d, &priv_key.n);
+    }
+    private_op(ciphertext, priv_key)
+}
+
+/// x^d mod n für ungerades n mit Basis- und Exponenten-Blinding.
+///
+/// Die Basis wird mit r^e für ein zufälliges r multipliziert und das Ergebnis mit r⁻¹, so
+/// dass die Exponentiation nie den vom Angreifer gewählten Wert sieht. Statt d dient
+/// d + k·(e·d − 1) mit zufälligem 64-Bit-k als Exponent: e·d − 1 ist ein Vielfaches von
+/// λ(n), das Ergebnis bleibt gleich, die Bitfolge wechselt aber bei jedem Aufruf. Die
+/// Breite des Exponenten hängt nur von n, d und e ab, nicht von k.
+fn private_op(x: &BigUint, key: &PrivateKey) -> BigUint {
+    let n = &key.n;
+    let limbs = n.iter_u64_digits().len();
+    let ctx = CtModulus::new(&FixedUint::from_biguint(n, limbs));
+    let fixed = |value: &BigUint| FixedUint::from_biguint(&(value % n), limbs);
+
+    let (r, r_inverse) = loop {
+        let r = OsRng.gen_biguint_below(n);
+        if let Some(inverse) = fixed(&r).inverse_odd(ctx.modulus()) {
+            break (r, inverse);
+        }
+    };
+    let blinded = ctx.mont_mul(&ctx.to_montgomery(&fixed(x)), &fixed(&r.modpow(&key.e, n)));
+
+    let d_limbs = key.d.iter_u64_digits().len().max(limbs);
+    let e_limbs = key.e.iter_u64_digits().len().max(1);
+    let width = d_limbs + e_limbs + 1;
+    let d = FixedUint::from_biguint(&key.d, d_limbs);
+    let mut multiple = FixedUint::from_biguint(&key.e, e_limbs).mul(&d);
+    multiple.sub_assign(&FixedUint::from_biguint(&BigUint::one(), multiple.width()));
+    let k = FixedUint::from_biguint(&BigUint::from(OsRng.next_u64()), 1);
+    let mut exponent = multiple.mul(&k);
+    exponent.add_assign(&d.resized(width));
+
+    let y = ctx.pow(&blinded, &exponent);
+    ctx.mont_mul(&ctx.to_montgomery(&y), &r_inverse)
+        .to_biguint()
+}
+
+/// Signaturprimitiv s = m^d mod n (RSASP1) über [`decrypt_rsa`]. Vor der Herausgabe wird
+/// s^e ≡ m geprüft, damit ein Rechenfehler – etwa durch einen gezielt herbeigeführten
+/// Fehler – keine Signatur nach außen gibt, aus der sich n faktorisieren ließe.
+///
+/// Ein vorübergehender Fehler wird durch eine zweite Rechnung behoben; schlägt auch sie
+/// fehl, passt d nicht zu n und e, und es gibt einen Fehler statt einer falschen Signatur.
+fn sign_raw(m: &BigUint, priv_key: &PrivateKey) -> Result<BigUint, CryptoError> {
+    let public = PublicKey {
+        e: priv_key.e.clone(),
+        n: priv_key.n.clone(),
+    };
+    for _ in 0..2 {
+        let s = decrypt_rsa(m, priv_key);
+        if encrypt_rsa(&s, &public) == *m {
+            return Ok(s);
+        }
+    }
+    Err(CryptoError::InvalidFormat(
+        "RSA-Signatur besteht die Prüfung mit dem öffentlichen Schlüssel nicht \
+         (privater Schlüssel passt nicht zu Modul und Exponent)"
+            .to_string(),
+    ))
+}
+
+/// Länge des Moduls in Byte (k in RFC 8017).
+fn modulus_len(n: &BigUint) -> usize {
+    n.bits().div_ceil(8) as usize
+}
+
+/// I2OSP: Ganzzahl als Big-Endian-Bytefolge fester Länge.
+fn i2osp(x: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = x.to_bytes_be();
+    let mut out = vec![0u8; len.saturating_sub(bytes.len())];
+    out.extend_from_slice(&bytes);
+    out
+}
+
+/// MGF1 (RFC 8017, B.2.1) mit der Hash-Funktion `D`.
+fn mgf1<D: Digest>(seed: &[u8], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len + <D as Digest>::output_size());
+    let mut counter = 0u32;
+    while out.len() < len {
+        let mut hasher = D::new();
+        hasher.update(seed);
+        hasher.update(counter.to_be_bytes());
+        out.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+/// RSAES-OAEP-Verschlüsselung mit SHA-256 und MGF1-SHA-256 (RFC 8017, 7.1.1).
+///
+/// Die Nachricht darf höchstens k − 66 Byte lang sein (k = Modullänge in Byte).
+pub fn encrypt_rsa_oaep(message: &[u8], label: &[u8], pub_key: &PublicKey) -> Vec<u8> {
+    oaep_encrypt::<Sha256>(message, label, pub_key)
 }
 
-/// Generiert eine zufällige Primzahl mit der gewünschten Bitlänge.
-fn gen_prime(bits: usize) -> BigUint {
-    let mut rng = OsRng;
+/// RSAES-OAEP-Entschlüsselung mit SHA-256 und MGF1-SHA-256 (RFC 8017, 7.1.2).
+///
+/// Gibt None zurück, wenn der Ciphertext nicht korrekt kodiert ist.
+pub fn decrypt_rsa_oaep(ciphertext: &[u8], label: &[u8], priv_key: &PrivateKey) -> Option<Vec<u8>> {
+    oaep_decrypt::<Sha256>(ciphertext, label, priv_key)
+}
+
+/// RSAES-OAEP mit SHA-1 und MGF1-SHA-1 (Standardparameter von RFC 8017, nur für Interoperabilität).
+pub fn encrypt_rsa_oaep_sha1(message: &[u8], label: &[u8], pub_key: &PublicKey) -> Vec<u8> {
+    oaep_encrypt::<Sha1>(message, label, pub_key)
+}
+
+/// Entschlüsselt RSAES-OAEP mit SHA-1 und MGF1-SHA-1.
+pub fn decrypt_rsa_oaep_sha1(
+    ciphertext: &[u8],
+    label: &[u8],
+    priv_key: &PrivateKey,
+) -> Option<Vec<u8>> {
+    oaep_decrypt::<Sha1>(ciphertext, label, priv_key)
+}
+
+fn oaep_encrypt<D: Digest>(message: &[u8], label: &[u8], pub_key: &PublicKey) -> Vec<u8> {
+    let k = modulus_len(&pub_key.n);
+    let h_len = <D as Digest>::output_size();
+    assert!(
+        message.len() + 2 * h_len + 2 <= k,
+        "Nachricht zu lang für RSA-OAEP"
+    );
+
+    let l_hash = D::digest(label);
+    let mut db = Vec::with_capacity(k - h_len - 1);
+    db.extend_from_slice(&l_hash);
+    db.resize(k - message.len() - h_len - 2, 0);
+    db.push(0x01);
+    db.extend_from_slice(message);
+
+    let mut seed = vec![0u8; h_len];
+    OsRng.fill_bytes(&mut seed);
+
+    let db_mask = mgf1::<D>(&seed, db.len());
+    db.iter_mut().zip(db_mask).for_each(|(b, m)| *b ^= m);
+    let seed_mask = mgf1::<D>(&db, h_len);
+    seed.iter_mut().zip(seed_mask).for_each(|(b, m)| *b ^= m);
+
+    let mut em = vec![0u8];
+    em.extend_from_slice(&seed);
+    em.extend_from_slice(&db);
+
+    let c = encrypt_rsa(&BigUint::from_bytes_be(&em), pub_key);
+    i2osp(&c, k)
+}
+
+fn oaep_decrypt<D: Digest>(
+    ciphertext: &[u8],
+    label: &[u8],
+    priv_key: &PrivateKey,
+) -> Option<Vec<u8>> {
+    let k = modulus_len(&priv_key.n);
+    let h_len = <D as Digest>::output_size();
+    if ciphertext.len() != k || k < 2 * h_len + 2 {
+        return None;
+    }
+    let c = BigUint::from_bytes_be(ciphertext);
+    if c >= priv_key.n {
+        return None;
+    }
+    let em = i2osp(&decrypt_rsa(&c, priv_key), k);
+
+    let (masked_seed, masked_db) = em[1..].split_at(h_len);
+    let seed_mask = mgf1::<D>(masked_db, h_len);
+    let seed: Vec<u8> = masked_seed
+        .iter()
+        .zip(seed_mask)
+        .map(|(b, m)| b ^ m)
+        .collect();
+    let db_mask = mgf1::<D>(&seed, masked_db.len());
+    let db: Vec<u8> = masked_db.iter().zip(db_mask).map(|(b, m)| b ^ m).collect();
+
+    // Prüfungen und Suche nach dem Trennbyte ohne datenabhängige Verzweigung; entschieden
+    // wird erst am Ende, sodass Fehlerart und Position des Trennbytes nicht am Zeitverhalten
+    // ablesbar sind
+    let l_hash = D::digest(label);
+    let mut good = em[0].ct_eq(&0) & db[..h_len].ct_eq(&l_hash[..]);
+    let rest = &db[h_len..];
+    let mut found = Choice::from(0);
+    let mut index = 0u64;
+    for (i, b) in rest.iter().enumerate() {
+        let nonzero = !b.ct_eq(&0);
+        let first = nonzero & !found;
+        index.conditional_assign(&(i as u64), first);
+        // das erste Byte ungleich 0 muss 0x01 sein
+        good &= !first | b.ct_eq(&0x01);
+        found |= nonzero;
+    }
+    good &= found;
+    if bool::from(good) {
+        Some(rest[index as usize + 1..].to_vec())
+    } else {
+        None
+    }
+}
+
+/// Salt-Länge für RSASSA-PSS (entspricht der SHA-256-Ausgabelänge).
+const PSS_SALT_LEN: usize = 32;
+
+/// M' = 0x00 × 8 || mHash || salt, gehasht (RFC 8017, 9.1.1 Schritt 5–6).
+fn pss_hash(m_hash: &[u8], salt: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update([0u8; 8]);
+    hasher.update(m_hash);
+    hasher.update(salt);
+    hasher.finalize().to_vec()
+}
+
+/// RSASSA-PSS-Signatur mit SHA-256, MGF1-SHA-256 und 32 Byte Salt (RFC 8017, 8.1.1).
+pub fn sign_rsa_pss(message: &[u8], priv_key: &PrivateKey) -> Result<Vec<u8>, CryptoError> {
+    let k = modulus_len(&priv_key.n);
+    let em_bits = (priv_key.n.bits() as usize).saturating_sub(1);
+    let em_len = em_bits.div_ceil(8);
+    let h_len = 32;
+    if em_len < h_len + PSS_SALT_LEN + 2 {
+        return Err(CryptoError::InvalidFormat(
+            "Modul zu klein für RSASSA-PSS".to_string(),
+        ));
+    }
+
+    let m_hash = Sha256::digest(message);
+    let mut salt = [0u8; PSS_SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let h = pss_hash(&m_hash, &salt);
+
+    let mut db = vec![0u8; em_len - PSS_SALT_LEN - h_len - 2];
+    db.push(0x01);
+    db.extend_from_slice(&salt);
+    let db_mask = mgf1::<Sha256>(&h, db.len());
+    db.iter_mut().zip(db_mask).for_each(|(b, m)| *b ^= m);
+    db[0] &= 0xff >> (8 * em_len - em_bits);
+
+    let mut em = db;
+    em.extend_from_slice(&h);
+    em.push(0xbc);
+
+    let s = sign_raw(&BigUint::from_bytes_be(&em), priv_key)?;
+    Ok(i2osp(&s, k))
+}
+
+/// Prüft eine RSASSA-PSS-Signatur mit SHA-256 (RFC 8017, 8.1.2).
+pub fn verify_rsa_pss(message: &[u8], signature: &[u8], pub_key: &PublicKey) -> bool {
+    let k = modulus_len(&pub_key.n);
+    let em_bits = (pub_key.n.bits() as usize).saturating_sub(1);
+    let em_len = em_bits.div_ceil(8);
+    let h_len = 32;
+    if signature.len() != k || em_len < h_len + PSS_SALT_LEN + 2 {
+        return false;
+    }
+    let s = BigUint::from_bytes_be(signature);
+    if s >= pub_key.n {
+        return false;
+    }
+    let m = encrypt_rsa(&s, pub_key);
+    if m.bits() as usize > em_bits {
+        return false;
+    }
+    let em = i2osp(&m, em_len);
+    if em[em_len - 1] != 0xbc {
+        return false;
+    }
+
+    let (masked_db, h) = em[..em_len - 1].split_at(em_len - h_len - 1);
+    let top_mask = 0xffu8 >> (8 * em_len - em_bits);
+    if masked_db[0] & !top_mask != 0 {
+        return false;
+    }
+    let db_mask = mgf1::<Sha256>(h, masked_db.len());
+    let mut db: Vec<u8> = masked_db.iter().zip(db_mask).map(|(b, m)| b ^ m).collect();
+    db[0] &= top_mask;
+
+    let ps_len = em_len - h_len - PSS_SALT_LEN - 2;
+    if db[..ps_len].iter().any(|&b| b != 0) || db[ps_len] != 0x01 {
+        return false;
+    }
+    let salt = &db[ps_len + 1..];
+    pss_hash(&Sha256::digest(message), salt) == h
+}
+
+/// Hash-Verfahren für RSASSA-PKCS1-v1_5.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pkcs1Hash {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl Pkcs1Hash {
+    /// DER-kodierter DigestInfo-Präfix (RFC 8017, 9.2, Anmerkung 1).
+    fn digest_info_prefix(self) -> &'static [u8] {
+        match self {
+            Pkcs1Hash::Sha256 => &[
+                0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+                0x01, 0x05, 0x00, 0x04, 0x20,
+            ],
+            Pkcs1Hash::Sha384 => &[
+                0x30, 0x41, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+                0x02, 0x05, 0x00, 0x04, 0x30,
+            ],
+            Pkcs1Hash::Sha512 => &[
+                0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+                0x03, 0x05, 0x00, 0x04, 0x40,
+            ],
+        }
+    }
+
+    /// Länge des Hashwerts in Byte.
+    pub fn output_len(self) -> usize {
+        match self {
+            Pkcs1Hash::Sha256 => 32,
+            Pkcs1Hash::Sha384 => 48,
+            Pkcs1Hash::Sha512 => 64,
+        }
+    }
+
+    /// Berechnet den Hashwert einer Nachricht.
+    pub fn digest(self, message: &[u8]) -> Vec<u8> {
+        match self {
+            Pkcs1Hash::Sha256 => Sha256::digest(message).to_vec(),
+            Pkcs1Hash::Sha384 => Sha384::digest(message).to_vec(),
+            Pkcs1Hash::Sha512 => Sha512::digest(message).to_vec(),
+        }
+    }
+}
+
+/// EMSA-PKCS1-v1_5-Kodierung eines bereits berechneten Hashwerts (RFC 8017, 9.2).
+fn emsa_pkcs1v15(hash: Pkcs1Hash, digest: &[u8], em_len: usize) -> Option<Vec<u8>> {
+    let prefix = hash.digest_info_prefix();
+    let t_len = prefix.len() + digest.len();
+    if digest.len() != hash.output_len() || em_len < t_len + 11 {
+        return None;
+    }
+    let mut em = vec![0x00, 0x01];
+    em.resize(em_len - t_len - 1, 0xff);
+    em.push(0x00);
+    em.extend_from_slice(prefix);
+    em.extend_from_slice(digest);
+    Some(em)
+}
+
+/// RSASSA-PKCS1-v1_5-Signatur über einen bereits berechneten Hashwert (RFC 8017, 8.2.1).
+pub fn sign_rsa_pkcs1v15(
+    hash: Pkcs1Hash,
+    digest: &[u8],
+    priv_key: &PrivateKey,
+) -> Result<Vec<u8>, CryptoError> {
+    let k = modulus_len(&priv_key.n);
+    let em = emsa_pkcs1v15(hash, digest, k).ok_or_else(|| {
+        CryptoError::InvalidFormat("Modul zu klein für RSASSA-PKCS1-v1_5".to_string())
+    })?;
+    let s = sign_raw(&BigUint::from_bytes_be(&em), priv_key)?;
+    Ok(i2osp(&s, k))
+}
+
+/// Prüft eine RSASSA-PKCS1-v1_5-Signatur über einen Hashwert (RFC 8017, 8.2.2).
+pub fn verify_rsa_pkcs1v15(
+    hash: Pkcs1Hash,
+    digest: &[u8],
+    signature: &[u8],
+    pub_key: &PublicKey,
+) -> bool {
+    let k = modulus_len(&pub_key.n);
+    let s = BigUint::from_bytes_be(signature);
+    if signature.len() > k || s >= pub_key.n {
+        return false;
+    }
+    match emsa_pkcs1v15(hash, digest, k) {
+        Some(expected) => i2osp(&encrypt_rsa(&s, pub_key), k) == expected,
+        None => false,
+    }
+}
+
+/// RSAES-PKCS1-v1_5-Verschlüsselung (RFC 8017, 7.2.1).
+///
+/// Nur für Formate, die das Verfahren vorschreiben (z. B. OpenPGP); sonst OAEP verwenden.
+pub fn encrypt_rsa_pkcs1v15(message: &[u8], pub_key: &PublicKey) -> Vec<u8> {
+    let k = modulus_len(&pub_key.n);
+    assert!(message.len() + 11 <= k, "Nachricht zu lang für RSA-PKCS1");
+    let mut em = vec![0x00, 0x02];
+    while em.len() < k - message.len() - 1 {
+        let b = (OsRng.next_u32() & 0xff) as u8;
+        if b != 0 {
+            em.push(b);
+        }
+    }
+    em.push(0x00);
+    em.extend_from_slice(message);
+    i2osp(&encrypt_rsa(&BigUint::from_bytes_be(&em), pub_key), k)
+}
+
+/// RSAES-PKCS1-v1_5-Entschlüsselung (RFC 8017, 7.2.2) mit impliziter Zurückweisung nach
+/// draft-irtf-cfrg-rsa-guidance.
+///
+/// Bei ungültigem Padding kommt kein Fehler, sondern eine synthetische Nachricht, die aus d
+/// und dem Chiffrat abgeleitet ist: für dasselbe Chiffrat immer dieselbe, ohne d nicht
+/// vorhersagbar. Das Padding wird ohne datenabhängige Verzweigung geprüft, sodass weder
+/// Rückgabewert noch Laufzeit als Bleichenbacher-Orakel taugen; ein falscher Schlüssel
+/// fällt erst beim Aufrufer auf (Prüfsumme, MAC). None nur bei Chiffraten, die schon ohne
+/// d als ungültig erkennbar sind (zu lang, ≥ n).
+pub fn decrypt_rsa_pkcs1v15(ciphertext: &[u8], priv_key: &PrivateKey) -> Option<Vec<u8>> {
+    let k = modulus_len(&priv_key.n);
+    let c = BigUint::from_bytes_be(ciphertext);
+    if ciphertext.len() > k || k < 11 || c >= priv_key.n {
+        return None;
+    }
+    let em = i2osp(&decrypt_rsa(&c, priv_key), k);
+    let (synthetic, synthetic_len) = implicit_rejection(ciphertext, priv_key, k);
+
+    // 0x00 || 0x02 || PS (mindestens 8 Byte ≠ 0) || 0x00 || M
+    let mut good = em[0].ct_eq(&0) & em[1].ct_eq(&2);
+    let mut found = Choice::from(0);
+    let mut separator = 0u64;
+    for (i, b) in em.iter().enumerate().skip(2) {
+        let zero = b.ct_eq(&0);
+        separator.conditional_assign(&(i as u64), zero & !found);
+        found |= zero;
+    }
+    good &= found & separator.ct_gt(&9);
+
+    // beide Nachrichten stehen rechtsbündig in k Byte; ausgewählt wird per Maske
+    let real_len = k as u64 - separator - 1;
+    let len = u64::conditional_select(&synthetic_len, &real_len, good) as usize;
+    let out: Vec<u8> = em
+        .iter()
+        .zip(&synthetic)
+        .map(|(real, fake)| u8::conditional_select(fake, real, good))
+        .collect();
+    Some(out[k - len..].to_vec())
+}
+
+/// Synthetische Nachricht (rechtsbündig in k Byte) und ihre Länge für ungültiges Padding
+/// (draft-irtf-cfrg-rsa-guidance, Abschnitt 7): KDK = HMAC-SHA256(SHA-256(d), c), daraus
+/// per PRF Nachricht und 128 Kandidaten für die Länge, von denen der letzte unter
+/// k − 10 gilt.
+fn implicit_rejection(ciphertext: &[u8], priv_key: &PrivateKey, k: usize) -> (Vec<u8>, u64) {
+    let mut kdk = <Hmac<Sha256> as Mac>::new_from_slice(&Sha256::digest(i2osp(&priv_key.d, k)))
+        .expect("HMAC nimmt jede Schlüssellänge");
+    kdk.update(&[vec![0u8; k - ciphertext.len()], ciphertext.to_vec()].concat());
+    let kdk = kdk.finalize().into_bytes();
+
+    let prf = |label: &[u8], len: usize| {
+        let mut out = Vec::with_capacity(len + 32);
+        for i in 0u16.. {
+            if out.len() >= len {
+                break;
+            }
+            let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&kdk).unwrap();
+            mac.update(&i.to_be_bytes());
+            mac.update(label);
+            mac.update(&((len * 8) as u16).to_be_bytes());
+            out.extend_from_slice(&mac.finalize().into_bytes());
+        }
+        out.truncate(len);
+        out
+    };
+    let message = prf(b"message", k);
+    let candidates = prf(b"length", 256);
+
+    // Maske der nächsten Zweierpotenz über der größten Länge; hängt nur von k ab
+    let max_len = (k - 10) as u64;
+    let mask = u64::MAX >> (max_len - 1).leading_zeros().min(63);
+    let mut len = 0u64;
+    for pair in candidates.chunks(2) {
+        let candidate = u64::from(u16::from_be_bytes([pair[0], pair[1]])) & mask;
+        len.conditional_assign(&candidate, candidate.ct_lt(&max_len));
+    }
+    (message, len)
+}
+
+/// Rekonstruiert die Primfaktoren p < q aus (n, e, d) (NIST SP 800-56B, Anhang C).
+///
+/// Wird benötigt, wenn ein Format p und q verlangt, der Schlüssel aber nur d und n enthält.
+/// Gerechnet wird mit fester Breite in konstanter Laufzeit: Je Basis laufen immer alle
+/// Quadrierungen, Vergleiche sind Masken, und die Wurzel von 1 wird per Maske festgehalten.
+/// Vom Schlüssel hängt nur ab, wie viele Basen nötig sind (meist genügt die erste).
+pub fn recover_primes(pub_key: &PublicKey, priv_key: &PrivateKey) -> Option<(BigUint, BigUint)> {
+    let one = BigUint::one();
+    let n = &pub_key.n;
+    // importierte Schlüssel: d außerhalb von [1, n) passt zu keinem Modul
+    if priv_key.d.is_zero() || &priv_key.d >= n {
+        return None;
+    }
+    let k = &priv_key.d * &pub_key.e - &one;
+    if k.is_zero() || k.bit(0) || n.is_even() {
+        return None;
+    }
+    let t = k.trailing_zeros()?;
+    let r = &k >> t;
+
+    let limbs = n.iter_u64_digits().len();
+    let n_fixed = FixedUint::from_biguint(n, limbs);
+    let ctx = CtModulus::new(&n_fixed);
+    let montgomery = |value: &BigUint| ctx.to_montgomery(&FixedUint::from_biguint(value, limbs));
+    let one_m = montgomery(&one);
+    let minus_one_m = montgomery(&(n - &one));
+    let r = FixedUint::from_biguint(&r, r.iter_u64_digits().len().max(limbs));
+
+    for g in 2u32..100 {
+        let base = FixedUint::from_biguint(&(BigUint::from(g) % n), limbs);
+        let mut y = ctx.to_montgomery(&ctx.pow(&base, &r));
+        // Folge endet, sobald ±1 erreicht ist; y mit y² = 1 davor ist eine echte Wurzel
+        let mut done = y.eq(&one_m) | y.eq(&minus_one_m);
+        let mut root = FixedUint::zero(limbs);
+        let mut found = 0u64;
+        for _ in 0..t {
+            let x = ctx.mont_mul(&y, &y);
+            let hit = !done & x.eq(&one_m);
+            root.conditional_assign(&y, hit);
+            found |= hit;
+            done |= x.eq(&one_m) | x.eq(&minus_one_m);
+            y = x;
+        }
+        if found != 0 {
+            let mut root_minus_one = ctx.from_montgomery(&root);
+            root_minus_one.sub_assign(&FixedUint::from_biguint(&one, limbs));
+            let mut p = root_minus_one.gcd(&n_fixed);
+            let mut q = n_fixed.div_rem(&p).0;
+            let swap = q.lt(&p);
+            FixedUint::conditional_swap(&mut p, &mut q, swap);
+            return Some((p.to_biguint(), q.to_biguint()));
+        }
+    }
+    None
+}
+
+/// CRT-Parameter eines privaten Schlüssels (RFC 8017, 3.2) mit p < q.
+#[derive(Debug, Clone)]
+pub struct CrtParams {
+    pub p: BigUint,
+    pub q: BigUint,
+    /// d mod (p − 1)
+    pub dp: BigUint,
+    /// d mod (q − 1)
+    pub dq: BigUint,
+    /// q⁻¹ mod p
+    pub qinv: BigUint,
+}
+
+/// Rekonstruiert p und q und berechnet daraus die CRT-Parameter für den Export in Formate,
+/// die sie verlangen (PKCS#8, JWK, OpenSSH). Reste und Inverses laufen in konstanter
+/// Laufzeit; `None`, wenn sich keine zwei Primfaktoren finden lassen.
+pub fn crt_params(pub_key: &PublicKey, priv_key: &PrivateKey) -> Option<CrtParams> {
+    let (p, q) = recover_primes(pub_key, priv_key)?;
+    let qinv = constant_time::inverse_mod_prime(&q, &p);
+    if !constant_time::mul_mod(&qinv, &q, &p).is_one() {
+        return None;
+    }
+    Some(CrtParams {
+        dp: constant_time::rem(&priv_key.d, &(&p - 1u32)),
+        dq: constant_time::rem(&priv_key.d, &(&q - 1u32)),
+        qinv,
+        p,
+        q,
+    })
+}
+
+/// Generiert eine zufällige Primzahl p mit der gewünschten Bitlänge und ggT(p − 1, e) = 1;
+/// die obersten beiden Bit sind gesetzt, damit `n = p·q` genau die Summe der Längen hat
+/// (und p ≥ √2 · 2^(bits − 1) gilt). Gesucht wird auf allen Kernen.
+fn gen_rsa_prime(bits: usize, e: &BigUint) -> BigUint {
+    let options = PrimeOptions {
+        top_two_bits: true,
+        threads: 0,
+        ..PrimeOptions::new(bits)
+    };
     loop {
-        let mut candidate = rng.gen_biguint(bits.try_into().unwrap());
-        // stelle sicher, dass die Zahl ungerade ist
-        candidate.set_bit(0, true);
-        if is_probably_prime(&candidate, 10) {
-            return candidate;
+        let p = generate_prime(&options).expect("RSA-Primfaktoren brauchen mindestens 2 Bit");
+        if constant_time::gcd(&(&p - 1u32), e).is_one() {
+            return p;
         }
     }
 }
@@ -82,4 +870,246 @@ mod tests {
 
         assert_eq!(message, decrypted);
     }
+
+    #[test]
+    fn test_rsa_blinding() {
+        let (pub_key, priv_key) = generate_rsa_keypair(512);
+        // Blinding und Exponent ändern sich je Aufruf, das Ergebnis nicht
+        let m = OsRng.gen_biguint_below(&pub_key.n);
+        let c = encrypt_rsa(&m, &pub_key);
+        for _ in 0..5 {
+            assert_eq!(private_op(&c, &priv_key), m);
+        }
+        assert_eq!(private_op(&BigUint::zero(), &priv_key), BigUint::zero());
+        assert_eq!(private_op(&(&pub_key.n + 1u32), &priv_key), BigUint::one());
+
+        // mit falschem e entsteht keine Signatur
+        let wrong = PrivateKey {
+            e: BigUint::from(3u32),
+            ..priv_key
+        };
+        assert!(sign_rsa_pss(b"Nachricht", &wrong).is_err());
+        let digest = Pkcs1Hash::Sha256.digest(b"Nachricht");
+        assert!(sign_rsa_pkcs1v15(Pkcs1Hash::Sha256, &digest, &wrong).is_err());
+    }
+
+    #[test]
+    fn test_rsa_oaep_roundtrip() {
+        let (pub_key, priv_key) = generate_rsa_keypair(1024);
+        let message = b"AES-Dateischluessel (32 Byte)!!!";
+
+        let ct = encrypt_rsa_oaep(message, b"label", &pub_key);
+        assert_eq!(ct.len(), 128);
+        assert_eq!(
+            decrypt_rsa_oaep(&ct, b"label", &priv_key).unwrap(),
+            message.to_vec()
+        );
+
+        // falsches Label oder manipulierter Ciphertext
+        assert!(decrypt_rsa_oaep(&ct, b"anders", &priv_key).is_none());
+        let mut tampered = ct.clone();
+        tampered[10] ^= 0x80;
+        assert!(decrypt_rsa_oaep(&tampered, b"label", &priv_key).is_none());
+
+        // SHA-1-Variante ist nicht mit SHA-256 austauschbar
+        let ct = encrypt_rsa_oaep_sha1(message, b"", &pub_key);
+        assert_eq!(
+            decrypt_rsa_oaep_sha1(&ct, b"", &priv_key).unwrap(),
+            message.to_vec()
+        );
+        assert!(decrypt_rsa_oaep(&ct, b"", &priv_key).is_none());
+    }
+
+    #[test]
+    fn test_rsa_pss_sign_verify() {
+        let (pub_key, priv_key) = generate_rsa_keypair(1024);
+        let sig = sign_rsa_pss(b"Nachricht", &priv_key).unwrap();
+        assert_eq!(sig.len(), 128);
+        assert!(verify_rsa_pss(b"Nachricht", &sig, &pub_key));
+        assert!(!verify_rsa_pss(b"Nachricht!", &sig, &pub_key));
+
+        let mut tampered = sig.clone();
+        tampered[5] ^= 1;
+        assert!(!verify_rsa_pss(b"Nachricht", &tampered, &pub_key));
+
+        // unsinnige Moduln aus importierten Schlüsseln: ungültig statt Panik
+        for n in [0u32, 1] {
+            let key = PublicKey {
+                n: BigUint::from(n),
+                e: pub_key.e.clone(),
+            };
+            assert!(!verify_rsa_pss(b"Nachricht", &[], &key));
+            assert!(!verify_rsa_pss(b"Nachricht", &sig, &key));
+        }
+    }
+
+    #[test]
+    fn test_rsa_pkcs1v15_sign_verify_and_encrypt() {
+        let (pub_key, priv_key) = generate_rsa_keypair(1024);
+        let digest = Pkcs1Hash::Sha256.digest(b"Nachricht");
+        let sig = sign_rsa_pkcs1v15(Pkcs1Hash::Sha256, &digest, &priv_key).unwrap();
+        assert!(verify_rsa_pkcs1v15(
+            Pkcs1Hash::Sha256,
+            &digest,
+            &sig,
+            &pub_key
+        ));
+        assert!(!verify_rsa_pkcs1v15(
+            Pkcs1Hash::Sha512,
+            &Pkcs1Hash::Sha512.digest(b"Nachricht"),
+            &sig,
+            &pub_key
+        ));
+
+        let ct = encrypt_rsa_pkcs1v15(b"Sitzungsschluessel", &pub_key);
+        assert_eq!(
+            decrypt_rsa_pkcs1v15(&ct, &priv_key).unwrap(),
+            b"Sitzungsschluessel".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_rsa_pkcs1v15_implicit_rejection() {
+        let (pub_key, priv_key) = generate_rsa_keypair(1024);
+        let k = modulus_len(&pub_key.n);
+        let encrypt_raw = |em: &[u8]| i2osp(&encrypt_rsa(&BigUint::from_bytes_be(em), &pub_key), k);
+        let valid = |message: &[u8]| {
+            let mut em = vec![0x00, 0x02];
+            em.extend(std::iter::repeat_n(0x5a, k - 3 - message.len()));
+            em.push(0x00);
+            em.extend_from_slice(message);
+            em
+        };
+        let message = b"Sitzungsschluessel";
+        assert_eq!(
+            decrypt_rsa_pkcs1v15(&encrypt_raw(&valid(message)), &priv_key).unwrap(),
+            message
+        );
+
+        // falscher Kopf, kein Trennbyte, zu kurzes PS: keine Fehlermeldung, sondern eine
+        // feste, vom Schlüssel abhängige Ersatznachricht
+        let mut wrong_type = valid(message);
+        wrong_type[1] = 0x01;
+        let mut wrong_first = valid(message);
+        wrong_first[0] = 0x01;
+        let no_separator = [&[0x00, 0x02][..], &vec![0x5a; k - 2]].concat();
+        let mut short_padding = valid(message);
+        short_padding[5] = 0x00;
+        let (_, other_key) = generate_rsa_keypair(1024);
+        for em in [wrong_type, wrong_first, no_separator, short_padding] {
+            let ct = encrypt_raw(&em);
+            let synthetic = decrypt_rsa_pkcs1v15(&ct, &priv_key).unwrap();
+            assert!(synthetic.len() < k - 10);
+            assert_ne!(synthetic, message);
+            assert_eq!(decrypt_rsa_pkcs1v15(&ct, &priv_key).unwrap(), synthetic);
+            let other = PrivateKey {
+                d: other_key.d.clone(),
+                ..priv_key.clone()
+            };
+            assert_ne!(
+                implicit_rejection(&ct, &other, k),
+                implicit_rejection(&ct, &priv_key, k)
+            );
+        }
+        // schon ohne d erkennbar ungültig
+        assert!(decrypt_rsa_pkcs1v15(&vec![0xff; k], &priv_key).is_none());
+    }
+
+    #[test]
+    fn test_generate_rsa_key_fips() {
+        let options = RsaKeyOptions {
+            fips: true,
+            ..RsaKeyOptions::new(2048)
+        };
+        let (public, private) = generate_rsa_key(&options).unwrap();
+        assert_eq!(public.n.bits(), 2048);
+        assert!(validate_rsa_key(&public, Some(&private)).is_empty());
+        let m = BigUint::from(42u32);
+        assert_eq!(decrypt_rsa(&encrypt_rsa(&m, &public), &private), m);
+
+        // d modulo φ(n) statt λ(n), zu kleines n, ungültiger Modul
+        let (p, q) = recover_primes(&public, &private).unwrap();
+        let lambda = constant_time::lcm(&(&p - 1u32), &(&q - 1u32));
+        let unreduced = PrivateKey {
+            d: &private.d + &lambda,
+            ..private.clone()
+        };
+        assert_eq!(
+            validate_rsa_key(&public, Some(&unreduced)),
+            vec![RsaKeyIssue::PrivateExponentNotReduced]
+        );
+        let other = PrivateKey {
+            n: &private.n + 2u32,
+            ..private.clone()
+        };
+        assert_eq!(
+            validate_rsa_key(&public, Some(&other)),
+            vec![RsaKeyIssue::Inconsistent]
+        );
+        for d in [BigUint::zero(), public.n.clone()] {
+            let broken = PrivateKey {
+                d,
+                ..private.clone()
+            };
+            assert_eq!(
+                validate_rsa_key(&public, Some(&broken)),
+                vec![RsaKeyIssue::Inconsistent]
+            );
+        }
+        let bad = PublicKey {
+            e: BigUint::from(3u32),
+            n: (BigUint::one() << 2047) + 1u32,
+        };
+        assert_eq!(
+            validate_rsa_key(&bad, None),
+            vec![
+                RsaKeyIssue::Modulus("n ist durch 3 teilbar".into()),
+                RsaKeyIssue::PublicExponent
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_rsa_key_options() {
+        // beliebiger Exponent außerhalb des FIPS-Modus
+        let options = RsaKeyOptions {
+            e: BigUint::from(3u32),
+            ..RsaKeyOptions::new(1024)
+        };
+        let (public, private) = generate_rsa_key(&options).unwrap();
+        assert_eq!(public.e, BigUint::from(3u32));
+        assert_eq!(public.n.bits(), 1024);
+        assert!(private.d > BigUint::one() << 512);
+        let m = BigUint::from(1234567u32);
+        assert_eq!(decrypt_rsa(&encrypt_rsa(&m, &public), &private), m);
+        assert_eq!(
+            validate_rsa_key(&public, Some(&private)),
+            vec![
+                RsaKeyIssue::ModulusLength(1024),
+                RsaKeyIssue::PublicExponent
+            ]
+        );
+        // ungerade Länge: p bekommt das zusätzliche Bit
+        let (public, _) = generate_rsa_key(&RsaKeyOptions::new(777)).unwrap();
+        assert_eq!(public.n.bits(), 777);
+
+        let fips = |bits, e: u64| RsaKeyOptions {
+            e: BigUint::from(e),
+            fips: true,
+            ..RsaKeyOptions::new(bits)
+        };
+        assert!(generate_rsa_key(&fips(1024, 65537)).is_err());
+        assert!(generate_rsa_key(&fips(2049, 65537)).is_err());
+        assert!(generate_rsa_key(&fips(2048, 3)).is_err());
+        assert!(generate_rsa_key(&fips(2048, 65536)).is_err());
+        assert!(generate_rsa_key(&RsaKeyOptions::new(256)).is_err());
+    }
+
+    #[test]
+    fn test_recover_primes() {
+        let (pub_key, priv_key) = generate_rsa_keypair(512);
+        let (p, q) = recover_primes(&pub_key, &priv_key).unwrap();
+        assert!(p < q);
+        assert_eq!(&p * &q, pub_key.n);
+    }
 }
@@ -0,0 +1,85 @@
+use rand::rngs::OsRng;
+use x25519_dalek::StaticSecret;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    pub bytes: [u8; 32],
+}
+
+#[derive(Clone)]
+pub struct PrivateKey {
+    pub bytes: [u8; 32],
+}
+
+/// Generiert ein X25519-Schlüsselpaar (RFC 7748).
+pub fn generate_x25519_keypair() -> (PublicKey, PrivateKey) {
+    let secret = StaticSecret::random_from_rng(OsRng);
+    let priv_key = PrivateKey {
+        bytes: secret.to_bytes(),
+    };
+    (public_key_from_private(&priv_key), priv_key)
+}
+
+/// Leitet den öffentlichen Schlüssel aus dem privaten Skalar ab.
+pub fn public_key_from_private(priv_key: &PrivateKey) -> PublicKey {
+    let secret = StaticSecret::from(priv_key.bytes);
+    PublicKey {
+        bytes: x25519_dalek::PublicKey::from(&secret).to_bytes(),
+    }
+}
+
+/// X25519-Diffie-Hellman: gemeinsames Geheimnis aus eigenem privaten und fremdem öffentlichen Schlüssel.
+///
+/// Gibt None zurück, wenn das Ergebnis der Nullpunkt ist (Punkt kleiner Ordnung).
+pub fn diffie_hellman(priv_key: &PrivateKey, pub_key: &PublicKey) -> Option<[u8; 32]> {
+    let secret = StaticSecret::from(priv_key.bytes);
+    let shared = secret.diffie_hellman(&x25519_dalek::PublicKey::from(pub_key.bytes));
+    if shared.was_contributory() {
+        Some(shared.to_bytes())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_secret_matches() {
+        let (pub_a, priv_a) = generate_x25519_keypair();
+        let (pub_b, priv_b) = generate_x25519_keypair();
+        assert_eq!(
+            diffie_hellman(&priv_a, &pub_b).unwrap(),
+            diffie_hellman(&priv_b, &pub_a).unwrap()
+        );
+        assert_eq!(public_key_from_private(&priv_a), pub_a);
+    }
+
+    #[test]
+    fn test_rfc7748_vector() {
+        let alice = PrivateKey {
+            bytes: hex::decode("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        };
+        let bob = PublicKey {
+            bytes: hex::decode("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        };
+        assert_eq!(
+            hex::encode(diffie_hellman(&alice, &bob).unwrap()),
+            "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742"
+        );
+    }
+
+    #[test]
+    fn test_low_order_point_rejected() {
+        let (_, priv_key) = generate_x25519_keypair();
+        let zero = PublicKey { bytes: [0u8; 32] };
+        assert!(diffie_hellman(&priv_key, &zero).is_none());
+    }
+}
@@ -0,0 +1,373 @@
+//! Verschlüsselte Inhalte: AuthEnvelopedData (RFC 5083) mit AES-256-GCM und
+//! EnvelopedData (RFC 5652, 6) mit AES-CBC, jeweils mit KeyTransRecipientInfo und RSA-OAEP.
+
+use crate::asymmetric::rsa;
+use crate::cms::{content_info, oid, read_content_info, CertificateId};
+use crate::symmetric::aes;
+use crate::utils::der::{self, Oid, Reader};
+use crate::utils::error::CryptoError;
+use crate::x509::{Certificate, KeyUsage, SubjectPublicKey};
+use rand::RngCore;
+
+/// Länge des GCM-Authentifizierungs-Tags.
+const GCM_TAG_LEN: usize = 16;
+
+/// Verschlüsselt `content` für die RSA-Schlüssel der Empfängerzertifikate
+/// (AuthEnvelopedData, AES-256-GCM, RSA-OAEP mit SHA-256) und gibt die ContentInfo zurück.
+pub fn encrypt(content: &[u8], recipients: &[Certificate]) -> Result<Vec<u8>, CryptoError> {
+    if recipients.is_empty() {
+        return Err(CryptoError::InvalidFormat(
+            "mindestens ein Empfänger erforderlich".to_string(),
+        ));
+    }
+    let mut cek = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut cek);
+
+    let mut infos = Vec::new();
+    for cert in recipients {
+        let SubjectPublicKey::Rsa(public) = &cert.public_key else {
+            return Err(CryptoError::Unsupported(format!(
+                "Empfänger \"{}\": nur RSA-Schlüssel",
+                cert.subject
+            )));
+        };
+        if cert
+            .key_usage()?
+            .is_some_and(|usage| !usage.contains(KeyUsage::KEY_ENCIPHERMENT))
+        {
+            return Err(CryptoError::InvalidCertificate(format!(
+                "\"{}\": Schlüssel nicht für Schlüsseltransport zugelassen",
+                cert.subject
+            )));
+        }
+        infos.push(der::sequence(&[
+            der::small_uint(0),
+            CertificateId::for_certificate(cert).to_der(),
+            KeyTransport::OaepSha256.to_der(),
+            der::octet_string(&KeyTransport::OaepSha256.wrap(&cek, public)),
+        ]));
+    }
+
+    let (nonce, mut ciphertext) = aes::encrypt_aes256_gcm(&cek, content, b"");
+    let tag = ciphertext.split_off(ciphertext.len() - GCM_TAG_LEN);
+    let algorithm = der::sequence(&[
+        der::oid(oid::AES256_GCM),
+        der::sequence(&[
+            der::octet_string(&nonce),
+            der::small_uint(GCM_TAG_LEN as u64),
+        ]),
+    ]);
+    let auth_enveloped = der::sequence(&[
+        der::small_uint(0),
+        der::set_of(&infos),
+        der::sequence(&[
+            der::oid(oid::DATA),
+            algorithm,
+            der::tlv(der::context_primitive(0), &ciphertext),
+        ]),
+        der::octet_string(&tag),
+    ]);
+    Ok(content_info(oid::AUTH_ENVELOPED_DATA, &auth_enveloped))
+}
+
+/// Entschlüsselt AuthEnvelopedData oder EnvelopedData mit dem RSA-Schlüssel zu `cert`.
+pub fn decrypt(
+    data: &[u8],
+    cert: &Certificate,
+    key: &rsa::PrivateKey,
+) -> Result<Vec<u8>, CryptoError> {
+    EnvelopedData::from_bytes(data)?.decrypt(cert, key)
+}
+
+/// RSA-Schlüsseltransport (RSAES-OAEP, RFC 4055, 3.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyTransport {
+    /// Standardparameter: SHA-1 und MGF1-SHA-1.
+    OaepSha1,
+    OaepSha256,
+}
+
+impl KeyTransport {
+    fn read(reader: &mut Reader) -> Result<Option<Self>, CryptoError> {
+        let mut alg = reader.sequence()?;
+        if !alg.oid()?.is(oid::RSAES_OAEP) {
+            return Ok(None);
+        }
+        if alg.is_empty() {
+            return Ok(Some(KeyTransport::OaepSha1));
+        }
+        let mut params = alg.sequence()?;
+        alg.finish()?;
+        let hash = match params.optional(der::context(0))? {
+            Some(inner) => read_hash(&mut Reader::new(inner))?,
+            None => Oid::from_arcs(oid::SHA1),
+        };
+        let mgf_hash = match params.optional(der::context(1))? {
+            Some(inner) => {
+                let mut mgf = Reader::new(inner).sequence()?;
+                if !mgf.oid()?.is(oid::MGF1) {
+                    return Err(CryptoError::Unsupported("OAEP-Maskenfunktion".to_string()));
+                }
+                let hash = read_hash(&mut mgf)?;
+                mgf.finish()?;
+                hash
+            }
+            None => Oid::from_arcs(oid::SHA1),
+        };
+        if !params.is_empty() {
+            return Err(CryptoError::Unsupported("OAEP-Label".to_string()));
+        }
+        match (hash == mgf_hash, hash.arcs()) {
+            (true, arcs) if arcs == oid::SHA1 => Ok(Some(KeyTransport::OaepSha1)),
+            (true, arcs) if arcs == oid::SHA256 => Ok(Some(KeyTransport::OaepSha256)),
+            _ => Err(CryptoError::Unsupported(format!("OAEP mit Hash {}", hash))),
+        }
+    }
+
+    fn to_der(self) -> Vec<u8> {
+        match self {
+            KeyTransport::OaepSha1 => {
+                der::sequence(&[der::oid(oid::RSAES_OAEP), der::sequence(&[])])
+            }
+            KeyTransport::OaepSha256 => {
+                let sha256 = der::sequence(&[der::oid(oid::SHA256)]);
+                der::sequence(&[
+                    der::oid(oid::RSAES_OAEP),
+                    der::sequence(&[
+                        der::explicit(0, &sha256),
+                        der::explicit(1, &der::sequence(&[der::oid(oid::MGF1), sha256.clone()])),
+                    ]),
+                ])
+            }
+        }
+    }
+
+    fn wrap(self, cek: &[u8], public: &rsa::PublicKey) -> Vec<u8> {
+        match self {
+            KeyTransport::OaepSha1 => rsa::encrypt_rsa_oaep_sha1(cek, b"", public),
+            KeyTransport::OaepSha256 => rsa::encrypt_rsa_oaep(cek, b"", public),
+        }
+    }
+
+    fn unwrap(self, encrypted_key: &[u8], private: &rsa::PrivateKey) -> Option<Vec<u8>> {
+        match self {
+            KeyTransport::OaepSha1 => rsa::decrypt_rsa_oaep_sha1(encrypted_key, b"", private),
+            KeyTransport::OaepSha256 => rsa::decrypt_rsa_oaep(encrypted_key, b"", private),
+        }
+    }
+}
+
+/// Hash-AlgorithmIdentifier der OAEP-Parameter (Parameter fehlen oder sind NULL).
+fn read_hash(reader: &mut Reader) -> Result<Oid, CryptoError> {
+    let mut alg = reader.sequence()?;
+    let hash = alg.oid()?;
+    if !alg.is_empty() {
+        alg.null()?;
+    }
+    alg.finish()?;
+    Ok(hash)
+}
+
+/// Empfängereintrag (KeyTransRecipientInfo) mit RSA-OAEP.
+#[derive(Debug, Clone)]
+pub struct RecipientInfo {
+    pub rid: CertificateId,
+    pub key_transport: KeyTransport,
+    pub encrypted_key: Vec<u8>,
+}
+
+/// Inhaltsverschlüsselung mit ihren Parametern.
+#[derive(Debug, Clone)]
+enum ContentEncryption {
+    Aes256Gcm { nonce: Vec<u8>, tag: Vec<u8> },
+    AesCbc { key_len: usize, iv: [u8; 16] },
+}
+
+/// Gelesenes AuthEnvelopedData oder EnvelopedData.
+#[derive(Debug, Clone)]
+pub struct EnvelopedData {
+    /// Unterstützte Empfängereinträge; andere Arten (z. B. Schlüsselvereinbarung) fehlen hier.
+    pub recipients: Vec<RecipientInfo>,
+    pub content_type: Oid,
+    encryption: ContentEncryption,
+    encrypted_content: Vec<u8>,
+    /// Kodierung der authentifizierten Attribute als `SET OF` (zusätzliche GCM-Daten).
+    aad: Vec<u8>,
+}
+
+impl EnvelopedData {
+    /// Liest eine ContentInfo aus DER oder PEM.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, CryptoError> {
+        let (content_type, content) = read_content_info(data)?;
+        let authenticated = if content_type.is(oid::AUTH_ENVELOPED_DATA) {
+            true
+        } else if content_type.is(oid::ENVELOPED_DATA) {
+            false
+        } else {
+            return Err(CryptoError::InvalidFormat(format!(
+                "keine verschlüsselten Daten, sondern {}",
+                content_type
+            )));
+        };
+        let mut outer = Reader::new(&content);
+        let mut env = outer.sequence()?;
+        outer.finish()?;
+        env.small_uint()?;
+        env.optional(der::context(0))?;
+
+        let mut infos = env.nested(der::TAG_SET)?;
+        let mut recipients = Vec::new();
+        while !infos.is_empty() {
+            // nur KeyTransRecipientInfo (SEQUENCE); kari, kekri, pwri und ori werden übergangen
+            if infos.peek_tag() != Some(der::TAG_SEQUENCE) {
+                infos.read_any()?;
+                continue;
+            }
+            let mut ktri = infos.sequence()?;
+            ktri.small_uint()?;
+            let rid = CertificateId::read(&mut ktri)?;
+            let key_transport = KeyTransport::read(&mut ktri)?;
+            let encrypted_key = ktri.octet_string()?.to_vec();
+            ktri.finish()?;
+            if let Some(key_transport) = key_transport {
+                recipients.push(RecipientInfo {
+                    rid,
+                    key_transport,
+                    encrypted_key,
+                });
+            }
+        }
+        if recipients.is_empty() {
+            return Err(CryptoError::Unsupported(
+                "keine Empfänger mit RSA-OAEP-Schlüsseltransport".to_string(),
+            ));
+        }
+
+        let mut info = env.sequence()?;
+        let content_type = info.oid()?;
+        let mut algorithm = info.sequence()?;
+        let algorithm_oid = algorithm.oid()?;
+        let encrypted_content = info
+            .optional(der::context_primitive(0))?
+            .ok_or_else(|| {
+                CryptoError::Unsupported("abgesetzter verschlüsselter Inhalt".to_string())
+            })?
+            .to_vec();
+        info.finish()?;
+
+        let mut encryption = if algorithm_oid.is(oid::AES256_GCM) && authenticated {
+            let mut params = algorithm.sequence()?;
+            let nonce = params.octet_string()?.to_vec();
+            let tag_len = if params.is_empty() {
+                12
+            } else {
+                params.small_uint()?
+            };
+            params.finish()?;
+            if nonce.len() != 12 || tag_len != GCM_TAG_LEN as u64 {
+                return Err(CryptoError::Unsupported(
+                    "GCM-Parameter (Nonce 12 Byte, Tag 16 Byte erforderlich)".to_string(),
+                ));
+            }
+            ContentEncryption::Aes256Gcm {
+                nonce,
+                tag: Vec::new(),
+            }
+        } else if !authenticated {
+            let key_len = [
+                (oid::AES128_CBC, 16),
+                (oid::AES192_CBC, 24),
+                (oid::AES256_CBC, 32),
+            ]
+            .into_iter()
+            .find(|(arcs, _)| algorithm_oid.is(arcs))
+            .map(|(_, len)| len)
+            .ok_or_else(|| {
+                CryptoError::Unsupported(format!("Inhaltsverschlüsselung {}", algorithm_oid))
+            })?;
+            let iv = algorithm.octet_string()?.try_into().map_err(|_| {
+                CryptoError::InvalidFormat("AES-CBC-IV muss 16 Byte lang sein".to_string())
+            })?;
+            ContentEncryption::AesCbc { key_len, iv }
+        } else {
+            return Err(CryptoError::Unsupported(format!(
+                "authentifizierte Inhaltsverschlüsselung {}",
+                algorithm_oid
+            )));
+        };
+        algorithm.finish()?;
+
+        let mut aad = Vec::new();
+        if authenticated {
+            if let Some(attributes) = env.optional(der::context(1))? {
+                aad = der::tlv(der::TAG_SET, attributes);
+            }
+            let mac = env.octet_string()?.to_vec();
+            if let ContentEncryption::Aes256Gcm { tag, .. } = &mut encryption {
+                *tag = mac;
+            }
+        }
+        env.optional(der::context(2))?;
+        env.optional(der::context(1))?;
+        env.finish()?;
+
+        Ok(EnvelopedData {
+            recipients,
+            content_type,
+            encryption,
+            encrypted_content,
+            aad,
+        })
+    }
+
+    /// Name der Inhaltsverschlüsselung, z. B. "AES-256-GCM".
+    pub fn content_encryption(&self) -> String {
+        match &self.encryption {
+            ContentEncryption::Aes256Gcm { .. } => "AES-256-GCM".to_string(),
+            ContentEncryption::AesCbc { key_len, .. } => format!("AES-{}-CBC", key_len * 8),
+        }
+    }
+
+    /// Entschlüsselt mit dem RSA-Schlüssel des Empfängerzertifikats `cert`.
+    pub fn decrypt(
+        &self,
+        cert: &Certificate,
+        key: &rsa::PrivateKey,
+    ) -> Result<Vec<u8>, CryptoError> {
+        let mut matching = self
+            .recipients
+            .iter()
+            .filter(|recipient| recipient.rid.matches(cert))
+            .peekable();
+        if matching.peek().is_none() {
+            return Err(CryptoError::NoMatchingRecipient);
+        }
+        for recipient in matching {
+            if let Some(cek) = recipient
+                .key_transport
+                .unwrap(&recipient.encrypted_key, key)
+            {
+                return self.decrypt_content(&cek);
+            }
+        }
+        Err(CryptoError::DecryptionFailed)
+    }
+
+    fn decrypt_content(&self, cek: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        match &self.encryption {
+            ContentEncryption::Aes256Gcm { nonce, tag } => {
+                let cek: &[u8; 32] = cek.try_into().map_err(|_| CryptoError::DecryptionFailed)?;
+                let sealed = [self.encrypted_content.as_slice(), tag].concat();
+                aes::decrypt_aes256_gcm(cek, nonce, &sealed, &self.aad)
+                    .ok_or(CryptoError::DecryptionFailed)
+            }
+            ContentEncryption::AesCbc { key_len, iv } => {
+                if cek.len() != *key_len {
+                    return Err(CryptoError::DecryptionFailed);
+                }
+                aes::decrypt_aes_cbc(cek, iv, &self.encrypted_content)
+                    .ok_or(CryptoError::DecryptionFailed)
+            }
+        }
+    }
+}
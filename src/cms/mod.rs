@@ -0,0 +1,194 @@
+//! Cryptographic Message Syntax (RFC 5652) für signierte und verschlüsselte Dokumente.
+//!
+//! SignedData wird angehängt oder abgesetzt mit signierten Attributen (contentType,
+//! messageDigest, signingTime) erzeugt und geprüft; Signaturschlüssel und Zertifikate
+//! stammen aus [`crate::x509`]. Verschlüsselt wird als AuthEnvelopedData (RFC 5083) mit
+//! AES-256-GCM und RSA-OAEP-Schlüsseltransport (RFC 8017, RFC 4055); beim Entschlüsseln
+//! wird zusätzlich klassisches EnvelopedData mit AES-CBC gelesen. Das Ergebnis ist mit
+//! `openssl cms -inform DER` lesbar.
+
+pub mod enveloped;
+pub mod signed;
+
+pub use enveloped::{decrypt, encrypt, EnvelopedData, KeyTransport, RecipientInfo};
+pub use signed::{sign, SignedData, SignerInfo};
+
+use crate::utils::der::{self, Oid, Reader};
+use crate::utils::error::CryptoError;
+use crate::x509::{pem_decode, pem_encode, Certificate, Name};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::fmt;
+
+/// PEM-Label von `openssl cms -outform PEM`.
+pub const PEM_CMS: &str = "CMS";
+
+/// Objektkennungen aus RFC 5652, RFC 5083, RFC 5084, RFC 4055 und RFC 3565.
+pub mod oid {
+    pub const DATA: &[u64] = &[1, 2, 840, 113549, 1, 7, 1];
+    pub const SIGNED_DATA: &[u64] = &[1, 2, 840, 113549, 1, 7, 2];
+    pub const ENVELOPED_DATA: &[u64] = &[1, 2, 840, 113549, 1, 7, 3];
+    pub const AUTH_ENVELOPED_DATA: &[u64] = &[1, 2, 840, 113549, 1, 9, 16, 1, 23];
+
+    pub const CONTENT_TYPE: &[u64] = &[1, 2, 840, 113549, 1, 9, 3];
+    pub const MESSAGE_DIGEST: &[u64] = &[1, 2, 840, 113549, 1, 9, 4];
+    pub const SIGNING_TIME: &[u64] = &[1, 2, 840, 113549, 1, 9, 5];
+
+    pub const SHA1: &[u64] = &[1, 3, 14, 3, 2, 26];
+    pub const SHA256: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 2, 1];
+    pub const SHA384: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 2, 2];
+    pub const SHA512: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 2, 3];
+
+    pub const RSAES_OAEP: &[u64] = &[1, 2, 840, 113549, 1, 1, 7];
+    pub const MGF1: &[u64] = &[1, 2, 840, 113549, 1, 1, 8];
+
+    pub const AES128_CBC: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 1, 2];
+    pub const AES192_CBC: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 1, 22];
+    pub const AES256_CBC: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 1, 42];
+    pub const AES256_GCM: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 1, 46];
+}
+
+/// Hashverfahren für messageDigest und Signaturen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    /// Liest einen AlgorithmIdentifier; Parameter fehlen oder sind NULL.
+    pub fn read(reader: &mut Reader) -> Result<Self, CryptoError> {
+        let mut alg = reader.sequence()?;
+        let alg_oid = alg.oid()?;
+        if !alg.is_empty() {
+            alg.null()?;
+        }
+        alg.finish()?;
+        [
+            (oid::SHA256, DigestAlgorithm::Sha256),
+            (oid::SHA384, DigestAlgorithm::Sha384),
+            (oid::SHA512, DigestAlgorithm::Sha512),
+        ]
+        .into_iter()
+        .find(|(arcs, _)| alg_oid.is(arcs))
+        .map(|(_, alg)| alg)
+        .ok_or_else(|| CryptoError::Unsupported(format!("Hashverfahren {}", alg_oid)))
+    }
+
+    /// AlgorithmIdentifier ohne Parameter (RFC 5754, 2).
+    pub fn to_der(self) -> Vec<u8> {
+        der::sequence(&[der::oid(self.arcs())])
+    }
+
+    fn arcs(self) -> &'static [u64] {
+        match self {
+            DigestAlgorithm::Sha256 => oid::SHA256,
+            DigestAlgorithm::Sha384 => oid::SHA384,
+            DigestAlgorithm::Sha512 => oid::SHA512,
+        }
+    }
+
+    pub fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            DigestAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+            DigestAlgorithm::Sha384 => Sha384::digest(data).to_vec(),
+            DigestAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+        }
+    }
+}
+
+/// Kodiert eine ContentInfo `SEQUENCE { contentType, [0] EXPLICIT content }`.
+fn content_info(content_type: &[u64], content: &[u8]) -> Vec<u8> {
+    der::sequence(&[der::oid(content_type), der::explicit(0, content)])
+}
+
+/// Liest eine ContentInfo aus DER oder PEM und gibt Inhaltstyp und Inhalt zurück.
+fn read_content_info(data: &[u8]) -> Result<(Oid, Vec<u8>), CryptoError> {
+    let der = if data.starts_with(b"-----BEGIN") {
+        let text = String::from_utf8_lossy(data);
+        pem_decode(&text, PEM_CMS).or_else(|_| pem_decode(&text, "PKCS7"))?
+    } else {
+        data.to_vec()
+    };
+    let mut outer = Reader::new(&der);
+    let mut info = outer.sequence()?;
+    outer.finish()?;
+    let content_type = info.oid()?;
+    let content = info.read(der::context(0))?.to_vec();
+    info.finish()?;
+    Ok((content_type, content))
+}
+
+/// Kodiert eine CMS-Struktur (DER) als PEM.
+pub fn to_pem(der: &[u8]) -> String {
+    pem_encode(PEM_CMS, der)
+}
+
+/// Kodiert Elemente als DER-`SET OF` mit implizitem Kontext-Tag `[n]`.
+fn implicit_set(n: u8, items: &[Vec<u8>]) -> Vec<u8> {
+    let set = der::set_of(items);
+    let content = Reader::new(&set).read(der::TAG_SET).unwrap().to_vec();
+    der::tlv(der::context(n), &content)
+}
+
+/// Verweis auf das Zertifikat eines Unterzeichners oder Empfängers
+/// (SignerIdentifier bzw. RecipientIdentifier, RFC 5652, 5.3 und 6.2.1).
+#[derive(Debug, Clone)]
+pub enum CertificateId {
+    IssuerAndSerial { issuer: Name, serial: BigUint },
+    KeyId(Vec<u8>),
+}
+
+impl CertificateId {
+    pub fn for_certificate(cert: &Certificate) -> Self {
+        CertificateId::IssuerAndSerial {
+            issuer: cert.issuer.clone(),
+            serial: cert.serial.clone(),
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, CryptoError> {
+        if let Some(key_id) = reader.optional(der::context_primitive(0))? {
+            return Ok(CertificateId::KeyId(key_id.to_vec()));
+        }
+        let mut ias = reader.sequence()?;
+        let issuer = Name::from_der(ias.read_raw(der::TAG_SEQUENCE)?)?;
+        let serial = ias.uint()?;
+        ias.finish()?;
+        Ok(CertificateId::IssuerAndSerial { issuer, serial })
+    }
+
+    fn to_der(&self) -> Vec<u8> {
+        match self {
+            CertificateId::IssuerAndSerial { issuer, serial } => {
+                der::sequence(&[issuer.to_der().to_vec(), der::uint(serial)])
+            }
+            CertificateId::KeyId(key_id) => der::tlv(der::context_primitive(0), key_id),
+        }
+    }
+
+    /// Prüft, ob der Verweis auf `cert` zeigt.
+    pub fn matches(&self, cert: &Certificate) -> bool {
+        match self {
+            CertificateId::IssuerAndSerial { issuer, serial } => {
+                cert.serial == *serial && cert.issuer.matches(issuer)
+            }
+            CertificateId::KeyId(key_id) => match cert.subject_key_id() {
+                Ok(Some(id)) => id == *key_id,
+                _ => cert.public_key.key_id() == *key_id,
+            },
+        }
+    }
+}
+
+impl fmt::Display for CertificateId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CertificateId::IssuerAndSerial { issuer, serial } => {
+                write!(f, "Aussteller \"{}\", Seriennummer {:x}", issuer, serial)
+            }
+            CertificateId::KeyId(key_id) => write!(f, "Schlüsselkennung {}", hex::encode(key_id)),
+        }
+    }
+}
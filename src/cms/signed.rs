@@ -0,0 +1,414 @@
+//! SignedData (RFC 5652, 5) mit signierten Attributen, angehängt oder abgesetzt.
+
+use crate::cms::{
+    content_info, implicit_set, oid, read_content_info, CertificateId, DigestAlgorithm,
+};
+use crate::utils::der::{self, Oid, Reader};
+use crate::utils::error::CryptoError;
+use crate::x509::{
+    self, verify_path, Certificate, SignatureAlgorithm, SigningKey, SubjectPublicKey,
+};
+use crate::x509::{TrustStore, VerifyOptions};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Signiert `content` mit dem Schlüssel zu `certificate` und gibt die ContentInfo (DER) zurück.
+///
+/// Das Unterzeichnerzertifikat und `chain` werden mitgeliefert; bei `detached` enthält
+/// die Struktur nur die Signatur. Ed25519 verwendet SHA-512 als Hash (RFC 8419),
+/// RSA und ECDSA SHA-256.
+pub fn sign(
+    content: &[u8],
+    certificate: &Certificate,
+    key: &SigningKey,
+    chain: &[Certificate],
+    detached: bool,
+) -> Result<Vec<u8>, CryptoError> {
+    if key.public_key() != certificate.public_key {
+        return Err(CryptoError::InvalidFormat(
+            "Schlüssel passt nicht zum Zertifikat".to_string(),
+        ));
+    }
+    let digest_algorithm = match key {
+        SigningKey::Ed25519 { .. } => DigestAlgorithm::Sha512,
+        _ => DigestAlgorithm::Sha256,
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Systemzeit vor 1970")
+        .as_secs() as i64;
+    let attributes = [
+        attribute(oid::CONTENT_TYPE, der::oid(oid::DATA)),
+        attribute(oid::SIGNING_TIME, der::time(now)),
+        attribute(
+            oid::MESSAGE_DIGEST,
+            der::octet_string(&digest_algorithm.digest(content)),
+        ),
+    ];
+    // signiert wird die Kodierung als SET OF, übertragen mit implizitem Tag [0]
+    let signed_attributes = der::set_of(&attributes);
+    let signature = key.sign(&signed_attributes)?;
+
+    let signer_info = der::sequence(&[
+        der::small_uint(1),
+        CertificateId::for_certificate(certificate).to_der(),
+        digest_algorithm.to_der(),
+        implicit_set(0, &attributes),
+        key.signature_algorithm().to_der(),
+        der::octet_string(&signature),
+    ]);
+
+    let mut encapsulated = vec![der::oid(oid::DATA)];
+    if !detached {
+        encapsulated.push(der::explicit(0, &der::octet_string(content)));
+    }
+    let mut certificates = vec![certificate.to_der().to_vec()];
+    for cert in chain {
+        if cert.to_der() != certificate.to_der() {
+            certificates.push(cert.to_der().to_vec());
+        }
+    }
+    let signed_data = der::sequence(&[
+        der::small_uint(1),
+        der::set_of(&[digest_algorithm.to_der()]),
+        der::sequence(&encapsulated),
+        implicit_set(0, &certificates),
+        der::set_of(&[signer_info]),
+    ]);
+    Ok(content_info(oid::SIGNED_DATA, &signed_data))
+}
+
+fn attribute(attr: &[u64], value: Vec<u8>) -> Vec<u8> {
+    der::sequence(&[der::oid(attr), der::set_of(&[value])])
+}
+
+/// Angaben eines Unterzeichners (SignerInfo).
+#[derive(Debug, Clone)]
+pub struct SignerInfo {
+    pub sid: CertificateId,
+    pub digest_algorithm: DigestAlgorithm,
+    pub signature_algorithm: SignatureAlgorithm,
+    pub signature: Vec<u8>,
+    /// Zeitpunkt aus dem Attribut signingTime (Angabe des Unterzeichners, ungeprüft).
+    pub signing_time: Option<i64>,
+    /// Signierte Attribute, neu kodiert als `SET OF` (Grundlage der Signatur).
+    signed_attributes: Option<Vec<u8>>,
+    content_type: Option<Oid>,
+    message_digest: Option<Vec<u8>>,
+}
+
+impl SignerInfo {
+    fn read(reader: &mut Reader) -> Result<Self, CryptoError> {
+        let mut si = reader.sequence()?;
+        let version = si.small_uint()?;
+        if version != 1 && version != 3 {
+            return Err(CryptoError::Unsupported(format!(
+                "SignerInfo-Version {}",
+                version
+            )));
+        }
+        let sid = CertificateId::read(&mut si)?;
+        let digest_algorithm = DigestAlgorithm::read(&mut si)?;
+        let signed_attributes = si
+            .optional(der::context(0))?
+            .map(|content| der::tlv(der::TAG_SET, content));
+        let signature_algorithm = read_signature_algorithm(&mut si, digest_algorithm)?;
+        let signature = si.octet_string()?.to_vec();
+        si.optional(der::context(1))?;
+        si.finish()?;
+
+        let mut info = SignerInfo {
+            sid,
+            digest_algorithm,
+            signature_algorithm,
+            signature,
+            signing_time: None,
+            signed_attributes: None,
+            content_type: None,
+            message_digest: None,
+        };
+        if let Some(encoded) = signed_attributes {
+            info.read_attributes(&encoded)?;
+            info.signed_attributes = Some(encoded);
+        }
+        Ok(info)
+    }
+
+    fn read_attributes(&mut self, encoded: &[u8]) -> Result<(), CryptoError> {
+        let mut outer = Reader::new(encoded);
+        let mut attributes = outer.nested(der::TAG_SET)?;
+        outer.finish()?;
+        while !attributes.is_empty() {
+            let mut attribute = attributes.sequence()?;
+            let attr = attribute.oid()?;
+            let mut values = attribute.nested(der::TAG_SET)?;
+            attribute.finish()?;
+            let known = [oid::CONTENT_TYPE, oid::MESSAGE_DIGEST, oid::SIGNING_TIME];
+            if !known.iter().any(|arcs| attr.is(arcs)) {
+                continue;
+            }
+            let duplicate = if attr.is(oid::CONTENT_TYPE) {
+                self.content_type.replace(values.oid()?).is_some()
+            } else if attr.is(oid::MESSAGE_DIGEST) {
+                let digest = values.octet_string()?.to_vec();
+                self.message_digest.replace(digest).is_some()
+            } else {
+                self.signing_time.replace(values.time()?).is_some()
+            };
+            values.finish()?;
+            if duplicate {
+                return Err(CryptoError::InvalidFormat(format!(
+                    "Attribut {} mehrfach vorhanden",
+                    attr
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Prüft Signatur und signierte Attribute gegen den Inhalt.
+    fn verify(
+        &self,
+        content_type: &Oid,
+        content: &[u8],
+        key: &SubjectPublicKey,
+    ) -> Result<(), CryptoError> {
+        let message = match &self.signed_attributes {
+            Some(encoded) => {
+                if self.content_type.as_ref() != Some(content_type) {
+                    return Err(CryptoError::InvalidFormat(
+                        "Attribut contentType fehlt oder passt nicht".to_string(),
+                    ));
+                }
+                let digest = self.message_digest.as_ref().ok_or_else(|| {
+                    CryptoError::InvalidFormat("Attribut messageDigest fehlt".to_string())
+                })?;
+                if *digest != self.digest_algorithm.digest(content) {
+                    return Err(CryptoError::InvalidSignature);
+                }
+                encoded.as_slice()
+            }
+            // ohne Attribute nur für den Inhaltstyp data zulässig (RFC 5652, 5.3)
+            None if content_type.is(oid::DATA) => content,
+            None => {
+                return Err(CryptoError::InvalidFormat(
+                    "signierte Attribute fehlen".to_string(),
+                ))
+            }
+        };
+        if self
+            .signature_algorithm
+            .verify(key, message, &self.signature)
+        {
+            Ok(())
+        } else {
+            Err(CryptoError::InvalidSignature)
+        }
+    }
+}
+
+/// Liest den Signaturalgorithmus; `rsaEncryption` (wie von OpenSSL geschrieben) wird
+/// zusammen mit dem Hashverfahren als RSASSA-PKCS1-v1_5 gedeutet.
+fn read_signature_algorithm(
+    reader: &mut Reader,
+    digest_algorithm: DigestAlgorithm,
+) -> Result<SignatureAlgorithm, CryptoError> {
+    let raw = reader.read_raw(der::TAG_SEQUENCE)?;
+    let mut alg = Reader::new(raw).sequence()?;
+    if alg.oid()?.is(x509::oid::RSA_ENCRYPTION) {
+        if !alg.is_empty() {
+            alg.null()?;
+        }
+        alg.finish()?;
+        return Ok(match digest_algorithm {
+            DigestAlgorithm::Sha256 => SignatureAlgorithm::RsaSha256,
+            DigestAlgorithm::Sha384 => SignatureAlgorithm::RsaSha384,
+            DigestAlgorithm::Sha512 => SignatureAlgorithm::RsaSha512,
+        });
+    }
+    SignatureAlgorithm::read(&mut Reader::new(raw))
+}
+
+/// Gelesene SignedData-Struktur.
+#[derive(Debug, Clone)]
+pub struct SignedData {
+    /// Inhaltstyp (eContentType), in der Regel `id-data`.
+    pub content_type: Oid,
+    /// Signierter Inhalt; `None` bei abgesetzter Signatur.
+    pub content: Option<Vec<u8>>,
+    /// Mitgelieferte Zertifikate.
+    pub certificates: Vec<Certificate>,
+    pub signers: Vec<SignerInfo>,
+}
+
+impl SignedData {
+    /// Liest eine ContentInfo mit SignedData aus DER oder PEM (`CMS` bzw. `PKCS7`).
+    pub fn from_bytes(data: &[u8]) -> Result<Self, CryptoError> {
+        let (content_type, content) = read_content_info(data)?;
+        if !content_type.is(oid::SIGNED_DATA) {
+            return Err(CryptoError::InvalidFormat(format!(
+                "kein SignedData, sondern {}",
+                content_type
+            )));
+        }
+        let mut outer = Reader::new(&content);
+        let mut sd = outer.sequence()?;
+        outer.finish()?;
+        let version = sd.small_uint()?;
+        if !(1..=5).contains(&version) {
+            return Err(CryptoError::Unsupported(format!(
+                "SignedData-Version {}",
+                version
+            )));
+        }
+        sd.nested(der::TAG_SET)?;
+
+        let mut encapsulated = sd.sequence()?;
+        let content_type = encapsulated.oid()?;
+        let content = match encapsulated.optional(der::context(0))? {
+            Some(explicit) => {
+                let mut inner = Reader::new(explicit);
+                let content = inner.octet_string()?.to_vec();
+                inner.finish()?;
+                Some(content)
+            }
+            None => None,
+        };
+        encapsulated.finish()?;
+
+        let mut certificates = Vec::new();
+        if let Some(set) = sd.optional(der::context(0))? {
+            let mut set = Reader::new(set);
+            while !set.is_empty() {
+                // andere CertificateChoices (Attributzertifikate usw.) werden übergangen
+                let choice = set.read_any()?;
+                if choice.tag == der::TAG_SEQUENCE {
+                    certificates.push(Certificate::from_der(choice.raw)?);
+                }
+            }
+        }
+        sd.optional(der::context(1))?;
+
+        let mut infos = sd.nested(der::TAG_SET)?;
+        sd.finish()?;
+        let mut signers = Vec::new();
+        while !infos.is_empty() {
+            signers.push(SignerInfo::read(&mut infos)?);
+        }
+        Ok(SignedData {
+            content_type,
+            content,
+            certificates,
+            signers,
+        })
+    }
+
+    /// Prüft alle Unterzeichner und gibt ihre Zertifikate zurück.
+    ///
+    /// `detached` ist der Inhalt einer abgesetzten Signatur; `extra` ergänzt die
+    /// mitgelieferten Zertifikate. Vertrauenswürdigkeit prüft erst [`Self::verify_trusted`].
+    pub fn verify(
+        &self,
+        detached: Option<&[u8]>,
+        extra: &[Certificate],
+    ) -> Result<Vec<Certificate>, CryptoError> {
+        let content = match (&self.content, detached) {
+            (Some(_), Some(_)) => {
+                return Err(CryptoError::InvalidFormat(
+                    "Inhalt ist bereits in der Signatur enthalten".to_string(),
+                ))
+            }
+            (Some(content), None) => content.as_slice(),
+            (None, Some(content)) => content,
+            (None, None) => {
+                return Err(CryptoError::InvalidFormat(
+                    "abgesetzte Signatur: signierter Inhalt fehlt".to_string(),
+                ))
+            }
+        };
+        if self.signers.is_empty() {
+            return Err(CryptoError::InvalidFormat(
+                "keine Unterzeichner enthalten".to_string(),
+            ));
+        }
+        self.signers
+            .iter()
+            .map(|signer| {
+                let cert = self
+                    .certificates
+                    .iter()
+                    .chain(extra)
+                    .find(|cert| signer.sid.matches(cert))
+                    .ok_or_else(|| {
+                        CryptoError::InvalidCertificate(format!(
+                            "Zertifikat des Unterzeichners fehlt ({})",
+                            signer.sid
+                        ))
+                    })?;
+                signer.verify(&self.content_type, content, &cert.public_key)?;
+                Ok(cert.clone())
+            })
+            .collect()
+    }
+
+    /// Prüft alle Unterzeichner und deren Zertifikatspfade gegen `store`; gibt je
+    /// Unterzeichner den Pfad (Zertifikat zuerst) zurück.
+    pub fn verify_trusted(
+        &self,
+        detached: Option<&[u8]>,
+        extra: &[Certificate],
+        store: &TrustStore,
+        options: &VerifyOptions,
+    ) -> Result<Vec<Vec<Certificate>>, CryptoError> {
+        let intermediates: Vec<Certificate> =
+            self.certificates.iter().chain(extra).cloned().collect();
+        self.verify(detached, extra)?
+            .iter()
+            .map(|cert| verify_path(cert, &intermediates, store, options))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asymmetric::ed25519;
+    use crate::x509::{CertificateParams, Name};
+
+    fn signer() -> (Certificate, SigningKey) {
+        let (_, private) = ed25519::generate_ed25519_keypair();
+        let key = SigningKey::from_ed25519(&private);
+        let params = CertificateParams::new(Name::parse("CN=Unterzeichner").unwrap(), 0, 36500);
+        (Certificate::self_signed(&params, &key).unwrap(), key)
+    }
+
+    #[test]
+    fn test_sign_verify_attached_and_detached() {
+        let (cert, key) = signer();
+        let attached = sign(b"Vertrag", &cert, &key, &[], false).unwrap();
+        let parsed = SignedData::from_bytes(&attached).unwrap();
+        assert_eq!(parsed.content.as_deref(), Some(&b"Vertrag"[..]));
+        assert_eq!(parsed.signers[0].digest_algorithm, DigestAlgorithm::Sha512);
+        assert!(parsed.signers[0].signing_time.is_some());
+        assert_eq!(parsed.verify(None, &[]).unwrap()[0].to_der(), cert.to_der());
+
+        let detached = sign(b"Vertrag", &cert, &key, &[], true).unwrap();
+        let parsed = SignedData::from_bytes(&crate::cms::to_pem(&detached).into_bytes()).unwrap();
+        assert!(parsed.content.is_none());
+        parsed.verify(Some(b"Vertrag"), &[]).unwrap();
+        assert!(matches!(
+            parsed.verify(Some(b"Vertrag!"), &[]),
+            Err(CryptoError::InvalidSignature)
+        ));
+        assert!(parsed.verify(None, &[]).is_err());
+
+        // verfälschte Signatur und fremder Schlüssel
+        let mut tampered = parsed.clone();
+        tampered.signers[0].signature[5] ^= 1;
+        assert!(matches!(
+            tampered.verify(Some(b"Vertrag"), &[]),
+            Err(CryptoError::InvalidSignature)
+        ));
+        let (other, _) = signer();
+        assert!(sign(b"x", &other, &key, &[], false).is_err());
+    }
+}
@@ -1,78 +1,1827 @@
-use crate::asymmetric::rsa::{decrypt_rsa, encrypt_rsa, PrivateKey, PublicKey};
-use crate::symmetric::aes::{decrypt_aes256, encrypt_aes256};
-use base64::Engine;
+use crate::age;
+use crate::algorithms::factorization::factorize;
+use crate::algorithms::primality::{
+    maurer_prime, shawe_taylor_prime, PrimeCertificate, PrimeProof,
+};
+use crate::archive;
+use crate::asymmetric::rsa::{
+    decrypt_rsa, encrypt_rsa, generate_rsa_key, validate_rsa_key, PrivateKey, PublicKey,
+    RsaKeyOptions,
+};
+use crate::asymmetric::{ed25519, x25519};
+use crate::cms;
+use crate::hybrid::envelope;
+use crate::hybrid::recipient::{Identity, Recipient};
+use crate::hybrid::rekey::{self, FileFormat};
+use crate::hybrid::seal;
+use crate::keystore;
+use crate::openpgp;
+use crate::openpgp::armor::ArmorKind;
+use crate::openpgp::key::format_fingerprint;
+use crate::pkcs12;
+use crate::pqcrypto::mlkem;
+use crate::signature::{self, HashAlgorithm, Signature};
+use crate::ssh;
+use crate::symmetric::aes::{decrypt_aes_cbc, encrypt_aes256};
+use crate::utils::armor::{self, Label};
+use crate::utils::der;
+use crate::utils::error::CryptoError;
+use crate::x509;
 use hex::decode;
 use num_bigint::BigUint;
-use sha2::{Digest, Sha256};
+use num_traits::Zero;
+use serde_json::json;
 use std::fs;
 use std::fs::{read, write};
+use std::io::{self, IsTerminal, Read, Write};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
 
 // ---------- PEM Helper -----------
-fn to_pem(label: &str, data: &[u8]) -> String {
-    let b64 = base64::engine::general_purpose::STANDARD.encode(data);
-    let mut pem = format!("-----BEGIN {}-----\n", label);
-    for chunk in b64.as_bytes().chunks(64) {
-        pem.push_str(&String::from_utf8_lossy(chunk));
-        pem.push('\n');
+/// Liest den Inhalt einer Schlüsseldatei; das Label bestimmt die Art des Schlüssels.
+fn from_pem(pem: &str) -> Vec<u8> {
+    let label = pem_label(pem).unwrap_or_else(|| fail("Kein PEM-Block gefunden"));
+    x509::pem_decode(pem, label).unwrap_or_else(|e| fail(e))
+}
+
+/// Liefert das Label aus der BEGIN-Zeile, z. B. "RSA PUBLIC KEY".
+fn pem_label(pem: &str) -> Option<&str> {
+    pem.lines()
+        .find_map(|line| line.strip_prefix("-----BEGIN "))
+        .and_then(|rest| rest.strip_suffix("-----"))
+}
+
+// ---------- Ein-/Ausgabe -----------
+/// Art der Meldungen auf der Konsole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    /// Nur Ergebnisse (Listen, Anzeigen) und Fehler.
+    Quiet,
+    /// Ein JSON-Objekt pro Zeile; Fehler als `{"error": …}` auf stderr.
+    Json,
+}
+
+/// Einstellungen aus den globalen Optionen `--quiet`, `--json` und `--armor`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OutputOptions {
+    pub format: OutputFormat,
+    pub armor: bool,
+}
+
+static OUTPUT: OnceLock<OutputOptions> = OnceLock::new();
+/// Gesetzt, sobald Nutzdaten nach stdout geschrieben wurden; Meldungen gehen dann nach stderr.
+static DATA_ON_STDOUT: AtomicBool = AtomicBool::new(false);
+
+/// Legt die Ausgabe-Einstellungen fest; nur der erste Aufruf zählt.
+pub fn configure_output(options: OutputOptions) {
+    let _ = OUTPUT.set(options);
+}
+
+fn output_options() -> OutputOptions {
+    OUTPUT.get().copied().unwrap_or_default()
+}
+
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Pfad für Meldungen; `-` wird als stdin/stdout angezeigt.
+fn display_path(path: &Path, stdio: &str) -> String {
+    if is_stdio(path) {
+        stdio.to_string()
+    } else {
+        path.display().to_string()
     }
-    pem.push_str(&format!("-----END {}-----\n", label));
-    pem
 }
 
-fn from_pem(pem: &str) -> Vec<u8> {
-    let data: String = pem
-        .lines()
-        .filter(|line| !line.starts_with("-----"))
-        .collect();
-    base64::engine::general_purpose::STANDARD
-        .decode(&data)
-        .expect("Kein gültiges Base64")
+fn print_message(line: &str, force_stderr: bool) {
+    if force_stderr || DATA_ON_STDOUT.load(Ordering::Relaxed) {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
+
+/// Meldet den Ausgang eines Befehls als Text, als JSON-Zeile (`--json`) oder gar nicht
+/// (`--quiet`).
+pub fn report(text: &str, json: serde_json::Value) {
+    match output_options().format {
+        OutputFormat::Text => print_message(text, false),
+        OutputFormat::Quiet => {}
+        OutputFormat::Json => print_message(&json.to_string(), false),
+    }
+}
+
+/// Wie [`report`], aber auch mit `--quiet`: für Befehle, deren Ausgabe das Ergebnis ist.
+pub fn report_result(text: &str, json: serde_json::Value) {
+    match output_options().format {
+        OutputFormat::Text | OutputFormat::Quiet => print_message(text, false),
+        OutputFormat::Json => print_message(&json.to_string(), false),
+    }
+}
+
+/// Warnung, die die Ausgabe nicht beendet (z. B. übersprungene Dateien); immer auf stderr.
+pub fn warn(text: &str, json: serde_json::Value) {
+    match output_options().format {
+        OutputFormat::Text | OutputFormat::Quiet => print_message(text, true),
+        OutputFormat::Json => print_message(&json.to_string(), true),
+    }
+}
+
+/// Bricht mit einer Fehlermeldung und Exit-Code 1 ab.
+pub fn fail(message: impl std::fmt::Display) -> ! {
+    match output_options().format {
+        OutputFormat::Json => {
+            eprintln!("{}", json!({ "error": message.to_string() }))
+        }
+        _ => eprintln!("{}", message),
+    }
+    std::process::exit(1);
+}
+
+/// Liest eine Eingabedatei; `-` liest stdin.
+pub fn read_input(path: &Path) -> Vec<u8> {
+    let result = if is_stdio(path) {
+        let mut data = Vec::new();
+        io::stdin().lock().read_to_end(&mut data).map(|_| data)
+    } else {
+        read(path)
+    };
+    result.unwrap_or_else(|e| fail(format!("{}: {}", display_path(path, "stdin"), e)))
+}
+
+/// Liest verschlüsselte Daten oder Signaturen; ASCII-Armor mit dem Label `label` wird erkannt
+/// und entfernt, fehlerhaftes Armor bricht ab.
+pub fn read_input_dearmored(path: &Path, label: Label) -> Vec<u8> {
+    let data = read_input(path);
+    armor::dearmor(&data, label)
+        .unwrap_or_else(|e| fail(format!("{}: {}", display_path(path, "stdin"), e)))
+}
+
+/// Schreibt eine Ausgabedatei; `-` schreibt nach stdout. Mit `--armor` und `armor_label`
+/// wird als ASCII-Armor geschrieben, binäre Daten auf einem Terminal werden abgelehnt.
+pub fn write_output(path: &Path, data: &[u8], armor_label: Option<Label>) {
+    let armored = match armor_label {
+        Some(label) if output_options().armor => {
+            Some(armor::Armor::new(label, data.to_vec()).encode())
+        }
+        _ => None,
+    };
+    let data = armored.as_ref().map_or(data, |pem| pem.as_bytes());
+    let result = if is_stdio(path) {
+        DATA_ON_STDOUT.store(true, Ordering::Relaxed);
+        let mut stdout = io::stdout().lock();
+        if armor_label.is_some() && armored.is_none() && stdout.is_terminal() {
+            fail("Binäre Ausgabe auf ein Terminal; --out <datei> oder --armor angeben");
+        }
+        stdout.write_all(data).and_then(|_| stdout.flush())
+    } else {
+        write(path, data)
+    };
+    result.unwrap_or_else(|e| fail(format!("{}: {}", display_path(path, "stdout"), e)));
+}
+
+/// Legt einen Ausgabeordner an.
+pub fn create_out_dir(path: &Path) {
+    fs::create_dir_all(path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
 }
 
 // ----- Key Writing (PEM) ----------
 pub fn write_rsa_keys_pem(pub_key: &PublicKey, priv_key: &PrivateKey, out_dir: &Path) {
-    let n_bytes = pub_key.n.to_bytes_be();
-    let d_bytes = priv_key.d.to_bytes_be();
-
-    let pub_pem = to_pem("RSA PUBLIC KEY", &n_bytes);
-    let priv_pem = to_pem("RSA PRIVATE KEY", &d_bytes);
+    let pub_pem = keystore::key::rsa_public_to_pem(pub_key);
+    let priv_pem = x509::pem_encode("RSA PRIVATE KEY", &priv_key.d.to_bytes_be());
 
     write(out_dir.join("rsa_public.key"), pub_pem).unwrap();
     write(out_dir.join("rsa_private.key"), priv_pem).unwrap();
 }
 
+pub fn write_x25519_keys_pem(
+    pub_key: &x25519::PublicKey,
+    priv_key: &x25519::PrivateKey,
+    out_dir: &Path,
+) {
+    write(
+        out_dir.join("x25519_public.key"),
+        x509::pem_encode("X25519 PUBLIC KEY", &pub_key.bytes),
+    )
+    .unwrap();
+    write(
+        out_dir.join("x25519_private.key"),
+        x509::pem_encode("X25519 PRIVATE KEY", &priv_key.bytes),
+    )
+    .unwrap();
+}
+
+pub fn write_ed25519_keys_pem(
+    pub_key: &ed25519::PublicKey,
+    priv_key: &ed25519::PrivateKey,
+    out_dir: &Path,
+) {
+    write(
+        out_dir.join("ed25519_public.key"),
+        x509::pem_encode("ED25519 PUBLIC KEY", &pub_key.bytes),
+    )
+    .unwrap();
+    write(
+        out_dir.join("ed25519_private.key"),
+        x509::pem_encode("ED25519 PRIVATE KEY", &priv_key.bytes),
+    )
+    .unwrap();
+}
+
+pub fn write_mlkem_keys_pem(
+    pub_key: &mlkem::PublicKey,
+    priv_key: &mlkem::PrivateKey,
+    out_dir: &Path,
+) {
+    write(
+        out_dir.join("mlkem_public.key"),
+        x509::pem_encode("ML-KEM-768 PUBLIC KEY", &pub_key.bytes),
+    )
+    .unwrap();
+    write(
+        out_dir.join("mlkem_private.key"),
+        x509::pem_encode("ML-KEM-768 PRIVATE KEY", &priv_key.bytes),
+    )
+    .unwrap();
+}
+
 // ----- Key Reading (PEM) ----------
 pub fn load_rsa_public_key(path: &Path) -> PublicKey {
+    let path = &public_key_path(path);
     let content = fs::read_to_string(path).expect("Fehler beim Lesen des Public Keys");
-    let n = from_pem(&content);
-
-    // Standard Public Exponent 65537
-    let e = BigUint::from(65537u32);
-
-    PublicKey {
-        n: BigUint::from_bytes_be(&n),
-        e,
-    }
+    exit_on_error(keystore::key::rsa_public_from_pem(&content))
 }
 
 pub fn load_rsa_private_key(path: &Path) -> PrivateKey {
     let content = fs::read_to_string(path).expect("Fehler beim Lesen des Private Keys");
     let d = from_pem(&content);
-
-    let pub_content = fs::read_to_string(path.with_file_name("rsa_public.key"))
-        .expect("Fehler beim Lesen des Public Keys");
-    let n = from_pem(&pub_content);
+    // n und e stehen in der öffentlichen Schlüsseldatei daneben
+    let public = load_rsa_public_key(&path.with_file_name("rsa_public.key"));
 
     PrivateKey {
-        n: BigUint::from_bytes_be(&n),
+        n: public.n,
         d: BigUint::from_bytes_be(&d),
+        e: public.e,
+    }
+}
+
+/// Zu einer privaten Schlüsseldatei die öffentliche daneben (`rsa_private.key` →
+/// `rsa_public.key`); andere Pfade bleiben unverändert.
+fn public_key_path(path: &Path) -> std::path::PathBuf {
+    let label = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| pem_label(&content).map(str::to_string));
+    let prefix = match label.as_deref() {
+        Some(keystore::key::PEM_RSA_PRIVATE) => "rsa",
+        Some(keystore::key::PEM_X25519_PRIVATE) => "x25519",
+        Some(keystore::key::PEM_ED25519_PRIVATE) => "ed25519",
+        Some(keystore::key::PEM_MLKEM_PRIVATE) => "mlkem",
+        _ => return path.to_path_buf(),
+    };
+    path.with_file_name(format!("{}_public.key", prefix))
+}
+
+/// Lädt einen Empfänger-Schlüssel; die Art wird am PEM-Label erkannt.
+///
+/// Statt der öffentlichen darf auch die private Schlüsseldatei angegeben werden.
+pub fn load_recipient(path: &Path) -> Recipient {
+    let path = &public_key_path(path);
+    let content =
+        fs::read_to_string(path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+    match pem_label(&content) {
+        Some("RSA PUBLIC KEY") => Recipient::Rsa(load_rsa_public_key(path)),
+        Some("X25519 PUBLIC KEY") => Recipient::X25519(x25519::PublicKey {
+            bytes: from_pem(&content)
+                .try_into()
+                .unwrap_or_else(|_| fail("X25519-Schlüssel muss 32 Byte lang sein")),
+        }),
+        Some("ML-KEM-768 PUBLIC KEY") => Recipient::MlKem(mlkem::PublicKey {
+            bytes: from_pem(&content),
+        }),
+        _ => fail(format!(
+            "Unbekannter Empfänger-Schlüssel: {}",
+            path.display()
+        )),
+    }
+}
+
+/// Liest eine Empfängerliste: ein Schlüsselpfad pro Zeile, `#` leitet Kommentare ein.
+/// Relative Pfade beziehen sich auf das Verzeichnis der Liste.
+pub fn load_recipients_file(path: &Path) -> Vec<Recipient> {
+    let content = fs::read_to_string(path).expect("Fehler beim Lesen der Empfängerliste");
+    let base = path.parent().unwrap_or(Path::new("."));
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| load_recipient(&base.join(line)))
+        .collect()
+}
+
+/// Lädt einen privaten Schlüssel zum Entschlüsseln; die Art wird am PEM-Label erkannt.
+pub fn load_identity(path: &Path) -> Identity {
+    let content = fs::read_to_string(path).expect("Fehler beim Lesen des Private Keys");
+    match pem_label(&content) {
+        Some("RSA PRIVATE KEY") => Identity::Rsa(load_rsa_private_key(path)),
+        Some("X25519 PRIVATE KEY") => Identity::X25519(x25519::PrivateKey {
+            bytes: from_pem(&content)
+                .try_into()
+                .unwrap_or_else(|_| fail("X25519-Schlüssel muss 32 Byte lang sein")),
+        }),
+        Some("ML-KEM-768 PRIVATE KEY") => Identity::MlKem(mlkem::PrivateKey {
+            bytes: from_pem(&content),
+        }),
+        _ => fail(format!(
+            "Unbekannter privater Schlüssel: {}",
+            path.display()
+        )),
+    }
+}
+
+fn load_x25519_private_key(path: &Path) -> x25519::PrivateKey {
+    match load_identity(path) {
+        Identity::X25519(priv_key) => priv_key,
+        _ => fail(format!("Kein X25519-Schlüssel: {}", path.display())),
+    }
+}
+
+// ---------- Schlüsselspeicher -----------
+/// Öffnet den Schlüsselspeicher am Standardort; bei Fehlern Abbruch mit Exit-Code 1.
+pub fn open_key_store() -> keystore::KeyStore {
+    let Some(root) = keystore::KeyStore::default_path() else {
+        fail(format!(
+            "Kein Ort für den Schlüsselspeicher bekannt, bitte {} setzen",
+            keystore::ENV_KEYSTORE
+        ));
+    };
+    store_or_exit(keystore::KeyStore::open(&root))
+}
+
+fn store_or_exit<T>(result: Result<T, keystore::KeyStoreError>) -> T {
+    result.unwrap_or_else(|e| fail(e))
+}
+
+/// Pfad eines Schlüssels aus dem Speicher: der private, falls vorhanden, sonst der öffentliche.
+/// Die Ladefunktionen für öffentliche Schlüssel akzeptieren auch den privaten.
+pub fn key_store_path(query: &str) -> std::path::PathBuf {
+    let entry = store_or_exit(open_key_store().find(query));
+    entry.private_path().unwrap_or_else(|| entry.public_path())
+}
+
+/// Schlüsselangabe der Befehlszeile: eine vorhandene Datei, sonst ein Eintrag im Speicher.
+pub fn resolve_key(value: &str) -> std::path::PathBuf {
+    let path = Path::new(value);
+    if path.exists() {
+        path.to_path_buf()
+    } else {
+        key_store_path(value)
+    }
+}
+
+/// Erzeugt ein Schlüsselpaar als PEM (öffentlich, privat) im Format der Schlüsseldateien.
+/// RSA-Schlüssel haben `bits` Bit und den öffentlichen Exponenten `exponent` (dezimal oder
+/// hexadezimal mit `0x`); mit `fips` werden sie nach FIPS 186-5 erzeugt.
+fn generate_key_pem(algorithm: &str, bits: usize, exponent: &str, fips: bool) -> (String, String) {
+    match algorithm {
+        "rsa" => {
+            let options = RsaKeyOptions {
+                bits,
+                e: parse_number(exponent),
+                fips,
+            };
+            let (pub_key, priv_key) = exit_on_error(generate_rsa_key(&options));
+            (
+                keystore::key::rsa_public_to_pem(&pub_key),
+                x509::pem_encode(keystore::key::PEM_RSA_PRIVATE, &priv_key.d.to_bytes_be()),
+            )
+        }
+        "x25519" => {
+            let (pub_key, priv_key) = x25519::generate_x25519_keypair();
+            (
+                x509::pem_encode(keystore::key::PEM_X25519_PUBLIC, &pub_key.bytes),
+                x509::pem_encode(keystore::key::PEM_X25519_PRIVATE, &priv_key.bytes),
+            )
+        }
+        "ed25519" => {
+            let (pub_key, priv_key) = ed25519::generate_ed25519_keypair();
+            (
+                x509::pem_encode(keystore::key::PEM_ED25519_PUBLIC, &pub_key.bytes),
+                x509::pem_encode(keystore::key::PEM_ED25519_PRIVATE, &priv_key.bytes),
+            )
+        }
+        "mlkem" => {
+            let (pub_key, priv_key) = mlkem::generate_mlkem_keypair();
+            (
+                x509::pem_encode(keystore::key::PEM_MLKEM_PUBLIC, &pub_key.bytes),
+                x509::pem_encode(keystore::key::PEM_MLKEM_PRIVATE, &priv_key.bytes),
+            )
+        }
+        other => fail(format!(
+            "Unbekannter Schlüsseltyp: {} (rsa, x25519, ed25519, mlkem)",
+            other
+        )),
+    }
+}
+
+/// Nimmt ein neu erzeugtes Schlüsselpaar unter einer Bezeichnung in den Speicher auf.
+pub fn key_generate(algorithm: &str, label: &str, bits: usize, exponent: &str, fips: bool) {
+    let (public_pem, private_pem) = generate_key_pem(algorithm, bits, exponent, fips);
+    let store = open_key_store();
+    let entry = store_or_exit(store.import_pem(&public_pem, Some(&private_pem), Some(label)));
+    report_key_entry(&entry, false);
+}
+
+/// Erzeugt ein Schlüsselpaar als Dateien `<typ>_public.key` und `<typ>_private.key`.
+pub fn key_generate_files(
+    algorithm: &str,
+    bits: usize,
+    exponent: &str,
+    fips: bool,
+    out_dir: &Path,
+) {
+    let (public_pem, private_pem) = generate_key_pem(algorithm, bits, exponent, fips);
+    let public_key = exit_on_error(keystore::key::PublicKey::from_pem(&public_pem));
+    let prefix = public_key.file_prefix();
+    create_out_dir(out_dir);
+    write_output(
+        &out_dir.join(format!("{}_public.key", prefix)),
+        public_pem.as_bytes(),
+        None,
+    );
+    write_output(
+        &out_dir.join(format!("{}_private.key", prefix)),
+        private_pem.as_bytes(),
+        None,
+    );
+    report(
+        &format!(
+            "{}-Schlüssel gespeichert in: {}",
+            public_key.description(),
+            out_dir.display()
+        ),
+        json!({
+            "key": public_key.description(),
+            "fingerprint": hex::encode(public_key.fingerprint()),
+            "output": out_dir,
+        }),
+    );
+}
+
+/// Nimmt eine Schlüsseldatei auf; bei einer privaten wird die öffentliche daneben mitgenommen.
+pub fn key_import(path: &Path, label: Option<&str>) {
+    let public_path = public_key_path(path);
+    let public_pem = fs::read_to_string(&public_path)
+        .unwrap_or_else(|e| fail(format!("{}: {}", public_path.display(), e)));
+    let private_pem = (public_path != path).then(|| {
+        fs::read_to_string(path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)))
+    });
+    let store = open_key_store();
+    let entry = store_or_exit(store.import_pem(&public_pem, private_pem.as_deref(), label));
+    report_key_entry(&entry, false);
+}
+
+fn key_entry_json(entry: &keystore::KeyEntry) -> serde_json::Value {
+    json!({
+        "key_id": entry.key_id(),
+        "fingerprint": entry.fingerprint_hex(),
+        "type": entry.public_key.description(),
+        "private": entry.private_path().is_some(),
+        "labels": entry.labels,
+        "created": entry.created,
+        "path": entry.dir,
+    })
+}
+
+fn report_key_entry(entry: &keystore::KeyEntry, long: bool) {
+    let mut text = format!(
+        "{}  {:<10} {:<6} {}",
+        entry.key_id(),
+        entry.public_key.description(),
+        if entry.private_path().is_some() {
+            "privat"
+        } else {
+            ""
+        },
+        entry.labels.join(", ")
+    );
+    if long {
+        text.push_str(&format!(
+            "\n    SHA-256: {}\n    Pfad:    {}",
+            entry.fingerprint_hex(),
+            entry.dir.display()
+        ));
+    }
+    report_result(&text, key_entry_json(entry));
+}
+
+/// Listet alle Schlüssel; mit `long` zusätzlich den vollen Fingerabdruck und den Pfad.
+pub fn key_list(long: bool) {
+    let store = open_key_store();
+    for entry in store_or_exit(store.entries()) {
+        report_key_entry(&entry, long);
+    }
+}
+
+pub fn key_label(query: &str, label: &str) {
+    let entry = store_or_exit(open_key_store().add_label(query, label));
+    report_key_entry(&entry, false);
+}
+
+pub fn key_export(query: &str, out_dir: &Path, include_private: bool) {
+    let written = store_or_exit(open_key_store().export(query, out_dir, include_private));
+    let text: Vec<String> = written
+        .iter()
+        .map(|path| format!("Geschrieben: {}", path.display()))
+        .collect();
+    report(&text.join("\n"), json!({ "written": written }));
+}
+
+/// Löscht einen Schlüssel; liegt der private Teil im Speicher, nur mit `force`.
+pub fn key_delete(query: &str, force: bool) {
+    let store = open_key_store();
+    let entry = store_or_exit(store.find(query));
+    if entry.private_path().is_some() && !force {
+        fail(format!(
+            "{} enthält einen privaten Schlüssel; zum Löschen --force angeben",
+            entry.key_id()
+        ));
+    }
+    store_or_exit(store.delete(&entry.fingerprint_hex()));
+    report(
+        &format!("Gelöscht: {} ({})", entry.key_id(), entry.labels.join(", ")),
+        json!({ "deleted": entry.fingerprint_hex(), "labels": entry.labels }),
+    );
+}
+
+// ---------- OpenPGP -----------
+fn unix_now() -> u32 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("Systemzeit vor 1970")
+        .as_secs() as u32
+}
+
+/// Lädt ein OpenPGP-Zertifikat bzw. einen privaten Schlüssel; bei Fehlern Abbruch mit Exit-Code 1.
+pub fn load_pgp_cert(path: &Path, passphrase: Option<&str>) -> openpgp::Cert {
+    let data = read_input(path);
+    openpgp::Cert::from_bytes(&data, passphrase.map(str::as_bytes))
+        .unwrap_or_else(|e| fail(format!("{}: {}", display_path(path, "stdin"), e)))
+}
+
+/// Beschreibung eines Zertifikats als Text und JSON.
+fn describe_pgp_cert(cert: &openpgp::Cert) -> (String, serde_json::Value) {
+    let mut text = format!("Fingerabdruck: {}", format_fingerprint(&cert.fingerprint()));
+    for uid in &cert.user_ids {
+        text.push_str(&format!("\nUser-ID:       {}", uid.value));
+    }
+    let mut subkeys = Vec::new();
+    for subkey in &cert.subkeys {
+        let fingerprint = format_fingerprint(&subkey.key.public.fingerprint());
+        let algo = subkey.key.public.params.algo();
+        text.push_str(&format!(
+            "\nUnterschlüssel: {} (Algorithmus {})",
+            fingerprint, algo
+        ));
+        subkeys.push(json!({ "fingerprint": fingerprint, "algorithm": algo }));
+    }
+    let json = json!({
+        "fingerprint": format_fingerprint(&cert.fingerprint()),
+        "user_ids": cert.user_ids.iter().map(|uid| uid.value.clone()).collect::<Vec<_>>(),
+        "subkeys": subkeys,
+        "secret": cert.has_secret(),
+    });
+    (text, json)
+}
+
+fn write_pgp_cert(cert: &openpgp::Cert, out_dir: &Path) {
+    create_out_dir(out_dir);
+    write_output(
+        &out_dir.join("pgp_public.asc"),
+        cert.to_public_armor().as_bytes(),
+        None,
+    );
+    if let Some(secret) = cert.to_secret_armor() {
+        write_output(&out_dir.join("pgp_secret.asc"), secret.as_bytes(), None);
+    }
+}
+
+/// Erstellt aus einem RSA- oder Ed25519-Schlüssel (optional mit X25519-Unterschlüssel)
+/// ein OpenPGP-Zertifikat und den zugehörigen privaten Schlüssel.
+pub fn pgp_export_key(priv_key_path: &Path, user_id: &str, subkey: Option<&Path>, out_dir: &Path) {
+    let content = fs::read_to_string(priv_key_path).expect("Fehler beim Lesen des Private Keys");
+    let created = unix_now();
+    let cert = match pem_label(&content) {
+        Some("RSA PRIVATE KEY") => {
+            let priv_key = load_rsa_private_key(priv_key_path);
+            let pub_key = load_rsa_public_key(&priv_key_path.with_file_name("rsa_public.key"));
+            openpgp::Cert::from_rsa(&pub_key, &priv_key, user_id, created)
+        }
+        Some("ED25519 PRIVATE KEY") => {
+            let priv_key = ed25519::PrivateKey {
+                bytes: from_pem(&content)
+                    .try_into()
+                    .unwrap_or_else(|_| fail("Ed25519-Schlüssel muss 32 Byte lang sein")),
+            };
+            let subkey = subkey.map(load_x25519_private_key);
+            openpgp::Cert::from_ed25519(&priv_key, subkey.as_ref(), user_id, created)
+        }
+        _ => fail(format!(
+            "Nicht unterstützter Schlüssel: {}",
+            priv_key_path.display()
+        )),
+    };
+    let cert = exit_on_error(cert);
+    write_pgp_cert(&cert, out_dir);
+    let (text, mut json) = describe_pgp_cert(&cert);
+    json["output"] = json!(out_dir);
+    report(
+        &format!(
+            "{}\nOpenPGP-Schlüssel gespeichert in: {}",
+            text,
+            out_dir.display()
+        ),
+        json,
+    );
+}
+
+/// Importiert einen OpenPGP-Schlüssel (z. B. aus `gpg --export`), prüft die Eigensignaturen
+/// und speichert ihn normalisiert; private Schlüssel werden dabei entsperrt abgelegt.
+pub fn pgp_import_key(path: &Path, passphrase: Option<&str>, out_dir: &Path) {
+    let cert = load_pgp_cert(path, passphrase);
+    write_pgp_cert(&cert, out_dir);
+    let (text, mut json) = describe_pgp_cert(&cert);
+    json["output"] = json!(out_dir);
+    report(
+        &format!(
+            "{}\nOpenPGP-{} importiert nach: {}",
+            text,
+            if cert.has_secret() {
+                "Schlüssel (privat)"
+            } else {
+                "Zertifikat"
+            },
+            out_dir.display()
+        ),
+        json,
+    );
+}
+
+pub fn pgp_decrypt_file(input_path: &Path, key_path: &Path, output_path: &Path) {
+    let data = read_input(input_path);
+    let cert = load_pgp_cert(key_path, None);
+    let plaintext = exit_on_error(openpgp::decrypt(&data, &cert));
+    write_output(output_path, &plaintext, None);
+    report(
+        &format!(
+            "OpenPGP-Nachricht entschlüsselt gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({ "output": display_path(output_path, "stdout") }),
+    );
+}
+
+/// Erzeugt eine abgesetzte OpenPGP-Signatur (ASCII-Armor), prüfbar mit `gpg --verify`.
+pub fn pgp_sign_file(input_path: &Path, key_path: &Path, sig_path: &Path) {
+    let data = read_input(input_path);
+    let cert = load_pgp_cert(key_path, None);
+    let sig = exit_on_error(openpgp::sign_detached(&data, &cert, unix_now()));
+    write_output(
+        sig_path,
+        openpgp::armor::encode(ArmorKind::Signature, &sig).as_bytes(),
+        None,
+    );
+    report(
+        &format!(
+            "OpenPGP-Signatur gespeichert in: {}",
+            display_path(sig_path, "stdout")
+        ),
+        json!({ "output": display_path(sig_path, "stdout") }),
+    );
+}
+
+pub fn pgp_verify_file(input_path: &Path, sig_path: &Path, cert_path: &Path) {
+    let data = read_input(input_path);
+    let sig = read_input(sig_path);
+    let cert = load_pgp_cert(cert_path, None);
+    let v = exit_on_error(openpgp::verify_detached(&data, &sig, &cert));
+    report(
+        &format!(
+            "Signatur gültig (Schlüssel {}, erstellt {})",
+            format_fingerprint(&v.fingerprint),
+            v.created
+        ),
+        json!({
+            "valid": true,
+            "fingerprint": format_fingerprint(&v.fingerprint),
+            "created": v.created,
+        }),
+    );
+}
+
+// ---------- X.509 -----------
+fn exit_on_error<T>(result: Result<T, CryptoError>) -> T {
+    result.unwrap_or_else(|e| fail(e))
+}
+
+/// Lädt einen RSA- oder Ed25519-Schlüssel zum Signieren von Zertifikaten.
+pub fn load_signing_key(path: &Path) -> x509::SigningKey {
+    let content = fs::read_to_string(path).expect("Fehler beim Lesen des Private Keys");
+    match pem_label(&content) {
+        Some("RSA PRIVATE KEY") => x509::SigningKey::Rsa {
+            public: load_rsa_public_key(&path.with_file_name("rsa_public.key")),
+            private: load_rsa_private_key(path),
+        },
+        Some("ED25519 PRIVATE KEY") => x509::SigningKey::from_ed25519(&ed25519::PrivateKey {
+            bytes: from_pem(&content)
+                .try_into()
+                .unwrap_or_else(|_| fail("Ed25519-Schlüssel muss 32 Byte lang sein")),
+        }),
+        _ => fail(format!("Nicht unterstützter Schlüssel: {}", path.display())),
+    }
+}
+
+pub fn load_certificate(path: &Path) -> x509::Certificate {
+    let data = read_input(path);
+    x509::Certificate::from_bytes(&data)
+        .unwrap_or_else(|e| fail(format!("{}: {}", display_path(path, "stdin"), e)))
+}
+
+/// Lädt alle Zertifikate aus den PEM-Dateien, z. B. Zwischenzertifikate.
+pub fn load_certificate_chain(paths: &[&Path]) -> Vec<x509::Certificate> {
+    let mut chain = Vec::new();
+    for path in paths {
+        let text = String::from_utf8_lossy(&read_input(path)).into_owned();
+        chain.extend(exit_on_error(x509::Certificate::from_pem_chain(&text)));
+    }
+    chain
+}
+
+/// Lädt alle Zertifikate aus einer PEM-Datei oder allen `.pem`/`.crt`-Dateien eines Ordners.
+pub fn load_trust_store(path: &Path) -> x509::TrustStore {
+    let files = if path.is_dir() {
+        let mut files: Vec<_> = fs::read_dir(path)
+            .unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)))
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.extension()
+                    .is_some_and(|ext| ext == "pem" || ext == "crt")
+            })
+            .collect();
+        files.sort();
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+    let mut store = x509::TrustStore::new();
+    for file in files {
+        let text = String::from_utf8_lossy(&read_input(&file)).into_owned();
+        let bundle = x509::TrustStore::from_pem(&text)
+            .unwrap_or_else(|e| fail(format!("{}: {}", file.display(), e)));
+        for cert in bundle.anchors() {
+            store.add(cert.clone());
+        }
+    }
+    store
+}
+
+/// KeyUsage für Endstellen: Signaturen, bei RSA zusätzlich Schlüsseltransport.
+fn end_entity_key_usage(key: &x509::SubjectPublicKey) -> x509::KeyUsage {
+    match key {
+        x509::SubjectPublicKey::Rsa(_) => {
+            x509::KeyUsage::DIGITAL_SIGNATURE.union(x509::KeyUsage::KEY_ENCIPHERMENT)
+        }
+        _ => x509::KeyUsage::DIGITAL_SIGNATURE,
+    }
+}
+
+fn certificate_params(
+    subject: x509::Name,
+    days: u32,
+    san: &[x509::GeneralName],
+    ca: Option<Option<u32>>,
+    key: &x509::SubjectPublicKey,
+) -> x509::CertificateParams {
+    let params = x509::CertificateParams::new(subject, unix_now() as i64, days)
+        .with_subject_alt_names(san.to_vec());
+    match ca {
+        Some(path_len) => params.with_ca(path_len),
+        None => x509::CertificateParams {
+            key_usage: Some(end_entity_key_usage(key)),
+            ..params
+        },
     }
 }
 
+/// Beschreibung eines Zertifikats als Text und JSON.
+fn describe_certificate(cert: &x509::Certificate) -> (String, serde_json::Value) {
+    let serial = hex::encode(cert.serial.to_bytes_be());
+    let mut lines = vec![
+        format!("Inhaber:        {}", cert.subject),
+        format!("Aussteller:     {}", cert.issuer),
+        format!("Seriennummer:   {}", serial),
+        format!("Gültig ab:      {}", der::format_time(cert.not_before)),
+        format!("Gültig bis:     {}", der::format_time(cert.not_after)),
+        format!("Schlüssel:      {}", cert.public_key.description()),
+        format!("Signatur:       {}", cert.signature_algorithm.name()),
+    ];
+    let mut json = json!({
+        "subject": cert.subject.to_string(),
+        "issuer": cert.issuer.to_string(),
+        "serial": serial,
+        "not_before": der::format_time(cert.not_before),
+        "not_after": der::format_time(cert.not_after),
+        "key": cert.public_key.description(),
+        "signature_algorithm": cert.signature_algorithm.name(),
+        "sha256": hex::encode(cert.fingerprint()),
+    });
+    if let Ok(Some(bc)) = cert.basic_constraints() {
+        lines.push(match (bc.ca, bc.path_len) {
+            (true, Some(len)) => format!("CA:             ja (Pfadlänge {})", len),
+            (true, None) => "CA:             ja".to_string(),
+            (false, _) => "CA:             nein".to_string(),
+        });
+        json["ca"] = json!(bc.ca);
+        json["path_len"] = json!(bc.path_len);
+    }
+    if let Ok(Some(usage)) = cert.key_usage() {
+        lines.push(format!("KeyUsage:       {}", usage.names().join(", ")));
+        json["key_usage"] = json!(usage.names());
+    }
+    if let Ok(names) = cert.subject_alt_names() {
+        if !names.is_empty() {
+            let names: Vec<String> = names.iter().map(ToString::to_string).collect();
+            lines.push(format!("Alt. Namen:     {}", names.join(", ")));
+            json["subject_alt_names"] = json!(names);
+        }
+    }
+    lines.push(format!(
+        "SHA-256:        {}",
+        hex::encode(cert.fingerprint())
+    ));
+    (lines.join("\n"), json)
+}
+
+/// Meldet ein geschriebenes Zertifikat mit seiner Beschreibung.
+fn report_certificate_written(cert: &x509::Certificate, output_path: &Path) {
+    let (text, mut json) = describe_certificate(cert);
+    json["output"] = json!(display_path(output_path, "stdout"));
+    report(
+        &format!(
+            "{}\nZertifikat gespeichert in: {}",
+            text,
+            display_path(output_path, "stdout")
+        ),
+        json,
+    );
+}
+
+/// Erstellt ein selbstsigniertes Zertifikat, z. B. für eine eigene Wurzel-CA.
+pub fn x509_self_signed(
+    key_path: &Path,
+    subject: &str,
+    days: u32,
+    san: &[x509::GeneralName],
+    ca: Option<Option<u32>>,
+    output_path: &Path,
+) {
+    let key = load_signing_key(key_path);
+    let subject = exit_on_error(x509::Name::parse(subject));
+    let params = certificate_params(subject, days, san, ca, &key.public_key());
+    let cert = exit_on_error(x509::Certificate::self_signed(&params, &key));
+    write_output(output_path, cert.to_pem().as_bytes(), None);
+    report_certificate_written(&cert, output_path);
+}
+
+/// Erstellt eine PKCS#10-Zertifikatsanforderung.
+pub fn x509_create_csr(
+    key_path: &Path,
+    subject: &str,
+    san: &[x509::GeneralName],
+    output_path: &Path,
+) {
+    let key = load_signing_key(key_path);
+    let subject = exit_on_error(x509::Name::parse(subject));
+    let csr = exit_on_error(x509::CertificationRequest::create(&subject, san, &key));
+    write_output(output_path, csr.to_pem().as_bytes(), None);
+    report(
+        &format!(
+            "Zertifikatsanforderung gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({
+            "subject": csr.subject.to_string(),
+            "output": display_path(output_path, "stdout"),
+        }),
+    );
+}
+
+/// Stellt aus einer Anforderung mit Zertifikat und Schlüssel einer CA ein Zertifikat aus.
+/// Übernommen werden Inhaber, Schlüssel und gewünschte alternative Namen.
+pub fn x509_sign_csr(
+    csr_path: &Path,
+    ca_cert_path: &Path,
+    ca_key_path: &Path,
+    days: u32,
+    ca: Option<Option<u32>>,
+    output_path: &Path,
+) {
+    let text = String::from_utf8_lossy(&read_input(csr_path)).into_owned();
+    let csr = exit_on_error(x509::CertificationRequest::from_pem(&text));
+    exit_on_error(csr.verify());
+    let san = exit_on_error(csr.subject_alt_names());
+    let issuer = load_certificate(ca_cert_path);
+    let issuer_key = load_signing_key(ca_key_path);
+
+    let params = certificate_params(csr.subject.clone(), days, &san, ca, &csr.public_key);
+    let cert = exit_on_error(x509::Certificate::issue(
+        &params,
+        &csr.public_key,
+        &issuer,
+        &issuer_key,
+    ));
+    write_output(output_path, cert.to_pem().as_bytes(), None);
+    report_certificate_written(&cert, output_path);
+}
+
+/// Zeigt ein Zertifikat oder eine Zertifikatsanforderung an.
+pub fn x509_show(path: &Path) {
+    let data = read_input(path);
+    let text = String::from_utf8_lossy(&data);
+    if !text.contains("CERTIFICATE REQUEST-----") {
+        let cert = x509::Certificate::from_bytes(&data)
+            .unwrap_or_else(|e| fail(format!("{}: {}", display_path(path, "stdin"), e)));
+        let (text, json) = describe_certificate(&cert);
+        report_result(&text, json);
+        return;
+    }
+    let csr = exit_on_error(x509::CertificationRequest::from_pem(&text));
+    let names: Vec<String> = exit_on_error(csr.subject_alt_names())
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let verified = csr.verify();
+    let mut lines = vec![
+        "Zertifikatsanforderung (PKCS#10)".to_string(),
+        format!("Inhaber:        {}", csr.subject),
+        format!("Schlüssel:      {}", csr.public_key.description()),
+    ];
+    if !names.is_empty() {
+        lines.push(format!("Alt. Namen:     {}", names.join(", ")));
+    }
+    lines.push(match &verified {
+        Ok(()) => "Eigensignatur:  gültig".to_string(),
+        Err(e) => format!("Eigensignatur:  {}", e),
+    });
+    report_result(
+        &lines.join("\n"),
+        json!({
+            "type": "csr",
+            "subject": csr.subject.to_string(),
+            "key": csr.public_key.description(),
+            "subject_alt_names": names,
+            "self_signature_valid": verified.is_ok(),
+        }),
+    );
+}
+
+/// Prüft ein Zertifikat gegen einen Vertrauensspeicher; Exit-Code 1 bei Fehlern.
+pub fn x509_verify(
+    cert_path: &Path,
+    trust_path: &Path,
+    intermediates: &[&Path],
+    host: Option<&str>,
+) {
+    let leaf = load_certificate(cert_path);
+    let store = load_trust_store(trust_path);
+    let chain = load_certificate_chain(intermediates);
+    let mut options = x509::VerifyOptions::at(unix_now() as i64);
+    options.host = host;
+    let path = exit_on_error(x509::verify_path(&leaf, &chain, &store, &options));
+    let mut text = "Zertifikat gültig. Pfad:".to_string();
+    for (depth, cert) in path.iter().enumerate() {
+        text.push_str(&format!("\n  {}: {}", depth, cert.subject));
+    }
+    report(
+        &text,
+        json!({
+            "valid": true,
+            "path": path.iter().map(|c| c.subject.to_string()).collect::<Vec<_>>(),
+        }),
+    );
+}
+
+// ---------- CMS -----------
+/// Signiert eine Datei als CMS SignedData (DER), angehängt oder abgesetzt.
+pub fn cms_sign_file(
+    input_path: &Path,
+    cert_path: &Path,
+    key_path: &Path,
+    chain: &[&Path],
+    detached: bool,
+    output_path: &Path,
+) {
+    let content = read_input(input_path);
+    let cert = load_certificate(cert_path);
+    let key = load_signing_key(key_path);
+    let chain = load_certificate_chain(chain);
+    let signed = exit_on_error(cms::sign(&content, &cert, &key, &chain, detached));
+    write_output(output_path, &signed, Some(Label::Cms));
+    report(
+        &format!(
+            "CMS-Signatur gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({ "output": display_path(output_path, "stdout"), "detached": detached }),
+    );
+}
+
+/// Prüft CMS SignedData samt Zertifikatspfad; schreibt angehängten Inhalt auf Wunsch
+/// nach `output_path`. Exit-Code 1 bei Fehlern.
+pub fn cms_verify_file(
+    sig_path: &Path,
+    trust_path: &Path,
+    content_path: Option<&Path>,
+    output_path: Option<&Path>,
+) {
+    let data = read_input_dearmored(sig_path, Label::Cms);
+    let signed = exit_on_error(cms::SignedData::from_bytes(&data));
+    let store = load_trust_store(trust_path);
+    let detached = content_path.map(read_input);
+    let options = x509::VerifyOptions::at(unix_now() as i64);
+    let paths = exit_on_error(signed.verify_trusted(detached.as_deref(), &[], &store, &options));
+    if let (Some(output_path), Some(content)) = (output_path, &signed.content) {
+        write_output(output_path, content, None);
+    }
+    let mut lines = Vec::new();
+    let mut signers = Vec::new();
+    for (signer, path) in signed.signers.iter().zip(&paths) {
+        lines.push(format!("Signatur gültig: {}", path[0].subject));
+        if let Some(time) = signer.signing_time {
+            lines.push(format!("  Signiert am: {}", der::format_time(time)));
+        }
+        for (depth, cert) in path.iter().enumerate().skip(1) {
+            lines.push(format!("  {}: {}", depth, cert.subject));
+        }
+        signers.push(json!({
+            "signer": path[0].subject.to_string(),
+            "signing_time": signer.signing_time.map(der::format_time),
+            "path": path.iter().map(|c| c.subject.to_string()).collect::<Vec<_>>(),
+        }));
+    }
+    let mut json = json!({ "valid": true, "signers": signers });
+    if let (Some(output_path), Some(_)) = (output_path, &signed.content) {
+        lines.push(format!(
+            "Inhalt gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ));
+        json["output"] = json!(display_path(output_path, "stdout"));
+    }
+    report(&lines.join("\n"), json);
+}
+
+/// Verschlüsselt eine Datei als CMS AuthEnvelopedData für die Empfängerzertifikate.
+pub fn cms_encrypt_file(input_path: &Path, recipients: &[&Path], output_path: &Path) {
+    let content = read_input(input_path);
+    let recipients: Vec<x509::Certificate> =
+        recipients.iter().map(|p| load_certificate(p)).collect();
+    let enveloped = exit_on_error(cms::encrypt(&content, &recipients));
+    write_output(output_path, &enveloped, Some(Label::Cms));
+    report(
+        &format!(
+            "CMS-verschlüsselte Datei gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({
+            "output": display_path(output_path, "stdout"),
+            "recipients": recipients.len(),
+        }),
+    );
+}
+
+/// Entschlüsselt CMS (Auth)EnvelopedData mit Empfängerzertifikat und RSA-Schlüssel.
+pub fn cms_decrypt_file(input_path: &Path, cert_path: &Path, key_path: &Path, output_path: &Path) {
+    let data = read_input_dearmored(input_path, Label::Cms);
+    let cert = load_certificate(cert_path);
+    let key = load_rsa_private_key(key_path);
+    let content = exit_on_error(cms::decrypt(&data, &cert, &key));
+    write_output(output_path, &content, None);
+    report(
+        &format!(
+            "Entschlüsselte Datei gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({ "output": display_path(output_path, "stdout") }),
+    );
+}
+
+// ---------- PKCS#12 -----------
+/// Importiert Schlüssel und Zertifikate aus einer `.p12`/`.pfx`-Datei: Schlüsseldateien im
+/// Cryptomorph-Format, `cert.pem` und die übrigen Zertifikate in `chain.pem`.
+pub fn import_p12(input_path: &Path, password: &str, out_dir: &Path) {
+    let data = read_input_dearmored(input_path, Label::Pkcs12);
+    let bundle = exit_on_error(pkcs12::Pkcs12::from_der(&data, password));
+    create_out_dir(out_dir);
+    match &bundle.key {
+        Some(x509::SigningKey::Rsa { public, private }) => {
+            write_rsa_keys_pem(public, private, out_dir);
+        }
+        Some(x509::SigningKey::Ed25519 { public, private }) => {
+            write_ed25519_keys_pem(public, private, out_dir)
+        }
+        Some(x509::SigningKey::P256 { .. }) => {
+            fail("Nicht unterstützt: P-256-Schlüssel als Cryptomorph-Schlüsseldatei")
+        }
+        None => {}
+    }
+    let mut lines = Vec::new();
+    if let Some(key) = &bundle.key {
+        lines.push(format!("Schlüssel: {}", key.public_key().description()));
+    }
+    if let Some(cert) = &bundle.certificate {
+        write_output(&out_dir.join("cert.pem"), cert.to_pem().as_bytes(), None);
+        lines.push(format!("Zertifikat: {}", cert.subject));
+    }
+    if !bundle.ca_certificates.is_empty() {
+        let chain: String = bundle.ca_certificates.iter().map(|c| c.to_pem()).collect();
+        write_output(&out_dir.join("chain.pem"), chain.as_bytes(), None);
+        for cert in &bundle.ca_certificates {
+            lines.push(format!("Weiteres Zertifikat: {}", cert.subject));
+        }
+    }
+    if let Some(name) = &bundle.friendly_name {
+        lines.push(format!("Anzeigename: {}", name));
+    }
+    lines.push(format!(
+        "PKCS#12-Datei importiert nach: {}",
+        out_dir.display()
+    ));
+    report(
+        &lines.join("\n"),
+        json!({
+            "key": bundle.key.as_ref().map(|k| k.public_key().description()),
+            "certificate": bundle.certificate.as_ref().map(|c| c.subject.to_string()),
+            "ca_certificates": bundle
+                .ca_certificates
+                .iter()
+                .map(|c| c.subject.to_string())
+                .collect::<Vec<_>>(),
+            "friendly_name": bundle.friendly_name,
+            "output": out_dir,
+        }),
+    );
+}
+
+/// Exportiert Schlüssel, Zertifikat und Kette als PKCS#12 (PBES2/AES-256-CBC, HMAC-SHA256).
+/// Ohne `name` wird der Common Name des Zertifikats als Anzeigename verwendet.
+pub fn export_p12(
+    key_path: &Path,
+    cert_path: &Path,
+    chain: &[&Path],
+    name: Option<&str>,
+    password: &str,
+    output_path: &Path,
+) {
+    let cert = load_certificate(cert_path);
+    let friendly_name = name.or(cert.subject.common_name()).map(str::to_string);
+    let bundle = pkcs12::Pkcs12 {
+        key: Some(load_signing_key(key_path)),
+        certificate: Some(cert),
+        ca_certificates: load_certificate_chain(chain),
+        friendly_name,
+    };
+    let der = exit_on_error(bundle.to_der(password, pkcs12::DEFAULT_ITERATIONS));
+    write_output(output_path, &der, Some(Label::Pkcs12));
+    report(
+        &format!(
+            "PKCS#12-Datei gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({ "output": display_path(output_path, "stdout") }),
+    );
+}
+
+// ---------- OpenSSH -----------
+/// Lädt einen privaten Schlüssel zum SSH-Signieren: `openssh-key-v1` oder eine
+/// RSA-/Ed25519-Schlüsseldatei von Cryptomorph.
+pub fn load_ssh_private_key(path: &Path, passphrase: Option<&str>) -> ssh::PrivateKey {
+    let content = fs::read_to_string(path).expect("Fehler beim Lesen des Private Keys");
+    if pem_label(&content) == Some(ssh::key::PEM_PRIVATE_KEY) {
+        return exit_on_error(ssh::PrivateKey::from_openssh(&content, passphrase)).0;
+    }
+    match load_signing_key(path) {
+        x509::SigningKey::Rsa { public, private } => ssh::PrivateKey::Rsa { public, private },
+        x509::SigningKey::Ed25519 { public, private } => {
+            ssh::PrivateKey::Ed25519 { public, private }
+        }
+        x509::SigningKey::P256 { .. } => {
+            fail(format!("Nicht unterstützter Schlüssel: {}", path.display()))
+        }
+    }
+}
+
+/// Importiert einen OpenSSH-Schlüssel (`id_rsa`, `id_ed25519`) als Cryptomorph-Schlüsseldateien.
+pub fn ssh_import_key(input_path: &Path, passphrase: Option<&str>, out_dir: &Path) {
+    let content = String::from_utf8_lossy(&read_input(input_path)).into_owned();
+    let (key, comment) = exit_on_error(ssh::PrivateKey::from_openssh(&content, passphrase));
+    create_out_dir(out_dir);
+    match &key {
+        ssh::PrivateKey::Rsa { public, private } => {
+            write_rsa_keys_pem(public, private, out_dir);
+        }
+        ssh::PrivateKey::Ed25519 { public, private } => {
+            write_ed25519_keys_pem(public, private, out_dir)
+        }
+    }
+    let fingerprint = key.public_key().fingerprint();
+    let mut text = format!("Fingerabdruck: {}", fingerprint);
+    if !comment.is_empty() {
+        text.push_str(&format!("\nKommentar: {}", comment));
+    }
+    text.push_str(&format!(
+        "\nSSH-Schlüssel importiert nach: {}",
+        out_dir.display()
+    ));
+    report(
+        &text,
+        json!({ "fingerprint": fingerprint, "comment": comment, "output": out_dir }),
+    );
+}
+
+/// Exportiert einen RSA- oder Ed25519-Schlüssel als `id_rsa`/`id_ed25519` mit `.pub`-Datei;
+/// mit Passphrase wird der private Schlüssel wie von `ssh-keygen` verschlüsselt.
+pub fn ssh_export_key(
+    key_path: &Path,
+    comment: Option<&str>,
+    passphrase: Option<&str>,
+    out_dir: &Path,
+) {
+    let key = load_ssh_private_key(key_path, None);
+    let comment = comment.unwrap_or("");
+    let name = match key {
+        ssh::PrivateKey::Rsa { .. } => "id_rsa",
+        ssh::PrivateKey::Ed25519 { .. } => "id_ed25519",
+    };
+    let private = exit_on_error(key.to_openssh(comment, passphrase, ssh::key::DEFAULT_ROUNDS));
+    create_out_dir(out_dir);
+    write_output(&out_dir.join(name), private.as_bytes(), None);
+    // ssh und ssh-keygen verweigern private Schlüssel, die andere lesen dürfen
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(out_dir.join(name), fs::Permissions::from_mode(0o600))
+            .unwrap_or_else(|e| fail(format!("{}: {}", out_dir.join(name).display(), e)));
+    }
+    let public = format!("{}\n", key.public_key().to_openssh(comment));
+    write_output(
+        &out_dir.join(format!("{}.pub", name)),
+        public.as_bytes(),
+        None,
+    );
+    let fingerprint = key.public_key().fingerprint();
+    report(
+        &format!(
+            "Fingerabdruck: {}\nSSH-Schlüssel gespeichert in: {}",
+            fingerprint,
+            out_dir.join(name).display()
+        ),
+        json!({ "fingerprint": fingerprint, "output": out_dir.join(name) }),
+    );
+}
+
+/// Signiert eine Datei wie `ssh-keygen -Y sign -n <namespace>`.
+pub fn ssh_sign_file(
+    input_path: &Path,
+    key_path: &Path,
+    namespace: &str,
+    passphrase: Option<&str>,
+    output_path: &Path,
+) {
+    let message = read_input(input_path);
+    let key = load_ssh_private_key(key_path, passphrase);
+    let signature = exit_on_error(ssh::sign(&message, &key, namespace));
+    write_output(output_path, signature.to_armor().as_bytes(), None);
+    report(
+        &format!(
+            "SSH-Signatur gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({ "output": display_path(output_path, "stdout"), "namespace": namespace }),
+    );
+}
+
+/// Prüft eine SSH-Signatur wie `ssh-keygen -Y verify` gegen eine Datei mit erlaubten
+/// Unterzeichnern; bei ungültiger Signatur Abbruch mit Exit-Code 1.
+pub fn ssh_verify_file(
+    input_path: &Path,
+    signature_path: &Path,
+    allowed_signers_path: &Path,
+    principal: &str,
+    namespace: &str,
+) {
+    let message = read_input(input_path);
+    let armored = String::from_utf8_lossy(&read_input(signature_path)).into_owned();
+    let signature = exit_on_error(ssh::Signature::from_armor(&armored));
+    let signers = fs::read_to_string(allowed_signers_path)
+        .unwrap_or_else(|e| fail(format!("{}: {}", allowed_signers_path.display(), e)));
+    let signers = exit_on_error(ssh::AllowedSigners::parse(&signers));
+    exit_on_error(signers.verify(
+        &message,
+        &signature,
+        principal,
+        namespace,
+        i64::from(unix_now()),
+    ));
+    report(
+        &format!(
+            "Gültige \"{}\"-Signatur von {} mit {}-Schlüssel {}",
+            namespace,
+            principal,
+            signature.public_key.key_type(),
+            signature.public_key.fingerprint()
+        ),
+        json!({
+            "valid": true,
+            "namespace": namespace,
+            "principal": principal,
+            "key_type": signature.public_key.key_type(),
+            "fingerprint": signature.public_key.fingerprint(),
+        }),
+    );
+}
+
+// ---------- Datei-Verschlüsselung: mehrere Empfänger -----------
+pub fn encrypt_file_multi(input_path: &Path, recipients: &[Recipient], output_path: &Path) {
+    let plaintext = read_input(input_path);
+    let out = exit_on_error(envelope::encrypt(&plaintext, recipients));
+
+    write_output(output_path, &out, Some(Label::Encrypted));
+    report(
+        &format!(
+            "Datei für {} Empfänger verschlüsselt gespeichert in: {}",
+            recipients.len(),
+            display_path(output_path, "stdout")
+        ),
+        json!({
+            "recipients": recipients.len(),
+            "output": display_path(output_path, "stdout"),
+        }),
+    );
+}
+
+pub fn decrypt_file_multi(input_path: &Path, priv_key_path: &Path, output_path: &Path) {
+    let data = read_input_dearmored(input_path, Label::Encrypted);
+    let identity = load_identity(priv_key_path);
+
+    let plaintext = exit_on_error(envelope::decrypt(&data, &identity));
+    write_output(output_path, &plaintext, None);
+    report(
+        &format!(
+            "Datei entschlüsselt gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({ "output": display_path(output_path, "stdout") }),
+    );
+}
+
+// ---------- Schlüsselrotation (rekey) -----------
+/// Sammelt Dateien unter `path`; symbolische Links auf Verzeichnisse werden nicht verfolgt.
+fn collect_files(path: &Path, files: &mut Vec<std::path::PathBuf>) -> std::io::Result<()> {
+    if !fs::symlink_metadata(path)?.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+    for entry in entries {
+        let meta = fs::symlink_metadata(&entry)?;
+        if meta.is_dir() {
+            collect_files(&entry, files)?;
+        } else if meta.is_file() {
+            files.push(entry);
+        }
+    }
+    Ok(())
+}
+
+/// Ersetzt `path` atomar: erst eine temporäre Datei im selben Verzeichnis, dann umbenennen.
+/// Die Dateirechte des Originals bleiben erhalten.
+fn replace_file(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = path.with_file_name(format!(".{}.rekey-tmp", name));
+    write(&tmp, data)?;
+    let result = fs::metadata(path)
+        .and_then(|meta| fs::set_permissions(&tmp, meta.permissions()))
+        .and_then(|_| fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+fn rekey_error(file: &Path, error: impl std::fmt::Display) {
+    warn(
+        &format!("{}: {}", file.display(), error),
+        json!({ "file": file, "status": "error", "error": error.to_string() }),
+    );
+}
+
+/// Verpackt den Dateischlüssel aller Dateien unter `paths` für die neuen Empfänger.
+/// Verzeichnisse werden rekursiv durchsucht; Dateien, die kein bekanntes Format haben oder
+/// nicht zu `priv_key_path` passen, werden dort übersprungen. Mit `dry_run` wird nur
+/// berichtet, was geschehen würde.
+pub fn rekey_files(paths: &[&Path], priv_key_path: &Path, recipients: &[Recipient], dry_run: bool) {
+    let identity = load_identity(priv_key_path);
+    let (mut done, mut skipped, mut failed) = (0, 0, 0);
+    for root in paths {
+        let mut files = Vec::new();
+        if let Err(e) = collect_files(root, &mut files) {
+            rekey_error(root, e);
+            failed += 1;
+            continue;
+        }
+        let explicit = files.len() == 1 && files[0] == *root;
+        for file in files {
+            let data = match read(&file) {
+                Ok(data) => data,
+                Err(e) => {
+                    rekey_error(&file, e);
+                    failed += 1;
+                    continue;
+                }
+            };
+            // ASCII-Armor bleibt beim Umschlüsseln mit Label und Headern erhalten
+            let armored = armor::is_armored(&data).then(|| armor::Armor::decode(&data));
+            let binary = match &armored {
+                Some(Ok(block)) => &block.data,
+                _ => &data,
+            };
+            let inspected = match &armored {
+                Some(Err(e)) => Err(e.clone()),
+                _ => rekey::inspect(binary, &identity),
+            };
+            // Passt die Datei nicht zum Schlüssel, wird sie beim Durchsuchen übersprungen
+            let format = match inspected {
+                Ok(format) => format,
+                Err(e) if explicit => {
+                    rekey_error(&file, e);
+                    failed += 1;
+                    continue;
+                }
+                Err(e) => {
+                    report(
+                        &format!("{}: übersprungen ({})", file.display(), e),
+                        json!({ "file": file, "status": "skipped", "reason": e.to_string() }),
+                    );
+                    skipped += 1;
+                    continue;
+                }
+            };
+            // Auch im Probelauf wird der neue Header erzeugt, damit Fehler sichtbar werden
+            let result = rekey::rekey(binary, &identity, recipients)
+                .map_err(|e| e.to_string())
+                .and_then(|out| {
+                    if dry_run {
+                        return Ok(());
+                    }
+                    let out = match &armored {
+                        Some(Ok(block)) => armor::Armor {
+                            data: out,
+                            ..block.clone()
+                        }
+                        .encode()
+                        .into_bytes(),
+                        _ => out,
+                    };
+                    replace_file(&file, &out).map_err(|e| e.to_string())
+                });
+            if let Err(e) = result {
+                rekey_error(&file, e);
+                failed += 1;
+                continue;
+            }
+            let action = if dry_run {
+                "würde umgeschlüsselt"
+            } else {
+                "umgeschlüsselt"
+            };
+            let (text, format_name, old) = match format {
+                FileFormat::Envelope { recipients: old } => (
+                    format!(
+                        "{}: {}, {} → {} Empfänger",
+                        file.display(),
+                        action,
+                        old,
+                        recipients.len()
+                    ),
+                    "envelope",
+                    old,
+                ),
+                FileFormat::LegacyRsa => (
+                    format!("{}: {} (rsa_encrypt-Format)", file.display(), action),
+                    "rsa",
+                    1,
+                ),
+            };
+            report(
+                &text,
+                json!({
+                    "file": file,
+                    "status": if dry_run { "would_rekey" } else { "rekeyed" },
+                    "format": format_name,
+                    "old_recipients": old,
+                    "new_recipients": recipients.len(),
+                }),
+            );
+            done += 1;
+        }
+    }
+    report(
+        &format!(
+            "{} Dateien {}, {} übersprungen, {} Fehler",
+            done,
+            if dry_run {
+                "würden umgeschlüsselt"
+            } else {
+                "umgeschlüsselt"
+            },
+            skipped,
+            failed
+        ),
+        json!({
+            "rekeyed": done,
+            "skipped": skipped,
+            "failed": failed,
+            "dry_run": dry_run,
+        }),
+    );
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
+
+// ---------- Signieren + Verschlüsseln (seal/open) -----------
+pub fn seal_file(
+    input_path: &Path,
+    sender_key_path: &Path,
+    recipients: &[Recipient],
+    output_path: &Path,
+) {
+    let message = read_input(input_path);
+    let sender = load_rsa_private_key(sender_key_path);
+    let out = exit_on_error(seal::seal(&message, &sender, recipients));
+
+    write_output(output_path, &out, Some(Label::Sealed));
+    report(
+        &format!(
+            "Datei signiert und für {} Empfänger verschlüsselt: {}",
+            recipients.len(),
+            display_path(output_path, "stdout")
+        ),
+        json!({
+            "recipients": recipients.len(),
+            "output": display_path(output_path, "stdout"),
+        }),
+    );
+}
+
+pub fn open_file(
+    input_path: &Path,
+    priv_key_path: &Path,
+    sender_pub_path: &Path,
+    output_path: &Path,
+) {
+    let data = read_input_dearmored(input_path, Label::Sealed);
+    let identity = load_identity(priv_key_path);
+    let sender = load_rsa_public_key(sender_pub_path);
+
+    let message = exit_on_error(seal::open(&data, &identity, &sender));
+    write_output(output_path, &message, None);
+    report(
+        &format!(
+            "Absender verifiziert, Datei entschlüsselt gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({ "sender_verified": true, "output": display_path(output_path, "stdout") }),
+    );
+}
+
+// ---------- age-Format -----------
+/// Schreibt eine age-Identitätsdatei und gibt den zugehörigen Empfänger (`age1…`) zurück.
+pub fn write_age_identity(path: &Path) -> String {
+    let (pub_key, priv_key) = x25519::generate_x25519_keypair();
+    let recipient = age::encode_recipient(&pub_key);
+    let content = format!(
+        "# public key: {}\n{}\n",
+        recipient,
+        age::encode_identity(&priv_key)
+    );
+    write_output(path, content.as_bytes(), None);
+    recipient
+}
+
+/// Erzeugt eine age-Identität und meldet den Empfänger.
+pub fn age_keygen(path: &Path) {
+    let recipient = write_age_identity(path);
+    report(
+        &format!(
+            "age-Identität gespeichert in: {}\nÖffentlicher Schlüssel: {}",
+            display_path(path, "stdout"),
+            recipient
+        ),
+        json!({ "recipient": recipient, "output": display_path(path, "stdout") }),
+    );
+}
+
+/// Lädt einen age-Empfänger: `age1…` direkt oder eine X25519-PEM-Schlüsseldatei.
+pub fn load_age_recipient(value: &str) -> age::Recipient {
+    if let Some(pub_key) = age::parse_recipient(value) {
+        return age::Recipient::X25519(pub_key);
+    }
+    match load_recipient(Path::new(value)) {
+        Recipient::X25519(pub_key) => age::Recipient::X25519(pub_key),
+        _ => fail(format!("age unterstützt nur X25519-Empfänger: {}", value)),
+    }
+}
+
+/// Liest eine age-Empfängerliste (`age1…` pro Zeile, `#` für Kommentare).
+pub fn load_age_recipients_file(path: &Path) -> Vec<age::Recipient> {
+    let content = fs::read_to_string(path).expect("Fehler beim Lesen der Empfängerliste");
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            age::Recipient::X25519(
+                age::parse_recipient(line)
+                    .unwrap_or_else(|| fail(format!("Ungültiger age-Empfänger: {}", line))),
+            )
+        })
+        .collect()
+}
+
+/// Lädt age-Identitäten aus einer age-Identitätsdatei oder einem X25519-PEM-Schlüssel.
+pub fn load_age_identities(path: &Path) -> Vec<age::Identity> {
+    let content = fs::read_to_string(path).expect("Fehler beim Lesen der Identitätsdatei");
+    if pem_label(&content) == Some("X25519 PRIVATE KEY") {
+        let Identity::X25519(priv_key) = load_identity(path) else {
+            unreachable!()
+        };
+        return vec![age::Identity::X25519(priv_key)];
+    }
+    age::parse_identity_file(&content)
+        .unwrap_or_else(|| fail(format!("Ungültige age-Identitätsdatei: {}", path.display())))
+        .into_iter()
+        .map(age::Identity::X25519)
+        .collect()
+}
+
+/// Liest die Passphrase aus der ersten Zeile einer Datei.
+pub fn read_passphrase_file(path: &Path) -> String {
+    let content = fs::read_to_string(path).expect("Fehler beim Lesen der Passphrase-Datei");
+    content.lines().next().unwrap_or("").to_string()
+}
+
+/// Verschlüsselt im age-Format; mit `--armor` als ASCII-Armor nach age-Spezifikation.
+pub fn age_encrypt_file(input_path: &Path, recipients: &[age::Recipient], output_path: &Path) {
+    let plaintext = read_input(input_path);
+    let out = age::encrypt(&plaintext, recipients, false).unwrap_or_else(|e| fail(e));
+    write_output(output_path, &out, Some(Label::Age));
+    report(
+        &format!(
+            "age-Datei gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({
+            "recipients": recipients.len(),
+            "armor": output_options().armor,
+            "output": display_path(output_path, "stdout"),
+        }),
+    );
+}
+
+pub fn age_decrypt_file(input_path: &Path, identities: &[age::Identity], output_path: &Path) {
+    let data = read_input(input_path);
+    let plaintext = age::decrypt(&data, identities).unwrap_or_else(|e| fail(e));
+    write_output(output_path, &plaintext, None);
+    report(
+        &format!(
+            "age-Datei entschlüsselt gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({ "output": display_path(output_path, "stdout") }),
+    );
+}
+
+// ---------- Verzeichnisse (tar + age) -----------
+fn report_archive_summary(summary: &archive::Summary, text: String, mut json: serde_json::Value) {
+    json["files"] = json!(summary.files);
+    json["bytes"] = json!(summary.bytes);
+    json["directories"] = json!(summary.directories);
+    json["symlinks"] = json!(summary.symlinks);
+    report(
+        &format!(
+            "{} Dateien ({} Byte), {} Verzeichnisse, {} symbolische Links\n{}",
+            summary.files, summary.bytes, summary.directories, summary.symlinks, text
+        ),
+        json,
+    );
+}
+
+/// Packt und verschlüsselt ein Verzeichnis als Datenstrom; `-` schreibt nach stdout.
+/// Mit `--armor` wird der Strom fortlaufend als age-Armor geschrieben.
+pub fn encrypt_directory(dir: &Path, recipients: &[age::Recipient], output_path: &Path) {
+    let output: Box<dyn Write + Send> = if is_stdio(output_path) {
+        DATA_ON_STDOUT.store(true, Ordering::Relaxed);
+        let stdout = io::stdout();
+        if !output_options().armor && stdout.is_terminal() {
+            fail("Binäre Ausgabe auf ein Terminal; --out <datei> oder --armor angeben");
+        }
+        Box::new(stdout)
+    } else {
+        Box::new(
+            fs::File::create(output_path)
+                .unwrap_or_else(|e| fail(format!("{}: {}", output_path.display(), e))),
+        )
+    };
+    let output = io::BufWriter::new(output);
+    let io_err = |e: io::Error| {
+        archive::ArchiveError::Io(format!("{}: {}", display_path(output_path, "stdout"), e))
+    };
+    let result = if output_options().armor {
+        armor::ArmorWriter::new(output, Label::Age)
+            .map_err(io_err)
+            .and_then(|mut writer| {
+                let summary = archive::encrypt_dir(dir, recipients, &mut writer)?;
+                writer.finish().map_err(io_err)?;
+                Ok(summary)
+            })
+    } else {
+        archive::encrypt_dir(dir, recipients, output)
+    };
+    let summary = result.unwrap_or_else(|e| {
+        if !is_stdio(output_path) {
+            let _ = fs::remove_file(output_path);
+        }
+        fail(e)
+    });
+    for path in &summary.skipped {
+        warn(
+            &format!("Übergangen (kein regulärer Eintrag): {}", path.display()),
+            json!({ "skipped": path }),
+        );
+    }
+    report_archive_summary(
+        &summary,
+        format!(
+            "Verzeichnis verschlüsselt gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({ "output": display_path(output_path, "stdout") }),
+    );
+}
+
+/// Entschlüsselt und entpackt ein Verzeichnis; `-` liest stdin. ASCII-Armor wird erkannt.
+pub fn decrypt_directory(input_path: &Path, identities: &[age::Identity], dest: &Path) {
+    let input: Box<dyn io::Read + Send> = if is_stdio(input_path) {
+        Box::new(io::stdin())
+    } else {
+        Box::new(
+            fs::File::open(input_path)
+                .unwrap_or_else(|e| fail(format!("{}: {}", input_path.display(), e))),
+        )
+    };
+    let mut input = io::BufReader::new(input);
+    let peek = io::BufRead::fill_buf(&mut input)
+        .unwrap_or_else(|e| fail(format!("{}: {}", display_path(input_path, "stdin"), e)));
+    let result = if age::armor::is_armored(peek) {
+        // ASCII-Armor wird im Speicher dekodiert
+        let mut text = Vec::new();
+        input
+            .read_to_end(&mut text)
+            .unwrap_or_else(|e| fail(format!("{}: {}", display_path(input_path, "stdin"), e)));
+        age::armor::decode(&text)
+            .map_err(archive::ArchiveError::from)
+            .and_then(|binary| archive::decrypt_dir(&binary[..], identities, dest))
+    } else {
+        archive::decrypt_dir(input, identities, dest)
+    };
+    let summary = result.unwrap_or_else(|e| fail(e));
+    report_archive_summary(
+        &summary,
+        format!("Verzeichnis entschlüsselt nach: {}", dest.display()),
+        json!({ "output": dest }),
+    );
+}
+
 // ---------- Datei-Verschlüsselung: RSA + AES -----------
 pub fn rsa_encrypt_file(input_path: &Path, pub_key_path: &Path, output_path: &Path) {
-    let plaintext = read(input_path).expect("Fehler beim Lesen der Eingabedatei");
+    let plaintext = read_input(input_path);
     let pub_key = load_rsa_public_key(pub_key_path);
     let aes_key: [u8; 32] = rand::random();
     let (iv, ciphertext) = encrypt_aes256(&aes_key, &plaintext);
@@ -85,48 +1834,139 @@ pub fn rsa_encrypt_file(input_path: &Path, pub_key_path: &Path, output_path: &Pa
     out.extend_from_slice(&iv);
     out.extend_from_slice(&ciphertext);
 
-    write(output_path, out).expect("Fehler beim Schreiben der Ausgabedatei");
-    println!(
-        "Datei verschlüsselt gespeichert in: {}",
-        output_path.display()
+    write_output(output_path, &out, Some(Label::RsaEncrypted));
+    report(
+        &format!(
+            "Datei verschlüsselt gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({ "output": display_path(output_path, "stdout") }),
     );
 }
 
 // ---------- Datei-Entschlüsselung: RSA + AES -----------
 pub fn rsa_decrypt_file(input_path: &Path, priv_key_path: &Path, output_path: &Path) {
-    let data = read(input_path).expect("Fehler beim Lesen der Eingabedatei");
+    let data = read_input_dearmored(input_path, Label::RsaEncrypted);
     let priv_key = load_rsa_private_key(priv_key_path);
 
-    let key_len = u16::from_be_bytes([data[0], data[1]]) as usize;
-    let enc_key = &data[2..2 + key_len];
-    let iv = &data[2 + key_len..2 + key_len + 16];
-    let ciphertext = &data[2 + key_len + 16..];
+    // Länge (2 Byte) || RSA-verschlüsselter Schlüssel || IV (16 Byte) || Ciphertext
+    let parts = data.get(..2).and_then(|len| {
+        let key_len = u16::from_be_bytes([len[0], len[1]]) as usize;
+        let enc_key = data.get(2..2 + key_len)?;
+        let iv: &[u8; 16] = data.get(2 + key_len..2 + key_len + 16)?.try_into().ok()?;
+        Some((enc_key, iv, &data[2 + key_len + 16..]))
+    });
+    let (enc_key, iv, ciphertext) =
+        parts.unwrap_or_else(|| fail(CryptoError::InvalidFormat("Datei ist zu kurz".into())));
 
-    let aes_key_big = decrypt_rsa(&BigUint::from_bytes_be(enc_key), &priv_key);
-    let mut aes_key = aes_key_big.to_bytes_be();
-
-    if aes_key.len() < 32 {
-        let mut padded = vec![0u8; 32 - aes_key.len()];
-        padded.append(&mut aes_key);
-        aes_key = padded;
+    // Ein falscher Schlüssel zeigt sich als zu langer AES-Schlüssel oder ungültiges Padding
+    let aes_key = decrypt_rsa(&BigUint::from_bytes_be(enc_key), &priv_key).to_bytes_be();
+    if aes_key.len() > 32 {
+        fail(CryptoError::DecryptionFailed);
     }
+    let mut padded = vec![0u8; 32 - aes_key.len()];
+    padded.extend_from_slice(&aes_key);
+    let plaintext = decrypt_aes_cbc(&padded, iv, ciphertext)
+        .unwrap_or_else(|| fail(CryptoError::DecryptionFailed));
 
-    let aes_key: [u8; 32] = aes_key.try_into().expect("Ungültige AES-Key-Länge");
-    let plaintext = decrypt_aes256(&aes_key, iv, ciphertext);
+    write_output(output_path, &plaintext, None);
+    report(
+        &format!(
+            "Datei entschlüsselt gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({ "output": display_path(output_path, "stdout") }),
+    );
+}
 
-    write(output_path, &plaintext).expect("Fehler beim Schreiben der Ausgabedatei");
-    println!(
-        "Datei entschlüsselt gespeichert in: {}",
-        output_path.display()
+// ---------- RSA-Schlüsselprüfung -----------
+/// Lädt einen öffentlichen und, falls vorhanden, privaten RSA-Schlüssel aus einer
+/// Cryptomorph-Schlüsseldatei, einem OpenSSH-Schlüssel (`id_rsa`, `id_rsa.pub`) oder einem
+/// X.509-Zertifikat.
+fn load_rsa_key_any(path: &Path) -> (PublicKey, Option<PrivateKey>) {
+    let content = String::from_utf8_lossy(&read_input(path)).into_owned();
+    let not_rsa = || {
+        fail(format!(
+            "Kein RSA-Schlüssel: {}",
+            display_path(path, "stdin")
+        ))
+    };
+    match pem_label(&content) {
+        Some(keystore::key::PEM_RSA_PRIVATE) => {
+            (load_rsa_public_key(path), Some(load_rsa_private_key(path)))
+        }
+        Some(keystore::key::PEM_RSA_PUBLIC) => (load_rsa_public_key(path), None),
+        Some(ssh::key::PEM_PRIVATE_KEY) => {
+            match exit_on_error(ssh::PrivateKey::from_openssh(&content, None)).0 {
+                ssh::PrivateKey::Rsa { public, private } => (public, Some(private)),
+                _ => not_rsa(),
+            }
+        }
+        Some("CERTIFICATE") => {
+            match exit_on_error(x509::Certificate::from_pem(&content)).public_key {
+                x509::SubjectPublicKey::Rsa(public) => (public, None),
+                _ => not_rsa(),
+            }
+        }
+        _ if content.starts_with("ssh-rsa ") => {
+            match exit_on_error(ssh::PublicKey::from_openssh(&content)).0 {
+                ssh::PublicKey::Rsa(public) => (public, None),
+                _ => not_rsa(),
+            }
+        }
+        _ => not_rsa(),
+    }
+}
+
+/// Prüft einen RSA-Schlüssel nach FIPS 186-5; Exit-Code 1, wenn er nicht konform ist.
+pub fn rsa_validate_key(key_path: &Path) {
+    let (public, private) = load_rsa_key_any(key_path);
+    let issues = validate_rsa_key(&public, private.as_ref());
+    let kind = if private.is_some() {
+        "privater"
+    } else {
+        "öffentlicher"
+    };
+    let mut text = if issues.is_empty() {
+        format!(
+            "RSA-Schlüssel ({}, {} Bit) entspricht FIPS 186-5",
+            kind,
+            public.n.bits()
+        )
+    } else {
+        format!(
+            "RSA-Schlüssel ({}, {} Bit) entspricht nicht FIPS 186-5:",
+            kind,
+            public.n.bits()
+        )
+    };
+    for issue in &issues {
+        text.push_str(&format!("\n  - {}", issue));
+    }
+    report_result(
+        &text,
+        json!({
+            "bits": public.n.bits(),
+            "private": private.is_some(),
+            "valid": issues.is_empty(),
+            "issues": issues.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        }),
     );
+    if !issues.is_empty() {
+        std::process::exit(1);
+    }
 }
 
 // ---------- AES File-Only -----------
+fn parse_aes_key(key_hex: &str) -> [u8; 32] {
+    let key = decode(key_hex).unwrap_or_else(|_| fail("Ungültiger Hex-Schlüssel"));
+    key.try_into()
+        .unwrap_or_else(|_| fail("AES-Schlüssel muss 32 Byte lang sein"))
+}
+
 pub fn aes_encrypt_file(input_path: &Path, key_hex: &str, output_path: &Path) {
-    let plaintext = read(input_path).expect("Fehler beim Lesen der Eingabedatei");
-    let key = decode(key_hex).expect("Ungültiger Hex-Schlüssel");
-    assert_eq!(key.len(), 32, "AES-Schlüssel muss 32 Byte lang sein");
-    let key_array: [u8; 32] = key.try_into().unwrap();
+    let plaintext = read_input(input_path);
+    let key_array = parse_aes_key(key_hex);
 
     let (iv, ciphertext) = encrypt_aes256(&key_array, &plaintext);
 
@@ -134,54 +1974,356 @@ pub fn aes_encrypt_file(input_path: &Path, key_hex: &str, output_path: &Path) {
     out.extend_from_slice(&iv);
     out.extend_from_slice(&ciphertext);
 
-    write(output_path, out).expect("Fehler beim Schreiben der Ausgabedatei");
-    println!("AES-Datei gespeichert in: {}", output_path.display());
+    write_output(output_path, &out, Some(Label::AesEncrypted));
+    report(
+        &format!(
+            "AES-Datei gespeichert in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({ "output": display_path(output_path, "stdout") }),
+    );
 }
 
 pub fn aes_decrypt_file(input_path: &Path, key_hex: &str, output_path: &Path) {
-    let data = read(input_path).expect("Fehler beim Lesen der Eingabedatei");
-    let key = decode(key_hex).expect("Ungültiger Hex-Schlüssel");
-    assert_eq!(key.len(), 32, "AES-Schlüssel muss 32 Byte lang sein");
-    let key_array: [u8; 32] = key.try_into().unwrap();
-
-    let iv = &data[..16];
-    let ciphertext = &data[16..];
+    let data = read_input_dearmored(input_path, Label::AesEncrypted);
+    let key_array = parse_aes_key(key_hex);
 
-    let plaintext = decrypt_aes256(&key_array, iv, ciphertext);
-    write(output_path, plaintext).expect("Fehler beim Schreiben der Ausgabedatei");
-    println!(
-        "Datei erfolgreich entschlüsselt in: {}",
-        output_path.display()
+    // IV (16 Byte) || Ciphertext
+    let Some((iv, ciphertext)) = data.split_first_chunk::<16>() else {
+        fail(CryptoError::InvalidFormat("Datei ist zu kurz".into()));
+    };
+    // ein falscher Schlüssel zeigt sich als ungültiges Padding
+    let plaintext = decrypt_aes_cbc(&key_array, iv, ciphertext)
+        .unwrap_or_else(|| fail(CryptoError::DecryptionFailed));
+    write_output(output_path, &plaintext, None);
+    report(
+        &format!(
+            "Datei erfolgreich entschlüsselt in: {}",
+            display_path(output_path, "stdout")
+        ),
+        json!({ "output": display_path(output_path, "stdout") }),
     );
 }
 
+/// Gibt einen zufälligen 256-Bit-Schlüssel als Hex aus.
+pub fn aes_keygen() {
+    let key: [u8; 32] = rand::random();
+    match output_options().format {
+        OutputFormat::Text => {
+            println!("🔑 Zufälliger AES-256-Schlüssel (Hex):");
+            println!("{}", hex::encode(key));
+        }
+        _ => report_result(&hex::encode(key), json!({ "key": hex::encode(key) })),
+    }
+}
+
 // ---------- Signieren & Verifizieren -----------
-pub fn rsa_sign_file(input_path: &Path, priv_key_path: &Path, sig_path: &Path) {
-    let data = read(input_path).expect("Fehler beim Lesen der Datei");
-    let hash = Sha256::digest(&data);
-    let hash_int = BigUint::from_bytes_be(&hash);
+/// Signiert eine Datei mit einem RSA- oder Ed25519-Schlüssel. Die abgesetzte Signatur nennt
+/// Unterzeichner, Verfahren, Erstellzeit und Kommentar; mit `clearsign` entsteht eine
+/// signierte Textnachricht.
+pub fn sign_file(
+    input_path: &Path,
+    key_path: &Path,
+    output_path: &Path,
+    hash: HashAlgorithm,
+    comment: Option<&str>,
+    clearsign: bool,
+) {
+    let data = read_input(input_path);
+    let key = load_signing_key(key_path);
+    let created = u64::from(unix_now());
+    if clearsign {
+        let text = std::str::from_utf8(&data)
+            .unwrap_or_else(|_| fail("--clearsign ist nur für Textdateien (UTF-8) möglich"));
+        let signed = exit_on_error(signature::clearsign::sign(
+            text, &key, hash, created, comment,
+        ));
+        write_output(output_path, signed.as_bytes(), None);
+    } else {
+        let sig = exit_on_error(Signature::sign(&data, &key, hash, created, comment));
+        write_output(output_path, &sig.to_bytes(), Some(Label::Signature));
+    }
+    let fingerprint = hex::encode(key.public_key().fingerprint());
+    report(
+        &format!(
+            "Datei signiert: {}\nUnterzeichner: {}",
+            display_path(output_path, "stdout"),
+            fingerprint
+        ),
+        json!({
+            "output": display_path(output_path, "stdout"),
+            "signer": fingerprint,
+            "clearsign": clearsign,
+        }),
+    );
+}
 
-    let priv_key = load_rsa_private_key(priv_key_path);
-    let signature = decrypt_rsa(&hash_int, &priv_key);
-    let sig_bytes = signature.to_bytes_be();
+/// Öffentlicher Schlüssel zum Prüfen aus einer Schlüsseldatei (auch der privaten).
+fn load_verifying_key(path: &Path) -> x509::SubjectPublicKey {
+    let path = &public_key_path(path);
+    let content =
+        fs::read_to_string(path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+    let key = exit_on_error(keystore::key::PublicKey::from_pem(&content));
+    x509::SubjectPublicKey::from_spki(&key.to_spki()).unwrap_or_else(|_| {
+        fail(format!(
+            "{} kann nicht signieren: {}",
+            key.description(),
+            path.display()
+        ))
+    })
+}
+
+/// Prüft eine Signatur. Ohne `sig_path` ist die Eingabe eine signierte Textnachricht, deren
+/// Text nach erfolgreicher Prüfung nach `output_path` geschrieben wird. Ohne `key_path` wird
+/// der Unterzeichner im Schlüsselspeicher gesucht. Eine ungültige Signatur endet mit Exit-Code 1.
+pub fn verify_file(
+    input_path: &Path,
+    sig_path: Option<&Path>,
+    key_path: Option<&Path>,
+    output_path: Option<&Path>,
+) {
+    let data = read_input(input_path);
+    let (message, sig, text) = match sig_path {
+        Some(sig_path) => {
+            let sig_data = read_input_dearmored(sig_path, Label::Signature);
+            if !signature::is_signature(&sig_data) {
+                return verify_legacy_rsa_file(&data, &sig_data, key_path);
+            }
+            let sig = exit_on_error(Signature::from_bytes(&sig_data));
+            (data, sig, None)
+        }
+        None => {
+            if !signature::clearsign::is_clearsigned(&data) {
+                fail("Keine signierte Textnachricht; abgesetzte Signatur mit --sig angeben");
+            }
+            let (text, sig) = exit_on_error(signature::clearsign::decode(&data));
+            (signature::clearsign::signed_text(&text), sig, Some(text))
+        }
+    };
 
-    write(sig_path, sig_bytes).expect("Fehler beim Schreiben der Signatur");
-    println!("Datei signiert: {}", sig_path.display());
+    let fingerprint = hex::encode(sig.signer);
+    let (key, labels) = match key_path {
+        Some(path) => (load_verifying_key(path), Vec::new()),
+        None => {
+            let entry = open_key_store().find(&fingerprint).unwrap_or_else(|_| {
+                fail(format!(
+                    "Unterzeichner {} ist nicht im Schlüsselspeicher; --key angeben",
+                    fingerprint
+                ))
+            });
+            (load_verifying_key(&entry.public_path()), entry.labels)
+        }
+    };
+    if key.fingerprint() != sig.signer {
+        fail(format!(
+            "Signatur stammt von {}, nicht vom angegebenen Schlüssel {}",
+            fingerprint,
+            hex::encode(key.fingerprint())
+        ));
+    }
+    if sig.verify(&message, &key).is_err() {
+        fail("Signatur ungültig!");
+    }
+    if let (Some(text), Some(output_path)) = (&text, output_path) {
+        write_output(output_path, text.as_bytes(), None);
+    }
+
+    let mut lines = vec![
+        "Signatur gültig.".to_string(),
+        format!("Unterzeichner: {}", fingerprint),
+    ];
+    if !labels.is_empty() {
+        lines.push(format!("Bezeichnungen: {}", labels.join(", ")));
+    }
+    lines.push(format!(
+        "Verfahren: {}, {}",
+        sig.scheme.name(),
+        sig.hash.name()
+    ));
+    lines.push(format!(
+        "Erstellt: {}",
+        der::format_time(sig.created as i64)
+    ));
+    if let Some(comment) = &sig.comment {
+        lines.push(format!("Kommentar: {}", comment));
+    }
+    report(
+        &lines.join("\n"),
+        json!({
+            "valid": true,
+            "signer": fingerprint,
+            "labels": labels,
+            "scheme": sig.scheme.name(),
+            "hash": sig.hash.name(),
+            "created": sig.created,
+            "comment": sig.comment,
+        }),
+    );
 }
 
-pub fn rsa_verify_file(input_path: &Path, pub_key_path: &Path, sig_path: &Path) {
-    let data = read(input_path).expect("Fehler beim Lesen der Datei");
-    let signature = read(sig_path).expect("Fehler beim Lesen der Signatur");
-    let hash = Sha256::digest(&data);
+/// Prüft eine Signatur im alten Format ohne Metadaten; dafür ist der RSA-Schlüssel nötig.
+fn verify_legacy_rsa_file(data: &[u8], signature: &[u8], key_path: Option<&Path>) {
+    let Some(key_path) = key_path else {
+        fail("Signatur im alten Format ohne Unterzeichner; --key angeben");
+    };
+    let x509::SubjectPublicKey::Rsa(pub_key) = load_verifying_key(key_path) else {
+        fail("Signaturen im alten Format erfordern einen RSA-Schlüssel");
+    };
+    warn(
+        "Altes Signaturformat ohne Unterzeichner und Zeitpunkt; neu signieren empfohlen",
+        json!({ "warning": "legacy_format" }),
+    );
+    if !signature::verify_legacy_rsa(data, signature, &pub_key) {
+        fail("Signatur ungültig!");
+    }
+    report("Signatur gültig.", json!({ "valid": true, "legacy": true }));
+}
 
-    let sig_int = BigUint::from_bytes_be(&signature);
-    let pub_key = load_rsa_public_key(pub_key_path);
-    let decrypted = encrypt_rsa(&sig_int, &pub_key);
-    let hash_int = BigUint::from_bytes_be(&hash);
+// ---------- Primzahlen -----------
+/// Liest eine Zahl dezimal oder hexadezimal (`0x…`).
+fn parse_number(text: &str) -> BigUint {
+    let text = text.trim();
+    let parsed = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16),
+        None => BigUint::parse_bytes(text.as_bytes(), 10),
+    };
+    parsed.unwrap_or_else(|| fail(format!("Keine gültige Zahl: {}", text)))
+}
 
-    if decrypted == hash_int {
-        println!("Signatur gültig.");
+fn proof_name(certificate: &PrimeCertificate) -> &'static str {
+    match certificate.proof {
+        PrimeProof::TrialDivision => "Probedivision",
+        PrimeProof::Pratt { .. } => "Pratt",
+        PrimeProof::Pocklington { .. } => "Pocklington",
+    }
+}
+
+/// Erstellt ein Primzahlzertifikat für `number` und schreibt es nach `output_path`.
+pub fn prime_prove(number: &str, output_path: &Path) {
+    let n = parse_number(number);
+    let certificate = PrimeCertificate::prove(&n).unwrap_or_else(|e| fail(e));
+    write_output(output_path, certificate.to_text().as_bytes(), None);
+    report(
+        &format!(
+            "Primzahl bewiesen ({}): {}\nZertifikat: {}",
+            proof_name(&certificate),
+            n,
+            display_path(output_path, "stdout")
+        ),
+        json!({
+            "prime": n.to_string(),
+            "bits": n.bits(),
+            "proof": proof_name(&certificate),
+            "output": display_path(output_path, "stdout"),
+        }),
+    );
+}
+
+/// Prüft ein Primzahlzertifikat; ein ungültiges endet mit Exit-Code 1.
+pub fn prime_check(input_path: &Path) {
+    let data = read_input(input_path);
+    let text = std::str::from_utf8(&data)
+        .unwrap_or_else(|_| fail("Primzahlzertifikat ist kein gültiger Text"));
+    let certificate = PrimeCertificate::parse(text)
+        .and_then(|certificate| certificate.verify().map(|_| certificate))
+        .unwrap_or_else(|e| fail(e));
+    let n = &certificate.n;
+    report_result(
+        &format!(
+            "Primzahl bewiesen ({}, {} Bit): {}",
+            proof_name(&certificate),
+            n.bits(),
+            n
+        ),
+        json!({
+            "prime": n.to_string(),
+            "bits": n.bits(),
+            "proof": proof_name(&certificate),
+            "valid": true,
+        }),
+    );
+}
+
+/// Zerlegt `number` in Primfaktoren und gibt φ, λ und die Teilerfunktionen mit aus.
+pub fn factor_number(number: &str) {
+    let n = parse_number(number);
+    if n.is_zero() {
+        fail("0 hat keine Primfaktorzerlegung");
+    }
+    let factorization = factorize(&n);
+    let product = if factorization.factors.is_empty() {
+        "1".to_string()
     } else {
-        println!("Signatur ungültig!");
+        factorization
+            .factors
+            .iter()
+            .map(|(p, e)| match e {
+                1 => p.to_string(),
+                _ => format!("{}^{}", p, e),
+            })
+            .collect::<Vec<_>>()
+            .join(" · ")
+    };
+    let totient = factorization.totient();
+    let lambda = factorization.carmichael_lambda();
+    let count = factorization.divisor_count();
+    let sum = factorization.divisor_sum(1);
+    report_result(
+        &format!(
+            "{} = {}\nφ(n) = {}\nλ(n) = {}\nTeiler: {}, Summe {}",
+            n, product, totient, lambda, count, sum
+        ),
+        json!({
+            "number": n.to_string(),
+            "factors": factorization
+                .factors
+                .iter()
+                .map(|(p, e)| json!({ "prime": p.to_string(), "exponent": e }))
+                .collect::<Vec<_>>(),
+            "totient": totient.to_string(),
+            "carmichael_lambda": lambda.to_string(),
+            "divisor_count": count.to_string(),
+            "divisor_sum": sum.to_string(),
+        }),
+    );
+}
+
+/// Erzeugt eine beweisbare Primzahl mit `method` (`maurer` oder `shawe-taylor`) und
+/// schreibt das Zertifikat optional nach `cert_path`. Ohne `seed` zieht Shawe-Taylor einen
+/// zufälligen Startwert, der zur Reproduktion mit ausgegeben wird.
+pub fn prime_generate(bits: usize, method: &str, seed: Option<&str>, cert_path: Option<&Path>) {
+    let (certificate, seed) = match method {
+        "shawe-taylor" => {
+            let seed = match seed {
+                Some(hex) => decode(hex)
+                    .unwrap_or_else(|_| fail("Startwert muss hexadezimal angegeben werden")),
+                None => rand::random::<[u8; 32]>().to_vec(),
+            };
+            let prime = shawe_taylor_prime(bits, &seed).unwrap_or_else(|e| fail(e));
+            (prime.certificate, Some(hex::encode(seed)))
+        }
+        _ => {
+            if seed.is_some() {
+                fail("--seed gibt es nur mit --method shawe-taylor");
+            }
+            (maurer_prime(bits).unwrap_or_else(|e| fail(e)), None)
+        }
+    };
+    if let Some(path) = cert_path {
+        write_output(path, certificate.to_text().as_bytes(), None);
     }
+    if let Some(seed) = &seed {
+        report(&format!("Startwert: {}", seed), json!({ "seed": seed }));
+    }
+    let n = &certificate.n;
+    report_result(
+        &n.to_string(),
+        json!({
+            "prime": n.to_string(),
+            "bits": n.bits(),
+            "method": method,
+            "seed": seed,
+            "certificate": cert_path.map(|path| display_path(path, "stdout")),
+        }),
+    );
 }
@@ -0,0 +1,245 @@
+//! Hybrides Dateiformat mit mehreren Empfängern.
+//!
+//! Aufbau:
+//!
+//! ```text
+//! "CMPH" | Version (1 Byte) | Anzahl Einträge (u16)
+//! je Eintrag: Art (1 Byte) | Länge (u32) | Inhalt
+//! HMAC-SHA256 über alle vorherigen Header-Bytes (32 Byte)
+//! Nonce (12 Byte) | AES-256-GCM-Ciphertext der Nutzdaten
+//! ```
+//!
+//! Header-MAC- und Nutzdatenschlüssel werden per HKDF aus dem Dateischlüssel
+//! abgeleitet. Die Nutzdaten hängen damit nicht von den Empfängereinträgen ab.
+
+use crate::hybrid::recipient::{Identity, Recipient, Stanza, StanzaKind};
+use crate::symmetric::aes::{decrypt_aes256_gcm, encrypt_aes256_gcm};
+use crate::utils::error::CryptoError;
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub const MAGIC: &[u8; 4] = b"CMPH";
+pub const VERSION: u8 = 1;
+
+const HEADER_INFO: &[u8] = b"cryptomorph/v1/header";
+const PAYLOAD_INFO: &[u8] = b"cryptomorph/v1/payload";
+
+/// Header einer Mehrempfänger-Datei.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub stanzas: Vec<Stanza>,
+    pub mac: [u8; 32],
+}
+
+/// Leitet einen Teilschlüssel aus dem Dateischlüssel ab.
+fn derive_key(file_key: &[u8; 32], info: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, file_key)
+        .expand(info, &mut key)
+        .expect("HKDF-Ausgabelänge ungültig");
+    key
+}
+
+fn header_mac(file_key: &[u8; 32], header_bytes: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(&derive_key(file_key, HEADER_INFO))
+        .expect("HMAC akzeptiert jede Schlüssellänge");
+    mac.update(header_bytes);
+    mac
+}
+
+impl Header {
+    /// Erstellt einen Header für die gegebenen Einträge und berechnet den MAC.
+    pub fn new(stanzas: Vec<Stanza>, file_key: &[u8; 32]) -> Self {
+        let mut header = Header {
+            stanzas,
+            mac: [0u8; 32],
+        };
+        let tag = header_mac(file_key, &header.encode_without_mac()).finalize();
+        header.mac.copy_from_slice(&tag.into_bytes());
+        header
+    }
+
+    /// Kodiert alle Header-Felder außer dem MAC.
+    fn encode_without_mac(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(VERSION);
+        out.extend_from_slice(&(self.stanzas.len() as u16).to_be_bytes());
+        for stanza in &self.stanzas {
+            out.push(stanza.kind as u8);
+            out.extend_from_slice(&(stanza.body.len() as u32).to_be_bytes());
+            out.extend_from_slice(&stanza.body);
+        }
+        out
+    }
+
+    /// Serialisiert den vollständigen Header.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.encode_without_mac();
+        out.extend_from_slice(&self.mac);
+        out
+    }
+
+    /// Liest einen Header vom Anfang von `data`.
+    /// Gibt den Header und die Anzahl gelesener Bytes zurück.
+    pub fn parse(data: &[u8]) -> Result<(Header, usize), CryptoError> {
+        let err = |msg: &str| CryptoError::InvalidFormat(msg.to_string());
+        if !is_envelope(data) {
+            return Err(err("kein Cryptomorph-Mehrempfänger-Header"));
+        }
+        if data[4] != VERSION {
+            return Err(err("nicht unterstützte Formatversion"));
+        }
+        let mut pos = 5;
+        let take = |pos: &mut usize, len: usize| -> Result<&[u8], CryptoError> {
+            let end = pos
+                .checked_add(len)
+                .filter(|&end| end <= data.len())
+                .ok_or_else(|| err("Header abgeschnitten"))?;
+            let slice = &data[*pos..end];
+            *pos = end;
+            Ok(slice)
+        };
+
+        let count = u16::from_be_bytes(take(&mut pos, 2)?.try_into().unwrap()) as usize;
+        if count == 0 {
+            return Err(err("Header ohne Empfänger"));
+        }
+        let mut stanzas = Vec::with_capacity(count);
+        for _ in 0..count {
+            let kind = StanzaKind::from_byte(take(&mut pos, 1)?[0])
+                .ok_or_else(|| err("unbekannte Empfängerart"))?;
+            let len = u32::from_be_bytes(take(&mut pos, 4)?.try_into().unwrap()) as usize;
+            let body = take(&mut pos, len)?.to_vec();
+            stanzas.push(Stanza { kind, body });
+        }
+        let mac = take(&mut pos, 32)?.try_into().unwrap();
+        Ok((Header { stanzas, mac }, pos))
+    }
+
+    /// Prüft den Header-MAC mit dem entpackten Dateischlüssel (konstante Laufzeit).
+    pub fn verify_mac(&self, file_key: &[u8; 32]) -> bool {
+        header_mac(file_key, &self.encode_without_mac())
+            .verify_slice(&self.mac)
+            .is_ok()
+    }
+
+    /// Sucht den ersten Eintrag, den `identity` entpacken kann, und prüft den MAC.
+    pub fn unwrap_file_key(&self, identity: &Identity) -> Result<[u8; 32], CryptoError> {
+        let file_key = self
+            .stanzas
+            .iter()
+            .find_map(|stanza| identity.unwrap(stanza))
+            .ok_or(CryptoError::NoMatchingRecipient)?;
+        if !self.verify_mac(&file_key) {
+            return Err(CryptoError::DecryptionFailed);
+        }
+        Ok(file_key)
+    }
+}
+
+/// Prüft anhand der Magic-Bytes, ob `data` eine Mehrempfänger-Datei ist.
+pub fn is_envelope(data: &[u8]) -> bool {
+    data.len() >= 5 && &data[..4] == MAGIC
+}
+
+/// Verschlüsselt `plaintext` für alle Empfänger.
+/// Jeder Empfänger kann die Datei allein mit seinem privaten Schlüssel öffnen.
+pub fn encrypt(plaintext: &[u8], recipients: &[Recipient]) -> Result<Vec<u8>, CryptoError> {
+    if recipients.is_empty() || recipients.len() > u16::MAX as usize {
+        return Err(CryptoError::InvalidFormat(
+            "ungültige Anzahl an Empfängern".into(),
+        ));
+    }
+    let file_key: [u8; 32] = rand::random();
+    let stanzas = recipients
+        .iter()
+        .map(|r| r.wrap(&file_key))
+        .collect::<Result<Vec<_>, _>>()?;
+    let header = Header::new(stanzas, &file_key);
+
+    let (nonce, ciphertext) =
+        encrypt_aes256_gcm(&derive_key(&file_key, PAYLOAD_INFO), plaintext, &[]);
+
+    let mut out = header.to_bytes();
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Entschlüsselt eine Mehrempfänger-Datei mit dem privaten Schlüssel eines Empfängers.
+pub fn decrypt(data: &[u8], identity: &Identity) -> Result<Vec<u8>, CryptoError> {
+    let (header, header_len) = Header::parse(data)?;
+    let file_key = header.unwrap_file_key(identity)?;
+
+    let payload = &data[header_len..];
+    if payload.len() < 12 {
+        return Err(CryptoError::InvalidFormat("Nutzdaten abgeschnitten".into()));
+    }
+    let (nonce, ciphertext) = payload.split_at(12);
+    decrypt_aes256_gcm(&derive_key(&file_key, PAYLOAD_INFO), nonce, ciphertext, &[])
+        .ok_or(CryptoError::DecryptionFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asymmetric::rsa::generate_rsa_keypair;
+    use crate::asymmetric::x25519::generate_x25519_keypair;
+    use crate::pqcrypto::mlkem::generate_mlkem_keypair;
+
+    #[test]
+    fn test_mixed_recipients_each_can_decrypt() {
+        let (rsa_pub, rsa_priv) = generate_rsa_keypair(1024);
+        let (x_pub, x_priv) = generate_x25519_keypair();
+        let (m_pub, m_priv) = generate_mlkem_keypair();
+        let recipients = [
+            Recipient::Rsa(rsa_pub),
+            Recipient::X25519(x_pub),
+            Recipient::MlKem(m_pub),
+        ];
+
+        let plaintext = b"Build-Artefakt fuer das ganze Team";
+        let data = encrypt(plaintext, &recipients).unwrap();
+
+        for identity in [
+            Identity::Rsa(rsa_priv),
+            Identity::X25519(x_priv),
+            Identity::MlKem(m_priv),
+        ] {
+            assert_eq!(decrypt(&data, &identity).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn test_non_recipient_is_rejected() {
+        let (x_pub, _) = generate_x25519_keypair();
+        let (_, other_priv) = generate_x25519_keypair();
+        let data = encrypt(b"geheim", &[Recipient::X25519(x_pub)]).unwrap();
+        assert_eq!(
+            decrypt(&data, &Identity::X25519(other_priv)),
+            Err(CryptoError::NoMatchingRecipient)
+        );
+    }
+
+    #[test]
+    fn test_tampered_header_is_rejected() {
+        let (a_pub, a_priv) = generate_x25519_keypair();
+        let (b_pub, _) = generate_x25519_keypair();
+        let data = encrypt(
+            b"geheim",
+            &[Recipient::X25519(a_pub), Recipient::X25519(b_pub)],
+        )
+        .unwrap();
+
+        // Entfernt den zweiten Empfänger, ohne den MAC anzupassen
+        let (mut header, len) = Header::parse(&data).unwrap();
+        header.stanzas.pop();
+        let mut forged = header.to_bytes();
+        forged.extend_from_slice(&data[len..]);
+        assert_eq!(
+            decrypt(&forged, &Identity::X25519(a_priv)),
+            Err(CryptoError::DecryptionFailed)
+        );
+    }
+}
@@ -0,0 +1,4 @@
+pub mod envelope;
+pub mod recipient;
+pub mod rekey;
+pub mod seal;
@@ -0,0 +1,222 @@
+use crate::asymmetric::rsa::{self, decrypt_rsa_oaep, encrypt_rsa_oaep};
+use crate::asymmetric::x25519;
+use crate::pqcrypto::mlkem;
+use crate::symmetric::aes::{decrypt_aes256_gcm, encrypt_aes256_gcm};
+use crate::utils::error::CryptoError;
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+
+const RSA_OAEP_LABEL: &[u8] = b"cryptomorph/v1/rsa-oaep";
+const X25519_INFO: &[u8] = b"cryptomorph/v1/x25519";
+const MLKEM_INFO: &[u8] = b"cryptomorph/v1/mlkem768";
+
+/// Verfahren, mit dem der Dateischlüssel in einem Empfängereintrag verpackt ist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StanzaKind {
+    RsaOaep = 1,
+    X25519 = 2,
+    MlKem768 = 3,
+}
+
+impl StanzaKind {
+    pub fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            1 => Some(StanzaKind::RsaOaep),
+            2 => Some(StanzaKind::X25519),
+            3 => Some(StanzaKind::MlKem768),
+            _ => None,
+        }
+    }
+}
+
+/// Ein Empfängereintrag im Header: der für genau einen Empfänger verpackte Dateischlüssel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stanza {
+    pub kind: StanzaKind,
+    pub body: Vec<u8>,
+}
+
+/// Öffentlicher Schlüssel eines Empfängers.
+#[derive(Debug)]
+pub enum Recipient {
+    Rsa(rsa::PublicKey),
+    X25519(x25519::PublicKey),
+    MlKem(mlkem::PublicKey),
+}
+
+/// Privater Schlüssel, mit dem ein Empfänger seinen Eintrag entpackt.
+pub enum Identity {
+    Rsa(rsa::PrivateKey),
+    X25519(x25519::PrivateKey),
+    MlKem(mlkem::PrivateKey),
+}
+
+/// SHA-256 über Schlüsselart und öffentliches Schlüsselmaterial.
+fn fingerprint(kind: StanzaKind, key_bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([kind as u8]);
+    hasher.update(key_bytes);
+    hasher.finalize().into()
+}
+
+/// Leitet einen AES-256-Schlüssel zum Verpacken des Dateischlüssels ab (HKDF-SHA256).
+fn wrap_key(shared: &[u8], salt: &[u8], info: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(salt), shared)
+        .expand(info, &mut key)
+        .expect("HKDF-Ausgabelänge ungültig");
+    key
+}
+
+/// Verpackt den Dateischlüssel mit AES-256-GCM: nonce || ciphertext.
+fn seal_file_key(key: &[u8; 32], file_key: &[u8; 32]) -> Vec<u8> {
+    let (nonce, ct) = encrypt_aes256_gcm(key, file_key, &[]);
+    let mut out = nonce;
+    out.extend_from_slice(&ct);
+    out
+}
+
+fn open_file_key(key: &[u8; 32], sealed: &[u8]) -> Option<[u8; 32]> {
+    if sealed.len() < 12 {
+        return None;
+    }
+    let (nonce, ct) = sealed.split_at(12);
+    decrypt_aes256_gcm(key, nonce, ct, &[])?.try_into().ok()
+}
+
+impl Recipient {
+    /// Fingerabdruck des Empfängerschlüssels (bei RSA über den Modul n).
+    pub fn fingerprint(&self) -> [u8; 32] {
+        match self {
+            Recipient::Rsa(pub_key) => fingerprint(StanzaKind::RsaOaep, &pub_key.n.to_bytes_be()),
+            Recipient::X25519(pub_key) => fingerprint(StanzaKind::X25519, &pub_key.bytes),
+            Recipient::MlKem(pub_key) => fingerprint(StanzaKind::MlKem768, &pub_key.bytes),
+        }
+    }
+
+    /// Verpackt den Dateischlüssel für diesen Empfänger.
+    pub fn wrap(&self, file_key: &[u8; 32]) -> Result<Stanza, CryptoError> {
+        match self {
+            Recipient::Rsa(pub_key) => Ok(Stanza {
+                kind: StanzaKind::RsaOaep,
+                body: encrypt_rsa_oaep(file_key, RSA_OAEP_LABEL, pub_key),
+            }),
+            Recipient::X25519(pub_key) => {
+                let (eph_pub, eph_priv) = x25519::generate_x25519_keypair();
+                let shared = x25519::diffie_hellman(&eph_priv, pub_key).ok_or_else(|| {
+                    CryptoError::InvalidFormat("ungültiger X25519-Empfängerschlüssel".into())
+                })?;
+                let salt = [eph_pub.bytes, pub_key.bytes].concat();
+                let key = wrap_key(&shared, &salt, X25519_INFO);
+
+                let mut body = eph_pub.bytes.to_vec();
+                body.extend_from_slice(&seal_file_key(&key, file_key));
+                Ok(Stanza {
+                    kind: StanzaKind::X25519,
+                    body,
+                })
+            }
+            Recipient::MlKem(pub_key) => {
+                let (ct, shared) = mlkem::encapsulate(pub_key).ok_or_else(|| {
+                    CryptoError::InvalidFormat("ungültiger ML-KEM-Empfängerschlüssel".into())
+                })?;
+                let key = wrap_key(&shared, &ct, MLKEM_INFO);
+
+                let mut body = ct;
+                body.extend_from_slice(&seal_file_key(&key, file_key));
+                Ok(Stanza {
+                    kind: StanzaKind::MlKem768,
+                    body,
+                })
+            }
+        }
+    }
+}
+
+impl Identity {
+    /// Fingerabdruck des zugehörigen öffentlichen Schlüssels, siehe [`Recipient::fingerprint`].
+    pub fn fingerprint(&self) -> [u8; 32] {
+        match self {
+            Identity::Rsa(priv_key) => fingerprint(StanzaKind::RsaOaep, &priv_key.n.to_bytes_be()),
+            Identity::X25519(priv_key) => fingerprint(
+                StanzaKind::X25519,
+                &x25519::public_key_from_private(priv_key).bytes,
+            ),
+            Identity::MlKem(priv_key) => fingerprint(
+                StanzaKind::MlKem768,
+                &mlkem::public_key_from_private(priv_key).bytes,
+            ),
+        }
+    }
+
+    /// Versucht, den Dateischlüssel aus einem Eintrag zu entpacken.
+    /// Gibt None zurück, wenn der Eintrag nicht für diesen Schlüssel bestimmt ist.
+    pub fn unwrap(&self, stanza: &Stanza) -> Option<[u8; 32]> {
+        match (self, stanza.kind) {
+            (Identity::Rsa(priv_key), StanzaKind::RsaOaep) => {
+                decrypt_rsa_oaep(&stanza.body, RSA_OAEP_LABEL, priv_key)?
+                    .try_into()
+                    .ok()
+            }
+            (Identity::X25519(priv_key), StanzaKind::X25519) => {
+                if stanza.body.len() < 32 {
+                    return None;
+                }
+                let (eph, sealed) = stanza.body.split_at(32);
+                let eph_pub = x25519::PublicKey {
+                    bytes: eph.try_into().ok()?,
+                };
+                let own_pub = x25519::public_key_from_private(priv_key);
+                let shared = x25519::diffie_hellman(priv_key, &eph_pub)?;
+                let salt = [eph_pub.bytes, own_pub.bytes].concat();
+                open_file_key(&wrap_key(&shared, &salt, X25519_INFO), sealed)
+            }
+            (Identity::MlKem(priv_key), StanzaKind::MlKem768) => {
+                if stanza.body.len() < mlkem::CIPHERTEXT_LEN {
+                    return None;
+                }
+                let (ct, sealed) = stanza.body.split_at(mlkem::CIPHERTEXT_LEN);
+                let shared = mlkem::decapsulate(ct, priv_key)?;
+                open_file_key(&wrap_key(&shared, ct, MLKEM_INFO), sealed)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asymmetric::rsa::generate_rsa_keypair;
+    use crate::pqcrypto::mlkem::generate_mlkem_keypair;
+
+    #[test]
+    fn test_wrap_unwrap_each_kind() {
+        let file_key: [u8; 32] = rand::random();
+
+        let (rsa_pub, rsa_priv) = generate_rsa_keypair(1024);
+        let (x_pub, x_priv) = x25519::generate_x25519_keypair();
+        let (m_pub, m_priv) = generate_mlkem_keypair();
+
+        let pairs = [
+            (Recipient::Rsa(rsa_pub), Identity::Rsa(rsa_priv)),
+            (Recipient::X25519(x_pub), Identity::X25519(x_priv)),
+            (Recipient::MlKem(m_pub), Identity::MlKem(m_priv)),
+        ];
+        for (recipient, identity) in &pairs {
+            assert_eq!(recipient.fingerprint(), identity.fingerprint());
+            let stanza = recipient.wrap(&file_key).unwrap();
+            assert_eq!(identity.unwrap(&stanza), Some(file_key));
+        }
+    }
+
+    #[test]
+    fn test_foreign_stanza_is_ignored() {
+        let file_key: [u8; 32] = rand::random();
+        let (x_pub, _) = x25519::generate_x25519_keypair();
+        let (_, other_priv) = x25519::generate_x25519_keypair();
+
+        let stanza = Recipient::X25519(x_pub).wrap(&file_key).unwrap();
+        assert_eq!(Identity::X25519(other_priv).unwrap(&stanza), None);
+    }
+}
@@ -0,0 +1,184 @@
+//! Schlüsselrotation: verpackt nur den Dateischlüssel neu, die verschlüsselten Nutzdaten
+//! werden unverändert übernommen und nie entschlüsselt.
+//!
+//! Unterstützt werden
+//!
+//! - Mehrempfänger-Dateien ([`envelope`], `encrypt`): neuer Header für beliebige Empfänger,
+//! - Dateien von `rsa_encrypt` (Altformat `Schlüssellänge (u16) | RSA(AES-Schlüssel) | IV |
+//!   AES-256-CBC`): genau ein neuer RSA-Empfänger, da das Format nur einen Eintrag kennt.
+
+use crate::asymmetric::rsa::{decrypt_rsa, encrypt_rsa};
+use crate::hybrid::envelope::{self, Header};
+use crate::hybrid::recipient::{Identity, Recipient};
+use crate::utils::error::CryptoError;
+use num_bigint::BigUint;
+
+/// Erkanntes Dateiformat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// Mehrempfänger-Format mit der Anzahl der Empfängereinträge.
+    Envelope { recipients: usize },
+    /// Altformat von `rsa_encrypt`.
+    LegacyRsa,
+}
+
+/// Zerlegt eine Datei im Altformat in verschlüsselten Schlüssel und Rest (IV + Ciphertext).
+fn split_legacy(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let key_len = u16::from_be_bytes(data.get(..2)?.try_into().ok()?) as usize;
+    let rest = data.get(2 + key_len..)?;
+    // IV und mindestens ein CBC-Block
+    if key_len == 0 || rest.len() < 32 || rest.len() % 16 != 0 {
+        return None;
+    }
+    Some((&data[2..2 + key_len], rest))
+}
+
+/// Entpackt den AES-Schlüssel des Altformats. Das Format verwendet RSA ohne Padding; ein
+/// falscher Schlüssel fällt nur daran auf, dass das Ergebnis nicht in 32 Byte passt.
+fn unwrap_legacy(enc_key: &[u8], identity: &Identity) -> Result<BigUint, CryptoError> {
+    let Identity::Rsa(priv_key) = identity else {
+        return Err(CryptoError::NoMatchingRecipient);
+    };
+    let c = BigUint::from_bytes_be(enc_key);
+    if c >= priv_key.n {
+        return Err(CryptoError::NoMatchingRecipient);
+    }
+    let aes_key = decrypt_rsa(&c, priv_key);
+    if aes_key.bits() > 256 {
+        return Err(CryptoError::NoMatchingRecipient);
+    }
+    Ok(aes_key)
+}
+
+/// Prüft, ob `identity` die Datei umschlüsseln könnte, ohne etwas zu verändern
+/// (für Probeläufe).
+pub fn inspect(data: &[u8], identity: &Identity) -> Result<FileFormat, CryptoError> {
+    if envelope::is_envelope(data) {
+        let (header, _) = Header::parse(data)?;
+        header.unwrap_file_key(identity)?;
+        return Ok(FileFormat::Envelope {
+            recipients: header.stanzas.len(),
+        });
+    }
+    let (enc_key, _) = split_legacy(data).ok_or_else(|| {
+        CryptoError::InvalidFormat("weder Mehrempfänger-Datei noch rsa_encrypt-Datei".into())
+    })?;
+    unwrap_legacy(enc_key, identity)?;
+    Ok(FileFormat::LegacyRsa)
+}
+
+/// Verpackt den Dateischlüssel für `recipients` neu und gibt die neue Datei zurück.
+/// Die Nutzdaten werden Byte für Byte übernommen.
+pub fn rekey(
+    data: &[u8],
+    identity: &Identity,
+    recipients: &[Recipient],
+) -> Result<Vec<u8>, CryptoError> {
+    if recipients.is_empty() || recipients.len() > u16::MAX as usize {
+        return Err(CryptoError::InvalidFormat(
+            "ungültige Anzahl an Empfängern".into(),
+        ));
+    }
+    if envelope::is_envelope(data) {
+        let (header, header_len) = Header::parse(data)?;
+        let file_key = header.unwrap_file_key(identity)?;
+        let stanzas = recipients
+            .iter()
+            .map(|r| r.wrap(&file_key))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut out = Header::new(stanzas, &file_key).to_bytes();
+        out.extend_from_slice(&data[header_len..]);
+        return Ok(out);
+    }
+
+    let (enc_key, rest) = split_legacy(data).ok_or_else(|| {
+        CryptoError::InvalidFormat("weder Mehrempfänger-Datei noch rsa_encrypt-Datei".into())
+    })?;
+    let [Recipient::Rsa(pub_key)] = recipients else {
+        return Err(CryptoError::Unsupported(
+            "rsa_encrypt-Dateien haben genau einen RSA-Empfänger; für mehrere Empfänger mit encrypt neu verschlüsseln".into(),
+        ));
+    };
+    let aes_key = unwrap_legacy(enc_key, identity)?;
+    let new_key = encrypt_rsa(&aes_key, pub_key).to_bytes_be();
+    let mut out = (new_key.len() as u16).to_be_bytes().to_vec();
+    out.extend_from_slice(&new_key);
+    out.extend_from_slice(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asymmetric::rsa::generate_rsa_keypair;
+    use crate::asymmetric::x25519::generate_x25519_keypair;
+    use crate::symmetric::aes::{decrypt_aes256, encrypt_aes256};
+
+    #[test]
+    fn test_rekey_envelope_keeps_payload() {
+        let (old_pub, old_priv) = generate_x25519_keypair();
+        let (new_pub, new_priv) = generate_x25519_keypair();
+        let (rsa_pub, rsa_priv) = generate_rsa_keypair(1024);
+        let data = envelope::encrypt(b"Quartalszahlen", &[Recipient::X25519(old_pub)]).unwrap();
+        let old = Identity::X25519(old_priv);
+        assert_eq!(
+            inspect(&data, &old).unwrap(),
+            FileFormat::Envelope { recipients: 1 }
+        );
+
+        let recipients = [Recipient::X25519(new_pub), Recipient::Rsa(rsa_pub)];
+        let rekeyed = rekey(&data, &old, &recipients).unwrap();
+        let (_, old_len) = Header::parse(&data).unwrap();
+        let (_, new_len) = Header::parse(&rekeyed).unwrap();
+        assert_eq!(data[old_len..], rekeyed[new_len..]);
+
+        for identity in [Identity::X25519(new_priv), Identity::Rsa(rsa_priv)] {
+            assert_eq!(
+                envelope::decrypt(&rekeyed, &identity).unwrap(),
+                b"Quartalszahlen"
+            );
+        }
+        assert_eq!(
+            envelope::decrypt(&rekeyed, &old),
+            Err(CryptoError::NoMatchingRecipient)
+        );
+    }
+
+    #[test]
+    fn test_rekey_legacy_rsa() {
+        let (old_pub, old_priv) = generate_rsa_keypair(1024);
+        let (new_pub, new_priv) = generate_rsa_keypair(1024);
+        let (x_pub, _) = generate_x25519_keypair();
+
+        // wie rsa_encrypt_file
+        let aes_key: [u8; 32] = rand::random();
+        let (iv, ciphertext) = encrypt_aes256(&aes_key, b"alte Datei");
+        let enc_key = encrypt_rsa(&BigUint::from_bytes_be(&aes_key), &old_pub).to_bytes_be();
+        let mut data = (enc_key.len() as u16).to_be_bytes().to_vec();
+        data.extend_from_slice(&enc_key);
+        data.extend_from_slice(&iv);
+        data.extend_from_slice(&ciphertext);
+
+        let old = Identity::Rsa(old_priv);
+        let new = Identity::Rsa(new_priv);
+        assert_eq!(inspect(&data, &old).unwrap(), FileFormat::LegacyRsa);
+        assert_eq!(inspect(&data, &new), Err(CryptoError::NoMatchingRecipient));
+        assert!(matches!(
+            rekey(&data, &old, &[Recipient::X25519(x_pub)]),
+            Err(CryptoError::Unsupported(_))
+        ));
+
+        let rekeyed = rekey(&data, &old, &[Recipient::Rsa(new_pub)]).unwrap();
+        assert!(rekeyed.ends_with(&[iv.as_slice(), &ciphertext].concat()));
+        let (enc_key, rest) = split_legacy(&rekeyed).unwrap();
+        let Identity::Rsa(new_priv) = &new else {
+            unreachable!()
+        };
+        let mut key = decrypt_rsa(&BigUint::from_bytes_be(enc_key), new_priv).to_bytes_be();
+        while key.len() < 32 {
+            key.insert(0, 0);
+        }
+        let plaintext = decrypt_aes256(&key.try_into().unwrap(), &rest[..16], &rest[16..]);
+        assert_eq!(plaintext, b"alte Datei");
+    }
+}
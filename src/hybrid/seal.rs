@@ -0,0 +1,158 @@
+//! Signieren-dann-Verschlüsseln mit Absenderidentität (`seal` / `open`).
+//!
+//! Der Absender signiert die Nachricht zusammen mit den Fingerabdrücken aller
+//! Empfänger. Ein Empfänger, der die Nachricht neu verschlüsselt an Dritte
+//! weiterleitet, kann die Signatur daher nicht wiederverwenden: beim Öffnen
+//! muss der eigene Fingerabdruck in der signierten Empfängerliste stehen.
+//!
+//! Innerer Klartext (wird mit [`envelope::encrypt`] verschlüsselt):
+//!
+//! ```text
+//! "CMSG" | Version (1 Byte) | Absender-Fingerabdruck (32 Byte)
+//! Anzahl Empfänger (u16) | Empfänger-Fingerabdrücke (je 32 Byte)
+//! Signaturlänge (u16) | RSASSA-PSS-Signatur | Nachricht
+//! ```
+
+use crate::asymmetric::rsa::{self, sign_rsa_pss, verify_rsa_pss};
+use crate::hybrid::envelope;
+use crate::hybrid::recipient::{Identity, Recipient};
+use crate::utils::error::CryptoError;
+
+const MAGIC: &[u8; 4] = b"CMSG";
+const VERSION: u8 = 1;
+const CONTEXT: &[u8] = b"cryptomorph/v1/seal";
+
+/// Signierter Inhalt: Kontext, Absender, Empfängerliste und Nachricht.
+fn transcript(sender_fp: &[u8; 32], recipient_fps: &[[u8; 32]], message: &[u8]) -> Vec<u8> {
+    let mut out = CONTEXT.to_vec();
+    out.extend_from_slice(sender_fp);
+    out.extend_from_slice(&(recipient_fps.len() as u16).to_be_bytes());
+    for fp in recipient_fps {
+        out.extend_from_slice(fp);
+    }
+    out.extend_from_slice(message);
+    out
+}
+
+/// Signiert `message` mit dem RSA-Schlüssel des Absenders und verschlüsselt sie für alle Empfänger.
+pub fn seal(
+    message: &[u8],
+    sender: &rsa::PrivateKey,
+    recipients: &[Recipient],
+) -> Result<Vec<u8>, CryptoError> {
+    let sender_fp = Identity::Rsa(sender.clone()).fingerprint();
+    let recipient_fps: Vec<[u8; 32]> = recipients.iter().map(Recipient::fingerprint).collect();
+    let signature = sign_rsa_pss(&transcript(&sender_fp, &recipient_fps, message), sender)?;
+
+    let mut inner = MAGIC.to_vec();
+    inner.push(VERSION);
+    inner.extend_from_slice(&sender_fp);
+    inner.extend_from_slice(&(recipient_fps.len() as u16).to_be_bytes());
+    for fp in &recipient_fps {
+        inner.extend_from_slice(fp);
+    }
+    inner.extend_from_slice(&(signature.len() as u16).to_be_bytes());
+    inner.extend_from_slice(&signature);
+    inner.extend_from_slice(message);
+
+    envelope::encrypt(&inner, recipients)
+}
+
+/// Entschlüsselt eine versiegelte Nachricht und prüft Absender und Empfängerbindung.
+///
+/// Schlägt fehl, wenn die Signatur nicht vom erwarteten Absender stammt oder
+/// `identity` nicht zu den vom Absender signierten Empfängern gehört.
+pub fn open(
+    data: &[u8],
+    identity: &Identity,
+    sender: &rsa::PublicKey,
+) -> Result<Vec<u8>, CryptoError> {
+    let inner = envelope::decrypt(data, identity)?;
+    let err = |msg: &str| CryptoError::InvalidFormat(msg.to_string());
+
+    if inner.len() < 5 || &inner[..4] != MAGIC {
+        return Err(err("keine versiegelte Nachricht"));
+    }
+    if inner[4] != VERSION {
+        return Err(err("nicht unterstützte Formatversion"));
+    }
+    let mut pos = 5;
+    let mut take = |len: usize| -> Result<&[u8], CryptoError> {
+        let slice = inner
+            .get(pos..pos + len)
+            .ok_or_else(|| err("Nachricht abgeschnitten"))?;
+        pos += len;
+        Ok(slice)
+    };
+
+    let sender_fp: [u8; 32] = take(32)?.try_into().unwrap();
+    let count = u16::from_be_bytes(take(2)?.try_into().unwrap()) as usize;
+    let recipient_fps = (0..count)
+        .map(|_| take(32).map(|fp| fp.try_into().unwrap()))
+        .collect::<Result<Vec<[u8; 32]>, _>>()?;
+    let sig_len = u16::from_be_bytes(take(2)?.try_into().unwrap()) as usize;
+    let signature = take(sig_len)?.to_vec();
+    let message = &inner[pos..];
+
+    if sender_fp != Recipient::Rsa(sender.clone()).fingerprint()
+        || !verify_rsa_pss(
+            &transcript(&sender_fp, &recipient_fps, message),
+            &signature,
+            sender,
+        )
+    {
+        return Err(CryptoError::InvalidSignature);
+    }
+    if !recipient_fps.contains(&identity.fingerprint()) {
+        return Err(CryptoError::NotSignedForRecipient);
+    }
+    Ok(message.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asymmetric::rsa::generate_rsa_keypair;
+    use crate::asymmetric::x25519::generate_x25519_keypair;
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let (alice_pub, alice_priv) = generate_rsa_keypair(1024);
+        let (bob_pub, bob_priv) = generate_x25519_keypair();
+
+        let sealed = seal(b"Vertrag v2", &alice_priv, &[Recipient::X25519(bob_pub)]).unwrap();
+        let opened = open(&sealed, &Identity::X25519(bob_priv), &alice_pub).unwrap();
+        assert_eq!(opened, b"Vertrag v2");
+    }
+
+    #[test]
+    fn test_wrong_sender_is_rejected() {
+        let (_, alice_priv) = generate_rsa_keypair(1024);
+        let (mallory_pub, _) = generate_rsa_keypair(1024);
+        let (bob_pub, bob_priv) = generate_x25519_keypair();
+
+        let sealed = seal(b"Hallo", &alice_priv, &[Recipient::X25519(bob_pub)]).unwrap();
+        assert_eq!(
+            open(&sealed, &Identity::X25519(bob_priv), &mallory_pub),
+            Err(CryptoError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_surreptitious_forwarding_is_rejected() {
+        let (alice_pub, alice_priv) = generate_rsa_keypair(1024);
+        let (bob_pub, bob_priv) = generate_x25519_keypair();
+        let (carol_pub, carol_priv) = generate_x25519_keypair();
+
+        let sealed = seal(b"Nur fuer Bob", &alice_priv, &[Recipient::X25519(bob_pub)]).unwrap();
+
+        // Bob entschlüsselt den inneren Teil und verschlüsselt ihn unverändert an Carol
+        let inner = envelope::decrypt(&sealed, &Identity::X25519(bob_priv)).unwrap();
+        let forwarded = envelope::encrypt(&inner, &[Recipient::X25519(carol_pub)]).unwrap();
+
+        assert_eq!(
+            open(&forwarded, &Identity::X25519(carol_priv), &alice_pub),
+            Err(CryptoError::NotSignedForRecipient)
+        );
+    }
+}
@@ -0,0 +1,434 @@
+//! JSON Web Encryption (RFC 7516) mit RSA-OAEP-256 bzw. RSA-OAEP und A256GCM,
+//! in kompakter sowie allgemeiner und flacher JSON-Serialisierung.
+
+use crate::asymmetric::rsa;
+use crate::jose::jwk::{Jwk, KeyMaterial};
+use crate::jose::jws::MIN_RSA_BITS;
+use crate::jose::{b64_decode, b64_encode, decode_header, header_str, reject_crit};
+use crate::utils::error::CryptoError;
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde_json::{json, Map, Value};
+
+/// Einziges unterstütztes Inhaltsverfahren.
+pub const CONTENT_ENCRYPTION: &str = "A256GCM";
+
+const CEK_LEN: usize = 32;
+const IV_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// Verfahren zur Verschlüsselung des Inhaltsschlüssels (RFC 7518, 4.3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAlgorithm {
+    /// RSAES-OAEP mit SHA-256 und MGF1-SHA-256.
+    RsaOaep256,
+    /// RSAES-OAEP mit SHA-1 (Standardparameter); für ältere Gegenstellen.
+    RsaOaep,
+}
+
+impl KeyAlgorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyAlgorithm::RsaOaep256 => "RSA-OAEP-256",
+            KeyAlgorithm::RsaOaep => "RSA-OAEP",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, CryptoError> {
+        match name {
+            "RSA-OAEP-256" => Ok(KeyAlgorithm::RsaOaep256),
+            "RSA-OAEP" => Ok(KeyAlgorithm::RsaOaep),
+            _ => Err(CryptoError::Unsupported(format!(
+                "JWE-Algorithmus {}",
+                name
+            ))),
+        }
+    }
+}
+
+/// Ergebnis einer erfolgreichen Entschlüsselung mit den zusammengeführten Header-Parametern.
+#[derive(Debug, Clone)]
+pub struct DecryptedJwe {
+    pub header: Map<String, Value>,
+    pub plaintext: Vec<u8>,
+    /// Zusätzliche authentisierte Daten (nur JSON-Serialisierung).
+    pub aad: Option<Vec<u8>>,
+}
+
+fn rsa_public(alg: KeyAlgorithm, key: &Jwk) -> Result<&rsa::PublicKey, CryptoError> {
+    let KeyMaterial::Rsa { public, .. } = &key.key else {
+        return Err(CryptoError::InvalidFormat(
+            "JWE-Empfänger benötigt einen RSA-Schlüssel".to_string(),
+        ));
+    };
+    if public.n.bits() < MIN_RSA_BITS {
+        return Err(CryptoError::Unsupported(format!(
+            "RSA-Schlüssel mit weniger als {} Bit",
+            MIN_RSA_BITS
+        )));
+    }
+    let alg_ok = key.alg.as_deref().is_none_or(|a| a == alg.name());
+    let use_ok = key.key_use.as_deref().is_none_or(|u| u == "enc");
+    if !alg_ok || !use_ok {
+        return Err(CryptoError::InvalidFormat(format!(
+            "Schlüssel ist nicht für {} vorgesehen",
+            alg.name()
+        )));
+    }
+    Ok(public)
+}
+
+fn wrap_cek(alg: KeyAlgorithm, cek: &[u8], key: &Jwk) -> Result<Vec<u8>, CryptoError> {
+    let public = rsa_public(alg, key)?;
+    Ok(match alg {
+        KeyAlgorithm::RsaOaep256 => rsa::encrypt_rsa_oaep(cek, b"", public),
+        KeyAlgorithm::RsaOaep => rsa::encrypt_rsa_oaep_sha1(cek, b"", public),
+    })
+}
+
+fn unwrap_cek(alg: KeyAlgorithm, encrypted_key: &[u8], key: &Jwk) -> Option<Vec<u8>> {
+    rsa_public(alg, key).ok()?;
+    let KeyMaterial::Rsa {
+        private: Some(private),
+        ..
+    } = &key.key
+    else {
+        return None;
+    };
+    let cek = match alg {
+        KeyAlgorithm::RsaOaep256 => rsa::decrypt_rsa_oaep(encrypted_key, b"", private),
+        KeyAlgorithm::RsaOaep => rsa::decrypt_rsa_oaep_sha1(encrypted_key, b"", private),
+    }?;
+    (cek.len() == CEK_LEN).then_some(cek)
+}
+
+/// Authentisierte Daten: ASCII(BASE64URL(geschützter Header)) [|| '.' || BASE64URL(aad)].
+fn content_aad(protected_b64: &str, aad_b64: Option<&str>) -> Vec<u8> {
+    match aad_b64 {
+        Some(aad) => format!("{}.{}", protected_b64, aad).into_bytes(),
+        None => protected_b64.as_bytes().to_vec(),
+    }
+}
+
+/// Ergebnis der Inhaltsverschlüsselung: (IV, Ciphertext, Tag).
+fn seal_content(cek: &[u8], aad: &[u8], plaintext: &[u8]) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    let mut iv = [0u8; IV_LEN];
+    OsRng.fill_bytes(&mut iv);
+    let cipher = Aes256Gcm::new_from_slice(cek).expect("CEK hat 32 Byte");
+    let mut ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&iv),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .expect("AES-GCM-Verschlüsselung");
+    let tag = ciphertext.split_off(ciphertext.len() - TAG_LEN);
+    (iv.to_vec(), ciphertext, tag)
+}
+
+/// Verschlüsselt `plaintext` für einen RSA-Empfänger (kompakte Serialisierung).
+pub fn encrypt_compact(
+    plaintext: &[u8],
+    alg: KeyAlgorithm,
+    recipient: &Jwk,
+) -> Result<String, CryptoError> {
+    let mut header = Map::new();
+    header.insert("alg".to_string(), json!(alg.name()));
+    header.insert("enc".to_string(), json!(CONTENT_ENCRYPTION));
+    if let Some(kid) = &recipient.kid {
+        header.insert("kid".to_string(), json!(kid));
+    }
+    let protected_b64 =
+        b64_encode(&serde_json::to_vec(&header).expect("Header ist serialisierbar"));
+
+    let mut cek = [0u8; CEK_LEN];
+    OsRng.fill_bytes(&mut cek);
+    let encrypted_key = wrap_cek(alg, &cek, recipient)?;
+    let (iv, ciphertext, tag) = seal_content(&cek, &content_aad(&protected_b64, None), plaintext);
+
+    Ok([
+        protected_b64,
+        b64_encode(&encrypted_key),
+        b64_encode(&iv),
+        b64_encode(&ciphertext),
+        b64_encode(&tag),
+    ]
+    .join("."))
+}
+
+/// Verschlüsselt `plaintext` für mehrere RSA-Empfänger (allgemeine JSON-Serialisierung).
+///
+/// `alg` und `kid` stehen im Header des jeweiligen Empfängers, `enc` im geschützten Header.
+pub fn encrypt_json(
+    plaintext: &[u8],
+    alg: KeyAlgorithm,
+    recipients: &[&Jwk],
+    aad: Option<&[u8]>,
+) -> Result<String, CryptoError> {
+    if recipients.is_empty() {
+        return Err(CryptoError::InvalidFormat(
+            "mindestens ein Empfänger erforderlich".to_string(),
+        ));
+    }
+    let protected_b64 = b64_encode(
+        &serde_json::to_vec(&json!({ "enc": CONTENT_ENCRYPTION })).expect("serialisierbar"),
+    );
+    let mut cek = [0u8; CEK_LEN];
+    OsRng.fill_bytes(&mut cek);
+
+    let mut entries = Vec::new();
+    for recipient in recipients {
+        let mut header = Map::new();
+        header.insert("alg".to_string(), json!(alg.name()));
+        if let Some(kid) = &recipient.kid {
+            header.insert("kid".to_string(), json!(kid));
+        }
+        entries.push(json!({
+            "header": header,
+            "encrypted_key": b64_encode(&wrap_cek(alg, &cek, recipient)?),
+        }));
+    }
+
+    let aad_b64 = aad.map(b64_encode);
+    let (iv, ciphertext, tag) = seal_content(
+        &cek,
+        &content_aad(&protected_b64, aad_b64.as_deref()),
+        plaintext,
+    );
+    let mut out = Map::new();
+    out.insert("protected".to_string(), json!(protected_b64));
+    out.insert("recipients".to_string(), Value::Array(entries));
+    if let Some(aad_b64) = aad_b64 {
+        out.insert("aad".to_string(), json!(aad_b64));
+    }
+    out.insert("iv".to_string(), json!(b64_encode(&iv)));
+    out.insert("ciphertext".to_string(), json!(b64_encode(&ciphertext)));
+    out.insert("tag".to_string(), json!(b64_encode(&tag)));
+    Ok(serde_json::to_string_pretty(&Value::Object(out)).expect("serialisierbar"))
+}
+
+/// Gemeinsame Darstellung beider Serialisierungen.
+struct ParsedJwe {
+    protected_b64: String,
+    shared_header: Map<String, Value>,
+    recipients: Vec<(Map<String, Value>, Vec<u8>)>,
+    aad_b64: Option<String>,
+    iv: Vec<u8>,
+    ciphertext: Vec<u8>,
+    tag: Vec<u8>,
+}
+
+fn parse_compact(token: &str) -> Result<ParsedJwe, CryptoError> {
+    let parts: Vec<&str> = token.trim().split('.').collect();
+    let [protected_b64, encrypted_key, iv, ciphertext, tag] = parts[..] else {
+        return Err(CryptoError::InvalidFormat(
+            "kompaktes JWE muss aus fünf Teilen bestehen".to_string(),
+        ));
+    };
+    Ok(ParsedJwe {
+        protected_b64: protected_b64.to_string(),
+        shared_header: decode_header(protected_b64)?,
+        recipients: vec![(Map::new(), b64_decode(encrypted_key)?)],
+        aad_b64: None,
+        iv: b64_decode(iv)?,
+        ciphertext: b64_decode(ciphertext)?,
+        tag: b64_decode(tag)?,
+    })
+}
+
+/// Führt Header zusammen; doppelte Parameter sind unzulässig (RFC 7516, 7.2.1).
+fn merge_into(
+    target: &mut Map<String, Value>,
+    other: &Map<String, Value>,
+) -> Result<(), CryptoError> {
+    for (name, value) in other {
+        if target.insert(name.clone(), value.clone()).is_some() {
+            return Err(CryptoError::InvalidFormat(format!(
+                "Header-Parameter \"{}\" mehrfach angegeben",
+                name
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn parse_json(json: &str) -> Result<ParsedJwe, CryptoError> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| CryptoError::InvalidFormat(e.to_string()))?;
+    let obj = value
+        .as_object()
+        .ok_or_else(|| CryptoError::InvalidFormat("JWE ist kein JSON-Objekt".to_string()))?;
+    let field = |name: &str| -> Result<Option<&str>, CryptoError> {
+        match obj.get(name) {
+            None => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s)),
+            Some(_) => Err(CryptoError::InvalidFormat(format!(
+                "JWE-Feld \"{}\" ist keine Zeichenkette",
+                name
+            ))),
+        }
+    };
+    let required =
+        |name: &str| -> Result<Vec<u8>, CryptoError> {
+            b64_decode(field(name)?.ok_or_else(|| {
+                CryptoError::InvalidFormat(format!("JWE-Feld \"{}\" fehlt", name))
+            })?)
+        };
+    let object = |value: Option<&Value>| -> Result<Map<String, Value>, CryptoError> {
+        match value {
+            None => Ok(Map::new()),
+            Some(Value::Object(map)) => Ok(map.clone()),
+            Some(_) => Err(CryptoError::InvalidFormat(
+                "JWE-Header ist kein JSON-Objekt".to_string(),
+            )),
+        }
+    };
+
+    let protected_b64 = field("protected")?.unwrap_or_default().to_string();
+    let mut shared_header = if protected_b64.is_empty() {
+        Map::new()
+    } else {
+        decode_header(&protected_b64)?
+    };
+    merge_into(&mut shared_header, &object(obj.get("unprotected"))?)?;
+
+    let recipient = |entry: &Value| -> Result<(Map<String, Value>, Vec<u8>), CryptoError> {
+        let encrypted_key = entry
+            .get("encrypted_key")
+            .and_then(Value::as_str)
+            .ok_or_else(|| CryptoError::InvalidFormat("\"encrypted_key\" fehlt".to_string()))?;
+        Ok((object(entry.get("header"))?, b64_decode(encrypted_key)?))
+    };
+    let recipients = match obj.get("recipients") {
+        Some(Value::Array(entries)) => entries.iter().map(recipient).collect::<Result<_, _>>()?,
+        Some(_) => {
+            return Err(CryptoError::InvalidFormat(
+                "\"recipients\" ist kein Array".to_string(),
+            ))
+        }
+        // flache Serialisierung: Empfängerfelder auf oberster Ebene
+        None => vec![recipient(&value)?],
+    };
+
+    Ok(ParsedJwe {
+        protected_b64,
+        shared_header,
+        recipients,
+        aad_b64: field("aad")?.map(str::to_string),
+        iv: required("iv")?,
+        ciphertext: required("ciphertext")?,
+        tag: required("tag")?,
+    })
+}
+
+fn decrypt_parsed(jwe: ParsedJwe, key: &Jwk) -> Result<DecryptedJwe, CryptoError> {
+    if let Some(aad_b64) = &jwe.aad_b64 {
+        b64_decode(aad_b64)?;
+    }
+    for (recipient_header, encrypted_key) in &jwe.recipients {
+        let mut header = jwe.shared_header.clone();
+        merge_into(&mut header, recipient_header)?;
+        reject_crit(&header)?;
+        if header.contains_key("zip") {
+            return Err(CryptoError::Unsupported(
+                "komprimierte JWE (zip)".to_string(),
+            ));
+        }
+        let enc = header_str(&header, "enc")?;
+        if enc != CONTENT_ENCRYPTION {
+            return Err(CryptoError::Unsupported(format!(
+                "JWE-Inhaltsverfahren {}",
+                enc
+            )));
+        }
+        let alg = KeyAlgorithm::from_name(header_str(&header, "alg")?)?;
+
+        // Empfänger mit abweichender Schlüsselkennung überspringen
+        let kid = header.get("kid").and_then(Value::as_str);
+        if matches!((kid, key.kid.as_deref()), (Some(a), Some(b)) if a != b) {
+            continue;
+        }
+        let Some(cek) = unwrap_cek(alg, encrypted_key, key) else {
+            continue;
+        };
+
+        if jwe.iv.len() != IV_LEN || jwe.tag.len() != TAG_LEN {
+            return Err(CryptoError::DecryptionFailed);
+        }
+        let mut sealed = jwe.ciphertext.clone();
+        sealed.extend_from_slice(&jwe.tag);
+        let aad = content_aad(&jwe.protected_b64, jwe.aad_b64.as_deref());
+        let cipher = Aes256Gcm::new_from_slice(&cek).expect("CEK hat 32 Byte");
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(&jwe.iv),
+                Payload {
+                    msg: &sealed,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| CryptoError::DecryptionFailed)?;
+        return Ok(DecryptedJwe {
+            header,
+            plaintext,
+            aad: jwe.aad_b64.as_deref().map(b64_decode).transpose()?,
+        });
+    }
+    Err(CryptoError::NoMatchingRecipient)
+}
+
+/// Entschlüsselt ein kompaktes JWE.
+pub fn decrypt_compact(token: &str, key: &Jwk) -> Result<DecryptedJwe, CryptoError> {
+    decrypt_parsed(parse_compact(token)?, key)
+}
+
+/// Entschlüsselt ein JWE in allgemeiner oder flacher JSON-Serialisierung.
+pub fn decrypt_json(json: &str, key: &Jwk) -> Result<DecryptedJwe, CryptoError> {
+    decrypt_parsed(parse_json(json)?, key)
+}
+
+/// Entschlüsselt ein JWE in beliebiger Serialisierung (JSON beginnt mit `{`).
+pub fn decrypt(data: &str, key: &Jwk) -> Result<DecryptedJwe, CryptoError> {
+    if data.trim_start().starts_with('{') {
+        decrypt_json(data, key)
+    } else {
+        decrypt_compact(data, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_short_rsa_keys_and_malformed_input() {
+        let (public, private) = rsa::generate_rsa_keypair(1024);
+        let key = Jwk::from_rsa(&public, Some(&private));
+        assert!(matches!(
+            encrypt_compact(b"x", KeyAlgorithm::RsaOaep256, &key),
+            Err(CryptoError::Unsupported(_))
+        ));
+
+        assert!(decrypt_compact("a.b.c", &key).is_err());
+        assert!(decrypt_json("{\"ciphertext\": 1}", &key).is_err());
+
+        // doppelter Parameter in geschütztem und ungeschütztem Header
+        let protected = b64_encode(br#"{"enc":"A256GCM","alg":"RSA-OAEP-256"}"#);
+        let json = json!({
+            "protected": protected,
+            "unprotected": {"alg": "RSA-OAEP"},
+            "encrypted_key": "AA",
+            "iv": "AA",
+            "ciphertext": "AA",
+            "tag": "AA",
+        });
+        assert!(matches!(
+            decrypt_json(&json.to_string(), &key),
+            Err(CryptoError::InvalidFormat(_))
+        ));
+    }
+}
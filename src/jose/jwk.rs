@@ -0,0 +1,373 @@
+//! JSON Web Keys (RFC 7517, RFC 7518 Abschnitt 6, RFC 8037) und Thumbprints (RFC 7638).
+
+use crate::asymmetric::{ecdsa, ed25519, rsa};
+use crate::jose::{b64_decode, b64_encode};
+use crate::utils::error::CryptoError;
+use num_bigint::BigUint;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+/// Schlüsselmaterial eines JWK; der private Teil ist optional.
+#[derive(Clone)]
+pub enum KeyMaterial {
+    Rsa {
+        public: rsa::PublicKey,
+        private: Option<rsa::PrivateKey>,
+    },
+    P256 {
+        public: ecdsa::PublicKey,
+        private: Option<ecdsa::PrivateKey>,
+    },
+    Ed25519 {
+        public: ed25519::PublicKey,
+        private: Option<ed25519::PrivateKey>,
+    },
+}
+
+/// Ein JSON Web Key mit den optionalen Parametern `kid`, `alg` und `use`.
+#[derive(Clone)]
+pub struct Jwk {
+    pub key: KeyMaterial,
+    pub kid: Option<String>,
+    pub alg: Option<String>,
+    pub key_use: Option<String>,
+}
+
+fn invalid(msg: &str) -> CryptoError {
+    CryptoError::InvalidFormat(msg.to_string())
+}
+
+fn get_str<'a>(obj: &'a Map<String, Value>, name: &str) -> Result<Option<&'a str>, CryptoError> {
+    match obj.get(name) {
+        None => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(_) => Err(CryptoError::InvalidFormat(format!(
+            "JWK-Parameter \"{}\" ist keine Zeichenkette",
+            name
+        ))),
+    }
+}
+
+fn required_bytes(obj: &Map<String, Value>, name: &str) -> Result<Vec<u8>, CryptoError> {
+    let s = get_str(obj, name)?
+        .ok_or_else(|| CryptoError::InvalidFormat(format!("JWK-Parameter \"{}\" fehlt", name)))?;
+    b64_decode(s)
+}
+
+fn optional_bytes(obj: &Map<String, Value>, name: &str) -> Result<Option<Vec<u8>>, CryptoError> {
+    get_str(obj, name)?.map(b64_decode).transpose()
+}
+
+fn fixed<const N: usize>(bytes: Vec<u8>, what: &str) -> Result<[u8; N], CryptoError> {
+    bytes
+        .try_into()
+        .map_err(|_| CryptoError::InvalidFormat(format!("{} muss {} Byte lang sein", what, N)))
+}
+
+fn uint(value: &BigUint) -> String {
+    b64_encode(&value.to_bytes_be())
+}
+
+impl Jwk {
+    fn new(key: KeyMaterial) -> Self {
+        Jwk {
+            key,
+            kid: None,
+            alg: None,
+            key_use: None,
+        }
+    }
+
+    pub fn from_rsa(public: &rsa::PublicKey, private: Option<&rsa::PrivateKey>) -> Self {
+        Jwk::new(KeyMaterial::Rsa {
+            public: public.clone(),
+            private: private.cloned(),
+        })
+    }
+
+    pub fn from_p256(public: &ecdsa::PublicKey, private: Option<&ecdsa::PrivateKey>) -> Self {
+        Jwk::new(KeyMaterial::P256 {
+            public: public.clone(),
+            private: private.cloned(),
+        })
+    }
+
+    pub fn from_ed25519(
+        public: &ed25519::PublicKey,
+        private: Option<&ed25519::PrivateKey>,
+    ) -> Self {
+        Jwk::new(KeyMaterial::Ed25519 {
+            public: public.clone(),
+            private: private.cloned(),
+        })
+    }
+
+    pub fn with_kid(mut self, kid: &str) -> Self {
+        self.kid = Some(kid.to_string());
+        self
+    }
+
+    /// Liest einen JWK aus JSON.
+    pub fn parse(json: &str) -> Result<Self, CryptoError> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| CryptoError::InvalidFormat(e.to_string()))?;
+        Jwk::from_value(&value)
+    }
+
+    /// Liest einen JWK aus einem bereits geparsten JSON-Wert.
+    ///
+    /// Private Schlüssel werden gegen den öffentlichen Teil geprüft.
+    pub fn from_value(value: &Value) -> Result<Self, CryptoError> {
+        let obj = value
+            .as_object()
+            .ok_or_else(|| invalid("JWK ist kein JSON-Objekt"))?;
+        let kty = get_str(obj, "kty")?.ok_or_else(|| invalid("JWK ohne \"kty\""))?;
+        let crv = get_str(obj, "crv")?;
+
+        let key = match (kty, crv) {
+            ("RSA", _) => {
+                let public = rsa::PublicKey {
+                    n: BigUint::from_bytes_be(&required_bytes(obj, "n")?),
+                    e: BigUint::from_bytes_be(&required_bytes(obj, "e")?),
+                };
+                if public.n.bits() < 512 || public.e < BigUint::from(3u32) {
+                    return Err(invalid("ungültiger RSA-Schlüssel"));
+                }
+                let private = optional_bytes(obj, "d")?.map(|d| rsa::PrivateKey {
+                    d: BigUint::from_bytes_be(&d),
+                    n: public.n.clone(),
+                    e: public.e.clone(),
+                });
+                if let Some(private) = &private {
+                    // (2^e)^d ≡ 2 (mod n) muss für ein zusammengehöriges Paar gelten
+                    let two = BigUint::from(2u32);
+                    let m = rsa::encrypt_rsa(&two, &public);
+                    if rsa::decrypt_rsa(&m, private) != two {
+                        return Err(invalid("privater RSA-Schlüssel passt nicht zu n und e"));
+                    }
+                }
+                KeyMaterial::Rsa { public, private }
+            }
+            ("EC", Some("P-256")) => {
+                let public = ecdsa::PublicKey {
+                    x: fixed(required_bytes(obj, "x")?, "x")?,
+                    y: fixed(required_bytes(obj, "y")?, "y")?,
+                };
+                if !ecdsa::is_valid_public_key(&public) {
+                    return Err(invalid("Punkt liegt nicht auf P-256"));
+                }
+                let private = optional_bytes(obj, "d")?
+                    .map(|d| fixed(d, "d").map(|bytes| ecdsa::PrivateKey { bytes }))
+                    .transpose()?;
+                if let Some(private) = &private {
+                    if ecdsa::public_key_from_private(private).as_ref() != Some(&public) {
+                        return Err(invalid("privater P-256-Schlüssel passt nicht zu x und y"));
+                    }
+                }
+                KeyMaterial::P256 { public, private }
+            }
+            ("OKP", Some("Ed25519")) => {
+                let public = ed25519::PublicKey {
+                    bytes: fixed(required_bytes(obj, "x")?, "x")?,
+                };
+                let private = optional_bytes(obj, "d")?
+                    .map(|d| fixed(d, "d").map(|bytes| ed25519::PrivateKey { bytes }))
+                    .transpose()?;
+                if let Some(private) = &private {
+                    if ed25519::public_key_from_private(private) != public {
+                        return Err(invalid("privater Ed25519-Schlüssel passt nicht zu x"));
+                    }
+                }
+                KeyMaterial::Ed25519 { public, private }
+            }
+            (kty, crv) => {
+                return Err(CryptoError::Unsupported(format!(
+                    "JWK-Typ {}{}",
+                    kty,
+                    crv.map(|c| format!("/{}", c)).unwrap_or_default()
+                )))
+            }
+        };
+
+        Ok(Jwk {
+            key,
+            kid: get_str(obj, "kid")?.map(str::to_string),
+            alg: get_str(obj, "alg")?.map(str::to_string),
+            key_use: get_str(obj, "use")?.map(str::to_string),
+        })
+    }
+
+    pub fn is_private(&self) -> bool {
+        match &self.key {
+            KeyMaterial::Rsa { private, .. } => private.is_some(),
+            KeyMaterial::P256 { private, .. } => private.is_some(),
+            KeyMaterial::Ed25519 { private, .. } => private.is_some(),
+        }
+    }
+
+    /// Derselbe Schlüssel ohne privaten Teil.
+    pub fn to_public(&self) -> Jwk {
+        let key = match &self.key {
+            KeyMaterial::Rsa { public, .. } => KeyMaterial::Rsa {
+                public: public.clone(),
+                private: None,
+            },
+            KeyMaterial::P256 { public, .. } => KeyMaterial::P256 {
+                public: public.clone(),
+                private: None,
+            },
+            KeyMaterial::Ed25519 { public, .. } => KeyMaterial::Ed25519 {
+                public: public.clone(),
+                private: None,
+            },
+        };
+        Jwk {
+            key,
+            ..self.clone()
+        }
+    }
+
+    /// JSON-Darstellung; enthält den privaten Teil, falls vorhanden.
+    ///
+    /// Bei RSA werden p, q und die CRT-Parameter aus d rekonstruiert.
+    pub fn to_value(&self) -> Value {
+        let mut obj = Map::new();
+        let mut put = |name: &str, value: String| {
+            obj.insert(name.to_string(), Value::String(value));
+        };
+        match &self.key {
+            KeyMaterial::Rsa { public, private } => {
+                put("kty", "RSA".to_string());
+                put("n", uint(&public.n));
+                put("e", uint(&public.e));
+                if let Some(private) = private {
+                    put("d", uint(&private.d));
+                    if let Some(crt) = rsa::crt_params(public, private) {
+                        put("dp", uint(&crt.dp));
+                        put("dq", uint(&crt.dq));
+                        put("qi", uint(&crt.qinv));
+                        put("p", uint(&crt.p));
+                        put("q", uint(&crt.q));
+                    }
+                }
+            }
+            KeyMaterial::P256 { public, private } => {
+                put("kty", "EC".to_string());
+                put("crv", "P-256".to_string());
+                put("x", b64_encode(&public.x));
+                put("y", b64_encode(&public.y));
+                if let Some(private) = private {
+                    put("d", b64_encode(&private.bytes));
+                }
+            }
+            KeyMaterial::Ed25519 { public, private } => {
+                put("kty", "OKP".to_string());
+                put("crv", "Ed25519".to_string());
+                put("x", b64_encode(&public.bytes));
+                if let Some(private) = private {
+                    put("d", b64_encode(&private.bytes));
+                }
+            }
+        }
+        for (name, value) in [
+            ("kid", &self.kid),
+            ("alg", &self.alg),
+            ("use", &self.key_use),
+        ] {
+            if let Some(value) = value {
+                put(name, value.clone());
+            }
+        }
+        Value::Object(obj)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.to_value()).expect("JWK ist serialisierbar")
+    }
+
+    /// JWK-Thumbprint (RFC 7638): SHA-256 über die Pflichtparameter in lexikographischer
+    /// Reihenfolge, base64url-kodiert.
+    pub fn thumbprint(&self) -> String {
+        let canonical = match &self.key {
+            KeyMaterial::Rsa { public, .. } => format!(
+                r#"{{"e":"{}","kty":"RSA","n":"{}"}}"#,
+                uint(&public.e),
+                uint(&public.n)
+            ),
+            KeyMaterial::P256 { public, .. } => format!(
+                r#"{{"crv":"P-256","kty":"EC","x":"{}","y":"{}"}}"#,
+                b64_encode(&public.x),
+                b64_encode(&public.y)
+            ),
+            KeyMaterial::Ed25519 { public, .. } => format!(
+                r#"{{"crv":"Ed25519","kty":"OKP","x":"{}"}}"#,
+                b64_encode(&public.bytes)
+            ),
+        };
+        b64_encode(&Sha256::digest(canonical.as_bytes()))
+    }
+}
+
+/// Liest ein JWK Set (`{"keys": [...]}`). Schlüssel nicht unterstützter Typen werden
+/// übersprungen (RFC 7517, 5).
+pub fn parse_jwk_set(json: &str) -> Result<Vec<Jwk>, CryptoError> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| CryptoError::InvalidFormat(e.to_string()))?;
+    let keys = value
+        .get("keys")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("JWK Set ohne \"keys\""))?;
+    let mut out = Vec::new();
+    for key in keys {
+        match Jwk::from_value(key) {
+            Ok(jwk) => out.push(jwk),
+            Err(CryptoError::Unsupported(_)) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(out)
+}
+
+/// Serialisiert Schlüssel als JWK Set.
+pub fn jwk_set_to_json(keys: &[Jwk]) -> String {
+    let keys: Vec<Value> = keys.iter().map(Jwk::to_value).collect();
+    serde_json::to_string_pretty(&serde_json::json!({ "keys": keys }))
+        .expect("JWK Set ist serialisierbar")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ed25519_roundtrip_and_public_only() {
+        let (public, private) = ed25519::generate_ed25519_keypair();
+        let jwk = Jwk::from_ed25519(&public, Some(&private)).with_kid("k1");
+        let parsed = Jwk::parse(&jwk.to_json()).unwrap();
+        assert!(parsed.is_private());
+        assert_eq!(parsed.kid.as_deref(), Some("k1"));
+        assert_eq!(parsed.thumbprint(), jwk.thumbprint());
+
+        let public_json = jwk.to_public().to_json();
+        assert!(!public_json.contains("\"d\""));
+        assert!(!Jwk::parse(&public_json).unwrap().is_private());
+    }
+
+    #[test]
+    fn test_rejects_mismatched_or_malformed_keys() {
+        let (public, _) = ecdsa::generate_p256_keypair();
+        let (_, other) = ecdsa::generate_p256_keypair();
+        let json = Jwk::from_p256(&public, Some(&other)).to_json();
+        assert!(matches!(
+            Jwk::parse(&json),
+            Err(CryptoError::InvalidFormat(_))
+        ));
+
+        assert!(Jwk::parse(r#"{"kty":"RSA","e":"AQAB"}"#).is_err());
+        assert!(Jwk::parse(r#"{"kty":"OKP","crv":"Ed25519","x":"AAAA"}"#).is_err());
+        assert!(matches!(
+            Jwk::parse(r#"{"kty":"oct","k":"AAAA"}"#),
+            Err(CryptoError::Unsupported(_))
+        ));
+    }
+}
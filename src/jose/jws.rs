@@ -0,0 +1,233 @@
+//! JSON Web Signature in kompakter Serialisierung (RFC 7515) mit RS256, PS256, EdDSA und ES256.
+
+use crate::asymmetric::rsa::{self, Pkcs1Hash};
+use crate::asymmetric::{ecdsa, ed25519};
+use crate::jose::jwk::{Jwk, KeyMaterial};
+use crate::jose::{b64_decode, b64_encode, decode_header, header_str, reject_crit};
+use crate::utils::error::CryptoError;
+use serde_json::{Map, Value};
+
+/// Mindestlänge für RSA-Schlüssel in JOSE (RFC 7518, 3.3 und 4.3).
+pub const MIN_RSA_BITS: u64 = 2048;
+
+/// Unterstützte Signaturverfahren (RFC 7518, 3.1; RFC 8037, 3.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JwsAlgorithm {
+    Rs256,
+    Ps256,
+    EdDsa,
+    Es256,
+}
+
+impl JwsAlgorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            JwsAlgorithm::Rs256 => "RS256",
+            JwsAlgorithm::Ps256 => "PS256",
+            JwsAlgorithm::EdDsa => "EdDSA",
+            JwsAlgorithm::Es256 => "ES256",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, CryptoError> {
+        match name {
+            "RS256" => Ok(JwsAlgorithm::Rs256),
+            "PS256" => Ok(JwsAlgorithm::Ps256),
+            "EdDSA" => Ok(JwsAlgorithm::EdDsa),
+            "ES256" => Ok(JwsAlgorithm::Es256),
+            _ => Err(CryptoError::Unsupported(format!(
+                "JWS-Algorithmus {}",
+                name
+            ))),
+        }
+    }
+
+    /// Standardverfahren für einen Schlüssel (RSA → RS256).
+    pub fn for_key(key: &Jwk) -> Self {
+        match &key.key {
+            KeyMaterial::Rsa { .. } => JwsAlgorithm::Rs256,
+            KeyMaterial::P256 { .. } => JwsAlgorithm::Es256,
+            KeyMaterial::Ed25519 { .. } => JwsAlgorithm::EdDsa,
+        }
+    }
+}
+
+/// Ergebnis einer erfolgreichen Prüfung.
+#[derive(Debug, Clone)]
+pub struct VerifiedJws {
+    pub header: Map<String, Value>,
+    pub payload: Vec<u8>,
+}
+
+/// Prüft, ob Schlüssel und Verfahren zusammenpassen, auch gegenüber `alg` und `use` im JWK.
+fn check_key(alg: JwsAlgorithm, key: &Jwk) -> Result<(), CryptoError> {
+    let matches = match (&key.key, alg) {
+        (KeyMaterial::Rsa { public, .. }, JwsAlgorithm::Rs256 | JwsAlgorithm::Ps256) => {
+            if public.n.bits() < MIN_RSA_BITS {
+                return Err(CryptoError::Unsupported(format!(
+                    "RSA-Schlüssel mit weniger als {} Bit",
+                    MIN_RSA_BITS
+                )));
+            }
+            true
+        }
+        (KeyMaterial::P256 { .. }, JwsAlgorithm::Es256) => true,
+        (KeyMaterial::Ed25519 { .. }, JwsAlgorithm::EdDsa) => true,
+        _ => false,
+    };
+    let alg_ok = key.alg.as_deref().is_none_or(|a| a == alg.name());
+    let use_ok = key.key_use.as_deref().is_none_or(|u| u == "sig");
+    if matches && alg_ok && use_ok {
+        Ok(())
+    } else {
+        Err(CryptoError::InvalidFormat(format!(
+            "Schlüssel ist nicht für {} vorgesehen",
+            alg.name()
+        )))
+    }
+}
+
+fn sign_input(alg: JwsAlgorithm, input: &[u8], key: &Jwk) -> Result<Vec<u8>, CryptoError> {
+    let missing =
+        || CryptoError::InvalidFormat("JWK enthält keinen privaten Schlüssel".to_string());
+    Ok(match (&key.key, alg) {
+        (KeyMaterial::Rsa { private, .. }, JwsAlgorithm::Rs256) => {
+            let private = private.as_ref().ok_or_else(missing)?;
+            rsa::sign_rsa_pkcs1v15(Pkcs1Hash::Sha256, &Pkcs1Hash::Sha256.digest(input), private)?
+        }
+        (KeyMaterial::Rsa { private, .. }, _) => {
+            rsa::sign_rsa_pss(input, private.as_ref().ok_or_else(missing)?)?
+        }
+        (KeyMaterial::P256 { private, .. }, _) => {
+            ecdsa::sign_ecdsa_p256(input, private.as_ref().ok_or_else(missing)?).to_vec()
+        }
+        (KeyMaterial::Ed25519 { private, .. }, _) => {
+            ed25519::sign_ed25519(input, private.as_ref().ok_or_else(missing)?).to_vec()
+        }
+    })
+}
+
+fn verify_input(alg: JwsAlgorithm, input: &[u8], signature: &[u8], key: &Jwk) -> bool {
+    match (&key.key, alg) {
+        (KeyMaterial::Rsa { public, .. }, JwsAlgorithm::Rs256) => rsa::verify_rsa_pkcs1v15(
+            Pkcs1Hash::Sha256,
+            &Pkcs1Hash::Sha256.digest(input),
+            signature,
+            public,
+        ),
+        (KeyMaterial::Rsa { public, .. }, _) => rsa::verify_rsa_pss(input, signature, public),
+        (KeyMaterial::P256 { public, .. }, _) => ecdsa::verify_ecdsa_p256(input, signature, public),
+        (KeyMaterial::Ed25519 { public, .. }, _) => {
+            ed25519::verify_ed25519(input, signature, public)
+        }
+    }
+}
+
+/// Signiert `payload` und gibt das kompakte JWS zurück.
+///
+/// Der geschützte Header enthält `alg` sowie `kid`, falls der Schlüssel eine Kennung hat.
+pub fn sign_compact(payload: &[u8], alg: JwsAlgorithm, key: &Jwk) -> Result<String, CryptoError> {
+    let mut header = Map::new();
+    if let Some(kid) = &key.kid {
+        header.insert("kid".to_string(), Value::String(kid.clone()));
+    }
+    sign_compact_with_header(header, payload, alg, key)
+}
+
+/// Wie [`sign_compact`], mit zusätzlichen Header-Parametern (z. B. `"typ": "JWT"`).
+pub fn sign_compact_with_header(
+    mut header: Map<String, Value>,
+    payload: &[u8],
+    alg: JwsAlgorithm,
+    key: &Jwk,
+) -> Result<String, CryptoError> {
+    check_key(alg, key)?;
+    reject_crit(&header)?;
+    header.insert("alg".to_string(), Value::String(alg.name().to_string()));
+    let header_json = serde_json::to_vec(&header).expect("Header ist serialisierbar");
+
+    let input = format!("{}.{}", b64_encode(&header_json), b64_encode(payload));
+    let signature = sign_input(alg, input.as_bytes(), key)?;
+    Ok(format!("{}.{}", input, b64_encode(&signature)))
+}
+
+/// Prüft ein kompaktes JWS mit dem gegebenen Schlüssel.
+///
+/// Das Verfahren stammt aus dem Header, muss aber zum Schlüsseltyp passen; `none` und
+/// Header mit `crit` werden abgelehnt.
+pub fn verify_compact(token: &str, key: &Jwk) -> Result<VerifiedJws, CryptoError> {
+    let parts: Vec<&str> = token.trim().split('.').collect();
+    let [header_b64, payload_b64, signature_b64] = parts[..] else {
+        return Err(CryptoError::InvalidFormat(
+            "kompaktes JWS muss aus drei Teilen bestehen".to_string(),
+        ));
+    };
+    let header = decode_header(header_b64)?;
+    reject_crit(&header)?;
+    let alg = JwsAlgorithm::from_name(header_str(&header, "alg")?)?;
+    check_key(alg, key)?;
+
+    let payload = b64_decode(payload_b64)?;
+    let signature = b64_decode(signature_b64)?;
+    let input = &token.trim().as_bytes()[..header_b64.len() + 1 + payload_b64.len()];
+    if !verify_input(alg, input, &signature, key) {
+        return Err(CryptoError::InvalidSignature);
+    }
+    Ok(VerifiedJws { header, payload })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_verify_roundtrip_ec_keys() {
+        let (ed_pub, ed_priv) = ed25519::generate_ed25519_keypair();
+        let (p_pub, p_priv) = ecdsa::generate_p256_keypair();
+        for key in [
+            Jwk::from_ed25519(&ed_pub, Some(&ed_priv)).with_kid("ed"),
+            Jwk::from_p256(&p_pub, Some(&p_priv)),
+        ] {
+            let alg = JwsAlgorithm::for_key(&key);
+            let token = sign_compact(b"{\"sub\":\"alice\"}", alg, &key).unwrap();
+            let verified = verify_compact(&token, &key.to_public()).unwrap();
+            assert_eq!(verified.payload, b"{\"sub\":\"alice\"}");
+            assert_eq!(verified.header["alg"], alg.name());
+            assert_eq!(
+                verified.header.get("kid").and_then(Value::as_str),
+                key.kid.as_deref()
+            );
+
+            // Manipulierte Payload
+            let mut parts: Vec<&str> = token.split('.').collect();
+            let other = b64_encode(b"{\"sub\":\"mallory\"}");
+            parts[1] = &other;
+            assert_eq!(
+                verify_compact(&parts.join("."), &key).unwrap_err(),
+                CryptoError::InvalidSignature
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_none_crit_and_wrong_key_type() {
+        let (public, private) = ed25519::generate_ed25519_keypair();
+        let key = Jwk::from_ed25519(&public, Some(&private));
+        let payload = b64_encode(b"x");
+
+        let none = format!("{}.{}.", b64_encode(br#"{"alg":"none"}"#), payload);
+        assert!(matches!(
+            verify_compact(&none, &key),
+            Err(CryptoError::Unsupported(_))
+        ));
+
+        let mut header = Map::new();
+        header.insert("crit".to_string(), serde_json::json!(["exp"]));
+        assert!(sign_compact_with_header(header, b"x", JwsAlgorithm::EdDsa, &key).is_err());
+
+        let token = sign_compact(b"x", JwsAlgorithm::EdDsa, &key).unwrap();
+        let (p_pub, _) = ecdsa::generate_p256_keypair();
+        assert!(verify_compact(&token, &Jwk::from_p256(&p_pub, None)).is_err());
+        assert!(sign_compact(b"x", JwsAlgorithm::Es256, &key).is_err());
+    }
+}
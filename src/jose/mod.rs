@@ -0,0 +1,59 @@
+//! JSON Object Signing and Encryption (RFC 7515–7518).
+//!
+//! Umfasst JWK für RSA-, P-256- und Ed25519-Schlüssel (inkl. Thumbprint nach RFC 7638),
+//! JWS in kompakter Serialisierung (RS256, PS256, EdDSA, ES256) und JWE mit
+//! RSA-OAEP-256 bzw. RSA-OAEP und A256GCM in kompakter und JSON-Serialisierung.
+//! Ein JWT ist damit ein kompaktes JWS mit JSON-Claims als Payload.
+
+pub mod jwe;
+pub mod jwk;
+pub mod jws;
+
+pub use jwk::{Jwk, KeyMaterial};
+
+use crate::utils::error::CryptoError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde_json::{Map, Value};
+
+/// Kodiert Bytes als base64url ohne Padding.
+pub fn b64_encode(data: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(data)
+}
+
+/// Dekodiert base64url ohne Padding; andere Alphabete oder `=` werden abgelehnt.
+pub fn b64_decode(s: &str) -> Result<Vec<u8>, CryptoError> {
+    URL_SAFE_NO_PAD
+        .decode(s)
+        .map_err(|_| CryptoError::InvalidFormat(format!("ungültiges base64url: {:.20}", s)))
+}
+
+/// Dekodiert einen base64url-kodierten JOSE-Header; er muss ein JSON-Objekt sein.
+fn decode_header(b64: &str) -> Result<Map<String, Value>, CryptoError> {
+    match serde_json::from_slice(&b64_decode(b64)?) {
+        Ok(Value::Object(header)) => Ok(header),
+        _ => Err(CryptoError::InvalidFormat(
+            "JOSE-Header ist kein JSON-Objekt".to_string(),
+        )),
+    }
+}
+
+/// Liest einen Zeichenketten-Parameter aus einem Header.
+fn header_str<'a>(header: &'a Map<String, Value>, name: &str) -> Result<&'a str, CryptoError> {
+    header
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| CryptoError::InvalidFormat(format!("Header-Parameter \"{}\" fehlt", name)))
+}
+
+/// Erweiterungen über `crit` werden nicht unterstützt und müssen daher abgelehnt werden
+/// (RFC 7515, 4.1.11).
+fn reject_crit(header: &Map<String, Value>) -> Result<(), CryptoError> {
+    match header.get("crit") {
+        None => Ok(()),
+        Some(crit) => Err(CryptoError::Unsupported(format!(
+            "kritische Header-Parameter {}",
+            crit
+        ))),
+    }
+}
@@ -0,0 +1,241 @@
+//! Schlüssel im Dateiformat der CLI (`rsa_public.key`, `x25519_private.key` usw.) und ihr
+//! SHA-256-Fingerabdruck über die SubjectPublicKeyInfo.
+
+use crate::asymmetric::{ed25519, rsa, x25519};
+use crate::pqcrypto::mlkem;
+use crate::utils::der;
+use crate::utils::error::CryptoError;
+use crate::x509::{self, oid, SubjectPublicKey};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+pub const PEM_RSA_PUBLIC: &str = "RSA PUBLIC KEY";
+pub const PEM_RSA_PRIVATE: &str = "RSA PRIVATE KEY";
+/// Zweiter Block in `rsa_public.key` für einen öffentlichen Exponenten ≠ 65537.
+pub const PEM_RSA_EXPONENT: &str = "RSA PUBLIC EXPONENT";
+pub const PEM_X25519_PUBLIC: &str = "X25519 PUBLIC KEY";
+pub const PEM_X25519_PRIVATE: &str = "X25519 PRIVATE KEY";
+pub const PEM_ED25519_PUBLIC: &str = "ED25519 PUBLIC KEY";
+pub const PEM_ED25519_PRIVATE: &str = "ED25519 PRIVATE KEY";
+pub const PEM_MLKEM_PUBLIC: &str = "ML-KEM-768 PUBLIC KEY";
+pub const PEM_MLKEM_PRIVATE: &str = "ML-KEM-768 PRIVATE KEY";
+
+/// Öffentlicher Exponent von RSA-Schlüsseldateien ohne Block [`PEM_RSA_EXPONENT`].
+const RSA_EXPONENT: u32 = 65537;
+
+/// Öffentliche RSA-Schlüsseldatei: n, bei e ≠ 65537 gefolgt von e in einem eigenen Block.
+/// Dateien mit e = 65537 bleiben so, wie ältere Versionen sie geschrieben haben.
+pub fn rsa_public_to_pem(key: &rsa::PublicKey) -> String {
+    let mut pem = x509::pem_encode(PEM_RSA_PUBLIC, &key.n.to_bytes_be());
+    if key.e != BigUint::from(RSA_EXPONENT) {
+        pem.push_str(&x509::pem_encode(PEM_RSA_EXPONENT, &key.e.to_bytes_be()));
+    }
+    pem
+}
+
+/// Liest eine öffentliche RSA-Schlüsseldatei; ohne Exponenten-Block ist e = 65537.
+pub fn rsa_public_from_pem(text: &str) -> Result<rsa::PublicKey, CryptoError> {
+    let n = BigUint::from_bytes_be(&x509::pem_decode(text, PEM_RSA_PUBLIC)?);
+    let e = match x509::pem_decode_all(text, PEM_RSA_EXPONENT)?.as_slice() {
+        [] => BigUint::from(RSA_EXPONENT),
+        [e] => BigUint::from_bytes_be(e),
+        _ => {
+            return Err(CryptoError::InvalidFormat(format!(
+                "mehrere PEM-Blöcke {} gefunden",
+                PEM_RSA_EXPONENT
+            )))
+        }
+    };
+    Ok(rsa::PublicKey { n, e })
+}
+
+/// Liefert das Label aus der ersten BEGIN-Zeile.
+fn pem_label(text: &str) -> Option<&str> {
+    text.lines()
+        .find_map(|line| line.trim().strip_prefix("-----BEGIN "))
+        .and_then(|rest| rest.strip_suffix("-----"))
+}
+
+fn fixed<const N: usize>(bytes: Vec<u8>, what: &str) -> Result<[u8; N], CryptoError> {
+    bytes
+        .try_into()
+        .map_err(|_| CryptoError::InvalidFormat(format!("{} muss {} Byte lang sein", what, N)))
+}
+
+/// Öffentlicher Schlüssel einer Schlüsseldatei.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublicKey {
+    Rsa(rsa::PublicKey),
+    X25519(x25519::PublicKey),
+    Ed25519(ed25519::PublicKey),
+    MlKem(mlkem::PublicKey),
+}
+
+impl PublicKey {
+    /// Liest eine öffentliche Schlüsseldatei; die Art wird am PEM-Label erkannt.
+    pub fn from_pem(text: &str) -> Result<Self, CryptoError> {
+        let label = pem_label(text)
+            .ok_or_else(|| CryptoError::InvalidFormat("kein PEM-Block gefunden".to_string()))?;
+        let bytes = x509::pem_decode(text, label)?;
+        match label {
+            PEM_RSA_PUBLIC => Ok(PublicKey::Rsa(rsa_public_from_pem(text)?)),
+            PEM_X25519_PUBLIC => Ok(PublicKey::X25519(x25519::PublicKey {
+                bytes: fixed(bytes, "X25519-Schlüssel")?,
+            })),
+            PEM_ED25519_PUBLIC => Ok(PublicKey::Ed25519(ed25519::PublicKey {
+                bytes: fixed(bytes, "Ed25519-Schlüssel")?,
+            })),
+            PEM_MLKEM_PUBLIC => Ok(PublicKey::MlKem(mlkem::PublicKey { bytes })),
+            other => Err(CryptoError::Unsupported(format!(
+                "Schlüsseldatei {}",
+                other
+            ))),
+        }
+    }
+
+    pub fn to_pem(&self) -> String {
+        match self {
+            PublicKey::Rsa(key) => rsa_public_to_pem(key),
+            PublicKey::X25519(key) => x509::pem_encode(PEM_X25519_PUBLIC, &key.bytes),
+            PublicKey::Ed25519(key) => x509::pem_encode(PEM_ED25519_PUBLIC, &key.bytes),
+            PublicKey::MlKem(key) => x509::pem_encode(PEM_MLKEM_PUBLIC, &key.bytes),
+        }
+    }
+
+    /// Präfix der Dateinamen, z. B. `rsa` für `rsa_public.key`.
+    pub fn file_prefix(&self) -> &'static str {
+        match self {
+            PublicKey::Rsa(_) => "rsa",
+            PublicKey::X25519(_) => "x25519",
+            PublicKey::Ed25519(_) => "ed25519",
+            PublicKey::MlKem(_) => "mlkem",
+        }
+    }
+
+    /// Kurzbeschreibung, z. B. "RSA-2048".
+    pub fn description(&self) -> String {
+        match self {
+            PublicKey::Rsa(key) => format!("RSA-{}", key.n.bits()),
+            PublicKey::X25519(_) => "X25519".to_string(),
+            PublicKey::Ed25519(_) => "Ed25519".to_string(),
+            PublicKey::MlKem(_) => "ML-KEM-768".to_string(),
+        }
+    }
+
+    /// DER-kodierte SubjectPublicKeyInfo (RFC 5280, RFC 8410; ML-KEM mit der NIST-Kennung).
+    pub fn to_spki(&self) -> Vec<u8> {
+        let (alg, key) = match self {
+            PublicKey::Rsa(key) => return SubjectPublicKey::Rsa(key.clone()).to_spki(),
+            PublicKey::Ed25519(key) => return SubjectPublicKey::Ed25519(key.clone()).to_spki(),
+            PublicKey::X25519(key) => (oid::X25519, &key.bytes[..]),
+            PublicKey::MlKem(key) => (oid::ML_KEM_768, &key.bytes[..]),
+        };
+        der::sequence(&[der::sequence(&[der::oid(alg)]), der::bit_string(key)])
+    }
+
+    /// SHA-256 über die SubjectPublicKeyInfo, wie `openssl pkey -pubout -outform DER | sha256sum`.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        Sha256::digest(self.to_spki()).into()
+    }
+
+    /// Prüft, ob eine private Schlüsseldatei zu diesem Schlüssel gehört.
+    pub fn matches_private_pem(&self, text: &str) -> Result<bool, CryptoError> {
+        let label = pem_label(text)
+            .ok_or_else(|| CryptoError::InvalidFormat("kein PEM-Block gefunden".to_string()))?;
+        let bytes = x509::pem_decode(text, label)?;
+        Ok(match (self, label) {
+            (PublicKey::Rsa(key), PEM_RSA_PRIVATE) => {
+                // (2^e)^d ≡ 2 mod n genau dann, wenn d zu (n, e) passt (bis auf Zufall)
+                let private = rsa::PrivateKey {
+                    d: BigUint::from_bytes_be(&bytes),
+                    n: key.n.clone(),
+                    e: key.e.clone(),
+                };
+                let two = BigUint::from(2u8);
+                rsa::decrypt_rsa(&rsa::encrypt_rsa(&two, key), &private) == two
+            }
+            (PublicKey::X25519(key), PEM_X25519_PRIVATE) => {
+                let private = x25519::PrivateKey {
+                    bytes: fixed(bytes, "X25519-Schlüssel")?,
+                };
+                x25519::public_key_from_private(&private) == *key
+            }
+            (PublicKey::Ed25519(key), PEM_ED25519_PRIVATE) => {
+                let private = ed25519::PrivateKey {
+                    bytes: fixed(bytes, "Ed25519-Schlüssel")?,
+                };
+                ed25519::public_key_from_private(&private) == *key
+            }
+            (PublicKey::MlKem(key), PEM_MLKEM_PRIVATE) => {
+                mlkem::public_key_from_private(&mlkem::PrivateKey { bytes }) == *key
+            }
+            _ => false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spki_fingerprint_matches_x509() {
+        let (public, _) = ed25519::generate_ed25519_keypair();
+        let key = PublicKey::Ed25519(public.clone());
+        assert_eq!(
+            key.fingerprint(),
+            SubjectPublicKey::Ed25519(public).fingerprint()
+        );
+        // X25519-SPKI aus RFC 8410, Abschnitt 10.1
+        let bytes: [u8; 32] =
+            hex::decode("19bf44096984cdfe8541bac167dc3b96c85086aa30b6b6cb0c5c38ad703166e1")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let spki = PublicKey::X25519(x25519::PublicKey { bytes }).to_spki();
+        assert_eq!(
+            hex::encode(&spki),
+            "302a300506032b656e03210019bf44096984cdfe8541bac167dc3b96c85086aa30b6b6cb0c5c38ad703166e1"
+        );
+    }
+
+    #[test]
+    fn test_pem_roundtrip_and_private_match() {
+        let (public, private) = x25519::generate_x25519_keypair();
+        let key = PublicKey::X25519(public);
+        assert_eq!(PublicKey::from_pem(&key.to_pem()).unwrap(), key);
+        let private_pem = x509::pem_encode(PEM_X25519_PRIVATE, &private.bytes);
+        assert!(key.matches_private_pem(&private_pem).unwrap());
+        let (_, other) = x25519::generate_x25519_keypair();
+        let other_pem = x509::pem_encode(PEM_X25519_PRIVATE, &other.bytes);
+        assert!(!key.matches_private_pem(&other_pem).unwrap());
+        assert!(!key.matches_private_pem(&key.to_pem()).unwrap());
+    }
+
+    #[test]
+    fn test_rsa_public_exponent_block() {
+        let n = BigUint::from(3233u32);
+        // e = 65537 wie bisher nur mit n
+        let key = PublicKey::Rsa(rsa::PublicKey {
+            n: n.clone(),
+            e: BigUint::from(RSA_EXPONENT),
+        });
+        assert!(!key.to_pem().contains(PEM_RSA_EXPONENT));
+        assert_eq!(PublicKey::from_pem(&key.to_pem()).unwrap(), key);
+        // anderer Exponent als zweiter Block, der private Schlüssel passt nur mit ihm
+        let key = PublicKey::Rsa(rsa::PublicKey {
+            n: n.clone(),
+            e: BigUint::from(17u32),
+        });
+        assert!(key.to_pem().contains(PEM_RSA_EXPONENT));
+        assert_eq!(PublicKey::from_pem(&key.to_pem()).unwrap(), key);
+        let private_pem = x509::pem_encode(PEM_RSA_PRIVATE, &[0x0a, 0xc1]);
+        assert!(key.matches_private_pem(&private_pem).unwrap());
+        let twice = format!(
+            "{}{}",
+            key.to_pem(),
+            x509::pem_encode(PEM_RSA_EXPONENT, &[3])
+        );
+        assert!(PublicKey::from_pem(&twice).is_err());
+    }
+}
@@ -0,0 +1,433 @@
+//! Lokaler Schlüsselspeicher.
+//!
+//! Jeder Schlüssel liegt in einem eigenen Verzeichnis, benannt nach dem SHA-256-Fingerabdruck
+//! seiner SubjectPublicKeyInfo (hex). Darin stehen die Schlüsseldateien im gewohnten Format
+//! (`rsa_public.key`, `rsa_private.key`, …), sodass alle Befehle sie unverändert laden können,
+//! und `meta.json` mit Bezeichnungen und Erstellungszeit. Einen separaten Index gibt es
+//! nicht; das Verzeichnis selbst ist der Index.
+//!
+//! Schlüssel werden über eine Bezeichnung (`alice`), den vollen Fingerabdruck oder dessen
+//! Anfang (mindestens 8 Hex-Zeichen; die ersten 16 sind die Schlüssel-ID) gefunden.
+
+pub mod key;
+
+pub use key::PublicKey;
+
+use crate::utils::error::CryptoError;
+use serde_json::{json, Value};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Umgebungsvariable, die den Speicherort überschreibt.
+pub const ENV_KEYSTORE: &str = "CRYPTOMORPH_KEYSTORE";
+const META_FILE: &str = "meta.json";
+/// Mindestlänge eines Fingerabdruck-Präfixes bei der Suche.
+const MIN_PREFIX_LEN: usize = 8;
+const KEY_ID_LEN: usize = 16;
+
+/// Fehlerklassen des Schlüsselspeichers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyStoreError {
+    /// Kein Schlüssel passt zur Angabe.
+    NotFound(String),
+    /// Mehrere Schlüssel passen zum Fingerabdruck-Präfix.
+    Ambiguous(String),
+    /// Die Bezeichnung ist bereits einem anderen Schlüssel zugeordnet.
+    LabelInUse(String),
+    /// Die Bezeichnung enthält unzulässige Zeichen.
+    InvalidLabel(String),
+    /// Schlüsseldatei ungültig oder privater Schlüssel passt nicht.
+    Key(CryptoError),
+    /// Ein- oder Ausgabefehler.
+    Io(String),
+}
+
+impl fmt::Display for KeyStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyStoreError::NotFound(query) => write!(f, "Kein Schlüssel gefunden: {}", query),
+            KeyStoreError::Ambiguous(query) => {
+                write!(
+                    f,
+                    "Mehrere Schlüssel passen zu {}, bitte genauer angeben",
+                    query
+                )
+            }
+            KeyStoreError::LabelInUse(label) => {
+                write!(f, "Bezeichnung {} ist bereits vergeben", label)
+            }
+            KeyStoreError::InvalidLabel(label) => write!(
+                f,
+                "Ungültige Bezeichnung {:?} (erlaubt: Buchstaben, Ziffern, . _ - @)",
+                label
+            ),
+            KeyStoreError::Key(e) => write!(f, "{}", e),
+            KeyStoreError::Io(msg) => write!(f, "E/A-Fehler: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for KeyStoreError {}
+
+impl From<CryptoError> for KeyStoreError {
+    fn from(e: CryptoError) -> Self {
+        KeyStoreError::Key(e)
+    }
+}
+
+fn io_err(e: std::io::Error) -> KeyStoreError {
+    KeyStoreError::Io(e.to_string())
+}
+
+/// Ein Schlüssel im Speicher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyEntry {
+    pub public_key: PublicKey,
+    pub fingerprint: [u8; 32],
+    pub labels: Vec<String>,
+    /// Zeitpunkt des Imports in Sekunden seit 1970.
+    pub created: u64,
+    pub dir: PathBuf,
+}
+
+impl KeyEntry {
+    pub fn fingerprint_hex(&self) -> String {
+        hex::encode(self.fingerprint)
+    }
+
+    /// Die ersten 16 Hex-Zeichen des Fingerabdrucks.
+    pub fn key_id(&self) -> String {
+        self.fingerprint_hex()[..KEY_ID_LEN].to_string()
+    }
+
+    pub fn public_path(&self) -> PathBuf {
+        self.dir
+            .join(format!("{}_public.key", self.public_key.file_prefix()))
+    }
+
+    /// Pfad des privaten Schlüssels, falls er im Speicher liegt.
+    pub fn private_path(&self) -> Option<PathBuf> {
+        let path = self
+            .dir
+            .join(format!("{}_private.key", self.public_key.file_prefix()));
+        path.is_file().then_some(path)
+    }
+
+    fn write_meta(&self) -> Result<(), KeyStoreError> {
+        let meta = json!({ "labels": self.labels, "created": self.created });
+        fs::write(self.dir.join(META_FILE), format!("{:#}\n", meta)).map_err(io_err)
+    }
+}
+
+/// Bezeichnungen: Buchstaben, Ziffern und `. _ - @`, höchstens 64 Zeichen.
+fn validate_label(label: &str) -> Result<(), KeyStoreError> {
+    let valid = !label.is_empty()
+        && label.len() <= 64
+        && label
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '.' | '_' | '-' | '@'));
+    if valid {
+        Ok(())
+    } else {
+        Err(KeyStoreError::InvalidLabel(label.to_string()))
+    }
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Schreibt eine Datei, die nur der Eigentümer lesen darf.
+fn write_private(path: &Path, content: &str) -> Result<(), KeyStoreError> {
+    fs::write(path, content).map_err(io_err)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600)).map_err(io_err)?;
+    }
+    Ok(())
+}
+
+/// Verzeichnisgestützter Schlüsselspeicher.
+#[derive(Debug, Clone)]
+pub struct KeyStore {
+    root: PathBuf,
+}
+
+impl KeyStore {
+    /// Standardort: `$CRYPTOMORPH_KEYSTORE`, sonst `$XDG_DATA_HOME/cryptomorph/keys`,
+    /// `~/.local/share/cryptomorph/keys` bzw. unter Windows `%APPDATA%\cryptomorph\keys`.
+    pub fn default_path() -> Option<PathBuf> {
+        let var = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty());
+        if let Some(dir) = var(ENV_KEYSTORE) {
+            return Some(PathBuf::from(dir));
+        }
+        let data_home = var("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var("HOME").map(|home| Path::new(&home).join(".local/share")))
+            .or_else(|| var("APPDATA").map(PathBuf::from))?;
+        Some(data_home.join("cryptomorph").join("keys"))
+    }
+
+    /// Öffnet den Speicher und legt das Verzeichnis bei Bedarf an.
+    pub fn open(root: &Path) -> Result<Self, KeyStoreError> {
+        fs::create_dir_all(root).map_err(io_err)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(root, fs::Permissions::from_mode(0o700)).map_err(io_err)?;
+        }
+        Ok(KeyStore {
+            root: root.to_path_buf(),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Alle Schlüssel, sortiert nach Erstellungszeit und Fingerabdruck.
+    pub fn entries(&self) -> Result<Vec<KeyEntry>, KeyStoreError> {
+        let mut entries = Vec::new();
+        for dir in fs::read_dir(&self.root).map_err(io_err)? {
+            let dir = dir.map_err(io_err)?.path();
+            let is_key_dir = dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.len() == 64 && hex::decode(name).is_ok());
+            if is_key_dir && dir.join(META_FILE).is_file() {
+                entries.push(self.load_entry(&dir)?);
+            }
+        }
+        entries.sort_by_key(|entry| (entry.created, entry.fingerprint));
+        Ok(entries)
+    }
+
+    fn load_entry(&self, dir: &Path) -> Result<KeyEntry, KeyStoreError> {
+        let meta = fs::read_to_string(dir.join(META_FILE)).map_err(io_err)?;
+        let meta: Value = serde_json::from_str(&meta).map_err(|e| {
+            KeyStoreError::Key(CryptoError::InvalidFormat(format!(
+                "{}: {}",
+                dir.join(META_FILE).display(),
+                e
+            )))
+        })?;
+        let public_file = fs::read_dir(dir)
+            .map_err(io_err)?
+            .filter_map(Result::ok)
+            .map(|f| f.path())
+            .find(|p| p.to_string_lossy().ends_with("_public.key"))
+            .ok_or_else(|| KeyStoreError::NotFound(dir.display().to_string()))?;
+        let public_key = PublicKey::from_pem(&fs::read_to_string(&public_file).map_err(io_err)?)?;
+        let labels = meta["labels"]
+            .as_array()
+            .map(|labels| {
+                labels
+                    .iter()
+                    .filter_map(|l| l.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(KeyEntry {
+            fingerprint: public_key.fingerprint(),
+            public_key,
+            labels,
+            created: meta["created"].as_u64().unwrap_or(0),
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// Sucht einen Schlüssel über Bezeichnung oder Fingerabdruck(-Anfang).
+    pub fn find(&self, query: &str) -> Result<KeyEntry, KeyStoreError> {
+        let entries = self.entries()?;
+        if let Some(entry) = entries.iter().find(|e| e.labels.iter().any(|l| l == query)) {
+            return Ok(entry.clone());
+        }
+        let prefix = query.to_ascii_lowercase();
+        if prefix.len() < MIN_PREFIX_LEN || !prefix.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(KeyStoreError::NotFound(query.to_string()));
+        }
+        let mut matches = entries
+            .into_iter()
+            .filter(|e| e.fingerprint_hex().starts_with(&prefix));
+        match (matches.next(), matches.next()) {
+            (Some(entry), None) => Ok(entry),
+            (Some(_), Some(_)) => Err(KeyStoreError::Ambiguous(query.to_string())),
+            (None, _) => Err(KeyStoreError::NotFound(query.to_string())),
+        }
+    }
+
+    /// Nimmt einen Schlüssel auf (öffentliche Schlüsseldatei, optional die private).
+    ///
+    /// Ist der Schlüssel schon vorhanden, werden Bezeichnung und privater Schlüssel ergänzt.
+    pub fn import_pem(
+        &self,
+        public_pem: &str,
+        private_pem: Option<&str>,
+        label: Option<&str>,
+    ) -> Result<KeyEntry, KeyStoreError> {
+        let public_key = PublicKey::from_pem(public_pem)?;
+        if let Some(private_pem) = private_pem {
+            if !public_key.matches_private_pem(private_pem)? {
+                return Err(KeyStoreError::Key(CryptoError::InvalidFormat(
+                    "privater Schlüssel passt nicht zum öffentlichen".to_string(),
+                )));
+            }
+        }
+        let fingerprint = public_key.fingerprint();
+        let dir = self.root.join(hex::encode(fingerprint));
+        let mut entry = if dir.join(META_FILE).is_file() {
+            self.load_entry(&dir)?
+        } else {
+            KeyEntry {
+                public_key,
+                fingerprint,
+                labels: Vec::new(),
+                created: now(),
+                dir,
+            }
+        };
+        if let Some(label) = label {
+            self.check_label(label, &entry)?;
+            if !entry.labels.iter().any(|l| l == label) {
+                entry.labels.push(label.to_string());
+            }
+        }
+        fs::create_dir_all(&entry.dir).map_err(io_err)?;
+        fs::write(entry.public_path(), entry.public_key.to_pem()).map_err(io_err)?;
+        if let Some(private_pem) = private_pem {
+            let path = entry
+                .dir
+                .join(format!("{}_private.key", entry.public_key.file_prefix()));
+            write_private(&path, private_pem)?;
+        }
+        entry.write_meta()?;
+        Ok(entry)
+    }
+
+    fn check_label(&self, label: &str, entry: &KeyEntry) -> Result<(), KeyStoreError> {
+        validate_label(label)?;
+        let taken = self.entries()?.into_iter().any(|other| {
+            other.fingerprint != entry.fingerprint && other.labels.iter().any(|l| l == label)
+        });
+        if taken {
+            return Err(KeyStoreError::LabelInUse(label.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Ordnet einem Schlüssel eine weitere Bezeichnung zu.
+    pub fn add_label(&self, query: &str, label: &str) -> Result<KeyEntry, KeyStoreError> {
+        let mut entry = self.find(query)?;
+        self.check_label(label, &entry)?;
+        if !entry.labels.iter().any(|l| l == label) {
+            entry.labels.push(label.to_string());
+            entry.write_meta()?;
+        }
+        Ok(entry)
+    }
+
+    /// Kopiert die Schlüsseldateien nach `out_dir`; den privaten nur auf Wunsch.
+    pub fn export(
+        &self,
+        query: &str,
+        out_dir: &Path,
+        include_private: bool,
+    ) -> Result<Vec<PathBuf>, KeyStoreError> {
+        let entry = self.find(query)?;
+        fs::create_dir_all(out_dir).map_err(io_err)?;
+        let public = out_dir.join(entry.public_path().file_name().unwrap());
+        fs::copy(entry.public_path(), &public).map_err(io_err)?;
+        let mut written = vec![public];
+        if include_private {
+            let source = entry.private_path().ok_or_else(|| {
+                KeyStoreError::NotFound(format!("privater Schlüssel zu {}", query))
+            })?;
+            let target = out_dir.join(source.file_name().unwrap());
+            write_private(&target, &fs::read_to_string(&source).map_err(io_err)?)?;
+            written.push(target);
+        }
+        Ok(written)
+    }
+
+    /// Entfernt einen Schlüssel samt privatem Teil aus dem Speicher.
+    pub fn delete(&self, query: &str) -> Result<KeyEntry, KeyStoreError> {
+        let entry = self.find(query)?;
+        fs::remove_dir_all(&entry.dir).map_err(io_err)?;
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asymmetric::ed25519;
+    use crate::x509;
+
+    fn temp_store(name: &str) -> KeyStore {
+        let dir = std::env::temp_dir().join(format!(
+            "cryptomorph-keystore-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        KeyStore::open(&dir).unwrap()
+    }
+
+    fn ed25519_pems() -> (String, String) {
+        let (public, private) = ed25519::generate_ed25519_keypair();
+        (
+            PublicKey::Ed25519(public).to_pem(),
+            x509::pem_encode(key::PEM_ED25519_PRIVATE, &private.bytes),
+        )
+    }
+
+    #[test]
+    fn test_import_find_delete() {
+        let store = temp_store("basic");
+        let (alice_pub, alice_priv) = ed25519_pems();
+        let (bob_pub, _) = ed25519_pems();
+
+        let alice = store
+            .import_pem(&alice_pub, Some(&alice_priv), Some("alice"))
+            .unwrap();
+        let bob = store.import_pem(&bob_pub, None, Some("bob")).unwrap();
+        assert!(alice.private_path().is_some());
+        assert!(bob.private_path().is_none());
+
+        assert_eq!(store.find("alice").unwrap(), alice);
+        assert_eq!(store.find(&alice.key_id().to_uppercase()).unwrap(), alice);
+        assert_eq!(store.find(&bob.fingerprint_hex()).unwrap(), bob);
+        assert!(matches!(
+            store.find("carol"),
+            Err(KeyStoreError::NotFound(_))
+        ));
+        assert!(matches!(store.find("abc"), Err(KeyStoreError::NotFound(_))));
+
+        // erneuter Import ergänzt nur die Bezeichnung
+        let again = store.import_pem(&alice_pub, None, Some("a")).unwrap();
+        assert_eq!(again.labels, ["alice", "a"]);
+        assert_eq!(store.entries().unwrap().len(), 2);
+        assert_eq!(
+            store.import_pem(&bob_pub, None, Some("alice")),
+            Err(KeyStoreError::LabelInUse("alice".to_string()))
+        );
+        assert!(matches!(
+            store.add_label("bob", "mit Leerzeichen"),
+            Err(KeyStoreError::InvalidLabel(_))
+        ));
+        // privater Schlüssel eines anderen Paars wird abgelehnt
+        assert!(matches!(
+            store.import_pem(&bob_pub, Some(&alice_priv), None),
+            Err(KeyStoreError::Key(_))
+        ));
+
+        store.delete("bob").unwrap();
+        assert_eq!(store.entries().unwrap(), vec![again]);
+        fs::remove_dir_all(store.root()).unwrap();
+    }
+}
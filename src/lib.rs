@@ -1,4 +1,17 @@
+pub mod age;
 pub mod algorithms;
+pub mod archive;
 pub mod asymmetric;
+pub mod cms;
 pub mod cryptomorph_cli;
+pub mod hybrid;
+pub mod jose;
+pub mod keystore;
+pub mod openpgp;
+pub mod pkcs12;
+pub mod pqcrypto;
+pub mod signature;
+pub mod ssh;
 pub mod symmetric;
+pub mod utils;
+pub mod x509;
@@ -1,99 +1,451 @@
-use cryptomorph::asymmetric::rsa::generate_rsa_keypair;
+mod args;
+
+use args::{
+    AesCommand, AgeCommand, AgeIdentities, AgeRecipients, Cli, CmsCommand, Command, KeyAlgorithm,
+    KeyCommand, P12Command, PgpCommand, PrimeCommand, ProvablePrimeMethod, Recipients, RsaCommand,
+    SignatureHash, SshCommand, X509Command,
+};
+use clap::{CommandFactory, Parser};
+use cryptomorph::age;
 use cryptomorph::cryptomorph_cli;
+use cryptomorph::hybrid::recipient::Recipient;
+use cryptomorph::signature::HashAlgorithm;
 use cryptomorph_cli::{
-    aes_decrypt_file, aes_encrypt_file, rsa_decrypt_file, rsa_encrypt_file, rsa_sign_file,
-    rsa_verify_file, write_rsa_keys_pem,
+    aes_decrypt_file, aes_encrypt_file, aes_keygen, age_decrypt_file, age_encrypt_file, age_keygen,
+    cms_decrypt_file, cms_encrypt_file, cms_sign_file, cms_verify_file, configure_output,
+    create_out_dir, decrypt_directory, decrypt_file_multi, encrypt_directory, encrypt_file_multi,
+    export_p12, factor_number, fail, import_p12, key_delete, key_export, key_generate,
+    key_generate_files, key_import, key_label, key_list, load_age_identities, load_age_recipient,
+    load_age_recipients_file, load_recipient, load_recipients_file, open_file, pgp_decrypt_file,
+    pgp_export_key, pgp_import_key, pgp_sign_file, pgp_verify_file, prime_check, prime_generate,
+    prime_prove, read_passphrase_file, rekey_files, report, resolve_key, rsa_decrypt_file,
+    rsa_encrypt_file, rsa_validate_key, seal_file, sign_file, ssh_export_key, ssh_import_key,
+    ssh_sign_file, ssh_verify_file, verify_file, write_output, x509_create_csr, x509_self_signed,
+    x509_show, x509_sign_csr, x509_verify, OutputFormat, OutputOptions,
 };
-use rand::RngCore;
-use std::env;
-use std::fs::create_dir_all;
-use std::path::Path;
-
-fn print_help() {
-    println!("\nCryptomorph CLI – Hybrides Verschlüsselungstool\n");
-    println!("Verwendung:");
-    println!("  Rsa_Key_Gen <bitlänge> <output-pfad>");
-    println!("      → Generiert ein RSA-Schlüsselpaar mit gegebener Bitlänge (z. B. 4096)");
-    println!("  rsa_encrypt <input.txt> <rsa_public.key> <output.bin>");
-    println!("      → Verschlüsselt Datei mit AES + RSA (hybrid)");
-    println!("  rsa_decrypt <input.bin> <rsa_private.key> <output.txt>");
-    println!("      → Entschlüsselt Datei mit RSA → AES");
-    println!("  aes_encrypt <input.txt> <key.hex> <output.bin>");
-    println!("      → Verschlüsselt Datei direkt mit AES-256");
-    println!("  aes_decrypt <input.bin> <key.hex> <output.txt>");
-    println!("      → Entschlüsselt AES-Datei");
-    println!("  rsa_sign <input.txt> <rsa_private.key> <signature.sig>");
-    println!("      → Signiert Datei mit privatem Schlüssel");
-    println!("  rsa_verify <input.txt> <rsa_public.key> <signature.sig>");
-    println!("      → Verifiziert Signatur mit öffentlichem Schlüssel");
-    println!("  gen_aes_key");
-    println!("      → Generiert zufälligen 256-Bit AES-Schlüssel (Hex)\n");
+use std::path::{Path, PathBuf};
+
+/// Lädt die Empfänger aus `--recipient` und `--recipients-file`.
+fn load_recipients(args: &Recipients) -> Vec<Recipient> {
+    let mut recipients: Vec<Recipient> = args
+        .recipient
+        .iter()
+        .map(|value| load_recipient(&resolve_key(value)))
+        .collect();
+    for path in &args.recipients_file {
+        recipients.extend(load_recipients_file(path));
+    }
+    recipients
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+fn load_age_recipients(args: &AgeRecipients) -> Vec<age::Recipient> {
+    let mut recipients: Vec<age::Recipient> = args
+        .recipient
+        .iter()
+        .map(|r| load_age_recipient(r))
+        .collect();
+    for path in &args.recipients_file {
+        recipients.extend(load_age_recipients_file(path));
+    }
+    if let Some(path) = &args.passphrase_file {
+        recipients.push(age::Recipient::Scrypt {
+            passphrase: read_passphrase_file(path),
+            log_n: age::DEFAULT_SCRYPT_LOG_N,
+        });
+    }
+    recipients
+}
+
+fn load_age_identity_args(args: &AgeIdentities) -> Vec<age::Identity> {
+    let mut identities: Vec<age::Identity> = args
+        .identity
+        .iter()
+        .flat_map(|path| load_age_identities(path))
+        .collect();
+    if let Some(path) = &args.passphrase_file {
+        identities.push(age::Identity::Scrypt {
+            passphrase: read_passphrase_file(path),
+            max_log_n: age::MAX_SCRYPT_LOG_N,
+        });
+    }
+    identities
+}
+
+fn paths(paths: &[PathBuf]) -> Vec<&Path> {
+    paths.iter().map(PathBuf::as_path).collect()
+}
 
-    if args.len() < 2 || args[1] == "--help" || args[1] == "-h" {
-        print_help();
-        return;
+fn key_algorithm(algorithm: KeyAlgorithm) -> &'static str {
+    match algorithm {
+        KeyAlgorithm::Rsa => "rsa",
+        KeyAlgorithm::X25519 => "x25519",
+        KeyAlgorithm::Ed25519 => "ed25519",
+        KeyAlgorithm::Mlkem => "mlkem",
     }
+}
+
+fn hash_algorithm(hash: SignatureHash) -> HashAlgorithm {
+    match hash {
+        SignatureHash::Sha256 => HashAlgorithm::Sha256,
+        SignatureHash::Sha512 => HashAlgorithm::Sha512,
+    }
+}
+
+fn run_key(command: KeyCommand) {
+    match command {
+        KeyCommand::Generate {
+            algorithm,
+            label,
+            out,
+            bits,
+            exponent,
+            fips,
+        } => match (label, out) {
+            (Some(label), _) => {
+                key_generate(key_algorithm(algorithm), &label, bits, &exponent, fips)
+            }
+            (None, Some(out)) => {
+                key_generate_files(key_algorithm(algorithm), bits, &exponent, fips, &out)
+            }
+            (None, None) => unreachable!("von clap geprüft"),
+        },
+        KeyCommand::Import { file, label } => key_import(&file, label.as_deref()),
+        KeyCommand::List { long } => key_list(long),
+        KeyCommand::Label { query, label } => key_label(&query, &label),
+        KeyCommand::Export {
+            query,
+            out,
+            private,
+        } => key_export(&query, &out.out, private),
+        KeyCommand::Delete { query, force } => key_delete(&query, force),
+    }
+}
 
-    match args[1].as_str() {
-        "Rsa_Key_Gen" if args.len() == 4 => {
-            let bits: usize = args[2].parse().expect("Ungültige Bitlänge");
-            let out_dir = Path::new(&args[3]);
-            create_dir_all(&out_dir).expect("Konnte Ausgabeordner nicht erstellen");
+fn run_age(command: AgeCommand) {
+    match command {
+        AgeCommand::Keygen { out } => age_keygen(&out),
+        AgeCommand::Encrypt { io, recipients } => {
+            age_encrypt_file(&io.input, &load_age_recipients(&recipients), &io.out)
+        }
+        AgeCommand::Decrypt { io, identities } => {
+            age_decrypt_file(&io.input, &load_age_identity_args(&identities), &io.out)
+        }
+    }
+}
+
+fn run_pgp(command: PgpCommand) {
+    match command {
+        PgpCommand::ExportKey {
+            key,
+            user_id,
+            subkey,
+            out,
+        } => {
+            let subkey = subkey.as_deref().map(resolve_key);
+            pgp_export_key(&resolve_key(&key), &user_id, subkey.as_deref(), &out.out);
+        }
+        PgpCommand::Import {
+            input,
+            out,
+            passphrase_file,
+        } => {
+            let passphrase = passphrase_file.as_deref().map(read_passphrase_file);
+            pgp_import_key(&input.input, passphrase.as_deref(), &out.out);
+        }
+        PgpCommand::Decrypt { io, key } => pgp_decrypt_file(&io.input, &resolve_key(&key), &io.out),
+        PgpCommand::Sign { io, key } => pgp_sign_file(&io.input, &resolve_key(&key), &io.out),
+        PgpCommand::Verify { input, sig, cert } => pgp_verify_file(&input.input, &sig, &cert),
+    }
+}
 
-            let (pub_key, priv_key) = generate_rsa_keypair(bits);
-            write_rsa_keys_pem(&pub_key, &priv_key, out_dir);
+fn run_x509(command: X509Command) {
+    match command {
+        X509Command::SelfSigned {
+            key,
+            subject,
+            days,
+            san,
+            ca,
+            out,
+        } => x509_self_signed(&resolve_key(&key), &subject, days, &san, ca.ca(), &out),
+        X509Command::Csr {
+            key,
+            subject,
+            san,
+            out,
+        } => x509_create_csr(&resolve_key(&key), &subject, &san, &out),
+        X509Command::SignCsr {
+            io,
+            ca_cert,
+            key,
+            days,
+            ca,
+        } => x509_sign_csr(
+            &io.input,
+            &ca_cert,
+            &resolve_key(&key),
+            days,
+            ca.ca(),
+            &io.out,
+        ),
+        X509Command::Show { input } => x509_show(&input.input),
+        X509Command::Verify {
+            input,
+            trust,
+            intermediate,
+            host,
+        } => x509_verify(&input.input, &trust, &paths(&intermediate), host.as_deref()),
+    }
+}
 
-            println!("RSA-Schlüssel gespeichert in: {}", out_dir.display());
+fn run_cms(command: CmsCommand) {
+    match command {
+        CmsCommand::Sign {
+            io,
+            cert,
+            key,
+            chain,
+            detached,
+        } => cms_sign_file(
+            &io.input,
+            &cert,
+            &resolve_key(&key),
+            &paths(&chain),
+            detached,
+            &io.out,
+        ),
+        CmsCommand::Verify {
+            input,
+            trust,
+            content,
+            out,
+        } => cms_verify_file(&input.input, &trust, content.as_deref(), out.as_deref()),
+        CmsCommand::Encrypt { io, recipient } => {
+            cms_encrypt_file(&io.input, &paths(&recipient), &io.out)
         }
-        "rsa_encrypt" if args.len() == 5 => {
-            let input = Path::new(&args[2]);
-            let pub_key = Path::new(&args[3]);
-            let output = Path::new(&args[4]);
-            rsa_encrypt_file(input, pub_key, output);
+        CmsCommand::Decrypt { io, cert, key } => {
+            cms_decrypt_file(&io.input, &cert, &resolve_key(&key), &io.out)
         }
-        "rsa_decrypt" if args.len() == 5 => {
-            let input = Path::new(&args[2]);
-            let priv_key = Path::new(&args[3]);
-            let output = Path::new(&args[4]);
-            rsa_decrypt_file(input, priv_key, output);
+    }
+}
+
+fn run_p12(command: P12Command) {
+    match command {
+        P12Command::Import {
+            input,
+            out,
+            passphrase_file,
+        } => {
+            let password = passphrase_file
+                .as_deref()
+                .map(read_passphrase_file)
+                .unwrap_or_default();
+            import_p12(&input.input, &password, &out.out);
+        }
+        P12Command::Export {
+            key,
+            cert,
+            chain,
+            name,
+            passphrase_file,
+            out,
+        } => export_p12(
+            &resolve_key(&key),
+            &cert,
+            &paths(&chain),
+            name.as_deref(),
+            &read_passphrase_file(&passphrase_file),
+            &out,
+        ),
+    }
+}
+
+fn run_ssh(command: SshCommand) {
+    match command {
+        SshCommand::Import {
+            input,
+            out,
+            passphrase_file,
+        } => {
+            let passphrase = passphrase_file.as_deref().map(read_passphrase_file);
+            ssh_import_key(&input.input, passphrase.as_deref(), &out.out);
         }
-        "aes_encrypt" if args.len() == 5 => {
-            let input = Path::new(&args[2]);
-            let key_hex = &args[3];
-            let output = Path::new(&args[4]);
-            aes_encrypt_file(input, key_hex, output);
+        SshCommand::Export {
+            key,
+            out,
+            comment,
+            passphrase_file,
+        } => {
+            let passphrase = passphrase_file.as_deref().map(read_passphrase_file);
+            ssh_export_key(
+                &resolve_key(&key),
+                comment.as_deref(),
+                passphrase.as_deref(),
+                &out.out,
+            );
         }
-        "aes_decrypt" if args.len() == 5 => {
-            let input = Path::new(&args[2]);
-            let key_hex = &args[3];
-            let output = Path::new(&args[4]);
-            aes_decrypt_file(input, key_hex, output);
+        SshCommand::Sign {
+            io,
+            key,
+            namespace,
+            passphrase_file,
+        } => {
+            let passphrase = passphrase_file.as_deref().map(read_passphrase_file);
+            ssh_sign_file(
+                &io.input,
+                &resolve_key(&key),
+                &namespace,
+                passphrase.as_deref(),
+                &io.out,
+            );
         }
-        "rsa_sign" if args.len() == 5 => {
-            let input = Path::new(&args[2]);
-            let priv_key = Path::new(&args[3]);
-            let signature = Path::new(&args[4]);
-            rsa_sign_file(input, priv_key, signature);
+        SshCommand::Verify {
+            input,
+            sig,
+            allowed_signers,
+            principal,
+            namespace,
+        } => ssh_verify_file(&input.input, &sig, &allowed_signers, &principal, &namespace),
+    }
+}
+
+fn run_rsa(command: RsaCommand) {
+    match command {
+        RsaCommand::Encrypt { io, key } => rsa_encrypt_file(&io.input, &resolve_key(&key), &io.out),
+        RsaCommand::Decrypt { io, key } => rsa_decrypt_file(&io.input, &resolve_key(&key), &io.out),
+        RsaCommand::Validate { key } => rsa_validate_key(&resolve_key(&key)),
+    }
+}
+
+fn run_aes(command: AesCommand) {
+    match command {
+        AesCommand::Encrypt { io, key } => aes_encrypt_file(&io.input, &key, &io.out),
+        AesCommand::Decrypt { io, key } => aes_decrypt_file(&io.input, &key, &io.out),
+        AesCommand::Keygen => aes_keygen(),
+    }
+}
+
+fn run_prime(command: PrimeCommand) {
+    match command {
+        PrimeCommand::Prove { number, out } => prime_prove(&number, &out),
+        PrimeCommand::Check { input } => prime_check(&input.input),
+        PrimeCommand::Generate {
+            bits,
+            method,
+            seed,
+            cert,
+        } => {
+            let method = match method {
+                ProvablePrimeMethod::Maurer => "maurer",
+                ProvablePrimeMethod::ShaweTaylor => "shawe-taylor",
+            };
+            prime_generate(bits, method, seed.as_deref(), cert.as_deref())
         }
-        "rsa_verify" if args.len() == 5 => {
-            let input = Path::new(&args[2]);
-            let pub_key = Path::new(&args[3]);
-            let signature = Path::new(&args[4]);
-            rsa_verify_file(input, pub_key, signature);
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    configure_output(OutputOptions {
+        format: match (cli.quiet, cli.json) {
+            (_, true) => OutputFormat::Json,
+            (true, false) => OutputFormat::Quiet,
+            (false, false) => OutputFormat::Text,
+        },
+        armor: cli.armor,
+    });
+
+    match cli.command {
+        Command::Key(command) => run_key(command),
+        Command::Encrypt { io, recipients } => {
+            encrypt_file_multi(&io.input, &load_recipients(&recipients), &io.out)
         }
-        "gen_aes_key" => {
-            let mut key = [0u8; 32];
-            rand::thread_rng().fill_bytes(&mut key);
-            println!("🔑 Zufälliger AES-256-Schlüssel (Hex):");
-            println!("{}", hex::encode(key));
+        Command::Decrypt { io, key } => decrypt_file_multi(&io.input, &resolve_key(&key), &io.out),
+        Command::Rekey {
+            paths: files,
+            key,
+            recipients,
+            dry_run,
+        } => rekey_files(
+            &paths(&files),
+            &resolve_key(&key),
+            &load_recipients(&recipients),
+            dry_run,
+        ),
+        Command::Seal {
+            io,
+            key,
+            recipients,
+        } => seal_file(
+            &io.input,
+            &resolve_key(&key),
+            &load_recipients(&recipients),
+            &io.out,
+        ),
+        Command::Open { io, key, sender } => open_file(
+            &io.input,
+            &resolve_key(&key),
+            &resolve_key(&sender),
+            &io.out,
+        ),
+        Command::Sign {
+            io,
+            key,
+            comment,
+            clearsign,
+            hash,
+        } => sign_file(
+            &io.input,
+            &resolve_key(&key),
+            &io.out,
+            hash_algorithm(hash),
+            comment.as_deref(),
+            clearsign,
+        ),
+        Command::Verify {
+            input,
+            sig,
+            key,
+            out,
+        } => verify_file(
+            &input.input,
+            sig.as_deref(),
+            key.map(|key| resolve_key(&key)).as_deref(),
+            out.as_deref(),
+        ),
+        Command::Age(command) => run_age(command),
+        Command::EncryptDir {
+            dir,
+            out,
+            recipients,
+        } => encrypt_directory(&dir, &load_age_recipients(&recipients), &out),
+        Command::DecryptDir {
+            input,
+            dest,
+            identities,
+        } => decrypt_directory(&input.input, &load_age_identity_args(&identities), &dest),
+        Command::Pgp(command) => run_pgp(command),
+        Command::X509(command) => run_x509(command),
+        Command::Cms(command) => run_cms(command),
+        Command::P12(command) => run_p12(command),
+        Command::Ssh(command) => run_ssh(command),
+        Command::Rsa(command) => run_rsa(command),
+        Command::Aes(command) => run_aes(command),
+        Command::Prime(command) => run_prime(command),
+        Command::Factor { number } => factor_number(&number),
+        Command::Completions { shell } => {
+            let mut script = Vec::new();
+            clap_complete::generate(shell, &mut Cli::command(), "cryptomorph", &mut script);
+            write_output(Path::new("-"), &script, None);
         }
-        _ => {
-            eprintln!("Ungültige Argumente. Hilfe mit ./cryptomorph --help");
-            std::process::exit(1);
+        Command::Manpages { out } => {
+            create_out_dir(&out.out);
+            clap_mangen::generate_to(Cli::command(), &out.out)
+                .unwrap_or_else(|e| fail(format!("{}: {}", out.out.display(), e)));
+            report(
+                &format!("Man-Pages gespeichert in: {}", out.out.display()),
+                serde_json::json!({ "output": out.out }),
+            );
         }
     }
 }
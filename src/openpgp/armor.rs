@@ -0,0 +1,162 @@
+//! ASCII-Armor für OpenPGP (RFC 9580, Abschnitt 6) mit CRC-24-Prüfsumme.
+
+pub use crate::utils::armor::crc24;
+use crate::utils::error::CryptoError;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+const LINE_LEN: usize = 64;
+
+/// Art des Armor-Blocks, bestimmt durch das Label der BEGIN-Zeile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArmorKind {
+    Message,
+    PublicKey,
+    PrivateKey,
+    Signature,
+}
+
+impl ArmorKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ArmorKind::Message => "PGP MESSAGE",
+            ArmorKind::PublicKey => "PGP PUBLIC KEY BLOCK",
+            ArmorKind::PrivateKey => "PGP PRIVATE KEY BLOCK",
+            ArmorKind::Signature => "PGP SIGNATURE",
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        [
+            ArmorKind::Message,
+            ArmorKind::PublicKey,
+            ArmorKind::PrivateKey,
+            ArmorKind::Signature,
+        ]
+        .into_iter()
+        .find(|kind| kind.label() == label)
+    }
+}
+
+/// Prüft, ob `data` (nach führendem Leerraum) mit einer OpenPGP-Armor-Zeile beginnt.
+pub fn is_armored(data: &[u8]) -> bool {
+    data.trim_ascii_start().starts_with(b"-----BEGIN PGP ")
+}
+
+/// Kodiert Binärdaten als ASCII-Armor inklusive CRC-24-Prüfsumme.
+pub fn encode(kind: ArmorKind, data: &[u8]) -> String {
+    let b64 = STANDARD.encode(data);
+    let mut out = format!("-----BEGIN {}-----\n\n", kind.label());
+    for chunk in b64.as_bytes().chunks(LINE_LEN) {
+        out.push_str(std::str::from_utf8(chunk).unwrap());
+        out.push('\n');
+    }
+    out.push('=');
+    out.push_str(&STANDARD.encode(&crc24(data).to_be_bytes()[1..]));
+    out.push('\n');
+    out.push_str(&format!("-----END {}-----\n", kind.label()));
+    out
+}
+
+/// Dekodiert den ersten Armor-Block in `data`.
+///
+/// Text vor der BEGIN-Zeile wird ignoriert, Armor-Header (`Key: Value`) werden übersprungen.
+/// Base64 muss kanonisch sein; eine vorhandene Prüfsumme muss stimmen.
+pub fn decode(data: &[u8]) -> Result<(ArmorKind, Vec<u8>), CryptoError> {
+    let err = |msg: &str| CryptoError::InvalidFormat(format!("OpenPGP-Armor: {}", msg));
+    let text = std::str::from_utf8(data).map_err(|_| err("kein gültiger Text"))?;
+    let mut lines = text.lines().map(str::trim_end);
+
+    let label = lines
+        .by_ref()
+        .find_map(|line| {
+            line.strip_prefix("-----BEGIN ")
+                .and_then(|rest| rest.strip_suffix("-----"))
+        })
+        .ok_or_else(|| err("BEGIN-Zeile fehlt"))?;
+    let kind = ArmorKind::from_label(label)
+        .ok_or_else(|| CryptoError::Unsupported(format!("Armor-Typ {}", label)))?;
+
+    // Header bis zur ersten Leerzeile
+    for line in lines.by_ref() {
+        if line.is_empty() {
+            break;
+        }
+        if !line.contains(": ") {
+            return Err(err("ungültige Header-Zeile"));
+        }
+    }
+
+    let mut b64 = String::new();
+    let mut checksum = None;
+    let mut end = None;
+    for line in lines.by_ref() {
+        if let Some(rest) = line.strip_prefix("-----END ") {
+            end = rest.strip_suffix("-----");
+            break;
+        }
+        if checksum.is_some() {
+            return Err(err("Daten nach der Prüfsumme"));
+        }
+        if let Some(crc) = line.strip_prefix('=') {
+            checksum = Some(crc.to_string());
+        } else {
+            b64.push_str(line);
+        }
+    }
+    if end != Some(label) {
+        return Err(err("END-Zeile fehlt oder passt nicht"));
+    }
+
+    let bytes = STANDARD
+        .decode(&b64)
+        .map_err(|_| err("ungültiges Base64"))?;
+    if let Some(crc) = checksum {
+        let crc = STANDARD
+            .decode(&crc)
+            .map_err(|_| err("ungültige Prüfsumme"))?;
+        if crc != crc24(&bytes).to_be_bytes()[1..] {
+            return Err(err("Prüfsumme stimmt nicht"));
+        }
+    }
+    Ok((kind, bytes))
+}
+
+/// Dekodiert Armor, falls vorhanden; Binärdaten werden unverändert zurückgegeben.
+pub fn dearmor(data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if is_armored(data) {
+        decode(data).map(|(_, bytes)| bytes)
+    } else {
+        Ok(data.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_and_checksum() {
+        let data: Vec<u8> = (0..200u8).collect();
+        let armored = encode(ArmorKind::Signature, &data);
+        assert!(is_armored(armored.as_bytes()));
+        assert_eq!(
+            decode(armored.as_bytes()).unwrap(),
+            (ArmorKind::Signature, data.clone())
+        );
+
+        // Header und vorangestellter Text werden toleriert
+        let with_header = armored.replacen("-----\n\n", "-----\nComment: Test\n\n", 1);
+        let with_prefix = format!("Vorspann\n{}", with_header);
+        assert_eq!(decode(with_prefix.as_bytes()).unwrap().1, data);
+
+        // falsche Prüfsumme und Müll im Base64 werden abgelehnt
+        let crc_line = armored.lines().find(|l| l.starts_with('=')).unwrap();
+        let bad_crc = armored.replace(crc_line, "=AAAA");
+        assert!(decode(bad_crc.as_bytes()).is_err());
+        let garbage = armored.replacen("AAEC", "A!EC", 1);
+        assert!(decode(garbage.as_bytes()).is_err());
+        let wrong_end = armored.replace("END PGP SIGNATURE", "END PGP MESSAGE");
+        assert!(decode(wrong_end.as_bytes()).is_err());
+    }
+}
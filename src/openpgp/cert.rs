@@ -0,0 +1,431 @@
+//! Zertifikate (übertragbare öffentliche bzw. private Schlüssel, RFC 9580, 10.1).
+//!
+//! Beim Einlesen werden alle Eigensignaturen geprüft: User-IDs und Unterschlüssel ohne
+//! gültige Bindung werden verworfen, widerrufene Unterschlüssel ebenso.
+
+use crate::asymmetric::{ed25519, rsa, x25519};
+use crate::openpgp::armor::{self, ArmorKind};
+use crate::openpgp::crypto::{HashAlgo, SymAlgo};
+use crate::openpgp::key::{Key, PublicKeyPacket, PublicParams, SecretParams};
+use crate::openpgp::packet::{
+    parse_packets, write_packet, Packet, TAG_PUBLIC_KEY, TAG_PUBLIC_SUBKEY, TAG_SECRET_KEY,
+    TAG_SECRET_SUBKEY, TAG_SIGNATURE, TAG_USER_ID,
+};
+use crate::openpgp::signature::{
+    Signature, Subpacket, KEY_FLAG_CERTIFY, KEY_FLAG_ENCRYPT, KEY_FLAG_SIGN, SIG_CERT_POSITIVE,
+    SIG_DIRECT_KEY, SIG_KEY_REVOCATION, SIG_PRIMARY_KEY_BINDING, SIG_SUBKEY_BINDING,
+    SIG_SUBKEY_REVOCATION, SUB_FEATURES, SUB_KEY_FLAGS, SUB_PREFERRED_COMPRESSION,
+    SUB_PREFERRED_HASH, SUB_PREFERRED_SYM,
+};
+use crate::utils::error::CryptoError;
+
+/// Eine User-ID mit ihren gültigen Eigenzertifizierungen.
+#[derive(Clone)]
+pub struct UserId {
+    pub value: String,
+    pub signatures: Vec<Signature>,
+}
+
+/// Ein Unterschlüssel mit seinen gültigen Bindungssignaturen.
+#[derive(Clone)]
+pub struct Subkey {
+    pub key: Key,
+    pub signatures: Vec<Signature>,
+}
+
+/// Ein OpenPGP-Zertifikat; bei privaten Schlüsseln sind die Geheimteile entsperrt.
+#[derive(Clone)]
+pub struct Cert {
+    pub primary: Key,
+    /// Direkte Schlüsselsignaturen.
+    pub signatures: Vec<Signature>,
+    pub user_ids: Vec<UserId>,
+    pub subkeys: Vec<Subkey>,
+}
+
+fn invalid(msg: &str) -> CryptoError {
+    CryptoError::InvalidFormat(msg.to_string())
+}
+
+/// Neueste Signatur einer Liste (nach Erstellungszeit).
+fn latest(signatures: &[Signature]) -> Option<&Signature> {
+    signatures.iter().max_by_key(|sig| sig.creation_time())
+}
+
+fn hash_user_id(hasher: &mut crate::openpgp::crypto::Hasher, user_id: &[u8]) {
+    hasher.update(&[0xb4]);
+    hasher.update(&(user_id.len() as u32).to_be_bytes());
+    hasher.update(user_id);
+}
+
+impl Cert {
+    /// Liest das erste Zertifikat aus `data` (binär oder mit Armor) und prüft es.
+    ///
+    /// Geschützte private Schlüssel werden mit `passphrase` entsperrt.
+    pub fn from_bytes(data: &[u8], passphrase: Option<&[u8]>) -> Result<Self, CryptoError> {
+        let packets = parse_packets(&armor::dearmor(data)?)?;
+        Self::from_packets(&packets, passphrase)?.validate()
+    }
+
+    fn from_packets(packets: &[Packet], passphrase: Option<&[u8]>) -> Result<Self, CryptoError> {
+        let mut iter = packets.iter().peekable();
+        let first = iter.next().ok_or_else(|| invalid("keine Schlüsseldaten"))?;
+        let primary = match first.tag {
+            TAG_PUBLIC_KEY => Key {
+                public: PublicKeyPacket::parse(&first.body)?,
+                secret: None,
+            },
+            TAG_SECRET_KEY => Key::parse_secret(&first.body, passphrase)?,
+            _ => return Err(invalid("Zertifikat beginnt nicht mit einem Schlüsselpaket")),
+        };
+        let mut cert = Cert {
+            primary,
+            signatures: Vec::new(),
+            user_ids: Vec::new(),
+            subkeys: Vec::new(),
+        };
+
+        // Signaturen gehören zum zuletzt gelesenen Element (Schlüssel, User-ID, Unterschlüssel)
+        enum Current {
+            Primary,
+            UserId,
+            Subkey,
+            Skipped,
+        }
+        let mut current = Current::Primary;
+        for packet in iter {
+            match packet.tag {
+                TAG_PUBLIC_KEY | TAG_SECRET_KEY => break, // nächstes Zertifikat
+                TAG_USER_ID => {
+                    cert.user_ids.push(UserId {
+                        value: String::from_utf8_lossy(&packet.body).into_owned(),
+                        signatures: Vec::new(),
+                    });
+                    current = Current::UserId;
+                }
+                TAG_PUBLIC_SUBKEY | TAG_SECRET_SUBKEY => {
+                    let key = if packet.tag == TAG_PUBLIC_SUBKEY {
+                        PublicKeyPacket::parse(&packet.body).map(|public| Key {
+                            public,
+                            secret: None,
+                        })
+                    } else {
+                        Key::parse_secret(&packet.body, passphrase)
+                    };
+                    match key {
+                        Ok(key) => {
+                            cert.subkeys.push(Subkey {
+                                key,
+                                signatures: Vec::new(),
+                            });
+                            current = Current::Subkey;
+                        }
+                        // nicht unterstützte Unterschlüssel überspringen
+                        Err(CryptoError::Unsupported(_)) => current = Current::Skipped,
+                        Err(e) => return Err(e),
+                    }
+                }
+                TAG_SIGNATURE => {
+                    let Ok(sig) = Signature::parse(&packet.body) else {
+                        continue;
+                    };
+                    match current {
+                        Current::Primary => cert.signatures.push(sig),
+                        Current::UserId => cert.user_ids.last_mut().unwrap().signatures.push(sig),
+                        Current::Subkey => cert.subkeys.last_mut().unwrap().signatures.push(sig),
+                        Current::Skipped => {}
+                    }
+                }
+                // Benutzerattribute (Fotos) werden samt Signaturen übersprungen
+                _ => current = Current::Skipped,
+            }
+        }
+        Ok(cert)
+    }
+
+    /// Prüft alle Eigensignaturen und entfernt ungültige Komponenten.
+    fn validate(mut self) -> Result<Self, CryptoError> {
+        let primary = self.primary.public.clone();
+        if matches!(primary.params, PublicParams::Unknown(_)) {
+            return Err(CryptoError::Unsupported(format!(
+                "Public-Key-Algorithmus {}",
+                primary.params.algo()
+            )));
+        }
+        let by_primary =
+            |sig: &Signature| sig.is_issued_by(&primary) && sig.hash_algo != HashAlgo::Sha1 as u8;
+
+        let direct_ok =
+            |sig: &Signature| by_primary(sig) && sig.verify(&primary, |h| primary.hash_into(h));
+        if self
+            .signatures
+            .iter()
+            .any(|sig| sig.sig_type == SIG_KEY_REVOCATION && direct_ok(sig))
+        {
+            return Err(invalid("Schlüssel wurde widerrufen"));
+        }
+        self.signatures
+            .retain(|sig| sig.sig_type == SIG_DIRECT_KEY && direct_ok(sig));
+
+        for uid in &mut self.user_ids {
+            let value = uid.value.clone();
+            uid.signatures.retain(|sig| {
+                (0x10..=0x13).contains(&sig.sig_type)
+                    && by_primary(sig)
+                    && sig.verify(&primary, |h| {
+                        primary.hash_into(h);
+                        hash_user_id(h, value.as_bytes());
+                    })
+            });
+        }
+        self.user_ids.retain(|uid| !uid.signatures.is_empty());
+        if self.user_ids.is_empty() && self.signatures.is_empty() {
+            return Err(invalid("keine gültige Eigensignatur des Hauptschlüssels"));
+        }
+
+        self.subkeys.retain_mut(|subkey| {
+            let sub = subkey.key.public.clone();
+            if matches!(sub.params, PublicParams::Unknown(_)) {
+                return false;
+            }
+            let binding_ok = |sig: &Signature| {
+                by_primary(sig)
+                    && sig.verify(&primary, |h| {
+                        primary.hash_into(h);
+                        sub.hash_into(h);
+                    })
+            };
+            if subkey
+                .signatures
+                .iter()
+                .any(|sig| sig.sig_type == SIG_SUBKEY_REVOCATION && binding_ok(sig))
+            {
+                return false;
+            }
+            subkey.signatures.retain(|sig| {
+                if sig.sig_type != SIG_SUBKEY_BINDING || !binding_ok(sig) {
+                    return false;
+                }
+                // Signatur-Unterschlüssel brauchen eine gültige Rückbindung (0x19)
+                if sig.key_flags().unwrap_or(0) & KEY_FLAG_SIGN == 0 {
+                    return true;
+                }
+                sig.embedded_signature().is_some_and(|back| {
+                    back.sig_type == SIG_PRIMARY_KEY_BINDING
+                        && back.verify(&sub, |h| {
+                            primary.hash_into(h);
+                            sub.hash_into(h);
+                        })
+                })
+            });
+            !subkey.signatures.is_empty()
+        });
+        Ok(self)
+    }
+
+    pub fn fingerprint(&self) -> Vec<u8> {
+        self.primary.public.fingerprint()
+    }
+
+    pub fn has_secret(&self) -> bool {
+        self.primary.secret.is_some() || self.subkeys.iter().any(|s| s.key.secret.is_some())
+    }
+
+    /// Schlüsselflags des Hauptschlüssels aus der neuesten Eigensignatur.
+    fn primary_flags(&self) -> Option<u8> {
+        let sigs: Vec<Signature> = self
+            .signatures
+            .iter()
+            .chain(self.user_ids.iter().flat_map(|uid| uid.signatures.iter()))
+            .cloned()
+            .collect();
+        latest(&sigs).and_then(Signature::key_flags)
+    }
+
+    /// Schlüssel, die laut Flags (oder mangels Flags laut Algorithmus) verwendet werden dürfen.
+    fn keys_with(&self, flag: u8, capable: fn(&PublicParams) -> bool) -> Vec<&Key> {
+        let mut keys = Vec::new();
+        let allowed = |flags: Option<u8>, params: &PublicParams| {
+            capable(params) && flags.is_none_or(|f| f & flag != 0)
+        };
+        if allowed(self.primary_flags(), &self.primary.public.params) {
+            keys.push(&self.primary);
+        }
+        for subkey in &self.subkeys {
+            let flags = latest(&subkey.signatures).and_then(Signature::key_flags);
+            if allowed(flags, &subkey.key.public.params) {
+                keys.push(&subkey.key);
+            }
+        }
+        keys
+    }
+
+    pub fn encryption_keys(&self) -> Vec<&Key> {
+        self.keys_with(KEY_FLAG_ENCRYPT, PublicParams::can_encrypt)
+    }
+
+    pub fn signing_keys(&self) -> Vec<&Key> {
+        self.keys_with(KEY_FLAG_SIGN, PublicParams::can_sign)
+    }
+
+    fn serialize(&self, secret: bool) -> Option<Vec<u8>> {
+        let mut out = Vec::new();
+        let write_key = |out: &mut Vec<u8>, key: &Key, public_tag: u8, secret_tag: u8| {
+            if secret {
+                write_packet(out, secret_tag, &key.secret_body()?);
+            } else {
+                write_packet(out, public_tag, key.public.body());
+            }
+            Some(())
+        };
+        write_key(&mut out, &self.primary, TAG_PUBLIC_KEY, TAG_SECRET_KEY)?;
+        for sig in &self.signatures {
+            write_packet(&mut out, TAG_SIGNATURE, &sig.to_body());
+        }
+        for uid in &self.user_ids {
+            write_packet(&mut out, TAG_USER_ID, uid.value.as_bytes());
+            for sig in &uid.signatures {
+                write_packet(&mut out, TAG_SIGNATURE, &sig.to_body());
+            }
+        }
+        for subkey in &self.subkeys {
+            if secret && subkey.key.secret.is_none() {
+                continue;
+            }
+            write_key(&mut out, &subkey.key, TAG_PUBLIC_SUBKEY, TAG_SECRET_SUBKEY)?;
+            for sig in &subkey.signatures {
+                write_packet(&mut out, TAG_SIGNATURE, &sig.to_body());
+            }
+        }
+        Some(out)
+    }
+
+    /// Öffentliches Zertifikat als ASCII-Armor.
+    pub fn to_public_armor(&self) -> String {
+        armor::encode(ArmorKind::PublicKey, &self.serialize(false).unwrap())
+    }
+
+    /// Privater Schlüssel (ungeschützt) als ASCII-Armor; None ohne privaten Hauptschlüssel.
+    pub fn to_secret_armor(&self) -> Option<String> {
+        Some(armor::encode(ArmorKind::PrivateKey, &self.serialize(true)?))
+    }
+
+    /// Erstellt ein v4-Zertifikat aus einem RSA-Schlüsselpaar (signieren und verschlüsseln).
+    pub fn from_rsa(
+        pub_key: &rsa::PublicKey,
+        priv_key: &rsa::PrivateKey,
+        user_id: &str,
+        created: u32,
+    ) -> Result<Self, CryptoError> {
+        let (p, q) = rsa::recover_primes(pub_key, priv_key)
+            .ok_or_else(|| invalid("RSA-Schlüsselpaar ist inkonsistent"))?;
+        let primary = Key {
+            public: PublicKeyPacket::new(4, created, PublicParams::Rsa(pub_key.clone())),
+            secret: Some(SecretParams::Rsa {
+                key: priv_key.clone(),
+                p,
+                q,
+            }),
+        };
+        Self::build(
+            primary,
+            None,
+            user_id,
+            created,
+            KEY_FLAG_CERTIFY | KEY_FLAG_SIGN | KEY_FLAG_ENCRYPT,
+        )
+    }
+
+    /// Erstellt ein v4-Zertifikat mit Ed25519-Hauptschlüssel und optionalem
+    /// X25519-Verschlüsselungsunterschlüssel (EdDSALegacy/ECDH, kompatibel zu GnuPG 2.2).
+    pub fn from_ed25519(
+        priv_key: &ed25519::PrivateKey,
+        subkey: Option<&x25519::PrivateKey>,
+        user_id: &str,
+        created: u32,
+    ) -> Result<Self, CryptoError> {
+        let primary = Key {
+            public: PublicKeyPacket::new(
+                4,
+                created,
+                PublicParams::EdDsaLegacy(ed25519::public_key_from_private(priv_key)),
+            ),
+            secret: Some(SecretParams::Ed25519(priv_key.clone())),
+        };
+        let subkey = subkey.map(|x| Key {
+            public: PublicKeyPacket::new(
+                4,
+                created,
+                PublicParams::EcdhCv25519 {
+                    key: x25519::public_key_from_private(x),
+                    hash: HashAlgo::Sha256,
+                    sym: SymAlgo::Aes128,
+                },
+            ),
+            secret: Some(SecretParams::X25519(x.clone())),
+        });
+        Self::build(
+            primary,
+            subkey,
+            user_id,
+            created,
+            KEY_FLAG_CERTIFY | KEY_FLAG_SIGN,
+        )
+    }
+
+    fn build(
+        primary: Key,
+        subkey: Option<Key>,
+        user_id: &str,
+        created: u32,
+        primary_flags: u8,
+    ) -> Result<Self, CryptoError> {
+        let public = primary.public.clone();
+        let uid_sig = Signature::create(
+            &primary,
+            SIG_CERT_POSITIVE,
+            vec![
+                Subpacket::new(SUB_KEY_FLAGS, &[primary_flags]),
+                Subpacket::new(SUB_PREFERRED_SYM, &[9, 8, 7]),
+                Subpacket::new(SUB_PREFERRED_HASH, &[8, 10, 9]),
+                Subpacket::new(SUB_PREFERRED_COMPRESSION, &[2, 1, 0]),
+                // Features: SEIPD v1 (MDC)
+                Subpacket::new(SUB_FEATURES, &[0x01]),
+            ],
+            created,
+            |h| {
+                public.hash_into(h);
+                hash_user_id(h, user_id.as_bytes());
+            },
+        )?;
+
+        let mut subkeys = Vec::new();
+        if let Some(key) = subkey {
+            let sub = key.public.clone();
+            let binding = Signature::create(
+                &primary,
+                SIG_SUBKEY_BINDING,
+                vec![Subpacket::new(SUB_KEY_FLAGS, &[KEY_FLAG_ENCRYPT])],
+                created,
+                |h| {
+                    public.hash_into(h);
+                    sub.hash_into(h);
+                },
+            )?;
+            subkeys.push(Subkey {
+                key,
+                signatures: vec![binding],
+            });
+        }
+
+        Ok(Cert {
+            primary,
+            signatures: Vec::new(),
+            user_ids: vec![UserId {
+                value: user_id.to_string(),
+                signatures: vec![uid_sig],
+            }],
+            subkeys,
+        })
+    }
+}
@@ -0,0 +1,283 @@
+//! Algorithmenkennungen und Hilfsverfahren für OpenPGP (RFC 9580, Abschnitte 3.7 und 9).
+
+use crate::asymmetric::rsa::Pkcs1Hash;
+use crate::openpgp::packet::Reader;
+use crate::utils::error::CryptoError;
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::aead::consts::{U12, U15, U16};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::AesGcm;
+use eax::Eax;
+use ocb3::Ocb3;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+/// Symmetrische Verfahren; unterstützt werden nur die AES-Varianten.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymAlgo {
+    Aes128 = 7,
+    Aes192 = 8,
+    Aes256 = 9,
+}
+
+impl SymAlgo {
+    pub fn from_id(id: u8) -> Result<Self, CryptoError> {
+        match id {
+            7 => Ok(SymAlgo::Aes128),
+            8 => Ok(SymAlgo::Aes192),
+            9 => Ok(SymAlgo::Aes256),
+            _ => Err(CryptoError::Unsupported(format!(
+                "symmetrischer Algorithmus {}",
+                id
+            ))),
+        }
+    }
+
+    pub fn key_len(self) -> usize {
+        match self {
+            SymAlgo::Aes128 => 16,
+            SymAlgo::Aes192 => 24,
+            SymAlgo::Aes256 => 32,
+        }
+    }
+
+    /// Ermittelt das Verfahren aus der Länge eines Sitzungsschlüssels.
+    pub fn from_key_len(len: usize) -> Result<Self, CryptoError> {
+        match len {
+            16 => Ok(SymAlgo::Aes128),
+            24 => Ok(SymAlgo::Aes192),
+            32 => Ok(SymAlgo::Aes256),
+            _ => Err(CryptoError::DecryptionFailed),
+        }
+    }
+}
+
+/// Hash-Verfahren. SHA-1 ist nur für S2K, MDC und v4-Fingerabdrücke zulässig.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgo {
+    Sha1 = 2,
+    Sha256 = 8,
+    Sha384 = 9,
+    Sha512 = 10,
+}
+
+impl HashAlgo {
+    pub fn from_id(id: u8) -> Result<Self, CryptoError> {
+        match id {
+            2 => Ok(HashAlgo::Sha1),
+            8 => Ok(HashAlgo::Sha256),
+            9 => Ok(HashAlgo::Sha384),
+            10 => Ok(HashAlgo::Sha512),
+            _ => Err(CryptoError::Unsupported(format!("Hash-Algorithmus {}", id))),
+        }
+    }
+
+    /// Zugehöriges Verfahren für RSASSA-PKCS1-v1_5; SHA-1-Signaturen werden abgelehnt.
+    pub fn pkcs1(self) -> Result<Pkcs1Hash, CryptoError> {
+        match self {
+            HashAlgo::Sha1 => Err(CryptoError::Unsupported("SHA-1-Signaturen".to_string())),
+            HashAlgo::Sha256 => Ok(Pkcs1Hash::Sha256),
+            HashAlgo::Sha384 => Ok(Pkcs1Hash::Sha384),
+            HashAlgo::Sha512 => Ok(Pkcs1Hash::Sha512),
+        }
+    }
+
+    /// Salt-Länge für v6-Signaturen (RFC 9580, 9.5).
+    pub fn salt_len(self) -> usize {
+        match self {
+            HashAlgo::Sha1 => 0,
+            HashAlgo::Sha256 => 16,
+            HashAlgo::Sha384 => 24,
+            HashAlgo::Sha512 => 32,
+        }
+    }
+
+    pub fn hasher(self) -> Hasher {
+        match self {
+            HashAlgo::Sha1 => Hasher::Sha1(Sha1::new()),
+            HashAlgo::Sha256 => Hasher::Sha256(Sha256::new()),
+            HashAlgo::Sha384 => Hasher::Sha384(Sha384::new()),
+            HashAlgo::Sha512 => Hasher::Sha512(Sha512::new()),
+        }
+    }
+}
+
+/// Inkrementeller Hash über eines der unterstützten Verfahren.
+pub enum Hasher {
+    Sha1(Sha1),
+    Sha256(Sha256),
+    Sha384(Sha384),
+    Sha512(Sha512),
+}
+
+impl Hasher {
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha1(h) => h.update(data),
+            Hasher::Sha256(h) => h.update(data),
+            Hasher::Sha384(h) => h.update(data),
+            Hasher::Sha512(h) => h.update(data),
+        }
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        match self {
+            Hasher::Sha1(h) => h.finalize().to_vec(),
+            Hasher::Sha256(h) => h.finalize().to_vec(),
+            Hasher::Sha384(h) => h.finalize().to_vec(),
+            Hasher::Sha512(h) => h.finalize().to_vec(),
+        }
+    }
+}
+
+/// AEAD-Verfahren für SEIPD v2 (RFC 9580, 5.13.2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AeadAlgo {
+    Eax = 1,
+    Ocb = 2,
+    Gcm = 3,
+}
+
+impl AeadAlgo {
+    pub fn from_id(id: u8) -> Result<Self, CryptoError> {
+        match id {
+            1 => Ok(AeadAlgo::Eax),
+            2 => Ok(AeadAlgo::Ocb),
+            3 => Ok(AeadAlgo::Gcm),
+            _ => Err(CryptoError::Unsupported(format!("AEAD-Algorithmus {}", id))),
+        }
+    }
+
+    pub fn nonce_len(self) -> usize {
+        match self {
+            AeadAlgo::Eax => 16,
+            AeadAlgo::Ocb => 15,
+            AeadAlgo::Gcm => 12,
+        }
+    }
+
+    /// Entschlüsselt und prüft einen Block; der Ciphertext enthält das 16-Byte-Tag.
+    pub fn open(
+        self,
+        sym: SymAlgo,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Option<Vec<u8>> {
+        let payload = Payload {
+            msg: ciphertext,
+            aad,
+        };
+        match (self, sym) {
+            (AeadAlgo::Eax, SymAlgo::Aes128) => open_with::<Eax<Aes128>>(key, nonce, payload),
+            (AeadAlgo::Eax, SymAlgo::Aes192) => open_with::<Eax<Aes192>>(key, nonce, payload),
+            (AeadAlgo::Eax, SymAlgo::Aes256) => open_with::<Eax<Aes256>>(key, nonce, payload),
+            (AeadAlgo::Ocb, SymAlgo::Aes128) => {
+                open_with::<Ocb3<Aes128, U15, U16>>(key, nonce, payload)
+            }
+            (AeadAlgo::Ocb, SymAlgo::Aes192) => {
+                open_with::<Ocb3<Aes192, U15, U16>>(key, nonce, payload)
+            }
+            (AeadAlgo::Ocb, SymAlgo::Aes256) => {
+                open_with::<Ocb3<Aes256, U15, U16>>(key, nonce, payload)
+            }
+            (AeadAlgo::Gcm, SymAlgo::Aes128) => {
+                open_with::<AesGcm<Aes128, U12>>(key, nonce, payload)
+            }
+            (AeadAlgo::Gcm, SymAlgo::Aes192) => {
+                open_with::<AesGcm<Aes192, U12>>(key, nonce, payload)
+            }
+            (AeadAlgo::Gcm, SymAlgo::Aes256) => {
+                open_with::<AesGcm<Aes256, U12>>(key, nonce, payload)
+            }
+        }
+    }
+}
+
+fn open_with<C: KeyInit + Aead>(key: &[u8], nonce: &[u8], payload: Payload) -> Option<Vec<u8>> {
+    let cipher = C::new_from_slice(key).ok()?;
+    if nonce.len() != aes_gcm::aead::Nonce::<C>::default().len() {
+        return None;
+    }
+    cipher
+        .decrypt(aes_gcm::aead::Nonce::<C>::from_slice(nonce), payload)
+        .ok()
+}
+
+/// String-to-Key-Spezifikation zum Ableiten eines Schlüssels aus einer Passphrase (RFC 9580, 3.7).
+#[derive(Debug, Clone)]
+pub enum S2k {
+    Simple(HashAlgo),
+    Salted(HashAlgo, [u8; 8]),
+    Iterated(HashAlgo, [u8; 8], usize),
+}
+
+impl S2k {
+    pub fn parse(reader: &mut Reader) -> Result<Self, CryptoError> {
+        let kind = reader.u8()?;
+        match kind {
+            0 => Ok(S2k::Simple(HashAlgo::from_id(reader.u8()?)?)),
+            1 | 3 => {
+                let hash = HashAlgo::from_id(reader.u8()?)?;
+                let salt: [u8; 8] = reader.take(8)?.try_into().unwrap();
+                if kind == 1 {
+                    return Ok(S2k::Salted(hash, salt));
+                }
+                let c = reader.u8()? as usize;
+                let count = (16 + (c & 15)) << ((c >> 4) + 6);
+                Ok(S2k::Iterated(hash, salt, count))
+            }
+            _ => Err(CryptoError::Unsupported(format!("S2K-Typ {}", kind))),
+        }
+    }
+
+    /// Leitet einen Schlüssel der Länge `len` aus der Passphrase ab.
+    pub fn derive(&self, passphrase: &[u8], len: usize) -> Vec<u8> {
+        let (hash, salt, count) = match self {
+            S2k::Simple(hash) => (*hash, &[][..], 0),
+            S2k::Salted(hash, salt) => (*hash, &salt[..], 0),
+            S2k::Iterated(hash, salt, count) => (*hash, &salt[..], *count),
+        };
+        let mut input = salt.to_vec();
+        input.extend_from_slice(passphrase);
+        let total = count.max(input.len());
+
+        let mut key = Vec::with_capacity(len);
+        let mut preload = 0;
+        while key.len() < len {
+            let mut hasher = hash.hasher();
+            hasher.update(&vec![0u8; preload]);
+            let mut remaining = total;
+            while remaining > 0 {
+                let n = remaining.min(input.len());
+                hasher.update(&input[..n]);
+                remaining -= n;
+            }
+            key.extend_from_slice(&hasher.finalize());
+            preload += 1;
+        }
+        key.truncate(len);
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_s2k_iterated_count_and_simple() {
+        // Kodierter Zähler 0x60 entspricht 65536 Byte (RFC 9580, 3.7.1.3)
+        let mut reader = Reader::new(&[3, 8, 1, 2, 3, 4, 5, 6, 7, 8, 0x60]);
+        match S2k::parse(&mut reader).unwrap() {
+            S2k::Iterated(HashAlgo::Sha256, _, count) => assert_eq!(count, 65536),
+            other => panic!("unerwartet: {:?}", other),
+        }
+
+        // Einfaches S2K mit Schlüssel länger als der Hash: zweiter Block mit einem Null-Byte davor
+        let key = S2k::Simple(HashAlgo::Sha256).derive(b"pw", 40);
+        assert_eq!(&key[..32], &Sha256::digest(b"pw")[..]);
+        assert_eq!(&key[32..], &Sha256::digest(b"\0pw")[..8]);
+    }
+}
@@ -0,0 +1,544 @@
+//! Öffentliche und private Schlüsselpakete (RFC 9580, 5.5) für Version 4 und 6.
+//!
+//! Unterstützt RSA, Ed25519 (nativ und als EdDSALegacy) sowie X25519 (nativ und als
+//! ECDH über Curve25519). Andere Kurven werden erkannt, aber nicht verwendet.
+
+use crate::algorithms::constant_time::{self, FixedUint};
+use crate::asymmetric::{ed25519, rsa, x25519};
+use crate::openpgp::crypto::{HashAlgo, Hasher, S2k, SymAlgo};
+use crate::openpgp::packet::{pad_left, write_mpi, Reader};
+use crate::symmetric::aes::decrypt_aes_cfb;
+use crate::utils::error::CryptoError;
+use num_bigint::BigUint;
+use num_traits::One;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+pub const ALGO_RSA: u8 = 1;
+pub const ALGO_ECDH: u8 = 18;
+pub const ALGO_EDDSA_LEGACY: u8 = 22;
+pub const ALGO_X25519: u8 = 25;
+pub const ALGO_ED25519: u8 = 27;
+
+/// OID 1.3.6.1.4.1.11591.15.1 (Ed25519 für EdDSALegacy).
+const OID_ED25519_LEGACY: &[u8] = &[0x2b, 0x06, 0x01, 0x04, 0x01, 0xda, 0x47, 0x0f, 0x01];
+/// OID 1.3.6.1.4.1.3029.1.5.1 (Curve25519 für ECDH).
+pub const OID_CV25519: &[u8] = &[0x2b, 0x06, 0x01, 0x04, 0x01, 0x97, 0x55, 0x01, 0x05, 0x01];
+/// Präfix für native Punkte in EdDSALegacy- und ECDH-MPIs.
+const NATIVE_POINT: u8 = 0x40;
+
+fn invalid(msg: &str) -> CryptoError {
+    CryptoError::InvalidFormat(msg.to_string())
+}
+
+/// Öffentliches Schlüsselmaterial.
+#[derive(Debug, Clone)]
+pub enum PublicParams {
+    Rsa(rsa::PublicKey),
+    EdDsaLegacy(ed25519::PublicKey),
+    Ed25519(ed25519::PublicKey),
+    /// ECDH über Curve25519 mit KDF-Parametern (RFC 9580, 11.5).
+    EcdhCv25519 {
+        key: x25519::PublicKey,
+        hash: HashAlgo,
+        sym: SymAlgo,
+    },
+    X25519(x25519::PublicKey),
+    /// Nicht unterstützter Algorithmus oder nicht unterstützte Kurve.
+    Unknown(u8),
+}
+
+impl PublicParams {
+    pub fn algo(&self) -> u8 {
+        match self {
+            PublicParams::Rsa(_) => ALGO_RSA,
+            PublicParams::EdDsaLegacy(_) => ALGO_EDDSA_LEGACY,
+            PublicParams::Ed25519(_) => ALGO_ED25519,
+            PublicParams::EcdhCv25519 { .. } => ALGO_ECDH,
+            PublicParams::X25519(_) => ALGO_X25519,
+            PublicParams::Unknown(algo) => *algo,
+        }
+    }
+
+    pub fn can_sign(&self) -> bool {
+        matches!(
+            self,
+            PublicParams::Rsa(_) | PublicParams::EdDsaLegacy(_) | PublicParams::Ed25519(_)
+        )
+    }
+
+    pub fn can_encrypt(&self) -> bool {
+        matches!(
+            self,
+            PublicParams::Rsa(_) | PublicParams::EcdhCv25519 { .. } | PublicParams::X25519(_)
+        )
+    }
+
+    fn parse(algo: u8, reader: &mut Reader) -> Result<Self, CryptoError> {
+        match algo {
+            ALGO_RSA => {
+                let n = BigUint::from_bytes_be(reader.mpi()?);
+                let e = BigUint::from_bytes_be(reader.mpi()?);
+                Ok(PublicParams::Rsa(rsa::PublicKey { e, n }))
+            }
+            ALGO_EDDSA_LEGACY => {
+                let oid = read_oid(reader)?;
+                let point = reader.mpi()?;
+                if oid != OID_ED25519_LEGACY {
+                    return Ok(PublicParams::Unknown(algo));
+                }
+                Ok(PublicParams::EdDsaLegacy(ed25519::PublicKey {
+                    bytes: native_point(point)?,
+                }))
+            }
+            ALGO_ECDH => {
+                let oid = read_oid(reader)?;
+                let point = reader.mpi()?;
+                let kdf_len = reader.u8()? as usize;
+                let kdf = reader.take(kdf_len)?;
+                if oid != OID_CV25519 {
+                    return Ok(PublicParams::Unknown(algo));
+                }
+                if kdf_len != 3 || kdf[0] != 1 {
+                    return Err(invalid("ungültige ECDH-KDF-Parameter"));
+                }
+                Ok(PublicParams::EcdhCv25519 {
+                    key: x25519::PublicKey {
+                        bytes: native_point(point)?,
+                    },
+                    hash: HashAlgo::from_id(kdf[1])?,
+                    sym: SymAlgo::from_id(kdf[2])?,
+                })
+            }
+            ALGO_X25519 => Ok(PublicParams::X25519(x25519::PublicKey {
+                bytes: reader.take(32)?.try_into().unwrap(),
+            })),
+            ALGO_ED25519 => Ok(PublicParams::Ed25519(ed25519::PublicKey {
+                bytes: reader.take(32)?.try_into().unwrap(),
+            })),
+            _ => Err(CryptoError::Unsupported(format!(
+                "Public-Key-Algorithmus {}",
+                algo
+            ))),
+        }
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            PublicParams::Rsa(key) => {
+                write_mpi(out, &key.n.to_bytes_be());
+                write_mpi(out, &key.e.to_bytes_be());
+            }
+            PublicParams::EdDsaLegacy(key) => {
+                out.push(OID_ED25519_LEGACY.len() as u8);
+                out.extend_from_slice(OID_ED25519_LEGACY);
+                write_native_point(out, &key.bytes);
+            }
+            PublicParams::EcdhCv25519 { key, hash, sym } => {
+                out.push(OID_CV25519.len() as u8);
+                out.extend_from_slice(OID_CV25519);
+                write_native_point(out, &key.bytes);
+                out.extend_from_slice(&[3, 1, *hash as u8, *sym as u8]);
+            }
+            PublicParams::X25519(key) => out.extend_from_slice(&key.bytes),
+            PublicParams::Ed25519(key) => out.extend_from_slice(&key.bytes),
+            PublicParams::Unknown(_) => {
+                unreachable!("unbekannte Schlüssel werden nie serialisiert")
+            }
+        }
+    }
+}
+
+fn read_oid<'a>(reader: &mut Reader<'a>) -> Result<&'a [u8], CryptoError> {
+    let len = reader.u8()? as usize;
+    if len == 0 || len == 0xff {
+        return Err(invalid("ungültige Kurven-OID"));
+    }
+    reader.take(len)
+}
+
+fn native_point(mpi: &[u8]) -> Result<[u8; 32], CryptoError> {
+    match mpi.split_first() {
+        Some((&NATIVE_POINT, point)) if point.len() == 32 => Ok(point.try_into().unwrap()),
+        _ => Err(invalid("ungültiger Curve25519-Punkt")),
+    }
+}
+
+fn write_native_point(out: &mut Vec<u8>, point: &[u8; 32]) {
+    let mut mpi = vec![NATIVE_POINT];
+    mpi.extend_from_slice(point);
+    write_mpi(out, &mpi);
+}
+
+/// Privates Schlüsselmaterial (unverschlüsselt).
+#[derive(Clone)]
+pub enum SecretParams {
+    /// RSA mit den Primfaktoren, die das Format zusätzlich verlangt.
+    Rsa {
+        key: rsa::PrivateKey,
+        p: BigUint,
+        q: BigUint,
+    },
+    /// Ed25519-Seed (für EdDSALegacy und natives Ed25519).
+    Ed25519(ed25519::PrivateKey),
+    /// X25519-Skalar in nativer Bytefolge (für ECDH und natives X25519).
+    X25519(x25519::PrivateKey),
+}
+
+/// Öffentlicher Teil eines (Unter-)Schlüssels.
+#[derive(Debug, Clone)]
+pub struct PublicKeyPacket {
+    pub version: u8,
+    pub created: u32,
+    pub params: PublicParams,
+    body: Vec<u8>,
+}
+
+impl PublicKeyPacket {
+    pub fn new(version: u8, created: u32, params: PublicParams) -> Self {
+        let mut material = Vec::new();
+        params.write(&mut material);
+        let mut body = vec![version];
+        body.extend_from_slice(&created.to_be_bytes());
+        body.push(params.algo());
+        if version == 6 {
+            body.extend_from_slice(&(material.len() as u32).to_be_bytes());
+        }
+        body.extend_from_slice(&material);
+        PublicKeyPacket {
+            version,
+            created,
+            params,
+            body,
+        }
+    }
+
+    /// Liest ein Public-Key- oder Public-Subkey-Paket.
+    pub fn parse(body: &[u8]) -> Result<Self, CryptoError> {
+        let mut reader = Reader::new(body);
+        let key = Self::read(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(invalid("überzählige Daten im Schlüsselpaket"));
+        }
+        Ok(key)
+    }
+
+    /// Liest den öffentlichen Teil; `body` enthält genau die gelesenen Bytes.
+    fn read(reader: &mut Reader) -> Result<Self, CryptoError> {
+        let start = reader.position();
+        let version = reader.u8()?;
+        let created = reader.u32()?;
+        let algo = reader.u8()?;
+        let params = match version {
+            4 => match PublicParams::parse(algo, reader) {
+                Err(CryptoError::Unsupported(_)) => {
+                    reader.rest();
+                    PublicParams::Unknown(algo)
+                }
+                other => other?,
+            },
+            6 => {
+                // v6-Schlüssel dürfen die Legacy-Algorithmen nicht verwenden (RFC 9580, 5.5.5)
+                if algo == ALGO_ECDH || algo == ALGO_EDDSA_LEGACY {
+                    return Err(invalid("Legacy-Algorithmus in v6-Schlüssel"));
+                }
+                let len = reader.u32()? as usize;
+                let material = reader.take(len)?;
+                let mut inner = Reader::new(material);
+                match PublicParams::parse(algo, &mut inner) {
+                    Ok(params) if inner.is_empty() => params,
+                    Ok(_) => return Err(invalid("überzählige Daten im Schlüsselmaterial")),
+                    Err(CryptoError::Unsupported(_)) => PublicParams::Unknown(algo),
+                    Err(e) => return Err(e),
+                }
+            }
+            _ => {
+                return Err(CryptoError::Unsupported(format!(
+                    "Schlüsselversion {}",
+                    version
+                )))
+            }
+        };
+        Ok(PublicKeyPacket {
+            version,
+            created,
+            params,
+            body: reader.slice_from(start).to_vec(),
+        })
+    }
+
+    /// Serialisierter Paketinhalt.
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// v4: SHA-1, v6: SHA-256 über den Schlüssel (RFC 9580, 5.5.4).
+    pub fn fingerprint(&self) -> Vec<u8> {
+        if self.version == 6 {
+            let mut hasher = Sha256::new();
+            hasher.update([0x9b]);
+            hasher.update((self.body.len() as u32).to_be_bytes());
+            hasher.update(&self.body);
+            hasher.finalize().to_vec()
+        } else {
+            let mut hasher = Sha1::new();
+            hasher.update([0x99]);
+            hasher.update((self.body.len() as u16).to_be_bytes());
+            hasher.update(&self.body);
+            hasher.finalize().to_vec()
+        }
+    }
+
+    /// Key-ID: v4 die letzten, v6 die ersten 8 Byte des Fingerabdrucks.
+    pub fn key_id(&self) -> [u8; 8] {
+        let fp = self.fingerprint();
+        let id = if self.version == 6 {
+            &fp[..8]
+        } else {
+            &fp[fp.len() - 8..]
+        };
+        id.try_into().unwrap()
+    }
+
+    /// Fügt den Schlüssel in der für Signaturen vorgeschriebenen Form zum Hash hinzu.
+    pub fn hash_into(&self, hasher: &mut Hasher) {
+        if self.version == 6 {
+            hasher.update(&[0x9b]);
+            hasher.update(&(self.body.len() as u32).to_be_bytes());
+        } else {
+            hasher.update(&[0x99]);
+            hasher.update(&(self.body.len() as u16).to_be_bytes());
+        }
+        hasher.update(&self.body);
+    }
+}
+
+/// Ein (Unter-)Schlüssel, optional mit entsperrtem privaten Teil.
+#[derive(Clone)]
+pub struct Key {
+    pub public: PublicKeyPacket,
+    pub secret: Option<SecretParams>,
+}
+
+impl Key {
+    /// Liest ein Secret-Key- oder Secret-Subkey-Paket.
+    ///
+    /// Geschützte Schlüssel (S2K-Verwendung 254/255) werden mit `passphrase` entsperrt.
+    pub fn parse_secret(body: &[u8], passphrase: Option<&[u8]>) -> Result<Self, CryptoError> {
+        let mut reader = Reader::new(body);
+        let public = PublicKeyPacket::read(&mut reader)?;
+        if let PublicParams::Unknown(algo) = public.params {
+            return Err(CryptoError::Unsupported(format!(
+                "Public-Key-Algorithmus {}",
+                algo
+            )));
+        }
+        let v6 = public.version == 6;
+
+        let usage = reader.u8()?;
+        let material = match usage {
+            0 => {
+                let rest = reader.rest();
+                if v6 {
+                    rest.to_vec()
+                } else {
+                    let (material, checksum) = rest
+                        .split_at_checked(rest.len().saturating_sub(2))
+                        .filter(|(_, c)| c.len() == 2)
+                        .ok_or_else(|| invalid("Prüfsumme fehlt"))?;
+                    if checksum16(material) != u16::from_be_bytes([checksum[0], checksum[1]]) {
+                        return Err(invalid("Prüfsumme des privaten Schlüssels falsch"));
+                    }
+                    material.to_vec()
+                }
+            }
+            254 | 255 => {
+                if v6 {
+                    reader.u8()?; // Länge der folgenden Parameter
+                }
+                let sym = SymAlgo::from_id(reader.u8()?)?;
+                if v6 {
+                    reader.u8()?; // Länge der S2K-Angabe
+                }
+                let s2k = S2k::parse(&mut reader)?;
+                let iv: [u8; 16] = reader.take(16)?.try_into().unwrap();
+                let passphrase = passphrase.ok_or_else(|| {
+                    invalid("privater Schlüssel ist passwortgeschützt (Passphrase fehlt)")
+                })?;
+                let kek = s2k.derive(passphrase, sym.key_len());
+                let plain = decrypt_aes_cfb(&kek, &iv, reader.rest()).unwrap();
+                unprotect(&plain, usage)?
+            }
+            253 => {
+                return Err(CryptoError::Unsupported(
+                    "AEAD-geschützte private Schlüssel".to_string(),
+                ))
+            }
+            _ => {
+                return Err(CryptoError::Unsupported(format!(
+                    "S2K-Verwendung {}",
+                    usage
+                )))
+            }
+        };
+
+        let secret = parse_secret_material(&public.params, &material)?;
+        let key = Key {
+            public,
+            secret: Some(secret),
+        };
+        if !key.secret_matches_public() {
+            return Err(invalid("privater Schlüssel passt nicht zum öffentlichen"));
+        }
+        Ok(key)
+    }
+
+    fn secret_matches_public(&self) -> bool {
+        match (&self.public.params, &self.secret) {
+            (PublicParams::Rsa(pub_key), Some(SecretParams::Rsa { p, q, .. })) => {
+                // Produkt und Vergleich in fester Breite; die Breiten stehen in den MPI-Längen
+                let limbs = [&pub_key.n, p, q]
+                    .iter()
+                    .map(|x| x.iter_u64_digits().len())
+                    .fold(1, usize::max);
+                let product =
+                    FixedUint::from_biguint(p, limbs).mul(&FixedUint::from_biguint(q, limbs));
+                product.eq(&FixedUint::from_biguint(&pub_key.n, 2 * limbs)) != 0
+            }
+            (
+                PublicParams::EdDsaLegacy(pub_key) | PublicParams::Ed25519(pub_key),
+                Some(SecretParams::Ed25519(priv_key)),
+            ) => ed25519::public_key_from_private(priv_key) == *pub_key,
+            (
+                PublicParams::EcdhCv25519 { key: pub_key, .. } | PublicParams::X25519(pub_key),
+                Some(SecretParams::X25519(priv_key)),
+            ) => x25519::public_key_from_private(priv_key) == *pub_key,
+            _ => false,
+        }
+    }
+
+    /// Serialisiert den Schlüssel als ungeschütztes Secret-Key-Paket.
+    pub fn secret_body(&self) -> Option<Vec<u8>> {
+        let secret = self.secret.as_ref()?;
+        let mut material = Vec::new();
+        match secret {
+            SecretParams::Rsa { key, p, q } => {
+                // u = p⁻¹ mod q, mit p < q (RFC 9580, 5.5.5.1); ohne Verzweigung geordnet
+                let limbs = p.iter_u64_digits().len().max(q.iter_u64_digits().len());
+                let mut p = FixedUint::from_biguint(p, limbs);
+                let mut q = FixedUint::from_biguint(q, limbs);
+                let swap = q.lt(&p);
+                FixedUint::conditional_swap(&mut p, &mut q, swap);
+                let (p, q) = (&p.to_biguint(), &q.to_biguint());
+                let u = constant_time::inverse_mod_prime(p, q);
+                if !constant_time::mul_mod(&u, p, q).is_one() {
+                    return None;
+                }
+                write_mpi(&mut material, &key.d.to_bytes_be());
+                write_mpi(&mut material, &p.to_bytes_be());
+                write_mpi(&mut material, &q.to_bytes_be());
+                write_mpi(&mut material, &u.to_bytes_be());
+            }
+            SecretParams::Ed25519(key) => match self.public.params {
+                PublicParams::EdDsaLegacy(_) => write_mpi(&mut material, &key.bytes),
+                _ => material.extend_from_slice(&key.bytes),
+            },
+            SecretParams::X25519(key) => match self.public.params {
+                PublicParams::EcdhCv25519 { .. } => {
+                    let mut be = key.bytes;
+                    be.reverse();
+                    write_mpi(&mut material, &be);
+                }
+                _ => material.extend_from_slice(&key.bytes),
+            },
+        }
+
+        let mut body = self.public.body().to_vec();
+        body.push(0);
+        body.extend_from_slice(&material);
+        if self.public.version != 6 {
+            body.extend_from_slice(&checksum16(&material).to_be_bytes());
+        }
+        Some(body)
+    }
+}
+
+/// Prüft und entfernt die Integritätsangabe eines entschlüsselten Schlüsselmaterials.
+fn unprotect(plain: &[u8], usage: u8) -> Result<Vec<u8>, CryptoError> {
+    let check_len = if usage == 254 { 20 } else { 2 };
+    if plain.len() < check_len {
+        return Err(CryptoError::DecryptionFailed);
+    }
+    let (material, check) = plain.split_at(plain.len() - check_len);
+    let ok = if usage == 254 {
+        Sha1::digest(material)[..] == *check
+    } else {
+        checksum16(material).to_be_bytes() == check
+    };
+    if ok {
+        Ok(material.to_vec())
+    } else {
+        // falsche Passphrase oder beschädigter Schlüssel
+        Err(CryptoError::DecryptionFailed)
+    }
+}
+
+fn parse_secret_material(
+    params: &PublicParams,
+    material: &[u8],
+) -> Result<SecretParams, CryptoError> {
+    let mut reader = Reader::new(material);
+    let secret = match params {
+        PublicParams::Rsa(pub_key) => {
+            let d = BigUint::from_bytes_be(reader.mpi()?);
+            let p = BigUint::from_bytes_be(reader.mpi()?);
+            let q = BigUint::from_bytes_be(reader.mpi()?);
+            reader.mpi()?; // u wird bei Bedarf neu berechnet
+            SecretParams::Rsa {
+                key: rsa::PrivateKey {
+                    d,
+                    n: pub_key.n.clone(),
+                    e: pub_key.e.clone(),
+                },
+                p,
+                q,
+            }
+        }
+        PublicParams::EdDsaLegacy(_) => SecretParams::Ed25519(ed25519::PrivateKey {
+            bytes: pad32(reader.mpi()?)?,
+        }),
+        PublicParams::EcdhCv25519 { .. } => {
+            // Skalar als MPI in umgekehrter (Big-Endian-)Reihenfolge
+            let mut bytes = pad32(reader.mpi()?)?;
+            bytes.reverse();
+            SecretParams::X25519(x25519::PrivateKey { bytes })
+        }
+        PublicParams::Ed25519(_) => SecretParams::Ed25519(ed25519::PrivateKey {
+            bytes: reader.take(32)?.try_into().unwrap(),
+        }),
+        PublicParams::X25519(_) => SecretParams::X25519(x25519::PrivateKey {
+            bytes: reader.take(32)?.try_into().unwrap(),
+        }),
+        PublicParams::Unknown(_) => unreachable!(),
+    };
+    if !reader.is_empty() {
+        return Err(invalid("überzählige Daten im privaten Schlüssel"));
+    }
+    Ok(secret)
+}
+
+fn pad32(value: &[u8]) -> Result<[u8; 32], CryptoError> {
+    pad_left(value, 32)
+        .map(|v| v.try_into().unwrap())
+        .ok_or_else(|| invalid("Schlüssel länger als 32 Byte"))
+}
+
+/// Summe aller Bytes modulo 65536 (RFC 9580, 5.5.3).
+pub fn checksum16(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |acc, &b| acc.wrapping_add(b as u16))
+}
+
+/// Formatiert einen Fingerabdruck als Großbuchstaben-Hex.
+pub fn format_fingerprint(fp: &[u8]) -> String {
+    hex::encode_upper(fp)
+}
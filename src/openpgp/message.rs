@@ -0,0 +1,379 @@
+//! Entschlüsseln von OpenPGP-Nachrichten (RFC 9580, 5.1, 5.13).
+//!
+//! Unterstützt PKESK v3 und v6 für RSA, ECDH (Curve25519) und X25519 sowie
+//! SEIPD v1 (CFB mit MDC) und v2 (AEAD: OCB, GCM, EAX). Nachrichten ohne
+//! Integritätsschutz (SED, Tag 9) werden abgelehnt.
+
+use crate::asymmetric::{rsa, x25519};
+use crate::openpgp::armor;
+use crate::openpgp::cert::Cert;
+use crate::openpgp::crypto::{AeadAlgo, HashAlgo, SymAlgo};
+use crate::openpgp::key::{checksum16, Key, PublicParams, SecretParams, ALGO_ECDH, OID_CV25519};
+use crate::openpgp::packet::{
+    parse_packets, Packet, Reader, TAG_COMPRESSED, TAG_LITERAL, TAG_MARKER, TAG_ONE_PASS_SIGNATURE,
+    TAG_PADDING, TAG_PKESK, TAG_SED, TAG_SEIPD, TAG_SIGNATURE, TAG_SKESK,
+};
+use crate::symmetric::aes::{aes_key_unwrap, decrypt_aes_cfb};
+use crate::utils::error::CryptoError;
+use flate2::read::{DeflateDecoder, ZlibDecoder};
+use hkdf::Hkdf;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+
+/// Maximale Verschachtelungstiefe komprimierter Pakete.
+const MAX_NESTING: usize = 8;
+/// Größte zulässige AEAD-Chunk-Größe (2^22 Byte, RFC 9580, 5.13.2).
+const MAX_CHUNK_SIZE_OCTET: u8 = 16;
+const AEAD_TAG_LEN: usize = 16;
+
+fn invalid(msg: &str) -> CryptoError {
+    CryptoError::InvalidFormat(msg.to_string())
+}
+
+/// Ein Sitzungsschlüssel; bei PKESK v3 samt symmetrischem Algorithmus.
+struct SessionKey {
+    sym: Option<SymAlgo>,
+    key: Vec<u8>,
+}
+
+/// Entschlüsselt eine Nachricht (binär oder mit Armor) mit den privaten Schlüsseln aus `cert`
+/// und gibt den Inhalt des Literal-Data-Pakets zurück.
+pub fn decrypt(data: &[u8], cert: &Cert) -> Result<Vec<u8>, CryptoError> {
+    let packets = parse_packets(&armor::dearmor(data)?)?;
+
+    let mut encrypted = None;
+    let mut pkesks = Vec::new();
+    for packet in &packets {
+        match packet.tag {
+            TAG_PKESK => pkesks.push(&packet.body),
+            TAG_SKESK | TAG_MARKER | TAG_PADDING => {}
+            TAG_SEIPD => {
+                encrypted = Some(packet);
+                break;
+            }
+            TAG_SED => {
+                return Err(CryptoError::Unsupported(
+                    "Verschlüsselung ohne Integritätsschutz (SED)".to_string(),
+                ))
+            }
+            _ => return Err(invalid("unerwartetes Paket in verschlüsselter Nachricht")),
+        }
+    }
+    let encrypted = encrypted.ok_or_else(|| invalid("kein verschlüsseltes Datenpaket"))?;
+
+    let keys: Vec<&Key> = cert
+        .encryption_keys()
+        .into_iter()
+        .filter(|key| key.secret.is_some())
+        .collect();
+    let mut matched = false;
+    for pkesk in pkesks {
+        for key in &keys {
+            let Some(session) = decrypt_pkesk(pkesk, key)? else {
+                continue;
+            };
+            matched = true;
+            if let Ok(plaintext) = decrypt_seipd(&encrypted.body, &session) {
+                return read_literal(&plaintext, 0);
+            }
+        }
+    }
+    if matched {
+        Err(CryptoError::DecryptionFailed)
+    } else {
+        Err(CryptoError::NoMatchingRecipient)
+    }
+}
+
+/// Versucht, den Sitzungsschlüssel aus einem PKESK-Paket mit `key` zu gewinnen.
+///
+/// Ok(None) bedeutet: Paket ist nicht an diesen Schlüssel adressiert oder passt nicht.
+fn decrypt_pkesk(body: &[u8], key: &Key) -> Result<Option<SessionKey>, CryptoError> {
+    let mut reader = Reader::new(body);
+    let version = reader.u8()?;
+    let public = &key.public;
+    let addressed = match version {
+        3 => {
+            let key_id = reader.take(8)?;
+            // Key-ID 0 steht für einen anonymen Empfänger
+            key_id == public.key_id() || key_id == [0u8; 8]
+        }
+        6 => {
+            let len = reader.u8()? as usize;
+            let target = reader.take(len)?;
+            match target.split_first() {
+                None => true,
+                Some((_, fp)) => fp == public.fingerprint(),
+            }
+        }
+        _ => return Ok(None),
+    };
+    let algo = reader.u8()?;
+    if !addressed || algo != public.params.algo() {
+        return Ok(None);
+    }
+
+    let secret = key.secret.as_ref().unwrap();
+    let decoded = match (&public.params, secret) {
+        (PublicParams::Rsa(_), SecretParams::Rsa { key: priv_key, .. }) => {
+            let m = reader.mpi()?;
+            rsa::decrypt_rsa_pkcs1v15(m, priv_key)
+                .and_then(|m| decode_session_key(&m, version == 3))
+        }
+        (PublicParams::EcdhCv25519 { hash, sym, .. }, SecretParams::X25519(priv_key)) => {
+            let point = reader.mpi()?;
+            let len = reader.u8()? as usize;
+            let wrapped = reader.take(len)?;
+            ecdh_unwrap(key, *hash, *sym, priv_key, point, wrapped)
+                .and_then(|m| strip_pkcs5(&m).and_then(|m| decode_session_key(m, version == 3)))
+        }
+        (PublicParams::X25519(pub_key), SecretParams::X25519(priv_key)) => {
+            let ephemeral: [u8; 32] = reader.take(32)?.try_into().unwrap();
+            let len = reader.u8()? as usize;
+            let mut rest = reader.take(len)?;
+            let sym = if version == 3 {
+                let (&sym, wrapped) = rest.split_first().ok_or_else(|| invalid("PKESK leer"))?;
+                rest = wrapped;
+                Some(SymAlgo::from_id(sym)?)
+            } else {
+                None
+            };
+            x25519_unwrap(pub_key, priv_key, &ephemeral, rest).map(|key| SessionKey { sym, key })
+        }
+        _ => None,
+    };
+    Ok(decoded)
+}
+
+/// Zerlegt [Algorithmus] || Schlüssel || Prüfsumme (RFC 9580, 5.1.3).
+fn decode_session_key(m: &[u8], with_algo: bool) -> Option<SessionKey> {
+    let (sym, rest) = if with_algo {
+        let (&id, rest) = m.split_first()?;
+        (Some(SymAlgo::from_id(id).ok()?), rest)
+    } else {
+        (None, m)
+    };
+    let (key, checksum) = rest.split_at_checked(rest.len().checked_sub(2)?)?;
+    if checksum16(key).to_be_bytes() != checksum {
+        return None;
+    }
+    if let Some(sym) = sym {
+        if key.len() != sym.key_len() {
+            return None;
+        }
+    }
+    Some(SessionKey {
+        sym,
+        key: key.to_vec(),
+    })
+}
+
+/// Entfernt das PKCS#5-Padding der ECDH-Schlüsselverpackung.
+fn strip_pkcs5(m: &[u8]) -> Option<&[u8]> {
+    let pad = *m.last()? as usize;
+    if pad == 0 || pad > m.len() || m[m.len() - pad..].iter().any(|&b| b as usize != pad) {
+        return None;
+    }
+    Some(&m[..m.len() - pad])
+}
+
+/// ECDH mit Curve25519 und KDF nach RFC 9580, 11.5.
+fn ecdh_unwrap(
+    key: &Key,
+    hash: HashAlgo,
+    sym: SymAlgo,
+    priv_key: &x25519::PrivateKey,
+    point: &[u8],
+    wrapped: &[u8],
+) -> Option<Vec<u8>> {
+    let (&prefix, ephemeral) = point.split_first()?;
+    if prefix != 0x40 {
+        return None;
+    }
+    let ephemeral = x25519::PublicKey {
+        bytes: ephemeral.try_into().ok()?,
+    };
+    let shared = x25519::diffie_hellman(priv_key, &ephemeral)?;
+
+    let mut param = vec![OID_CV25519.len() as u8];
+    param.extend_from_slice(OID_CV25519);
+    param.extend_from_slice(&[ALGO_ECDH, 3, 1, hash as u8, sym as u8]);
+    param.extend_from_slice(b"Anonymous Sender    ");
+    param.extend_from_slice(&key.public.fingerprint());
+
+    let mut hasher = hash.hasher();
+    hasher.update(&[0, 0, 0, 1]);
+    hasher.update(&shared);
+    hasher.update(&param);
+    let kek = hasher.finalize();
+    aes_key_unwrap(kek.get(..sym.key_len())?, wrapped)
+}
+
+/// X25519-Schlüsselverpackung nach RFC 9580, 5.1.6.
+fn x25519_unwrap(
+    pub_key: &x25519::PublicKey,
+    priv_key: &x25519::PrivateKey,
+    ephemeral: &[u8; 32],
+    wrapped: &[u8],
+) -> Option<Vec<u8>> {
+    let shared = x25519::diffie_hellman(priv_key, &x25519::PublicKey { bytes: *ephemeral })?;
+    let mut ikm = ephemeral.to_vec();
+    ikm.extend_from_slice(&pub_key.bytes);
+    ikm.extend_from_slice(&shared);
+    let mut kek = [0u8; 16];
+    Hkdf::<Sha256>::new(None, &ikm)
+        .expand(b"OpenPGP X25519", &mut kek)
+        .ok()?;
+    aes_key_unwrap(&kek, wrapped)
+}
+
+fn decrypt_seipd(body: &[u8], session: &SessionKey) -> Result<Vec<u8>, CryptoError> {
+    let mut reader = Reader::new(body);
+    match reader.u8()? {
+        1 => {
+            let sym = match session.sym {
+                Some(sym) => sym,
+                None => SymAlgo::from_key_len(session.key.len())?,
+            };
+            decrypt_seipd_v1(sym, &session.key, reader.rest())
+        }
+        2 => decrypt_seipd_v2(&mut reader, session),
+        v => Err(CryptoError::Unsupported(format!("SEIPD-Version {}", v))),
+    }
+}
+
+/// SEIPD v1: CFB mit Null-IV, zufälligem Präfix und abschließendem MDC (SHA-1).
+fn decrypt_seipd_v1(sym: SymAlgo, key: &[u8], ct: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if key.len() != sym.key_len() || ct.len() < 16 + 2 + 22 {
+        return Err(CryptoError::DecryptionFailed);
+    }
+    let plain = decrypt_aes_cfb(key, &[0u8; 16], ct).ok_or(CryptoError::DecryptionFailed)?;
+    let (covered, mdc) = plain.split_at(plain.len() - 20);
+    if !covered.ends_with(&[0xd3, 0x14]) || Sha1::digest(covered)[..] != *mdc {
+        return Err(CryptoError::DecryptionFailed);
+    }
+    Ok(covered[18..covered.len() - 2].to_vec())
+}
+
+/// SEIPD v2: HKDF-abgeleiteter Nachrichtenschlüssel und AEAD-Chunks (RFC 9580, 5.13.2).
+fn decrypt_seipd_v2(reader: &mut Reader, session: &SessionKey) -> Result<Vec<u8>, CryptoError> {
+    let sym = SymAlgo::from_id(reader.u8()?)?;
+    let aead = AeadAlgo::from_id(reader.u8()?)?;
+    let chunk_octet = reader.u8()?;
+    if chunk_octet > MAX_CHUNK_SIZE_OCTET {
+        return Err(invalid("AEAD-Chunk-Größe zu groß"));
+    }
+    let salt = reader.take(32)?;
+    if session.key.len() != sym.key_len() || session.sym.is_some_and(|s| s != sym) {
+        return Err(CryptoError::DecryptionFailed);
+    }
+
+    let info = [0xc0 | TAG_SEIPD, 2, sym as u8, aead as u8, chunk_octet];
+    let iv_len = aead.nonce_len() - 8;
+    let mut okm = vec![0u8; sym.key_len() + iv_len];
+    Hkdf::<Sha256>::new(Some(salt), &session.key)
+        .expand(&info, &mut okm)
+        .map_err(|_| CryptoError::DecryptionFailed)?;
+    let (message_key, iv) = okm.split_at(sym.key_len());
+
+    let data = reader.rest();
+    if data.len() < AEAD_TAG_LEN {
+        return Err(CryptoError::DecryptionFailed);
+    }
+    let (chunks, final_tag) = data.split_at(data.len() - AEAD_TAG_LEN);
+    let chunk_size = 1usize << (chunk_octet + 6);
+    let nonce = |index: u64| {
+        let mut nonce = iv.to_vec();
+        nonce.extend_from_slice(&index.to_be_bytes());
+        nonce
+    };
+
+    let mut plaintext = Vec::with_capacity(chunks.len());
+    let mut index = 0u64;
+    for chunk in chunks.chunks(chunk_size + AEAD_TAG_LEN) {
+        let pt = aead
+            .open(sym, message_key, &nonce(index), &info, chunk)
+            .ok_or(CryptoError::DecryptionFailed)?;
+        plaintext.extend_from_slice(&pt);
+        index += 1;
+    }
+
+    // Abschluss-Tag über die Gesamtlänge verhindert Abschneiden ganzer Chunks
+    let mut final_aad = info.to_vec();
+    final_aad.extend_from_slice(&(plaintext.len() as u64).to_be_bytes());
+    aead.open(sym, message_key, &nonce(index), &final_aad, final_tag)
+        .ok_or(CryptoError::DecryptionFailed)?;
+    Ok(plaintext)
+}
+
+/// Sucht im entschlüsselten Inhalt das Literal-Data-Paket (ggf. in komprimierten Daten).
+fn read_literal(data: &[u8], depth: usize) -> Result<Vec<u8>, CryptoError> {
+    if depth > MAX_NESTING {
+        return Err(invalid("zu tief verschachtelte Pakete"));
+    }
+    let packets = parse_packets(data)?;
+    let mut literal: Option<&Packet> = None;
+    for packet in &packets {
+        match packet.tag {
+            TAG_LITERAL if literal.is_none() => literal = Some(packet),
+            TAG_COMPRESSED if packets.len() == 1 => {
+                return read_literal(&decompress(&packet.body)?, depth + 1)
+            }
+            TAG_ONE_PASS_SIGNATURE | TAG_SIGNATURE | TAG_MARKER | TAG_PADDING => {}
+            _ => return Err(invalid("unerwartetes Paket im Nachrichteninhalt")),
+        }
+    }
+    let literal = literal.ok_or_else(|| invalid("kein Literal-Data-Paket"))?;
+
+    let mut reader = Reader::new(&literal.body);
+    reader.u8()?; // Format (b, t, u, ...)
+    let name_len = reader.u8()? as usize;
+    reader.take(name_len)?;
+    reader.u32()?; // Datum
+    Ok(reader.rest().to_vec())
+}
+
+fn decompress(body: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let (&algo, data) = body
+        .split_first()
+        .ok_or_else(|| invalid("leeres Kompressionspaket"))?;
+    let mut out = Vec::new();
+    let result = match algo {
+        0 => {
+            out.extend_from_slice(data);
+            Ok(0)
+        }
+        1 => DeflateDecoder::new(data).read_to_end(&mut out),
+        2 => ZlibDecoder::new(data).read_to_end(&mut out),
+        _ => {
+            return Err(CryptoError::Unsupported(format!(
+                "Kompressionsverfahren {}",
+                algo
+            )))
+        }
+    };
+    result.map_err(|_| invalid("komprimierte Daten beschädigt"))?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_key_checksum() {
+        let key = [7u8; 16];
+        let mut m = vec![SymAlgo::Aes128 as u8];
+        m.extend_from_slice(&key);
+        m.extend_from_slice(&checksum16(&key).to_be_bytes());
+        let session = decode_session_key(&m, true).unwrap();
+        assert_eq!(session.key, key);
+        assert_eq!(session.sym, Some(SymAlgo::Aes128));
+
+        m[3] ^= 1;
+        assert!(decode_session_key(&m, true).is_none());
+        assert_eq!(strip_pkcs5(&[1, 2, 3, 3, 3]).unwrap(), &[1, 2]);
+        assert!(strip_pkcs5(&[1, 2, 3, 2]).is_none());
+    }
+}
@@ -0,0 +1,18 @@
+//! OpenPGP nach RFC 9580.
+//!
+//! Umfasst Paketrahmen, ASCII-Armor mit CRC-24, Schlüssel- und Signaturpakete (v4 und v6),
+//! das Importieren von Zertifikaten, das Entschlüsseln von Nachrichten (PKESK v3/v6,
+//! SEIPD v1/v2) sowie abgesetzte Signaturen, die mit `gpg --verify` geprüft werden können.
+//! Verschlüsseln von OpenPGP-Nachrichten ist nicht enthalten.
+
+pub mod armor;
+pub mod cert;
+pub mod crypto;
+pub mod key;
+pub mod message;
+pub mod packet;
+pub mod signature;
+
+pub use cert::Cert;
+pub use message::decrypt;
+pub use signature::{sign_detached, verify_detached, Verification};
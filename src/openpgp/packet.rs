@@ -0,0 +1,252 @@
+//! OpenPGP-Paketrahmen (RFC 9580, Abschnitt 4): Header im alten und neuen Format,
+//! Teillängen und MPI-Kodierung.
+
+use crate::utils::error::CryptoError;
+
+pub const TAG_PKESK: u8 = 1;
+pub const TAG_SIGNATURE: u8 = 2;
+pub const TAG_SKESK: u8 = 3;
+pub const TAG_ONE_PASS_SIGNATURE: u8 = 4;
+pub const TAG_SECRET_KEY: u8 = 5;
+pub const TAG_PUBLIC_KEY: u8 = 6;
+pub const TAG_SECRET_SUBKEY: u8 = 7;
+pub const TAG_COMPRESSED: u8 = 8;
+pub const TAG_SED: u8 = 9;
+pub const TAG_MARKER: u8 = 10;
+pub const TAG_LITERAL: u8 = 11;
+pub const TAG_TRUST: u8 = 12;
+pub const TAG_USER_ID: u8 = 13;
+pub const TAG_PUBLIC_SUBKEY: u8 = 14;
+pub const TAG_USER_ATTRIBUTE: u8 = 17;
+pub const TAG_SEIPD: u8 = 18;
+pub const TAG_MDC: u8 = 19;
+pub const TAG_PADDING: u8 = 21;
+
+fn truncated() -> CryptoError {
+    CryptoError::InvalidFormat("OpenPGP-Daten abgeschnitten".to_string())
+}
+
+/// Ein OpenPGP-Paket mit zusammengesetztem Inhalt (Teillängen bereits aufgelöst).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub tag: u8,
+    pub body: Vec<u8>,
+}
+
+/// Zerlegt eine Bytefolge in Pakete.
+pub fn parse_packets(data: &[u8]) -> Result<Vec<Packet>, CryptoError> {
+    let mut reader = Reader::new(data);
+    let mut packets = Vec::new();
+    while !reader.is_empty() {
+        packets.push(read_packet(&mut reader)?);
+    }
+    Ok(packets)
+}
+
+fn read_packet(reader: &mut Reader) -> Result<Packet, CryptoError> {
+    let ctb = reader.u8()?;
+    if ctb & 0x80 == 0 {
+        return Err(CryptoError::InvalidFormat(format!(
+            "ungültiges Paket-Kopfbyte 0x{:02x}",
+            ctb
+        )));
+    }
+
+    if ctb & 0x40 == 0 {
+        // altes Format: Tag in Bits 5..2, Längentyp in Bits 1..0
+        let tag = (ctb >> 2) & 0x0f;
+        let len = match ctb & 0x03 {
+            0 => reader.u8()? as usize,
+            1 => reader.u16()? as usize,
+            2 => reader.u32()? as usize,
+            _ => reader.remaining(),
+        };
+        return Ok(Packet {
+            tag,
+            body: reader.take(len)?.to_vec(),
+        });
+    }
+
+    let tag = ctb & 0x3f;
+    let mut body = Vec::new();
+    loop {
+        let first = reader.u8()?;
+        match first {
+            0..=191 => {
+                body.extend_from_slice(reader.take(first as usize)?);
+                break;
+            }
+            192..=223 => {
+                let len = ((first as usize - 192) << 8) + reader.u8()? as usize + 192;
+                body.extend_from_slice(reader.take(len)?);
+                break;
+            }
+            255 => {
+                let len = reader.u32()? as usize;
+                body.extend_from_slice(reader.take(len)?);
+                break;
+            }
+            _ => {
+                // Teillänge: 2^(first & 0x1f) Byte, danach folgt ein weiterer Längenkopf
+                let len = 1usize << (first & 0x1f);
+                body.extend_from_slice(reader.take(len)?);
+            }
+        }
+    }
+    Ok(Packet { tag, body })
+}
+
+/// Kodiert eine Länge im neuen Format (ohne Teillängen).
+pub fn encode_length(out: &mut Vec<u8>, len: usize) {
+    if len < 192 {
+        out.push(len as u8);
+    } else if len < 8384 {
+        let v = len - 192;
+        out.push((v >> 8) as u8 + 192);
+        out.push(v as u8);
+    } else {
+        out.push(0xff);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+/// Hängt ein Paket im neuen Format an `out` an.
+pub fn write_packet(out: &mut Vec<u8>, tag: u8, body: &[u8]) {
+    out.push(0xc0 | tag);
+    encode_length(out, body.len());
+    out.extend_from_slice(body);
+}
+
+/// Hängt eine MPI an (Bitlänge + Betrag, führende Nullen entfernt).
+pub fn write_mpi(out: &mut Vec<u8>, value: &[u8]) {
+    let start = value.iter().position(|&b| b != 0).unwrap_or(value.len());
+    let value = &value[start..];
+    let bits = match value.first() {
+        Some(&b) => (value.len() - 1) * 8 + (8 - b.leading_zeros() as usize),
+        None => 0,
+    };
+    out.extend_from_slice(&(bits as u16).to_be_bytes());
+    out.extend_from_slice(value);
+}
+
+/// Bringt eine Zahl (Big-Endian) links mit Nullen auf genau `len` Byte.
+pub fn pad_left(value: &[u8], len: usize) -> Option<Vec<u8>> {
+    let start = value.iter().position(|&b| b != 0).unwrap_or(value.len());
+    let value = &value[start..];
+    if value.len() > len {
+        return None;
+    }
+    let mut out = vec![0u8; len - value.len()];
+    out.extend_from_slice(value);
+    Some(out)
+}
+
+/// Lesezeiger über einem Paketinhalt.
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Bereits gelesene Bytes ab Position `start`.
+    pub fn slice_from(&self, start: usize) -> &'a [u8] {
+        &self.data[start..self.pos]
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], CryptoError> {
+        if self.remaining() < len {
+            return Err(truncated());
+        }
+        let out = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(out)
+    }
+
+    pub fn rest(&mut self) -> &'a [u8] {
+        let out = &self.data[self.pos..];
+        self.pos = self.data.len();
+        out
+    }
+
+    pub fn u8(&mut self) -> Result<u8, CryptoError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, CryptoError> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, CryptoError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    /// Liest eine MPI und gibt ihren Betrag (Big-Endian) zurück.
+    pub fn mpi(&mut self) -> Result<&'a [u8], CryptoError> {
+        let bits = self.u16()? as usize;
+        self.take(bits.div_ceil(8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_length_encodings_roundtrip() {
+        for len in [0, 191, 192, 8383, 8384, 100_000] {
+            let body = vec![0x5a; len];
+            let mut out = Vec::new();
+            write_packet(&mut out, TAG_LITERAL, &body);
+            let packets = parse_packets(&out).unwrap();
+            assert_eq!(
+                packets,
+                vec![Packet {
+                    tag: TAG_LITERAL,
+                    body
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn test_partial_and_old_format() {
+        // neues Format mit Teillänge 2^9 = 512 Byte und abschließender Länge 3
+        let mut data = vec![0xc0 | TAG_LITERAL, 0xe9];
+        data.extend_from_slice(&[1u8; 512]);
+        data.extend_from_slice(&[3, 2, 2, 2]);
+        // altes Format, Tag 13 (User-ID), 1-Byte-Länge
+        data.extend_from_slice(&[0x80 | (TAG_USER_ID << 2), 2, b'h', b'i']);
+
+        let packets = parse_packets(&data).unwrap();
+        assert_eq!(packets[0].body.len(), 515);
+        assert_eq!(packets[1].tag, TAG_USER_ID);
+        assert_eq!(packets[1].body, b"hi");
+
+        assert!(parse_packets(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_mpi_encoding() {
+        let mut out = Vec::new();
+        write_mpi(&mut out, &[0x00, 0x01, 0xff]);
+        assert_eq!(out, vec![0x00, 0x09, 0x01, 0xff]);
+        assert_eq!(Reader::new(&out).mpi().unwrap(), &[0x01, 0xff]);
+        assert_eq!(pad_left(&[0, 0, 7], 2).unwrap(), vec![0, 7]);
+    }
+}
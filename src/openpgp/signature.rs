@@ -0,0 +1,472 @@
+//! Signaturpakete der Versionen 4 und 6 (RFC 9580, 5.2) und abgesetzte Signaturen.
+
+use crate::asymmetric::{ed25519, rsa};
+use crate::openpgp::armor;
+use crate::openpgp::cert::Cert;
+use crate::openpgp::crypto::{HashAlgo, Hasher};
+use crate::openpgp::key::{Key, PublicKeyPacket, PublicParams, SecretParams};
+use crate::openpgp::packet::{
+    encode_length, pad_left, parse_packets, write_mpi, write_packet, Reader, TAG_SIGNATURE,
+};
+use crate::utils::error::CryptoError;
+use rand::RngCore;
+
+pub const SIG_BINARY: u8 = 0x00;
+pub const SIG_TEXT: u8 = 0x01;
+pub const SIG_CERT_POSITIVE: u8 = 0x13;
+pub const SIG_SUBKEY_BINDING: u8 = 0x18;
+pub const SIG_PRIMARY_KEY_BINDING: u8 = 0x19;
+pub const SIG_DIRECT_KEY: u8 = 0x1f;
+pub const SIG_KEY_REVOCATION: u8 = 0x20;
+pub const SIG_SUBKEY_REVOCATION: u8 = 0x28;
+
+pub const SUB_CREATION_TIME: u8 = 2;
+pub const SUB_PREFERRED_SYM: u8 = 11;
+pub const SUB_ISSUER_KEY_ID: u8 = 16;
+pub const SUB_PREFERRED_HASH: u8 = 21;
+pub const SUB_PREFERRED_COMPRESSION: u8 = 22;
+pub const SUB_KEY_FLAGS: u8 = 27;
+pub const SUB_FEATURES: u8 = 30;
+pub const SUB_EMBEDDED_SIGNATURE: u8 = 32;
+pub const SUB_ISSUER_FINGERPRINT: u8 = 33;
+
+pub const KEY_FLAG_CERTIFY: u8 = 0x01;
+pub const KEY_FLAG_SIGN: u8 = 0x02;
+pub const KEY_FLAG_ENCRYPT: u8 = 0x04 | 0x08;
+
+/// Hash-Verfahren für neu erzeugte Signaturen.
+const SIGNING_HASH: HashAlgo = HashAlgo::Sha256;
+
+/// Ein Signatur-Unterpaket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subpacket {
+    pub kind: u8,
+    pub critical: bool,
+    pub data: Vec<u8>,
+}
+
+impl Subpacket {
+    pub fn new(kind: u8, data: &[u8]) -> Self {
+        Subpacket {
+            kind,
+            critical: false,
+            data: data.to_vec(),
+        }
+    }
+}
+
+fn parse_subpackets(area: &[u8]) -> Result<Vec<Subpacket>, CryptoError> {
+    let mut reader = Reader::new(area);
+    let mut out = Vec::new();
+    while !reader.is_empty() {
+        let first = reader.u8()? as usize;
+        let len = match first {
+            0..=191 => first,
+            192..=254 => ((first - 192) << 8) + reader.u8()? as usize + 192,
+            _ => reader.u32()? as usize,
+        };
+        let body = reader.take(len)?;
+        let (&kind, data) = body
+            .split_first()
+            .ok_or_else(|| CryptoError::InvalidFormat("leeres Unterpaket".to_string()))?;
+        out.push(Subpacket {
+            kind: kind & 0x7f,
+            critical: kind & 0x80 != 0,
+            data: data.to_vec(),
+        });
+    }
+    Ok(out)
+}
+
+fn write_subpackets(subpackets: &[Subpacket]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for sp in subpackets {
+        encode_length(&mut out, sp.data.len() + 1);
+        out.push(sp.kind | if sp.critical { 0x80 } else { 0 });
+        out.extend_from_slice(&sp.data);
+    }
+    out
+}
+
+/// Signaturwert je nach Public-Key-Algorithmus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureValue {
+    Rsa(Vec<u8>),
+    EdDsaLegacy { r: Vec<u8>, s: Vec<u8> },
+    Ed25519([u8; 64]),
+    Unknown(Vec<u8>),
+}
+
+/// Ein Signaturpaket. Die Unterpaket-Bereiche bleiben roh erhalten, da sie gehasht werden.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub version: u8,
+    pub sig_type: u8,
+    pub pk_algo: u8,
+    pub hash_algo: u8,
+    pub hashed_area: Vec<u8>,
+    pub unhashed_area: Vec<u8>,
+    pub digest_prefix: [u8; 2],
+    pub salt: Vec<u8>,
+    pub value: SignatureValue,
+}
+
+impl Signature {
+    pub fn parse(body: &[u8]) -> Result<Self, CryptoError> {
+        let mut reader = Reader::new(body);
+        let version = reader.u8()?;
+        if version != 4 && version != 6 {
+            return Err(CryptoError::Unsupported(format!(
+                "Signaturversion {}",
+                version
+            )));
+        }
+        let sig_type = reader.u8()?;
+        let pk_algo = reader.u8()?;
+        let hash_algo = reader.u8()?;
+        let area_len = |r: &mut Reader| -> Result<usize, CryptoError> {
+            Ok(if version == 6 {
+                r.u32()? as usize
+            } else {
+                r.u16()? as usize
+            })
+        };
+        let len = area_len(&mut reader)?;
+        let hashed_area = reader.take(len)?.to_vec();
+        let len = area_len(&mut reader)?;
+        let unhashed_area = reader.take(len)?.to_vec();
+        let digest_prefix = reader.take(2)?.try_into().unwrap();
+        let salt = if version == 6 {
+            let len = reader.u8()? as usize;
+            reader.take(len)?.to_vec()
+        } else {
+            Vec::new()
+        };
+
+        let value = match pk_algo {
+            crate::openpgp::key::ALGO_RSA => SignatureValue::Rsa(reader.mpi()?.to_vec()),
+            crate::openpgp::key::ALGO_EDDSA_LEGACY => SignatureValue::EdDsaLegacy {
+                r: reader.mpi()?.to_vec(),
+                s: reader.mpi()?.to_vec(),
+            },
+            crate::openpgp::key::ALGO_ED25519 => {
+                SignatureValue::Ed25519(reader.take(64)?.try_into().unwrap())
+            }
+            _ => SignatureValue::Unknown(reader.rest().to_vec()),
+        };
+        if !reader.is_empty() {
+            return Err(CryptoError::InvalidFormat(
+                "überzählige Daten in der Signatur".to_string(),
+            ));
+        }
+        Ok(Signature {
+            version,
+            sig_type,
+            pk_algo,
+            hash_algo,
+            hashed_area,
+            unhashed_area,
+            digest_prefix,
+            salt,
+            value,
+        })
+    }
+
+    pub fn to_body(&self) -> Vec<u8> {
+        let mut out = vec![self.version, self.sig_type, self.pk_algo, self.hash_algo];
+        for area in [&self.hashed_area, &self.unhashed_area] {
+            if self.version == 6 {
+                out.extend_from_slice(&(area.len() as u32).to_be_bytes());
+            } else {
+                out.extend_from_slice(&(area.len() as u16).to_be_bytes());
+            }
+            out.extend_from_slice(area);
+        }
+        out.extend_from_slice(&self.digest_prefix);
+        if self.version == 6 {
+            out.push(self.salt.len() as u8);
+            out.extend_from_slice(&self.salt);
+        }
+        match &self.value {
+            SignatureValue::Rsa(s) => write_mpi(&mut out, s),
+            SignatureValue::EdDsaLegacy { r, s } => {
+                write_mpi(&mut out, r);
+                write_mpi(&mut out, s);
+            }
+            SignatureValue::Ed25519(sig) => out.extend_from_slice(sig),
+            SignatureValue::Unknown(raw) => out.extend_from_slice(raw),
+        }
+        out
+    }
+
+    /// Unterpakete aus dem gehashten und dem ungehashten Bereich.
+    fn subpackets(&self, hashed_only: bool) -> Vec<Subpacket> {
+        let mut out = parse_subpackets(&self.hashed_area).unwrap_or_default();
+        if !hashed_only {
+            out.extend(parse_subpackets(&self.unhashed_area).unwrap_or_default());
+        }
+        out
+    }
+
+    fn find(&self, kind: u8, hashed_only: bool) -> Option<Vec<u8>> {
+        self.subpackets(hashed_only)
+            .into_iter()
+            .find(|sp| sp.kind == kind)
+            .map(|sp| sp.data)
+    }
+
+    pub fn creation_time(&self) -> Option<u32> {
+        let data = self.find(SUB_CREATION_TIME, true)?;
+        Some(u32::from_be_bytes(data.get(..4)?.try_into().ok()?))
+    }
+
+    pub fn key_flags(&self) -> Option<u8> {
+        self.find(SUB_KEY_FLAGS, true)?.first().copied()
+    }
+
+    /// Eingebettete Signatur, z. B. die Rückbindung eines Signatur-Unterschlüssels.
+    pub fn embedded_signature(&self) -> Option<Signature> {
+        Signature::parse(&self.find(SUB_EMBEDDED_SIGNATURE, false)?).ok()
+    }
+
+    /// Prüft anhand von Issuer-Fingerabdruck oder Key-ID, ob `key` der Aussteller sein kann.
+    pub fn is_issued_by(&self, key: &PublicKeyPacket) -> bool {
+        if let Some(fp) = self.find(SUB_ISSUER_FINGERPRINT, false) {
+            return fp.get(1..) == Some(&key.fingerprint()[..]);
+        }
+        if let Some(id) = self.find(SUB_ISSUER_KEY_ID, false) {
+            return id == key.key_id();
+        }
+        true
+    }
+
+    /// Berechnet den Hash über Salt, signierte Daten und den Signatur-Trailer.
+    fn digest(&self, feed: impl FnOnce(&mut Hasher)) -> Result<Vec<u8>, CryptoError> {
+        let hash = HashAlgo::from_id(self.hash_algo)?;
+        let mut hasher = hash.hasher();
+        if self.version == 6 {
+            hasher.update(&self.salt);
+        }
+        feed(&mut hasher);
+
+        let mut trailer = vec![self.version, self.sig_type, self.pk_algo, self.hash_algo];
+        if self.version == 6 {
+            trailer.extend_from_slice(&(self.hashed_area.len() as u32).to_be_bytes());
+        } else {
+            trailer.extend_from_slice(&(self.hashed_area.len() as u16).to_be_bytes());
+        }
+        trailer.extend_from_slice(&self.hashed_area);
+        let hashed_len = trailer.len() as u32;
+        trailer.extend_from_slice(&[self.version, 0xff]);
+        trailer.extend_from_slice(&hashed_len.to_be_bytes());
+        hasher.update(&trailer);
+        Ok(hasher.finalize())
+    }
+
+    /// Prüft die Signatur mit `key` über die von `feed` gelieferten Daten.
+    pub fn verify(&self, key: &PublicKeyPacket, feed: impl FnOnce(&mut Hasher)) -> bool {
+        if self.version != key.version
+            || self.pk_algo != key.params.algo()
+            || self.creation_time().is_none()
+        {
+            return false;
+        }
+        let Ok(hash) = HashAlgo::from_id(self.hash_algo) else {
+            return false;
+        };
+        if self.version == 6 && self.salt.len() != hash.salt_len() {
+            return false;
+        }
+        let Ok(digest) = self.digest(feed) else {
+            return false;
+        };
+        if digest[..2] != self.digest_prefix {
+            return false;
+        }
+
+        match (&key.params, &self.value) {
+            (PublicParams::Rsa(pub_key), SignatureValue::Rsa(s)) => match hash.pkcs1() {
+                Ok(pkcs1) => rsa::verify_rsa_pkcs1v15(pkcs1, &digest, s, pub_key),
+                Err(_) => false,
+            },
+            (PublicParams::EdDsaLegacy(pub_key), SignatureValue::EdDsaLegacy { r, s }) => {
+                match (pad_left(r, 32), pad_left(s, 32)) {
+                    (Some(mut sig), Some(s)) => {
+                        sig.extend_from_slice(&s);
+                        ed25519::verify_ed25519(&digest, &sig, pub_key)
+                    }
+                    _ => false,
+                }
+            }
+            (PublicParams::Ed25519(pub_key), SignatureValue::Ed25519(sig)) => {
+                ed25519::verify_ed25519(&digest, sig, pub_key)
+            }
+            _ => false,
+        }
+    }
+
+    /// Erzeugt eine Signatur mit `signer` über die von `feed` gelieferten Daten.
+    ///
+    /// Erstellungszeit und Issuer-Angaben werden ergänzt; die Version folgt der Schlüsselversion.
+    pub fn create(
+        signer: &Key,
+        sig_type: u8,
+        mut hashed: Vec<Subpacket>,
+        created: u32,
+        feed: impl FnOnce(&mut Hasher),
+    ) -> Result<Signature, CryptoError> {
+        let public = &signer.public;
+        let secret = signer
+            .secret
+            .as_ref()
+            .ok_or_else(|| CryptoError::InvalidFormat("privater Schlüssel fehlt".to_string()))?;
+
+        let mut issuer_fp = vec![public.version];
+        issuer_fp.extend_from_slice(&public.fingerprint());
+        hashed.insert(0, Subpacket::new(SUB_CREATION_TIME, &created.to_be_bytes()));
+        hashed.push(Subpacket::new(SUB_ISSUER_FINGERPRINT, &issuer_fp));
+        let unhashed = if public.version == 6 {
+            Vec::new()
+        } else {
+            vec![Subpacket::new(SUB_ISSUER_KEY_ID, &public.key_id())]
+        };
+        let salt = if public.version == 6 {
+            let mut salt = vec![0u8; SIGNING_HASH.salt_len()];
+            rand::rngs::OsRng.fill_bytes(&mut salt);
+            salt
+        } else {
+            Vec::new()
+        };
+
+        let mut sig = Signature {
+            version: public.version,
+            sig_type,
+            pk_algo: public.params.algo(),
+            hash_algo: SIGNING_HASH as u8,
+            hashed_area: write_subpackets(&hashed),
+            unhashed_area: write_subpackets(&unhashed),
+            digest_prefix: [0; 2],
+            salt,
+            value: SignatureValue::Unknown(Vec::new()),
+        };
+        let digest = sig.digest(feed)?;
+        sig.digest_prefix = [digest[0], digest[1]];
+        sig.value = match (&public.params, secret) {
+            (PublicParams::Rsa(_), SecretParams::Rsa { key, .. }) => {
+                SignatureValue::Rsa(rsa::sign_rsa_pkcs1v15(SIGNING_HASH.pkcs1()?, &digest, key)?)
+            }
+            (PublicParams::EdDsaLegacy(_), SecretParams::Ed25519(key)) => {
+                let raw = ed25519::sign_ed25519(&digest, key);
+                SignatureValue::EdDsaLegacy {
+                    r: raw[..32].to_vec(),
+                    s: raw[32..].to_vec(),
+                }
+            }
+            (PublicParams::Ed25519(_), SecretParams::Ed25519(key)) => {
+                SignatureValue::Ed25519(ed25519::sign_ed25519(&digest, key))
+            }
+            _ => {
+                return Err(CryptoError::Unsupported(
+                    "Signieren mit diesem Schlüsseltyp".to_string(),
+                ))
+            }
+        };
+        Ok(sig)
+    }
+}
+
+/// Fügt ein Dokument zum Hash hinzu; Textsignaturen verwenden CRLF-Zeilenenden.
+fn hash_document(hasher: &mut Hasher, data: &[u8], sig_type: u8) {
+    if sig_type != SIG_TEXT {
+        hasher.update(data);
+        return;
+    }
+    let mut prev = 0u8;
+    for &b in data {
+        if b == b'\n' && prev != b'\r' {
+            hasher.update(b"\r");
+        }
+        hasher.update(&[b]);
+        prev = b;
+    }
+}
+
+/// Ergebnis einer erfolgreichen Signaturprüfung.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    /// Fingerabdruck des signierenden (Unter-)Schlüssels.
+    pub fingerprint: Vec<u8>,
+    pub created: u32,
+}
+
+/// Erzeugt eine abgesetzte Binärsignatur (Signaturpaket) mit dem Signaturschlüssel von `cert`.
+pub fn sign_detached(data: &[u8], cert: &Cert, created: u32) -> Result<Vec<u8>, CryptoError> {
+    let signer = cert
+        .signing_keys()
+        .into_iter()
+        .find(|key| key.secret.is_some())
+        .ok_or_else(|| {
+            CryptoError::InvalidFormat("kein privater Signaturschlüssel vorhanden".to_string())
+        })?;
+    let sig = Signature::create(signer, SIG_BINARY, Vec::new(), created, |h| h.update(data))?;
+    let mut out = Vec::new();
+    write_packet(&mut out, TAG_SIGNATURE, &sig.to_body());
+    Ok(out)
+}
+
+/// Prüft eine abgesetzte Signatur (binär oder mit Armor) gegen die Signaturschlüssel von `cert`.
+pub fn verify_detached(
+    data: &[u8],
+    signature: &[u8],
+    cert: &Cert,
+) -> Result<Verification, CryptoError> {
+    let packets = parse_packets(&armor::dearmor(signature)?)?;
+    for packet in packets.iter().filter(|p| p.tag == TAG_SIGNATURE) {
+        let Ok(sig) = Signature::parse(&packet.body) else {
+            continue;
+        };
+        if sig.sig_type != SIG_BINARY && sig.sig_type != SIG_TEXT {
+            continue;
+        }
+        for key in cert.signing_keys() {
+            if sig.is_issued_by(&key.public)
+                && sig.verify(&key.public, |h| hash_document(h, data, sig.sig_type))
+            {
+                return Ok(Verification {
+                    fingerprint: key.public.fingerprint(),
+                    created: sig.creation_time().unwrap_or(0),
+                });
+            }
+        }
+    }
+    Err(CryptoError::InvalidSignature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subpacket_roundtrip() {
+        let subpackets = vec![
+            Subpacket::new(SUB_CREATION_TIME, &[0, 0, 1, 0]),
+            Subpacket {
+                kind: SUB_KEY_FLAGS,
+                critical: true,
+                data: vec![KEY_FLAG_SIGN],
+            },
+            Subpacket::new(SUB_EMBEDDED_SIGNATURE, &[7u8; 300]),
+        ];
+        let area = write_subpackets(&subpackets);
+        assert_eq!(parse_subpackets(&area).unwrap(), subpackets);
+    }
+
+    #[test]
+    fn test_text_signature_normalizes_line_endings() {
+        let mut lf = HashAlgo::Sha256.hasher();
+        hash_document(&mut lf, b"a\nb\r\n", SIG_TEXT);
+        let mut crlf = HashAlgo::Sha256.hasher();
+        crlf.update(b"a\r\nb\r\n");
+        assert_eq!(lf.finalize(), crlf.finalize());
+    }
+}
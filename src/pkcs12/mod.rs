@@ -0,0 +1,404 @@
+//! PKCS#12 (RFC 7292): private Schlüssel samt Zertifikatskette in passwortgeschützten
+//! `.p12`/`.pfx`-Dateien, wie sie Browser, Windows und Java-Keystores austauschen.
+//!
+//! Gelesen werden Schlüssel als keyBag oder pkcs8ShroudedKeyBag und X.509-Zertifikate als
+//! certBag, unverschlüsselt oder als EncryptedData mit PBES2 (PBKDF2 + AES-CBC). Der MAC
+//! (HMAC-SHA-1/-2 mit PKCS#12-Schlüsselableitung) wird vor dem Entschlüsseln geprüft.
+//! Geschrieben wird wie bei OpenSSL 3 und Java ab Version 12: Zertifikate verschlüsselt,
+//! Schlüssel als pkcs8ShroudedKeyBag, jeweils PBES2 mit PBKDF2-HMAC-SHA256 und AES-256-CBC,
+//! MAC mit HMAC-SHA256. Legacy-Verfahren (RC2, 3DES) werden nicht unterstützt.
+
+pub mod pbe;
+pub mod pkcs8;
+
+pub use pbe::{HashAlgorithm, Pbes2};
+
+use crate::cms::oid::DATA;
+use crate::utils::der::{self, Reader};
+use crate::utils::error::CryptoError;
+use crate::x509::{Certificate, SigningKey};
+use rand::RngCore;
+use sha1::{Digest, Sha1};
+
+/// Iterationszahl für PBKDF2 und MAC beim Erzeugen.
+pub const DEFAULT_ITERATIONS: u32 = 100_000;
+
+/// Objektkennungen aus RFC 7292 und RFC 2985.
+pub mod oid {
+    pub const ENCRYPTED_DATA: &[u64] = &[1, 2, 840, 113549, 1, 7, 6];
+
+    pub const KEY_BAG: &[u64] = &[1, 2, 840, 113549, 1, 12, 10, 1, 1];
+    pub const PKCS8_SHROUDED_KEY_BAG: &[u64] = &[1, 2, 840, 113549, 1, 12, 10, 1, 2];
+    pub const CERT_BAG: &[u64] = &[1, 2, 840, 113549, 1, 12, 10, 1, 3];
+    pub const SAFE_CONTENTS_BAG: &[u64] = &[1, 2, 840, 113549, 1, 12, 10, 1, 6];
+
+    pub const X509_CERTIFICATE: &[u64] = &[1, 2, 840, 113549, 1, 9, 22, 1];
+    pub const FRIENDLY_NAME: &[u64] = &[1, 2, 840, 113549, 1, 9, 20];
+    pub const LOCAL_KEY_ID: &[u64] = &[1, 2, 840, 113549, 1, 9, 21];
+}
+
+/// Inhalt einer PKCS#12-Datei.
+#[derive(Clone)]
+pub struct Pkcs12 {
+    pub key: Option<SigningKey>,
+    /// Zertifikat zum Schlüssel (über localKeyId oder den öffentlichen Schlüssel zugeordnet).
+    pub certificate: Option<Certificate>,
+    /// Übrige Zertifikate, z. B. Zwischen- und Wurzel-CAs.
+    pub ca_certificates: Vec<Certificate>,
+    /// Anzeigename (friendlyName), bei Java der Alias.
+    pub friendly_name: Option<String>,
+}
+
+/// Die von Cryptomorph ausgewerteten Attribute eines SafeBag.
+#[derive(Default)]
+struct BagAttributes {
+    friendly_name: Option<String>,
+    local_key_id: Option<Vec<u8>>,
+}
+
+impl BagAttributes {
+    fn read(reader: &mut Reader) -> Result<Self, CryptoError> {
+        let mut attributes = BagAttributes::default();
+        let mut set = reader.nested(der::TAG_SET)?;
+        while !set.is_empty() {
+            let mut attribute = set.sequence()?;
+            let attr_oid = attribute.oid()?;
+            let mut values = attribute.nested(der::TAG_SET)?;
+            attribute.finish()?;
+            if attr_oid.is(oid::FRIENDLY_NAME) {
+                attributes.friendly_name = Some(values.string()?);
+            } else if attr_oid.is(oid::LOCAL_KEY_ID) {
+                attributes.local_key_id = Some(values.octet_string()?.to_vec());
+            }
+            // weitere Attribute (z. B. Javas trustedKeyUsage) werden übergangen
+        }
+        Ok(attributes)
+    }
+
+    fn to_der(&self) -> Option<Vec<u8>> {
+        let mut attributes = Vec::new();
+        if let Some(name) = &self.friendly_name {
+            attributes.push(der::sequence(&[
+                der::oid(oid::FRIENDLY_NAME),
+                der::set_of(&[der::bmp_string(name)]),
+            ]));
+        }
+        if let Some(id) = &self.local_key_id {
+            attributes.push(der::sequence(&[
+                der::oid(oid::LOCAL_KEY_ID),
+                der::set_of(&[der::octet_string(id)]),
+            ]));
+        }
+        (!attributes.is_empty()).then(|| der::set_of(&attributes))
+    }
+}
+
+/// Gelesene Schlüssel und Zertifikate vor der Zuordnung.
+#[derive(Default)]
+struct Bags {
+    keys: Vec<(SigningKey, BagAttributes)>,
+    certificates: Vec<(Certificate, BagAttributes)>,
+}
+
+impl Bags {
+    /// Liest SafeContents (`SEQUENCE OF SafeBag`); unbekannte Bag-Typen werden übergangen.
+    fn read(&mut self, data: &[u8], password: &str) -> Result<(), CryptoError> {
+        let mut outer = Reader::new(data);
+        let mut contents = outer.sequence()?;
+        outer.finish()?;
+        while !contents.is_empty() {
+            let mut bag = contents.sequence()?;
+            let bag_id = bag.oid()?;
+            let mut value = bag.nested(der::context(0))?;
+            let attributes = if bag.is_empty() {
+                BagAttributes::default()
+            } else {
+                BagAttributes::read(&mut bag)?
+            };
+            bag.finish()?;
+
+            if bag_id.is(oid::KEY_BAG) {
+                let key = pkcs8::decode(value.read_raw(der::TAG_SEQUENCE)?)?;
+                self.keys.push((key, attributes));
+            } else if bag_id.is(oid::PKCS8_SHROUDED_KEY_BAG) {
+                let key = pkcs8::decrypt(value.read_raw(der::TAG_SEQUENCE)?, password)?;
+                self.keys.push((key, attributes));
+            } else if bag_id.is(oid::CERT_BAG) {
+                let mut cert_bag = value.sequence()?;
+                if cert_bag.oid()?.is(oid::X509_CERTIFICATE) {
+                    let mut cert_value = cert_bag.nested(der::context(0))?;
+                    let cert = Certificate::from_der(cert_value.octet_string()?)?;
+                    cert_value.finish()?;
+                    self.certificates.push((cert, attributes));
+                }
+            } else if bag_id.is(oid::SAFE_CONTENTS_BAG) {
+                self.read(value.read_raw(der::TAG_SEQUENCE)?, password)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Kodiert eine ContentInfo vom Typ `data`.
+fn data_content_info(content: &[u8]) -> Vec<u8> {
+    der::sequence(&[
+        der::oid(DATA),
+        der::explicit(0, &der::octet_string(content)),
+    ])
+}
+
+fn safe_bag(bag_id: &[u64], value: &[u8], attributes: &BagAttributes) -> Vec<u8> {
+    let mut bag = vec![der::oid(bag_id), der::explicit(0, value)];
+    bag.extend(attributes.to_der());
+    der::sequence(&bag)
+}
+
+fn cert_bag(cert: &Certificate, attributes: &BagAttributes) -> Vec<u8> {
+    let value = der::sequence(&[
+        der::oid(oid::X509_CERTIFICATE),
+        der::explicit(0, &der::octet_string(cert.to_der())),
+    ]);
+    safe_bag(oid::CERT_BAG, &value, attributes)
+}
+
+/// Schlüssel für den MAC (RFC 7292, B.2 mit ID 3).
+fn mac_key(digest: HashAlgorithm, password: &str, salt: &[u8], iterations: u32) -> Vec<u8> {
+    digest.pkcs12_kdf(password, salt, 3, iterations, digest.output_len())
+}
+
+impl Pkcs12 {
+    /// Liest eine DER-kodierte PFX-Struktur; ein falsches Passwort ergibt `DecryptionFailed`.
+    ///
+    /// Dateien ohne MAC (`openssl pkcs12 -nomac`) werden ebenfalls angenommen.
+    pub fn from_der(data: &[u8], password: &str) -> Result<Self, CryptoError> {
+        let mut outer = Reader::new(data);
+        let mut pfx = outer.sequence()?;
+        outer.finish()?;
+        if pfx.small_uint()? != 3 {
+            return Err(CryptoError::InvalidFormat(
+                "PKCS#12: Version 3 erwartet".to_string(),
+            ));
+        }
+        let mut auth_safe = pfx.sequence()?;
+        if !auth_safe.oid()?.is(DATA) {
+            return Err(CryptoError::Unsupported(
+                "PKCS#12 mit Public-Key-Integritätsschutz".to_string(),
+            ));
+        }
+        let mut explicit = auth_safe.nested(der::context(0))?;
+        let content = explicit.octet_string()?;
+        explicit.finish()?;
+        auth_safe.finish()?;
+
+        if !pfx.is_empty() {
+            let mut mac_data = pfx.sequence()?;
+            let mut digest_info = mac_data.sequence()?;
+            let digest = HashAlgorithm::read_digest(&mut digest_info)?;
+            let mac = digest_info.octet_string()?;
+            digest_info.finish()?;
+            let salt = mac_data.octet_string()?;
+            let iterations = if mac_data.is_empty() {
+                1
+            } else {
+                mac_data.small_uint()?
+            };
+            mac_data.finish()?;
+            let iterations = u32::try_from(iterations).map_err(|_| {
+                CryptoError::InvalidFormat("PKCS#12: ungültige Iterationszahl".to_string())
+            })?;
+            let key = mac_key(digest, password, salt, iterations);
+            if !digest.verify_hmac(&key, content, mac) {
+                // falsches Passwort oder veränderte Datei
+                return Err(CryptoError::DecryptionFailed);
+            }
+        }
+        pfx.finish()?;
+
+        let mut bags = Bags::default();
+        let mut outer = Reader::new(content);
+        let mut safes = outer.sequence()?;
+        outer.finish()?;
+        while !safes.is_empty() {
+            let mut info = safes.sequence()?;
+            let content_type = info.oid()?;
+            let mut explicit = info.nested(der::context(0))?;
+            info.finish()?;
+            if content_type.is(DATA) {
+                bags.read(explicit.octet_string()?, password)?;
+            } else if content_type.is(oid::ENCRYPTED_DATA) {
+                let mut encrypted_data = explicit.sequence()?;
+                encrypted_data.small_uint()?;
+                let mut encrypted = encrypted_data.sequence()?;
+                if !encrypted.oid()?.is(DATA) {
+                    return Err(CryptoError::InvalidFormat(
+                        "PKCS#12: verschlüsselter Inhalt muss vom Typ data sein".to_string(),
+                    ));
+                }
+                let pbe = Pbes2::read(&mut encrypted)?;
+                let ciphertext = encrypted.read(der::context_primitive(0))?;
+                encrypted.finish()?;
+                bags.read(&pbe.decrypt(password, ciphertext)?, password)?;
+            } else {
+                return Err(CryptoError::Unsupported(format!(
+                    "PKCS#12-Inhaltstyp {}",
+                    content_type
+                )));
+            }
+            explicit.finish()?;
+        }
+        Self::assemble(bags)
+    }
+
+    /// Ordnet dem Schlüssel sein Zertifikat zu: zuerst über localKeyId, sonst über den
+    /// öffentlichen Schlüssel.
+    fn assemble(mut bags: Bags) -> Result<Self, CryptoError> {
+        if bags.keys.len() > 1 {
+            return Err(CryptoError::Unsupported(
+                "PKCS#12 mit mehreren privaten Schlüsseln".to_string(),
+            ));
+        }
+        let Some((key, key_attributes)) = bags.keys.pop() else {
+            return Ok(Pkcs12 {
+                key: None,
+                certificate: None,
+                ca_certificates: bags.certificates.into_iter().map(|(c, _)| c).collect(),
+                friendly_name: None,
+            });
+        };
+        let public_key = key.public_key();
+        let position = key_attributes
+            .local_key_id
+            .as_ref()
+            .and_then(|id| {
+                bags.certificates
+                    .iter()
+                    .position(|(_, a)| a.local_key_id.as_ref() == Some(id))
+            })
+            .or_else(|| {
+                bags.certificates
+                    .iter()
+                    .position(|(c, _)| c.public_key == public_key)
+            });
+        let (certificate, cert_attributes) = match position {
+            Some(i) => {
+                let (cert, attributes) = bags.certificates.remove(i);
+                if cert.public_key != public_key {
+                    return Err(CryptoError::InvalidFormat(
+                        "PKCS#12: Schlüssel passt nicht zum Zertifikat".to_string(),
+                    ));
+                }
+                (Some(cert), attributes)
+            }
+            None => (None, BagAttributes::default()),
+        };
+        Ok(Pkcs12 {
+            key: Some(key),
+            certificate,
+            ca_certificates: bags.certificates.into_iter().map(|(c, _)| c).collect(),
+            friendly_name: key_attributes
+                .friendly_name
+                .or(cert_attributes.friendly_name),
+        })
+    }
+
+    /// Erzeugt eine PFX-Struktur (DER) mit PBES2/AES-256-CBC und HMAC-SHA256.
+    pub fn to_der(&self, password: &str, iterations: u32) -> Result<Vec<u8>, CryptoError> {
+        if let (Some(key), Some(cert)) = (&self.key, &self.certificate) {
+            if key.public_key() != cert.public_key {
+                return Err(CryptoError::InvalidFormat(
+                    "PKCS#12: Schlüssel passt nicht zum Zertifikat".to_string(),
+                ));
+            }
+        }
+        // localKeyId wie bei OpenSSL: SHA-1 des Zertifikats
+        let attributes = BagAttributes {
+            friendly_name: self.friendly_name.clone(),
+            local_key_id: match (&self.certificate, &self.key) {
+                (Some(cert), _) => Some(Sha1::digest(cert.to_der()).to_vec()),
+                (None, Some(key)) => Some(key.public_key().key_id()),
+                (None, None) => None,
+            },
+        };
+
+        let mut safes = Vec::new();
+        let mut cert_bags: Vec<Vec<u8>> = self
+            .certificate
+            .iter()
+            .map(|cert| cert_bag(cert, &attributes))
+            .collect();
+        cert_bags.extend(
+            self.ca_certificates
+                .iter()
+                .map(|cert| cert_bag(cert, &BagAttributes::default())),
+        );
+        if !cert_bags.is_empty() {
+            let pbe = Pbes2::new(iterations);
+            let ciphertext = pbe.encrypt(password, &der::sequence(&cert_bags));
+            let encrypted_data = der::sequence(&[
+                der::small_uint(0),
+                der::sequence(&[
+                    der::oid(DATA),
+                    pbe.to_der(),
+                    der::tlv(der::context_primitive(0), &ciphertext),
+                ]),
+            ]);
+            safes.push(der::sequence(&[
+                der::oid(oid::ENCRYPTED_DATA),
+                der::explicit(0, &encrypted_data),
+            ]));
+        }
+        if let Some(key) = &self.key {
+            let shrouded = pkcs8::encrypt(key, password, iterations)?;
+            let bag = safe_bag(oid::PKCS8_SHROUDED_KEY_BAG, &shrouded, &attributes);
+            safes.push(data_content_info(&der::sequence(&[bag])));
+        }
+        let auth_safe = der::sequence(&safes);
+
+        let digest = HashAlgorithm::Sha256;
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let mac = digest.hmac(&mac_key(digest, password, &salt, iterations), &auth_safe);
+        let mac_data = der::sequence(&[
+            der::sequence(&[digest.digest_to_der(), der::octet_string(&mac)]),
+            der::octet_string(&salt),
+            der::small_uint(iterations as u64),
+        ]);
+        Ok(der::sequence(&[
+            der::small_uint(3),
+            data_content_info(&auth_safe),
+            mac_data,
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asymmetric::ed25519;
+    use crate::x509::{CertificateParams, Name};
+
+    #[test]
+    fn test_pkcs12_roundtrip() {
+        let (_, private) = ed25519::generate_ed25519_keypair();
+        let key = SigningKey::from_ed25519(&private);
+        let params = CertificateParams::new(Name::parse("CN=Alice").unwrap(), 0, 30);
+        let cert = Certificate::self_signed(&params, &key).unwrap();
+        let bundle = Pkcs12 {
+            key: Some(key.clone()),
+            certificate: Some(cert.clone()),
+            ca_certificates: vec![],
+            friendly_name: Some("Schlüssel für Alice".to_string()),
+        };
+        let der = bundle.to_der("geheim", 10).unwrap();
+
+        let parsed = Pkcs12::from_der(&der, "geheim").unwrap();
+        assert_eq!(parsed.key.unwrap().public_key(), key.public_key());
+        assert_eq!(parsed.certificate.unwrap().to_der(), cert.to_der());
+        assert!(parsed.ca_certificates.is_empty());
+        assert_eq!(parsed.friendly_name.as_deref(), Some("Schlüssel für Alice"));
+        assert!(matches!(
+            Pkcs12::from_der(&der, "falsch"),
+            Err(CryptoError::DecryptionFailed)
+        ));
+    }
+}
@@ -0,0 +1,445 @@
+//! Passwortbasierte Verschlüsselung: PBKDF2 und PBES2 mit AES-CBC (RFC 8018) sowie die
+//! Schlüsselableitung aus PKCS#12 (RFC 7292, Anhang B), die nur noch für den MAC dient.
+
+use crate::cms::oid as cms_oid;
+use crate::symmetric::aes;
+use crate::utils::der::{self, Oid, Reader};
+use crate::utils::error::CryptoError;
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+/// Objektkennungen aus RFC 8018 und RFC 7292.
+pub mod oid {
+    pub const PBKDF2: &[u64] = &[1, 2, 840, 113549, 1, 5, 12];
+    pub const PBES2: &[u64] = &[1, 2, 840, 113549, 1, 5, 13];
+    pub const PBMAC1: &[u64] = &[1, 2, 840, 113549, 1, 5, 14];
+
+    pub const HMAC_WITH_SHA1: &[u64] = &[1, 2, 840, 113549, 2, 7];
+    pub const HMAC_WITH_SHA256: &[u64] = &[1, 2, 840, 113549, 2, 9];
+    pub const HMAC_WITH_SHA384: &[u64] = &[1, 2, 840, 113549, 2, 10];
+    pub const HMAC_WITH_SHA512: &[u64] = &[1, 2, 840, 113549, 2, 11];
+
+    /// Präfix der pbeWithSHAAnd…-Verfahren (RC2, RC4, 3DES) aus PKCS#12 v1.
+    pub const PKCS12_PBE: &[u64] = &[1, 2, 840, 113549, 1, 12, 1];
+}
+
+/// Hashverfahren für PBKDF2, den PKCS#12-MAC und dessen Schlüsselableitung.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    const ALL: [HashAlgorithm; 4] = [
+        HashAlgorithm::Sha1,
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Sha384,
+        HashAlgorithm::Sha512,
+    ];
+
+    fn digest_arcs(self) -> &'static [u64] {
+        match self {
+            HashAlgorithm::Sha1 => cms_oid::SHA1,
+            HashAlgorithm::Sha256 => cms_oid::SHA256,
+            HashAlgorithm::Sha384 => cms_oid::SHA384,
+            HashAlgorithm::Sha512 => cms_oid::SHA512,
+        }
+    }
+
+    fn hmac_arcs(self) -> &'static [u64] {
+        match self {
+            HashAlgorithm::Sha1 => oid::HMAC_WITH_SHA1,
+            HashAlgorithm::Sha256 => oid::HMAC_WITH_SHA256,
+            HashAlgorithm::Sha384 => oid::HMAC_WITH_SHA384,
+            HashAlgorithm::Sha512 => oid::HMAC_WITH_SHA512,
+        }
+    }
+
+    /// Liest den AlgorithmIdentifier eines Hashverfahrens (Parameter fehlen oder sind NULL).
+    pub fn read_digest(reader: &mut Reader) -> Result<Self, CryptoError> {
+        let alg_oid = read_algorithm_without_params(reader)?;
+        if alg_oid.is(oid::PBMAC1) {
+            return Err(CryptoError::Unsupported("PBMAC1 (RFC 9579)".to_string()));
+        }
+        Self::ALL
+            .into_iter()
+            .find(|alg| alg_oid.is(alg.digest_arcs()))
+            .ok_or_else(|| CryptoError::Unsupported(format!("Hashverfahren {}", alg_oid)))
+    }
+
+    /// AlgorithmIdentifier des Hashverfahrens mit NULL-Parametern (wie OpenSSL).
+    pub fn digest_to_der(self) -> Vec<u8> {
+        der::sequence(&[der::oid(self.digest_arcs()), der::null()])
+    }
+
+    /// Liest die PRF von PBKDF2 (hmacWithSHA…).
+    fn read_hmac(reader: &mut Reader) -> Result<Self, CryptoError> {
+        let alg_oid = read_algorithm_without_params(reader)?;
+        Self::ALL
+            .into_iter()
+            .find(|alg| alg_oid.is(alg.hmac_arcs()))
+            .ok_or_else(|| CryptoError::Unsupported(format!("PBKDF2-PRF {}", alg_oid)))
+    }
+
+    pub fn output_len(self) -> usize {
+        match self {
+            HashAlgorithm::Sha1 => 20,
+            HashAlgorithm::Sha256 => 32,
+            HashAlgorithm::Sha384 => 48,
+            HashAlgorithm::Sha512 => 64,
+        }
+    }
+
+    pub fn hmac(self, key: &[u8], data: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Sha1 => hmac_with::<Hmac<Sha1>>(key, data),
+            HashAlgorithm::Sha256 => hmac_with::<Hmac<Sha256>>(key, data),
+            HashAlgorithm::Sha384 => hmac_with::<Hmac<Sha384>>(key, data),
+            HashAlgorithm::Sha512 => hmac_with::<Hmac<Sha512>>(key, data),
+        }
+    }
+
+    /// Prüft einen HMAC in konstanter Laufzeit.
+    pub fn verify_hmac(self, key: &[u8], data: &[u8], tag: &[u8]) -> bool {
+        match self {
+            HashAlgorithm::Sha1 => verify_hmac_with::<Hmac<Sha1>>(key, data, tag),
+            HashAlgorithm::Sha256 => verify_hmac_with::<Hmac<Sha256>>(key, data, tag),
+            HashAlgorithm::Sha384 => verify_hmac_with::<Hmac<Sha384>>(key, data, tag),
+            HashAlgorithm::Sha512 => verify_hmac_with::<Hmac<Sha512>>(key, data, tag),
+        }
+    }
+
+    /// PBKDF2 mit HMAC als PRF (RFC 8018, 5.2).
+    pub fn pbkdf2(self, password: &[u8], salt: &[u8], iterations: u32, len: usize) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Sha1 => pbkdf2_with::<Hmac<Sha1>>(password, salt, iterations, len),
+            HashAlgorithm::Sha256 => pbkdf2_with::<Hmac<Sha256>>(password, salt, iterations, len),
+            HashAlgorithm::Sha384 => pbkdf2_with::<Hmac<Sha384>>(password, salt, iterations, len),
+            HashAlgorithm::Sha512 => pbkdf2_with::<Hmac<Sha512>>(password, salt, iterations, len),
+        }
+    }
+
+    /// Schlüsselableitung nach RFC 7292, Anhang B.2; `id` 1 = Schlüssel, 2 = IV, 3 = MAC.
+    ///
+    /// Das Passwort geht als nullterminierter BMPString ein.
+    pub fn pkcs12_kdf(
+        self,
+        password: &str,
+        salt: &[u8],
+        id: u8,
+        iterations: u32,
+        len: usize,
+    ) -> Vec<u8> {
+        let password: Vec<u8> = password
+            .encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_be_bytes)
+            .collect();
+        match self {
+            HashAlgorithm::Sha1 => {
+                pkcs12_kdf_with::<Sha1>(64, &password, salt, id, iterations, len)
+            }
+            HashAlgorithm::Sha256 => {
+                pkcs12_kdf_with::<Sha256>(64, &password, salt, id, iterations, len)
+            }
+            HashAlgorithm::Sha384 => {
+                pkcs12_kdf_with::<Sha384>(128, &password, salt, id, iterations, len)
+            }
+            HashAlgorithm::Sha512 => {
+                pkcs12_kdf_with::<Sha512>(128, &password, salt, id, iterations, len)
+            }
+        }
+    }
+}
+
+/// Liest einen AlgorithmIdentifier, dessen Parameter fehlen oder NULL sind.
+fn read_algorithm_without_params(reader: &mut Reader) -> Result<Oid, CryptoError> {
+    let mut alg = reader.sequence()?;
+    let alg_oid = alg.oid()?;
+    if !alg.is_empty() {
+        alg.null()?;
+    }
+    alg.finish()?;
+    Ok(alg_oid)
+}
+
+fn hmac_with<M: Mac + KeyInit>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mac = <M as KeyInit>::new_from_slice(key).expect("HMAC akzeptiert jede Schlüssellänge");
+    mac.chain_update(data).finalize().into_bytes().to_vec()
+}
+
+fn verify_hmac_with<M: Mac + KeyInit>(key: &[u8], data: &[u8], tag: &[u8]) -> bool {
+    let mac = <M as KeyInit>::new_from_slice(key).expect("HMAC akzeptiert jede Schlüssellänge");
+    mac.chain_update(data).verify_slice(tag).is_ok()
+}
+
+fn pbkdf2_with<M: Mac + KeyInit + Clone>(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    len: usize,
+) -> Vec<u8> {
+    let prf =
+        <M as KeyInit>::new_from_slice(password).expect("HMAC akzeptiert jede Schlüssellänge");
+    let mut out = Vec::with_capacity(len);
+    let mut index = 1u32;
+    while out.len() < len {
+        let mut u = prf
+            .clone()
+            .chain_update(salt)
+            .chain_update(index.to_be_bytes())
+            .finalize()
+            .into_bytes();
+        let mut block = u.clone();
+        for _ in 1..iterations {
+            u = prf.clone().chain_update(&u).finalize().into_bytes();
+            block.iter_mut().zip(&u).for_each(|(b, x)| *b ^= x);
+        }
+        out.extend_from_slice(&block);
+        index += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+fn pkcs12_kdf_with<D: Digest>(
+    block_len: usize,
+    password: &[u8],
+    salt: &[u8],
+    id: u8,
+    iterations: u32,
+    len: usize,
+) -> Vec<u8> {
+    // S und P auf ein Vielfaches der Blocklänge v wiederholen
+    let stretch = |data: &[u8]| -> Vec<u8> {
+        let target = data.len().div_ceil(block_len) * block_len;
+        data.iter().cycle().take(target).copied().collect()
+    };
+    let diversifier = vec![id; block_len];
+    let mut input = [stretch(salt), stretch(password)].concat();
+    let mut out = Vec::with_capacity(len);
+    loop {
+        let mut a = D::new()
+            .chain_update(&diversifier)
+            .chain_update(&input)
+            .finalize();
+        for _ in 1..iterations {
+            a = D::digest(&a);
+        }
+        out.extend_from_slice(&a);
+        if out.len() >= len {
+            break;
+        }
+        // I_j = (I_j + B + 1) mod 2^(8v)
+        let b: Vec<u8> = a.iter().cycle().take(block_len).copied().collect();
+        for chunk in input.chunks_mut(block_len) {
+            let mut carry = 1u16;
+            for (x, y) in chunk.iter_mut().zip(&b).rev() {
+                let sum = *x as u16 + *y as u16 + carry;
+                *x = sum as u8;
+                carry = sum >> 8;
+            }
+        }
+    }
+    out.truncate(len);
+    out
+}
+
+/// PBES2-Parameter mit PBKDF2 und AES-CBC (RFC 8018, A.2 und A.4).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pbes2 {
+    pub salt: Vec<u8>,
+    pub iterations: u32,
+    pub prf: HashAlgorithm,
+    /// AES-Schlüssellänge in Byte (16, 24 oder 32).
+    pub key_len: usize,
+    pub iv: [u8; 16],
+}
+
+impl Pbes2 {
+    /// PBKDF2-HMAC-SHA256 und AES-256-CBC mit zufälligem Salt und IV (wie OpenSSL 3).
+    pub fn new(iterations: u32) -> Self {
+        let mut salt = vec![0u8; 16];
+        let mut iv = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut iv);
+        Pbes2 {
+            salt,
+            iterations,
+            prf: HashAlgorithm::Sha256,
+            key_len: 32,
+            iv,
+        }
+    }
+
+    /// Liest den AlgorithmIdentifier eines passwortverschlüsselten Inhalts.
+    pub fn read(reader: &mut Reader) -> Result<Self, CryptoError> {
+        let mut alg = reader.sequence()?;
+        let alg_oid = alg.oid()?;
+        if alg_oid.arcs().starts_with(oid::PKCS12_PBE) {
+            return Err(CryptoError::Unsupported(format!(
+                "Legacy-Verschlüsselung {} (RC2/3DES, z. B. `openssl pkcs12 -legacy`)",
+                alg_oid
+            )));
+        }
+        if !alg_oid.is(oid::PBES2) {
+            return Err(CryptoError::Unsupported(format!(
+                "Verschlüsselungsverfahren {}",
+                alg_oid
+            )));
+        }
+        let mut params = alg.sequence()?;
+        alg.finish()?;
+
+        let mut kdf = params.sequence()?;
+        let kdf_oid = kdf.oid()?;
+        if !kdf_oid.is(oid::PBKDF2) {
+            return Err(CryptoError::Unsupported(format!(
+                "Schlüsselableitung {}",
+                kdf_oid
+            )));
+        }
+        let mut kdf_params = kdf.sequence()?;
+        kdf.finish()?;
+        let salt = kdf_params.octet_string()?.to_vec();
+        let iterations = kdf_params.small_uint()?;
+        let key_length = match kdf_params.peek_tag() {
+            Some(der::TAG_INTEGER) => Some(kdf_params.small_uint()?),
+            _ => None,
+        };
+        let prf = if kdf_params.is_empty() {
+            HashAlgorithm::Sha1
+        } else {
+            HashAlgorithm::read_hmac(&mut kdf_params)?
+        };
+        kdf_params.finish()?;
+
+        let mut enc = params.sequence()?;
+        params.finish()?;
+        let enc_oid = enc.oid()?;
+        let key_len = [
+            (cms_oid::AES128_CBC, 16),
+            (cms_oid::AES192_CBC, 24),
+            (cms_oid::AES256_CBC, 32),
+        ]
+        .into_iter()
+        .find(|(arcs, _)| enc_oid.is(arcs))
+        .map(|(_, len)| len)
+        .ok_or_else(|| {
+            CryptoError::Unsupported(format!("Verschlüsselungsverfahren {}", enc_oid))
+        })?;
+        let iv = enc.octet_string()?.try_into().map_err(|_| {
+            CryptoError::InvalidFormat("PBES2: IV muss 16 Byte lang sein".to_string())
+        })?;
+        enc.finish()?;
+
+        if iterations == 0 || iterations > u32::MAX as u64 {
+            return Err(CryptoError::InvalidFormat(
+                "PBKDF2: ungültige Iterationszahl".to_string(),
+            ));
+        }
+        if key_length.is_some_and(|len| len != key_len as u64) {
+            return Err(CryptoError::InvalidFormat(
+                "PBKDF2: Schlüssellänge passt nicht zum Verfahren".to_string(),
+            ));
+        }
+        Ok(Pbes2 {
+            salt,
+            iterations: iterations as u32,
+            prf,
+            key_len,
+            iv,
+        })
+    }
+
+    /// AlgorithmIdentifier `pkcs5PBES2` samt Parametern.
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut kdf_params = vec![
+            der::octet_string(&self.salt),
+            der::small_uint(self.iterations as u64),
+        ];
+        // hmacWithSHA1 ist der Standardwert und entfällt in DER
+        if self.prf != HashAlgorithm::Sha1 {
+            kdf_params.push(der::sequence(&[
+                der::oid(self.prf.hmac_arcs()),
+                der::null(),
+            ]));
+        }
+        let cipher = match self.key_len {
+            16 => cms_oid::AES128_CBC,
+            24 => cms_oid::AES192_CBC,
+            _ => cms_oid::AES256_CBC,
+        };
+        der::sequence(&[
+            der::oid(oid::PBES2),
+            der::sequence(&[
+                der::sequence(&[der::oid(oid::PBKDF2), der::sequence(&kdf_params)]),
+                der::sequence(&[der::oid(cipher), der::octet_string(&self.iv)]),
+            ]),
+        ])
+    }
+
+    /// Das Passwort geht als UTF-8 in PBKDF2 ein (wie bei OpenSSL und Java).
+    fn key(&self, password: &str) -> Vec<u8> {
+        self.prf.pbkdf2(
+            password.as_bytes(),
+            &self.salt,
+            self.iterations,
+            self.key_len,
+        )
+    }
+
+    pub fn encrypt(&self, password: &str, plaintext: &[u8]) -> Vec<u8> {
+        aes::encrypt_aes_cbc(&self.key(password), &self.iv, plaintext)
+            .expect("gültige AES-Schlüssellänge")
+    }
+
+    /// Entschlüsselt; falsches Passwort oder beschädigte Daten ergeben `DecryptionFailed`.
+    pub fn decrypt(&self, password: &str, ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        aes::decrypt_aes_cbc(&self.key(password), &self.iv, ciphertext)
+            .ok_or(CryptoError::DecryptionFailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pbkdf2_vectors() {
+        // RFC 6070, Fall 2
+        let dk = HashAlgorithm::Sha1.pbkdf2(b"password", b"salt", 2, 20);
+        assert_eq!(hex::encode(dk), "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957");
+        // PBKDF2-HMAC-SHA256, c = 1 (RFC 7914, 11)
+        let dk = HashAlgorithm::Sha256.pbkdf2(b"passwd", b"salt", 1, 64);
+        assert_eq!(
+            hex::encode(dk),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc\
+             49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783"
+        );
+    }
+
+    #[test]
+    fn test_pbes2_roundtrip() {
+        let pbe = Pbes2::new(10);
+        let der = pbe.to_der();
+        let parsed = Pbes2::read(&mut Reader::new(&der)).unwrap();
+        assert_eq!(parsed, pbe);
+
+        let ciphertext = pbe.encrypt("geheim", b"Schluesselmaterial");
+        assert_eq!(
+            parsed.decrypt("geheim", &ciphertext).unwrap(),
+            b"Schluesselmaterial"
+        );
+        assert_ne!(
+            parsed.decrypt("falsch", &ciphertext),
+            Ok(b"Schluesselmaterial".to_vec())
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Private Schlüssel als PKCS#8 PrivateKeyInfo (RFC 5208, RFC 5958), unverschlüsselt oder
+//! als EncryptedPrivateKeyInfo mit PBES2.
+//!
+//! RSA-Schlüssel bestehen in Cryptomorph nur aus (n, e, d); beim Kodieren werden p, q und
+//! die CRT-Parameter rekonstruiert, beim Lesen wird nur (n, e, d) übernommen.
+
+use crate::asymmetric::{ecdsa, ed25519, rsa};
+use crate::pkcs12::pbe::Pbes2;
+use crate::utils::der::{self, Reader};
+use crate::utils::error::CryptoError;
+use crate::x509::{oid, SigningKey};
+use num_bigint::BigUint;
+
+fn invalid(msg: &str) -> CryptoError {
+    CryptoError::InvalidFormat(format!("PKCS#8: {}", msg))
+}
+
+/// Liest eine DER-kodierte PrivateKeyInfo (RSA, Ed25519 oder ECDSA P-256).
+pub fn decode(data: &[u8]) -> Result<SigningKey, CryptoError> {
+    let mut outer = Reader::new(data);
+    let mut info = outer.sequence()?;
+    outer.finish()?;
+    if info.small_uint()? > 1 {
+        return Err(invalid("unbekannte Version"));
+    }
+    let mut alg = info.sequence()?;
+    let alg_oid = alg.oid()?;
+    let private = info.octet_string()?;
+    // attributes [0] und publicKey [1] (RFC 5958) werden nicht benötigt
+    info.optional(der::context(0))?;
+    info.optional(der::context_primitive(1))?;
+    info.finish()?;
+
+    if alg_oid.is(oid::RSA_ENCRYPTION) {
+        if !alg.is_empty() {
+            alg.null()?;
+        }
+        alg.finish()?;
+        let mut outer = Reader::new(private);
+        let mut key = outer.sequence()?;
+        outer.finish()?;
+        if key.small_uint()? != 0 {
+            return Err(CryptoError::Unsupported(
+                "RSA-Schlüssel mit mehr als zwei Primfaktoren".to_string(),
+            ));
+        }
+        let n = key.uint()?;
+        let e = key.uint()?;
+        let d = key.uint()?;
+        if n.bits() < 512 || e < BigUint::from(3u8) || d.bits() == 0 {
+            return Err(invalid("ungültiger RSA-Schlüssel"));
+        }
+        Ok(SigningKey::Rsa {
+            public: rsa::PublicKey {
+                n: n.clone(),
+                e: e.clone(),
+            },
+            private: rsa::PrivateKey { n, d, e },
+        })
+    } else if alg_oid.is(oid::ED25519) {
+        alg.finish()?;
+        let mut outer = Reader::new(private);
+        let bytes = outer.octet_string()?;
+        outer.finish()?;
+        let bytes = bytes
+            .try_into()
+            .map_err(|_| invalid("Ed25519-Schlüssel muss 32 Byte lang sein"))?;
+        Ok(SigningKey::from_ed25519(&ed25519::PrivateKey { bytes }))
+    } else if alg_oid.is(oid::EC_PUBLIC_KEY) {
+        let curve = alg.oid()?;
+        alg.finish()?;
+        if !curve.is(oid::PRIME256V1) {
+            return Err(CryptoError::Unsupported(format!(
+                "elliptische Kurve {}",
+                curve
+            )));
+        }
+        // ECPrivateKey (RFC 5915); Parameter und öffentlicher Punkt werden neu berechnet
+        let mut outer = Reader::new(private);
+        let mut key = outer.sequence()?;
+        outer.finish()?;
+        if key.small_uint()? != 1 {
+            return Err(invalid("unbekannte ECPrivateKey-Version"));
+        }
+        let scalar = key.octet_string()?;
+        if scalar.len() > 32 {
+            return Err(invalid("P-256-Skalar zu lang"));
+        }
+        let mut bytes = [0u8; 32];
+        bytes[32 - scalar.len()..].copy_from_slice(scalar);
+        let private = ecdsa::PrivateKey { bytes };
+        let public = ecdsa::public_key_from_private(&private)
+            .ok_or_else(|| invalid("ungültiger P-256-Skalar"))?;
+        Ok(SigningKey::P256 { public, private })
+    } else {
+        Err(CryptoError::Unsupported(format!(
+            "Schlüsselalgorithmus {}",
+            alg_oid
+        )))
+    }
+}
+
+/// Kodiert einen Schlüssel als PrivateKeyInfo (Version 0).
+pub fn encode(key: &SigningKey) -> Result<Vec<u8>, CryptoError> {
+    let (alg, private) = match key {
+        SigningKey::Rsa { public, private } => {
+            let crt = rsa::crt_params(public, private)
+                .ok_or_else(|| invalid("RSA-Primfaktoren lassen sich nicht rekonstruieren"))?;
+            let rsa_key = der::sequence(&[
+                der::small_uint(0),
+                der::uint(&public.n),
+                der::uint(&public.e),
+                der::uint(&private.d),
+                der::uint(&crt.p),
+                der::uint(&crt.q),
+                der::uint(&crt.dp),
+                der::uint(&crt.dq),
+                der::uint(&crt.qinv),
+            ]);
+            (
+                der::sequence(&[der::oid(oid::RSA_ENCRYPTION), der::null()]),
+                rsa_key,
+            )
+        }
+        SigningKey::Ed25519 { private, .. } => (
+            der::sequence(&[der::oid(oid::ED25519)]),
+            der::octet_string(&private.bytes),
+        ),
+        SigningKey::P256 { public, private } => {
+            let point = [&[0x04][..], &public.x, &public.y].concat();
+            (
+                der::sequence(&[der::oid(oid::EC_PUBLIC_KEY), der::oid(oid::PRIME256V1)]),
+                der::sequence(&[
+                    der::small_uint(1),
+                    der::octet_string(&private.bytes),
+                    der::explicit(1, &der::bit_string(&point)),
+                ]),
+            )
+        }
+    };
+    Ok(der::sequence(&[
+        der::small_uint(0),
+        alg,
+        der::octet_string(&private),
+    ]))
+}
+
+/// Entschlüsselt eine EncryptedPrivateKeyInfo; ein falsches Passwort ergibt `DecryptionFailed`.
+pub fn decrypt(data: &[u8], password: &str) -> Result<SigningKey, CryptoError> {
+    let mut outer = Reader::new(data);
+    let mut info = outer.sequence()?;
+    outer.finish()?;
+    let pbe = Pbes2::read(&mut info)?;
+    let encrypted = info.octet_string()?;
+    info.finish()?;
+    let plain = pbe.decrypt(password, encrypted)?;
+    // unpassendes Passwort mit zufällig gültigem Padding
+    decode(&plain).map_err(|_| CryptoError::DecryptionFailed)
+}
+
+/// Verschlüsselt einen Schlüssel als EncryptedPrivateKeyInfo (PBES2, AES-256-CBC).
+pub fn encrypt(key: &SigningKey, password: &str, iterations: u32) -> Result<Vec<u8>, CryptoError> {
+    let pbe = Pbes2::new(iterations);
+    let encrypted = pbe.encrypt(password, &encode(key)?);
+    Ok(der::sequence(&[
+        pbe.to_der(),
+        der::octet_string(&encrypted),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pkcs8_roundtrip() {
+        let (public, private) = rsa::generate_rsa_keypair(1024);
+        let (_, ed_private) = ed25519::generate_ed25519_keypair();
+        let (p256_public, p256_private) = ecdsa::generate_p256_keypair();
+        let keys = [
+            SigningKey::Rsa { public, private },
+            SigningKey::from_ed25519(&ed_private),
+            SigningKey::P256 {
+                public: p256_public,
+                private: p256_private,
+            },
+        ];
+        for key in &keys {
+            let decoded = decode(&encode(key).unwrap()).unwrap();
+            assert_eq!(decoded.public_key(), key.public_key());
+
+            let encrypted = encrypt(key, "geheim", 10).unwrap();
+            let decrypted = decrypt(&encrypted, "geheim").unwrap();
+            assert_eq!(decrypted.sign(b"x").unwrap(), key.sign(b"x").unwrap());
+            assert!(matches!(
+                decrypt(&encrypted, "falsch"),
+                Err(CryptoError::DecryptionFailed)
+            ));
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use ml_kem::kem::{Decapsulate, Encapsulate};
+use ml_kem::{Ciphertext, EncodedSizeUser, KemCore, MlKem768};
+use rand::rngs::OsRng;
+
+type EncapsulationKey = <MlKem768 as KemCore>::EncapsulationKey;
+type DecapsulationKey = <MlKem768 as KemCore>::DecapsulationKey;
+
+/// Länge eines ML-KEM-768-Kapsel-Ciphertexts in Byte.
+pub const CIPHERTEXT_LEN: usize = 1088;
+
+/// ML-KEM-768-Kapselungsschlüssel (1184 Byte).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    pub bytes: Vec<u8>,
+}
+
+/// ML-KEM-768-Entkapselungsschlüssel (2400 Byte).
+#[derive(Clone)]
+pub struct PrivateKey {
+    pub bytes: Vec<u8>,
+}
+
+/// Generiert ein ML-KEM-768-Schlüsselpaar (FIPS 203).
+pub fn generate_mlkem_keypair() -> (PublicKey, PrivateKey) {
+    let (dk, ek) = MlKem768::generate(&mut OsRng);
+    (
+        PublicKey {
+            bytes: ek.as_bytes().to_vec(),
+        },
+        PrivateKey {
+            bytes: dk.as_bytes().to_vec(),
+        },
+    )
+}
+
+/// Liest den im Entkapselungsschlüssel eingebetteten Kapselungsschlüssel aus
+/// (FIPS 203: dk = dk_PKE || ek || H(ek) || z).
+pub fn public_key_from_private(priv_key: &PrivateKey) -> PublicKey {
+    PublicKey {
+        bytes: priv_key.bytes[1152..1152 + 1184].to_vec(),
+    }
+}
+
+/// Kapselt ein zufälliges 32-Byte-Geheimnis für den öffentlichen Schlüssel.
+/// Gibt (Ciphertext, Geheimnis) zurück, oder None bei ungültiger Schlüssellänge.
+pub fn encapsulate(pub_key: &PublicKey) -> Option<(Vec<u8>, [u8; 32])> {
+    let encoded = pub_key.bytes.as_slice().try_into().ok()?;
+    let ek = EncapsulationKey::from_bytes(encoded);
+    let (ct, shared) = ek.encapsulate(&mut OsRng).ok()?;
+    Some((ct.to_vec(), shared.into()))
+}
+
+/// Entkapselt das gemeinsame Geheimnis aus einem Ciphertext.
+/// Gibt None bei ungültigen Längen zurück.
+pub fn decapsulate(ciphertext: &[u8], priv_key: &PrivateKey) -> Option<[u8; 32]> {
+    let encoded = priv_key.bytes.as_slice().try_into().ok()?;
+    let dk = DecapsulationKey::from_bytes(encoded);
+    let ct: &Ciphertext<MlKem768> = ciphertext.try_into().ok()?;
+    let shared = dk.decapsulate(ct).ok()?;
+    Some(shared.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encapsulate_decapsulate() {
+        let (pub_key, priv_key) = generate_mlkem_keypair();
+        assert_eq!(pub_key.bytes.len(), 1184);
+        assert_eq!(priv_key.bytes.len(), 2400);
+        assert_eq!(public_key_from_private(&priv_key), pub_key);
+
+        let (ct, shared) = encapsulate(&pub_key).unwrap();
+        assert_eq!(ct.len(), CIPHERTEXT_LEN);
+        assert_eq!(decapsulate(&ct, &priv_key).unwrap(), shared);
+
+        // implizite Ablehnung: manipulierter Ciphertext liefert anderes Geheimnis
+        let mut tampered = ct.clone();
+        tampered[0] ^= 1;
+        assert_ne!(decapsulate(&tampered, &priv_key).unwrap(), shared);
+    }
+}
@@ -0,0 +1,206 @@
+//! Signierte Textnachrichten ("clearsign"), angelehnt an das Cleartext Signature Framework
+//! von OpenPGP (RFC 9580, Abschnitt 7).
+//!
+//! ```text
+//! -----BEGIN CRYPTOMORPH SIGNED MESSAGE-----
+//! Hash: SHA256
+//!
+//! Text, Zeilen mit führendem '-' als "- -…"
+//! -----BEGIN CRYPTOMORPH SIGNATURE-----
+//! …
+//! -----END CRYPTOMORPH SIGNATURE-----
+//! ```
+//!
+//! Signiert wird der kanonische Text: Leerzeichen und Tabulatoren am Zeilenende entfernt,
+//! Zeilen mit CRLF verbunden, ohne Zeilenumbruch am Ende. So übersteht die Signatur
+//! E-Mail-Programme, die Zeilenenden oder Leerraum am Zeilenende verändern.
+
+use crate::signature::{HashAlgorithm, Signature};
+use crate::utils::armor::{Armor, Label};
+use crate::utils::error::CryptoError;
+use crate::x509::SigningKey;
+
+pub const BEGIN: &str = "-----BEGIN CRYPTOMORPH SIGNED MESSAGE-----";
+const SIGNATURE_BEGIN: &str = "-----BEGIN CRYPTOMORPH SIGNATURE-----";
+
+fn err(msg: &str) -> CryptoError {
+    CryptoError::InvalidFormat(format!("signierte Nachricht: {}", msg))
+}
+
+/// Zerlegt Text in Zeilen; ein abschließender Zeilenumbruch beginnt keine neue Zeile.
+fn split_lines(text: &str) -> Vec<&str> {
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    if text.is_empty() {
+        return Vec::new();
+    }
+    text.split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect()
+}
+
+/// Kanonische Form der Zeilen, über die signiert wird.
+fn canonical(lines: &[&str]) -> Vec<u8> {
+    lines
+        .iter()
+        .map(|line| line.trim_end_matches([' ', '\t']))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        .into_bytes()
+}
+
+/// Prüft, ob `data` (nach führendem Leerraum) eine signierte Textnachricht ist.
+pub fn is_clearsigned(data: &[u8]) -> bool {
+    data.trim_ascii_start().starts_with(BEGIN.as_bytes())
+}
+
+/// Signiert `text` und gibt die signierte Nachricht zurück.
+pub fn sign(
+    text: &str,
+    key: &SigningKey,
+    hash: HashAlgorithm,
+    created: u64,
+    comment: Option<&str>,
+) -> Result<String, CryptoError> {
+    let lines = split_lines(text);
+    let signature = Signature::sign(&canonical(&lines), key, hash, created, comment)?;
+
+    let mut out = format!("{}\nHash: {}\n\n", BEGIN, hash.name());
+    for line in lines {
+        if line.starts_with('-') {
+            out.push_str("- ");
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push_str(&Armor::new(Label::Signature, signature.to_bytes()).encode());
+    Ok(out)
+}
+
+/// Zerlegt eine signierte Nachricht in Text und Signatur, ohne die Signatur zu prüfen.
+///
+/// Der Text wird mit LF-Zeilenenden und abschließendem Zeilenumbruch zurückgegeben; geprüft
+/// wird [`signed_text`] mit [`Signature::verify`]. Text vor oder nach der Nachricht, fehlender
+/// oder widersprüchlicher `Hash`-Header und falsch maskierte Zeilen werden abgelehnt.
+pub fn decode(data: &[u8]) -> Result<(String, Signature), CryptoError> {
+    let text = std::str::from_utf8(data.trim_ascii()).map_err(|_| err("kein gültiger Text"))?;
+    let mut lines = text
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line));
+    if lines.next() != Some(BEGIN) {
+        return Err(err("BEGIN-Zeile fehlt"));
+    }
+
+    let mut hash = None;
+    for line in lines.by_ref() {
+        if line.is_empty() {
+            break;
+        }
+        let name = line
+            .strip_prefix("Hash: ")
+            .ok_or_else(|| err("unbekannter Header"))?;
+        if hash.is_some() {
+            return Err(err("mehrere Hash-Header"));
+        }
+        hash = Some(
+            HashAlgorithm::from_name(name)
+                .ok_or_else(|| CryptoError::Unsupported(format!("Hashfunktion {}", name)))?,
+        );
+    }
+    let hash = hash.ok_or_else(|| err("Hash-Header fehlt"))?;
+
+    let mut body = Vec::new();
+    let mut found = false;
+    for line in lines.by_ref() {
+        if line == SIGNATURE_BEGIN {
+            found = true;
+            break;
+        }
+        match line.strip_prefix('-') {
+            Some(escaped) => body.push(
+                escaped
+                    .strip_prefix(' ')
+                    .ok_or_else(|| err("Zeile mit '-' nicht maskiert"))?,
+            ),
+            None => body.push(line),
+        }
+    }
+    if !found {
+        return Err(err("Signatur fehlt"));
+    }
+    let armor = format!(
+        "{}\n{}",
+        SIGNATURE_BEGIN,
+        lines.collect::<Vec<_>>().join("\n")
+    );
+    let armor = Armor::decode(armor.as_bytes())?;
+    if armor.label != Label::Signature {
+        return Err(err("falscher Signaturblock"));
+    }
+    let signature = Signature::from_bytes(&armor.data)?;
+    if signature.hash != hash {
+        return Err(err("Hash-Header passt nicht zur Signatur"));
+    }
+
+    let text = body.iter().map(|line| format!("{}\n", line)).collect();
+    Ok((text, signature))
+}
+
+/// Kanonische Form eines mit [`decode`] gelesenen Textes, zum Prüfen mit
+/// [`Signature::verify`].
+pub fn signed_text(text: &str) -> Vec<u8> {
+    canonical(&split_lines(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asymmetric::ed25519;
+
+    #[test]
+    fn test_roundtrip_and_canonical_form() {
+        let key = SigningKey::from_ed25519(&ed25519::generate_ed25519_keypair().1);
+        let text = "Hallo Welt  \n-- \nGruß\n\n";
+        let signed = sign(text, &key, HashAlgorithm::Sha512, 42, Some("Notiz")).unwrap();
+        assert!(is_clearsigned(signed.as_bytes()));
+        assert!(signed.starts_with(&format!("{}\nHash: SHA512\n\nHallo Welt  \n- -- \n", BEGIN)));
+
+        let (decoded, signature) = decode(signed.as_bytes()).unwrap();
+        assert_eq!(decoded, text);
+        assert_eq!(signature.comment.as_deref(), Some("Notiz"));
+        assert!(signature
+            .verify(&signed_text(&decoded), &key.public_key())
+            .is_ok());
+
+        // CRLF und Leerraum am Zeilenende ändern die Signatur nicht
+        let mangled = signed.replace('\n', "\r\n").replace("Welt  ", "Welt");
+        let (decoded, signature) = decode(mangled.as_bytes()).unwrap();
+        assert!(signature
+            .verify(&signed_text(&decoded), &key.public_key())
+            .is_ok());
+
+        // geänderter Text fällt auf
+        let forged = signed.replace("Gruß", "Gruss");
+        let (decoded, signature) = decode(forged.as_bytes()).unwrap();
+        assert!(signature
+            .verify(&signed_text(&decoded), &key.public_key())
+            .is_err());
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        let key = SigningKey::from_ed25519(&ed25519::generate_ed25519_keypair().1);
+        let signed = sign("-x\n", &key, HashAlgorithm::Sha256, 1, None).unwrap();
+        assert_eq!(decode(signed.as_bytes()).unwrap().0, "-x\n");
+        let cases = [
+            signed.replace("- -x", "-x"),
+            signed.replace("Hash: SHA256", "Hash: SHA512"),
+            signed.replace("Hash: SHA256\n", "Hash: SHA256\nComment: x\n"),
+            signed.replace("Hash: SHA256\n", ""),
+            signed.replace(SIGNATURE_BEGIN, "-----BEGIN CRYPTOMORPH SEALED FILE-----"),
+            format!("Vorspann\n{}", signed),
+        ];
+        for case in cases {
+            assert!(decode(case.as_bytes()).is_err(), "{}", case);
+        }
+    }
+}
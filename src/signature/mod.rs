@@ -0,0 +1,357 @@
+//! Signaturdateien mit Metadaten (`sign` / `verify`).
+//!
+//! Eine Signatur hält fest, wer (SHA-256-Fingerabdruck der SubjectPublicKeyInfo, wie im
+//! Schlüsselspeicher), womit (Verfahren und Hashfunktion) und wann signiert hat, dazu einen
+//! optionalen Kommentar. Alle Angaben sind mitsigniert; signiert wird der Hashwert der Nachricht.
+//!
+//! ```text
+//! "CMSS" | Version (1 Byte) | Verfahren (1 Byte) | Hashfunktion (1 Byte)
+//! Fingerabdruck (32 Byte) | Erstellzeit (u64, Sekunden seit 1970)
+//! Kommentarlänge (u16) | Kommentar (UTF-8) | Signaturlänge (u16) | Signatur
+//! ```
+//!
+//! Signiert wird `"cryptomorph/v1/signature"` gefolgt von allen Feldern vor der
+//! Signaturlänge und dem Hashwert der Nachricht.
+
+pub mod clearsign;
+
+use crate::asymmetric::ed25519;
+use crate::asymmetric::rsa::{self, sign_rsa_pss, verify_rsa_pss};
+use crate::utils::error::CryptoError;
+use crate::x509::{SigningKey, SubjectPublicKey};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256, Sha512};
+
+pub const MAGIC: &[u8; 4] = b"CMSS";
+pub const VERSION: u8 = 1;
+const CONTEXT: &[u8] = b"cryptomorph/v1/signature";
+
+/// Signaturverfahren.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    /// RSASSA-PSS mit SHA-256, MGF1-SHA-256 und 32 Byte Salt.
+    RsaPss,
+    Ed25519,
+}
+
+impl Scheme {
+    pub fn to_byte(self) -> u8 {
+        match self {
+            Scheme::RsaPss => 1,
+            Scheme::Ed25519 => 2,
+        }
+    }
+
+    pub fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            1 => Some(Scheme::RsaPss),
+            2 => Some(Scheme::Ed25519),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Scheme::RsaPss => "RSASSA-PSS",
+            Scheme::Ed25519 => "Ed25519",
+        }
+    }
+}
+
+/// Hashfunktion über die Nachricht.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    pub fn to_byte(self) -> u8 {
+        match self {
+            HashAlgorithm::Sha256 => 1,
+            HashAlgorithm::Sha512 => 2,
+        }
+    }
+
+    pub fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            1 => Some(HashAlgorithm::Sha256),
+            2 => Some(HashAlgorithm::Sha512),
+            _ => None,
+        }
+    }
+
+    /// Name wie im `Hash`-Header, z. B. `SHA256`.
+    pub fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "SHA256",
+            HashAlgorithm::Sha512 => "SHA512",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [HashAlgorithm::Sha256, HashAlgorithm::Sha512]
+            .into_iter()
+            .find(|hash| hash.name() == name)
+    }
+
+    pub fn digest(self, message: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Sha256 => Sha256::digest(message).to_vec(),
+            HashAlgorithm::Sha512 => Sha512::digest(message).to_vec(),
+        }
+    }
+}
+
+/// Eine Signatur mit ihren Metadaten.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub scheme: Scheme,
+    pub hash: HashAlgorithm,
+    /// Fingerabdruck des Unterzeichners (SHA-256 über die SubjectPublicKeyInfo).
+    pub signer: [u8; 32],
+    /// Erstellzeit in Sekunden seit 1970.
+    pub created: u64,
+    pub comment: Option<String>,
+    pub value: Vec<u8>,
+}
+
+fn err(msg: &str) -> CryptoError {
+    CryptoError::InvalidFormat(format!("Signatur: {}", msg))
+}
+
+/// Prüft, ob `data` mit der Kennung einer Signaturdatei beginnt.
+pub fn is_signature(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+impl Signature {
+    /// Alle Felder vor der Signaturlänge.
+    fn header(&self) -> Vec<u8> {
+        let comment = self.comment.as_deref().unwrap_or("");
+        let mut out = MAGIC.to_vec();
+        out.push(VERSION);
+        out.push(self.scheme.to_byte());
+        out.push(self.hash.to_byte());
+        out.extend_from_slice(&self.signer);
+        out.extend_from_slice(&self.created.to_be_bytes());
+        out.extend_from_slice(&(comment.len() as u16).to_be_bytes());
+        out.extend_from_slice(comment.as_bytes());
+        out
+    }
+
+    /// Signierter Inhalt: Kontext, Metadaten und Hashwert der Nachricht.
+    fn signed_data(&self, message: &[u8]) -> Vec<u8> {
+        let mut out = CONTEXT.to_vec();
+        out.extend_from_slice(&self.header());
+        out.extend_from_slice(&self.hash.digest(message));
+        out
+    }
+
+    /// Signiert `message`; das Verfahren ergibt sich aus dem Schlüssel (RSA → RSASSA-PSS).
+    pub fn sign(
+        message: &[u8],
+        key: &SigningKey,
+        hash: HashAlgorithm,
+        created: u64,
+        comment: Option<&str>,
+    ) -> Result<Self, CryptoError> {
+        if comment.is_some_and(|c| c.len() > u16::MAX as usize) {
+            return Err(err("Kommentar zu lang"));
+        }
+        let scheme = match key {
+            SigningKey::Rsa { .. } => Scheme::RsaPss,
+            SigningKey::Ed25519 { .. } => Scheme::Ed25519,
+            SigningKey::P256 { .. } => {
+                return Err(CryptoError::Unsupported(
+                    "ECDSA-Schlüssel für Signaturdateien".to_string(),
+                ))
+            }
+        };
+        let mut signature = Signature {
+            scheme,
+            hash,
+            signer: key.public_key().fingerprint(),
+            created,
+            comment: comment.map(str::to_string),
+            value: Vec::new(),
+        };
+        let data = signature.signed_data(message);
+        signature.value = match key {
+            SigningKey::Rsa { private, .. } => sign_rsa_pss(&data, private)?,
+            SigningKey::Ed25519 { private, .. } => ed25519::sign_ed25519(&data, private).to_vec(),
+            SigningKey::P256 { .. } => unreachable!(),
+        };
+        Ok(signature)
+    }
+
+    /// Prüft die Signatur über `message` mit dem öffentlichen Schlüssel des Unterzeichners.
+    /// Ein Schlüssel mit anderem Fingerabdruck oder unpassendem Verfahren wird abgelehnt.
+    pub fn verify(&self, message: &[u8], key: &SubjectPublicKey) -> Result<(), CryptoError> {
+        if key.fingerprint() != self.signer {
+            return Err(CryptoError::InvalidSignature);
+        }
+        let data = self.signed_data(message);
+        let valid = match (self.scheme, key) {
+            (Scheme::RsaPss, SubjectPublicKey::Rsa(public)) => {
+                verify_rsa_pss(&data, &self.value, public)
+            }
+            (Scheme::Ed25519, SubjectPublicKey::Ed25519(public)) => {
+                ed25519::verify_ed25519(&data, &self.value, public)
+            }
+            _ => false,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(CryptoError::InvalidSignature)
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.header();
+        out.extend_from_slice(&(self.value.len() as u16).to_be_bytes());
+        out.extend_from_slice(&self.value);
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, CryptoError> {
+        let mut rest = data
+            .strip_prefix(MAGIC)
+            .ok_or_else(|| err("unbekanntes Format"))?;
+        let mut take = |n: usize| -> Result<&[u8], CryptoError> {
+            if rest.len() < n {
+                return Err(err("abgeschnitten"));
+            }
+            let (head, tail) = rest.split_at(n);
+            rest = tail;
+            Ok(head)
+        };
+        let fixed = take(3)?;
+        if fixed[0] != VERSION {
+            return Err(CryptoError::Unsupported(format!(
+                "Signaturversion {}",
+                fixed[0]
+            )));
+        }
+        let scheme = Scheme::from_byte(fixed[1])
+            .ok_or_else(|| CryptoError::Unsupported(format!("Signaturverfahren {}", fixed[1])))?;
+        let hash = HashAlgorithm::from_byte(fixed[2])
+            .ok_or_else(|| CryptoError::Unsupported(format!("Hashfunktion {}", fixed[2])))?;
+        let signer: [u8; 32] = take(32)?.try_into().unwrap();
+        let created = u64::from_be_bytes(take(8)?.try_into().unwrap());
+        let len = u16::from_be_bytes(take(2)?.try_into().unwrap()) as usize;
+        let comment = std::str::from_utf8(take(len)?)
+            .map_err(|_| err("Kommentar ist kein UTF-8"))?
+            .to_string();
+        let len = u16::from_be_bytes(take(2)?.try_into().unwrap()) as usize;
+        let value = take(len)?.to_vec();
+        if !rest.is_empty() {
+            return Err(err("Daten nach der Signatur"));
+        }
+        Ok(Signature {
+            scheme,
+            hash,
+            signer,
+            created,
+            comment: (!comment.is_empty()).then_some(comment),
+            value,
+        })
+    }
+}
+
+/// Prüft eine Signatur im alten Format ohne Metadaten: RSA ohne Padding über SHA-256.
+pub fn verify_legacy_rsa(message: &[u8], signature: &[u8], key: &rsa::PublicKey) -> bool {
+    let s = BigUint::from_bytes_be(signature);
+    s < key.n && rsa::encrypt_rsa(&s, key) == BigUint::from_bytes_be(&Sha256::digest(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asymmetric::rsa::generate_rsa_keypair;
+
+    fn ed25519_key() -> SigningKey {
+        SigningKey::from_ed25519(&ed25519::generate_ed25519_keypair().1)
+    }
+
+    #[test]
+    fn test_sign_verify_roundtrip() {
+        let (public, private) = generate_rsa_keypair(1024);
+        let rsa_key = SigningKey::Rsa { public, private };
+        for key in [rsa_key, ed25519_key()] {
+            for hash in [HashAlgorithm::Sha256, HashAlgorithm::Sha512] {
+                let sig =
+                    Signature::sign(b"Nachricht", &key, hash, 1_700_000_000, Some("Test")).unwrap();
+                let parsed = Signature::from_bytes(&sig.to_bytes()).unwrap();
+                assert_eq!(parsed, sig);
+                assert_eq!(parsed.signer, key.public_key().fingerprint());
+                assert!(parsed.verify(b"Nachricht", &key.public_key()).is_ok());
+                assert_eq!(
+                    parsed.verify(b"Nachricht!", &key.public_key()),
+                    Err(CryptoError::InvalidSignature)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_metadata_is_signed() {
+        let key = ed25519_key();
+        let sig = Signature::sign(b"x", &key, HashAlgorithm::Sha256, 1, None).unwrap();
+        assert_eq!(
+            Signature::from_bytes(&sig.to_bytes()).unwrap().comment,
+            None
+        );
+
+        let changed = [
+            Signature {
+                created: 2,
+                ..sig.clone()
+            },
+            Signature {
+                comment: Some("nachträglich".to_string()),
+                ..sig.clone()
+            },
+            Signature {
+                hash: HashAlgorithm::Sha512,
+                ..sig.clone()
+            },
+        ];
+        for forged in changed {
+            assert!(forged.verify(b"x", &key.public_key()).is_err());
+        }
+
+        // falscher Schlüssel
+        assert!(sig.verify(b"x", &ed25519_key().public_key()).is_err());
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        let key = ed25519_key();
+        let bytes = Signature::sign(b"x", &key, HashAlgorithm::Sha256, 1, Some("k"))
+            .unwrap()
+            .to_bytes();
+        assert!(Signature::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut extra = bytes.clone();
+        extra.push(0);
+        assert!(Signature::from_bytes(&extra).is_err());
+        let mut scheme = bytes.clone();
+        scheme[5] = 9;
+        assert!(matches!(
+            Signature::from_bytes(&scheme),
+            Err(CryptoError::Unsupported(_))
+        ));
+        assert!(!is_signature(&bytes[1..]));
+    }
+
+    #[test]
+    fn test_legacy_rsa() {
+        let (public, private) = generate_rsa_keypair(1024);
+        let hash = BigUint::from_bytes_be(&Sha256::digest(b"alt"));
+        let sig = rsa::decrypt_rsa(&hash, &private).to_bytes_be();
+        assert!(verify_legacy_rsa(b"alt", &sig, &public));
+        assert!(!verify_legacy_rsa(b"neu", &sig, &public));
+    }
+}
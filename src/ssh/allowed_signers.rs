@@ -0,0 +1,292 @@
+//! Dateien mit erlaubten Unterzeichnern im Format von `ssh-keygen -Y verify -f`
+//! (Abschnitt ALLOWED SIGNERS in ssh-keygen(1)).
+//!
+//! Jede Zeile enthält Prinzipale (durch Kommas getrennte Muster mit `*` und `?`),
+//! optionale Optionen (`namespaces="…"`, `valid-after="…"`, `valid-before="…"`,
+//! `cert-authority`), den Schlüsseltyp, den Schlüssel in Base64 und einen Kommentar.
+//! Zeitangaben ohne Zonenangabe werden als UTC gelesen (OpenSSH: lokale Zeit).
+//! Einträge mit `cert-authority` gelten nur für SSH-Zertifikate und passen daher nie.
+
+use crate::ssh::key::PublicKey;
+use crate::ssh::sshsig::Signature;
+use crate::utils::der::days_from_civil;
+use crate::utils::error::CryptoError;
+
+/// Ein Eintrag der Datei.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllowedSigner {
+    pub principals: Vec<String>,
+    /// Erlaubte Namensräume als Muster; `None` erlaubt alle.
+    pub namespaces: Option<Vec<String>>,
+    /// Gültigkeitsbeginn in Sekunden seit 1970.
+    pub valid_after: Option<i64>,
+    /// Gültigkeitsende in Sekunden seit 1970.
+    pub valid_before: Option<i64>,
+    pub cert_authority: bool,
+    pub public_key: PublicKey,
+}
+
+impl AllowedSigner {
+    fn allows(&self, principal: &str, namespace: &str, time: i64) -> bool {
+        !self.cert_authority
+            && match_list(&self.principals, principal)
+            && self
+                .namespaces
+                .as_ref()
+                .is_none_or(|namespaces| match_list(namespaces, namespace))
+            && self.valid_after.is_none_or(|after| time >= after)
+            && self.valid_before.is_none_or(|before| time <= before)
+    }
+}
+
+/// Inhalt einer Datei mit erlaubten Unterzeichnern.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AllowedSigners {
+    pub entries: Vec<AllowedSigner>,
+}
+
+impl AllowedSigners {
+    /// Liest die Datei; Leerzeilen, Kommentare (`#`) und Schlüsseltypen, die Cryptomorph nicht
+    /// kennt (z. B. ECDSA), werden übersprungen.
+    pub fn parse(text: &str) -> Result<Self, CryptoError> {
+        let mut entries = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match parse_line(line) {
+                Ok(entry) => entries.push(entry),
+                Err(CryptoError::Unsupported(_)) => {}
+                Err(e) => {
+                    return Err(CryptoError::InvalidFormat(format!(
+                        "erlaubte Unterzeichner, Zeile {}: {}",
+                        number + 1,
+                        e
+                    )))
+                }
+            }
+        }
+        Ok(AllowedSigners { entries })
+    }
+
+    /// Prüft eine Signatur wie `ssh-keygen -Y verify`: Die Signatur muss gültig sein und
+    /// ihr Schlüssel für `principal` und `namespace` zum Zeitpunkt `time` eingetragen sein.
+    ///
+    /// Gibt den passenden Eintrag zurück.
+    pub fn verify(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+        principal: &str,
+        namespace: &str,
+        time: i64,
+    ) -> Result<&AllowedSigner, CryptoError> {
+        signature.verify(message, namespace)?;
+        self.entries
+            .iter()
+            .find(|entry| {
+                entry.public_key == signature.public_key && entry.allows(principal, namespace, time)
+            })
+            .ok_or(CryptoError::InvalidSignature)
+    }
+
+    /// Prinzipale, unter denen ein Schlüssel eingetragen ist (vgl. `ssh-keygen -Y find-principals`).
+    pub fn find_principals(&self, key: &PublicKey, time: i64) -> Vec<&str> {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                !entry.cert_authority
+                    && entry.public_key == *key
+                    && entry.valid_after.is_none_or(|after| time >= after)
+                    && entry.valid_before.is_none_or(|before| time <= before)
+            })
+            .flat_map(|entry| entry.principals.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+fn invalid(msg: &str) -> CryptoError {
+    CryptoError::InvalidFormat(msg.to_string())
+}
+
+fn parse_line(line: &str) -> Result<AllowedSigner, CryptoError> {
+    let (principals, rest) = next_token(line);
+    let principals = split_list(&unquote(principals));
+    if principals.is_empty() {
+        return Err(invalid("Prinzipal fehlt"));
+    }
+
+    let mut namespaces = None;
+    let mut valid_after = None;
+    let mut valid_before = None;
+    let mut cert_authority = false;
+    let (mut key_type, mut rest) = next_token(rest);
+    if !is_key_type(key_type) {
+        for option in split_options(key_type) {
+            let (name, value) = match option.split_once('=') {
+                Some((name, value)) => (name, Some(unquote(value))),
+                None => (option, None),
+            };
+            match (name.to_ascii_lowercase().as_str(), value) {
+                ("cert-authority", None) => cert_authority = true,
+                ("namespaces", Some(value)) => namespaces = Some(split_list(&value)),
+                ("valid-after", Some(value)) => valid_after = Some(parse_time(&value)?),
+                ("valid-before", Some(value)) => valid_before = Some(parse_time(&value)?),
+                _ => return Err(invalid(&format!("unbekannte Option {}", option))),
+            }
+        }
+        (key_type, rest) = next_token(rest);
+    }
+    // nach dem Schlüssel folgt nur noch ein Kommentar
+    let (b64, _) = next_token(rest);
+    let (public_key, _) = PublicKey::from_openssh(&format!("{} {}", key_type, b64))?;
+    Ok(AllowedSigner {
+        principals,
+        namespaces,
+        valid_after,
+        valid_before,
+        cert_authority,
+        public_key,
+    })
+}
+
+fn is_key_type(token: &str) -> bool {
+    ["ssh-", "ecdsa-", "sk-"]
+        .iter()
+        .any(|prefix| token.starts_with(prefix))
+}
+
+/// Trennt das erste Wort ab; Leerraum in Anführungszeichen gehört zum Wort.
+fn next_token(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    let mut quoted = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => return (&text[..i], &text[i..]),
+            _ => {}
+        }
+    }
+    (text, "")
+}
+
+/// Trennt Optionen an Kommas außerhalb von Anführungszeichen.
+fn split_options(text: &str) -> Vec<&str> {
+    let mut options = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                options.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    options.push(&text[start..]);
+    options
+}
+
+fn unquote(text: &str) -> String {
+    text.replace('"', "")
+}
+
+fn split_list(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Zeitangabe `YYYYMMDD[HHMM[SS]][Z]` in Sekunden seit 1970.
+fn parse_time(text: &str) -> Result<i64, CryptoError> {
+    let digits = text.strip_suffix(['Z', 'z']).unwrap_or(text);
+    if !matches!(digits.len(), 8 | 12 | 14) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid(&format!("ungültige Zeitangabe {}", text)));
+    }
+    let field = |start: usize| -> u32 {
+        digits
+            .get(start..start + 2)
+            .map_or(0, |d| d.parse().unwrap())
+    };
+    let year: i64 = digits[..4].parse().unwrap();
+    let (month, day, hour, minute, second) = (field(4), field(6), field(8), field(10), field(12));
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return Err(invalid(&format!("ungültige Zeitangabe {}", text)));
+    }
+    Ok(days_from_civil(year, month, day) * 86_400 + i64::from(hour * 3600 + minute * 60 + second))
+}
+
+/// Prüft eine Musterliste wie OpenSSH: Ein passendes `!muster` schließt aus, sonst genügt
+/// ein passendes positives Muster.
+fn match_list(patterns: &[String], text: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        match pattern.strip_prefix('!') {
+            Some(negated) if glob_match(negated.as_bytes(), text.as_bytes()) => return false,
+            Some(_) => {}
+            None => matched |= glob_match(pattern.as_bytes(), text.as_bytes()),
+        }
+    }
+    matched
+}
+
+/// Platzhalter `*` (beliebig viele Zeichen) und `?` (genau ein Zeichen).
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|i| glob_match(rest, &text[i..])),
+        Some((&c, rest)) => text
+            .split_first()
+            .is_some_and(|(&t, tail)| (c == b'?' || c == t) && glob_match(rest, tail)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_and_times() {
+        let list = split_list("*@example.test,!mallory@example.test");
+        assert!(match_list(&list, "alice@example.test"));
+        assert!(!match_list(&list, "mallory@example.test"));
+        assert!(!match_list(&list, "alice@example.org"));
+        assert!(glob_match(b"ci-??", b"ci-01"));
+        assert!(!glob_match(b"ci-??", b"ci-1"));
+
+        assert_eq!(parse_time("19700102").unwrap(), 86_400);
+        assert_eq!(parse_time("197001010001Z").unwrap(), 60);
+        assert_eq!(parse_time("20240229120000").unwrap(), 1_709_208_000);
+        assert!(parse_time("2024-02-29").is_err());
+        assert!(parse_time("20241301").is_err());
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let line = concat!(
+            r#""ci@example.test,dev@example.test" cert-authority,namespaces="file,git" "#,
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIITopALjHY0GoUxA4v/zWKTl0Ow3snqAJk7cLXIhwOLZ ",
+            "Kommentar mit Leerzeichen"
+        );
+        let signers = AllowedSigners::parse(&format!("# Kommentar\n\n{}\n", line)).unwrap();
+        let entry = &signers.entries[0];
+        assert_eq!(entry.principals, ["ci@example.test", "dev@example.test"]);
+        assert_eq!(entry.namespaces.as_deref().unwrap(), ["file", "git"]);
+        assert!(entry.cert_authority);
+
+        assert!(AllowedSigners::parse("alice unbekannt=1 ssh-ed25519 AAAA").is_err());
+        // unbekannte Schlüsseltypen werden übersprungen
+        let ecdsa = "bob ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTY=";
+        assert!(AllowedSigners::parse(ecdsa).unwrap().entries.is_empty());
+    }
+}
@@ -0,0 +1,180 @@
+//! SSH-Datenkodierung (RFC 4251, Abschnitt 5) und die Textrahmen der OpenSSH-Formate.
+
+use crate::utils::error::CryptoError;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use num_bigint::BigUint;
+
+/// Zeilenlänge der Base64-Blöcke, wie sie `ssh-keygen` schreibt.
+const LINE_LEN: usize = 70;
+
+fn truncated() -> CryptoError {
+    CryptoError::InvalidFormat("SSH-Daten abgeschnitten".to_string())
+}
+
+/// Hängt einen `uint32` an.
+pub fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Hängt einen `string` (Länge + Inhalt) an.
+pub fn put_string(out: &mut Vec<u8>, value: &[u8]) {
+    put_u32(out, value.len() as u32);
+    out.extend_from_slice(value);
+}
+
+/// Hängt einen `mpint` an: Zweierkomplement, führendes Nullbyte bei gesetztem Vorzeichenbit.
+pub fn put_mpint(out: &mut Vec<u8>, value: &BigUint) {
+    let mut bytes = value.to_bytes_be();
+    if bytes == [0] {
+        bytes.clear();
+    } else if bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+    put_string(out, &bytes);
+}
+
+/// Lesezeiger über SSH-kodierten Daten.
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], CryptoError> {
+        if self.data.len() - self.pos < len {
+            return Err(truncated());
+        }
+        let out = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(out)
+    }
+
+    pub fn rest(&mut self) -> &'a [u8] {
+        let out = &self.data[self.pos..];
+        self.pos = self.data.len();
+        out
+    }
+
+    pub fn u32(&mut self) -> Result<u32, CryptoError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub fn string(&mut self) -> Result<&'a [u8], CryptoError> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    /// Liest einen `string`, der UTF-8-Text enthalten muss.
+    pub fn text(&mut self) -> Result<&'a str, CryptoError> {
+        std::str::from_utf8(self.string()?)
+            .map_err(|_| CryptoError::InvalidFormat("SSH-Text ist kein UTF-8".to_string()))
+    }
+
+    /// Liest einen nichtnegativen `mpint`.
+    pub fn mpint(&mut self) -> Result<BigUint, CryptoError> {
+        let bytes = self.string()?;
+        if bytes.first().is_some_and(|&b| b & 0x80 != 0) {
+            return Err(CryptoError::InvalidFormat(
+                "negativer SSH-mpint".to_string(),
+            ));
+        }
+        Ok(BigUint::from_bytes_be(bytes))
+    }
+
+    /// Stellt sicher, dass alle Daten gelesen wurden.
+    pub fn finish(&self) -> Result<(), CryptoError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(CryptoError::InvalidFormat(
+                "überzählige Bytes in SSH-Daten".to_string(),
+            ))
+        }
+    }
+}
+
+/// Rahmt Binärdaten wie `ssh-keygen` ein (`-----BEGIN <label>-----`, 70 Zeichen pro Zeile).
+pub fn armor(label: &str, data: &[u8]) -> String {
+    let b64 = STANDARD.encode(data);
+    let mut out = format!("-----BEGIN {}-----\n", label);
+    for chunk in b64.as_bytes().chunks(LINE_LEN) {
+        out.push_str(std::str::from_utf8(chunk).unwrap());
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {}-----\n", label));
+    out
+}
+
+/// Liest den ersten Block mit dem gegebenen Label; Text davor und danach wird ignoriert.
+pub fn dearmor(text: &str, label: &str) -> Result<Vec<u8>, CryptoError> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    let mut lines = text.lines().map(str::trim);
+    if !lines.any(|line| line == begin) {
+        return Err(CryptoError::InvalidFormat(format!(
+            "kein Block {} gefunden",
+            label
+        )));
+    }
+    let mut b64 = String::new();
+    for line in lines {
+        if line == end {
+            return STANDARD.decode(b64.as_bytes()).map_err(|_| {
+                CryptoError::InvalidFormat(format!("ungültiges Base64 in Block {}", label))
+            });
+        }
+        b64.push_str(line);
+    }
+    Err(CryptoError::InvalidFormat(format!(
+        "Block {} ohne Ende",
+        label
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mpint_rfc4251_examples() {
+        // Beispiele aus RFC 4251, Abschnitt 5
+        let cases: [(&str, &[u8]); 3] = [
+            ("0", &[0, 0, 0, 0]),
+            (
+                "9a378f9b2e332a7",
+                &[0, 0, 0, 8, 0x09, 0xa3, 0x78, 0xf9, 0xb2, 0xe3, 0x32, 0xa7],
+            ),
+            ("80", &[0, 0, 0, 2, 0x00, 0x80]),
+        ];
+        for (hex, encoded) in cases {
+            let value = BigUint::parse_bytes(hex.as_bytes(), 16).unwrap();
+            let mut out = Vec::new();
+            put_mpint(&mut out, &value);
+            assert_eq!(out, encoded);
+            let mut reader = Reader::new(&out);
+            assert_eq!(reader.mpint().unwrap(), value);
+            reader.finish().unwrap();
+        }
+        // -1234 als mpint
+        assert!(Reader::new(&[0, 0, 0, 2, 0xed, 0xcc]).mpint().is_err());
+    }
+
+    #[test]
+    fn test_armor_roundtrip() {
+        let data = vec![0xa5u8; 100];
+        let text = armor("SSH SIGNATURE", &data);
+        assert!(text.lines().all(|line| line.len() <= LINE_LEN));
+        let text = format!("Vorspann\n{}Nachspann\n", text);
+        assert_eq!(dearmor(&text, "SSH SIGNATURE").unwrap(), data);
+        assert!(dearmor(&text, "OPENSSH PRIVATE KEY").is_err());
+    }
+}
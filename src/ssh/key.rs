@@ -0,0 +1,454 @@
+//! OpenSSH-Schlüssel: öffentliche Schlüssel im Wire-Format (RFC 4253, 6.6; RFC 8709) und
+//! als `authorized_keys`-Zeile, private Schlüssel im Format `openssh-key-v1`
+//! (PROTOCOL.key in OpenSSH), optional mit bcrypt-pbkdf und AES-CTR verschlüsselt.
+
+use crate::asymmetric::{ed25519, rsa};
+use crate::ssh::encoding::{self, put_mpint, put_string, put_u32, Reader};
+use crate::symmetric::aes;
+use crate::utils::error::CryptoError;
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD};
+use base64::Engine;
+use num_bigint::BigUint;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+pub const SSH_RSA: &str = "ssh-rsa";
+pub const SSH_ED25519: &str = "ssh-ed25519";
+pub const RSA_SHA2_256: &str = "rsa-sha2-256";
+pub const RSA_SHA2_512: &str = "rsa-sha2-512";
+
+pub const PEM_PRIVATE_KEY: &str = "OPENSSH PRIVATE KEY";
+const AUTH_MAGIC: &[u8] = b"openssh-key-v1\0";
+/// bcrypt-pbkdf-Runden für neue verschlüsselte Schlüssel (Standard von `ssh-keygen -a`).
+pub const DEFAULT_ROUNDS: u32 = 16;
+/// Höchste Rundenzahl, die beim Entschlüsseln akzeptiert wird.
+const MAX_ROUNDS: u32 = 10_000;
+
+fn invalid(msg: &str) -> CryptoError {
+    CryptoError::InvalidFormat(format!("OpenSSH-Schlüssel: {}", msg))
+}
+
+/// Öffentlicher SSH-Schlüssel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublicKey {
+    Rsa(rsa::PublicKey),
+    Ed25519(ed25519::PublicKey),
+}
+
+impl PublicKey {
+    /// Schlüsseltyp, wie er im Wire-Format und in `.pub`-Dateien steht.
+    pub fn key_type(&self) -> &'static str {
+        match self {
+            PublicKey::Rsa(_) => SSH_RSA,
+            PublicKey::Ed25519(_) => SSH_ED25519,
+        }
+    }
+
+    /// Kodiert den Schlüssel im SSH-Wire-Format.
+    pub fn to_blob(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        put_string(&mut out, self.key_type().as_bytes());
+        match self {
+            PublicKey::Rsa(key) => {
+                put_mpint(&mut out, &key.e);
+                put_mpint(&mut out, &key.n);
+            }
+            PublicKey::Ed25519(key) => put_string(&mut out, &key.bytes),
+        }
+        out
+    }
+
+    /// Liest einen Schlüssel im SSH-Wire-Format.
+    pub fn from_blob(blob: &[u8]) -> Result<Self, CryptoError> {
+        let mut reader = Reader::new(blob);
+        let key = Self::read(&mut reader)?;
+        reader.finish()?;
+        Ok(key)
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, CryptoError> {
+        match reader.text()? {
+            SSH_RSA => {
+                let e = reader.mpint()?;
+                let n = reader.mpint()?;
+                if n.bits() < 1024 || e < BigUint::from(3u8) || !e.bit(0) {
+                    return Err(invalid("ungültiger RSA-Schlüssel"));
+                }
+                Ok(PublicKey::Rsa(rsa::PublicKey { e, n }))
+            }
+            SSH_ED25519 => Ok(PublicKey::Ed25519(read_ed25519_public(reader)?)),
+            other => Err(CryptoError::Unsupported(format!(
+                "SSH-Schlüsseltyp {}",
+                other
+            ))),
+        }
+    }
+
+    /// Liest eine Zeile im Format von `id_*.pub` bzw. `authorized_keys` ohne Optionen
+    /// (`<typ> <base64> [kommentar]`) und gibt Schlüssel und Kommentar zurück.
+    pub fn from_openssh(line: &str) -> Result<(Self, String), CryptoError> {
+        let mut parts = line.trim().splitn(3, char::is_whitespace);
+        let key_type = parts.next().unwrap_or_default();
+        let b64 = parts.next().ok_or_else(|| invalid("Base64-Teil fehlt"))?;
+        let comment = parts.next().unwrap_or_default().trim().to_string();
+        let blob = STANDARD
+            .decode(b64)
+            .map_err(|_| invalid("ungültiges Base64"))?;
+        let key = Self::from_blob(&blob)?;
+        if key.key_type() != key_type {
+            return Err(invalid("Schlüsseltyp passt nicht zum Inhalt"));
+        }
+        Ok((key, comment))
+    }
+
+    /// Schreibt den Schlüssel als Zeile im Format von `id_*.pub` (ohne Zeilenende).
+    pub fn to_openssh(&self, comment: &str) -> String {
+        let line = format!("{} {}", self.key_type(), STANDARD.encode(self.to_blob()));
+        if comment.is_empty() {
+            line
+        } else {
+            format!("{} {}", line, comment)
+        }
+    }
+
+    /// Fingerabdruck wie `ssh-keygen -l`: `SHA256:` und Base64 ohne Padding.
+    pub fn fingerprint(&self) -> String {
+        format!(
+            "SHA256:{}",
+            STANDARD_NO_PAD.encode(Sha256::digest(self.to_blob()))
+        )
+    }
+
+    /// Prüft eine SSH-Signatur (`string` Algorithmus, `string` Signatur) über `data`.
+    ///
+    /// Für RSA werden nur `rsa-sha2-256` und `rsa-sha2-512` akzeptiert, nicht SHA-1.
+    pub fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        let mut reader = Reader::new(signature);
+        let (Ok(algorithm), Ok(value)) = (reader.text(), reader.string()) else {
+            return false;
+        };
+        if reader.finish().is_err() {
+            return false;
+        }
+        match (self, algorithm) {
+            (PublicKey::Ed25519(key), SSH_ED25519) => ed25519::verify_ed25519(data, value, key),
+            (PublicKey::Rsa(key), RSA_SHA2_256) => {
+                let hash = rsa::Pkcs1Hash::Sha256;
+                rsa::verify_rsa_pkcs1v15(hash, &hash.digest(data), value, key)
+            }
+            (PublicKey::Rsa(key), RSA_SHA2_512) => {
+                let hash = rsa::Pkcs1Hash::Sha512;
+                rsa::verify_rsa_pkcs1v15(hash, &hash.digest(data), value, key)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn read_ed25519_public(reader: &mut Reader) -> Result<ed25519::PublicKey, CryptoError> {
+    let bytes = reader
+        .string()?
+        .try_into()
+        .map_err(|_| invalid("Ed25519-Schlüssel muss 32 Byte lang sein"))?;
+    Ok(ed25519::PublicKey { bytes })
+}
+
+/// Privater SSH-Schlüssel.
+#[derive(Clone)]
+pub enum PrivateKey {
+    Rsa {
+        public: rsa::PublicKey,
+        private: rsa::PrivateKey,
+    },
+    Ed25519 {
+        public: ed25519::PublicKey,
+        private: ed25519::PrivateKey,
+    },
+}
+
+impl PrivateKey {
+    pub fn from_ed25519(private: &ed25519::PrivateKey) -> Self {
+        PrivateKey::Ed25519 {
+            public: ed25519::public_key_from_private(private),
+            private: private.clone(),
+        }
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        match self {
+            PrivateKey::Rsa { public, .. } => PublicKey::Rsa(public.clone()),
+            PrivateKey::Ed25519 { public, .. } => PublicKey::Ed25519(public.clone()),
+        }
+    }
+
+    /// Signiert `data` und gibt die SSH-Signatur zurück; RSA verwendet `rsa-sha2-512`.
+    pub fn sign(&self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let (algorithm, value) = match self {
+            PrivateKey::Rsa { private, .. } => {
+                let hash = rsa::Pkcs1Hash::Sha512;
+                (
+                    RSA_SHA2_512,
+                    rsa::sign_rsa_pkcs1v15(hash, &hash.digest(data), private)?,
+                )
+            }
+            PrivateKey::Ed25519 { private, .. } => {
+                (SSH_ED25519, ed25519::sign_ed25519(data, private).to_vec())
+            }
+        };
+        let mut out = Vec::new();
+        put_string(&mut out, algorithm.as_bytes());
+        put_string(&mut out, &value);
+        Ok(out)
+    }
+
+    /// Liest einen Schlüssel im Format `openssh-key-v1` und gibt ihn mit Kommentar zurück.
+    ///
+    /// Verschlüsselte Schlüssel brauchen eine Passphrase; fehlt sie oder ist sie falsch,
+    /// ergibt das `DecryptionFailed`.
+    pub fn from_openssh(
+        text: &str,
+        passphrase: Option<&str>,
+    ) -> Result<(Self, String), CryptoError> {
+        let data = encoding::dearmor(text, PEM_PRIVATE_KEY)?;
+        let mut reader = Reader::new(&data);
+        if reader.take(AUTH_MAGIC.len())? != AUTH_MAGIC {
+            return Err(invalid("unbekannte Formatkennung"));
+        }
+        let cipher = reader.text()?;
+        let kdf = reader.text()?;
+        let kdf_options = reader.string()?;
+        if reader.u32()? != 1 {
+            return Err(CryptoError::Unsupported(
+                "OpenSSH-Datei mit mehreren Schlüsseln".to_string(),
+            ));
+        }
+        let public = PublicKey::from_blob(reader.string()?)?;
+        let encrypted = reader.string()?;
+        reader.finish()?;
+
+        let plain = match (cipher, kdf) {
+            ("none", "none") => encrypted.to_vec(),
+            (_, "bcrypt") => {
+                let key_len = ctr_key_len(cipher)?;
+                let mut options = Reader::new(kdf_options);
+                let salt = options.string()?;
+                let rounds = options.u32()?;
+                options.finish()?;
+                if rounds == 0 || rounds > MAX_ROUNDS {
+                    return Err(invalid("unzulässige Rundenzahl"));
+                }
+                let passphrase = passphrase.ok_or(CryptoError::DecryptionFailed)?;
+                let (key, iv) = derive_key(passphrase, salt, rounds, key_len)?;
+                if encrypted.len() % 16 != 0 {
+                    return Err(invalid("Länge kein Vielfaches der Blockgröße"));
+                }
+                aes::aes_ctr(&key, &iv, encrypted).unwrap()
+            }
+            _ => {
+                return Err(CryptoError::Unsupported(format!(
+                    "OpenSSH-Verschlüsselung {}/{}",
+                    cipher, kdf
+                )))
+            }
+        };
+
+        let mut reader = Reader::new(&plain);
+        // Prüfwerte: bei falscher Passphrase stimmen sie (fast sicher) nicht überein
+        if reader.u32()? != reader.u32()? {
+            return Err(CryptoError::DecryptionFailed);
+        }
+        let key = match reader.text()? {
+            SSH_RSA => read_rsa_private(&mut reader)?,
+            SSH_ED25519 => read_ed25519_private(&mut reader)?,
+            other => {
+                return Err(CryptoError::Unsupported(format!(
+                    "SSH-Schlüsseltyp {}",
+                    other
+                )))
+            }
+        };
+        let comment = reader.text()?.to_string();
+        let padding = reader.rest();
+        if !padding.iter().zip(1u8..).all(|(&b, i)| b == i) {
+            return Err(invalid("ungültiges Padding"));
+        }
+        if key.public_key() != public {
+            return Err(invalid(
+                "öffentlicher und privater Schlüssel passen nicht zusammen",
+            ));
+        }
+        Ok((key, comment))
+    }
+
+    /// Schreibt den Schlüssel im Format `openssh-key-v1`.
+    ///
+    /// Mit Passphrase wird er wie von `ssh-keygen` mit `aes256-ctr` und bcrypt-pbkdf
+    /// (`rounds` Runden, 16 Byte Salz) verschlüsselt.
+    pub fn to_openssh(
+        &self,
+        comment: &str,
+        passphrase: Option<&str>,
+        rounds: u32,
+    ) -> Result<String, CryptoError> {
+        let check = OsRng.next_u32();
+        let mut plain = Vec::new();
+        put_u32(&mut plain, check);
+        put_u32(&mut plain, check);
+        put_string(&mut plain, self.public_key().key_type().as_bytes());
+        match self {
+            PrivateKey::Rsa { public, private } => {
+                let crt = rsa::crt_params(public, private)
+                    .ok_or_else(|| invalid("RSA-Primfaktoren lassen sich nicht rekonstruieren"))?;
+                for value in [&public.n, &public.e, &private.d, &crt.qinv, &crt.p, &crt.q] {
+                    put_mpint(&mut plain, value);
+                }
+            }
+            PrivateKey::Ed25519 { public, private } => {
+                put_string(&mut plain, &public.bytes);
+                put_string(&mut plain, &[private.bytes, public.bytes].concat());
+            }
+        }
+        put_string(&mut plain, comment.as_bytes());
+        let block_size = if passphrase.is_some() { 16 } else { 8 };
+        let mut pad = 1u8;
+        while plain.len() % block_size != 0 {
+            plain.push(pad);
+            pad += 1;
+        }
+
+        let mut data = AUTH_MAGIC.to_vec();
+        let encrypted = match passphrase {
+            Some(passphrase) => {
+                let mut salt = [0u8; 16];
+                OsRng.fill_bytes(&mut salt);
+                let mut options = Vec::new();
+                put_string(&mut options, &salt);
+                put_u32(&mut options, rounds);
+                put_string(&mut data, b"aes256-ctr");
+                put_string(&mut data, b"bcrypt");
+                put_string(&mut data, &options);
+                let (key, iv) = derive_key(passphrase, &salt, rounds, 32)?;
+                aes::aes_ctr(&key, &iv, &plain).unwrap()
+            }
+            None => {
+                put_string(&mut data, b"none");
+                put_string(&mut data, b"none");
+                put_string(&mut data, b"");
+                plain
+            }
+        };
+        put_u32(&mut data, 1);
+        put_string(&mut data, &self.public_key().to_blob());
+        put_string(&mut data, &encrypted);
+        Ok(encoding::armor(PEM_PRIVATE_KEY, &data))
+    }
+}
+
+fn ctr_key_len(cipher: &str) -> Result<usize, CryptoError> {
+    match cipher {
+        "aes128-ctr" => Ok(16),
+        "aes192-ctr" => Ok(24),
+        "aes256-ctr" => Ok(32),
+        _ => Err(CryptoError::Unsupported(format!(
+            "OpenSSH-Verschlüsselung {}",
+            cipher
+        ))),
+    }
+}
+
+/// Leitet Schlüssel und IV für AES-CTR mit bcrypt-pbkdf ab.
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    rounds: u32,
+    key_len: usize,
+) -> Result<(Vec<u8>, [u8; 16]), CryptoError> {
+    let mut out = vec![0u8; key_len + 16];
+    // leere Passphrase oder leeres Salz lehnt bcrypt-pbkdf ab
+    bcrypt_pbkdf::bcrypt_pbkdf(passphrase.as_bytes(), salt, rounds, &mut out)
+        .map_err(|_| CryptoError::DecryptionFailed)?;
+    let iv = out[key_len..].try_into().unwrap();
+    out.truncate(key_len);
+    Ok((out, iv))
+}
+
+fn read_rsa_private(reader: &mut Reader) -> Result<PrivateKey, CryptoError> {
+    let n = reader.mpint()?;
+    let e = reader.mpint()?;
+    let d = reader.mpint()?;
+    let _iqmp = reader.mpint()?;
+    let p = reader.mpint()?;
+    let q = reader.mpint()?;
+    if &p * &q != n {
+        return Err(invalid("n ist nicht p·q"));
+    }
+    Ok(PrivateKey::Rsa {
+        public: rsa::PublicKey {
+            e: e.clone(),
+            n: n.clone(),
+        },
+        private: rsa::PrivateKey { d, n, e },
+    })
+}
+
+fn read_ed25519_private(reader: &mut Reader) -> Result<PrivateKey, CryptoError> {
+    let public = read_ed25519_public(reader)?;
+    let secret = reader.string()?;
+    // 64 Byte: Seed, gefolgt vom öffentlichen Schlüssel
+    if secret.len() != 64 || secret[32..] != public.bytes {
+        return Err(invalid("ungültiger Ed25519-Schlüssel"));
+    }
+    let private = ed25519::PrivateKey {
+        bytes: secret[..32].try_into().unwrap(),
+    };
+    Ok(PrivateKey::from_ed25519(&private))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_private_key_roundtrip() {
+        // mindestens 1024 Bit, wie OpenSSH verlangt (zwei 520-Bit-Primzahlen)
+        let (public, private) = rsa::generate_rsa_keypair(1040);
+        let (_, ed_private) = ed25519::generate_ed25519_keypair();
+        let keys = [
+            PrivateKey::Rsa { public, private },
+            PrivateKey::from_ed25519(&ed_private),
+        ];
+        for key in &keys {
+            let text = key.to_openssh("test@example.test", None, 0).unwrap();
+            let (decoded, comment) = PrivateKey::from_openssh(&text, None).unwrap();
+            assert_eq!(decoded.public_key(), key.public_key());
+            assert_eq!(comment, "test@example.test");
+
+            let text = key.to_openssh("", Some("geheim"), 2).unwrap();
+            let (decoded, _) = PrivateKey::from_openssh(&text, Some("geheim")).unwrap();
+            let signature = decoded.sign(b"Nachricht").unwrap();
+            assert!(key.public_key().verify(b"Nachricht", &signature));
+            assert!(!key.public_key().verify(b"Nachricht!", &signature));
+            for wrong in [Some("falsch"), None] {
+                assert!(matches!(
+                    PrivateKey::from_openssh(&text, wrong),
+                    Err(CryptoError::DecryptionFailed)
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_public_key_line() {
+        let (_, private) = ed25519::generate_ed25519_keypair();
+        let key = PrivateKey::from_ed25519(&private).public_key();
+        let line = key.to_openssh("alice@example.test");
+        assert_eq!(
+            PublicKey::from_openssh(&line).unwrap(),
+            (key.clone(), "alice@example.test".to_string())
+        );
+        let wrong_type = line.replacen(SSH_ED25519, SSH_RSA, 1);
+        assert!(PublicKey::from_openssh(&wrong_type).is_err());
+        assert_eq!(key.fingerprint().len(), "SHA256:".len() + 43);
+    }
+}
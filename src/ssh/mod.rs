@@ -0,0 +1,15 @@
+//! OpenSSH-Schlüssel und SSH-Signaturen.
+//!
+//! Umfasst öffentliche Schlüssel (`ssh-rsa`, `ssh-ed25519`) im `.pub`-Format, private
+//! Schlüssel im Format `openssh-key-v1` (auch mit bcrypt-pbkdf verschlüsselt) sowie
+//! SSHSIG-Signaturen über Dateien, die mit `ssh-keygen -Y sign/verify` austauschbar sind,
+//! samt Prüfung gegen eine Datei mit erlaubten Unterzeichnern.
+
+pub mod allowed_signers;
+pub mod encoding;
+pub mod key;
+pub mod sshsig;
+
+pub use allowed_signers::{AllowedSigner, AllowedSigners};
+pub use key::{PrivateKey, PublicKey};
+pub use sshsig::{sign, Signature};
@@ -0,0 +1,169 @@
+//! SSH-Signaturen über Dateien (PROTOCOL.sshsig in OpenSSH), kompatibel mit
+//! `ssh-keygen -Y sign` und `ssh-keygen -Y verify`.
+
+use crate::ssh::encoding::{self, put_string, put_u32, Reader};
+use crate::ssh::key::{PrivateKey, PublicKey};
+use crate::utils::error::CryptoError;
+use sha2::{Digest, Sha256, Sha512};
+
+const MAGIC: &[u8] = b"SSHSIG";
+const VERSION: u32 = 1;
+pub const PEM_SIGNATURE: &str = "SSH SIGNATURE";
+
+fn invalid(msg: &str) -> CryptoError {
+    CryptoError::InvalidFormat(format!("SSH-Signatur: {}", msg))
+}
+
+/// Hash-Verfahren über die Nachricht.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha512 => "sha512",
+        }
+    }
+
+    fn from_name(name: &str) -> Result<Self, CryptoError> {
+        match name {
+            "sha256" => Ok(HashAlgorithm::Sha256),
+            "sha512" => Ok(HashAlgorithm::Sha512),
+            other => Err(CryptoError::Unsupported(format!(
+                "SSHSIG-Hashverfahren {}",
+                other
+            ))),
+        }
+    }
+
+    fn digest(self, message: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Sha256 => Sha256::digest(message).to_vec(),
+            HashAlgorithm::Sha512 => Sha512::digest(message).to_vec(),
+        }
+    }
+}
+
+/// Eine SSHSIG-Signatur mit dem öffentlichen Schlüssel des Unterzeichners.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub public_key: PublicKey,
+    /// Anwendungsbereich, z. B. `file` oder `git`; verhindert, dass eine Signatur in einem
+    /// anderen Kontext wiederverwendet wird.
+    pub namespace: String,
+    pub hash_algorithm: HashAlgorithm,
+    /// SSH-Signatur (`string` Algorithmus, `string` Wert).
+    pub signature: Vec<u8>,
+}
+
+/// Die tatsächlich signierten Daten (PROTOCOL.sshsig, Abschnitt 3).
+fn signed_data(namespace: &str, hash_algorithm: HashAlgorithm, message: &[u8]) -> Vec<u8> {
+    let mut out = MAGIC.to_vec();
+    put_string(&mut out, namespace.as_bytes());
+    put_string(&mut out, b"");
+    put_string(&mut out, hash_algorithm.name().as_bytes());
+    put_string(&mut out, &hash_algorithm.digest(message));
+    out
+}
+
+/// Signiert eine Nachricht wie `ssh-keygen -Y sign -n <namespace>` (SHA-512).
+pub fn sign(message: &[u8], key: &PrivateKey, namespace: &str) -> Result<Signature, CryptoError> {
+    if namespace.is_empty() {
+        return Err(invalid("Namensraum darf nicht leer sein"));
+    }
+    let hash_algorithm = HashAlgorithm::Sha512;
+    Ok(Signature {
+        public_key: key.public_key(),
+        namespace: namespace.to_string(),
+        hash_algorithm,
+        signature: key.sign(&signed_data(namespace, hash_algorithm, message))?,
+    })
+}
+
+impl Signature {
+    pub fn to_blob(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        put_u32(&mut out, VERSION);
+        put_string(&mut out, &self.public_key.to_blob());
+        put_string(&mut out, self.namespace.as_bytes());
+        put_string(&mut out, b"");
+        put_string(&mut out, self.hash_algorithm.name().as_bytes());
+        put_string(&mut out, &self.signature);
+        out
+    }
+
+    pub fn from_blob(blob: &[u8]) -> Result<Self, CryptoError> {
+        let mut reader = Reader::new(blob);
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(invalid("Kennung SSHSIG fehlt"));
+        }
+        if reader.u32()? != VERSION {
+            return Err(CryptoError::Unsupported("SSHSIG-Version".to_string()));
+        }
+        let public_key = PublicKey::from_blob(reader.string()?)?;
+        let namespace = reader.text()?.to_string();
+        reader.string()?; // reserviert
+        let hash_algorithm = HashAlgorithm::from_name(reader.text()?)?;
+        let signature = reader.string()?.to_vec();
+        reader.finish()?;
+        Ok(Signature {
+            public_key,
+            namespace,
+            hash_algorithm,
+            signature,
+        })
+    }
+
+    /// ASCII-Form wie in `*.sig`-Dateien von `ssh-keygen`.
+    pub fn to_armor(&self) -> String {
+        encoding::armor(PEM_SIGNATURE, &self.to_blob())
+    }
+
+    pub fn from_armor(text: &str) -> Result<Self, CryptoError> {
+        Self::from_blob(&encoding::dearmor(text, PEM_SIGNATURE)?)
+    }
+
+    /// Prüft Namensraum und Signatur über `message` mit dem enthaltenen Schlüssel.
+    ///
+    /// Ob der Schlüssel vertrauenswürdig ist, entscheidet erst `AllowedSigners::verify`.
+    pub fn verify(&self, message: &[u8], namespace: &str) -> Result<(), CryptoError> {
+        if self.namespace != namespace {
+            return Err(CryptoError::InvalidSignature);
+        }
+        let data = signed_data(&self.namespace, self.hash_algorithm, message);
+        if self.public_key.verify(&data, &self.signature) {
+            Ok(())
+        } else {
+            Err(CryptoError::InvalidSignature)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asymmetric::ed25519;
+
+    #[test]
+    fn test_sign_verify_roundtrip() {
+        let (_, private) = ed25519::generate_ed25519_keypair();
+        let key = PrivateKey::from_ed25519(&private);
+        let signature = sign(b"Inhalt", &key, "file").unwrap();
+        let parsed = Signature::from_armor(&signature.to_armor()).unwrap();
+        assert_eq!(parsed, signature);
+        parsed.verify(b"Inhalt", "file").unwrap();
+        assert_eq!(
+            parsed.verify(b"Inhalt", "git"),
+            Err(CryptoError::InvalidSignature)
+        );
+        assert_eq!(
+            parsed.verify(b"anderer Inhalt", "file"),
+            Err(CryptoError::InvalidSignature)
+        );
+        assert!(sign(b"Inhalt", &key, "").is_err());
+    }
+}
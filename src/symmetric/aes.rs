@@ -1,4 +1,8 @@
-use aes::Aes256;
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockDecrypt, BlockEncrypt};
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
 use block_padding::Pkcs7;
 use cbc::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
 use cbc::{Decryptor, Encryptor};
@@ -27,6 +31,232 @@ pub fn decrypt_aes256(key: &[u8; 32], iv: &[u8], ciphertext: &[u8]) -> Vec<u8> {
     pt.to_vec()
 }
 
+/// Verschlüsselt Daten authentifiziert mit AES-256-GCM.
+/// `aad` wird mitauthentifiziert, aber nicht verschlüsselt.
+/// Gibt Nonce (12 Byte) und Ciphertext inkl. Tag zurück.
+pub fn encrypt_aes256_gcm(key: &[u8; 32], plaintext: &[u8], aad: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let nonce: [u8; 12] = rand::thread_rng().gen();
+    let cipher = Aes256Gcm::new(key.into());
+    let ct = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .expect("AES-GCM-Verschlüsselung fehlgeschlagen");
+    (nonce.to_vec(), ct)
+}
+
+/// Entschlüsselt AES-256-GCM.
+/// Gibt None zurück, wenn Nonce-Länge oder Authentifizierungs-Tag nicht stimmen.
+pub fn decrypt_aes256_gcm(
+    key: &[u8; 32],
+    nonce: &[u8],
+    ciphertext: &[u8],
+    aad: &[u8],
+) -> Option<Vec<u8>> {
+    if nonce.len() != 12 {
+        return None;
+    }
+    let cipher = Aes256Gcm::new(key.into());
+    cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .ok()
+}
+
+/// AES-Blockchiffre mit 128-, 192- oder 256-Bit-Schlüssel.
+pub enum AesCipher {
+    Aes128(Aes128),
+    Aes192(Aes192),
+    Aes256(Aes256),
+}
+
+impl AesCipher {
+    /// Wählt die Variante anhand der Schlüssellänge (16, 24 oder 32 Byte).
+    pub fn new(key: &[u8]) -> Option<Self> {
+        match key.len() {
+            16 => Some(AesCipher::Aes128(Aes128::new_from_slice(key).ok()?)),
+            24 => Some(AesCipher::Aes192(Aes192::new_from_slice(key).ok()?)),
+            32 => Some(AesCipher::Aes256(Aes256::new_from_slice(key).ok()?)),
+            _ => None,
+        }
+    }
+
+    pub fn encrypt_block(&self, block: &mut [u8; 16]) {
+        let block = GenericArray::from_mut_slice(block);
+        match self {
+            AesCipher::Aes128(c) => c.encrypt_block(block),
+            AesCipher::Aes192(c) => c.encrypt_block(block),
+            AesCipher::Aes256(c) => c.encrypt_block(block),
+        }
+    }
+
+    pub fn decrypt_block(&self, block: &mut [u8; 16]) {
+        let block = GenericArray::from_mut_slice(block);
+        match self {
+            AesCipher::Aes128(c) => c.decrypt_block(block),
+            AesCipher::Aes192(c) => c.decrypt_block(block),
+            AesCipher::Aes256(c) => c.decrypt_block(block),
+        }
+    }
+}
+
+/// AES-CBC-Verschlüsselung mit PKCS#7-Padding; die Schlüssellänge wählt die AES-Variante.
+pub fn encrypt_aes_cbc(key: &[u8], iv: &[u8; 16], plaintext: &[u8]) -> Option<Vec<u8>> {
+    let cipher = AesCipher::new(key)?;
+    let pad = 16 - plaintext.len() % 16;
+    let mut padded = plaintext.to_vec();
+    padded.resize(plaintext.len() + pad, pad as u8);
+    let mut previous = *iv;
+    let mut ciphertext = Vec::with_capacity(padded.len());
+    for chunk in padded.chunks(16) {
+        let mut block: [u8; 16] = chunk.try_into().unwrap();
+        block.iter_mut().zip(&previous).for_each(|(b, p)| *b ^= p);
+        cipher.encrypt_block(&mut block);
+        ciphertext.extend_from_slice(&block);
+        previous = block;
+    }
+    Some(ciphertext)
+}
+
+/// AES-CBC-Entschlüsselung mit PKCS#7-Padding; None bei ungültigem Padding.
+pub fn decrypt_aes_cbc(key: &[u8], iv: &[u8; 16], ciphertext: &[u8]) -> Option<Vec<u8>> {
+    let cipher = AesCipher::new(key)?;
+    if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(16) {
+        return None;
+    }
+    let mut previous = *iv;
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    for chunk in ciphertext.chunks(16) {
+        let mut block: [u8; 16] = chunk.try_into().unwrap();
+        cipher.decrypt_block(&mut block);
+        plaintext.extend(block.iter().zip(&previous).map(|(b, p)| b ^ p));
+        previous = chunk.try_into().unwrap();
+    }
+    let pad = *plaintext.last()? as usize;
+    if pad == 0
+        || pad > 16
+        || !plaintext[plaintext.len() - pad..]
+            .iter()
+            .all(|&b| b as usize == pad)
+    {
+        return None;
+    }
+    plaintext.truncate(plaintext.len() - pad);
+    Some(plaintext)
+}
+
+/// Startwert des AES Key Wrap (RFC 3394, 2.2.3.1).
+const KEY_WRAP_IV: [u8; 8] = [0xa6; 8];
+
+/// AES Key Wrap (RFC 3394). Der Schlüssel muss ein Vielfaches von 8 Byte (mind. 16) lang sein.
+pub fn aes_key_wrap(kek: &[u8], key: &[u8]) -> Option<Vec<u8>> {
+    if key.len() < 16 || !key.len().is_multiple_of(8) {
+        return None;
+    }
+    let cipher = AesCipher::new(kek)?;
+    let n = key.len() / 8;
+    let mut a = KEY_WRAP_IV;
+    let mut r: Vec<[u8; 8]> = key.chunks(8).map(|c| c.try_into().unwrap()).collect();
+    for j in 0..6 {
+        for (i, r_i) in r.iter_mut().enumerate() {
+            let mut b = [0u8; 16];
+            b[..8].copy_from_slice(&a);
+            b[8..].copy_from_slice(r_i);
+            cipher.encrypt_block(&mut b);
+            let t = (n * j + i + 1) as u64;
+            a.copy_from_slice(&b[..8]);
+            a.iter_mut().zip(t.to_be_bytes()).for_each(|(x, y)| *x ^= y);
+            r_i.copy_from_slice(&b[8..]);
+        }
+    }
+    let mut out = a.to_vec();
+    r.iter().for_each(|block| out.extend_from_slice(block));
+    Some(out)
+}
+
+/// AES Key Unwrap (RFC 3394). Gibt None zurück, wenn die Integritätsprüfung fehlschlägt.
+pub fn aes_key_unwrap(kek: &[u8], wrapped: &[u8]) -> Option<Vec<u8>> {
+    if wrapped.len() < 24 || !wrapped.len().is_multiple_of(8) {
+        return None;
+    }
+    let cipher = AesCipher::new(kek)?;
+    let n = wrapped.len() / 8 - 1;
+    let mut a: [u8; 8] = wrapped[..8].try_into().unwrap();
+    let mut r: Vec<[u8; 8]> = wrapped[8..]
+        .chunks(8)
+        .map(|c| c.try_into().unwrap())
+        .collect();
+    for j in (0..6).rev() {
+        for i in (0..n).rev() {
+            let t = (n * j + i + 1) as u64;
+            let mut b = [0u8; 16];
+            b[..8].copy_from_slice(&a);
+            b[..8]
+                .iter_mut()
+                .zip(t.to_be_bytes())
+                .for_each(|(x, y)| *x ^= y);
+            b[8..].copy_from_slice(&r[i]);
+            cipher.decrypt_block(&mut b);
+            a.copy_from_slice(&b[..8]);
+            r[i].copy_from_slice(&b[8..]);
+        }
+    }
+    if a != KEY_WRAP_IV {
+        return None;
+    }
+    Some(r.concat())
+}
+
+/// AES im CFB-Modus (128-Bit-Rückkopplung), Verschlüsselung.
+pub fn encrypt_aes_cfb(key: &[u8], iv: &[u8; 16], data: &[u8]) -> Option<Vec<u8>> {
+    let cipher = AesCipher::new(key)?;
+    let mut feedback = *iv;
+    let mut out = Vec::with_capacity(data.len());
+    for chunk in data.chunks(16) {
+        cipher.encrypt_block(&mut feedback);
+        let ct: Vec<u8> = chunk.iter().zip(feedback).map(|(p, k)| p ^ k).collect();
+        feedback[..ct.len()].copy_from_slice(&ct);
+        out.extend_from_slice(&ct);
+    }
+    Some(out)
+}
+
+/// AES im CFB-Modus (128-Bit-Rückkopplung), Entschlüsselung.
+pub fn decrypt_aes_cfb(key: &[u8], iv: &[u8; 16], data: &[u8]) -> Option<Vec<u8>> {
+    let cipher = AesCipher::new(key)?;
+    let mut feedback = *iv;
+    let mut out = Vec::with_capacity(data.len());
+    for chunk in data.chunks(16) {
+        cipher.encrypt_block(&mut feedback);
+        out.extend(chunk.iter().zip(feedback).map(|(c, k)| c ^ k));
+        feedback[..chunk.len()].copy_from_slice(chunk);
+    }
+    Some(out)
+}
+
+/// AES im CTR-Modus mit 128-Bit-Big-Endian-Zähler; Ver- und Entschlüsselung sind identisch.
+pub fn aes_ctr(key: &[u8], iv: &[u8; 16], data: &[u8]) -> Option<Vec<u8>> {
+    let cipher = AesCipher::new(key)?;
+    let mut counter = u128::from_be_bytes(*iv);
+    let mut out = Vec::with_capacity(data.len());
+    for chunk in data.chunks(16) {
+        let mut keystream = counter.to_be_bytes();
+        cipher.encrypt_block(&mut keystream);
+        out.extend(chunk.iter().zip(keystream).map(|(d, k)| d ^ k));
+        counter = counter.wrapping_add(1);
+    }
+    Some(out)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,4 +270,92 @@ mod tests {
         let pt = decrypt_aes256(&key, &iv, &ct);
         assert_eq!(pt, plaintext);
     }
+
+    #[test]
+    fn test_gcm_encrypt_decrypt() {
+        let key: [u8; 32] = rand::thread_rng().gen();
+        let plaintext = b"Authentifizierte Nachricht";
+        let (nonce, ct) = encrypt_aes256_gcm(&key, plaintext, b"header");
+        let pt = decrypt_aes256_gcm(&key, &nonce, &ct, b"header").unwrap();
+        assert_eq!(pt, plaintext);
+
+        // falsche AAD oder manipulierter Ciphertext werden abgelehnt
+        assert!(decrypt_aes256_gcm(&key, &nonce, &ct, b"anders").is_none());
+        let mut tampered = ct.clone();
+        tampered[0] ^= 1;
+        assert!(decrypt_aes256_gcm(&key, &nonce, &tampered, b"header").is_none());
+    }
+
+    #[test]
+    fn test_key_wrap_rfc3394_vector() {
+        // RFC 3394, 4.1: 128-Bit-Schlüssel mit 128-Bit-KEK
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let key = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let wrapped = aes_key_wrap(&kek, &key).unwrap();
+        assert_eq!(
+            hex::encode_upper(&wrapped),
+            "1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5"
+        );
+        assert_eq!(aes_key_unwrap(&kek, &wrapped).unwrap(), key);
+
+        let mut tampered = wrapped.clone();
+        tampered[3] ^= 1;
+        assert!(aes_key_unwrap(&kek, &tampered).is_none());
+    }
+
+    #[test]
+    fn test_cfb_roundtrip() {
+        let key: [u8; 24] = rand::thread_rng().gen();
+        let iv: [u8; 16] = rand::thread_rng().gen();
+        let data = b"CFB mit einem unvollstaendigen letzten Block";
+        let ct = encrypt_aes_cfb(&key, &iv, data).unwrap();
+        assert_eq!(ct.len(), data.len());
+        assert_eq!(decrypt_aes_cfb(&key, &iv, &ct).unwrap(), data.to_vec());
+    }
+
+    #[test]
+    fn test_cbc_padding() {
+        let key = [7u8; 16];
+        let cipher = AesCipher::new(&key).unwrap();
+        let iv = [1u8; 16];
+        // "abc" mit Padding 0x0d, CBC-verschlüsselt
+        let mut block = [0x0du8; 16];
+        block[..3].copy_from_slice(b"abc");
+        for (b, v) in block.iter_mut().zip(&iv) {
+            *b ^= v;
+        }
+        cipher.encrypt_block(&mut block);
+        assert_eq!(decrypt_aes_cbc(&key, &iv, &block).unwrap(), b"abc");
+        assert_eq!(encrypt_aes_cbc(&key, &iv, b"abc").unwrap(), block);
+
+        let mut wrong_iv = iv;
+        wrong_iv[15] ^= 1;
+        assert!(decrypt_aes_cbc(&key, &wrong_iv, &block).is_none());
+        assert!(decrypt_aes_cbc(&key, &iv, &block[..8]).is_none());
+
+        // volle Blöcke erhalten einen zusätzlichen Padding-Block
+        let data = [0x42u8; 32];
+        let ct = encrypt_aes_cbc(&[9u8; 32], &iv, &data).unwrap();
+        assert_eq!(ct.len(), 48);
+        assert_eq!(decrypt_aes_cbc(&[9u8; 32], &iv, &ct).unwrap(), data);
+    }
+
+    #[test]
+    fn test_ctr_sp800_38a_vector() {
+        // NIST SP 800-38A, F.5.5 (CTR-AES256.Encrypt), die ersten beiden Blöcke
+        let key = hex::decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4")
+            .unwrap();
+        let iv: [u8; 16] = hex::decode("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let pt = hex::decode("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51")
+            .unwrap();
+        let ct = aes_ctr(&key, &iv, &pt).unwrap();
+        assert_eq!(
+            hex::encode(&ct),
+            "601ec313775789a5b7a7f504bbf3d228f443e3ca4d62b59aca84e990cacaf5c5"
+        );
+        assert_eq!(aes_ctr(&key, &iv, &ct[..20]).unwrap(), pt[..20]);
+    }
 }
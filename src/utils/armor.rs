@@ -0,0 +1,432 @@
+//! ASCII-Armor für Chiffrate, Signaturen und andere Binärdaten der CLI.
+//!
+//! Ein Block besteht aus BEGIN-Zeile, optionalen Headern (`Key: Value`, durch eine Leerzeile
+//! abgeschlossen), Base64 mit 64 Zeichen pro Zeile, einer CRC-24-Prüfsumme (`=XXXX`, wie bei
+//! OpenPGP) und der END-Zeile. Fremde Formate (CMS, PKCS#12, age) werden ohne Header und
+//! Prüfsumme als PEM nach RFC 7468 geschrieben, damit andere Werkzeuge sie lesen können.
+
+use crate::utils::error::CryptoError;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::io::{self, Write};
+
+const LINE_LEN: usize = 64;
+/// Beim Lesen toleriert (RFC 7468, Abschnitt 2: andere Umbrüche sind zulässig).
+const MAX_LINE_LEN: usize = 76;
+const CRC24_INIT: u32 = 0xb704ce;
+const CRC24_POLY: u32 = 0x1864cfb;
+
+/// Art der Daten, bestimmt durch das Label der BEGIN-Zeile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Label {
+    /// Mehrempfänger-Datei von `encrypt`.
+    Encrypted,
+    /// Signierte und verschlüsselte Datei von `seal`.
+    Sealed,
+    /// Hybrid-Datei von `rsa encrypt`.
+    RsaEncrypted,
+    /// Datei von `aes encrypt`.
+    AesEncrypted,
+    /// Signatur von `sign`.
+    Signature,
+    /// CMS-Struktur, wie `openssl cms -outform PEM`.
+    Cms,
+    /// PKCS#12-Datei.
+    Pkcs12,
+    /// age-Datei (Armor nach age-Spezifikation).
+    Age,
+}
+
+impl Label {
+    const ALL: [Label; 8] = [
+        Label::Encrypted,
+        Label::Sealed,
+        Label::RsaEncrypted,
+        Label::AesEncrypted,
+        Label::Signature,
+        Label::Cms,
+        Label::Pkcs12,
+        Label::Age,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Label::Encrypted => "CRYPTOMORPH ENCRYPTED FILE",
+            Label::Sealed => "CRYPTOMORPH SEALED FILE",
+            Label::RsaEncrypted => "CRYPTOMORPH RSA ENCRYPTED FILE",
+            Label::AesEncrypted => "CRYPTOMORPH AES ENCRYPTED FILE",
+            Label::Signature => "CRYPTOMORPH SIGNATURE",
+            Label::Cms => "CMS",
+            Label::Pkcs12 => "PKCS12",
+            Label::Age => "AGE ENCRYPTED FILE",
+        }
+    }
+
+    /// Erkennt ein Label; `PKCS7` (ältere OpenSSL-Versionen) gilt als CMS.
+    pub fn from_label(label: &str) -> Option<Self> {
+        if label == "PKCS7" {
+            return Some(Label::Cms);
+        }
+        Self::ALL.into_iter().find(|l| l.as_str() == label)
+    }
+
+    /// Eigene Formate tragen Header und Prüfsumme, fremde bleiben reines PEM.
+    fn is_cryptomorph(self) -> bool {
+        !matches!(self, Label::Cms | Label::Pkcs12 | Label::Age)
+    }
+}
+
+/// CRC-24 nach RFC 9580, 6.1.1, fortsetzbar über mehrere Teilstücke.
+fn crc24_update(mut crc: u32, data: &[u8]) -> u32 {
+    for &byte in data {
+        crc ^= (byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x1000000 != 0 {
+                crc ^= CRC24_POLY;
+            }
+        }
+    }
+    crc & 0xffffff
+}
+
+/// CRC-24 nach RFC 9580, 6.1.1.
+pub fn crc24(data: &[u8]) -> u32 {
+    crc24_update(CRC24_INIT, data)
+}
+
+fn checksum_line(crc: u32) -> String {
+    format!("={}\n", STANDARD.encode(&crc.to_be_bytes()[1..]))
+}
+
+/// Header-Namen: ASCII-Buchstaben, Ziffern und `-`, wie `Comment` oder `Hash`.
+fn is_header_key(key: &str) -> bool {
+    !key.is_empty() && key.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+}
+
+fn is_base64_line(line: &str) -> bool {
+    !line.is_empty()
+        && line.len() <= MAX_LINE_LEN
+        && line
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'='))
+}
+
+/// Prüft, ob `data` (nach führendem Leerraum) mit einer BEGIN-Zeile beginnt.
+pub fn is_armored(data: &[u8]) -> bool {
+    data.trim_ascii_start().starts_with(b"-----BEGIN ")
+}
+
+/// Ein dekodierter oder zu schreibender Armor-Block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Armor {
+    pub label: Label,
+    pub headers: Vec<(String, String)>,
+    pub data: Vec<u8>,
+}
+
+impl Armor {
+    pub fn new(label: Label, data: Vec<u8>) -> Self {
+        Armor {
+            label,
+            headers: Vec::new(),
+            data,
+        }
+    }
+
+    /// Fügt einen Header hinzu. Fremde Formate haben keine Header; Zeilenumbrüche im Wert
+    /// und ungültige Namen werden abgelehnt.
+    pub fn with_header(mut self, key: &str, value: &str) -> Result<Self, CryptoError> {
+        if !self.label.is_cryptomorph() {
+            return Err(CryptoError::Unsupported(format!(
+                "Armor-Header bei {}",
+                self.label.as_str()
+            )));
+        }
+        if !is_header_key(key) || value.contains(['\r', '\n']) {
+            return Err(CryptoError::InvalidFormat(format!(
+                "ungültiger Armor-Header {}",
+                key
+            )));
+        }
+        self.headers.push((key.to_string(), value.to_string()));
+        Ok(self)
+    }
+
+    /// Wert des ersten Headers mit diesem Namen.
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn encode(&self) -> String {
+        let label = self.label.as_str();
+        let b64 = STANDARD.encode(&self.data);
+        let mut out =
+            String::with_capacity(b64.len() + b64.len() / LINE_LEN + 2 * label.len() + 40);
+        out.push_str(&format!("-----BEGIN {}-----\n", label));
+        for (key, value) in &self.headers {
+            out.push_str(&format!("{}: {}\n", key, value));
+        }
+        if !self.headers.is_empty() {
+            out.push('\n');
+        }
+        for chunk in b64.as_bytes().chunks(LINE_LEN) {
+            out.push_str(std::str::from_utf8(chunk).unwrap());
+            out.push('\n');
+        }
+        if self.label.is_cryptomorph() {
+            out.push_str(&checksum_line(crc24(&self.data)));
+        }
+        out.push_str(&format!("-----END {}-----\n", label));
+        out
+    }
+
+    /// Dekodiert genau einen Block.
+    ///
+    /// Erlaubt sind nur Leerraum vor und nach dem Block sowie LF- oder CRLF-Zeilenenden.
+    /// Unbekannte Labels, fehlerhafte Header, Zeichen außerhalb des Base64-Alphabets,
+    /// nicht-kanonisches Base64 und eine fehlende oder falsche Prüfsumme werden abgelehnt.
+    pub fn decode(data: &[u8]) -> Result<Self, CryptoError> {
+        let err = |msg: &str| CryptoError::InvalidFormat(format!("Armor: {}", msg));
+        let text = std::str::from_utf8(data.trim_ascii()).map_err(|_| err("kein gültiger Text"))?;
+        let lines: Vec<&str> = text
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .collect();
+
+        let name = lines[0]
+            .strip_prefix("-----BEGIN ")
+            .and_then(|rest| rest.strip_suffix("-----"))
+            .ok_or_else(|| err("BEGIN-Zeile fehlt"))?;
+        let label = Label::from_label(name)
+            .ok_or_else(|| CryptoError::Unsupported(format!("Armor-Typ {}", name)))?;
+        if lines.len() < 2 || lines[lines.len() - 1] != format!("-----END {}-----", name) {
+            return Err(err("END-Zeile fehlt oder passt nicht"));
+        }
+        let mut body = &lines[1..lines.len() - 1];
+
+        // Header nur bei eigenen Formaten; Base64 enthält nie ':'
+        let mut headers = Vec::new();
+        if body.first().is_some_and(|line| line.contains(':')) {
+            if !label.is_cryptomorph() {
+                return Err(err("Header sind bei diesem Typ nicht erlaubt"));
+            }
+            let end = body
+                .iter()
+                .position(|line| line.is_empty())
+                .ok_or_else(|| err("Leerzeile nach den Headern fehlt"))?;
+            for line in &body[..end] {
+                let (key, value) = line
+                    .split_once(": ")
+                    .filter(|(key, _)| is_header_key(key))
+                    .ok_or_else(|| err("ungültige Header-Zeile"))?;
+                headers.push((key.to_string(), value.to_string()));
+            }
+            body = &body[end + 1..];
+        }
+
+        let mut checksum = None;
+        if label.is_cryptomorph() {
+            let (last, rest) = body.split_last().ok_or_else(|| err("Prüfsumme fehlt"))?;
+            let crc = last
+                .strip_prefix('=')
+                .filter(|crc| crc.len() == 4)
+                .ok_or_else(|| err("Prüfsumme fehlt"))?;
+            checksum = Some(
+                STANDARD
+                    .decode(crc)
+                    .map_err(|_| err("ungültige Prüfsumme"))?,
+            );
+            body = rest;
+        }
+        if !body.iter().all(|line| is_base64_line(line)) {
+            return Err(err("ungültige Zeile im Base64-Teil"));
+        }
+        let bytes = STANDARD
+            .decode(body.concat())
+            .map_err(|_| err("ungültiges Base64"))?;
+        if let Some(crc) = checksum {
+            if crc != crc24(&bytes).to_be_bytes()[1..] {
+                return Err(err("Prüfsumme stimmt nicht"));
+            }
+        }
+        Ok(Armor {
+            label,
+            headers,
+            data: bytes,
+        })
+    }
+}
+
+/// Dekodiert Armor mit dem erwarteten Label, falls vorhanden; Binärdaten werden unverändert
+/// zurückgegeben.
+pub fn dearmor(data: &[u8], expected: Label) -> Result<Vec<u8>, CryptoError> {
+    if !is_armored(data) {
+        return Ok(data.to_vec());
+    }
+    let armor = Armor::decode(data)?;
+    if armor.label != expected {
+        return Err(CryptoError::InvalidFormat(format!(
+            "Armor-Typ {} statt {}",
+            armor.label.as_str(),
+            expected.as_str()
+        )));
+    }
+    Ok(armor.data)
+}
+
+/// Schreibt einen Armor-Block ohne Header fortlaufend, etwa für große Datenströme.
+/// Der Block wird erst mit [`ArmorWriter::finish`] abgeschlossen.
+pub struct ArmorWriter<W: Write> {
+    inner: W,
+    label: Label,
+    pending: Vec<u8>,
+    crc: u32,
+}
+
+/// Eingabebytes pro Ausgabezeile (64 Base64-Zeichen).
+const LINE_BYTES: usize = LINE_LEN / 4 * 3;
+
+impl<W: Write> ArmorWriter<W> {
+    pub fn new(mut inner: W, label: Label) -> io::Result<Self> {
+        writeln!(inner, "-----BEGIN {}-----", label.as_str())?;
+        Ok(ArmorWriter {
+            inner,
+            label,
+            pending: Vec::with_capacity(LINE_BYTES),
+            crc: CRC24_INIT,
+        })
+    }
+
+    fn write_line(&mut self) -> io::Result<()> {
+        let line = STANDARD.encode(&self.pending);
+        self.pending.clear();
+        self.inner.write_all(line.as_bytes())?;
+        self.inner.write_all(b"\n")
+    }
+
+    /// Schreibt den Rest, Prüfsumme und END-Zeile und gibt den inneren Writer zurück.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.pending.is_empty() {
+            self.write_line()?;
+        }
+        if self.label.is_cryptomorph() {
+            self.inner.write_all(checksum_line(self.crc).as_bytes())?;
+        }
+        writeln!(self.inner, "-----END {}-----", self.label.as_str())?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for ArmorWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let take = buf.len().min(LINE_BYTES - self.pending.len());
+        self.pending.extend_from_slice(&buf[..take]);
+        self.crc = crc24_update(self.crc, &buf[..take]);
+        if self.pending.len() == LINE_BYTES {
+            self.write_line()?;
+        }
+        Ok(take)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc24_check_value() {
+        // Prüfwert von CRC-24/OPENPGP
+        assert_eq!(crc24(b"123456789"), 0x21cf02);
+        assert_eq!(crc24(b""), CRC24_INIT);
+        assert_eq!(crc24_update(crc24(b"1234"), b"56789"), 0x21cf02);
+    }
+
+    #[test]
+    fn test_roundtrip_with_headers() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let armor = Armor::new(Label::Signature, data.clone())
+            .with_header("Comment", "Prüfung")
+            .unwrap();
+        let text = armor.encode();
+        assert!(is_armored(text.as_bytes()));
+        assert!(text.contains("\nComment: Prüfung\n\n"));
+        let decoded = Armor::decode(text.as_bytes()).unwrap();
+        assert_eq!(decoded, armor);
+        assert_eq!(decoded.header("Comment"), Some("Prüfung"));
+
+        // CRLF und umgebender Leerraum werden toleriert
+        let crlf = format!("\n  {}\n", text.replace('\n', "\r\n"));
+        assert_eq!(Armor::decode(crlf.as_bytes()).unwrap().data, data);
+        assert_eq!(dearmor(text.as_bytes(), Label::Signature).unwrap(), data);
+        assert_eq!(dearmor(&data, Label::Signature).unwrap(), data);
+        assert!(dearmor(text.as_bytes(), Label::Encrypted).is_err());
+
+        assert!(Armor::new(Label::Cms, vec![])
+            .with_header("A", "b")
+            .is_err());
+        assert!(Armor::new(Label::Sealed, vec![])
+            .with_header("A", "b\nc")
+            .is_err());
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        let text = Armor::new(Label::Encrypted, b"hallo welt".to_vec()).encode();
+        assert!(Armor::decode(text.as_bytes()).is_ok());
+
+        let crc_line = text.lines().find(|l| l.starts_with('=')).unwrap();
+        let cases = [
+            text.replace(crc_line, "=AAAA"),
+            text.replace(&format!("{}\n", crc_line), ""),
+            text.replacen("aGFs", "a!Fs", 1),
+            text.replacen("aGFs", "aGF", 1),
+            format!("Vorspann\n{}", text),
+            format!("{}garbage\n", text),
+            text.replace("END CRYPTOMORPH ENCRYPTED", "END CRYPTOMORPH SEALED"),
+            text.replacen("-----\n", "-----\nKein Header\n\n", 1),
+            text.replacen("-----\n", "-----\nComment: ohne Leerzeile\n", 1),
+            text.replace("ENCRYPTED FILE", "UNKNOWN FILE"),
+        ];
+        for case in cases {
+            assert!(Armor::decode(case.as_bytes()).is_err(), "{}", case);
+        }
+    }
+
+    #[test]
+    fn test_foreign_labels_are_plain_pem() {
+        let text = Armor::new(Label::Cms, vec![0x30, 0x00]).encode();
+        assert_eq!(text, "-----BEGIN CMS-----\nMAA=\n-----END CMS-----\n");
+        let pkcs7 = "-----BEGIN PKCS7-----\nMAA=\n-----END PKCS7-----\n";
+        assert_eq!(
+            dearmor(pkcs7.as_bytes(), Label::Cms).unwrap(),
+            vec![0x30, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_writer_matches_encode() {
+        for len in [0, 1, 47, 48, 49, 1000] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 7) as u8).collect();
+            for label in [Label::Sealed, Label::Age] {
+                let mut writer = ArmorWriter::new(Vec::new(), label).unwrap();
+                for chunk in data.chunks(13) {
+                    writer.write_all(chunk).unwrap();
+                }
+                let out = writer.finish().unwrap();
+                assert_eq!(
+                    String::from_utf8(out).unwrap(),
+                    Armor::new(label, data.clone()).encode()
+                );
+            }
+        }
+    }
+}
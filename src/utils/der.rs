@@ -0,0 +1,578 @@
+//! Minimaler DER-Kodierer und -Dekodierer (ITU-T X.690) für X.509 und verwandte Formate.
+//!
+//! Gelesen wird streng nach DER (definite, minimale Längen); unbekannte Elemente lassen sich
+//! als Rohdaten übernehmen. Geschrieben wird über kleine Hilfsfunktionen, die jeweils ein
+//! vollständiges TLV-Element liefern.
+
+use crate::utils::error::CryptoError;
+use num_bigint::BigUint;
+use std::fmt;
+
+pub const TAG_BOOLEAN: u8 = 0x01;
+pub const TAG_INTEGER: u8 = 0x02;
+pub const TAG_BIT_STRING: u8 = 0x03;
+pub const TAG_OCTET_STRING: u8 = 0x04;
+pub const TAG_NULL: u8 = 0x05;
+pub const TAG_OID: u8 = 0x06;
+pub const TAG_UTF8_STRING: u8 = 0x0c;
+pub const TAG_PRINTABLE_STRING: u8 = 0x13;
+pub const TAG_T61_STRING: u8 = 0x14;
+pub const TAG_IA5_STRING: u8 = 0x16;
+pub const TAG_UTC_TIME: u8 = 0x17;
+pub const TAG_GENERALIZED_TIME: u8 = 0x18;
+pub const TAG_BMP_STRING: u8 = 0x1e;
+pub const TAG_SEQUENCE: u8 = 0x30;
+pub const TAG_SET: u8 = 0x31;
+
+/// Tag eines kontextspezifischen, zusammengesetzten Elements `[n]`.
+pub const fn context(n: u8) -> u8 {
+    0xa0 | n
+}
+
+/// Tag eines kontextspezifischen, primitiven Elements `[n] IMPLICIT`.
+pub const fn context_primitive(n: u8) -> u8 {
+    0x80 | n
+}
+
+fn invalid(msg: &str) -> CryptoError {
+    CryptoError::InvalidFormat(format!("DER: {}", msg))
+}
+
+// ---------- Objektkennungen -----------
+
+/// Objektkennung (OBJECT IDENTIFIER) als Folge von Bögen.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Oid(Vec<u64>);
+
+impl Oid {
+    pub fn from_arcs(arcs: &[u64]) -> Self {
+        Oid(arcs.to_vec())
+    }
+
+    pub fn arcs(&self) -> &[u64] {
+        &self.0
+    }
+
+    /// Vergleicht mit einer Konstanten wie `&[1, 2, 840, 113549, 1, 1, 1]`.
+    pub fn is(&self, arcs: &[u64]) -> bool {
+        self.0 == arcs
+    }
+
+    fn decode(value: &[u8]) -> Result<Self, CryptoError> {
+        if value.is_empty() || value.last().is_some_and(|&b| b & 0x80 != 0) {
+            return Err(invalid("ungültige Objektkennung"));
+        }
+        let mut arcs = Vec::new();
+        let mut current: u64 = 0;
+        let mut start = true;
+        for &byte in value {
+            if start && byte == 0x80 {
+                return Err(invalid("nicht minimal kodierte Objektkennung"));
+            }
+            if current > u64::MAX >> 7 {
+                return Err(invalid("Objektkennung zu groß"));
+            }
+            current = (current << 7) | u64::from(byte & 0x7f);
+            start = byte & 0x80 == 0;
+            if start {
+                if arcs.is_empty() {
+                    let first = (current / 40).min(2);
+                    arcs.push(first);
+                    arcs.push(current - first * 40);
+                } else {
+                    arcs.push(current);
+                }
+                current = 0;
+            }
+        }
+        Ok(Oid(arcs))
+    }
+}
+
+impl fmt::Display for Oid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let arcs: Vec<String> = self.0.iter().map(u64::to_string).collect();
+        write!(f, "{}", arcs.join("."))
+    }
+}
+
+// ---------- Lesen -----------
+
+/// Ein gelesenes Element: Tag, Inhalt und vollständige Kodierung.
+#[derive(Debug, Clone, Copy)]
+pub struct Tlv<'a> {
+    pub tag: u8,
+    pub value: &'a [u8],
+    pub raw: &'a [u8],
+}
+
+/// Liest DER-Elemente nacheinander aus einem Puffer.
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    /// Liest das nächste Element unabhängig vom Tag.
+    pub fn read_any(&mut self) -> Result<Tlv<'a>, CryptoError> {
+        let start = self.pos;
+        let rest = &self.data[start..];
+        let (&tag, rest) = rest
+            .split_first()
+            .ok_or_else(|| invalid("Daten abgeschnitten"))?;
+        if tag & 0x1f == 0x1f {
+            return Err(invalid("mehrbytige Tags werden nicht unterstützt"));
+        }
+        let (&first, rest) = rest
+            .split_first()
+            .ok_or_else(|| invalid("Daten abgeschnitten"))?;
+        let (len, header) = match first {
+            0..=0x7f => (first as usize, 2),
+            0x80 => return Err(invalid("unbestimmte Länge ist in DER unzulässig")),
+            _ => {
+                let count = (first & 0x7f) as usize;
+                if count > 4 || rest.len() < count {
+                    return Err(invalid("ungültige Längenangabe"));
+                }
+                let len = rest[..count]
+                    .iter()
+                    .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+                if len < 0x80 || rest[0] == 0 {
+                    return Err(invalid("nicht minimal kodierte Länge"));
+                }
+                (len, 2 + count)
+            }
+        };
+        let end = start
+            .checked_add(header + len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| invalid("Daten abgeschnitten"))?;
+        self.pos = end;
+        Ok(Tlv {
+            tag,
+            value: &self.data[start + header..end],
+            raw: &self.data[start..end],
+        })
+    }
+
+    /// Liest ein Element mit erwartetem Tag und gibt die vollständige Kodierung zurück.
+    pub fn read_raw(&mut self, tag: u8) -> Result<&'a [u8], CryptoError> {
+        let tlv = self.read_any()?;
+        if tlv.tag != tag {
+            return Err(CryptoError::InvalidFormat(format!(
+                "DER: Tag 0x{:02x} erwartet, 0x{:02x} gefunden",
+                tag, tlv.tag
+            )));
+        }
+        Ok(tlv.raw)
+    }
+
+    /// Liest ein Element mit erwartetem Tag und gibt den Inhalt zurück.
+    pub fn read(&mut self, tag: u8) -> Result<&'a [u8], CryptoError> {
+        let raw = self.read_raw(tag)?;
+        let mut inner = Reader::new(raw);
+        Ok(inner.read_any()?.value)
+    }
+
+    /// Liest das nächste Element nur, wenn es das gegebene Tag hat.
+    pub fn optional(&mut self, tag: u8) -> Result<Option<&'a [u8]>, CryptoError> {
+        if self.peek_tag() == Some(tag) {
+            self.read(tag).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Liest ein zusammengesetztes Element und liefert einen Leser für dessen Inhalt.
+    pub fn nested(&mut self, tag: u8) -> Result<Reader<'a>, CryptoError> {
+        Ok(Reader::new(self.read(tag)?))
+    }
+
+    pub fn sequence(&mut self) -> Result<Reader<'a>, CryptoError> {
+        self.nested(TAG_SEQUENCE)
+    }
+
+    /// Liest eine nicht negative ganze Zahl.
+    pub fn uint(&mut self) -> Result<BigUint, CryptoError> {
+        let value = self.read(TAG_INTEGER)?;
+        match value.first() {
+            None => Err(invalid("leere Ganzzahl")),
+            Some(b) if b & 0x80 != 0 => Err(invalid("negative Ganzzahl")),
+            Some(_) => Ok(BigUint::from_bytes_be(value)),
+        }
+    }
+
+    /// Liest eine kleine nicht negative ganze Zahl (z. B. Versionsnummern).
+    pub fn small_uint(&mut self) -> Result<u64, CryptoError> {
+        let value = self.uint()?;
+        u64::try_from(value).map_err(|_| invalid("Ganzzahl zu groß"))
+    }
+
+    pub fn boolean(&mut self) -> Result<bool, CryptoError> {
+        match self.read(TAG_BOOLEAN)? {
+            [0x00] => Ok(false),
+            [0xff] => Ok(true),
+            _ => Err(invalid("ungültiger Wahrheitswert")),
+        }
+    }
+
+    pub fn oid(&mut self) -> Result<Oid, CryptoError> {
+        Oid::decode(self.read(TAG_OID)?)
+    }
+
+    pub fn null(&mut self) -> Result<(), CryptoError> {
+        match self.read(TAG_NULL)? {
+            [] => Ok(()),
+            _ => Err(invalid("NULL mit Inhalt")),
+        }
+    }
+
+    pub fn octet_string(&mut self) -> Result<&'a [u8], CryptoError> {
+        self.read(TAG_OCTET_STRING)
+    }
+
+    /// Liest einen BIT STRING ohne ungenutzte Bits (Schlüssel, Signaturen).
+    pub fn bit_string(&mut self) -> Result<&'a [u8], CryptoError> {
+        match self.read(TAG_BIT_STRING)?.split_first() {
+            Some((0, bytes)) => Ok(bytes),
+            _ => Err(invalid("BIT STRING mit ungenutzten Bits")),
+        }
+    }
+
+    /// Liest einen BIT STRING als Bitfeld (Bit 0 ist das höchstwertige Bit des ersten Bytes).
+    pub fn bit_flags(&mut self) -> Result<u32, CryptoError> {
+        let value = self.read(TAG_BIT_STRING)?;
+        let (&unused, bytes) = value
+            .split_first()
+            .ok_or_else(|| invalid("leerer BIT STRING"))?;
+        if unused > 7 || bytes.len() > 4 || (bytes.is_empty() && unused != 0) {
+            return Err(invalid("ungültiger BIT STRING"));
+        }
+        let mut flags = 0u32;
+        for (i, &byte) in bytes.iter().enumerate() {
+            for bit in 0..8 {
+                if byte & (0x80 >> bit) != 0 {
+                    flags |= 1 << (i * 8 + bit);
+                }
+            }
+        }
+        Ok(flags)
+    }
+
+    /// Liest eine Zeichenkette (UTF8String, PrintableString, IA5String, T61String, BMPString).
+    pub fn string(&mut self) -> Result<String, CryptoError> {
+        let tlv = self.read_any()?;
+        match tlv.tag {
+            TAG_UTF8_STRING | TAG_PRINTABLE_STRING | TAG_IA5_STRING => {
+                String::from_utf8(tlv.value.to_vec()).map_err(|_| invalid("ungültiges UTF-8"))
+            }
+            // T61String wird in der Praxis als Latin-1 verwendet
+            TAG_T61_STRING => Ok(tlv.value.iter().map(|&b| b as char).collect()),
+            TAG_BMP_STRING if tlv.value.len() % 2 == 0 => {
+                let units: Vec<u16> = tlv
+                    .value
+                    .chunks(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                    .collect();
+                String::from_utf16(&units).map_err(|_| invalid("ungültiger BMPString"))
+            }
+            _ => Err(CryptoError::InvalidFormat(format!(
+                "DER: Zeichenkette erwartet, Tag 0x{:02x} gefunden",
+                tlv.tag
+            ))),
+        }
+    }
+
+    /// Liest UTCTime oder GeneralizedTime und gibt Sekunden seit 1970 (UTC) zurück.
+    pub fn time(&mut self) -> Result<i64, CryptoError> {
+        let tlv = self.read_any()?;
+        let text = std::str::from_utf8(tlv.value).map_err(|_| invalid("ungültige Zeitangabe"))?;
+        let digits = text
+            .strip_suffix('Z')
+            .filter(|d| d.bytes().all(|b| b.is_ascii_digit()))
+            .ok_or_else(|| invalid("Zeitangabe muss in UTC (Z) vorliegen"))?;
+        let (year, rest) = match (tlv.tag, digits.len()) {
+            (TAG_UTC_TIME, 12) => {
+                let yy: i64 = digits[..2].parse().unwrap();
+                (if yy >= 50 { 1900 + yy } else { 2000 + yy }, &digits[2..])
+            }
+            (TAG_GENERALIZED_TIME, 14) => (digits[..4].parse().unwrap(), &digits[4..]),
+            _ => return Err(invalid("ungültige Zeitangabe")),
+        };
+        let field = |i: usize| -> u32 { rest[i * 2..i * 2 + 2].parse().unwrap() };
+        let (month, day, hour, minute, second) = (field(0), field(1), field(2), field(3), field(4));
+        if !(1..=12).contains(&month)
+            || !(1..=31).contains(&day)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return Err(invalid("ungültige Zeitangabe"));
+        }
+        Ok(days_from_civil(year, month, day) * 86_400
+            + i64::from(hour * 3600 + minute * 60 + second))
+    }
+
+    /// Stellt sicher, dass alle Daten gelesen wurden.
+    pub fn finish(&self) -> Result<(), CryptoError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid("überzählige Daten am Ende"))
+        }
+    }
+}
+
+// ---------- Schreiben -----------
+
+/// Kodiert ein Element aus Tag und Inhalt.
+pub fn tlv(tag: u8, value: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = value.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let skip = bytes.iter().take_while(|&&b| b == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(value);
+    out
+}
+
+/// SEQUENCE aus bereits kodierten Elementen.
+pub fn sequence(items: &[Vec<u8>]) -> Vec<u8> {
+    tlv(TAG_SEQUENCE, &items.concat())
+}
+
+/// SET OF aus bereits kodierten Elementen, in DER-Reihenfolge sortiert.
+pub fn set_of(items: &[Vec<u8>]) -> Vec<u8> {
+    let mut sorted = items.to_vec();
+    sorted.sort();
+    tlv(TAG_SET, &sorted.concat())
+}
+
+/// Zusammengesetztes kontextspezifisches Element `[n]` (EXPLICIT).
+pub fn explicit(n: u8, inner: &[u8]) -> Vec<u8> {
+    tlv(context(n), inner)
+}
+
+/// Nicht negative ganze Zahl aus Big-Endian-Bytes.
+pub fn uint_bytes(bytes: &[u8]) -> Vec<u8> {
+    let skip = bytes.iter().take_while(|&&b| b == 0).count();
+    let mut value = Vec::with_capacity(bytes.len() - skip + 1);
+    if bytes[skip..].first().is_none_or(|&b| b & 0x80 != 0) {
+        value.push(0);
+    }
+    value.extend_from_slice(&bytes[skip..]);
+    tlv(TAG_INTEGER, &value)
+}
+
+pub fn uint(value: &BigUint) -> Vec<u8> {
+    uint_bytes(&value.to_bytes_be())
+}
+
+pub fn small_uint(value: u64) -> Vec<u8> {
+    uint_bytes(&value.to_be_bytes())
+}
+
+pub fn boolean(value: bool) -> Vec<u8> {
+    tlv(TAG_BOOLEAN, &[if value { 0xff } else { 0x00 }])
+}
+
+pub fn null() -> Vec<u8> {
+    tlv(TAG_NULL, &[])
+}
+
+pub fn oid(arcs: &[u64]) -> Vec<u8> {
+    assert!(arcs.len() >= 2 && arcs[0] <= 2, "ungültige Objektkennung");
+    let mut value = Vec::new();
+    for arc in std::iter::once(arcs[0] * 40 + arcs[1]).chain(arcs[2..].iter().copied()) {
+        let mut groups = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            groups.push(0x80 | (rest & 0x7f) as u8);
+            rest >>= 7;
+        }
+        value.extend(groups.iter().rev());
+    }
+    tlv(TAG_OID, &value)
+}
+
+pub fn octet_string(bytes: &[u8]) -> Vec<u8> {
+    tlv(TAG_OCTET_STRING, bytes)
+}
+
+/// BIT STRING ohne ungenutzte Bits.
+pub fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    let mut value = vec![0];
+    value.extend_from_slice(bytes);
+    tlv(TAG_BIT_STRING, &value)
+}
+
+/// BIT STRING aus einem Bitfeld, mit entfernten Null-Bits am Ende (X.690, 11.2.2).
+pub fn bit_flags(flags: u32) -> Vec<u8> {
+    let bits = 32 - flags.leading_zeros() as usize;
+    let bit_at = |i: usize| flags >> i & 1 == 1;
+    let mut bytes = vec![0u8; bits.div_ceil(8)];
+    for i in (0..bits).filter(|&i| bit_at(i)) {
+        bytes[i / 8] |= 0x80 >> (i % 8);
+    }
+    let unused = (bytes.len() * 8 - bits) as u8;
+    let mut value = vec![unused];
+    value.extend_from_slice(&bytes);
+    tlv(TAG_BIT_STRING, &value)
+}
+
+pub fn utf8_string(s: &str) -> Vec<u8> {
+    tlv(TAG_UTF8_STRING, s.as_bytes())
+}
+
+pub fn printable_string(s: &str) -> Vec<u8> {
+    tlv(TAG_PRINTABLE_STRING, s.as_bytes())
+}
+
+pub fn ia5_string(s: &str) -> Vec<u8> {
+    tlv(TAG_IA5_STRING, s.as_bytes())
+}
+
+/// BMPString (UTF-16BE), z. B. für friendlyName in PKCS#12.
+pub fn bmp_string(s: &str) -> Vec<u8> {
+    let bytes: Vec<u8> = s.encode_utf16().flat_map(u16::to_be_bytes).collect();
+    tlv(TAG_BMP_STRING, &bytes)
+}
+
+/// Zeitangabe nach RFC 5280, 4.1.2.5: UTCTime bis 2049, danach GeneralizedTime.
+pub fn time(unix: i64) -> Vec<u8> {
+    let (year, month, day, hour, minute, second) = civil_time(unix);
+    if (1950..2050).contains(&year) {
+        let text = format!(
+            "{:02}{:02}{:02}{:02}{:02}{:02}Z",
+            year % 100,
+            month,
+            day,
+            hour,
+            minute,
+            second
+        );
+        tlv(TAG_UTC_TIME, text.as_bytes())
+    } else {
+        let text = format!(
+            "{:04}{:02}{:02}{:02}{:02}{:02}Z",
+            year, month, day, hour, minute, second
+        );
+        tlv(TAG_GENERALIZED_TIME, text.as_bytes())
+    }
+}
+
+// ---------- Kalender -----------
+
+/// Tage seit 1970-01-01 für ein Datum im gregorianischen Kalender.
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = i64::from((month + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Zerlegt Sekunden seit 1970 in (Jahr, Monat, Tag, Stunde, Minute, Sekunde).
+fn civil_time(unix: i64) -> (i64, u32, u32, u32, u32, u32) {
+    let days = unix.div_euclid(86_400) + 719_468;
+    let secs = unix.rem_euclid(86_400) as u32;
+    let era = days.div_euclid(146_097);
+    let doe = days - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day, secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Formatiert Sekunden seit 1970 als `JJJJ-MM-TT hh:mm:ss UTC`.
+pub fn format_time(unix: i64) -> String {
+    let (year, month, day, hour, minute, second) = civil_time(unix);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year, month, day, hour, minute, second
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer_oid_and_length_encoding() {
+        assert_eq!(small_uint(0), vec![0x02, 0x01, 0x00]);
+        assert_eq!(small_uint(128), vec![0x02, 0x02, 0x00, 0x80]);
+        assert_eq!(uint_bytes(&[0, 0, 1]), vec![0x02, 0x01, 0x01]);
+
+        let rsa = [1, 2, 840, 113_549, 1, 1, 1];
+        let encoded = oid(&rsa);
+        assert_eq!(
+            encoded,
+            [0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01]
+        );
+        let parsed = Reader::new(&encoded).oid().unwrap();
+        assert!(parsed.is(&rsa));
+        assert_eq!(parsed.to_string(), "1.2.840.113549.1.1.1");
+        assert!(Reader::new(&oid(&[2, 999, 3]))
+            .oid()
+            .unwrap()
+            .is(&[2, 999, 3]));
+
+        let long = octet_string(&[7u8; 300]);
+        assert_eq!(&long[..4], &[0x04, 0x82, 0x01, 0x2c]);
+        assert_eq!(Reader::new(&long).octet_string().unwrap().len(), 300);
+
+        // nicht minimale Länge und unbestimmte Länge
+        assert!(Reader::new(&[0x04, 0x81, 0x01, 0x00]).read_any().is_err());
+        assert!(Reader::new(&[0x30, 0x80, 0x00, 0x00]).read_any().is_err());
+        assert!(Reader::new(&[0x04, 0x05, 0x00]).read_any().is_err());
+    }
+
+    #[test]
+    fn test_time_roundtrip() {
+        for (unix, tag) in [
+            (0, TAG_UTC_TIME),
+            (951_782_400, TAG_UTC_TIME),             // 2000-02-29
+            (2_524_608_000, TAG_GENERALIZED_TIME),   // 2050-01-01
+            (253_402_300_799, TAG_GENERALIZED_TIME), // 9999-12-31 23:59:59
+        ] {
+            let encoded = time(unix);
+            assert_eq!(encoded[0], tag);
+            assert_eq!(Reader::new(&encoded).time().unwrap(), unix);
+        }
+        assert_eq!(format_time(951_782_400), "2000-02-29 00:00:00 UTC");
+        let no_zone = tlv(TAG_UTC_TIME, b"250101000000");
+        assert!(Reader::new(&no_zone).time().is_err());
+    }
+
+    #[test]
+    fn test_bit_flags() {
+        // digitalSignature (0) und keyCertSign (5): 0x84, zwei ungenutzte Bits
+        let encoded = bit_flags(0b10_0001);
+        assert_eq!(encoded, vec![0x03, 0x02, 0x02, 0x84]);
+        assert_eq!(Reader::new(&encoded).bit_flags().unwrap(), 0b10_0001);
+        // decipherOnly (8) benötigt ein zweites Byte
+        assert_eq!(bit_flags(1 << 8), vec![0x03, 0x03, 0x07, 0x00, 0x80]);
+    }
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+/// Fehler, die beim Verarbeiten von Cryptomorph-Daten auftreten können.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CryptoError {
+    /// Eingabedaten sind nicht im erwarteten Format.
+    InvalidFormat(String),
+    /// Keiner der Empfängereinträge passt zum gegebenen Schlüssel.
+    NoMatchingRecipient,
+    /// Authentifizierung oder Entschlüsselung ist fehlgeschlagen.
+    DecryptionFailed,
+    /// Die Signatur passt nicht zum erwarteten Absender.
+    InvalidSignature,
+    /// Der Absender hat die Nachricht nicht für diesen Empfänger signiert
+    /// (z. B. bei unbemerkter Weiterleitung durch einen anderen Empfänger).
+    NotSignedForRecipient,
+    /// Algorithmus oder Formatvariante wird nicht unterstützt.
+    Unsupported(String),
+    /// Zertifikat oder Zertifikatspfad ist nicht gültig (abgelaufen, nicht vertrauenswürdig,
+    /// unzulässige Erweiterungen usw.).
+    InvalidCertificate(String),
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CryptoError::InvalidFormat(msg) => write!(f, "Ungültiges Format: {}", msg),
+            CryptoError::NoMatchingRecipient => {
+                write!(f, "Kein Empfängereintrag passt zum Schlüssel")
+            }
+            CryptoError::DecryptionFailed => write!(f, "Entschlüsselung fehlgeschlagen"),
+            CryptoError::InvalidSignature => write!(f, "Signatur ungültig"),
+            CryptoError::NotSignedForRecipient => write!(
+                f,
+                "Nachricht wurde vom Absender nicht für diesen Empfänger signiert"
+            ),
+            CryptoError::Unsupported(what) => write!(f, "Nicht unterstützt: {}", what),
+            CryptoError::InvalidCertificate(why) => write!(f, "Zertifikat ungültig: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
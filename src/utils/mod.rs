@@ -0,0 +1,3 @@
+pub mod armor;
+pub mod der;
+pub mod error;
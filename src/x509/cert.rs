@@ -0,0 +1,394 @@
+//! X.509-v3-Zertifikate (RFC 5280, 4.1): Lesen, Schreiben und Ausstellen.
+
+use crate::utils::der::{self, Reader};
+use crate::utils::error::CryptoError;
+use crate::x509::extensions::{self, BasicConstraints, Extension, GeneralName, KeyUsage};
+use crate::x509::key::{SignatureAlgorithm, SigningKey, SubjectPublicKey};
+use crate::x509::name::Name;
+use crate::x509::{pem_decode, pem_decode_all, pem_encode, PEM_CERTIFICATE};
+use num_bigint::BigUint;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Ein gelesenes oder ausgestelltes Zertifikat.
+#[derive(Debug, Clone)]
+pub struct Certificate {
+    pub version: u8,
+    pub serial: BigUint,
+    pub signature_algorithm: SignatureAlgorithm,
+    pub issuer: Name,
+    pub subject: Name,
+    /// Gültigkeitszeitraum in Sekunden seit 1970 (UTC).
+    pub not_before: i64,
+    pub not_after: i64,
+    pub public_key: SubjectPublicKey,
+    pub extensions: Vec<Extension>,
+    pub signature: Vec<u8>,
+    tbs: Vec<u8>,
+    der: Vec<u8>,
+}
+
+impl Certificate {
+    pub fn from_der(data: &[u8]) -> Result<Self, CryptoError> {
+        let mut outer = Reader::new(data);
+        let raw = outer.read_raw(der::TAG_SEQUENCE)?;
+        outer.finish()?;
+        let mut cert = Reader::new(raw).sequence()?;
+        let tbs_raw = cert.read_raw(der::TAG_SEQUENCE)?;
+        let outer_alg = SignatureAlgorithm::read(&mut cert)?;
+        let signature = cert.bit_string()?.to_vec();
+        cert.finish()?;
+
+        let mut tbs = Reader::new(tbs_raw).sequence()?;
+        let version = match tbs.optional(der::context(0))? {
+            Some(inner) => {
+                let mut inner = Reader::new(inner);
+                let v = inner.small_uint()?;
+                inner.finish()?;
+                v
+            }
+            None => 0,
+        };
+        if version > 2 {
+            return Err(CryptoError::Unsupported(format!(
+                "X.509-Version {}",
+                version + 1
+            )));
+        }
+        let serial = tbs.uint()?;
+        if SignatureAlgorithm::read(&mut tbs)? != outer_alg {
+            return Err(CryptoError::InvalidFormat(
+                "Signaturverfahren im Zertifikat widersprüchlich".to_string(),
+            ));
+        }
+        let issuer = Name::from_der(tbs.read_raw(der::TAG_SEQUENCE)?)?;
+        let mut validity = tbs.sequence()?;
+        let not_before = validity.time()?;
+        let not_after = validity.time()?;
+        validity.finish()?;
+        let subject = Name::from_der(tbs.read_raw(der::TAG_SEQUENCE)?)?;
+        let public_key = SubjectPublicKey::from_spki(tbs.read_raw(der::TAG_SEQUENCE)?)?;
+        // issuerUniqueID und subjectUniqueID werden übersprungen
+        tbs.optional(der::context_primitive(1))?;
+        tbs.optional(der::context_primitive(2))?;
+        let extensions = match tbs.optional(der::context(3))? {
+            Some(content) if version == 2 => extensions::read_extensions(content)?,
+            Some(_) => {
+                return Err(CryptoError::InvalidFormat(
+                    "Erweiterungen erst ab X.509 v3 zulässig".to_string(),
+                ))
+            }
+            None => Vec::new(),
+        };
+        tbs.finish()?;
+
+        Ok(Certificate {
+            version: version as u8 + 1,
+            serial,
+            signature_algorithm: outer_alg,
+            issuer,
+            subject,
+            not_before,
+            not_after,
+            public_key,
+            extensions,
+            signature,
+            tbs: tbs_raw.to_vec(),
+            der: raw.to_vec(),
+        })
+    }
+
+    pub fn from_pem(text: &str) -> Result<Self, CryptoError> {
+        Certificate::from_der(&pem_decode(text, PEM_CERTIFICATE)?)
+    }
+
+    /// Liest alle Zertifikate einer PEM-Datei, z. B. einer Kette oder eines Bündels.
+    pub fn from_pem_chain(text: &str) -> Result<Vec<Self>, CryptoError> {
+        pem_decode_all(text, PEM_CERTIFICATE)?
+            .iter()
+            .map(|der| Certificate::from_der(der))
+            .collect()
+    }
+
+    /// Liest DER oder PEM, je nach Inhalt.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, CryptoError> {
+        match std::str::from_utf8(data) {
+            Ok(text) if text.contains("-----BEGIN") => Certificate::from_pem(text),
+            _ => Certificate::from_der(data),
+        }
+    }
+
+    pub fn to_der(&self) -> &[u8] {
+        &self.der
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem_encode(PEM_CERTIFICATE, &self.der)
+    }
+
+    /// SHA-256-Fingerabdruck über das DER-kodierte Zertifikat.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        Sha256::digest(&self.der).into()
+    }
+
+    /// Prüft die Signatur mit dem öffentlichen Schlüssel des Ausstellers.
+    pub fn verify_signature(&self, issuer_key: &SubjectPublicKey) -> Result<(), CryptoError> {
+        if self
+            .signature_algorithm
+            .verify(issuer_key, &self.tbs, &self.signature)
+        {
+            Ok(())
+        } else {
+            Err(CryptoError::InvalidSignature)
+        }
+    }
+
+    /// Aussteller und Inhaber stimmen überein (RFC 5280, 3.3 "self-issued").
+    pub fn is_self_issued(&self) -> bool {
+        self.issuer.matches(&self.subject)
+    }
+
+    pub fn is_valid_at(&self, time: i64) -> bool {
+        self.not_before <= time && time <= self.not_after
+    }
+
+    pub fn is_ca(&self) -> bool {
+        matches!(
+            self.basic_constraints(),
+            Ok(Some(BasicConstraints { ca: true, .. }))
+        )
+    }
+
+    pub fn subject_alt_names(&self) -> Result<Vec<GeneralName>, CryptoError> {
+        extensions::subject_alt_names(&self.extensions)
+    }
+
+    pub fn key_usage(&self) -> Result<Option<KeyUsage>, CryptoError> {
+        extensions::key_usage(&self.extensions)
+    }
+
+    pub fn basic_constraints(&self) -> Result<Option<BasicConstraints>, CryptoError> {
+        extensions::basic_constraints(&self.extensions)
+    }
+
+    pub fn extended_key_usage(&self) -> Result<Vec<der::Oid>, CryptoError> {
+        extensions::extended_key_usage(&self.extensions)
+    }
+
+    pub fn subject_key_id(&self) -> Result<Option<Vec<u8>>, CryptoError> {
+        extensions::subject_key_id(&self.extensions)
+    }
+
+    pub fn authority_key_id(&self) -> Result<Option<Vec<u8>>, CryptoError> {
+        extensions::authority_key_id(&self.extensions)
+    }
+
+    /// Stellt ein selbstsigniertes Zertifikat aus (z. B. für eine Wurzel-CA).
+    pub fn self_signed(params: &CertificateParams, key: &SigningKey) -> Result<Self, CryptoError> {
+        let public_key = key.public_key();
+        let key_id = public_key.key_id();
+        build(params, &public_key, &params.subject, &key_id, key)
+    }
+
+    /// Stellt mit dem Zertifikat und Schlüssel einer CA ein Zertifikat für `subject_key` aus.
+    pub fn issue(
+        params: &CertificateParams,
+        subject_key: &SubjectPublicKey,
+        issuer: &Certificate,
+        issuer_key: &SigningKey,
+    ) -> Result<Self, CryptoError> {
+        if issuer_key.public_key() != issuer.public_key {
+            return Err(CryptoError::InvalidFormat(
+                "Schlüssel passt nicht zum Ausstellerzertifikat".to_string(),
+            ));
+        }
+        if !issuer.is_ca() {
+            return Err(CryptoError::InvalidCertificate(format!(
+                "Aussteller \"{}\" ist keine CA",
+                issuer.subject
+            )));
+        }
+        if issuer
+            .key_usage()?
+            .is_some_and(|usage| !usage.contains(KeyUsage::KEY_CERT_SIGN))
+        {
+            return Err(CryptoError::InvalidCertificate(format!(
+                "Aussteller \"{}\" darf keine Zertifikate signieren",
+                issuer.subject
+            )));
+        }
+        let key_id = match issuer.subject_key_id()? {
+            Some(id) => id,
+            None => issuer.public_key.key_id(),
+        };
+        build(params, subject_key, &issuer.subject, &key_id, issuer_key)
+    }
+}
+
+/// Inhalt eines auszustellenden Zertifikats.
+#[derive(Debug, Clone)]
+pub struct CertificateParams {
+    pub subject: Name,
+    /// Seriennummer; ohne Angabe 16 zufällige Byte (RFC 5280, 4.1.2.2: höchstens 20 Byte).
+    pub serial: Option<BigUint>,
+    pub not_before: i64,
+    pub not_after: i64,
+    pub subject_alt_names: Vec<GeneralName>,
+    pub key_usage: Option<KeyUsage>,
+    pub extended_key_usage: Vec<&'static [u64]>,
+    pub basic_constraints: BasicConstraints,
+}
+
+impl CertificateParams {
+    /// Endstellen-Zertifikat, gültig ab `not_before` für `days` Tage.
+    pub fn new(subject: Name, not_before: i64, days: u32) -> Self {
+        CertificateParams {
+            subject,
+            serial: None,
+            not_before,
+            not_after: not_before + i64::from(days) * 86_400,
+            subject_alt_names: Vec::new(),
+            key_usage: None,
+            extended_key_usage: Vec::new(),
+            basic_constraints: BasicConstraints {
+                ca: false,
+                path_len: None,
+            },
+        }
+    }
+
+    /// Macht das Zertifikat zu einer CA (keyCertSign, cRLSign) mit optionaler Pfadlänge.
+    pub fn with_ca(mut self, path_len: Option<u32>) -> Self {
+        self.basic_constraints = BasicConstraints { ca: true, path_len };
+        self.key_usage = Some(KeyUsage::KEY_CERT_SIGN.union(KeyUsage::CRL_SIGN));
+        self
+    }
+
+    pub fn with_subject_alt_names(mut self, names: Vec<GeneralName>) -> Self {
+        self.subject_alt_names = names;
+        self
+    }
+
+    fn extensions(
+        &self,
+        subject_key: &SubjectPublicKey,
+        authority_key_id: &[u8],
+    ) -> Vec<Extension> {
+        let mut extensions = vec![Extension::basic_constraints(self.basic_constraints)];
+        if let Some(usage) = self.key_usage {
+            extensions.push(Extension::key_usage(usage));
+        }
+        if !self.extended_key_usage.is_empty() {
+            extensions.push(Extension::extended_key_usage(&self.extended_key_usage));
+        }
+        if !self.subject_alt_names.is_empty() {
+            extensions.push(Extension::subject_alt_name(&self.subject_alt_names));
+        }
+        extensions.push(Extension::subject_key_identifier(&subject_key.key_id()));
+        extensions.push(Extension::authority_key_identifier(authority_key_id));
+        extensions
+    }
+}
+
+fn random_serial() -> BigUint {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    // positiv und nicht null
+    bytes[0] = (bytes[0] & 0x7f) | 0x01;
+    BigUint::from_bytes_be(&bytes)
+}
+
+fn build(
+    params: &CertificateParams,
+    subject_key: &SubjectPublicKey,
+    issuer: &Name,
+    authority_key_id: &[u8],
+    signer: &SigningKey,
+) -> Result<Certificate, CryptoError> {
+    if params.not_after <= params.not_before {
+        return Err(CryptoError::InvalidFormat(
+            "Gültigkeitsende liegt vor dem Beginn".to_string(),
+        ));
+    }
+    let serial = params.serial.clone().unwrap_or_else(random_serial);
+    if serial.bits() == 0 || serial.bits() > 159 {
+        return Err(CryptoError::InvalidFormat(
+            "Seriennummer muss positiv und höchstens 20 Byte lang sein".to_string(),
+        ));
+    }
+    let alg = signer.signature_algorithm().to_der();
+    let extensions = params.extensions(subject_key, authority_key_id);
+    let tbs = der::sequence(&[
+        der::explicit(0, &der::small_uint(2)),
+        der::uint(&serial),
+        alg.clone(),
+        issuer.to_der().to_vec(),
+        der::sequence(&[der::time(params.not_before), der::time(params.not_after)]),
+        params.subject.to_der().to_vec(),
+        subject_key.to_spki(),
+        der::explicit(3, &extensions::extensions_to_der(&extensions)),
+    ]);
+    let signature = signer.sign(&tbs)?;
+    Certificate::from_der(&der::sequence(&[tbs, alg, der::bit_string(&signature)]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asymmetric::{ecdsa, ed25519};
+    use crate::x509::oid;
+
+    #[test]
+    fn test_self_signed_and_issued_roundtrip() {
+        let (_, ca_priv) = ed25519::generate_ed25519_keypair();
+        let ca_key = SigningKey::from_ed25519(&ca_priv);
+        let ca_params = CertificateParams::new(
+            Name::parse("CN=Test-CA, O=Cryptomorph").unwrap(),
+            1_700_000_000,
+            365,
+        )
+        .with_ca(Some(0));
+        let ca = Certificate::self_signed(&ca_params, &ca_key).unwrap();
+        assert_eq!(ca.version, 3);
+        assert!(ca.is_ca() && ca.is_self_issued());
+        ca.verify_signature(&ca.public_key).unwrap();
+        assert_eq!(ca.subject_key_id().unwrap(), ca.authority_key_id().unwrap());
+
+        let (p_pub, p_priv) = ecdsa::generate_p256_keypair();
+        let leaf_key = SigningKey::P256 {
+            public: p_pub,
+            private: p_priv,
+        };
+        let mut params =
+            CertificateParams::new(Name::parse("CN=server").unwrap(), 1_700_000_000, 30)
+                .with_subject_alt_names(vec![GeneralName::parse("server.example").unwrap()]);
+        params.extended_key_usage = vec![oid::KP_SERVER_AUTH];
+        params.serial = Some(BigUint::from(4711u32));
+        let leaf = Certificate::issue(&params, &leaf_key.public_key(), &ca, &ca_key).unwrap();
+
+        let parsed = Certificate::from_pem(&leaf.to_pem()).unwrap();
+        assert_eq!(parsed.to_der(), leaf.to_der());
+        assert_eq!(parsed.serial, BigUint::from(4711u32));
+        assert!(parsed.issuer.matches(&ca.subject));
+        assert_eq!(parsed.not_after - parsed.not_before, 30 * 86_400);
+        assert!(!parsed.is_ca());
+        assert_eq!(
+            parsed.subject_alt_names().unwrap(),
+            vec![GeneralName::Dns("server.example".to_string())]
+        );
+        assert_eq!(
+            parsed.authority_key_id().unwrap(),
+            ca.subject_key_id().unwrap()
+        );
+        parsed.verify_signature(&ca.public_key).unwrap();
+        assert_eq!(
+            parsed.verify_signature(&parsed.public_key),
+            Err(CryptoError::InvalidSignature)
+        );
+
+        // Endstellen dürfen nicht ausstellen, Schlüssel müssen zum Zertifikat passen
+        assert!(Certificate::issue(&params, &ca.public_key, &leaf, &leaf_key).is_err());
+        assert!(Certificate::issue(&params, &ca.public_key, &ca, &leaf_key).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+//! Zertifikatsanforderungen nach PKCS#10 (RFC 2986) mit Erweiterungswunsch (extensionRequest).
+
+use crate::utils::der::{self, Reader};
+use crate::utils::error::CryptoError;
+use crate::x509::extensions::{self, Extension, GeneralName};
+use crate::x509::key::{SignatureAlgorithm, SigningKey, SubjectPublicKey};
+use crate::x509::name::Name;
+use crate::x509::{oid, pem_decode, pem_encode, PEM_CERTIFICATE_REQUEST};
+
+/// Eine gelesene oder erzeugte Zertifikatsanforderung.
+#[derive(Debug, Clone)]
+pub struct CertificationRequest {
+    pub subject: Name,
+    pub public_key: SubjectPublicKey,
+    /// Gewünschte Erweiterungen aus dem Attribut extensionRequest (PKCS#9).
+    pub extensions: Vec<Extension>,
+    pub signature_algorithm: SignatureAlgorithm,
+    pub signature: Vec<u8>,
+    info: Vec<u8>,
+    der: Vec<u8>,
+}
+
+impl CertificationRequest {
+    /// Erzeugt und signiert eine Anforderung; alternative Namen werden als
+    /// extensionRequest aufgenommen.
+    pub fn create(
+        subject: &Name,
+        subject_alt_names: &[GeneralName],
+        key: &SigningKey,
+    ) -> Result<Self, CryptoError> {
+        let mut attributes = Vec::new();
+        if !subject_alt_names.is_empty() {
+            let requested =
+                extensions::extensions_to_der(&[Extension::subject_alt_name(subject_alt_names)]);
+            attributes.push(der::sequence(&[
+                der::oid(oid::EXTENSION_REQUEST),
+                der::set_of(&[requested]),
+            ]));
+        }
+        let info = der::sequence(&[
+            der::small_uint(0),
+            subject.to_der().to_vec(),
+            key.public_key().to_spki(),
+            der::tlv(der::context(0), &attributes.concat()),
+        ]);
+        let alg = key.signature_algorithm().to_der();
+        let signature = key.sign(&info)?;
+        CertificationRequest::from_der(&der::sequence(&[info, alg, der::bit_string(&signature)]))
+    }
+
+    pub fn from_der(data: &[u8]) -> Result<Self, CryptoError> {
+        let mut outer = Reader::new(data);
+        let raw = outer.read_raw(der::TAG_SEQUENCE)?;
+        outer.finish()?;
+        let mut csr = Reader::new(raw).sequence()?;
+        let info_raw = csr.read_raw(der::TAG_SEQUENCE)?;
+        let signature_algorithm = SignatureAlgorithm::read(&mut csr)?;
+        let signature = csr.bit_string()?.to_vec();
+        csr.finish()?;
+
+        let mut info = Reader::new(info_raw).sequence()?;
+        if info.small_uint()? != 0 {
+            return Err(CryptoError::Unsupported("PKCS#10-Version".to_string()));
+        }
+        let subject = Name::from_der(info.read_raw(der::TAG_SEQUENCE)?)?;
+        let public_key = SubjectPublicKey::from_spki(info.read_raw(der::TAG_SEQUENCE)?)?;
+        let mut attributes = info.nested(der::context(0))?;
+        info.finish()?;
+
+        let mut requested = Vec::new();
+        while !attributes.is_empty() {
+            let mut attribute = attributes.sequence()?;
+            let attr = attribute.oid()?;
+            let mut values = attribute.nested(der::TAG_SET)?;
+            attribute.finish()?;
+            if attr.is(oid::EXTENSION_REQUEST) {
+                requested = extensions::read_extensions(values.read_raw(der::TAG_SEQUENCE)?)?;
+                values.finish()?;
+            }
+        }
+
+        Ok(CertificationRequest {
+            subject,
+            public_key,
+            extensions: requested,
+            signature_algorithm,
+            signature,
+            info: info_raw.to_vec(),
+            der: raw.to_vec(),
+        })
+    }
+
+    pub fn from_pem(text: &str) -> Result<Self, CryptoError> {
+        // ältere Werkzeuge schreiben "NEW CERTIFICATE REQUEST"
+        pem_decode(text, PEM_CERTIFICATE_REQUEST)
+            .or_else(|_| pem_decode(text, "NEW CERTIFICATE REQUEST"))
+            .and_then(|der| CertificationRequest::from_der(&der))
+    }
+
+    pub fn to_der(&self) -> &[u8] {
+        &self.der
+    }
+
+    pub fn to_pem(&self) -> String {
+        pem_encode(PEM_CERTIFICATE_REQUEST, &self.der)
+    }
+
+    /// Prüft die Eigensignatur (Besitznachweis für den privaten Schlüssel).
+    pub fn verify(&self) -> Result<(), CryptoError> {
+        if self
+            .signature_algorithm
+            .verify(&self.public_key, &self.info, &self.signature)
+        {
+            Ok(())
+        } else {
+            Err(CryptoError::InvalidSignature)
+        }
+    }
+
+    pub fn subject_alt_names(&self) -> Result<Vec<GeneralName>, CryptoError> {
+        extensions::subject_alt_names(&self.extensions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asymmetric::ed25519;
+
+    #[test]
+    fn test_create_parse_verify() {
+        let (_, private) = ed25519::generate_ed25519_keypair();
+        let key = SigningKey::from_ed25519(&private);
+        let names = vec![
+            GeneralName::parse("app.example").unwrap(),
+            GeneralName::parse("ip:192.168.1.10").unwrap(),
+        ];
+        let subject = Name::parse("CN=app.example, O=Beispiel").unwrap();
+        let csr = CertificationRequest::create(&subject, &names, &key).unwrap();
+
+        let parsed = CertificationRequest::from_pem(&csr.to_pem()).unwrap();
+        parsed.verify().unwrap();
+        assert!(parsed.subject.matches(&subject));
+        assert_eq!(parsed.public_key, key.public_key());
+        assert_eq!(parsed.subject_alt_names().unwrap(), names);
+
+        // ohne alternative Namen bleibt die Attributmenge leer
+        let plain = CertificationRequest::create(&subject, &[], &key).unwrap();
+        assert!(plain.extensions.is_empty());
+        plain.verify().unwrap();
+
+        let mut tampered = parsed.clone();
+        tampered.signature[0] ^= 1;
+        assert_eq!(tampered.verify(), Err(CryptoError::InvalidSignature));
+    }
+}
@@ -0,0 +1,424 @@
+//! Zertifikatserweiterungen (RFC 5280, 4.2): SubjectAltName, KeyUsage, ExtendedKeyUsage,
+//! BasicConstraints sowie Subject- und Authority-Key-Identifier.
+
+use crate::utils::der::{self, Oid, Reader};
+use crate::utils::error::CryptoError;
+use crate::x509::oid;
+use std::fmt;
+use std::net::IpAddr;
+
+/// Eine Erweiterung mit DER-kodiertem Wert.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extension {
+    pub oid: Oid,
+    pub critical: bool,
+    pub value: Vec<u8>,
+}
+
+/// Erweiterungen, deren Inhalt diese Implementierung auswertet.
+const KNOWN: &[&[u64]] = &[
+    oid::SUBJECT_KEY_IDENTIFIER,
+    oid::KEY_USAGE,
+    oid::SUBJECT_ALT_NAME,
+    oid::BASIC_CONSTRAINTS,
+    oid::AUTHORITY_KEY_IDENTIFIER,
+    oid::EXT_KEY_USAGE,
+];
+
+impl Extension {
+    fn new(arcs: &[u64], critical: bool, value: Vec<u8>) -> Self {
+        Extension {
+            oid: Oid::from_arcs(arcs),
+            critical,
+            value,
+        }
+    }
+
+    pub fn subject_alt_name(names: &[GeneralName]) -> Self {
+        let names: Vec<Vec<u8>> = names.iter().map(GeneralName::to_der).collect();
+        Extension::new(oid::SUBJECT_ALT_NAME, false, der::sequence(&names))
+    }
+
+    pub fn key_usage(usage: KeyUsage) -> Self {
+        Extension::new(oid::KEY_USAGE, true, der::bit_flags(usage.0))
+    }
+
+    pub fn extended_key_usage(purposes: &[&[u64]]) -> Self {
+        let purposes: Vec<Vec<u8>> = purposes.iter().map(|arcs| der::oid(arcs)).collect();
+        Extension::new(oid::EXT_KEY_USAGE, false, der::sequence(&purposes))
+    }
+
+    pub fn basic_constraints(constraints: BasicConstraints) -> Self {
+        let mut fields = Vec::new();
+        if constraints.ca {
+            fields.push(der::boolean(true));
+            if let Some(len) = constraints.path_len {
+                fields.push(der::small_uint(len.into()));
+            }
+        }
+        Extension::new(oid::BASIC_CONSTRAINTS, true, der::sequence(&fields))
+    }
+
+    pub fn subject_key_identifier(key_id: &[u8]) -> Self {
+        Extension::new(
+            oid::SUBJECT_KEY_IDENTIFIER,
+            false,
+            der::octet_string(key_id),
+        )
+    }
+
+    pub fn authority_key_identifier(key_id: &[u8]) -> Self {
+        let key_id = der::tlv(der::context_primitive(0), key_id);
+        Extension::new(
+            oid::AUTHORITY_KEY_IDENTIFIER,
+            false,
+            der::sequence(&[key_id]),
+        )
+    }
+
+    /// Wird der Inhalt dieser Erweiterung ausgewertet?
+    pub fn is_known(&self) -> bool {
+        KNOWN.iter().any(|arcs| self.oid.is(arcs))
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, CryptoError> {
+        let mut seq = reader.sequence()?;
+        let oid = seq.oid()?;
+        let critical = if seq.peek_tag() == Some(der::TAG_BOOLEAN) {
+            seq.boolean()?
+        } else {
+            false
+        };
+        let value = seq.octet_string()?.to_vec();
+        seq.finish()?;
+        Ok(Extension {
+            oid,
+            critical,
+            value,
+        })
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut fields = vec![der::oid(self.oid.arcs())];
+        if self.critical {
+            fields.push(der::boolean(true));
+        }
+        fields.push(der::octet_string(&self.value));
+        der::sequence(&fields)
+    }
+}
+
+/// Liest `SEQUENCE OF Extension`; doppelte Erweiterungen sind unzulässig.
+pub fn read_extensions(content: &[u8]) -> Result<Vec<Extension>, CryptoError> {
+    let mut outer = Reader::new(content);
+    let mut seq = outer.sequence()?;
+    outer.finish()?;
+    let mut extensions: Vec<Extension> = Vec::new();
+    while !seq.is_empty() {
+        let ext = Extension::read(&mut seq)?;
+        if extensions.iter().any(|e| e.oid == ext.oid) {
+            return Err(CryptoError::InvalidFormat(format!(
+                "Erweiterung {} mehrfach vorhanden",
+                ext.oid
+            )));
+        }
+        extensions.push(ext);
+    }
+    Ok(extensions)
+}
+
+/// Kodiert `SEQUENCE OF Extension`.
+pub fn extensions_to_der(extensions: &[Extension]) -> Vec<u8> {
+    let items: Vec<Vec<u8>> = extensions.iter().map(Extension::to_der).collect();
+    der::sequence(&items)
+}
+
+fn find<'a>(extensions: &'a [Extension], arcs: &[u64]) -> Option<&'a Extension> {
+    extensions.iter().find(|e| e.oid.is(arcs))
+}
+
+// ---------- SubjectAltName -----------
+
+/// Alternativer Name (RFC 5280, 4.2.1.6).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneralName {
+    Dns(String),
+    Email(String),
+    Uri(String),
+    Ip(IpAddr),
+    /// Nicht ausgewertete Namensform mit ihrem Tag.
+    Other(u8),
+}
+
+impl GeneralName {
+    /// Liest `dns:…`, `ip:…`, `email:…` oder `uri:…`; ohne Präfix wird eine IP-Adresse
+    /// oder ein DNS-Name angenommen.
+    pub fn parse(text: &str) -> Result<Self, CryptoError> {
+        let (kind, value) = match text.split_once(':') {
+            Some((kind, value))
+                if ["dns", "ip", "email", "uri"].contains(&kind.to_ascii_lowercase().as_str()) =>
+            {
+                (kind.to_ascii_lowercase(), value)
+            }
+            _ if text.parse::<IpAddr>().is_ok() => ("ip".to_string(), text),
+            _ => ("dns".to_string(), text),
+        };
+        let invalid =
+            || CryptoError::InvalidFormat(format!("ungültiger alternativer Name: {}", text));
+        if value.is_empty() || !value.is_ascii() {
+            return Err(invalid());
+        }
+        match kind.as_str() {
+            "ip" => value.parse().map(GeneralName::Ip).map_err(|_| invalid()),
+            "email" if value.contains('@') => Ok(GeneralName::Email(value.to_string())),
+            "uri" if value.contains(':') => Ok(GeneralName::Uri(value.to_string())),
+            "dns" if !value.contains(|c: char| c.is_whitespace() || c == '@' || c == '/') => {
+                Ok(GeneralName::Dns(value.to_ascii_lowercase()))
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    fn to_der(&self) -> Vec<u8> {
+        match self {
+            GeneralName::Email(s) => der::tlv(der::context_primitive(1), s.as_bytes()),
+            GeneralName::Dns(s) => der::tlv(der::context_primitive(2), s.as_bytes()),
+            GeneralName::Uri(s) => der::tlv(der::context_primitive(6), s.as_bytes()),
+            GeneralName::Ip(IpAddr::V4(ip)) => der::tlv(der::context_primitive(7), &ip.octets()),
+            GeneralName::Ip(IpAddr::V6(ip)) => der::tlv(der::context_primitive(7), &ip.octets()),
+            GeneralName::Other(_) => unreachable!("nicht ausgewertete Namen werden nicht kodiert"),
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, CryptoError> {
+        let tlv = reader.read_any()?;
+        let text = || {
+            String::from_utf8(tlv.value.to_vec())
+                .map_err(|_| CryptoError::InvalidFormat("ungültiger alternativer Name".to_string()))
+        };
+        Ok(match tlv.tag {
+            0x81 => GeneralName::Email(text()?),
+            0x82 => GeneralName::Dns(text()?),
+            0x86 => GeneralName::Uri(text()?),
+            0x87 => match tlv.value.len() {
+                4 => GeneralName::Ip(IpAddr::from(<[u8; 4]>::try_from(tlv.value).unwrap())),
+                16 => GeneralName::Ip(IpAddr::from(<[u8; 16]>::try_from(tlv.value).unwrap())),
+                _ => {
+                    return Err(CryptoError::InvalidFormat(
+                        "ungültige IP-Adresse im alternativen Namen".to_string(),
+                    ))
+                }
+            },
+            tag => GeneralName::Other(tag),
+        })
+    }
+}
+
+impl fmt::Display for GeneralName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneralName::Dns(s) => write!(f, "DNS:{}", s),
+            GeneralName::Email(s) => write!(f, "email:{}", s),
+            GeneralName::Uri(s) => write!(f, "URI:{}", s),
+            GeneralName::Ip(ip) => write!(f, "IP:{}", ip),
+            GeneralName::Other(tag) => write!(f, "anderer Name [{}]", tag & 0x1f),
+        }
+    }
+}
+
+/// Alternative Namen aus der SubjectAltName-Erweiterung (leer, falls nicht vorhanden).
+pub fn subject_alt_names(extensions: &[Extension]) -> Result<Vec<GeneralName>, CryptoError> {
+    let Some(ext) = find(extensions, oid::SUBJECT_ALT_NAME) else {
+        return Ok(Vec::new());
+    };
+    let mut outer = Reader::new(&ext.value);
+    let mut seq = outer.sequence()?;
+    outer.finish()?;
+    let mut names = Vec::new();
+    while !seq.is_empty() {
+        names.push(GeneralName::read(&mut seq)?);
+    }
+    Ok(names)
+}
+
+// ---------- KeyUsage -----------
+
+/// Bitfeld der KeyUsage-Erweiterung (RFC 5280, 4.2.1.3); Bit 0 ist digitalSignature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyUsage(pub u32);
+
+impl KeyUsage {
+    pub const DIGITAL_SIGNATURE: KeyUsage = KeyUsage(1 << 0);
+    pub const NON_REPUDIATION: KeyUsage = KeyUsage(1 << 1);
+    pub const KEY_ENCIPHERMENT: KeyUsage = KeyUsage(1 << 2);
+    pub const DATA_ENCIPHERMENT: KeyUsage = KeyUsage(1 << 3);
+    pub const KEY_AGREEMENT: KeyUsage = KeyUsage(1 << 4);
+    pub const KEY_CERT_SIGN: KeyUsage = KeyUsage(1 << 5);
+    pub const CRL_SIGN: KeyUsage = KeyUsage(1 << 6);
+    pub const ENCIPHER_ONLY: KeyUsage = KeyUsage(1 << 7);
+    pub const DECIPHER_ONLY: KeyUsage = KeyUsage(1 << 8);
+
+    const NAMES: [&'static str; 9] = [
+        "digitalSignature",
+        "nonRepudiation",
+        "keyEncipherment",
+        "dataEncipherment",
+        "keyAgreement",
+        "keyCertSign",
+        "cRLSign",
+        "encipherOnly",
+        "decipherOnly",
+    ];
+
+    pub fn contains(self, other: KeyUsage) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn union(self, other: KeyUsage) -> KeyUsage {
+        KeyUsage(self.0 | other.0)
+    }
+
+    pub fn names(self) -> Vec<&'static str> {
+        (0..Self::NAMES.len())
+            .filter(|&bit| self.0 & (1 << bit) != 0)
+            .map(|bit| Self::NAMES[bit])
+            .collect()
+    }
+}
+
+pub fn key_usage(extensions: &[Extension]) -> Result<Option<KeyUsage>, CryptoError> {
+    let Some(ext) = find(extensions, oid::KEY_USAGE) else {
+        return Ok(None);
+    };
+    let mut reader = Reader::new(&ext.value);
+    let flags = reader.bit_flags()?;
+    reader.finish()?;
+    Ok(Some(KeyUsage(flags)))
+}
+
+/// Verwendungszwecke aus ExtendedKeyUsage (leer, falls nicht vorhanden).
+pub fn extended_key_usage(extensions: &[Extension]) -> Result<Vec<Oid>, CryptoError> {
+    let Some(ext) = find(extensions, oid::EXT_KEY_USAGE) else {
+        return Ok(Vec::new());
+    };
+    let mut outer = Reader::new(&ext.value);
+    let mut seq = outer.sequence()?;
+    outer.finish()?;
+    let mut purposes = Vec::new();
+    while !seq.is_empty() {
+        purposes.push(seq.oid()?);
+    }
+    Ok(purposes)
+}
+
+// ---------- BasicConstraints -----------
+
+/// BasicConstraints (RFC 5280, 4.2.1.9).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BasicConstraints {
+    pub ca: bool,
+    pub path_len: Option<u32>,
+}
+
+pub fn basic_constraints(
+    extensions: &[Extension],
+) -> Result<Option<BasicConstraints>, CryptoError> {
+    let Some(ext) = find(extensions, oid::BASIC_CONSTRAINTS) else {
+        return Ok(None);
+    };
+    let mut outer = Reader::new(&ext.value);
+    let mut seq = outer.sequence()?;
+    outer.finish()?;
+    let ca = if seq.peek_tag() == Some(der::TAG_BOOLEAN) {
+        seq.boolean()?
+    } else {
+        false
+    };
+    let path_len = if seq.is_empty() {
+        None
+    } else {
+        let len = seq.small_uint()?;
+        Some(
+            u32::try_from(len)
+                .map_err(|_| CryptoError::InvalidFormat("pathLenConstraint zu groß".to_string()))?,
+        )
+    };
+    seq.finish()?;
+    Ok(Some(BasicConstraints { ca, path_len }))
+}
+
+// ---------- Schlüsselkennungen -----------
+
+pub fn subject_key_id(extensions: &[Extension]) -> Result<Option<Vec<u8>>, CryptoError> {
+    let Some(ext) = find(extensions, oid::SUBJECT_KEY_IDENTIFIER) else {
+        return Ok(None);
+    };
+    let mut reader = Reader::new(&ext.value);
+    let key_id = reader.octet_string()?.to_vec();
+    reader.finish()?;
+    Ok(Some(key_id))
+}
+
+/// Feld `keyIdentifier` der AuthorityKeyIdentifier-Erweiterung, falls vorhanden.
+pub fn authority_key_id(extensions: &[Extension]) -> Result<Option<Vec<u8>>, CryptoError> {
+    let Some(ext) = find(extensions, oid::AUTHORITY_KEY_IDENTIFIER) else {
+        return Ok(None);
+    };
+    let mut outer = Reader::new(&ext.value);
+    let mut seq = outer.sequence()?;
+    outer.finish()?;
+    Ok(seq.optional(der::context_primitive(0))?.map(<[u8]>::to_vec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extensions_roundtrip() {
+        let names = vec![
+            GeneralName::parse("dns:WWW.example.org").unwrap(),
+            GeneralName::parse("10.0.0.1").unwrap(),
+            GeneralName::parse("::1").unwrap(),
+            GeneralName::parse("email:admin@example.org").unwrap(),
+            GeneralName::parse("uri:https://example.org/").unwrap(),
+        ];
+        assert_eq!(names[0], GeneralName::Dns("www.example.org".to_string()));
+        let usage = KeyUsage::KEY_CERT_SIGN.union(KeyUsage::CRL_SIGN);
+        let constraints = BasicConstraints {
+            ca: true,
+            path_len: Some(0),
+        };
+        let extensions = vec![
+            Extension::subject_alt_name(&names),
+            Extension::key_usage(usage),
+            Extension::basic_constraints(constraints),
+            Extension::extended_key_usage(&[oid::KP_SERVER_AUTH]),
+            Extension::subject_key_identifier(&[1, 2, 3]),
+            Extension::authority_key_identifier(&[4, 5, 6]),
+        ];
+        let parsed = read_extensions(&extensions_to_der(&extensions)).unwrap();
+        assert_eq!(parsed, extensions);
+        assert!(parsed.iter().all(Extension::is_known));
+
+        assert_eq!(subject_alt_names(&parsed).unwrap(), names);
+        assert_eq!(key_usage(&parsed).unwrap(), Some(usage));
+        assert_eq!(usage.names(), vec!["keyCertSign", "cRLSign"]);
+        assert_eq!(basic_constraints(&parsed).unwrap(), Some(constraints));
+        assert!(extended_key_usage(&parsed).unwrap()[0].is(oid::KP_SERVER_AUTH));
+        assert_eq!(subject_key_id(&parsed).unwrap(), Some(vec![1, 2, 3]));
+        assert_eq!(authority_key_id(&parsed).unwrap(), Some(vec![4, 5, 6]));
+
+        // Endstellen-Zertifikat: leere Sequenz, cA = FALSE
+        let leaf = Extension::basic_constraints(BasicConstraints {
+            ca: false,
+            path_len: None,
+        });
+        assert_eq!(leaf.value, vec![0x30, 0x00]);
+
+        let twice = extensions_to_der(&[extensions[1].clone(), extensions[1].clone()]);
+        assert!(read_extensions(&twice).is_err());
+        assert!(GeneralName::parse("email:ohne-at").is_err());
+        assert!(GeneralName::parse("ip:300.1.1.1").is_err());
+    }
+}
@@ -0,0 +1,296 @@
+//! Öffentliche Schlüssel (SubjectPublicKeyInfo), Signaturverfahren und Signierschlüssel.
+
+use crate::asymmetric::rsa::{self, Pkcs1Hash};
+use crate::asymmetric::{ecdsa, ed25519};
+use crate::utils::der::{self, Reader};
+use crate::utils::error::CryptoError;
+use crate::x509::oid;
+use num_bigint::BigUint;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// Öffentlicher Schlüssel eines Zertifikats oder einer Zertifikatsanforderung.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubjectPublicKey {
+    Rsa(rsa::PublicKey),
+    Ed25519(ed25519::PublicKey),
+    P256(ecdsa::PublicKey),
+}
+
+impl SubjectPublicKey {
+    /// Liest eine DER-kodierte SubjectPublicKeyInfo (RFC 5280, 4.1.2.7).
+    pub fn from_spki(raw: &[u8]) -> Result<Self, CryptoError> {
+        let mut outer = Reader::new(raw);
+        let mut spki = outer.sequence()?;
+        outer.finish()?;
+        let mut alg = spki.sequence()?;
+        let alg_oid = alg.oid()?;
+        let key = spki.bit_string()?;
+        spki.finish()?;
+
+        if alg_oid.is(oid::RSA_ENCRYPTION) {
+            if !alg.is_empty() {
+                alg.null()?;
+            }
+            alg.finish()?;
+            let mut outer = Reader::new(key);
+            let mut seq = outer.sequence()?;
+            let n = seq.uint()?;
+            let e = seq.uint()?;
+            seq.finish()?;
+            outer.finish()?;
+            if n.bits() < 512 || e < BigUint::from(3u8) {
+                return Err(CryptoError::InvalidFormat(
+                    "ungültiger RSA-Schlüssel".to_string(),
+                ));
+            }
+            Ok(SubjectPublicKey::Rsa(rsa::PublicKey { e, n }))
+        } else if alg_oid.is(oid::ED25519) {
+            alg.finish()?;
+            let bytes = key.try_into().map_err(|_| {
+                CryptoError::InvalidFormat("Ed25519-Schlüssel muss 32 Byte lang sein".to_string())
+            })?;
+            Ok(SubjectPublicKey::Ed25519(ed25519::PublicKey { bytes }))
+        } else if alg_oid.is(oid::EC_PUBLIC_KEY) {
+            let curve = alg.oid()?;
+            alg.finish()?;
+            if !curve.is(oid::PRIME256V1) {
+                return Err(CryptoError::Unsupported(format!(
+                    "elliptische Kurve {}",
+                    curve
+                )));
+            }
+            let point = match key {
+                [0x04, rest @ ..] if rest.len() == 64 => rest,
+                _ => {
+                    return Err(CryptoError::Unsupported(
+                        "komprimierte oder ungültige P-256-Punkte".to_string(),
+                    ))
+                }
+            };
+            let public = ecdsa::PublicKey {
+                x: point[..32].try_into().unwrap(),
+                y: point[32..].try_into().unwrap(),
+            };
+            if !ecdsa::is_valid_public_key(&public) {
+                return Err(CryptoError::InvalidFormat(
+                    "P-256-Punkt liegt nicht auf der Kurve".to_string(),
+                ));
+            }
+            Ok(SubjectPublicKey::P256(public))
+        } else {
+            Err(CryptoError::Unsupported(format!(
+                "Schlüsselalgorithmus {}",
+                alg_oid
+            )))
+        }
+    }
+
+    /// Inhalt des BIT STRING `subjectPublicKey`.
+    fn key_bytes(&self) -> Vec<u8> {
+        match self {
+            SubjectPublicKey::Rsa(public) => {
+                der::sequence(&[der::uint(&public.n), der::uint(&public.e)])
+            }
+            SubjectPublicKey::Ed25519(public) => public.bytes.to_vec(),
+            SubjectPublicKey::P256(public) => [&[0x04][..], &public.x, &public.y].concat(),
+        }
+    }
+
+    /// DER-kodierte SubjectPublicKeyInfo.
+    pub fn to_spki(&self) -> Vec<u8> {
+        let alg = match self {
+            SubjectPublicKey::Rsa(_) => {
+                der::sequence(&[der::oid(oid::RSA_ENCRYPTION), der::null()])
+            }
+            SubjectPublicKey::Ed25519(_) => der::sequence(&[der::oid(oid::ED25519)]),
+            SubjectPublicKey::P256(_) => {
+                der::sequence(&[der::oid(oid::EC_PUBLIC_KEY), der::oid(oid::PRIME256V1)])
+            }
+        };
+        der::sequence(&[alg, der::bit_string(&self.key_bytes())])
+    }
+
+    /// Schlüsselkennung nach RFC 5280, 4.2.1.2, Methode 1 (SHA-1 des Schlüssels).
+    pub fn key_id(&self) -> Vec<u8> {
+        Sha1::digest(self.key_bytes()).to_vec()
+    }
+
+    /// SHA-256 über die SubjectPublicKeyInfo.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        Sha256::digest(self.to_spki()).into()
+    }
+
+    /// Kurzbeschreibung, z. B. "RSA-2048".
+    pub fn description(&self) -> String {
+        match self {
+            SubjectPublicKey::Rsa(public) => format!("RSA-{}", public.n.bits()),
+            SubjectPublicKey::Ed25519(_) => "Ed25519".to_string(),
+            SubjectPublicKey::P256(_) => "ECDSA P-256".to_string(),
+        }
+    }
+}
+
+/// Signaturverfahren eines Zertifikats oder einer Anforderung.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureAlgorithm {
+    RsaSha256,
+    RsaSha384,
+    RsaSha512,
+    Ed25519,
+    EcdsaSha256,
+}
+
+impl SignatureAlgorithm {
+    /// Liest einen AlgorithmIdentifier.
+    pub fn read(reader: &mut Reader) -> Result<Self, CryptoError> {
+        let mut alg = reader.sequence()?;
+        let alg_oid = alg.oid()?;
+        let result = [
+            (oid::SHA256_WITH_RSA, SignatureAlgorithm::RsaSha256),
+            (oid::SHA384_WITH_RSA, SignatureAlgorithm::RsaSha384),
+            (oid::SHA512_WITH_RSA, SignatureAlgorithm::RsaSha512),
+            (oid::ED25519, SignatureAlgorithm::Ed25519),
+            (oid::ECDSA_WITH_SHA256, SignatureAlgorithm::EcdsaSha256),
+        ]
+        .into_iter()
+        .find(|(arcs, _)| alg_oid.is(arcs))
+        .map(|(_, alg)| alg)
+        .ok_or_else(|| CryptoError::Unsupported(format!("Signaturverfahren {}", alg_oid)))?;
+
+        // RSA: Parameter NULL (oder fehlend); Ed25519 und ECDSA: keine Parameter
+        if result.pkcs1_hash().is_some() && !alg.is_empty() {
+            alg.null()?;
+        }
+        alg.finish()?;
+        Ok(result)
+    }
+
+    /// DER-kodierter AlgorithmIdentifier.
+    pub fn to_der(self) -> Vec<u8> {
+        match self {
+            SignatureAlgorithm::RsaSha256 => {
+                der::sequence(&[der::oid(oid::SHA256_WITH_RSA), der::null()])
+            }
+            SignatureAlgorithm::RsaSha384 => {
+                der::sequence(&[der::oid(oid::SHA384_WITH_RSA), der::null()])
+            }
+            SignatureAlgorithm::RsaSha512 => {
+                der::sequence(&[der::oid(oid::SHA512_WITH_RSA), der::null()])
+            }
+            SignatureAlgorithm::Ed25519 => der::sequence(&[der::oid(oid::ED25519)]),
+            SignatureAlgorithm::EcdsaSha256 => der::sequence(&[der::oid(oid::ECDSA_WITH_SHA256)]),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SignatureAlgorithm::RsaSha256 => "sha256WithRSAEncryption",
+            SignatureAlgorithm::RsaSha384 => "sha384WithRSAEncryption",
+            SignatureAlgorithm::RsaSha512 => "sha512WithRSAEncryption",
+            SignatureAlgorithm::Ed25519 => "Ed25519",
+            SignatureAlgorithm::EcdsaSha256 => "ecdsa-with-SHA256",
+        }
+    }
+
+    fn pkcs1_hash(self) -> Option<Pkcs1Hash> {
+        match self {
+            SignatureAlgorithm::RsaSha256 => Some(Pkcs1Hash::Sha256),
+            SignatureAlgorithm::RsaSha384 => Some(Pkcs1Hash::Sha384),
+            SignatureAlgorithm::RsaSha512 => Some(Pkcs1Hash::Sha512),
+            _ => None,
+        }
+    }
+
+    /// Prüft eine Signatur über `message`; Verfahren und Schlüsseltyp müssen zusammenpassen.
+    pub fn verify(self, key: &SubjectPublicKey, message: &[u8], signature: &[u8]) -> bool {
+        match (self, key) {
+            (SignatureAlgorithm::Ed25519, SubjectPublicKey::Ed25519(public)) => {
+                ed25519::verify_ed25519(message, signature, public)
+            }
+            (SignatureAlgorithm::EcdsaSha256, SubjectPublicKey::P256(public)) => {
+                ecdsa_signature_from_der(signature)
+                    .is_some_and(|raw| ecdsa::verify_ecdsa_p256(message, &raw, public))
+            }
+            (alg, SubjectPublicKey::Rsa(public)) => alg.pkcs1_hash().is_some_and(|hash| {
+                rsa::verify_rsa_pkcs1v15(hash, &hash.digest(message), signature, public)
+            }),
+            _ => false,
+        }
+    }
+}
+
+/// Privater Schlüssel zum Signieren von Zertifikaten und Anforderungen.
+#[derive(Clone)]
+pub enum SigningKey {
+    Rsa {
+        public: rsa::PublicKey,
+        private: rsa::PrivateKey,
+    },
+    Ed25519 {
+        public: ed25519::PublicKey,
+        private: ed25519::PrivateKey,
+    },
+    P256 {
+        public: ecdsa::PublicKey,
+        private: ecdsa::PrivateKey,
+    },
+}
+
+impl SigningKey {
+    pub fn from_ed25519(private: &ed25519::PrivateKey) -> Self {
+        SigningKey::Ed25519 {
+            public: ed25519::public_key_from_private(private),
+            private: private.clone(),
+        }
+    }
+
+    pub fn public_key(&self) -> SubjectPublicKey {
+        match self {
+            SigningKey::Rsa { public, .. } => SubjectPublicKey::Rsa(public.clone()),
+            SigningKey::Ed25519 { public, .. } => SubjectPublicKey::Ed25519(public.clone()),
+            SigningKey::P256 { public, .. } => SubjectPublicKey::P256(public.clone()),
+        }
+    }
+
+    pub fn signature_algorithm(&self) -> SignatureAlgorithm {
+        match self {
+            SigningKey::Rsa { .. } => SignatureAlgorithm::RsaSha256,
+            SigningKey::Ed25519 { .. } => SignatureAlgorithm::Ed25519,
+            SigningKey::P256 { .. } => SignatureAlgorithm::EcdsaSha256,
+        }
+    }
+
+    /// Signiert `message` im Format des Signaturverfahrens (ECDSA als DER-Sequenz).
+    pub fn sign(&self, message: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        Ok(match self {
+            SigningKey::Rsa { private, .. } => rsa::sign_rsa_pkcs1v15(
+                Pkcs1Hash::Sha256,
+                &Pkcs1Hash::Sha256.digest(message),
+                private,
+            )?,
+            SigningKey::Ed25519 { private, .. } => ed25519::sign_ed25519(message, private).to_vec(),
+            SigningKey::P256 { private, .. } => {
+                let raw = ecdsa::sign_ecdsa_p256(message, private);
+                der::sequence(&[der::uint_bytes(&raw[..32]), der::uint_bytes(&raw[32..])])
+            }
+        })
+    }
+}
+
+/// Wandelt eine ECDSA-Signatur `SEQUENCE { r, s }` in die Form r || s um.
+fn ecdsa_signature_from_der(signature: &[u8]) -> Option<[u8; 64]> {
+    let mut outer = Reader::new(signature);
+    let mut seq = outer.sequence().ok()?;
+    let mut raw = [0u8; 64];
+    for half in raw.chunks_mut(32) {
+        let bytes = seq.uint().ok()?.to_bytes_be();
+        if bytes.len() > 32 {
+            return None;
+        }
+        half[32 - bytes.len()..].copy_from_slice(&bytes);
+    }
+    seq.finish().ok()?;
+    outer.finish().ok()?;
+    Some(raw)
+}
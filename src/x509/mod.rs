@@ -0,0 +1,127 @@
+//! X.509-v3-Zertifikate und PKCS#10-Zertifikatsanforderungen (RFC 5280, RFC 2986).
+//!
+//! Umfasst das Lesen und Schreiben in DER und PEM, die Erweiterungen SubjectAltName,
+//! KeyUsage, ExtendedKeyUsage, BasicConstraints sowie Schlüsselkennungen, das Ausstellen
+//! selbstsignierter und CA-signierter Zertifikate und die Pfadprüfung gegen einen lokalen
+//! Vertrauensspeicher. Signiert wird mit RSA (PKCS#1 v1.5, SHA-256), Ed25519 oder
+//! ECDSA P-256; geprüft werden zusätzlich RSA mit SHA-384 und SHA-512.
+
+pub mod cert;
+pub mod csr;
+pub mod extensions;
+pub mod key;
+pub mod name;
+pub mod path;
+
+pub use cert::{Certificate, CertificateParams};
+pub use csr::CertificationRequest;
+pub use extensions::{BasicConstraints, Extension, GeneralName, KeyUsage};
+pub use key::{SignatureAlgorithm, SigningKey, SubjectPublicKey};
+pub use name::Name;
+pub use path::{verify_path, TrustStore, VerifyOptions};
+
+use crate::utils::error::CryptoError;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+pub const PEM_CERTIFICATE: &str = "CERTIFICATE";
+pub const PEM_CERTIFICATE_REQUEST: &str = "CERTIFICATE REQUEST";
+
+/// Kodiert DER-Daten als PEM mit 64 Zeichen pro Zeile (RFC 7468).
+pub fn pem_encode(label: &str, der: &[u8]) -> String {
+    let b64 = STANDARD.encode(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for chunk in b64.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(chunk).unwrap());
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+/// Liest alle PEM-Blöcke mit dem gegebenen Label, z. B. aus einer Zertifikatskette.
+///
+/// Text außerhalb der Blöcke (etwa `openssl x509 -text`) wird übersprungen.
+pub fn pem_decode_all(text: &str, label: &str) -> Result<Vec<Vec<u8>>, CryptoError> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    let mut blocks = Vec::new();
+    let mut current: Option<String> = None;
+    for line in text.lines().map(str::trim) {
+        match current.as_mut() {
+            None if line == begin => current = Some(String::new()),
+            None => {}
+            Some(b64) if line == end => {
+                let der = STANDARD.decode(b64.as_bytes()).map_err(|_| {
+                    CryptoError::InvalidFormat(format!("ungültiges Base64 in PEM-Block {}", label))
+                })?;
+                blocks.push(der);
+                current = None;
+            }
+            Some(b64) => b64.push_str(line),
+        }
+    }
+    if current.is_some() {
+        return Err(CryptoError::InvalidFormat(format!(
+            "PEM-Block {} ohne Ende",
+            label
+        )));
+    }
+    Ok(blocks)
+}
+
+/// Liest genau einen PEM-Block mit dem gegebenen Label.
+pub fn pem_decode(text: &str, label: &str) -> Result<Vec<u8>, CryptoError> {
+    let mut blocks = pem_decode_all(text, label)?;
+    match blocks.len() {
+        1 => Ok(blocks.remove(0)),
+        0 => Err(CryptoError::InvalidFormat(format!(
+            "kein PEM-Block {} gefunden",
+            label
+        ))),
+        _ => Err(CryptoError::InvalidFormat(format!(
+            "mehrere PEM-Blöcke {} gefunden",
+            label
+        ))),
+    }
+}
+
+/// Objektkennungen aus RFC 5280, RFC 8017, RFC 8410 und RFC 5758 sowie NIST (ML-KEM).
+pub mod oid {
+    pub const RSA_ENCRYPTION: &[u64] = &[1, 2, 840, 113_549, 1, 1, 1];
+    pub const SHA256_WITH_RSA: &[u64] = &[1, 2, 840, 113_549, 1, 1, 11];
+    pub const SHA384_WITH_RSA: &[u64] = &[1, 2, 840, 113_549, 1, 1, 12];
+    pub const SHA512_WITH_RSA: &[u64] = &[1, 2, 840, 113_549, 1, 1, 13];
+    pub const EC_PUBLIC_KEY: &[u64] = &[1, 2, 840, 10_045, 2, 1];
+    pub const PRIME256V1: &[u64] = &[1, 2, 840, 10_045, 3, 1, 7];
+    pub const ECDSA_WITH_SHA256: &[u64] = &[1, 2, 840, 10_045, 4, 3, 2];
+    pub const ED25519: &[u64] = &[1, 3, 101, 112];
+    pub const X25519: &[u64] = &[1, 3, 101, 110];
+    pub const ML_KEM_768: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 4, 2];
+
+    pub const COMMON_NAME: &[u64] = &[2, 5, 4, 3];
+    pub const SURNAME: &[u64] = &[2, 5, 4, 4];
+    pub const SERIAL_NUMBER: &[u64] = &[2, 5, 4, 5];
+    pub const COUNTRY: &[u64] = &[2, 5, 4, 6];
+    pub const LOCALITY: &[u64] = &[2, 5, 4, 7];
+    pub const STATE: &[u64] = &[2, 5, 4, 8];
+    pub const STREET: &[u64] = &[2, 5, 4, 9];
+    pub const ORGANIZATION: &[u64] = &[2, 5, 4, 10];
+    pub const ORGANIZATIONAL_UNIT: &[u64] = &[2, 5, 4, 11];
+    pub const GIVEN_NAME: &[u64] = &[2, 5, 4, 42];
+    pub const EMAIL_ADDRESS: &[u64] = &[1, 2, 840, 113_549, 1, 9, 1];
+    pub const DOMAIN_COMPONENT: &[u64] = &[0, 9, 2342, 19_200_300, 100, 1, 25];
+    pub const EXTENSION_REQUEST: &[u64] = &[1, 2, 840, 113_549, 1, 9, 14];
+
+    pub const SUBJECT_KEY_IDENTIFIER: &[u64] = &[2, 5, 29, 14];
+    pub const KEY_USAGE: &[u64] = &[2, 5, 29, 15];
+    pub const SUBJECT_ALT_NAME: &[u64] = &[2, 5, 29, 17];
+    pub const BASIC_CONSTRAINTS: &[u64] = &[2, 5, 29, 19];
+    pub const AUTHORITY_KEY_IDENTIFIER: &[u64] = &[2, 5, 29, 35];
+    pub const EXT_KEY_USAGE: &[u64] = &[2, 5, 29, 37];
+
+    pub const KP_SERVER_AUTH: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 3, 1];
+    pub const KP_CLIENT_AUTH: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 3, 2];
+    pub const KP_CODE_SIGNING: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 3, 3];
+    pub const KP_EMAIL_PROTECTION: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 3, 4];
+}
@@ -0,0 +1,227 @@
+//! Distinguished Names (RFC 5280, 4.1.2.4) mit Textdarstellung ähnlich RFC 4514.
+
+use crate::utils::der::{self, Oid, Reader};
+use crate::utils::error::CryptoError;
+use crate::x509::oid;
+use std::fmt;
+
+/// Kurzbezeichnungen für die Textdarstellung.
+const SHORT_NAMES: &[(&str, &[u64])] = &[
+    ("CN", oid::COMMON_NAME),
+    ("SN", oid::SURNAME),
+    ("serialNumber", oid::SERIAL_NUMBER),
+    ("C", oid::COUNTRY),
+    ("L", oid::LOCALITY),
+    ("ST", oid::STATE),
+    ("street", oid::STREET),
+    ("O", oid::ORGANIZATION),
+    ("OU", oid::ORGANIZATIONAL_UNIT),
+    ("GN", oid::GIVEN_NAME),
+    ("emailAddress", oid::EMAIL_ADDRESS),
+    ("DC", oid::DOMAIN_COMPONENT),
+];
+
+/// Ein Distinguished Name; die DER-Kodierung wird unverändert mitgeführt, damit
+/// Ausstellernamen bitgenau übernommen werden können.
+#[derive(Debug, Clone)]
+pub struct Name {
+    pub attributes: Vec<(Oid, String)>,
+    der: Vec<u8>,
+}
+
+impl Name {
+    /// Baut einen Namen aus Attributen; jedes Attribut bildet einen eigenen RDN.
+    pub fn from_attributes(attributes: Vec<(Oid, String)>) -> Self {
+        let rdns: Vec<Vec<u8>> = attributes
+            .iter()
+            .map(|(attr, value)| {
+                // Länder- und Seriennummern sind PrintableString, E-Mail und DC IA5String
+                let encoded = if attr.is(oid::COUNTRY) || attr.is(oid::SERIAL_NUMBER) {
+                    der::printable_string(value)
+                } else if attr.is(oid::EMAIL_ADDRESS) || attr.is(oid::DOMAIN_COMPONENT) {
+                    der::ia5_string(value)
+                } else {
+                    der::utf8_string(value)
+                };
+                der::set_of(&[der::sequence(&[der::oid(attr.arcs()), encoded])])
+            })
+            .collect();
+        Name {
+            der: der::sequence(&rdns),
+            attributes,
+        }
+    }
+
+    /// Liest einen Namen wie `CN=Beispiel, O=Firma, C=DE`; `\,` maskiert Kommas.
+    pub fn parse(text: &str) -> Result<Self, CryptoError> {
+        let mut attributes = Vec::new();
+        for part in split_unescaped(text) {
+            let (key, value) = part.split_once('=').ok_or_else(|| {
+                CryptoError::InvalidFormat(format!("Namensbestandteil ohne '=': {}", part.trim()))
+            })?;
+            let key = key.trim();
+            let attr = SHORT_NAMES
+                .iter()
+                .find(|(short, _)| short.eq_ignore_ascii_case(key))
+                .map(|(_, arcs)| Oid::from_arcs(arcs))
+                .or_else(|| parse_dotted(key))
+                .ok_or_else(|| CryptoError::Unsupported(format!("Namensattribut {}", key)))?;
+            let value = value.trim();
+            if value.is_empty() {
+                return Err(CryptoError::InvalidFormat(format!(
+                    "leerer Wert für {}",
+                    key
+                )));
+            }
+            if attr.is(oid::COUNTRY) && (value.len() != 2 || !value.is_ascii()) {
+                return Err(CryptoError::InvalidFormat(
+                    "Ländercode muss aus zwei Buchstaben bestehen".to_string(),
+                ));
+            }
+            attributes.push((attr, value.to_string()));
+        }
+        if attributes.is_empty() {
+            return Err(CryptoError::InvalidFormat("leerer Name".to_string()));
+        }
+        Ok(Name::from_attributes(attributes))
+    }
+
+    /// Liest einen DER-kodierten Namen (`SEQUENCE OF RelativeDistinguishedName`).
+    pub fn from_der(raw: &[u8]) -> Result<Self, CryptoError> {
+        let mut outer = Reader::new(raw);
+        let mut rdns = outer.sequence()?;
+        outer.finish()?;
+        let mut attributes = Vec::new();
+        while !rdns.is_empty() {
+            let mut set = rdns.nested(der::TAG_SET)?;
+            while !set.is_empty() {
+                let mut atv = set.sequence()?;
+                let attr = atv.oid()?;
+                let value = match atv.string() {
+                    Ok(value) => value,
+                    Err(_) => {
+                        return Err(CryptoError::InvalidFormat(format!(
+                            "Namensattribut {} ist keine Zeichenkette",
+                            attr
+                        )))
+                    }
+                };
+                atv.finish()?;
+                attributes.push((attr, value));
+            }
+        }
+        Ok(Name {
+            attributes,
+            der: raw.to_vec(),
+        })
+    }
+
+    pub fn to_der(&self) -> &[u8] {
+        &self.der
+    }
+
+    /// Erster Wert eines Attributs, z. B. `name.get(oid::COMMON_NAME)`.
+    pub fn get(&self, attr: &[u64]) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(oid, _)| oid.is(attr))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn common_name(&self) -> Option<&str> {
+        self.get(oid::COMMON_NAME)
+    }
+
+    /// Namensvergleich nach RFC 5280, 7.1: bitgleich oder nach Normalisierung
+    /// (Groß-/Kleinschreibung, mehrfache Leerzeichen) gleich.
+    pub fn matches(&self, other: &Name) -> bool {
+        if self.der == other.der {
+            return true;
+        }
+        let normalize = |s: &str| {
+            s.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+        };
+        self.attributes.len() == other.attributes.len()
+            && self
+                .attributes
+                .iter()
+                .zip(&other.attributes)
+                .all(|((a, x), (b, y))| a == b && normalize(x) == normalize(y))
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self
+            .attributes
+            .iter()
+            .map(|(attr, value)| {
+                let key = SHORT_NAMES
+                    .iter()
+                    .find(|(_, arcs)| attr.is(arcs))
+                    .map(|(short, _)| short.to_string())
+                    .unwrap_or_else(|| attr.to_string());
+                format!(
+                    "{}={}",
+                    key,
+                    value.replace('\\', "\\\\").replace(',', "\\,")
+                )
+            })
+            .collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Zerlegt an Kommas, die nicht mit `\` maskiert sind, und entfernt die Maskierung.
+fn split_unescaped(text: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => parts.last_mut().unwrap().extend(chars.next()),
+            ',' => parts.push(String::new()),
+            _ => parts.last_mut().unwrap().push(c),
+        }
+    }
+    parts.retain(|p| !p.trim().is_empty());
+    parts
+}
+
+fn parse_dotted(key: &str) -> Option<Oid> {
+    let arcs: Option<Vec<u64>> = key.split('.').map(|a| a.parse().ok()).collect();
+    arcs.filter(|a| a.len() >= 2 && a[0] <= 2)
+        .map(|a| Oid::from_arcs(&a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_display_and_der_roundtrip() {
+        let name = Name::parse("CN=Muster\\, Max, O=Beispiel GmbH, C=DE, 2.5.4.5=42").unwrap();
+        assert_eq!(name.common_name(), Some("Muster, Max"));
+        assert_eq!(name.get(oid::SERIAL_NUMBER), Some("42"));
+        assert_eq!(
+            name.to_string(),
+            "CN=Muster\\, Max, O=Beispiel GmbH, C=DE, serialNumber=42"
+        );
+
+        let parsed = Name::from_der(name.to_der()).unwrap();
+        assert_eq!(parsed.attributes, name.attributes);
+        assert!(parsed.matches(&name));
+        assert!(
+            Name::parse("cn=muster\\,  MAX,o=beispiel gmbh,c=de,serialNumber=42")
+                .unwrap()
+                .matches(&name)
+        );
+        assert!(!Name::parse("CN=Muster").unwrap().matches(&name));
+
+        assert!(Name::parse("").is_err());
+        assert!(Name::parse("C=DEU").is_err());
+        assert!(Name::parse("XY=1").is_err());
+    }
+}
@@ -0,0 +1,360 @@
+//! Pfadprüfung gegen einen lokalen Vertrauensspeicher (vereinfacht nach RFC 5280, 6.1).
+//!
+//! Geprüft werden Signaturen, Gültigkeitszeiträume, Namensverkettung, BasicConstraints mit
+//! Pfadlänge, KeyUsage der Aussteller, unbekannte kritische Erweiterungen sowie optional
+//! DNS-Name bzw. IP-Adresse und Verwendungszweck des Endzertifikats. Sperrlisten und
+//! NameConstraints werden nicht ausgewertet.
+
+use crate::utils::der::format_time;
+use crate::utils::error::CryptoError;
+use crate::x509::cert::Certificate;
+use crate::x509::extensions::{GeneralName, KeyUsage};
+use std::net::IpAddr;
+
+/// Maximale Anzahl Zwischenzertifikate in einem Pfad.
+const MAX_INTERMEDIATES: usize = 8;
+
+/// anyExtendedKeyUsage (RFC 5280, 4.2.1.12).
+const ANY_EXTENDED_KEY_USAGE: &[u64] = &[2, 5, 29, 37, 0];
+
+/// Vertrauenswürdige Wurzelzertifikate.
+#[derive(Debug, Clone, Default)]
+pub struct TrustStore {
+    anchors: Vec<Certificate>,
+}
+
+impl TrustStore {
+    pub fn new() -> Self {
+        TrustStore::default()
+    }
+
+    pub fn add(&mut self, cert: Certificate) {
+        if !self.anchors.iter().any(|a| a.to_der() == cert.to_der()) {
+            self.anchors.push(cert);
+        }
+    }
+
+    /// Liest alle Zertifikate aus einem PEM-Bündel.
+    pub fn from_pem(text: &str) -> Result<Self, CryptoError> {
+        let mut store = TrustStore::new();
+        for cert in Certificate::from_pem_chain(text)? {
+            store.add(cert);
+        }
+        Ok(store)
+    }
+
+    pub fn anchors(&self) -> &[Certificate] {
+        &self.anchors
+    }
+
+    pub fn len(&self) -> usize {
+        self.anchors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.anchors.is_empty()
+    }
+}
+
+/// Rahmenbedingungen der Prüfung.
+#[derive(Debug, Clone)]
+pub struct VerifyOptions<'a> {
+    /// Prüfzeitpunkt in Sekunden seit 1970 (UTC).
+    pub time: i64,
+    /// DNS-Name oder IP-Adresse, für die das Endzertifikat gelten muss.
+    pub host: Option<&'a str>,
+    /// Verlangter Verwendungszweck (ExtendedKeyUsage), z. B. `oid::KP_SERVER_AUTH`.
+    pub purpose: Option<&'a [u64]>,
+}
+
+impl VerifyOptions<'_> {
+    pub fn at(time: i64) -> Self {
+        VerifyOptions {
+            time,
+            host: None,
+            purpose: None,
+        }
+    }
+}
+
+fn rejected(cert: &Certificate, why: &str) -> CryptoError {
+    CryptoError::InvalidCertificate(format!("\"{}\": {}", cert.subject, why))
+}
+
+/// Gemeinsame Prüfungen für jedes Zertifikat im Pfad.
+fn check_common(cert: &Certificate, time: i64) -> Result<(), CryptoError> {
+    if time < cert.not_before {
+        return Err(rejected(
+            cert,
+            &format!("erst gültig ab {}", format_time(cert.not_before)),
+        ));
+    }
+    if time > cert.not_after {
+        return Err(rejected(
+            cert,
+            &format!("abgelaufen am {}", format_time(cert.not_after)),
+        ));
+    }
+    if let Some(ext) = cert.extensions.iter().find(|e| e.critical && !e.is_known()) {
+        return Err(rejected(
+            cert,
+            &format!("unbekannte kritische Erweiterung {}", ext.oid),
+        ));
+    }
+    Ok(())
+}
+
+fn check_leaf(leaf: &Certificate, options: &VerifyOptions) -> Result<(), CryptoError> {
+    check_common(leaf, options.time)?;
+    if let Some(host) = options.host {
+        let names = leaf.subject_alt_names()?;
+        if !names.iter().any(|name| host_matches(name, host)) {
+            return Err(rejected(leaf, &format!("nicht gültig für {}", host)));
+        }
+    }
+    if let Some(purpose) = options.purpose {
+        let purposes = leaf.extended_key_usage()?;
+        if !purposes.is_empty()
+            && !purposes
+                .iter()
+                .any(|p| p.is(purpose) || p.is(ANY_EXTENDED_KEY_USAGE))
+        {
+            return Err(rejected(leaf, "Verwendungszweck nicht erlaubt"));
+        }
+    }
+    Ok(())
+}
+
+/// DNS-Vergleich ohne Groß-/Kleinschreibung; `*` nur als ganzes linkes Label (RFC 6125, 6.4.3).
+fn host_matches(name: &GeneralName, host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    match (name, host.parse::<IpAddr>()) {
+        (GeneralName::Ip(ip), Ok(host_ip)) => *ip == host_ip,
+        (GeneralName::Dns(pattern), Err(_)) => {
+            let pattern = pattern.trim_end_matches('.').to_ascii_lowercase();
+            match pattern.strip_prefix("*.") {
+                Some(suffix) => host
+                    .split_once('.')
+                    .is_some_and(|(label, rest)| !label.is_empty() && rest == suffix),
+                None => pattern == host,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Prüft, ob `issuer` das letzte Zertifikat in `path` ausgestellt haben kann.
+fn check_issuer(
+    issuer: &Certificate,
+    path: &[&Certificate],
+    is_anchor: bool,
+    time: i64,
+) -> Result<(), CryptoError> {
+    let child = path.last().unwrap();
+    child.verify_signature(&issuer.public_key).map_err(|_| {
+        rejected(
+            child,
+            &format!("Signatur von \"{}\" ungültig", issuer.subject),
+        )
+    })?;
+    check_common(issuer, time)?;
+
+    // v1-Wurzeln ohne Erweiterungen werden als CA akzeptiert
+    let constraints = issuer.basic_constraints()?;
+    match constraints {
+        Some(bc) if bc.ca => {}
+        None if is_anchor && issuer.version == 1 => {}
+        _ => return Err(rejected(issuer, "ist keine CA")),
+    }
+    if issuer
+        .key_usage()?
+        .is_some_and(|usage| !usage.contains(KeyUsage::KEY_CERT_SIGN))
+    {
+        return Err(rejected(
+            issuer,
+            "KeyUsage erlaubt keine Zertifikatssignatur",
+        ));
+    }
+    // Zwischenzertifikate unterhalb des Ausstellers, selbst ausgestellte zählen nicht mit
+    let below = path[1..].iter().filter(|c| !c.is_self_issued()).count();
+    if let Some(max) = constraints.and_then(|bc| bc.path_len) {
+        if below > max as usize {
+            return Err(rejected(
+                issuer,
+                &format!("Pfadlänge {} überschritten", max),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Kann `candidate` Aussteller des Zertifikats `child` sein (Name und Schlüsselkennung)?
+fn is_candidate(candidate: &Certificate, child: &Certificate) -> bool {
+    if !candidate.subject.matches(&child.issuer) {
+        return false;
+    }
+    match (child.authority_key_id(), candidate.subject_key_id()) {
+        (Ok(Some(aki)), Ok(Some(ski))) => aki == ski,
+        _ => true,
+    }
+}
+
+fn build_path<'a>(
+    path: &mut Vec<&'a Certificate>,
+    intermediates: &'a [Certificate],
+    store: &'a TrustStore,
+    time: i64,
+    last_error: &mut Option<CryptoError>,
+) -> bool {
+    let child = *path.last().unwrap();
+    for anchor in store.anchors.iter().filter(|a| is_candidate(a, child)) {
+        match check_issuer(anchor, path, true, time) {
+            Ok(()) => {
+                path.push(anchor);
+                return true;
+            }
+            Err(e) => *last_error = Some(e),
+        }
+    }
+    if path.len() > MAX_INTERMEDIATES {
+        return false;
+    }
+    for next in intermediates.iter().filter(|c| is_candidate(c, child)) {
+        if path.iter().any(|c| c.to_der() == next.to_der()) {
+            continue;
+        }
+        match check_issuer(next, path, false, time) {
+            Ok(()) => {
+                path.push(next);
+                if build_path(path, intermediates, store, time, last_error) {
+                    return true;
+                }
+                path.pop();
+            }
+            Err(e) => *last_error = Some(e),
+        }
+    }
+    false
+}
+
+/// Sucht und prüft einen Pfad vom Endzertifikat über `intermediates` zu einer Wurzel
+/// aus `store` und gibt ihn (Endzertifikat zuerst) zurück.
+pub fn verify_path(
+    leaf: &Certificate,
+    intermediates: &[Certificate],
+    store: &TrustStore,
+    options: &VerifyOptions,
+) -> Result<Vec<Certificate>, CryptoError> {
+    check_leaf(leaf, options)?;
+    if store.anchors.iter().any(|a| a.to_der() == leaf.to_der()) {
+        return Ok(vec![leaf.clone()]);
+    }
+    let mut path = vec![leaf];
+    let mut last_error = None;
+    if build_path(
+        &mut path,
+        intermediates,
+        store,
+        options.time,
+        &mut last_error,
+    ) {
+        return Ok(path.into_iter().cloned().collect());
+    }
+    Err(last_error.unwrap_or_else(|| {
+        rejected(
+            leaf,
+            &format!(
+                "kein Pfad zu einem vertrauenswürdigen Aussteller (\"{}\")",
+                leaf.issuer
+            ),
+        )
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asymmetric::ed25519;
+    use crate::x509::{oid, CertificateParams, Name, SigningKey};
+
+    const NOW: i64 = 1_750_000_000;
+
+    fn key() -> SigningKey {
+        SigningKey::from_ed25519(&ed25519::generate_ed25519_keypair().1)
+    }
+
+    fn params(cn: &str) -> CertificateParams {
+        CertificateParams::new(
+            Name::parse(&format!("CN={}", cn)).unwrap(),
+            NOW - 86_400,
+            30,
+        )
+    }
+
+    #[test]
+    fn test_chain_validation() {
+        let (root_key, sub_key, leaf_key) = (key(), key(), key());
+        let root = Certificate::self_signed(&params("Wurzel").with_ca(Some(1)), &root_key).unwrap();
+        let sub = Certificate::issue(
+            &params("Zwischen").with_ca(Some(0)),
+            &sub_key.public_key(),
+            &root,
+            &root_key,
+        )
+        .unwrap();
+        let mut leaf_params = params("www").with_subject_alt_names(vec![
+            GeneralName::parse("*.example.org").unwrap(),
+            GeneralName::parse("10.1.2.3").unwrap(),
+        ]);
+        leaf_params.extended_key_usage = vec![oid::KP_SERVER_AUTH];
+        let leaf =
+            Certificate::issue(&leaf_params, &leaf_key.public_key(), &sub, &sub_key).unwrap();
+
+        let chain = std::slice::from_ref(&sub);
+        let mut store = TrustStore::new();
+        store.add(root.clone());
+        let mut options = VerifyOptions::at(NOW);
+        options.host = Some("WWW.example.org.");
+        options.purpose = Some(oid::KP_SERVER_AUTH);
+        let path = verify_path(&leaf, chain, &store, &options).unwrap();
+        let subjects: Vec<String> = path.iter().map(|c| c.subject.to_string()).collect();
+        assert_eq!(subjects, ["CN=www", "CN=Zwischen", "CN=Wurzel"]);
+
+        options.host = Some("10.1.2.3");
+        verify_path(&leaf, chain, &store, &options).unwrap();
+
+        // falscher Name, falscher Zweck, fehlende Zwischenstufe, abgelaufen, leerer Speicher
+        for host in ["example.org", "a.b.example.org", "10.1.2.4"] {
+            options.host = Some(host);
+            assert!(verify_path(&leaf, chain, &store, &options).is_err());
+        }
+        options.host = None;
+        options.purpose = Some(oid::KP_CODE_SIGNING);
+        assert!(verify_path(&leaf, chain, &store, &options).is_err());
+        let options = VerifyOptions::at(NOW);
+        assert!(verify_path(&leaf, &[], &store, &options).is_err());
+        let later = VerifyOptions::at(NOW + 60 * 86_400);
+        assert!(matches!(
+            verify_path(&leaf, chain, &store, &later),
+            Err(CryptoError::InvalidCertificate(_))
+        ));
+        assert!(verify_path(&leaf, chain, &TrustStore::new(), &options).is_err());
+
+        // Endstelle als Aussteller und überschrittene Pfadlänge
+        let fake = Certificate::issue(&params("x"), &key().public_key(), &leaf, &leaf_key);
+        assert!(fake.is_err());
+        let sub2_key = key();
+        let sub2 = Certificate::issue(
+            &params("Zwischen 2").with_ca(None),
+            &sub2_key.public_key(),
+            &sub,
+            &sub_key,
+        )
+        .unwrap();
+        assert!(verify_path(&sub2, chain, &store, &options).is_ok());
+        let deep =
+            Certificate::issue(&params("tief"), &key().public_key(), &sub2, &sub2_key).unwrap();
+        let err = verify_path(&deep, &[sub.clone(), sub2], &store, &options).unwrap_err();
+        assert!(err.to_string().contains("Pfadlänge 0"), "{}", err);
+    }
+}
@@ -0,0 +1,133 @@
+use cryptomorph::age::{self, armor, AgeError, Identity};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, PartialEq)]
+enum Expect {
+    Success,
+    ArmorFailure,
+    HeaderFailure,
+    HmacFailure,
+    NoMatch,
+    PayloadFailure,
+}
+
+struct TestVector {
+    expect: Expect,
+    payload_sha256: Option<String>,
+    identities: Vec<Identity>,
+    armored: bool,
+    age_file: Vec<u8>,
+}
+
+fn parse_vector(data: &[u8]) -> TestVector {
+    let split = data
+        .windows(2)
+        .position(|w| w == b"\n\n")
+        .expect("Metadaten ohne Leerzeile");
+    let meta = std::str::from_utf8(&data[..split]).unwrap();
+
+    let mut vector = TestVector {
+        expect: Expect::Success,
+        payload_sha256: None,
+        identities: Vec::new(),
+        armored: false,
+        age_file: data[split + 2..].to_vec(),
+    };
+    for line in meta.lines() {
+        let (key, value) = line.split_once(": ").unwrap();
+        match key {
+            "expect" => {
+                vector.expect = match value {
+                    "success" => Expect::Success,
+                    "armor failure" => Expect::ArmorFailure,
+                    "header failure" => Expect::HeaderFailure,
+                    "HMAC failure" => Expect::HmacFailure,
+                    "no match" => Expect::NoMatch,
+                    "payload failure" => Expect::PayloadFailure,
+                    other => panic!("unbekannte Erwartung {}", other),
+                }
+            }
+            "payload" => vector.payload_sha256 = Some(value.to_string()),
+            "identity" => vector
+                .identities
+                .push(Identity::X25519(age::parse_identity(value).unwrap())),
+            "passphrase" => vector.identities.push(Identity::Scrypt {
+                passphrase: value.to_string(),
+                max_log_n: age::MAX_SCRYPT_LOG_N,
+            }),
+            "armored" => vector.armored = value == "yes",
+            "file key" | "comment" => {}
+            other => panic!("unbekanntes Metadatum {}", other),
+        }
+    }
+    vector
+}
+
+/// Führt einen Testvektor aus und liefert das Ergebnis samt bis dahin geschriebenem Klartext.
+fn run(vector: &TestVector) -> (Result<(), AgeError>, Vec<u8>) {
+    let mut out = Vec::new();
+    let binary = if vector.armored {
+        match armor::decode(&vector.age_file) {
+            Ok(binary) => binary,
+            Err(e) => return (Err(e), out),
+        }
+    } else {
+        vector.age_file.clone()
+    };
+    let result = age::decrypt_stream(&vector.identities, &binary[..], &mut out);
+    (result, out)
+}
+
+#[test]
+fn age_testkit_conformance() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testdata/age");
+    let mut names: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name != "README.md")
+        .collect();
+    names.sort();
+    assert!(names.len() > 100, "Testvektoren fehlen");
+
+    let mut failures = Vec::new();
+    for name in &names {
+        let vector = parse_vector(&fs::read(dir.join(name)).unwrap());
+        let (result, out) = run(&vector);
+        let actual = match &result {
+            Ok(()) => Expect::Success,
+            Err(AgeError::Armor(_)) => Expect::ArmorFailure,
+            Err(AgeError::Header(_)) => Expect::HeaderFailure,
+            Err(AgeError::HeaderMac) => Expect::HmacFailure,
+            Err(AgeError::NoMatch) => Expect::NoMatch,
+            Err(AgeError::Payload(_)) => Expect::PayloadFailure,
+            Err(AgeError::Io(e)) => panic!("{}: E/A-Fehler {}", name, e),
+        };
+        if actual != vector.expect {
+            failures.push(format!(
+                "{}: erwartet {:?}, erhalten {:?}",
+                name, vector.expect, result
+            ));
+            continue;
+        }
+        // Auch bei Payload-Fehlern dürfen nur vollständig geprüfte Blöcke ausgegeben werden
+        if let Some(expected) = &vector.payload_sha256 {
+            if &hex::encode(Sha256::digest(&out)) != expected {
+                failures.push(format!("{}: falscher Klartext", name));
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn age_testkit_armor_autodetect() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testdata/age");
+    let vector = parse_vector(&fs::read(dir.join("armor_whitespace_outside")).unwrap());
+    let plaintext = age::decrypt(&vector.age_file, &vector.identities).unwrap();
+    assert_eq!(
+        hex::encode(Sha256::digest(plaintext)),
+        vector.payload_sha256.unwrap()
+    );
+}
@@ -0,0 +1,90 @@
+use cryptomorph::archive::{self, ArchiveError};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LONG: &str = "verzeichnis-mit-einem-sehr-langen-namen-verzeichnis-mit-einem-sehr-langen-namen-verzeichnis-mit-einem-sehr-langen-namen-";
+
+fn open(name: &str) -> fs::File {
+    let path = format!("tests/testdata/archive/{}", name);
+    fs::File::open(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "cryptomorph-archive-vectors-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_unpack_gnu_and_pax_archives() {
+    for name in ["gnu.tar", "pax.tar"] {
+        let dest = temp_dir(name);
+        let summary = archive::unpack(open(name), &dest).unwrap();
+        assert_eq!(
+            (summary.files, summary.directories, summary.symlinks),
+            (2, 2, 2),
+            "{}",
+            name
+        );
+        let deep = dest.join(LONG).join("datei.txt");
+        assert_eq!(fs::read_to_string(&deep).unwrap(), "Inhalt in der Tiefe\n");
+        assert_eq!(
+            fs::read_link(dest.join("bin/verweis")).unwrap(),
+            Path::new("..").join(LONG).join("datei.txt")
+        );
+        assert_eq!(
+            fs::read_to_string(dest.join("start")).unwrap(),
+            "#!/bin/sh\necho hallo\n"
+        );
+        // 2024-05-01 12:00:00 UTC
+        let mtime = fs::metadata(&deep).unwrap().modified().unwrap();
+        assert_eq!(
+            mtime,
+            std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_714_564_800)
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |p: &str| fs::metadata(dest.join(p)).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode("bin/start.sh"), 0o750);
+            assert_eq!(mode(&format!("{}/datei.txt", LONG)), 0o640);
+        }
+        fs::remove_dir_all(&dest).unwrap();
+    }
+}
+
+#[test]
+fn test_reject_malicious_archives() {
+    let outside = Path::new("/tmp/ausbruch.txt");
+    let existed = outside.exists();
+    for (name, unsafe_entry) in [
+        ("evil_dotdot.tar", true),
+        ("evil_absolute.tar", true),
+        ("evil_symlink.tar", true),
+        ("evil_hardlink.tar", false),
+    ] {
+        let parent = temp_dir(name);
+        let dest = parent.join("ziel");
+        fs::create_dir(&dest).unwrap();
+        let result = archive::unpack(open(name), &dest);
+        if unsafe_entry {
+            assert!(
+                matches!(result, Err(ArchiveError::Unsafe(_))),
+                "{}: {:?}",
+                name,
+                result
+            );
+        } else {
+            assert!(matches!(result, Err(ArchiveError::Unsupported(_))));
+        }
+        assert!(!parent.join("ausbruch.txt").exists());
+        assert!(fs::symlink_metadata(dest.join("l/ausbruch.txt")).is_err());
+        fs::remove_dir_all(&parent).unwrap();
+    }
+    assert_eq!(outside.exists(), existed);
+}
@@ -0,0 +1,35 @@
+use cryptomorph::utils::armor::{self, Armor, Label};
+use std::fs;
+
+fn read(name: &str) -> Vec<u8> {
+    let path = format!("tests/testdata/armor/{}", name);
+    fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+#[test]
+fn test_read_gpg_armor() {
+    let armor = Armor::decode(&read("data_gpg.asc")).unwrap();
+    assert_eq!(armor.label, Label::Signature);
+    assert_eq!(
+        armor.header("Comment"),
+        Some("Use \"gpg --dearmor\" for unpacking")
+    );
+    assert_eq!(armor.data, read("data.bin"));
+
+    // gleiches Format: Schreiben ergibt die Datei von gpg
+    assert_eq!(armor.encode().into_bytes(), read("data_gpg.asc"));
+
+    assert!(Armor::decode(&read("data_bad_crc.asc")).is_err());
+}
+
+#[test]
+fn test_read_openssl_pkcs7() {
+    let der = read("certs.p7b");
+    assert_eq!(
+        armor::dearmor(&read("certs.p7b.pem"), Label::Cms).unwrap(),
+        der
+    );
+    // DER wird unverändert durchgereicht
+    assert_eq!(armor::dearmor(&der, Label::Cms).unwrap(), der);
+    assert!(armor::dearmor(&read("certs.p7b.pem"), Label::Pkcs12).is_err());
+}
@@ -0,0 +1,185 @@
+use cryptomorph::asymmetric::rsa;
+use cryptomorph::cms::{self, EnvelopedData, KeyTransport, SignedData};
+use cryptomorph::utils::error::CryptoError;
+use cryptomorph::x509::{Certificate, SignatureAlgorithm, TrustStore, VerifyOptions};
+use num_bigint::BigUint;
+use serde_json::Value;
+use std::fs;
+
+/// 2026-01-01 00:00:00 UTC, innerhalb der Gültigkeit der Testdaten (2025–2045).
+const NOW: i64 = 1_767_225_600;
+
+fn read(name: &str) -> Vec<u8> {
+    let path = format!("tests/testdata/cms/{}", name);
+    fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+fn cert(name: &str) -> Certificate {
+    Certificate::from_bytes(&read(name)).unwrap()
+}
+
+fn bob_key() -> rsa::PrivateKey {
+    let json: Value = serde_json::from_slice(&read("bob_rsa.json")).unwrap();
+    let hex = |name: &str| BigUint::parse_bytes(json[name].as_str().unwrap().as_bytes(), 16);
+    rsa::PrivateKey {
+        n: hex("n").unwrap(),
+        d: hex("d").unwrap(),
+        // Bobs Zertifikat trägt den üblichen Exponenten
+        e: BigUint::from(65537u32),
+    }
+}
+
+fn store() -> TrustStore {
+    TrustStore::from_pem(&String::from_utf8(read("ca.pem")).unwrap()).unwrap()
+}
+
+#[test]
+fn test_verify_openssl_signed_data() {
+    let content = read("content.txt");
+    let cases = [
+        ("signed_rsa.p7s", "CN=Bob", SignatureAlgorithm::RsaSha256),
+        (
+            "signed_rsa_noattr.p7s",
+            "CN=Bob",
+            SignatureAlgorithm::RsaSha256,
+        ),
+        (
+            "signed_p256.p7s",
+            "CN=Carol",
+            SignatureAlgorithm::EcdsaSha256,
+        ),
+        ("signed_ed25519.p7s", "CN=Dave", SignatureAlgorithm::Ed25519),
+    ];
+    for (file, subject, algorithm) in cases {
+        let signed = SignedData::from_bytes(&read(file)).unwrap();
+        assert_eq!(
+            signed.content.as_deref(),
+            Some(content.as_slice()),
+            "{}",
+            file
+        );
+        assert_eq!(signed.signers[0].signature_algorithm, algorithm, "{}", file);
+        let paths = signed
+            .verify_trusted(None, &[], &store(), &VerifyOptions::at(NOW))
+            .unwrap_or_else(|e| panic!("{}: {}", file, e));
+        assert!(paths[0][0].subject.to_string().starts_with(subject));
+        assert_eq!(paths[0].len(), 2);
+    }
+    let noattr = SignedData::from_bytes(&read("signed_rsa_noattr.p7s")).unwrap();
+    assert!(noattr.signers[0].signing_time.is_none());
+
+    // abgesetzt mit SHA-384
+    let detached = SignedData::from_bytes(&read("signed_rsa_detached_sha384.p7s")).unwrap();
+    assert!(detached.content.is_none());
+    assert_eq!(
+        detached.signers[0].signature_algorithm,
+        SignatureAlgorithm::RsaSha384
+    );
+    assert!(detached.signers[0].signing_time.is_some());
+    detached.verify(Some(&content), &[]).unwrap();
+    let mut changed = content.clone();
+    changed[0] ^= 1;
+    assert!(matches!(
+        detached.verify(Some(&changed), &[]),
+        Err(CryptoError::InvalidSignature)
+    ));
+
+    // ohne passende Vertrauensanker
+    let mut other = TrustStore::new();
+    other.add(cert("bob.pem"));
+    let signed = SignedData::from_bytes(&read("signed_p256.p7s")).unwrap();
+    assert!(signed
+        .verify_trusted(None, &[], &other, &VerifyOptions::at(NOW))
+        .is_err());
+}
+
+#[test]
+fn test_signer_certificate_lookup() {
+    // ohne eingebettete Zertifikate muss das Unterzeichnerzertifikat mitgegeben werden
+    let mut signed = SignedData::from_bytes(&read("signed_rsa.p7s")).unwrap();
+    signed.certificates.clear();
+    assert!(matches!(
+        signed.verify(None, &[]),
+        Err(CryptoError::InvalidCertificate(_))
+    ));
+    signed.verify(None, &[cert("bob.pem")]).unwrap();
+    assert!(matches!(
+        signed.verify(None, &[cert("carol.pem")]),
+        Err(CryptoError::InvalidCertificate(_))
+    ));
+}
+
+#[test]
+fn test_decrypt_openssl_enveloped_data() {
+    let content = read("content.txt");
+    let bob = cert("bob.pem");
+    for (file, cipher, transport) in [
+        (
+            "enveloped_gcm_oaep256.p7m",
+            "AES-256-GCM",
+            KeyTransport::OaepSha256,
+        ),
+        (
+            "enveloped_cbc_oaep.p7m",
+            "AES-128-CBC",
+            KeyTransport::OaepSha1,
+        ),
+    ] {
+        let enveloped = EnvelopedData::from_bytes(&read(file)).unwrap();
+        assert_eq!(enveloped.content_encryption(), cipher);
+        assert_eq!(enveloped.recipients[0].key_transport, transport);
+        assert!(enveloped.recipients[0].rid.matches(&bob));
+        assert_eq!(enveloped.decrypt(&bob, &bob_key()).unwrap(), content);
+    }
+
+    // RSAES-PKCS1-v1_5 (OpenSSL-Standard) wird nicht unterstützt
+    assert!(matches!(
+        cms::decrypt(&read("enveloped_pkcs1.p7m"), &bob, &bob_key()),
+        Err(CryptoError::Unsupported(_))
+    ));
+    // falsches Empfängerzertifikat
+    assert_eq!(
+        cms::decrypt(
+            &read("enveloped_gcm_oaep256.p7m"),
+            &cert("carol.pem"),
+            &bob_key()
+        )
+        .unwrap_err(),
+        CryptoError::NoMatchingRecipient
+    );
+
+    // manipuliertes Tag
+    let mut data = read("enveloped_gcm_oaep256.p7m");
+    let last = data.len() - 1;
+    data[last] ^= 1;
+    assert_eq!(
+        cms::decrypt(&data, &bob, &bob_key()).unwrap_err(),
+        CryptoError::DecryptionFailed
+    );
+}
+
+#[test]
+fn test_encrypt_for_openssl_certificate() {
+    let bob = cert("bob.pem");
+    let encrypted = cms::encrypt(b"Angebot", std::slice::from_ref(&bob)).unwrap();
+    let enveloped = EnvelopedData::from_bytes(&encrypted).unwrap();
+    assert_eq!(enveloped.content_encryption(), "AES-256-GCM");
+    assert_eq!(
+        enveloped.recipients[0].key_transport,
+        KeyTransport::OaepSha256
+    );
+    assert_eq!(enveloped.decrypt(&bob, &bob_key()).unwrap(), b"Angebot");
+
+    let pem = cms::to_pem(&encrypted);
+    assert_eq!(
+        cms::decrypt(pem.as_bytes(), &bob, &bob_key()).unwrap(),
+        b"Angebot"
+    );
+
+    // nur RSA-Empfänger
+    assert!(matches!(
+        cms::encrypt(b"x", &[cert("dave.pem")]),
+        Err(CryptoError::Unsupported(_))
+    ));
+    assert!(cms::encrypt(b"x", &[]).is_err());
+}
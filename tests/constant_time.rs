@@ -0,0 +1,115 @@
+//! Laufzeitmessung nach dudect (Reparaz, Balasch, Verbauwhede 2017): zwei Eingabeklassen –
+//! eine feste und eine zufällige – werden in zufälliger Reihenfolge gemessen, die langsamsten
+//! Messungen verworfen und die Mittelwerte mit Welchs t-Test verglichen. Ein großes |t|
+//! zeigt eine von den Daten abhängige Laufzeit.
+//!
+//! Als Gegenprobe muss `mod_exp` (gleitendes Fenster, Nullbits kosten nur eine Quadrierung)
+//! mit denselben Klassen deutlich auffallen; sonst wäre die Messung zu ungenau, um etwas
+//! auszusagen.
+//!
+//! Wanduhrzeiten schwanken auf ausgelasteten Rechnern und im Debug-Build zu stark für feste
+//! Schwellen; der Test läuft deshalb nur auf Anfrage:
+//! `cargo test --release --test constant_time -- --ignored`.
+
+use cryptomorph::algorithms::constant_time;
+use cryptomorph::algorithms::modular_arith::mod_exp;
+use cryptomorph::asymmetric::rsa::{decrypt_rsa, PrivateKey};
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::One;
+use rand::rngs::OsRng;
+use rand::Rng;
+use std::hint::black_box;
+use std::time::Instant;
+
+const BITS: u64 = 256;
+const SAMPLES: usize = 2000;
+/// Anteile der schnellsten Messungen, die je einen t-Test ergeben (Ausreißer durch
+/// Unterbrechungen fallen so heraus).
+const CROPS: [f64; 4] = [0.3, 0.5, 0.7, 0.9];
+
+/// Laufende Mittelwerte und Varianzen beider Klassen (Welford).
+#[derive(Default)]
+struct Welch {
+    n: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl Welch {
+    fn push(&mut self, class: usize, x: f64) {
+        self.n[class] += 1.0;
+        let delta = x - self.mean[class];
+        self.mean[class] += delta / self.n[class];
+        self.m2[class] += delta * (x - self.mean[class]);
+    }
+
+    fn t(&self) -> f64 {
+        let var = |c: usize| self.m2[c] / (self.n[c] - 1.0);
+        (self.mean[0] - self.mean[1]) / (var(0) / self.n[0] + var(1) / self.n[1]).sqrt()
+    }
+}
+
+/// Betragsgrößter t-Wert für `f`, aufgerufen mit dem Exponenten der jeweiligen Klasse: Klasse 0 ist immer
+/// 2^(BITS − 1) + 1, Klasse 1 ein zufälliger Exponent gleicher Länge.
+fn exponent_t(f: impl Fn(&BigUint) -> BigUint) -> f64 {
+    let mut rng = OsRng;
+    let fixed = (BigUint::one() << (BITS - 1)) + 1u32;
+    let mut samples = Vec::with_capacity(SAMPLES);
+    for _ in 0..SAMPLES {
+        let class = rng.gen_range(0..2);
+        let exponent = if class == 0 {
+            fixed.clone()
+        } else {
+            rng.gen_biguint(BITS - 1) | (BigUint::one() << (BITS - 1))
+        };
+        let start = Instant::now();
+        black_box(f(black_box(&exponent)));
+        samples.push((class, start.elapsed().as_nanos() as f64));
+    }
+
+    let mut times: Vec<f64> = samples.iter().map(|&(_, t)| t).collect();
+    times.sort_by(f64::total_cmp);
+    CROPS
+        .iter()
+        .map(|keep| {
+            let cutoff = times[(times.len() as f64 * keep) as usize];
+            let mut welch = Welch::default();
+            for &(class, t) in &samples {
+                if t < cutoff {
+                    welch.push(class, t);
+                }
+            }
+            welch.t()
+        })
+        // fehlt einer Klasse nach dem Abschneiden die Streuung, ist t nicht definiert
+        .filter(|t| t.is_finite())
+        .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .unwrap()
+}
+
+#[test]
+#[ignore = "Laufzeitmessung, nur mit --release auf einem ruhigen Rechner aussagekräftig"]
+fn test_private_exponent_timing() {
+    let mut rng = OsRng;
+    let n = rng.gen_biguint(BITS) | BigUint::one() | (BigUint::one() << (BITS - 1));
+    let base = rng.gen_biguint_below(&n);
+
+    let leaky = exponent_t(|d| mod_exp(base.clone(), d.clone(), &n));
+    assert!(leaky.abs() > 10.0, "Messung zu ungenau: t = {:.1}", leaky);
+
+    let ct = exponent_t(|d| constant_time::modpow(&base, d, &n));
+    assert!(ct.abs() < 10.0, "constant_time::modpow: t = {:.1}", ct);
+
+    // n und d gehören zu keinem echten Schlüssel; gemessen wird nur die Zeit
+    let rsa = exponent_t(|d| {
+        decrypt_rsa(
+            &base,
+            &PrivateKey {
+                d: d.clone(),
+                n: n.clone(),
+                e: BigUint::from(65537u32),
+            },
+        )
+    });
+    assert!(rsa.abs() < 10.0, "decrypt_rsa: t = {:.1}", rsa);
+}
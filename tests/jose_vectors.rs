@@ -0,0 +1,248 @@
+use cryptomorph::jose::jwe::{self, KeyAlgorithm};
+use cryptomorph::jose::jwk::{jwk_set_to_json, parse_jwk_set};
+use cryptomorph::jose::jws::{self, JwsAlgorithm};
+use cryptomorph::jose::{b64_decode, b64_encode, Jwk, KeyMaterial};
+use cryptomorph::utils::error::CryptoError;
+use serde_json::{Map, Value};
+use std::fs;
+
+fn load(name: &str) -> Value {
+    let path = format!("tests/testdata/jose/{}", name);
+    let data = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    serde_json::from_str(&data).unwrap()
+}
+
+fn rfc(vector: &str) -> Value {
+    load("rfc_vectors.json")[vector].clone()
+}
+
+fn jwk(value: &Value) -> Jwk {
+    Jwk::from_value(value).unwrap()
+}
+
+fn str_of<'a>(value: &'a Value, name: &str) -> &'a str {
+    value[name].as_str().unwrap()
+}
+
+/// Signiert die Payload des Beispiel-Tokens erneut mit exakt dessen Header.
+fn resign(token: &str, key: &Jwk) -> String {
+    let parts: Vec<&str> = token.split('.').collect();
+    let header: Map<String, Value> =
+        serde_json::from_slice(&b64_decode(parts[0]).unwrap()).unwrap();
+    let alg = JwsAlgorithm::from_name(header["alg"].as_str().unwrap()).unwrap();
+    let mut extra = header.clone();
+    extra.remove("alg");
+    jws::sign_compact_with_header(extra, &b64_decode(parts[1]).unwrap(), alg, key).unwrap()
+}
+
+#[test]
+fn test_rfc7515_a2_rs256() {
+    let vector = rfc("rfc7515_a2_rs256");
+    let key = jwk(&vector["jwk"]);
+    let token = str_of(&vector, "token");
+
+    let verified = jws::verify_compact(token, &key.to_public()).unwrap();
+    assert!(verified.payload.starts_with(b"{\"iss\":\"joe\",\r\n"));
+    // RSASSA-PKCS1-v1_5 ist deterministisch: identisches Token
+    assert_eq!(resign(token, &key), token);
+}
+
+#[test]
+fn test_rfc7515_a3_es256() {
+    let vector = rfc("rfc7515_a3_es256");
+    let key = jwk(&vector["jwk"]);
+    let token = str_of(&vector, "token");
+    jws::verify_compact(token, &key.to_public()).unwrap();
+
+    // ECDSA mit RFC-6979-Nonce: anderes, aber gültiges Token
+    let ours = resign(token, &key);
+    assert_ne!(ours, token);
+    jws::verify_compact(&ours, &key.to_public()).unwrap();
+
+    let mut tampered = token.to_string();
+    tampered.replace_range(tampered.len() - 2.., "AA");
+    assert_eq!(
+        jws::verify_compact(&tampered, &key).unwrap_err(),
+        CryptoError::InvalidSignature
+    );
+}
+
+#[test]
+fn test_rfc8037_a4_ed25519() {
+    let vector = rfc("rfc8037_a4_ed25519");
+    let key = jwk(&vector["jwk"]);
+    let token = str_of(&vector, "token");
+
+    assert_eq!(key.thumbprint(), str_of(&vector, "thumbprint"));
+    let verified = jws::verify_compact(token, &key.to_public()).unwrap();
+    assert_eq!(verified.payload, b"Example of Ed25519 signing");
+    assert_eq!(resign(token, &key), token);
+}
+
+#[test]
+fn test_rfc7516_a1_rsa_oaep_a256gcm() {
+    let vector = rfc("rfc7516_a1_rsa_oaep_a256gcm");
+    let key = jwk(&vector["jwk"]);
+    let token = str_of(&vector, "token");
+    let plaintext = str_of(&vector, "plaintext").as_bytes();
+
+    let decrypted = jwe::decrypt_compact(token, &key).unwrap();
+    assert_eq!(decrypted.plaintext, plaintext);
+    assert_eq!(decrypted.header["alg"], "RSA-OAEP");
+
+    // Dieselbe Nachricht in flacher JSON-Serialisierung (RFC 7516, 7.2.2)
+    let parts: Vec<&str> = token.split('.').collect();
+    let flattened = serde_json::json!({
+        "protected": parts[0],
+        "encrypted_key": parts[1],
+        "iv": parts[2],
+        "ciphertext": parts[3],
+        "tag": parts[4],
+    });
+    let decrypted = jwe::decrypt_json(&flattened.to_string(), &key).unwrap();
+    assert_eq!(decrypted.plaintext, plaintext);
+
+    // Manipuliertes Tag
+    let mut tampered = parts.clone();
+    let tag = b64_encode(&[0u8; 16]);
+    tampered[4] = &tag;
+    assert_eq!(
+        jwe::decrypt_compact(&tampered.join("."), &key).unwrap_err(),
+        CryptoError::DecryptionFailed
+    );
+}
+
+#[test]
+fn test_rfc7517_key_sets() {
+    let public = parse_jwk_set(&rfc("rfc7517_a1_public_keys").to_string()).unwrap();
+    assert_eq!(public.len(), 2);
+    assert!(matches!(public[0].key, KeyMaterial::P256 { .. }));
+    assert_eq!(public[0].key_use.as_deref(), Some("enc"));
+    assert_eq!(public[1].kid.as_deref(), Some("2011-04-29"));
+    assert_eq!(public[1].alg.as_deref(), Some("RS256"));
+
+    let private = parse_jwk_set(&rfc("rfc7517_a2_private_keys").to_string()).unwrap();
+    assert!(private.iter().all(Jwk::is_private));
+    for (a, b) in public.iter().zip(&private) {
+        assert_eq!(a.thumbprint(), b.thumbprint());
+    }
+
+    // Symmetrische Schlüssel werden übersprungen
+    assert!(parse_jwk_set(&rfc("rfc7517_a3_symmetric_keys").to_string())
+        .unwrap()
+        .is_empty());
+
+    // Export mit rekonstruierten CRT-Parametern und erneuter Import
+    let exported = jwk_set_to_json(&private);
+    let reparsed: Value = serde_json::from_str(&exported).unwrap();
+    let rsa = &reparsed["keys"][1];
+    let original = &rfc("rfc7517_a2_private_keys")["keys"][1];
+    for name in ["n", "e", "d"] {
+        assert_eq!(rsa[name], original[name]);
+    }
+    let mut primes = [str_of(rsa, "p"), str_of(rsa, "q")];
+    let mut expected = [str_of(original, "p"), str_of(original, "q")];
+    primes.sort();
+    expected.sort();
+    assert_eq!(primes, expected);
+    assert_eq!(parse_jwk_set(&exported).unwrap().len(), 2);
+}
+
+#[test]
+fn test_rfc7517_c1_private_key_for_jwe() {
+    let key = jwk(&rfc("rfc7517_c1_private_key"));
+    assert_eq!(key.kid.as_deref(), Some("juliet@capulet.lit"));
+    let token = jwe::encrypt_compact(b"Romeo", KeyAlgorithm::RsaOaep256, &key.to_public()).unwrap();
+    let decrypted = jwe::decrypt_compact(&token, &key).unwrap();
+    assert_eq!(decrypted.plaintext, b"Romeo");
+    assert_eq!(decrypted.header["kid"], "juliet@capulet.lit");
+
+    // Schlüssel mit "use": "enc" darf nicht signieren
+    assert!(jws::sign_compact(b"x", JwsAlgorithm::Rs256, &key).is_err());
+}
+
+#[test]
+fn test_rfc7638_thumbprint() {
+    let vector = rfc("rfc7638_3_1_thumbprint");
+    assert_eq!(
+        jwk(&vector["jwk"]).thumbprint(),
+        str_of(&vector, "thumbprint")
+    );
+}
+
+#[test]
+fn test_pyca_interop() {
+    let vector = load("interop_pyca.json");
+    let rsa = jwk(&vector["rsa_jwk"]);
+    let ec = jwk(&vector["ec_jwk"]);
+    let payload = str_of(&vector, "payload").as_bytes();
+    let plaintext = str_of(&vector, "plaintext").as_bytes();
+
+    assert_eq!(
+        jws::verify_compact(str_of(&vector, "ps256"), &rsa.to_public())
+            .unwrap()
+            .payload,
+        payload
+    );
+    assert_eq!(
+        jws::verify_compact(str_of(&vector, "es256"), &ec)
+            .unwrap()
+            .payload,
+        payload
+    );
+    // eigene PS256-Signatur mit dem importierten Schlüssel
+    let ours = jws::sign_compact(payload, JwsAlgorithm::Ps256, &rsa).unwrap();
+    jws::verify_compact(&ours, &rsa).unwrap();
+
+    assert_eq!(
+        jwe::decrypt(str_of(&vector, "jwe_compact"), &rsa)
+            .unwrap()
+            .plaintext,
+        plaintext
+    );
+    let decrypted = jwe::decrypt(&vector["jwe_json"].to_string(), &rsa).unwrap();
+    assert_eq!(decrypted.plaintext, plaintext);
+    assert_eq!(decrypted.aad.as_deref(), Some(&b"Kontext"[..]));
+    assert_eq!(decrypted.header["cty"], "text/plain");
+    assert_eq!(decrypted.header["kid"], "pyca-rsa");
+}
+
+#[test]
+fn test_jwe_json_multiple_recipients() {
+    let vector = rfc("rfc7517_a2_private_keys");
+    let mut alice = jwk(&vector["keys"][1]).with_kid("alice");
+    alice.alg = None;
+    alice.key_use = None;
+    let juliet = jwk(&rfc("rfc7517_c1_private_key"));
+
+    let json = jwe::encrypt_json(
+        b"Geheim",
+        KeyAlgorithm::RsaOaep256,
+        &[&alice.to_public(), &juliet.to_public()],
+        Some(b"Betreff"),
+    )
+    .unwrap();
+    for key in [&alice, &juliet] {
+        let decrypted = jwe::decrypt_json(&json, key).unwrap();
+        assert_eq!(decrypted.plaintext, b"Geheim");
+        assert_eq!(decrypted.aad.as_deref(), Some(&b"Betreff"[..]));
+    }
+
+    // AAD verändert
+    let mut value: Value = serde_json::from_str(&json).unwrap();
+    value["aad"] = Value::String(b64_encode(b"anders"));
+    assert_eq!(
+        jwe::decrypt_json(&value.to_string(), &alice).unwrap_err(),
+        CryptoError::DecryptionFailed
+    );
+
+    // fremder Schlüssel
+    let ed = jwk(&rfc("rfc8037_a4_ed25519")["jwk"]);
+    assert!(jwe::decrypt_json(&json, &ed).is_err());
+    let mut stranger = juliet.clone();
+    stranger.kid = Some("mallory".to_string());
+    assert_eq!(
+        jwe::decrypt_json(&json, &stranger).unwrap_err(),
+        CryptoError::NoMatchingRecipient
+    );
+}
@@ -0,0 +1,172 @@
+use cryptomorph::asymmetric::{ed25519, rsa, x25519};
+use cryptomorph::openpgp::{self, Cert};
+use cryptomorph::utils::error::CryptoError;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const RFC9580: &str = "tests/testdata/openpgp/rfc9580";
+const GNUPG: &str = "tests/testdata/openpgp/gnupg";
+
+fn read(path: impl AsRef<Path>) -> Vec<u8> {
+    fs::read(path.as_ref()).unwrap_or_else(|e| panic!("{}: {}", path.as_ref().display(), e))
+}
+
+fn vector_dir(name: &str) -> PathBuf {
+    Path::new(RFC9580).join(name)
+}
+
+fn load_cert(path: impl AsRef<Path>, passphrase: Option<&[u8]>) -> Cert {
+    Cert::from_bytes(&read(path), passphrase).unwrap()
+}
+
+#[test]
+fn test_rfc9580_vectors_decrypt() {
+    for name in [
+        "v4-ed25519-x25519",
+        "v4-legacy",
+        "v4-rsa",
+        "v6-25519-annex-a-4",
+        "v6-rsa",
+    ] {
+        let dir = vector_dir(name);
+        let cert = load_cert(dir.join("tsk.asc"), None);
+        assert!(cert.has_secret(), "{}", name);
+        for msg in ["enc-seipdv1.msg", "enc-seipdv2.msg"] {
+            let path = dir.join(msg);
+            if !path.exists() {
+                continue;
+            }
+            let plaintext = openpgp::decrypt(&read(&path), &cert)
+                .unwrap_or_else(|e| panic!("{}/{}: {}", name, msg, e));
+            assert_eq!(plaintext, b"hello world\n", "{}/{}", name, msg);
+        }
+    }
+}
+
+#[test]
+fn test_rfc9580_vectors_rejected() {
+    // v6-Schlüssel dürfen keine Legacy-Algorithmen (EdDSALegacy, ECDH) verwenden
+    let err = Cert::from_bytes(&read(vector_dir("v6-legacy_illegal").join("tsk.asc")), None);
+    assert!(matches!(err, Err(CryptoError::InvalidFormat(_))));
+
+    // NIST-Kurven werden nicht unterstützt
+    for name in ["v4-nistp", "v6-nistp"] {
+        let err = Cert::from_bytes(&read(vector_dir(name).join("tsk.asc")), None);
+        assert!(matches!(err, Err(CryptoError::Unsupported(_))), "{}", name);
+    }
+
+    // X448-Unterschlüssel werden ignoriert, daher passt kein Empfänger
+    let dir = vector_dir("v6-ed25519-x448");
+    let cert = load_cert(dir.join("tsk.asc"), None);
+    assert!(cert.encryption_keys().is_empty());
+    let err = openpgp::decrypt(&read(dir.join("enc-seipdv2.msg")), &cert);
+    assert!(matches!(err, Err(CryptoError::NoMatchingRecipient)));
+}
+
+#[test]
+fn test_gnupg_rsa_key_with_passphrase() {
+    let path = Path::new(GNUPG).join("alice_rsa_secret.asc");
+    assert!(Cert::from_bytes(&read(&path), None).is_err());
+    assert!(matches!(
+        Cert::from_bytes(&read(&path), Some(b"falsch")),
+        Err(CryptoError::DecryptionFailed)
+    ));
+
+    let cert = load_cert(&path, Some(b"geheim"));
+    assert_eq!(cert.user_ids[0].value, "Alice RSA <alice@example.org>");
+    let plaintext = openpgp::decrypt(&read(Path::new(GNUPG).join("to_alice.gpg")), &cert).unwrap();
+    assert_eq!(plaintext, read(Path::new(GNUPG).join("plain.txt")));
+
+    // Export ohne Passphrase und erneuter Import
+    let reloaded = Cert::from_bytes(cert.to_secret_armor().unwrap().as_bytes(), None).unwrap();
+    assert_eq!(reloaded.fingerprint(), cert.fingerprint());
+}
+
+#[test]
+fn test_gnupg_ed25519_messages_and_signatures() {
+    let gnupg = Path::new(GNUPG);
+    let cert = load_cert(gnupg.join("bob_ed25519_secret.asc"), None);
+    let plain = read(gnupg.join("plain.txt"));
+
+    assert_eq!(
+        openpgp::decrypt(&read(gnupg.join("to_bob.asc")), &cert).unwrap(),
+        plain
+    );
+    // komprimiert und mit Teillängen
+    assert_eq!(
+        openpgp::decrypt(&read(gnupg.join("large_to_bob.gpg")), &cert).unwrap(),
+        read(gnupg.join("large.txt"))
+    );
+
+    let public = load_cert(gnupg.join("bob_ed25519_public.asc"), None);
+    assert!(!public.has_secret());
+    for sig in ["plain.txt.asc", "plain.txt.text.asc"] {
+        let v = openpgp::verify_detached(&plain, &read(gnupg.join(sig)), &public).unwrap();
+        assert_eq!(v.fingerprint, public.fingerprint());
+    }
+    assert!(
+        openpgp::verify_detached(b"anderer Text", &read(gnupg.join("plain.txt.asc")), &public)
+            .is_err()
+    );
+
+    let alice = load_cert(gnupg.join("alice_rsa_public.asc"), None);
+    openpgp::verify_detached(&plain, &read(gnupg.join("plain.txt.sig")), &alice).unwrap();
+    assert!(openpgp::verify_detached(&plain, &read(gnupg.join("plain.txt.sig")), &public).is_err());
+}
+
+#[test]
+fn test_exported_keys_decrypt_gnupg_messages() {
+    let gnupg = Path::new(GNUPG);
+    let plain = read(gnupg.join("plain.txt"));
+    for (key, msg) in [
+        ("carol_exported_secret.asc", "to_carol.asc"),
+        ("dave_exported_secret.asc", "to_dave.gpg"),
+    ] {
+        let cert = load_cert(gnupg.join(key), None);
+        assert_eq!(
+            openpgp::decrypt(&read(gnupg.join(msg)), &cert).unwrap(),
+            plain
+        );
+    }
+}
+
+#[test]
+fn test_tampered_message_is_rejected() {
+    let gnupg = Path::new(GNUPG);
+    let cert = load_cert(gnupg.join("bob_ed25519_secret.asc"), None);
+    let mut msg = read(gnupg.join("large_to_bob.gpg"));
+    let last = msg.len() - 10;
+    msg[last] ^= 1;
+    assert!(openpgp::decrypt(&msg, &cert).is_err());
+}
+
+#[test]
+fn test_sign_verify_with_new_certs() {
+    let created = 1_700_000_000;
+    let data = b"Signierte Daten\r\nmit zwei Zeilen\n";
+
+    let (_, ed_priv) = ed25519::generate_ed25519_keypair();
+    let (_, x_priv) = x25519::generate_x25519_keypair();
+    let ed_cert = Cert::from_ed25519(
+        &ed_priv,
+        Some(&x_priv),
+        "Carol <carol@example.org>",
+        created,
+    )
+    .unwrap();
+    assert_eq!(ed_cert.encryption_keys().len(), 1);
+
+    let (rsa_pub, rsa_priv) = rsa::generate_rsa_keypair(1024);
+    let rsa_cert = Cert::from_rsa(&rsa_pub, &rsa_priv, "Dave <dave@example.org>", created).unwrap();
+
+    for cert in [&ed_cert, &rsa_cert] {
+        let public = Cert::from_bytes(cert.to_public_armor().as_bytes(), None).unwrap();
+        assert!(!public.has_secret());
+        let sig = openpgp::sign_detached(data, cert, created + 1).unwrap();
+        let v = openpgp::verify_detached(data, &sig, &public).unwrap();
+        assert_eq!(v.fingerprint, cert.fingerprint());
+        assert_eq!(v.created, created + 1);
+        assert!(openpgp::verify_detached(b"manipuliert", &sig, &public).is_err());
+        assert!(openpgp::sign_detached(data, &public, created).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use cryptomorph::pkcs12::Pkcs12;
+use cryptomorph::utils::error::CryptoError;
+use cryptomorph::x509::{Certificate, SignatureAlgorithm, SigningKey, SubjectPublicKey};
+use std::fs;
+
+const PASSWORD: &str = "geheim";
+
+fn read(name: &str) -> Vec<u8> {
+    let path = format!("tests/testdata/pkcs12/{}", name);
+    fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+fn cert(name: &str) -> Certificate {
+    Certificate::from_bytes(&read(name)).unwrap()
+}
+
+fn open(name: &str) -> Pkcs12 {
+    Pkcs12::from_der(&read(name), PASSWORD).unwrap_or_else(|e| panic!("{}: {}", name, e))
+}
+
+/// Der Schlüssel muss zum Zertifikat passen und damit prüfbare Signaturen erzeugen.
+fn assert_key_matches(key: &SigningKey, cert: &Certificate) {
+    assert_eq!(key.public_key(), cert.public_key);
+    let algorithm = key.signature_algorithm();
+    assert!(algorithm.verify(
+        &cert.public_key,
+        b"Nachricht",
+        &key.sign(b"Nachricht").unwrap()
+    ));
+}
+
+#[test]
+fn test_import_openssl_bundles() {
+    let alice = cert("alice.pem");
+    let ca = cert("ca.pem");
+
+    let bundle = open("alice.p12");
+    assert_eq!(
+        bundle.certificate.as_ref().unwrap().to_der(),
+        alice.to_der()
+    );
+    assert_key_matches(bundle.key.as_ref().unwrap(), &alice);
+    assert_eq!(bundle.ca_certificates.len(), 1);
+    assert_eq!(bundle.ca_certificates[0].to_der(), ca.to_der());
+    assert_eq!(bundle.friendly_name.as_deref(), Some("Alice"));
+    let Some(SigningKey::Rsa { public, .. }) = &bundle.key else {
+        panic!("RSA-Schlüssel erwartet");
+    };
+    assert_eq!(public.e, 65537u32.into());
+
+    // AES-128, unverschlüsselte Zertifikate und HMAC-SHA1 als MAC
+    let bundle = open("alice_aes128_sha1mac.p12");
+    assert_key_matches(bundle.key.as_ref().unwrap(), &alice);
+    assert!(bundle.ca_certificates.is_empty());
+    assert!(bundle.friendly_name.is_none());
+
+    // nur Zertifikate
+    let bundle = open("ca_only.p12");
+    assert!(bundle.key.is_none() && bundle.certificate.is_none());
+    assert_eq!(bundle.ca_certificates[0].to_der(), ca.to_der());
+}
+
+#[test]
+fn test_import_java_keystore() {
+    let bundle = open("java.p12");
+    let cert = bundle.certificate.unwrap();
+    assert_eq!(cert.subject.to_string(), "CN=Bob");
+    assert!(matches!(cert.public_key, SubjectPublicKey::P256(_)));
+    assert_eq!(cert.signature_algorithm, SignatureAlgorithm::EcdsaSha256);
+    assert_key_matches(&bundle.key.unwrap(), &cert);
+    assert_eq!(bundle.friendly_name.as_deref(), Some("bob"));
+}
+
+#[test]
+fn test_reject_wrong_password_and_legacy() {
+    assert!(matches!(
+        Pkcs12::from_der(&read("alice.p12"), "falsch"),
+        Err(CryptoError::DecryptionFailed)
+    ));
+    // veränderter Inhalt fällt beim MAC auf
+    let mut data = read("alice.p12");
+    let middle = data.len() / 2;
+    data[middle] ^= 1;
+    assert!(Pkcs12::from_der(&data, PASSWORD).is_err());
+    assert!(matches!(
+        Pkcs12::from_der(&read("alice_legacy.p12"), PASSWORD),
+        Err(CryptoError::Unsupported(_))
+    ));
+}
+
+#[test]
+fn test_export_roundtrip() {
+    let imported = open("alice.p12");
+    let exported = Pkcs12 {
+        friendly_name: Some("Alice (exportiert)".to_string()),
+        ..imported.clone()
+    };
+    let der = exported.to_der("neues Passwort", 100).unwrap();
+    let bundle = Pkcs12::from_der(&der, "neues Passwort").unwrap();
+    let alice = cert("alice.pem");
+    assert_eq!(
+        bundle.certificate.as_ref().unwrap().to_der(),
+        alice.to_der()
+    );
+    assert_key_matches(bundle.key.as_ref().unwrap(), &alice);
+    assert_eq!(bundle.ca_certificates[0].to_der(), cert("ca.pem").to_der());
+    assert_eq!(bundle.friendly_name.as_deref(), Some("Alice (exportiert)"));
+
+    // Schlüssel und Zertifikat müssen zusammenpassen
+    let mismatched = Pkcs12 {
+        key: open("java.p12").key,
+        ..imported
+    };
+    assert!(matches!(
+        mismatched.to_der(PASSWORD, 100),
+        Err(CryptoError::InvalidFormat(_))
+    ));
+}
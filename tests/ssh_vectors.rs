@@ -0,0 +1,165 @@
+use cryptomorph::ssh::{self, AllowedSigners, PrivateKey, PublicKey, Signature};
+use cryptomorph::utils::error::CryptoError;
+use std::fs;
+
+const PASSPHRASE: &str = "geheim";
+/// 2025-06-01 00:00:00 UTC
+const NOW: i64 = 1_748_736_000;
+
+fn read(name: &str) -> String {
+    let path = format!("tests/testdata/ssh/{}", name);
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+fn public(name: &str) -> (PublicKey, String) {
+    PublicKey::from_openssh(&read(&format!("{}.pub", name))).unwrap()
+}
+
+#[test]
+fn test_read_public_keys() {
+    let (key, comment) = public("id_ed25519");
+    assert_eq!(comment, "alice@example.test");
+    assert_eq!(
+        key.fingerprint(),
+        "SHA256:HKjFJQXqr4ILx4XE0BA1mhL2Urr2in8yBFC+GxtktIA"
+    );
+    let (key, comment) = public("id_rsa");
+    assert_eq!(comment, "bob@example.test");
+    assert_eq!(
+        key.fingerprint(),
+        "SHA256:6ScdewV59b9KN2OuKXUspV59zOQW1pNJEiXoiaMvJxM"
+    );
+    let PublicKey::Rsa(rsa) = &key else {
+        panic!("RSA-Schlüssel erwartet");
+    };
+    assert_eq!(rsa.n.bits(), 2048);
+    assert_eq!(rsa.e, 65537u32.into());
+
+    // Schreiben ergibt dieselbe Zeile
+    let line = read("id_rsa.pub");
+    assert_eq!(key.to_openssh(&comment), line.trim_end());
+
+    assert!(matches!(
+        PublicKey::from_openssh(&read("id_ecdsa.pub")),
+        Err(CryptoError::Unsupported(_))
+    ));
+}
+
+#[test]
+fn test_read_private_keys() {
+    for (name, passphrase) in [
+        ("id_ed25519", None),
+        ("id_rsa", None),
+        ("id_ed25519_enc", Some(PASSPHRASE)),
+        ("id_rsa_enc", Some(PASSPHRASE)),
+    ] {
+        let (key, comment) = PrivateKey::from_openssh(&read(name), passphrase)
+            .unwrap_or_else(|e| panic!("{}: {}", name, e));
+        let (expected, expected_comment) = public(name);
+        assert_eq!(key.public_key(), expected, "{}", name);
+        assert_eq!(comment, expected_comment);
+        assert!(expected.verify(b"Daten", &key.sign(b"Daten").unwrap()));
+    }
+    for wrong in [None, Some("falsch")] {
+        assert!(matches!(
+            PrivateKey::from_openssh(&read("id_ed25519_enc"), wrong),
+            Err(CryptoError::DecryptionFailed)
+        ));
+    }
+    assert!(matches!(
+        PrivateKey::from_openssh(&read("id_ecdsa"), None),
+        Err(CryptoError::Unsupported(_))
+    ));
+}
+
+#[test]
+fn test_verify_ssh_keygen_signatures() {
+    let message = read("message.txt");
+    let signers = AllowedSigners::parse(&read("allowed_signers")).unwrap();
+    assert_eq!(signers.entries.len(), 3);
+
+    let ed_sig = Signature::from_armor(&read("message.txt.sig")).unwrap();
+    assert_eq!(ed_sig.namespace, "file");
+    let entry = signers
+        .verify(
+            message.as_bytes(),
+            &ed_sig,
+            "alice@example.test",
+            "file",
+            NOW,
+        )
+        .unwrap();
+    assert_eq!(entry.principals, ["alice@example.test"]);
+
+    let rsa_sig = Signature::from_armor(&read("message_rsa_git.sig")).unwrap();
+    signers
+        .verify(
+            message.as_bytes(),
+            &rsa_sig,
+            "ci@ops.example.test",
+            "git",
+            NOW,
+        )
+        .unwrap();
+    assert_eq!(
+        signers.find_principals(&rsa_sig.public_key, NOW),
+        ["bob@example.test", "*@ops.example.test"]
+    );
+
+    let rejected = [
+        // falscher Namensraum
+        (&ed_sig, "alice@example.test", "git", NOW),
+        // Schlüssel gehört einem anderen Prinzipal
+        (&ed_sig, "bob@example.test", "file", NOW),
+        // Bob hat keine Namensraum-Beschränkung, aber die Signatur gilt nur für git
+        (&rsa_sig, "bob@example.test", "file", NOW),
+        // abgelaufener Eintrag
+        (&ed_sig, "old@example.test", "file", NOW),
+    ];
+    for (signature, principal, namespace, time) in rejected {
+        assert_eq!(
+            signers
+                .verify(message.as_bytes(), signature, principal, namespace, time)
+                .unwrap_err(),
+            CryptoError::InvalidSignature,
+            "{} {}",
+            principal,
+            namespace
+        );
+    }
+    // vor 2020 war der Eintrag von old@example.test noch gültig
+    signers
+        .verify(
+            message.as_bytes(),
+            &ed_sig,
+            "old@example.test",
+            "file",
+            1_500_000_000,
+        )
+        .unwrap();
+    assert!(signers
+        .verify(b"veraendert", &ed_sig, "alice@example.test", "file", NOW)
+        .is_err());
+}
+
+#[test]
+fn test_sign_and_reencrypt_roundtrip() {
+    let (key, comment) = PrivateKey::from_openssh(&read("id_rsa_enc"), Some(PASSPHRASE)).unwrap();
+    let text = key.to_openssh(&comment, Some("neu"), 2).unwrap();
+    let (key, _) = PrivateKey::from_openssh(&text, Some("neu")).unwrap();
+
+    let signature = ssh::sign(b"Inhalt", &key, "file").unwrap();
+    let armored = signature.to_armor();
+    assert!(armored.starts_with("-----BEGIN SSH SIGNATURE-----\n"));
+    let line = format!("bob@build {}", key.public_key().to_openssh(""));
+    let signers = AllowedSigners::parse(&line).unwrap();
+    signers
+        .verify(
+            b"Inhalt",
+            &Signature::from_armor(&armored).unwrap(),
+            "bob@build",
+            "file",
+            NOW,
+        )
+        .unwrap();
+}
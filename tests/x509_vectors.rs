@@ -0,0 +1,212 @@
+use cryptomorph::asymmetric::ed25519;
+use cryptomorph::utils::error::CryptoError;
+use cryptomorph::x509::{
+    oid, verify_path, Certificate, CertificateParams, CertificationRequest, GeneralName, KeyUsage,
+    Name, SignatureAlgorithm, SigningKey, SubjectPublicKey, TrustStore, VerifyOptions,
+};
+use std::fs;
+
+/// 2026-01-01 00:00:00 UTC, innerhalb der Gültigkeit der Testdaten (2025–2045).
+const NOW: i64 = 1_767_225_600;
+
+fn read(name: &str) -> String {
+    let path = format!("tests/testdata/x509/{}", name);
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+fn cert(name: &str) -> Certificate {
+    Certificate::from_pem(&read(name)).unwrap()
+}
+
+fn store() -> TrustStore {
+    TrustStore::from_pem(&read("root.pem")).unwrap()
+}
+
+fn verify(
+    leaf: &str,
+    intermediates: &[&str],
+    options: &VerifyOptions,
+) -> Result<Vec<Certificate>, CryptoError> {
+    let intermediates: Vec<Certificate> = intermediates.iter().map(|n| cert(n)).collect();
+    verify_path(&cert(leaf), &intermediates, &store(), options)
+}
+
+#[test]
+fn test_parse_openssl_certificates() {
+    let root = cert("root.pem");
+    assert_eq!(root.version, 3);
+    assert!(root.is_self_issued() && root.is_ca());
+    assert_eq!(
+        root.subject.to_string(),
+        "C=DE, O=Beispiel GmbH, CN=Beispiel Root CA"
+    );
+    assert_eq!(root.signature_algorithm, SignatureAlgorithm::RsaSha256);
+    root.verify_signature(&root.public_key).unwrap();
+
+    let inter = cert("inter.pem");
+    assert_eq!(
+        inter.basic_constraints().unwrap().unwrap().path_len,
+        Some(0)
+    );
+    assert!(inter.subject.matches(&cert("leaf_rsa.pem").issuer));
+
+    let leaf = cert("leaf_rsa.pem");
+    assert_eq!(leaf.serial, 10u32.into());
+    assert_eq!(leaf.not_before, 1_735_689_600);
+    assert!(!leaf.is_ca());
+    assert_eq!(
+        leaf.subject_alt_names().unwrap(),
+        vec![
+            GeneralName::Dns("www.example.test".to_string()),
+            GeneralName::Dns("*.api.example.test".to_string()),
+            GeneralName::parse("ip:192.0.2.7").unwrap(),
+        ]
+    );
+    let usage = leaf.key_usage().unwrap().unwrap();
+    assert!(usage.contains(KeyUsage::DIGITAL_SIGNATURE.union(KeyUsage::KEY_ENCIPHERMENT)));
+    assert!(!usage.contains(KeyUsage::KEY_CERT_SIGN));
+    assert!(leaf.extended_key_usage().unwrap()[0].is(oid::KP_SERVER_AUTH));
+    assert_eq!(
+        leaf.authority_key_id().unwrap(),
+        inter.subject_key_id().unwrap()
+    );
+
+    let ed = cert("leaf_ed25519.pem");
+    assert!(matches!(ed.public_key, SubjectPublicKey::Ed25519(_)));
+    assert_eq!(
+        ed.subject.get(oid::EMAIL_ADDRESS),
+        Some("client@example.test")
+    );
+    assert!(matches!(
+        cert("leaf_p256.pem").public_key,
+        SubjectPublicKey::P256(_)
+    ));
+
+    // PEM und DER werden gleichermaßen erkannt und bitgenau wieder ausgegeben
+    let der = Certificate::from_bytes(leaf.to_der()).unwrap();
+    assert_eq!(der.to_pem(), read("leaf_rsa.pem"));
+}
+
+#[test]
+fn test_verify_openssl_chains() {
+    let options = VerifyOptions {
+        host: Some("www.example.test"),
+        purpose: Some(oid::KP_SERVER_AUTH),
+        ..VerifyOptions::at(NOW)
+    };
+    let path = verify("leaf_rsa.pem", &["inter.pem"], &options).unwrap();
+    let subjects: Vec<String> = path.iter().map(|c| c.subject.to_string()).collect();
+    assert_eq!(
+        subjects,
+        [
+            "CN=www.example.test",
+            "C=DE, O=Beispiel GmbH, CN=Beispiel Intermediate CA",
+            "C=DE, O=Beispiel GmbH, CN=Beispiel Root CA",
+        ]
+    );
+
+    for host in ["v1.api.example.test", "192.0.2.7"] {
+        let options = VerifyOptions {
+            host: Some(host),
+            ..VerifyOptions::at(NOW)
+        };
+        verify("leaf_rsa.pem", &["inter.pem"], &options).unwrap();
+    }
+    for host in ["example.test", "a.b.api.example.test", "192.0.2.8"] {
+        let options = VerifyOptions {
+            host: Some(host),
+            ..VerifyOptions::at(NOW)
+        };
+        assert!(verify("leaf_rsa.pem", &["inter.pem"], &options).is_err());
+    }
+
+    let options = VerifyOptions {
+        purpose: Some(oid::KP_CLIENT_AUTH),
+        ..VerifyOptions::at(NOW)
+    };
+    verify("leaf_ed25519.pem", &["inter.pem"], &options).unwrap();
+    assert!(verify("leaf_rsa.pem", &["inter.pem"], &options).is_err());
+    verify("leaf_p256.pem", &["inter.pem"], &VerifyOptions::at(NOW)).unwrap();
+}
+
+#[test]
+fn test_reject_invalid_chains() {
+    let now = VerifyOptions::at(NOW);
+    let is_invalid = |result: Result<Vec<Certificate>, CryptoError>| {
+        matches!(result, Err(CryptoError::InvalidCertificate(_)))
+    };
+
+    // fehlende Zwischen-CA
+    assert!(is_invalid(verify("leaf_rsa.pem", &[], &now)));
+    // abgelaufen bzw. noch nicht gültig
+    assert!(is_invalid(verify("expired.pem", &["inter.pem"], &now)));
+    assert!(is_invalid(verify(
+        "leaf_rsa.pem",
+        &["inter.pem"],
+        &VerifyOptions::at(1_700_000_000)
+    )));
+    assert!(cert("expired.pem").is_valid_at(1_600_000_000));
+    // pathlen:0 der Zwischen-CA verbietet eine weitere CA darunter
+    assert!(is_invalid(verify(
+        "beyond.pem",
+        &["sub_ca.pem", "inter.pem"],
+        &now
+    )));
+    // unpassende Kandidaten werden übergangen
+    let path = verify("leaf_p256.pem", &["leaf_rsa.pem", "inter.pem"], &now).unwrap();
+    assert_eq!(path.len(), 3);
+
+    // verfälschte Signatur
+    let mut tampered = cert("leaf_p256.pem");
+    let last = tampered.signature.len() - 3;
+    tampered.signature[last] ^= 1;
+    assert!(tampered
+        .verify_signature(&cert("inter.pem").public_key)
+        .is_err());
+    assert!(verify_path(&tampered, &[cert("inter.pem")], &store(), &now).is_err());
+}
+
+#[test]
+fn test_openssl_csr_and_issuance() {
+    let request = CertificationRequest::from_pem(&read("request.csr")).unwrap();
+    request.verify().unwrap();
+    assert_eq!(request.subject.common_name(), Some("csr.example.test"));
+    assert_eq!(
+        request.subject_alt_names().unwrap(),
+        vec![
+            GeneralName::Dns("csr.example.test".to_string()),
+            GeneralName::parse("ip:2001:db8::1").unwrap(),
+        ]
+    );
+
+    // eigene Wurzel-CA stellt für die OpenSSL-Anforderung aus
+    let (_, ca_private) = ed25519::generate_ed25519_keypair();
+    let ca_key = SigningKey::from_ed25519(&ca_private);
+    let ca_params =
+        CertificateParams::new(Name::parse("CN=Test CA, C=DE").unwrap(), NOW, 3650).with_ca(None);
+    let ca = Certificate::self_signed(&ca_params, &ca_key).unwrap();
+
+    let params = CertificateParams::new(request.subject.clone(), NOW, 90)
+        .with_subject_alt_names(request.subject_alt_names().unwrap());
+    let issued = Certificate::issue(&params, &request.public_key, &ca, &ca_key).unwrap();
+    let issued = Certificate::from_pem(&issued.to_pem()).unwrap();
+    assert!(issued.issuer.matches(&ca.subject));
+    assert_eq!(issued.public_key, request.public_key);
+
+    let mut store = TrustStore::new();
+    store.add(ca.clone());
+    let options = VerifyOptions {
+        host: Some("2001:db8::1"),
+        ..VerifyOptions::at(NOW + 86_400)
+    };
+    let path = verify_path(&issued, &[], &store, &options).unwrap();
+    assert_eq!(path.len(), 2);
+
+    // ein Endzertifikat darf nicht weiter ausstellen
+    assert!(matches!(
+        Certificate::issue(&params, &request.public_key, &issued, &ca_key),
+        Err(CryptoError::InvalidFormat(_))
+    ));
+    // OpenSSL-Wurzel ist für die eigene Kette nicht zuständig
+    assert!(verify_path(&issued, &[], &self::store(), &options).is_err());
+}